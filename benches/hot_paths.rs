@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use x402_sdk::core::X402;
+use x402_sdk::headers::{ChallengeHeaders, decode_payment_required, parse_challenge_headers};
+use x402_sdk::resource::Resource;
+use x402_sdk::shard::DEFAULT_SHARDS;
+use x402_sdk::store::{InMemoryPaymentStore, PaymentRecord, PaymentStore};
+use x402_sdk::types::{
+    ChainType, Finality, PaymentRequest, PaymentVerification, TransactionLog, X402ProtocolResponse,
+};
+use x402_sdk::verifier::{PaymentVerifier, VerificationError};
+
+const PAYER: &str = "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5";
+
+/// Verifier answering from a canned provider response, so the benchmark
+/// measures the engine and not the network.
+struct MockedProvider;
+
+#[async_trait]
+impl PaymentVerifier for MockedProvider {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        Ok(PaymentVerification {
+            is_paid: true,
+            paid_amount: payment_request.amount.clone(),
+            transaction_hash: Some(format!("0x{}", "ab".repeat(32))),
+            verified_at: 1_700_000_000,
+            chain: payment_request.chain.clone(),
+            transaction_logs: vec![TransactionLog {
+                transaction_hash: format!("0x{}", "ab".repeat(32)),
+                from: payer_address.to_string(),
+                to: payment_request.recipient.clone(),
+                value: payment_request.amount.clone(),
+                block_number: 1,
+                log_index: 0,
+                data: None,
+                explorer_url: None,
+            }],
+            explorer_url: None,
+            conversion: None,
+            paid_currency: None,
+            failure_reason: None,
+            proof: None,
+        })
+    }
+
+    fn supports_chain(&self, _chain_type: &ChainType) -> bool {
+        true
+    }
+}
+
+fn engine() -> X402 {
+    sharded_engine(DEFAULT_SHARDS)
+}
+
+fn sharded_engine(shards: usize) -> X402 {
+    let mut engine = X402::from_default_config()
+        .unwrap()
+        .with_session_shards(shards);
+    engine
+        .verifier_registry_mut()
+        .register_verifier(ChainType::ethereum(), Box::new(MockedProvider));
+    engine
+}
+
+async fn challenge(engine: &X402) -> X402ProtocolResponse {
+    engine
+        .handle_access_request(PAYER, "/premium", None, None)
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+}
+
+fn record(recorded_at: u64) -> PaymentRecord {
+    PaymentRecord {
+        recorded_at,
+        nonce: format!("nonce-{}", recorded_at),
+        payer: PAYER.to_string(),
+        chain: "Ethereum".to_string(),
+        chain_id: "1".to_string(),
+        token: "ETH".to_string(),
+        gross: "1000".to_string(),
+        fee: "0".to_string(),
+        transaction_hash: None,
+        resource: "GET /premium".to_string(),
+        finality: Finality::Provisional,
+        tax: None,
+        invoice: None,
+    }
+}
+
+fn session_creation(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let engine = engine();
+    c.bench_function("session_creation", |b| {
+        b.to_async(&runtime).iter(|| async {
+            black_box(
+                engine
+                    .handle_access_request(PAYER, "/premium", None, None)
+                    .await
+                    .unwrap(),
+            )
+        })
+    });
+}
+
+/// sessions issued and verified by concurrent tasks, one shard serializes
+/// every task on the same lock
+fn session_contention(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("session_contention");
+    for shards in [1, DEFAULT_SHARDS] {
+        let engine = Arc::new(sharded_engine(shards));
+        group.bench_function(format!("{}_shards", shards), |b| {
+            b.to_async(&runtime).iter(|| {
+                let engine = engine.clone();
+                async move {
+                    let tasks: Vec<_> = (0..32)
+                        .map(|_| {
+                            let engine = engine.clone();
+                            tokio::spawn(async move {
+                                let nonce = challenge(&engine).await.payment_required.nonce;
+                                engine
+                                    .verify_payment(
+                                        PAYER,
+                                        &nonce,
+                                        &Resource::new("GET", "/premium").unwrap(),
+                                    )
+                                    .await
+                                    .unwrap()
+                            })
+                        })
+                        .collect();
+                    for task in tasks {
+                        black_box(task.await.unwrap());
+                    }
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn store_operations(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("payment_store");
+    group.bench_function("record_payment", |b| {
+        b.to_async(&runtime).iter_batched(
+            InMemoryPaymentStore::new,
+            |store| async move {
+                for recorded_at in 0..100 {
+                    store.record_payment(record(recorded_at)).await.unwrap();
+                }
+                store
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    let store = InMemoryPaymentStore::new();
+    runtime.block_on(async {
+        for recorded_at in 0..10_000 {
+            store.record_payment(record(recorded_at)).await.unwrap();
+        }
+    });
+    group.bench_function("payments_between", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(store.payments_between(4_000, 5_000).await.unwrap()) })
+    });
+    group.bench_function("payment_by_nonce", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { black_box(store.payment("nonce-9999").await.unwrap()) })
+    });
+    group.finish();
+}
+
+fn header_codecs(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let response = runtime.block_on(challenge(&engine()));
+    let renderer = ChallengeHeaders::new().with_www_authenticate("api");
+    let headers = renderer.render(&response).unwrap();
+    let mut group = c.benchmark_group("headers");
+    group.bench_function("encode", |b| {
+        b.iter(|| black_box(renderer.render(black_box(&response)).unwrap()))
+    });
+    group.bench_function("decode", |b| {
+        b.iter(|| black_box(decode_payment_required(black_box(&headers[0].1)).unwrap()))
+    });
+    group.bench_function("parse", |b| {
+        b.iter(|| {
+            black_box(
+                parse_challenge_headers(headers.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+                    .unwrap(),
+            )
+        })
+    });
+    group.finish();
+}
+
+fn verification(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let engine = engine();
+    let nonce = runtime.block_on(challenge(&engine)).payment_required.nonce;
+    let resource = Resource::new("GET", "/premium").unwrap();
+    c.bench_function("verify_payment", |b| {
+        b.to_async(&runtime).iter(|| async {
+            black_box(
+                engine
+                    .verify_payment(PAYER, &nonce, &resource)
+                    .await
+                    .unwrap(),
+            )
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    session_creation,
+    session_contention,
+    store_operations,
+    header_codecs,
+    verification
+);
+criterion_main!(benches);
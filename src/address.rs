@@ -0,0 +1,122 @@
+/// Address and transaction hash normalization module.
+use crate::types::ChainType;
+use ethers::types::H160;
+use ethers::utils::{hex, to_checksum};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    InvalidAddress(String),
+    InvalidHash(String),
+}
+
+impl std::fmt::Display for AddressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAddress(address) => write!(f, "Invalid address: {}", address),
+            Self::InvalidHash(hash) => write!(f, "Invalid transaction hash: {}", hash),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+/// lowercase `0x` hex of raw bytes
+pub fn to_hex(bytes: impl AsRef<[u8]>) -> String {
+    hex::encode_prefixed(bytes)
+}
+
+/// `0x` hex of exactly `len` bytes, any case, the prefix is optional
+fn hex_bytes(value: &str, len: usize) -> Option<Vec<u8>> {
+    let value = value.trim();
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    let bytes = hex::decode(digits).ok()?;
+    (bytes.len() == len).then_some(bytes)
+}
+
+/// base58 of exactly `len` bytes
+fn base58_bytes(value: &str, len: usize) -> Option<Vec<u8>> {
+    let bytes = bs58::decode(value.trim()).into_vec().ok()?;
+    (bytes.len() == len).then_some(bytes)
+}
+
+/// EVM address as lowercase `0x` hex, the form used in transaction logs
+pub fn evm_address(address: &str) -> Result<String, AddressError> {
+    hex_bytes(address, 20)
+        .map(to_hex)
+        .ok_or_else(|| AddressError::InvalidAddress(address.to_string()))
+}
+
+/// EVM address in the mixed case checksum form of EIP-55, for display
+pub fn checksum_address(address: &str) -> Result<String, AddressError> {
+    let address = evm_address(address)?;
+    let address =
+        H160::from_str(&address).map_err(|_| AddressError::InvalidAddress(address.clone()))?;
+    Ok(to_checksum(&address, None))
+}
+
+/// EVM transaction hash as lowercase `0x` hex
+pub fn evm_hash(hash: &str) -> Result<String, AddressError> {
+    hex_bytes(hash, 32)
+        .map(to_hex)
+        .ok_or_else(|| AddressError::InvalidHash(hash.to_string()))
+}
+
+/// Solana public key in base58
+pub fn solana_address(address: &str) -> Result<String, AddressError> {
+    base58_bytes(address, 32)
+        .map(|bytes| bs58::encode(bytes).into_string())
+        .ok_or_else(|| AddressError::InvalidAddress(address.to_string()))
+}
+
+/// Solana transaction signature in base58
+pub fn solana_signature(signature: &str) -> Result<String, AddressError> {
+    base58_bytes(signature, 64)
+        .map(|bytes| bs58::encode(bytes).into_string())
+        .ok_or_else(|| AddressError::InvalidHash(signature.to_string()))
+}
+
+/// canonical form of an address on a chain, lowercase hex on EVM, Aptos
+/// and Sui and base58 on Solana, custom chains are only trimmed
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::address::{checksum_address, normalize_address};
+/// use x402_sdk::types::ChainType;
+///
+/// let address = normalize_address(
+///     &ChainType::ethereum(),
+///     "0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+/// )
+/// .unwrap();
+/// assert_eq!(address, "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+/// assert_eq!(
+///     checksum_address(&address).unwrap(),
+///     "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+/// );
+/// ```
+pub fn normalize_address(chain_type: &ChainType, address: &str) -> Result<String, AddressError> {
+    match chain_type {
+        ChainType::Evm(_) => evm_address(address),
+        ChainType::Solana(_) => solana_address(address),
+        ChainType::Aptos(_) | ChainType::Sui(_) => Ok(address.trim().to_lowercase()),
+        ChainType::Custom(_) => Ok(address.trim().to_string()),
+    }
+}
+
+/// canonical form of a transaction hash or signature on a chain
+pub fn normalize_transaction_hash(
+    chain_type: &ChainType,
+    hash: &str,
+) -> Result<String, AddressError> {
+    match chain_type {
+        ChainType::Evm(_) => evm_hash(hash),
+        ChainType::Solana(_) => solana_signature(hash),
+        ChainType::Aptos(_) | ChainType::Sui(_) => Ok(hash.trim().to_lowercase()),
+        ChainType::Custom(_) => Ok(hash.trim().to_string()),
+    }
+}
@@ -0,0 +1,272 @@
+/// Revenue and conversion analytics module.
+use crate::clock::{Clock, SystemClock};
+use crate::events::{EventListener, PaymentEvent, PaymentEventKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Step of the payment funnel a session reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Stage {
+    Issued,
+    /// the client came back with the challenge nonce, claiming a payment
+    PaymentSeen,
+    Verified,
+    Served,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    timestamp: u64,
+    nonce: String,
+    stage: Stage,
+    /// method and path, without the query
+    resource: String,
+    chain_id: Option<String>,
+    amount: Option<u128>,
+}
+
+/// Sessions reaching each step of the funnel, each session counted once per
+/// step.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Funnel {
+    pub challenges_issued: usize,
+    pub payments_seen: usize,
+    pub payments_verified: usize,
+    pub content_served: usize,
+}
+
+impl Funnel {
+    /// share of issued challenges that were verified
+    pub fn conversion_rate(&self) -> f64 {
+        if self.challenges_issued == 0 {
+            return 0.0;
+        }
+        self.payments_verified as f64 / self.challenges_issued as f64
+    }
+}
+
+/// Verified revenue of a resource, in the smallest unit of the chain
+/// currency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceRevenue {
+    pub resource: String,
+    pub chain_id: String,
+    pub payments: usize,
+    pub amount: u128,
+}
+
+/// Analytics over the trailing window, for admin integrations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsReport {
+    pub window_secs: u64,
+    pub funnel: Funnel,
+    pub conversion_rate: f64,
+    /// mean seconds from challenge to verified payment, `None` without
+    /// verified payments whose challenge is still retained
+    pub average_time_to_pay_secs: Option<f64>,
+    pub revenue: Vec<ResourceRevenue>,
+}
+
+/// Conversion funnels, time-to-pay and revenue per resource over sliding
+/// windows, built from the engine event stream.
+///
+/// Events are retained for the retention period, 24 hours by default, and
+/// every query covers a trailing window within it.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use x402_sdk::analytics::Analytics;
+/// use x402_sdk::core::X402;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let analytics = Arc::new(Analytics::new().with_retention(7 * 86_400));
+/// let engine = X402::from_default_config()?.with_event_listener(analytics.clone());
+/// // ... serve requests, then
+/// let report = analytics.report(3_600);
+/// println!("{:.1}% converted in the last hour", report.conversion_rate * 100.0);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Analytics {
+    entries: Mutex<VecDeque<Entry>>,
+    clock: Arc<dyn Clock>,
+    retention_secs: u64,
+}
+
+impl Default for Analytics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analytics {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+            clock: Arc::new(SystemClock),
+            retention_secs: 86_400,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// how long events are kept, the longest window that can be queried
+    pub fn with_retention(mut self, retention_secs: u64) -> Self {
+        self.retention_secs = retention_secs;
+        self
+    }
+
+    fn in_window(&self, window_secs: u64) -> Vec<Entry> {
+        let from = self.clock.now().saturating_sub(window_secs);
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .filter(|entry| entry.timestamp >= from)
+            .cloned()
+            .collect()
+    }
+
+    /// funnel of the sessions with activity in the trailing window
+    pub fn funnel(&self, window_secs: u64) -> Funnel {
+        let entries = self.in_window(window_secs);
+        let count = |stage: Stage| {
+            entries
+                .iter()
+                .filter(|entry| entry.stage == stage)
+                .map(|entry| entry.nonce.as_str())
+                .collect::<HashSet<_>>()
+                .len()
+        };
+        Funnel {
+            challenges_issued: count(Stage::Issued),
+            payments_seen: count(Stage::PaymentSeen),
+            payments_verified: count(Stage::Verified),
+            content_served: count(Stage::Served),
+        }
+    }
+
+    /// mean seconds from the first challenge of a session to its verified
+    /// payment, for payments verified in the trailing window
+    pub fn average_time_to_pay(&self, window_secs: u64) -> Option<f64> {
+        let from = self.clock.now().saturating_sub(window_secs);
+        let entries = self.entries.lock().unwrap();
+        let mut issued_at = HashMap::new();
+        let mut durations = Vec::new();
+        for entry in entries.iter() {
+            match entry.stage {
+                Stage::Issued => {
+                    issued_at
+                        .entry(entry.nonce.as_str())
+                        .or_insert(entry.timestamp);
+                }
+                Stage::Verified if entry.timestamp >= from => {
+                    if let Some(issued_at) = issued_at.get(entry.nonce.as_str()) {
+                        durations.push(entry.timestamp.saturating_sub(*issued_at));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if durations.is_empty() {
+            return None;
+        }
+        Some(durations.iter().sum::<u64>() as f64 / durations.len() as f64)
+    }
+
+    /// verified revenue per resource and chain in the trailing window,
+    /// highest amount first
+    pub fn revenue_by_resource(&self, window_secs: u64) -> Vec<ResourceRevenue> {
+        let mut revenue: BTreeMap<(String, String), (usize, u128)> = BTreeMap::new();
+        for entry in self.in_window(window_secs) {
+            if entry.stage != Stage::Verified {
+                continue;
+            }
+            let line = revenue
+                .entry((entry.resource, entry.chain_id.unwrap_or_default()))
+                .or_default();
+            line.0 += 1;
+            line.1 += entry.amount.unwrap_or(0);
+        }
+        let mut lines: Vec<ResourceRevenue> = revenue
+            .into_iter()
+            .map(
+                |((resource, chain_id), (payments, amount))| ResourceRevenue {
+                    resource,
+                    chain_id,
+                    payments,
+                    amount,
+                },
+            )
+            .collect();
+        lines.sort_by_key(|line| std::cmp::Reverse(line.amount));
+        lines
+    }
+
+    pub fn report(&self, window_secs: u64) -> AnalyticsReport {
+        let funnel = self.funnel(window_secs);
+        AnalyticsReport {
+            window_secs,
+            conversion_rate: funnel.conversion_rate(),
+            funnel,
+            average_time_to_pay_secs: self.average_time_to_pay(window_secs),
+            revenue: self.revenue_by_resource(window_secs),
+        }
+    }
+}
+
+impl EventListener for Analytics {
+    fn on_event(&self, event: &PaymentEvent) {
+        let (nonce, stage, chain_id, amount) = match &event.kind {
+            PaymentEventKind::ChallengeIssued { payment_request } => {
+                (&payment_request.nonce, Stage::Issued, None, None)
+            }
+            PaymentEventKind::VerificationFailed { nonce, .. }
+            | PaymentEventKind::VerificationThrottled { nonce, .. }
+            | PaymentEventKind::SessionLockedOut { nonce, .. } => {
+                (nonce, Stage::PaymentSeen, None, None)
+            }
+            PaymentEventKind::PaymentVerified {
+                nonce,
+                verification,
+            } => (
+                nonce,
+                Stage::Verified,
+                Some(verification.chain.chain_id.clone()),
+                verification.paid_amount.parse().ok(),
+            ),
+            PaymentEventKind::ContentServed { nonce } => (nonce, Stage::Served, None, None),
+            _ => return,
+        };
+        let resource = format!("{} {}", event.resource.method, event.resource.path);
+        let mut entries = self.entries.lock().unwrap();
+        // a verified payment was necessarily seen first
+        if stage == Stage::Verified {
+            entries.push_back(Entry {
+                timestamp: event.timestamp,
+                nonce: nonce.clone(),
+                stage: Stage::PaymentSeen,
+                resource: resource.clone(),
+                chain_id: None,
+                amount: None,
+            });
+        }
+        entries.push_back(Entry {
+            timestamp: event.timestamp,
+            nonce: nonce.clone(),
+            stage,
+            resource,
+            chain_id,
+            amount,
+        });
+        let from = self.clock.now().saturating_sub(self.retention_secs);
+        while entries.front().is_some_and(|entry| entry.timestamp < from) {
+            entries.pop_front();
+        }
+    }
+}
@@ -0,0 +1,327 @@
+/// Suspicious payment pattern detection module.
+use crate::clock::{Clock, SystemClock};
+use crate::context::RequestContext;
+use crate::events::{EventListener, PaymentEvent, PaymentEventKind};
+use crate::policy::{AccessDecision, AccessPolicy};
+use crate::resource::Resource;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnomalyKind {
+    /// the payer opened many sessions in the window without paying any
+    UnpaidSessions { sessions: usize, window_secs: u64 },
+    /// the same transaction was presented as payment for several sessions
+    TransactionProbed {
+        transaction_hash: String,
+        sessions: usize,
+    },
+    /// the payer is on the screening list
+    ScreenedAddress { reason: String },
+}
+
+/// Structured signal about a suspicious pattern, fired once per payer and
+/// pattern until the payer is cleared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnomalySignal {
+    pub timestamp: u64,
+    pub payer: String,
+    pub kind: AnomalyKind,
+}
+
+/// Receives anomaly signals, e.g. to flag an account or page an operator.
+pub trait AnomalyHook: Send + Sync {
+    fn on_signal(&self, signal: &AnomalySignal);
+}
+
+/// Screens payer addresses against a sanctions or risk list.
+pub trait ScreeningProvider: Send + Sync {
+    /// reason the address is listed, `None` when it is clear
+    fn screen(&self, address: &str) -> Option<String>;
+}
+
+/// Screening provider backed by a fixed list of addresses.
+#[derive(Debug, Clone, Default)]
+pub struct StaticScreeningList {
+    addresses: HashMap<String, String>,
+}
+
+impl StaticScreeningList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_address(mut self, address: &str, reason: &str) -> Self {
+        self.addresses
+            .insert(address.to_lowercase(), reason.to_string());
+        self
+    }
+}
+
+impl ScreeningProvider for StaticScreeningList {
+    fn screen(&self, address: &str) -> Option<String> {
+        self.addresses.get(&address.to_lowercase()).cloned()
+    }
+}
+
+#[derive(Default)]
+struct DetectorState {
+    /// challenge nonces and their issue time per payer, until paid
+    unpaid: HashMap<String, HashMap<String, u64>>,
+    /// sessions each transaction was verified for
+    transactions: HashMap<String, HashSet<String>>,
+    /// signals already fired per payer
+    flagged: HashMap<String, Vec<AnomalyKind>>,
+}
+
+/// Watches the engine event stream for suspicious payment patterns and fires
+/// [`AnomalySignal`]s to the registered hooks.
+///
+/// Detected patterns are many unpaid sessions from one payer, one
+/// transaction verified for several sessions and payers on the screening
+/// list. Register the detector as an event listener, and also as an access
+/// policy to screen payers before any payment handling and, with
+/// [`with_blocking`](Self::with_blocking), to refuse flagged payers.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use x402_sdk::anomaly::{AnomalyDetector, StaticScreeningList};
+/// use x402_sdk::core::X402;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let screening = StaticScreeningList::new()
+///     .with_address("0x8589427373D6D84E98730D7795D8f6f8731FDA16", "sanctioned");
+/// let detector = Arc::new(
+///     AnomalyDetector::new()
+///         .with_screening(Arc::new(screening))
+///         .with_unpaid_threshold(20, 3_600)
+///         .with_blocking(true),
+/// );
+/// let engine = X402::from_default_config()?
+///     .with_event_listener(detector.clone())
+///     .with_access_policy(detector.clone());
+/// # Ok(())
+/// # }
+/// ```
+pub struct AnomalyDetector {
+    hooks: Vec<Arc<dyn AnomalyHook>>,
+    screening: Option<Arc<dyn ScreeningProvider>>,
+    clock: Arc<dyn Clock>,
+    unpaid_threshold: usize,
+    unpaid_window_secs: u64,
+    probe_threshold: usize,
+    blocking: bool,
+    state: Mutex<DetectorState>,
+}
+
+impl Default for AnomalyDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnomalyDetector {
+    pub fn new() -> Self {
+        Self {
+            hooks: Vec::new(),
+            screening: None,
+            clock: Arc::new(SystemClock),
+            unpaid_threshold: 20,
+            unpaid_window_secs: 3_600,
+            probe_threshold: 2,
+            blocking: false,
+            state: Mutex::new(DetectorState::default()),
+        }
+    }
+
+    pub fn with_hook(mut self, hook: Arc<dyn AnomalyHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    pub fn with_screening(mut self, screening: Arc<dyn ScreeningProvider>) -> Self {
+        self.screening = Some(screening);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// unpaid sessions within the window that flag a payer, 20 per hour by
+    /// default
+    pub fn with_unpaid_threshold(mut self, sessions: usize, window_secs: u64) -> Self {
+        self.unpaid_threshold = sessions;
+        self.unpaid_window_secs = window_secs;
+        self
+    }
+
+    /// sessions a transaction is verified for before it counts as probed,
+    /// 2 by default
+    pub fn with_probe_threshold(mut self, sessions: usize) -> Self {
+        self.probe_threshold = sessions;
+        self
+    }
+
+    /// deny access to flagged payers when registered as an access policy
+    pub fn with_blocking(mut self, blocking: bool) -> Self {
+        self.blocking = blocking;
+        self
+    }
+
+    /// signals fired for the payer since it was last cleared
+    pub fn signals(&self, payer: &str) -> Vec<AnomalyKind> {
+        self.state
+            .lock()
+            .unwrap()
+            .flagged
+            .get(&payer.to_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn is_flagged(&self, payer: &str) -> bool {
+        !self.signals(payer).is_empty()
+    }
+
+    /// forget the signals and unpaid sessions of a payer, e.g. after review
+    pub fn clear(&self, payer: &str) {
+        let payer = payer.to_lowercase();
+        let mut state = self.state.lock().unwrap();
+        state.flagged.remove(&payer);
+        state.unpaid.remove(&payer);
+    }
+
+    /// record the signal and fire the hooks, unless the payer already has a
+    /// signal of the same pattern
+    fn signal(&self, payer: &str, kind: AnomalyKind) {
+        {
+            let mut state = self.state.lock().unwrap();
+            let signals = state.flagged.entry(payer.to_lowercase()).or_default();
+            if signals
+                .iter()
+                .any(|fired| std::mem::discriminant(fired) == std::mem::discriminant(&kind))
+            {
+                return;
+            }
+            signals.push(kind.clone());
+        }
+        let signal = AnomalySignal {
+            timestamp: self.clock.now(),
+            payer: payer.to_string(),
+            kind,
+        };
+        for hook in &self.hooks {
+            hook.on_signal(&signal);
+        }
+    }
+
+    fn screen(&self, payer: &str) -> Option<String> {
+        let reason = self.screening.as_ref()?.screen(payer)?;
+        self.signal(
+            payer,
+            AnomalyKind::ScreenedAddress {
+                reason: reason.clone(),
+            },
+        );
+        Some(reason)
+    }
+
+    fn record_challenge(&self, payer: &str, nonce: &str, timestamp: u64) {
+        let from = timestamp.saturating_sub(self.unpaid_window_secs);
+        let sessions = {
+            let mut state = self.state.lock().unwrap();
+            let unpaid = state.unpaid.entry(payer.to_lowercase()).or_default();
+            unpaid.retain(|_, issued_at| *issued_at >= from);
+            unpaid.entry(nonce.to_string()).or_insert(timestamp);
+            unpaid.len()
+        };
+        if sessions >= self.unpaid_threshold {
+            self.signal(
+                payer,
+                AnomalyKind::UnpaidSessions {
+                    sessions,
+                    window_secs: self.unpaid_window_secs,
+                },
+            );
+        }
+    }
+
+    fn record_payment(&self, payer: &str, nonce: &str, transaction_hash: Option<&str>) {
+        let sessions = {
+            let mut state = self.state.lock().unwrap();
+            if let Some(unpaid) = state.unpaid.get_mut(&payer.to_lowercase()) {
+                unpaid.remove(nonce);
+            }
+            let Some(transaction_hash) = transaction_hash else {
+                return;
+            };
+            let sessions = state
+                .transactions
+                .entry(transaction_hash.to_lowercase())
+                .or_default();
+            sessions.insert(nonce.to_string());
+            sessions.len()
+        };
+        if let Some(transaction_hash) = transaction_hash
+            && sessions >= self.probe_threshold
+        {
+            self.signal(
+                payer,
+                AnomalyKind::TransactionProbed {
+                    transaction_hash: transaction_hash.to_string(),
+                    sessions,
+                },
+            );
+        }
+    }
+}
+
+impl EventListener for AnomalyDetector {
+    fn on_event(&self, event: &PaymentEvent) {
+        match &event.kind {
+            PaymentEventKind::ChallengeIssued { payment_request } => {
+                self.record_challenge(&event.user_address, &payment_request.nonce, event.timestamp);
+            }
+            PaymentEventKind::PaymentVerified {
+                nonce,
+                verification,
+            } => self.record_payment(
+                &event.user_address,
+                nonce,
+                verification.transaction_hash.as_deref(),
+            ),
+            PaymentEventKind::CouponRedeemed { nonce, .. } => {
+                self.record_payment(&event.user_address, nonce, None)
+            }
+            _ => {}
+        }
+    }
+}
+
+#[async_trait]
+impl AccessPolicy for AnomalyDetector {
+    async fn evaluate(
+        &self,
+        user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+    ) -> AccessDecision {
+        let screened = self.screen(user_address);
+        if !self.blocking {
+            return AccessDecision::RequirePayment;
+        }
+        if let Some(reason) = screened {
+            return AccessDecision::Deny(format!("payer screened: {}", reason));
+        }
+        if self.is_flagged(user_address) {
+            return AccessDecision::Deny("payer flagged for suspicious activity".to_string());
+        }
+        AccessDecision::RequirePayment
+    }
+}
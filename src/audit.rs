@@ -0,0 +1,92 @@
+/// Operator audit log module.
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+#[derive(Debug)]
+pub enum AuditError {
+    Backend(String),
+}
+
+impl std::fmt::Display for AuditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Backend(msg) => write!(f, "Audit log error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AuditError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    DisputeOpened,
+    DisputeAnnotated,
+    DisputeResolved,
+    VerificationOverridden {
+        decision: String,
+    },
+    /// a relay transaction was signed, by the relay address as operator
+    TransactionSigned {
+        transaction_hash: String,
+    },
+    TransactionSigningFailed {
+        reason: String,
+    },
+    /// a bulk revocation, targeting the revocation scope key
+    EntitlementsRevoked {
+        sessions: usize,
+    },
+}
+
+/// An operator action, recorded with the identity that performed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    /// operator identity as supplied by the admin integration
+    pub operator: String,
+    pub action: AuditAction,
+    /// payment nonce the action applies to, the authorization nonce for
+    /// relay transactions, the scope key for bulk revocations
+    pub target: String,
+    pub note: Option<String>,
+}
+
+/// Append-only log of operator actions.
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn append(&self, entry: AuditEntry) -> Result<(), AuditError>;
+
+    /// entries for a target, oldest first
+    async fn entries_for(&self, target: &str) -> Result<Vec<AuditEntry>, AuditError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryAuditLog {
+    entries: RwLock<Vec<AuditEntry>>,
+}
+
+impl InMemoryAuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AuditLog for InMemoryAuditLog {
+    async fn append(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        self.entries.write().unwrap().push(entry);
+        Ok(())
+    }
+
+    async fn entries_for(&self, target: &str) -> Result<Vec<AuditEntry>, AuditError> {
+        Ok(self
+            .entries
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|entry| entry.target == target)
+            .cloned()
+            .collect())
+    }
+}
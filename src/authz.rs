@@ -0,0 +1,369 @@
+/// External authorization server module for reverse proxies.
+use crate::cache::cache_headers;
+use crate::context::RequestContext;
+use crate::core::X402;
+use crate::headers::ChallengeHeaders;
+use crate::keys::JWKS_PATH;
+use crate::receipt::RECEIPT_HEADER;
+use crate::wire::{FieldCasing, encode_negotiated_in};
+use std::net::IpAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// header carrying the payer address of the original request
+pub const PAYER_HEADER: &str = "X-Payer-Address";
+/// header carrying the nonce of the paid challenge
+pub const NONCE_HEADER: &str = "X-Payment-Nonce";
+/// header carrying an optional coupon code
+pub const COUPON_HEADER: &str = "X-Payment-Coupon";
+/// original request target set by nginx `auth_request`
+pub const ORIGINAL_URI_HEADER: &str = "X-Original-URI";
+/// original request method set by nginx `auth_request`
+pub const ORIGINAL_METHOD_HEADER: &str = "X-Original-Method";
+
+/// largest accepted request head, in bytes
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+/// largest request body read and discarded, in bytes
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Request to authorize, as forwarded by the proxy.
+#[derive(Debug, Clone, Default)]
+pub struct AuthzRequest {
+    pub method: String,
+    /// request target of the check, `path?query`
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub client_ip: Option<IpAddr>,
+}
+
+impl AuthzRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Decision returned to the proxy, a 2xx status lets the original request
+/// through, anything else is sent to the client as is.
+#[derive(Debug, Clone)]
+pub struct AuthzResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// External authorization service enforcing x402 in front of any upstream.
+///
+/// Works as an nginx `auth_request` endpoint and as the HTTP authorization
+/// service of the Envoy `ext_authz` filter. The original request is taken
+/// from `X-Original-Method` and `X-Original-URI` when present, otherwise from
+/// the check request itself with the path prefix stripped, which matches the
+/// Envoy `path_prefix` setting. The payer, challenge nonce and coupon come
+/// from the `X-Payer-Address`, `X-Payment-Nonce` and `X-Payment-Coupon`
+/// headers, a `X-Payment-Receipt` header is honored as access token. A
+/// direct `GET` of [`JWKS_PATH`] is answered with the published signing
+/// keys, so services behind the proxy can check receipts.
+///
+/// nginx treats any status other than 2xx, 401 and 403 as an error, use
+/// [`with_denied_status(401)`](Self::with_denied_status) there and map the
+/// 401 back to 402 with `error_page`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use tokio::net::TcpListener;
+/// use x402_sdk::authz::AuthzServer;
+/// use x402_sdk::core::X402;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let engine = Arc::new(X402::from_default_config()?);
+/// let server = AuthzServer::new(engine).with_path_prefix("/x402-authz");
+/// server.serve(TcpListener::bind("127.0.0.1:9402").await?).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AuthzServer {
+    engine: Arc<X402>,
+    path_prefix: String,
+    denied_status: u16,
+    realm: Option<String>,
+    casing: FieldCasing,
+}
+
+impl AuthzServer {
+    pub fn new(engine: Arc<X402>) -> Self {
+        Self {
+            engine,
+            path_prefix: String::new(),
+            denied_status: 402,
+            realm: None,
+            casing: FieldCasing::Snake,
+        }
+    }
+
+    /// prefix of check requests to strip from the path, e.g. the Envoy
+    /// `path_prefix`
+    pub fn with_path_prefix(mut self, path_prefix: &str) -> Self {
+        self.path_prefix = path_prefix.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// status of denied requests, 402 by default
+    pub fn with_denied_status(mut self, denied_status: u16) -> Self {
+        self.denied_status = denied_status;
+        self
+    }
+
+    /// also emit a `WWW-Authenticate` challenge for the given realm
+    pub fn with_www_authenticate(mut self, realm: &str) -> Self {
+        self.realm = Some(realm.to_string());
+        self
+    }
+
+    /// field names of the challenge body and header, e.g. camelCase for
+    /// clients of other x402 implementations
+    pub fn with_casing(mut self, casing: FieldCasing) -> Self {
+        self.casing = casing;
+        self
+    }
+
+    /// decide on one forwarded request
+    pub async fn authorize(&self, request: &AuthzRequest) -> AuthzResponse {
+        if request.header(ORIGINAL_URI_HEADER).is_none()
+            && request.method == "GET"
+            && request.target.split('?').next() == Some(JWKS_PATH)
+        {
+            return self.jwks();
+        }
+        let method = request
+            .header(ORIGINAL_METHOD_HEADER)
+            .unwrap_or(&request.method);
+        let target = match request.header(ORIGINAL_URI_HEADER) {
+            Some(uri) => uri.to_string(),
+            None => match request.target.strip_prefix(&self.path_prefix) {
+                Some(target) if target.starts_with('/') => target.to_string(),
+                Some(target) => format!("/{}", target),
+                None => request.target.clone(),
+            },
+        };
+        let Some(payer) = request.header(PAYER_HEADER) else {
+            return text_response(
+                self.denied_status,
+                &format!("{} header is required", PAYER_HEADER),
+            );
+        };
+        let mut context = RequestContext::new(method);
+        for (name, value) in &request.headers {
+            context = context.with_header(name, value);
+        }
+        context.client_ip = request.client_ip;
+        let result = match self
+            .engine
+            .handle_access_request_with_coupon(
+                payer,
+                &target,
+                request.header(NONCE_HEADER),
+                None,
+                request.header(COUPON_HEADER),
+                &context,
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => return text_response(500, &e.to_string()),
+        };
+        let mut headers = cache_headers(&result, self.engine.clock().now());
+        if let Some(retry_after) = result.retry_after {
+            headers.push(("Retry-After".to_string(), retry_after.to_string()));
+        }
+        if result.should_serve_content {
+            if let Some(receipt) = &result.receipt
+                && let Ok(token) = receipt.to_token()
+            {
+                headers.push((RECEIPT_HEADER.to_string(), token));
+            }
+            return AuthzResponse {
+                status: 200,
+                headers,
+                body: Vec::new(),
+            };
+        }
+        let status = match result.http_status {
+            402 => self.denied_status,
+            // the proxy grants every 2xx, a pending verification must refuse
+            202 => 503,
+            status => status,
+        };
+        let Some(challenge) = &result.x402_response else {
+            let Some(message) = self.engine.message_for(&result, &context) else {
+                return AuthzResponse {
+                    status,
+                    headers,
+                    body: Vec::new(),
+                };
+            };
+            headers.push(("Content-Type".to_string(), "text/plain".to_string()));
+            return AuthzResponse {
+                status,
+                headers,
+                body: message.into_bytes(),
+            };
+        };
+        let mut challenge_headers = ChallengeHeaders::new().with_casing(self.casing);
+        if let Some(realm) = &self.realm {
+            challenge_headers = challenge_headers.with_www_authenticate(realm);
+        }
+        match challenge_headers.render(challenge) {
+            Ok(rendered) => headers.extend(rendered),
+            Err(e) => return text_response(500, &e.to_string()),
+        }
+        match encode_negotiated_in(request.header("Accept"), self.casing, challenge) {
+            Ok((content_type, body)) => {
+                headers.push(("Content-Type".to_string(), content_type.to_string()));
+                AuthzResponse {
+                    status,
+                    headers,
+                    body,
+                }
+            }
+            Err(e) => text_response(500, &e.to_string()),
+        }
+    }
+
+    /// the published key set, 404 when the engine signs nothing
+    fn jwks(&self) -> AuthzResponse {
+        let Some(jwks) = self.engine.jwks() else {
+            return text_response(404, "no signing keys");
+        };
+        match serde_json::to_vec(&jwks) {
+            Ok(body) => AuthzResponse {
+                status: 200,
+                headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+                body,
+            },
+            Err(e) => text_response(500, &e.to_string()),
+        }
+    }
+
+    /// accept check requests until the listener fails, one request per
+    /// connection
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        let server = Arc::new(self);
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let response = match read_request(BufReader::new(reader)).await {
+                    Ok(Some(mut request)) => {
+                        request.client_ip = request
+                            .header("X-Forwarded-For")
+                            .and_then(|value| value.split(',').next())
+                            .and_then(|ip| ip.trim().parse().ok())
+                            .or(Some(peer.ip()));
+                        server.authorize(&request).await
+                    }
+                    Ok(None) => return,
+                    Err(e) => text_response(400, &e.to_string()),
+                };
+                let _ = writer.write_all(&encode_response(&response)).await;
+                let _ = writer.shutdown().await;
+            });
+        }
+    }
+}
+
+fn text_response(status: u16, message: &str) -> AuthzResponse {
+    AuthzResponse {
+        status,
+        headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+        body: message.as_bytes().to_vec(),
+    }
+}
+
+/// read an HTTP/1.1 request head and discard its body, `None` when the
+/// connection closed before a request line
+async fn read_request<R>(mut reader: BufReader<R>) -> std::io::Result<Option<AuthzRequest>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg.to_string());
+    let mut head_bytes = 0;
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    head_bytes += line.len();
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(invalid("malformed request line"));
+    };
+    let mut request = AuthzRequest {
+        method: method.to_string(),
+        target: target.to_string(),
+        ..AuthzRequest::default()
+    };
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).await?;
+        head_bytes += read;
+        if head_bytes > MAX_HEAD_BYTES {
+            return Err(invalid("request head too large"));
+        }
+        let header = line.trim_end_matches(['\r', '\n']);
+        if read == 0 || header.is_empty() {
+            break;
+        }
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| invalid("malformed header"))?;
+        request
+            .headers
+            .push((name.trim().to_string(), value.trim().to_string()));
+    }
+    let content_length = request
+        .header("Content-Length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    if content_length > MAX_BODY_BYTES {
+        return Err(invalid("request body too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(request))
+}
+
+fn encode_response(response: &AuthzResponse) -> Vec<u8> {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    let mut bytes = head.into_bytes();
+    bytes.extend_from_slice(&response.body);
+    bytes
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        402 => "Payment Required",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use x402_sdk::scaffold::{Stack, scaffold};
+
+const USAGE: &str = "usage: x402 scaffold <axum|actix|worker> <name> [--dir <path>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let [command, stack, name, options @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    if command != "scaffold" {
+        return Err(USAGE.to_string());
+    }
+    let stack: Stack = stack.parse().map_err(|e| format!("{}\n{}", e, USAGE))?;
+    let dir = match options {
+        [] => PathBuf::from(name),
+        [flag, dir] if flag == "--dir" => PathBuf::from(dir),
+        _ => return Err(USAGE.to_string()),
+    };
+    let written = scaffold(stack, name, &dir).map_err(|e| e.to_string())?;
+    for path in &written {
+        println!("  created {}", path.display());
+    }
+    println!(
+        "\n{} project ready, try it without a wallet:\n  cd {}\n  X402_SIMULATE=1 cargo run",
+        stack.name(),
+        dir.display()
+    );
+    Ok(())
+}
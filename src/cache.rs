@@ -0,0 +1,157 @@
+/// Paid content caching module.
+use crate::receipt::{RECEIPT_HEADER, Receipt};
+use crate::resource::Resource;
+use crate::types::VerificationResult;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const CACHE_CONTROL_HEADER: &str = "Cache-Control";
+pub const VARY_HEADER: &str = "Vary";
+
+#[derive(Debug)]
+pub enum EdgeTokenError {
+    InvalidKey(String),
+    /// the receipt names a resource that does not parse
+    InvalidResource(String),
+    Unavailable(String),
+}
+
+impl std::fmt::Display for EdgeTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey(msg) => write!(f, "Invalid edge signing key: {}", msg),
+            Self::InvalidResource(msg) => write!(f, "Invalid resource: {}", msg),
+            Self::Unavailable(msg) => write!(f, "Edge token unavailable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for EdgeTokenError {}
+
+/// `Cache-Control` value for content paid until `expires_at`, cacheable only
+/// by the payer and never beyond the entitlement
+pub fn entitlement_cache_control(expires_at: u64, now: u64) -> String {
+    match expires_at.saturating_sub(now) {
+        0 => "private, no-store".to_string(),
+        max_age => format!("private, max-age={}", max_age),
+    }
+}
+
+/// caching headers for the outcome of an access request, challenges and
+/// denials are never stored, content granted with a receipt is cacheable
+/// until the receipt expires and other grants are revalidated every time
+pub fn cache_headers(result: &VerificationResult, now: u64) -> Vec<(String, String)> {
+    let cache_control = match (&result.receipt, result.should_serve_content) {
+        (_, false) => "no-store".to_string(),
+        (Some(receipt), true) => entitlement_cache_control(receipt.expires_at, now),
+        (None, true) => "private, no-cache".to_string(),
+    };
+    vec![
+        (CACHE_CONTROL_HEADER.to_string(), cache_control),
+        (VARY_HEADER.to_string(), RECEIPT_HEADER.to_string()),
+    ]
+}
+
+/// Credential letting an edge cache serve a paid asset without asking the
+/// origin again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeToken {
+    SignedUrl(String),
+    /// `Set-Cookie` header values
+    Cookies(Vec<String>),
+}
+
+/// Issues CDN credentials for paid resources, e.g. CloudFront signed cookies
+/// or Cloudflare signed URLs, valid until the entitlement expires.
+pub trait EdgeTokenIssuer: Send + Sync {
+    fn issue(&self, resource: &Resource, expires_at: u64) -> Result<EdgeToken, EdgeTokenError>;
+}
+
+/// edge token for the receipt of a granted request, `None` when access was
+/// not granted with a receipt
+pub fn edge_token(
+    issuer: &dyn EdgeTokenIssuer,
+    result: &VerificationResult,
+) -> Result<Option<EdgeToken>, EdgeTokenError> {
+    let Some(receipt) = result
+        .receipt
+        .as_ref()
+        .filter(|_| result.should_serve_content)
+    else {
+        return Ok(None);
+    };
+    receipt_edge_token(issuer, receipt).map(Some)
+}
+
+/// edge token valid for the resource and lifetime of a receipt
+pub fn receipt_edge_token(
+    issuer: &dyn EdgeTokenIssuer,
+    receipt: &Receipt,
+) -> Result<EdgeToken, EdgeTokenError> {
+    let resource = Resource::from_canonical(&receipt.resource)
+        .map_err(|e| EdgeTokenError::InvalidResource(e.to_string()))?;
+    issuer.issue(&resource, receipt.expires_at)
+}
+
+/// Signed URLs in the format of the Cloudflare HMAC token validation rule,
+/// `?verify=<expires_at>-<base64 HMAC-SHA256 of path and expires_at>`.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::cache::{CloudflareSignedUrls, EdgeToken, EdgeTokenIssuer};
+/// use x402_sdk::resource::Resource;
+///
+/// let issuer = CloudflareSignedUrls::new("https://cdn.example.com", "edge-secret");
+/// let resource = Resource::new("GET", "/videos/1.mp4").unwrap();
+/// let token = issuer.issue(&resource, 1_700_000_000).unwrap();
+/// let EdgeToken::SignedUrl(url) = token else { unreachable!() };
+/// assert!(url.starts_with("https://cdn.example.com/videos/1.mp4?verify=1700000000-"));
+/// ```
+pub struct CloudflareSignedUrls {
+    base_url: String,
+    secret: Vec<u8>,
+    param: String,
+}
+
+impl CloudflareSignedUrls {
+    pub fn new(base_url: &str, secret: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            secret: secret.as_bytes().to_vec(),
+            param: "verify".to_string(),
+        }
+    }
+
+    /// query parameter the token rule reads, `verify` by default
+    pub fn with_param(mut self, param: &str) -> Self {
+        self.param = param.to_string();
+        self
+    }
+}
+
+impl EdgeTokenIssuer for CloudflareSignedUrls {
+    fn issue(&self, resource: &Resource, expires_at: u64) -> Result<EdgeToken, EdgeTokenError> {
+        let mut mac = HmacSha256::new_from_slice(&self.secret)
+            .map_err(|e| EdgeTokenError::InvalidKey(e.to_string()))?;
+        mac.update(format!("{}{}", resource.path, expires_at).as_bytes());
+        let token = format!(
+            "{}-{}",
+            expires_at,
+            STANDARD.encode(mac.finalize().into_bytes())
+        );
+        let separator = if resource.query.is_empty() { '?' } else { '&' };
+        Ok(EdgeToken::SignedUrl(format!(
+            "{}{}{}{}={}",
+            self.base_url,
+            resource.path_with_query(),
+            separator,
+            self.param,
+            url::form_urlencoded::byte_serialize(token.as_bytes()).collect::<String>()
+        )))
+    }
+}
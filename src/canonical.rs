@@ -0,0 +1,151 @@
+/// Canonical JSON module.
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+#[derive(Debug)]
+pub enum CanonicalError {
+    EncodingError(String),
+}
+
+impl std::fmt::Display for CanonicalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EncodingError(msg) => write!(f, "Canonical encoding error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CanonicalError {}
+
+/// Canonical JSON of a payload following RFC 8785 (JCS): object keys sorted
+/// by their UTF-16 code units, no insignificant whitespace, minimal string
+/// escapes and numbers formatted like ECMAScript `Number.toString`.
+///
+/// Every signature over a JSON payload is computed over these bytes, so any
+/// implementation of the scheme reproduces them from the parsed payload.
+/// Integers beyond 2^53 are written as the nearest double, like any JCS
+/// implementation does, so amounts that large have to be sent as strings.
+///
+/// # Examples
+///
+/// ```rust
+/// use serde_json::json;
+/// use x402_sdk::canonical::to_canonical_string;
+///
+/// let canonical = to_canonical_string(&json!({"b": 4.50, "a": [1e30, 2e-3]})).unwrap();
+/// assert_eq!(canonical, r#"{"a":[1e+30,0.002],"b":4.5}"#);
+/// ```
+pub fn to_canonical_vec<T: Serialize + ?Sized>(payload: &T) -> Result<Vec<u8>, CanonicalError> {
+    let value =
+        serde_json::to_value(payload).map_err(|e| CanonicalError::EncodingError(e.to_string()))?;
+    let mut out = Vec::new();
+    write_canonical(&value, &mut out)?;
+    Ok(out)
+}
+
+pub fn to_canonical_string<T: Serialize + ?Sized>(payload: &T) -> Result<String, CanonicalError> {
+    // the encoder only writes valid UTF-8
+    to_canonical_vec(payload).map(|bytes| String::from_utf8(bytes).unwrap_or_default())
+}
+
+/// append the canonical encoding of `value` to `out`
+pub fn write_canonical(value: &Value, out: &mut Vec<u8>) -> Result<(), CanonicalError> {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push(b'{');
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_string(key, out)?;
+                out.push(b':');
+                write_canonical(&map[key], out)?;
+            }
+            out.push(b'}');
+        }
+        Value::Array(items) => {
+            out.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(b']');
+        }
+        Value::String(string) => write_string(string, out)?,
+        Value::Number(number) => out.extend(number_to_string(number)?.as_bytes()),
+        Value::Bool(true) => out.extend(b"true"),
+        Value::Bool(false) => out.extend(b"false"),
+        Value::Null => out.extend(b"null"),
+    }
+    Ok(())
+}
+
+/// serde_json escapes exactly what JCS does: `"`, `\` and control
+/// characters, with lowercase `\u00xx` where no short escape exists
+fn write_string(string: &str, out: &mut Vec<u8>) -> Result<(), CanonicalError> {
+    serde_json::to_writer(&mut *out, string)
+        .map_err(|e| CanonicalError::EncodingError(e.to_string()))
+}
+
+/// largest integer every double represents exactly
+const MAX_SAFE_INTEGER: u64 = 1 << 53;
+
+fn number_to_string(number: &Number) -> Result<String, CanonicalError> {
+    if let Some(integer) = number.as_u64()
+        && integer <= MAX_SAFE_INTEGER
+    {
+        return Ok(integer.to_string());
+    }
+    if let Some(integer) = number.as_i64()
+        && integer.unsigned_abs() <= MAX_SAFE_INTEGER
+    {
+        return Ok(integer.to_string());
+    }
+    let float = number
+        .as_f64()
+        .filter(|float| float.is_finite())
+        .ok_or_else(|| CanonicalError::EncodingError(format!("invalid number {}", number)))?;
+    Ok(ecmascript_number(float))
+}
+
+/// `Number.prototype.toString` of ECMAScript for a finite double
+fn ecmascript_number(float: f64) -> String {
+    if float == 0.0 {
+        return "0".to_string();
+    }
+    let sign = if float < 0.0 { "-" } else { "" };
+    // shortest round-tripping digits, e.g. `3.333333333333333e8`
+    let scientific = format!("{:e}", float.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let k = digits.len() as i32;
+    // position of the decimal point relative to the digits
+    let n = exponent + 1;
+    let formatted = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let fraction = if k > 1 {
+            format!(".{}", &digits[1..])
+        } else {
+            String::new()
+        };
+        let exponent_sign = if n >= 1 { "+" } else { "-" };
+        format!(
+            "{}{}e{}{}",
+            &digits[..1],
+            fraction,
+            exponent_sign,
+            (n - 1).abs()
+        )
+    };
+    format!("{}{}", sign, formatted)
+}
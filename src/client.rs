@@ -0,0 +1,234 @@
+/// Client polling module.
+use crate::core::{EngineError, X402};
+use crate::resource::Resource;
+use crate::types::PaymentVerification;
+use crate::verifier::VerificationError;
+use rand::Rng;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug)]
+pub enum PollError {
+    /// the payment was not verified before the deadline
+    Timeout {
+        attempts: u32,
+    },
+    Http(String),
+    Engine(EngineError),
+}
+
+impl std::fmt::Display for PollError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout { attempts } => {
+                write!(f, "Payment not verified after {} attempts", attempts)
+            }
+            Self::Http(msg) => write!(f, "HTTP error: {}", msg),
+            Self::Engine(err) => write!(f, "Engine error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PollError {}
+
+/// Exponential backoff between status checks. The paywall page of
+/// `x402 scaffold` polls with the same defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub multiplier: u32,
+    /// each delay is spread uniformly by up to this share in either
+    /// direction, so clients paying at the same time do not poll in lockstep
+    pub jitter_percent: u8,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+            multiplier: 2,
+            jitter_percent: 20,
+        }
+    }
+}
+
+impl Backoff {
+    /// delay after the `attempt`th check, counted from 0, before jitter
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let factor = self.multiplier.max(1).saturating_pow(attempt);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let base = self.base_delay(attempt);
+        let jitter = base.as_millis() as u64 * u64::from(self.jitter_percent.min(100)) / 100;
+        if jitter == 0 {
+            return base;
+        }
+        let offset = rand::rng().random_range(0..=2 * jitter);
+        (base + Duration::from_millis(offset)).saturating_sub(Duration::from_millis(jitter))
+    }
+}
+
+/// What [`poll_until_paid`] checks the payment status with.
+#[derive(Clone, Copy)]
+pub enum PollTarget<'a> {
+    /// the `verification_url` of the challenge, answering 200 once the
+    /// payment is verified, optionally with the verification as body, and
+    /// 402 or 202 while it is pending
+    Url(&'a str),
+    /// an engine in the same process, e.g. in a test or a worker
+    Engine {
+        engine: &'a X402,
+        user_address: &'a str,
+        resource: &'a Resource,
+    },
+}
+
+/// Outcome of a successful poll.
+#[derive(Debug, Clone)]
+pub struct Paid {
+    /// status checks made, the last one included
+    pub attempts: u32,
+    /// verification of the payment, `None` when the verification URL
+    /// answered without one
+    pub verification: Option<PaymentVerification>,
+}
+
+enum Status {
+    Paid(Option<Box<PaymentVerification>>),
+    /// seconds the server asked to wait, if any
+    Pending(Option<u64>),
+}
+
+/// Polls a payment until it is verified, with the default [`Backoff`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use tokio::time::Instant;
+/// use x402_sdk::client::{PollTarget, poll_until_paid};
+///
+/// # async fn example(verification_url: &str, nonce: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// let deadline = Instant::now() + Duration::from_secs(120);
+/// let paid = poll_until_paid(PollTarget::Url(verification_url), nonce, deadline).await?;
+/// println!("verified after {} checks", paid.attempts);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn poll_until_paid(
+    target: PollTarget<'_>,
+    nonce: &str,
+    deadline: Instant,
+) -> Result<Paid, PollError> {
+    Poller::new(Backoff::default())
+        .poll_until_paid(target, nonce, deadline)
+        .await
+}
+
+/// Polls payment status checks spaced by a [`Backoff`], honoring the
+/// `Retry-After` of throttled checks.
+#[derive(Debug, Clone)]
+pub struct Poller {
+    backoff: Backoff,
+    client: reqwest::Client,
+}
+
+impl Poller {
+    pub fn new(backoff: Backoff) -> Self {
+        Self {
+            backoff,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Checks the payment until it is verified or the deadline passes, the
+    /// last check is made at the deadline.
+    pub async fn poll_until_paid(
+        &self,
+        target: PollTarget<'_>,
+        nonce: &str,
+        deadline: Instant,
+    ) -> Result<Paid, PollError> {
+        let mut attempts = 0;
+        loop {
+            let status = self.check(target, nonce).await?;
+            attempts += 1;
+            let retry_after = match status {
+                Status::Paid(verification) => {
+                    return Ok(Paid {
+                        attempts,
+                        verification: verification.map(|verification| *verification),
+                    });
+                }
+                Status::Pending(retry_after) => retry_after,
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(PollError::Timeout { attempts });
+            }
+            let delay = self
+                .backoff
+                .delay(attempts - 1)
+                .max(Duration::from_secs(retry_after.unwrap_or(0)));
+            tokio::time::sleep(delay.min(deadline - now)).await;
+        }
+    }
+
+    async fn check(&self, target: PollTarget<'_>, nonce: &str) -> Result<Status, PollError> {
+        match target {
+            PollTarget::Url(url) => self.check_url(url).await,
+            PollTarget::Engine {
+                engine,
+                user_address,
+                resource,
+            } => match engine.verify_payment(user_address, nonce, resource).await {
+                Ok(verification) if verification.is_paid => {
+                    Ok(Status::Paid(Some(Box::new(verification))))
+                }
+                Ok(_) => Ok(Status::Pending(None)),
+                Err(EngineError::TooManyAttempts { retry_after })
+                | Err(EngineError::Locked { retry_after })
+                | Err(EngineError::VerificationFailed(
+                    VerificationError::CircuitOpen { retry_after }
+                    | VerificationError::Saturated { retry_after },
+                )) => Ok(Status::Pending(Some(retry_after))),
+                Err(err) => Err(PollError::Engine(err)),
+            },
+        }
+    }
+
+    async fn check_url(&self, url: &str) -> Result<Status, PollError> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| PollError::Http(e.to_string()))?;
+        let status = response.status().as_u16();
+        let retry_after = response
+            .headers()
+            .get("Retry-After")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+        match status {
+            200 => Ok(Status::Paid(response.json().await.ok().map(Box::new))),
+            202 | 402 | 429 | 503 => Ok(Status::Pending(retry_after)),
+            status => Err(PollError::Http(format!("unexpected status {}", status))),
+        }
+    }
+}
+
+impl Default for Poller {
+    fn default() -> Self {
+        Self::new(Backoff::default())
+    }
+}
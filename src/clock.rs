@@ -0,0 +1,25 @@
+/// Clock module.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time used for timestamps and expiry logic.
+///
+/// All engine and verifier timestamp reads go through this trait so that
+/// expiration behaviour can be driven deterministically, see
+/// [`MockClock`](crate::testing::MockClock).
+pub trait Clock: Send + Sync {
+    /// current unix timestamp in seconds
+    fn now(&self) -> u64;
+}
+
+/// Clock backed by the operating system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+}
@@ -0,0 +1,210 @@
+/// Sanctions and compliance screening module.
+use crate::anomaly::{ScreeningProvider, StaticScreeningList};
+use crate::types::ChainType;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+pub enum ComplianceError {
+    RequestFailed(String),
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for ComplianceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RequestFailed(msg) => write!(f, "Screening request failed: {}", msg),
+            Self::InvalidResponse(msg) => write!(f, "Invalid screening response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ComplianceError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreeningOutcome {
+    Clear,
+    /// the address must not be served, e.g. it is sanctioned
+    Blocked {
+        reason: String,
+    },
+}
+
+/// What the engine does when the compliance screen cannot answer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreeningFailureMode {
+    /// serve the content as if the payer was clear
+    FailOpen,
+    /// refuse the content until the screen answers
+    #[default]
+    FailClosed,
+}
+
+/// Screens the payer of a request before paid content is served, see
+/// [`X402::with_compliance_screen`](crate::core::X402::with_compliance_screen).
+#[async_trait]
+pub trait ComplianceScreen: Send + Sync {
+    async fn screen(
+        &self,
+        address: &str,
+        chain_type: &ChainType,
+    ) -> Result<ScreeningOutcome, ComplianceError>;
+}
+
+/// The static list works as a denylist on every chain.
+#[async_trait]
+impl ComplianceScreen for StaticScreeningList {
+    async fn screen(
+        &self,
+        address: &str,
+        _chain_type: &ChainType,
+    ) -> Result<ScreeningOutcome, ComplianceError> {
+        Ok(match ScreeningProvider::screen(self, address) {
+            Some(reason) => ScreeningOutcome::Blocked { reason },
+            None => ScreeningOutcome::Clear,
+        })
+    }
+}
+
+/// Request and response format of a screening API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreeningApi {
+    /// `GET {base_url}/address/{address}` with an `X-API-Key` header,
+    /// answering `{"identifications": [...]}`, as the Chainalysis sanctions
+    /// API
+    Chainalysis,
+    /// `POST {base_url}/sanctions/screening` with `[{"address": ...}]` and
+    /// basic authentication, answering `[{"address": ..., "isSanctioned":
+    /// bool}]`, as the TRM sanctions API
+    Trm,
+}
+
+#[derive(Deserialize)]
+struct ChainalysisResponse {
+    #[serde(default)]
+    identifications: Vec<ChainalysisIdentification>,
+}
+
+#[derive(Deserialize)]
+struct ChainalysisIdentification {
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrmScreening {
+    #[serde(default)]
+    is_sanctioned: bool,
+}
+
+/// Compliance screen backed by a hosted sanctions API.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::compliance::{ComplianceScreen, HttpComplianceScreen};
+/// use x402_sdk::types::ChainType;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let screen = HttpComplianceScreen::chainalysis("api-key");
+/// let outcome = screen
+///     .screen("0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5", &ChainType::ethereum())
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct HttpComplianceScreen {
+    api: ScreeningApi,
+    base_url: String,
+    api_key: String,
+    client: reqwest::Client,
+}
+
+impl HttpComplianceScreen {
+    pub fn new(api: ScreeningApi, base_url: &str, api_key: &str) -> Self {
+        Self {
+            api,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn chainalysis(api_key: &str) -> Self {
+        Self::new(
+            ScreeningApi::Chainalysis,
+            "https://public.chainalysis.com/api/v1",
+            api_key,
+        )
+    }
+
+    pub fn trm(api_key: &str) -> Self {
+        Self::new(
+            ScreeningApi::Trm,
+            "https://api.trmlabs.com/public/v1",
+            api_key,
+        )
+    }
+}
+
+#[async_trait]
+impl ComplianceScreen for HttpComplianceScreen {
+    async fn screen(
+        &self,
+        address: &str,
+        _chain_type: &ChainType,
+    ) -> Result<ScreeningOutcome, ComplianceError> {
+        let request = match self.api {
+            ScreeningApi::Chainalysis => self
+                .client
+                .get(format!("{}/address/{}", self.base_url, address))
+                .header("X-API-Key", &self.api_key),
+            ScreeningApi::Trm => self
+                .client
+                .post(format!("{}/sanctions/screening", self.base_url))
+                .basic_auth(&self.api_key, Some(&self.api_key))
+                .json(&serde_json::json!([{ "address": address }])),
+        };
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ComplianceError::RequestFailed(e.to_string()))?;
+        match self.api {
+            ScreeningApi::Chainalysis => {
+                let body: ChainalysisResponse = response
+                    .json()
+                    .await
+                    .map_err(|e| ComplianceError::InvalidResponse(e.to_string()))?;
+                Ok(match body.identifications.first() {
+                    None => ScreeningOutcome::Clear,
+                    Some(identification) => ScreeningOutcome::Blocked {
+                        reason: identification
+                            .name
+                            .clone()
+                            .or_else(|| identification.category.clone())
+                            .unwrap_or_else(|| "sanctioned".to_string()),
+                    },
+                })
+            }
+            ScreeningApi::Trm => {
+                let body: Vec<TrmScreening> = response
+                    .json()
+                    .await
+                    .map_err(|e| ComplianceError::InvalidResponse(e.to_string()))?;
+                let screening = body.first().ok_or_else(|| {
+                    ComplianceError::InvalidResponse("empty screening result".to_string())
+                })?;
+                if !screening.is_sanctioned {
+                    return Ok(ScreeningOutcome::Clear);
+                }
+                Ok(ScreeningOutcome::Blocked {
+                    reason: "sanctioned".to_string(),
+                })
+            }
+        }
+    }
+}
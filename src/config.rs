@@ -1,5 +1,12 @@
 /// Configuration module
-use crate::types::{AptosChain, ChainConfig, ChainType, EvmChain, SolanaChain, SuiChain};
+use crate::compliance::ScreeningFailureMode;
+use crate::crypto::SignatureSuite;
+use crate::redaction::RedactionPolicy;
+use crate::resource::Resource;
+use crate::simulation::SimulationRule;
+use crate::types::{
+    AmountBounds, AmountTolerance, ChainConfig, ChainType, EvmChain, PaymentRequest,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
@@ -48,6 +55,83 @@ pub struct X402Config {
     pub payments: PaymentConfig,
     pub cache: CacheConfig,
     pub default_chain: ChainType,
+    #[serde(default)]
+    pub sessions: SessionConfig,
+    #[serde(default)]
+    pub signing: Option<SigningConfig>,
+    #[serde(default)]
+    pub receipts: ReceiptConfig,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
+    #[serde(default)]
+    pub verification_pool: VerificationPoolConfig,
+    #[serde(default)]
+    pub settlement: SettlementConfig,
+    #[serde(default)]
+    pub compliance: ComplianceConfig,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    /// dry-run mode, verifications are decided by the rule instead of the
+    /// chains, see [`SimulatedVerifier`](crate::simulation::SimulatedVerifier)
+    #[serde(default)]
+    pub simulation: Option<SimulationRule>,
+}
+
+/// Redaction of sensitive fields in event payloads and audit records, see
+/// [`Redactor`](crate::redaction::Redactor). Events are redacted when
+/// emitted, before the outbox and the listeners see them. Every field is
+/// kept by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    pub payer_address: RedactionPolicy,
+    pub nonce: RedactionPolicy,
+    pub amount: RedactionPolicy,
+}
+
+impl RedactionConfig {
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+}
+
+/// Behavior of the compliance screen consulted before paid content is
+/// served.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComplianceConfig {
+    /// fail closed by default, refusing content while the screen is down
+    pub failure_mode: ScreeningFailureMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptConfig {
+    /// lifetime of issued receipts, the entitlement of a verified payment
+    pub ttl_secs: u64,
+    /// base URL of the human-readable receipt pages served by the
+    /// integration, the receipt token is appended as last path segment
+    #[serde(default)]
+    pub page_url: Option<String>,
+}
+
+impl Default for ReceiptConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 3600,
+            page_url: None,
+        }
+    }
+}
+
+/// Published identity of the key signing 402 challenges and receipts, the
+/// private key itself is read from the `X402_SIGNING_KEY` environment
+/// variable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningConfig {
+    /// signer of the suite: the address of a secp256k1 key, the hex public
+    /// key of an Ed25519 key or the key id of an HMAC secret
+    pub signer_address: String,
+    #[serde(default)]
+    pub suite: SignatureSuite,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +163,54 @@ pub struct PaymentConfig {
     pub expiration_time_secs: u64,
     pub allowed_currencies: Vec<CurrencyConfig>,
     pub fee_recovery_percent: f64,
+    /// challenges name the requester as beneficiary and accept a payment
+    /// from any payer carrying the session reference
+    #[serde(default)]
+    pub delegated: bool,
+    /// how far below the USD amount an any-token payment may be valued, in
+    /// basis points
+    #[serde(default = "default_slippage_bps")]
+    pub slippage_bps: u32,
+    /// dust tolerance per currency, keyed by token address or mint, or
+    /// [`NATIVE_TOKEN`](crate::rates::NATIVE_TOKEN) for the native currency
+    #[serde(default)]
+    pub amount_tolerances: HashMap<String, AmountTolerance>,
+    /// accepted range of custom amounts per currency, keyed like
+    /// `amount_tolerances`, unbounded when missing
+    #[serde(default)]
+    pub amount_bounds: HashMap<String, AmountBounds>,
+    /// transaction logs kept in a verification, `0` keeps every log
+    #[serde(default = "default_max_transaction_logs")]
+    pub max_transaction_logs: usize,
+    /// attach a proof of the payment to verifications, see
+    /// [`PaymentProof`](crate::types::PaymentProof)
+    #[serde(default)]
+    pub include_proofs: bool,
+    /// seconds after `expires_at` a challenge still verifies, so a payment
+    /// sent just before expiry is honored once it lands on chain
+    #[serde(default = "default_expiry_grace_secs")]
+    pub expiry_grace_secs: u64,
+}
+
+impl PaymentConfig {
+    /// expiry of a challenge past `expires_at` and the grace period
+    pub fn expired_at(&self, payment_request: &PaymentRequest, now: u64) -> Option<u64> {
+        payment_request
+            .expires_at
+            .filter(|expires_at| now > expires_at.saturating_add(self.expiry_grace_secs))
+    }
+}
+
+fn default_expiry_grace_secs() -> u64 {
+    60
+}
+
+fn default_slippage_bps() -> u32 {
+    100
+}
+
+fn default_max_transaction_logs() -> usize {
+    20
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -88,6 +220,170 @@ pub struct CacheConfig {
     pub max_entries: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionConfig {
+    pub nonce_strategy: NonceStrategy,
+    #[serde(default)]
+    pub binding: SessionBinding,
+    #[serde(default)]
+    pub attempts: AttemptLimitConfig,
+    /// seconds a replica holds the lock of a session it verifies, see
+    /// [`LockStore`](crate::store::LockStore)
+    #[serde(default = "default_lock_ttl_secs")]
+    pub lock_ttl_secs: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            nonce_strategy: NonceStrategy::default(),
+            binding: SessionBinding::default(),
+            attempts: AttemptLimitConfig::default(),
+            lock_ttl_secs: default_lock_ttl_secs(),
+        }
+    }
+}
+
+fn default_lock_ttl_secs() -> u64 {
+    30
+}
+
+/// Circuit breaker wrapped around every registered chain verifier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// consecutive RPC failures that open the circuit, 0 disables the breaker
+    pub failure_threshold: u32,
+    /// seconds the circuit stays open before a probe is let through
+    pub open_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_secs: 30,
+        }
+    }
+}
+
+/// Bounded queue in front of every registered chain verifier, limits apply
+/// per chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationPoolConfig {
+    /// verifications calling the chain at once, 0 disables the pool
+    pub max_concurrent: usize,
+    /// verifications waiting for a slot, further ones are turned away
+    pub max_queued: usize,
+    /// milliseconds a queued verification waits for a slot
+    pub queue_timeout_ms: u64,
+    /// seconds a turned away client is asked to wait before retrying
+    pub retry_after_secs: u64,
+}
+
+impl Default for VerificationPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 16,
+            max_queued: 64,
+            queue_timeout_ms: 2000,
+            retry_after_secs: 2,
+        }
+    }
+}
+
+/// Retry policy of queued relay settlements.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementConfig {
+    /// submissions and replacements before a settlement is failed
+    pub max_attempts: u32,
+    /// delay after a failed attempt, doubled for every further failure and
+    /// capped at `retry_max_secs`
+    pub retry_base_secs: u64,
+    pub retry_max_secs: u64,
+    /// seconds a submitted transaction may stay unmined before it is
+    /// replaced with a higher fee
+    pub replace_after_secs: u64,
+    /// fee increase of a replacement, in percent of the previous fee
+    pub gas_bump_percent: u32,
+}
+
+impl Default for SettlementConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            retry_base_secs: 15,
+            retry_max_secs: 900,
+            replace_after_secs: 120,
+            gas_bump_percent: 20,
+        }
+    }
+}
+
+/// Limits on verification attempts per session, bounding the RPC cost of
+/// clients that poll without ever paying.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttemptLimitConfig {
+    /// consecutive failed verifications before the session is locked, 0
+    /// disables the lockout
+    pub max_failed_attempts: u32,
+    pub lockout_secs: u64,
+    /// minimum delay after a failed attempt, doubled for every consecutive
+    /// failure and capped at `lockout_secs`, 0 disables the backoff
+    pub backoff_base_secs: u64,
+}
+
+impl Default for AttemptLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 10,
+            lockout_secs: 300,
+            backoff_base_secs: 0,
+        }
+    }
+}
+
+/// Which resources a verified payment session unlocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionBinding {
+    /// only the exact resource (method, path and query) the session was
+    /// issued for
+    #[default]
+    PerResource,
+    /// the session path and every path below it, for any method and query
+    PerPathPrefix,
+    /// any resource, the payment is bound to the payer only
+    AccountWide,
+}
+
+impl SessionBinding {
+    /// whether a session issued for `bound` may unlock `requested`
+    pub fn permits(&self, bound: &Resource, requested: &Resource) -> bool {
+        match self {
+            Self::PerResource => bound == requested,
+            Self::PerPathPrefix => {
+                let prefix = bound.path.trim_end_matches('/');
+                requested.path == bound.path
+                    || requested
+                        .path
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+            Self::AccountWide => true,
+        }
+    }
+}
+
+/// How payment nonces are generated when a 402 challenge is issued.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub enum NonceStrategy {
+    /// random UUID per challenge, sessions only live in the issuing process
+    #[default]
+    Random,
+    /// HMAC over payer, resource, amount and price epoch with a secret shared
+    /// by all replicas, so any replica can re-derive the session
+    Deterministic { secret: String, epoch_secs: u64 },
+}
+
 pub struct ConfigManager {
     config: X402Config,
     environment: HashMap<String, String>,
@@ -147,6 +443,10 @@ impl ConfigManager {
             .unwrap_or_else(|| "0x0000000000000000000000000000000000000000".to_string())
     }
 
+    pub fn get_signing_key(&self) -> Option<String> {
+        self.environment.get("X402_SIGNING_KEY").cloned()
+    }
+
     pub fn update_config<F>(&mut self, updater: F)
     where
         F: FnOnce(&mut X402Config),
@@ -197,6 +497,13 @@ impl ConfigManager {
                     decimals: 18,
                 }],
                 fee_recovery_percent: 0.1,
+                delegated: false,
+                slippage_bps: default_slippage_bps(),
+                amount_tolerances: HashMap::new(),
+                amount_bounds: HashMap::new(),
+                max_transaction_logs: default_max_transaction_logs(),
+                include_proofs: false,
+                expiry_grace_secs: default_expiry_grace_secs(),
             },
             cache: CacheConfig {
                 enabled: true,
@@ -204,6 +511,15 @@ impl ConfigManager {
                 max_entries: 1000,
             },
             default_chain: ChainType::Evm(EvmChain::Ethereum),
+            sessions: SessionConfig::default(),
+            signing: None,
+            receipts: ReceiptConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            verification_pool: VerificationPoolConfig::default(),
+            settlement: SettlementConfig::default(),
+            compliance: ComplianceConfig::default(),
+            redaction: RedactionConfig::default(),
+            simulation: None,
         }
     }
 }
@@ -244,7 +560,143 @@ impl ConfigBuilder {
         self
     }
 
+    /// seconds an expired challenge still verifies, 60 by default
+    pub fn with_expiry_grace(mut self, seconds: u64) -> Self {
+        self.config.payments.expiry_grace_secs = seconds;
+        self
+    }
+
+    pub fn with_delegated_payments(mut self, delegated: bool) -> Self {
+        self.config.payments.delegated = delegated;
+        self
+    }
+
+    /// accept payments of `token` short of the amount by the tolerance
+    pub fn with_amount_tolerance(mut self, token: &str, tolerance: AmountTolerance) -> Self {
+        self.config
+            .payments
+            .amount_tolerances
+            .insert(token.to_string(), tolerance);
+        self
+    }
+
+    /// reject custom amounts of `token` outside the bounds
+    pub fn with_amount_bounds(mut self, token: &str, bounds: AmountBounds) -> Self {
+        self.config
+            .payments
+            .amount_bounds
+            .insert(token.to_string(), bounds);
+        self
+    }
+
+    /// cap the transaction logs of a verification, `0` keeps every log
+    pub fn with_max_transaction_logs(mut self, max_transaction_logs: usize) -> Self {
+        self.config.payments.max_transaction_logs = max_transaction_logs;
+        self
+    }
+
+    pub fn with_payment_proofs(mut self, include_proofs: bool) -> Self {
+        self.config.payments.include_proofs = include_proofs;
+        self
+    }
+
+    pub fn with_attempt_limits(
+        mut self,
+        max_failed_attempts: u32,
+        lockout_secs: u64,
+        backoff_base_secs: u64,
+    ) -> Self {
+        self.config.sessions.attempts = AttemptLimitConfig {
+            max_failed_attempts,
+            lockout_secs,
+            backoff_base_secs,
+        };
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, open_secs: u64) -> Self {
+        self.config.circuit_breaker = CircuitBreakerConfig {
+            failure_threshold,
+            open_secs,
+        };
+        self
+    }
+
+    /// per chain concurrency limit and wait queue of verifications
+    pub fn with_verification_pool(
+        mut self,
+        max_concurrent: usize,
+        max_queued: usize,
+        queue_timeout_ms: u64,
+    ) -> Self {
+        self.config.verification_pool = VerificationPoolConfig {
+            max_concurrent,
+            max_queued,
+            queue_timeout_ms,
+            ..self.config.verification_pool
+        };
+        self
+    }
+
+    /// issue challenges as usual but decide payments by the rule, no RPC
+    /// is contacted
+    pub fn with_simulation(mut self, rule: SimulationRule) -> Self {
+        self.config.simulation = Some(rule);
+        self
+    }
+
+    pub fn with_settlement_retries(
+        mut self,
+        max_attempts: u32,
+        retry_base_secs: u64,
+        retry_max_secs: u64,
+    ) -> Self {
+        self.config.settlement.max_attempts = max_attempts;
+        self.config.settlement.retry_base_secs = retry_base_secs;
+        self.config.settlement.retry_max_secs = retry_max_secs;
+        self
+    }
+
+    pub fn with_session_binding(mut self, binding: SessionBinding) -> Self {
+        self.config.sessions.binding = binding;
+        self
+    }
+
+    pub fn with_deterministic_sessions(mut self, secret: &str, epoch_secs: u64) -> Self {
+        self.config.sessions.nonce_strategy = NonceStrategy::Deterministic {
+            secret: secret.to_string(),
+            epoch_secs,
+        };
+        self
+    }
+
+    pub fn with_screening_failure_mode(mut self, failure_mode: ScreeningFailureMode) -> Self {
+        self.config.compliance.failure_mode = failure_mode;
+        self
+    }
+
+    pub fn with_redaction(mut self, redaction: RedactionConfig) -> Self {
+        self.config.redaction = redaction;
+        self
+    }
+
+    pub fn with_receipt_ttl(mut self, seconds: u64) -> Self {
+        self.config.receipts.ttl_secs = seconds;
+        self
+    }
+
+    pub fn with_receipt_page_url(mut self, page_url: &str) -> Self {
+        self.config.receipts.page_url = Some(page_url.trim_end_matches('/').to_string());
+        self
+    }
+
     pub fn build(self) -> X402Config {
         self.config
     }
 }
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
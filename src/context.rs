@@ -0,0 +1,131 @@
+/// Request context module.
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// headers of the request kept on [`PaymentEvent`](crate::events::PaymentEvent)s,
+/// the others can carry credentials and are never emitted
+pub const EVENT_HEADERS: [&str; 3] = ["content-type", "traceparent", "tracestate"];
+
+/// Metadata of the incoming request carried through pricing, access policies
+/// and events.
+///
+/// Header names are stored lowercased so lookups are case-insensitive.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::context::RequestContext;
+///
+/// let context = RequestContext::new("GET")
+///     .with_header("User-Agent", "curl/8.0")
+///     .with_client_ip("203.0.113.7".parse().unwrap());
+/// assert_eq!(context.header("user-agent"), Some("curl/8.0"));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestContext {
+    pub method: String,
+    pub headers: HashMap<String, String>,
+    pub client_ip: Option<IpAddr>,
+    #[serde(skip)]
+    pub extensions: Extensions,
+}
+
+impl RequestContext {
+    pub fn new(method: &str) -> Self {
+        Self {
+            method: method.to_uppercase(),
+            headers: HashMap::new(),
+            client_ip: None,
+            extensions: Extensions::default(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers
+            .insert(name.to_ascii_lowercase(), value.to_string());
+        self
+    }
+
+    pub fn with_client_ip(mut self, client_ip: IpAddr) -> Self {
+        self.client_ip = Some(client_ip);
+        self
+    }
+
+    pub fn with_extension<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.extensions.insert(value);
+        self
+    }
+
+    /// get a header value by case-insensitive name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(|v| v.as_str())
+    }
+
+    /// Copy carried by events: the method, the [`EVENT_HEADERS`] and the
+    /// extensions. Credentials such as `Authorization`, `Cookie` or API key
+    /// headers and the client IP are left out whatever the redaction
+    /// config, events reach listeners, the outbox and external sinks.
+    pub fn for_event(&self) -> Self {
+        Self {
+            method: self.method.clone(),
+            headers: self
+                .headers
+                .iter()
+                .filter(|(name, _)| EVENT_HEADERS.contains(&name.as_str()))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            client_ip: None,
+            extensions: self.extensions.clone(),
+        }
+    }
+}
+
+impl Default for RequestContext {
+    fn default() -> Self {
+        Self::new("GET")
+    }
+}
+
+/// Type keyed map for arbitrary application data attached to a request.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    /// insert a value, replacing any previous value of the same type
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        self.map.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|v| v.downcast_ref::<T>())
+    }
+
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
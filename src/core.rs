@@ -1,20 +1,64 @@
 /// x402 Core module.
-use crate::config::{ConfigError, ConfigManager};
+use crate::audit::{AuditAction, AuditEntry, AuditError, AuditLog, InMemoryAuditLog};
+use crate::clock::{Clock, SystemClock};
+use crate::compliance::{ComplianceScreen, ScreeningFailureMode, ScreeningOutcome};
+use crate::config::{AttemptLimitConfig, ConfigError, ConfigManager};
+use crate::context::RequestContext;
+use crate::coupon::{CouponBook, CouponError};
+use crate::crypto::{CryptoSuite, SignatureSuite};
+use crate::discovery::DiscoveryDocument;
+use crate::dispute::{Dispute, DisputeNote, DisputeStatus, ManualOverride, OverrideDecision};
+use crate::events::{EventEmitter, EventListener, PaymentEventKind};
+use crate::export::{self, ExportError, ExportFormat, RevenueLine};
+use crate::hooks::{EngineHooks, HookDecision};
+use crate::i18n::{MessageCatalog, MessageKey};
+use crate::invoice::{Invoice, invoice};
+use crate::keys::{JwkSet, KeyRing};
+use crate::ledger::{Ledger, LedgerError};
+use crate::limits::PayloadLimits;
+use crate::policy::{AccessDecision, AccessPolicy};
+use crate::pricing::{PricingError, PricingProvider};
+use crate::rates::RateProvider;
+use crate::receipt::{RECEIPT_DOMAIN, RECEIPT_HEADER, Receipt, ReceiptError};
+use crate::receipt_page::ReceiptPage;
+use crate::redaction::{RedactingAuditLog, Redactor};
+use crate::relay::{RelayError, Relayer, TransactionStatus, TransferAuthorization};
+use crate::resource::{Resource, ResourceError};
+use crate::revocation::{RevocationReason, RevocationScope, RevocationStore};
+use crate::services::AccessRequest;
+use crate::services::challenge::Challenges;
+use crate::services::entitlement::Entitlements;
+use crate::services::verification::Verifications;
+use crate::shard::ShardedMap;
+use crate::signing::{ChallengeSigner, SignatureError};
+use crate::store::{
+    LockStore, OutboxStore, PaymentRecord, PaymentStore, SettlementJob, SettlementStatus,
+    SettlementStore, StoreError,
+};
+use crate::tax::{TaxCalculator, tax_line};
+use crate::telemetry;
 use crate::types::{
-    ChainType, Currency, PaymentRequest, PaymentVerification, VerificationResult,
-    X402ProtocolResponse,
+    ChainConfig, ChainType, Currency, ErrorReason, Finality, PaymentMetadata, PaymentRequest,
+    PaymentVerification, Requote, VerificationResult,
 };
+use crate::usage::UsageTracker;
+use crate::verifier::breaker::CircuitStatus;
+use crate::verifier::light_client::LightClient;
 use crate::verifier::{PaymentVerifier, VerificationError, VerifierRegistry};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Core for handling x402 Payment Required protocol.
 ///
 /// # Examples
 ///
-/// ```rust
-/// use x402::core::X402;
+/// ```rust,no_run
+/// use x402_sdk::core::X402;
+/// use x402_sdk::types::{ChainType, EvmChain};
 ///
 /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 /// let mut engine = X402::from_config_file("config.toml")?;
@@ -40,20 +84,344 @@ use uuid::Uuid;
 /// # }
 /// ```
 pub struct X402 {
-    config_manager: ConfigManager,
-    verifier_registry: VerifierRegistry,
-    payment_sessions_cache: Arc<RwLock<HashMap<String, PaymentSession>>>,
+    config_manager: Arc<ConfigManager>,
+    payment_sessions_cache: Arc<ShardedMap<PaymentSession>>,
+    challenges: Challenges,
+    verifications: Verifications,
+    entitlements: Entitlements,
+    clock: Arc<dyn Clock>,
+    access_policies: Vec<Arc<dyn AccessPolicy>>,
+    hooks: Vec<Arc<dyn EngineHooks>>,
+    events: EventEmitter,
+    payment_store: Option<Arc<dyn PaymentStore>>,
+    audit_log: Arc<dyn AuditLog>,
+    coupons: Option<Arc<CouponBook>>,
+    usage_tracker: Option<Arc<UsageTracker>>,
+    ledger: Option<Arc<Ledger>>,
+    relayer: Option<Arc<dyn Relayer>>,
+    settlement_store: Option<Arc<dyn SettlementStore>>,
+    lock_store: Option<Arc<dyn LockStore>>,
+    /// owner of the locks taken by this engine
+    replica_id: String,
+    compliance_screen: Option<Arc<dyn ComplianceScreen>>,
+    messages: MessageCatalog,
+    payload_limits: PayloadLimits,
 }
 
 impl X402 {
     pub fn new(config_manager: ConfigManager) -> Result<Self, EngineError> {
+        let config_manager = Arc::new(config_manager);
+        let key_ring = Self::load_challenge_signer(&config_manager)?.map(Self::single_key_ring);
+        let audit_log = Self::redacting(&config_manager, Arc::new(InMemoryAuditLog::new()));
+        let sessions = Arc::new(ShardedMap::default());
+        let mut challenges = Challenges::new(config_manager.clone(), sessions.clone())?;
+        let mut entitlements = Entitlements::new(config_manager.clone(), sessions.clone())
+            .with_audit_log(audit_log.clone());
+        if let Some(key_ring) = key_ring {
+            challenges = challenges.with_key_ring(key_ring.clone());
+            entitlements = entitlements.with_key_ring(key_ring);
+        }
         Ok(Self {
+            verifications: Verifications::new(config_manager.clone(), sessions.clone()),
+            challenges,
+            entitlements,
+            events: EventEmitter::new(Redactor::new(config_manager.get_config().redaction.clone())),
             config_manager,
-            verifier_registry: VerifierRegistry::new(),
-            payment_sessions_cache: Arc::new(RwLock::new(HashMap::new())),
+            payment_sessions_cache: sessions,
+            clock: Arc::new(SystemClock),
+            access_policies: Vec::new(),
+            hooks: Vec::new(),
+            payment_store: None,
+            audit_log,
+            coupons: None,
+            usage_tracker: None,
+            ledger: None,
+            relayer: None,
+            settlement_store: None,
+            lock_store: None,
+            replica_id: Uuid::new_v4().to_string(),
+            compliance_screen: None,
+            messages: MessageCatalog::new(),
+            payload_limits: PayloadLimits::default(),
         })
     }
 
+    /// load the signing key from the environment for the configured suite
+    /// and check it matches the signer published in the configuration
+    fn load_challenge_signer(
+        config_manager: &ConfigManager,
+    ) -> Result<Option<Arc<dyn CryptoSuite>>, EngineError> {
+        let Some(private_key) = config_manager.get_signing_key() else {
+            return Ok(None);
+        };
+        let signing = config_manager.get_config().signing.as_ref();
+        let suite = match signing {
+            Some(signing) => signing.suite.load(&private_key, &signing.signer_address)?,
+            None => SignatureSuite::default().load(&private_key, "default")?,
+        };
+        if let Some(signing) = signing
+            && !signing.signer_address.eq_ignore_ascii_case(&suite.signer())
+        {
+            return Err(EngineError::ConfigError(ConfigError::InvalidConfig(
+                format!(
+                    "signing key {} does not match configured signer {}",
+                    suite.signer(),
+                    signing.signer_address
+                ),
+            )));
+        }
+        Ok(Some(suite))
+    }
+
+    /// sign every issued 402 challenge with the given key, published under
+    /// the key id `default`
+    pub fn with_challenge_signer(self, signer: ChallengeSigner) -> Self {
+        self.with_key_ring(Self::single_key_ring(signer))
+    }
+
+    /// sign challenges and receipts with any suite, e.g. an Ed25519 key or
+    /// an HSM backed [`CryptoSuite`], published under the key id `default`
+    pub fn with_crypto_suite(self, suite: Arc<dyn CryptoSuite>) -> Self {
+        self.with_key_ring(Self::single_key_ring(suite))
+    }
+
+    /// sign issued 402 challenges with the active key of a rotating key ring
+    pub fn with_key_ring(mut self, key_ring: Arc<KeyRing>) -> Self {
+        self.challenges = self.challenges.with_key_ring(key_ring.clone());
+        self.entitlements = self.entitlements.with_key_ring(key_ring);
+        self
+    }
+
+    /// record verified payments for exports and admin tooling
+    pub fn with_payment_store(mut self, payment_store: Arc<dyn PaymentStore>) -> Self {
+        self.verifications = self.verifications.with_payment_store(payment_store.clone());
+        self.payment_store = Some(payment_store);
+        self
+    }
+
+    /// coupons accepted by [`handle_access_request`](Self::handle_access_request)
+    pub fn with_coupons(mut self, coupons: Arc<CouponBook>) -> Self {
+        self.challenges = self.challenges.with_coupons(coupons.clone());
+        self.coupons = Some(coupons);
+        self
+    }
+
+    /// count paid requests per payer, share the tracker with a
+    /// [`TieredPricing`](crate::pricing::TieredPricing) provider
+    pub fn with_usage_tracker(mut self, usage_tracker: Arc<UsageTracker>) -> Self {
+        self.usage_tracker = Some(usage_tracker);
+        self
+    }
+
+    /// budget accounts drawn down instead of issuing a challenge when the
+    /// payer proves it owns one with enough balance, see
+    /// [`Ledger::authorize`]
+    pub fn with_ledger(mut self, ledger: Arc<Ledger>) -> Self {
+        self.challenges = self.challenges.with_ledger(ledger.clone());
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// prices used to value any-token payments, set before registering the
+    /// chain verifiers
+    pub fn with_rate_provider(mut self, rate_provider: Arc<dyn RateProvider>) -> Self {
+        self.challenges = self.challenges.with_rate_provider(rate_provider.clone());
+        self.verifications = self.verifications.with_rate_provider(rate_provider);
+        self
+    }
+
+    /// light client rechecking EVM payments above the light-client threshold
+    /// of their pricing rule, set before registering the chain verifiers
+    pub fn with_light_client(mut self, light_client: Arc<dyn LightClient>) -> Self {
+        self.verifications = self.verifications.with_light_client(light_client);
+        self
+    }
+
+    /// relay signed token authorizations for payers without gas, its gas
+    /// surcharge is added to token challenges
+    pub fn with_relayer(mut self, relayer: Arc<dyn Relayer>) -> Self {
+        self.challenges = self.challenges.with_relayer(relayer.clone());
+        self.relayer = Some(relayer);
+        self
+    }
+
+    /// locks shared with the other replicas, a session is verified and a
+    /// settlement advanced by one replica at a time
+    pub fn with_lock_store(mut self, lock_store: Arc<dyn LockStore>) -> Self {
+        self.lock_store = Some(lock_store);
+        self
+    }
+
+    /// durable queue of relay settlements, see [`X402::queue_settlement`]
+    pub fn with_settlement_store(mut self, settlement_store: Arc<dyn SettlementStore>) -> Self {
+        self.settlement_store = Some(settlement_store);
+        self
+    }
+
+    /// screen payers before paid content is served, on a verified payment
+    /// and on a receipt, see [`ComplianceConfig`](crate::config::ComplianceConfig)
+    /// for the behavior when the screen fails
+    pub fn with_compliance_screen(mut self, screen: Arc<dyn ComplianceScreen>) -> Self {
+        self.compliance_screen = Some(screen);
+        self
+    }
+
+    /// tax added to or annotated on every quote, recorded with the payment
+    pub fn with_tax_calculator(mut self, tax_calculator: Arc<dyn TaxCalculator>) -> Self {
+        self.challenges = self.challenges.with_tax_calculator(tax_calculator);
+        self
+    }
+
+    /// translations of challenge descriptions and denial messages, chosen
+    /// by the locale of the request
+    pub fn with_message_catalog(mut self, messages: MessageCatalog) -> Self {
+        self.challenges = self.challenges.with_message_catalog(messages.clone());
+        self.messages = messages;
+        self
+    }
+
+    /// limits of payloads presented by clients, e.g. receipt tokens, only
+    /// their size is capped by default
+    pub fn with_payload_limits(mut self, payload_limits: PayloadLimits) -> Self {
+        self.payload_limits = payload_limits;
+        self
+    }
+
+    /// audit log of operator actions, targets are redacted as configured in
+    /// `redaction.nonce`
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = Self::redacting(&self.config_manager, audit_log);
+        self.entitlements = self.entitlements.with_audit_log(self.audit_log.clone());
+        self
+    }
+
+    fn redacting(
+        config_manager: &ConfigManager,
+        audit_log: Arc<dyn AuditLog>,
+    ) -> Arc<dyn AuditLog> {
+        let redactor = Redactor::new(config_manager.get_config().redaction.clone());
+        if !redactor.is_enabled() {
+            return audit_log;
+        }
+        Arc::new(RedactingAuditLog::new(audit_log, redactor))
+    }
+
+    /// redactor of the configured policies, for logs of the integration,
+    /// events are already redacted when emitted
+    pub fn redactor(&self) -> Redactor {
+        Redactor::new(self.config_manager.get_config().redaction.clone())
+    }
+
+    /// use a shared revocation store so revocations reach every replica
+    pub fn with_revocation_store(mut self, revocation_store: Arc<dyn RevocationStore>) -> Self {
+        self.entitlements = self.entitlements.with_revocation_store(revocation_store);
+        self
+    }
+
+    fn single_key_ring(signer: impl CryptoSuite + 'static) -> Arc<KeyRing> {
+        let key_ring = KeyRing::new(0);
+        key_ring.add_key("default", signer, 0, None);
+        Arc::new(key_ring)
+    }
+
+    /// published JWKS document, served by integrations at
+    /// [`JWKS_PATH`](crate::keys::JWKS_PATH)
+    pub fn jwks(&self) -> Option<JwkSet> {
+        self.entitlements.jwks()
+    }
+
+    /// replace the clock used for timestamps and expiry, verifiers registered
+    /// afterwards through `register_chain_verifier` share the same clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.challenges = self.challenges.with_clock(clock.clone());
+        self.verifications = self.verifications.with_clock(clock.clone());
+        self.entitlements = self.entitlements.with_clock(clock.clone());
+        let events = self.events.clone().with_clock(clock.clone());
+        self.clock = clock;
+        self.with_events(events)
+    }
+
+    /// split the session cache over the given number of locks, sessions
+    /// issued before the call are dropped
+    pub fn with_session_shards(self, shards: usize) -> Self {
+        self.with_sessions(Arc::new(ShardedMap::new(shards)))
+    }
+
+    /// Shares a session table with other engines or services, e.g. a
+    /// [`Verifications`] worker verifying the sessions this engine issues.
+    /// Sessions issued before the call are dropped.
+    pub fn with_sessions(mut self, sessions: Arc<ShardedMap<PaymentSession>>) -> Self {
+        self.challenges = self.challenges.with_sessions(sessions.clone());
+        self.verifications = self.verifications.with_sessions(sessions.clone());
+        self.entitlements = self.entitlements.with_sessions(sessions.clone());
+        self.payment_sessions_cache = sessions;
+        self
+    }
+
+    /// session table of the engine, see [`with_sessions`](Self::with_sessions)
+    pub fn sessions(&self) -> &Arc<ShardedMap<PaymentSession>> {
+        &self.payment_sessions_cache
+    }
+
+    /// set the provider deciding the amount charged per request
+    pub fn with_pricing_provider(mut self, pricing_provider: Arc<dyn PricingProvider>) -> Self {
+        self.challenges = self.challenges.with_pricing_provider(pricing_provider);
+        self
+    }
+
+    /// append an access policy, policies are evaluated in registration order
+    pub fn with_access_policy(mut self, policy: Arc<dyn AccessPolicy>) -> Self {
+        self.access_policies.push(policy);
+        self
+    }
+
+    /// append hooks around challenges and verifications, hooks run in
+    /// registration order
+    pub fn with_hooks(mut self, hooks: Arc<dyn EngineHooks>) -> Self {
+        self.challenges = self.challenges.with_hooks(hooks.clone());
+        self.hooks.push(hooks);
+        self
+    }
+
+    /// persist every event to the outbox before the listeners are called,
+    /// see [`OutboxDispatcher`](crate::outbox::OutboxDispatcher) for its
+    /// delivery. A request whose event the outbox fails to take fails with
+    /// the store error instead of going on without it.
+    pub fn with_outbox_store(self, outbox_store: Arc<dyn OutboxStore>) -> Self {
+        let events = self.events.clone().with_outbox_store(outbox_store);
+        self.with_events(events)
+    }
+
+    /// listeners get events redacted as configured in `redaction`
+    pub fn with_event_listener(self, listener: Arc<dyn EventListener>) -> Self {
+        let events = self.events.clone().with_listener(listener);
+        self.with_events(events)
+    }
+
+    /// emit through the same sinks from the engine and every service
+    fn with_events(mut self, events: EventEmitter) -> Self {
+        self.challenges = self.challenges.with_events(events.clone());
+        self.verifications = self.verifications.with_events(events.clone());
+        self.entitlements = self.entitlements.with_events(events.clone());
+        self.events = events;
+        self
+    }
+
+    /// the service issuing challenges
+    pub fn challenges(&self) -> &Challenges {
+        &self.challenges
+    }
+
+    /// the service verifying payments
+    pub fn verifications(&self) -> &Verifications {
+        &self.verifications
+    }
+
+    /// the service issuing and checking receipts
+    pub fn entitlements(&self) -> &Entitlements {
+        &self.entitlements
+    }
+
     pub fn from_config_file(path: &str) -> Result<Self, EngineError> {
         let config_manager = ConfigManager::from_file(path)?;
         Self::new(config_manager)
@@ -70,129 +438,613 @@ impl X402 {
         chain_type: ChainType,
         rpc_url: String,
     ) -> Result<(), EngineError> {
-        let chain_config = self
-            .config_manager
-            .get_chain_config(&chain_type)
-            .ok_or_else(|| EngineError::ChainNotSupported(chain_type.clone()))?;
-        let verifier: Box<dyn PaymentVerifier> = match &chain_type {
-            ChainType::Evm(_) => {
-                use crate::verifier::evm::EvmVerifier;
-                let evm_verifier = EvmVerifier::new(rpc_url, chain_type.clone())
-                    .await
-                    .map_err(EngineError::VerificationError)?;
-                Box::new(evm_verifier)
-            }
-            ChainType::Solana(_) => {
-                use crate::verifier::solana::SolanaVerifier;
-                let solana_verifier = SolanaVerifier::new();
-                Box::new(solana_verifier)
-            }
-            _ => {
-                return Err(EngineError::ChainNotSupported(chain_type));
-            }
-        };
-        self.verifier_registry
-            .register_verifier(chain_type, verifier);
-        Ok(())
+        self.verifications
+            .register_chain_verifier(chain_type, rpc_url)
+            .await
+    }
+
+    /// register a verifier built outside the engine, e.g. for Sui or Aptos,
+    /// once the RPC it reads is checked to serve the network of the chain
+    pub async fn register_custom_verifier(
+        &mut self,
+        chain_type: ChainType,
+        rpc_url: &str,
+        verifier: Box<dyn PaymentVerifier>,
+    ) -> Result<(), EngineError> {
+        self.verifications
+            .register_custom_verifier(chain_type, rpc_url, verifier)
+            .await
+    }
+
+    /// Registers a verifier for every configured chain with an RPC URL,
+    /// constructing them concurrently. Chains that fail are reported and
+    /// left unregistered, the others are usable. In simulation mode every
+    /// chain is registered, an RPC URL is not needed.
+    pub async fn register_all_configured(&mut self) -> RegistrationReport {
+        self.verifications.register_all_configured().await
     }
 
+    /// circuit breaker status of every registered verifier that has one
+    pub fn chain_health(&self) -> HashMap<ChainType, CircuitStatus> {
+        self.verifications.chain_health()
+    }
+
+    /// Verifies the payment of a session for the requested resource, the
+    /// resource has to be permitted by the configured
+    /// [`SessionBinding`](crate::config::SessionBinding).
     pub async fn verify_payment(
         &self,
         user_address: &str,
         payment_nonce: &str,
+        resource: &Resource,
     ) -> Result<PaymentVerification, EngineError> {
-        let (chain_type, payment_request) = {
-            let sessions = self.payment_sessions_cache.read().unwrap();
-            let session = sessions
-                .get(payment_nonce)
-                .ok_or(EngineError::InvalidSession)?;
-
-            if session.user_address != user_address {
-                return Err(EngineError::AddressMismatch);
+        self.verifications
+            .verify_payment(user_address, payment_nonce, resource)
+            .await
+    }
+
+    /// Flags a session as disputed, recorded in the audit log.
+    pub async fn open_dispute(
+        &self,
+        payment_nonce: &str,
+        operator: &str,
+        note: &str,
+    ) -> Result<(), EngineError> {
+        let now = self.clock.now();
+        self.update_session(payment_nonce, |session| {
+            if session
+                .dispute
+                .as_ref()
+                .is_some_and(|dispute| dispute.status == DisputeStatus::Open)
+            {
+                return Err(EngineError::InvalidDispute);
             }
+            session.dispute = Some(Dispute {
+                status: DisputeStatus::Open,
+                opened_by: operator.to_string(),
+                opened_at: now,
+                resolved_at: None,
+                notes: vec![DisputeNote {
+                    author: operator.to_string(),
+                    created_at: now,
+                    text: note.to_string(),
+                    evidence: Vec::new(),
+                }],
+            });
+            Ok(())
+        })?;
+        self.audit(operator, AuditAction::DisputeOpened, payment_nonce, note)
+            .await
+    }
 
-            (
-                session.payment_request.chain.chain_type.clone(),
-                session.payment_request.clone(),
-            )
-        };
-        let verifier = self
-            .verifier_registry
-            .get_verifier(&chain_type)
-            .ok_or(EngineError::ChainNotSupported(chain_type))?;
-        let verification = verifier
-            .verify_payment(&payment_request, user_address)
+    /// Attaches a note and evidence references to an open dispute.
+    pub async fn annotate_dispute(
+        &self,
+        payment_nonce: &str,
+        operator: &str,
+        note: &str,
+        evidence: Vec<String>,
+    ) -> Result<(), EngineError> {
+        let now = self.clock.now();
+        self.update_session(payment_nonce, |session| {
+            let dispute = session
+                .dispute
+                .as_mut()
+                .filter(|dispute| dispute.status == DisputeStatus::Open)
+                .ok_or(EngineError::InvalidDispute)?;
+            dispute.notes.push(DisputeNote {
+                author: operator.to_string(),
+                created_at: now,
+                text: note.to_string(),
+                evidence,
+            });
+            Ok(())
+        })?;
+        self.audit(operator, AuditAction::DisputeAnnotated, payment_nonce, note)
             .await
-            .map_err(EngineError::VerificationFailed)?;
-        if verification.is_paid {
-            let mut sessions = self.payment_sessions_cache.write().unwrap();
-            if let Some(session) = sessions.get_mut(payment_nonce) {
-                session.verified = true;
+    }
+
+    pub async fn resolve_dispute(
+        &self,
+        payment_nonce: &str,
+        operator: &str,
+        note: &str,
+    ) -> Result<(), EngineError> {
+        let now = self.clock.now();
+        self.update_session(payment_nonce, |session| {
+            let dispute = session
+                .dispute
+                .as_mut()
+                .filter(|dispute| dispute.status == DisputeStatus::Open)
+                .ok_or(EngineError::InvalidDispute)?;
+            dispute.status = DisputeStatus::Resolved;
+            dispute.resolved_at = Some(now);
+            dispute.notes.push(DisputeNote {
+                author: operator.to_string(),
+                created_at: now,
+                text: note.to_string(),
+                evidence: Vec::new(),
+            });
+            Ok(())
+        })?;
+        self.audit(operator, AuditAction::DisputeResolved, payment_nonce, note)
+            .await
+    }
+
+    pub fn dispute(&self, payment_nonce: &str) -> Option<Dispute> {
+        self.payment_sessions_cache
+            .read(payment_nonce)
+            .get(payment_nonce)
+            .and_then(|session| session.dispute.clone())
+    }
+
+    /// Manually grants or revokes a session by operator decision. A grant
+    /// lets the next request with the nonce through without a chain lookup,
+    /// a revoke refuses it and revokes receipts issued for the payment.
+    /// Sessions already paid on chain cannot be granted.
+    pub async fn override_verification(
+        &self,
+        payment_nonce: &str,
+        operator: &str,
+        decision: OverrideDecision,
+        reason: &str,
+    ) -> Result<(), EngineError> {
+        let now = self.clock.now();
+        self.update_session(payment_nonce, |session| {
+            if decision == OverrideDecision::Grant && session.verified {
+                return Err(EngineError::SessionSettled);
             }
+            session.verified = decision == OverrideDecision::Grant;
+            session.manual_override = Some(ManualOverride {
+                decision,
+                operator: operator.to_string(),
+                reason: reason.to_string(),
+                decided_at: now,
+            });
+            Ok(())
+        })?;
+        if decision == OverrideDecision::Revoke {
+            self.entitlements
+                .revoke_payment(
+                    payment_nonce,
+                    RevocationReason::Other(format!("revoked by {}: {}", operator, reason)),
+                )
+                .await?;
         }
-        Ok(verification)
+        self.audit(
+            operator,
+            AuditAction::VerificationOverridden {
+                decision: decision.to_string(),
+            },
+            payment_nonce,
+            reason,
+        )
+        .await
     }
 
-    fn create_payment_request(
+    pub fn manual_override(&self, payment_nonce: &str) -> Option<ManualOverride> {
+        self.payment_sessions_cache
+            .read(payment_nonce)
+            .get(payment_nonce)
+            .and_then(|session| session.manual_override.clone())
+    }
+
+    pub fn audit_log(&self) -> &Arc<dyn AuditLog> {
+        &self.audit_log
+    }
+
+    fn update_session<R>(
         &self,
-        user_address: &str,
-        resource_path: &str,
-        custom_amount: Option<&str>,
-    ) -> Result<PaymentRequest, EngineError> {
-        let config = self.config_manager.get_config();
-        let default_chain = self.config_manager.get_default_chain_config()?;
-
-        let amount = custom_amount
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| config.payments.default_amount.clone());
-        let currency = match &config.service.default_currency {
-            crate::config::CurrencyConfig {
-                currency_type,
-                address,
-                decimals,
-            } => match currency_type {
-                crate::config::CurrencyType::Native => Currency::Native,
-                crate::config::CurrencyType::Erc20 => {
-                    let token_address =
-                        address.clone().ok_or(EngineError::InvalidCurrencyConfig)?;
-                    Currency::Token {
-                        address: token_address,
-                        decimals: *decimals,
+        payment_nonce: &str,
+        update: impl FnOnce(&mut PaymentSession) -> Result<R, EngineError>,
+    ) -> Result<R, EngineError> {
+        let mut sessions = self.payment_sessions_cache.write(payment_nonce);
+        let session = sessions
+            .get_mut(payment_nonce)
+            .ok_or(EngineError::InvalidSession)?;
+        update(session)
+    }
+
+    async fn audit(
+        &self,
+        operator: &str,
+        action: AuditAction,
+        target: &str,
+        note: &str,
+    ) -> Result<(), EngineError> {
+        self.audit_log
+            .append(AuditEntry {
+                timestamp: self.clock.now(),
+                operator: operator.to_string(),
+                action,
+                target: target.to_string(),
+                note: Some(note.to_string()).filter(|note| !note.is_empty()),
+            })
+            .await
+            .map_err(EngineError::AuditError)
+    }
+
+    /// verification attempt counters of a session
+    pub fn session_attempts(&self, payment_nonce: &str) -> Option<SessionAttempts> {
+        self.payment_sessions_cache
+            .read(payment_nonce)
+            .get(payment_nonce)
+            .map(|session| session.attempts.clone())
+    }
+
+    /// copies of every payment session, pending or verified
+    pub fn export_sessions(&self) -> Vec<PaymentSession> {
+        self.payment_sessions_cache
+            .snapshots()
+            .flatten()
+            .map(|(_, session)| session)
+            .collect()
+    }
+
+    /// write every payment session as a JSON line, one shard at a time so
+    /// large caches are never copied as a whole. Returns the number of
+    /// sessions written.
+    pub fn export_sessions_to(
+        &self,
+        mut writer: impl std::io::Write,
+    ) -> Result<usize, EngineError> {
+        let mut exported = 0;
+        for shard in self.payment_sessions_cache.snapshots() {
+            for (_, session) in shard {
+                serde_json::to_writer(&mut writer, &session).map_err(session_io_error)?;
+                writer.write_all(b"\n").map_err(session_io_error)?;
+                exported += 1;
+            }
+        }
+        writer.flush().map_err(session_io_error)?;
+        Ok(exported)
+    }
+
+    /// add sessions exported by another engine, sessions already known
+    /// here are kept as they are
+    pub fn import_sessions(
+        &self,
+        sessions: impl IntoIterator<Item = PaymentSession>,
+    ) -> SessionImportReport {
+        let mut report = SessionImportReport::default();
+        for session in sessions {
+            let nonce = session.payment_request.nonce.clone();
+            let mut shard = self.payment_sessions_cache.write(&nonce);
+            match shard.entry(nonce) {
+                Entry::Vacant(entry) => {
+                    entry.insert(session);
+                    report.imported += 1;
+                }
+                Entry::Occupied(_) => report.skipped += 1,
+            }
+        }
+        report
+    }
+
+    /// read sessions written by [`X402::export_sessions_to`] line by line,
+    /// see [`X402::import_sessions`]
+    pub fn import_sessions_from(
+        &self,
+        reader: impl std::io::BufRead,
+    ) -> Result<SessionImportReport, EngineError> {
+        let mut report = SessionImportReport::default();
+        for line in reader.lines() {
+            let line = line.map_err(session_io_error)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let session: PaymentSession = serde_json::from_str(&line).map_err(session_io_error)?;
+            let imported = self.import_sessions([session]);
+            report.imported += imported.imported;
+            report.skipped += imported.skipped;
+        }
+        Ok(report)
+    }
+
+    /// copy every payment session into `target`, e.g. an engine built on a
+    /// new set of stores, so pending payments can still be verified there
+    pub fn migrate_sessions_to(&self, target: &X402) -> SessionImportReport {
+        let mut report = SessionImportReport::default();
+        for shard in self.payment_sessions_cache.snapshots() {
+            let imported = target.import_sessions(shard.into_iter().map(|(_, session)| session));
+            report.imported += imported.imported;
+            report.skipped += imported.skipped;
+        }
+        report
+    }
+
+    /// Relays a payer signed token authorization for a session, the merchant
+    /// pays the gas. Returns the transaction hash, the session is then
+    /// verified as usual with its nonce.
+    pub async fn relay_payment(
+        &self,
+        payment_nonce: &str,
+        authorization: &TransferAuthorization,
+    ) -> Result<String, EngineError> {
+        let relayer = self.relayer()?;
+        let (_, _, chain) = self.check_authorization(payment_nonce, authorization)?;
+        Ok(relayer.submit(&chain, authorization).await?)
+    }
+
+    /// Queues a payer signed token authorization for settlement by
+    /// [`X402::process_settlements`], a session is only ever queued once.
+    pub async fn queue_settlement(
+        &self,
+        payment_nonce: &str,
+        authorization: &TransferAuthorization,
+    ) -> Result<SettlementJob, EngineError> {
+        self.relayer()?;
+        let settlement_store = self.settlement_store()?;
+        if let Some(job) = settlement_store
+            .job(payment_nonce)
+            .await
+            .map_err(EngineError::StoreError)?
+        {
+            return Ok(job);
+        }
+        let (payer, resource, chain) = self.check_authorization(payment_nonce, authorization)?;
+        let now = self.clock.now();
+        settlement_store
+            .enqueue(SettlementJob {
+                key: payment_nonce.to_string(),
+                payment_nonce: payment_nonce.to_string(),
+                payer,
+                resource,
+                chain,
+                authorization: authorization.clone(),
+                status: SettlementStatus::Pending,
+                attempts: 0,
+                transaction_hashes: Vec::new(),
+                submitted_at: None,
+                next_attempt_at: now,
+                last_error: None,
+                created_at: now,
+            })
+            .await
+            .map_err(EngineError::StoreError)
+    }
+
+    /// Advances every due settlement: submits pending ones, confirms mined
+    /// ones, replaces stuck ones with a higher fee and retries failures with
+    /// exponential backoff until `settlement.max_attempts`. Returns the jobs
+    /// processed, in their new state.
+    pub async fn process_settlements(&self) -> Result<Vec<SettlementJob>, EngineError> {
+        let relayer = self.relayer()?;
+        let settlement_store = self.settlement_store()?;
+        let due = settlement_store
+            .due(self.clock.now())
+            .await
+            .map_err(EngineError::StoreError)?;
+        let mut processed = Vec::with_capacity(due.len());
+        for mut job in due {
+            // another replica is advancing the job
+            let _lock = match self.lock(&format!("settlement:{}", job.key)).await {
+                Err(EngineError::Locked { .. }) => continue,
+                lock => lock?,
+            };
+            // the new state is stored even when its event was not, the
+            // transaction is on chain already
+            let advanced = self.advance_settlement(relayer.as_ref(), &mut job).await;
+            settlement_store
+                .update(&job)
+                .await
+                .map_err(EngineError::StoreError)?;
+            advanced?;
+            processed.push(job);
+        }
+        Ok(processed)
+    }
+
+    async fn advance_settlement(
+        &self,
+        relayer: &dyn Relayer,
+        job: &mut SettlementJob,
+    ) -> Result<(), EngineError> {
+        let settlement = &self.config_manager.get_config().settlement;
+        let now = self.clock.now();
+        let latest = job.transaction_hashes.last().cloned();
+        let submission = match (job.status, latest) {
+            (SettlementStatus::Submitted, Some(latest)) => {
+                match relayer.transaction_status(&job.chain, &latest).await {
+                    Ok(TransactionStatus::Confirmed) => {
+                        job.status = SettlementStatus::Confirmed;
+                        job.last_error = None;
+                        return Ok(());
+                    }
+                    Ok(TransactionStatus::Reverted) => {
+                        // a replacement reverts once an earlier submission is mined
+                        for earlier in job.transaction_hashes.iter().rev().skip(1) {
+                            if let Ok(TransactionStatus::Confirmed) =
+                                relayer.transaction_status(&job.chain, earlier).await
+                            {
+                                job.status = SettlementStatus::Confirmed;
+                                job.last_error = None;
+                                return Ok(());
+                            }
+                        }
+                        return self
+                            .fail_settlement(job, "transaction reverted".to_string())
+                            .await;
+                    }
+                    Ok(TransactionStatus::Pending)
+                        if now < job.submitted_at.unwrap_or(0) + settlement.replace_after_secs =>
+                    {
+                        job.next_attempt_at =
+                            job.submitted_at.unwrap_or(now) + settlement.replace_after_secs;
+                        return Ok(());
+                    }
+                    Ok(TransactionStatus::Pending) => {
+                        if job.attempts >= settlement.max_attempts {
+                            return self
+                                .fail_settlement(
+                                    job,
+                                    format!("not mined after {} attempts", job.attempts),
+                                )
+                                .await;
+                        }
+                        relayer
+                            .replace(
+                                &job.chain,
+                                &job.authorization,
+                                &latest,
+                                settlement.gas_bump_percent,
+                            )
+                            .await
                     }
+                    Err(e) => Err(e),
                 }
-                _ => Currency::Native,
-            },
+            }
+            _ => relayer.submit(&job.chain, &job.authorization).await,
         };
-        Ok(PaymentRequest {
-            amount,
-            currency,
-            recipient: self.config_manager.get_service_address(),
-            chain: default_chain.clone(),
-            description: Some(format!("Access to: {}", resource_path)),
-            expires_at: Some(
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs()
-                    + config.payments.expiration_time_secs,
-            ),
-            nonce: Uuid::new_v4().to_string(),
+        job.attempts += 1;
+        match submission {
+            Ok(transaction_hash) => {
+                job.status = SettlementStatus::Submitted;
+                job.submitted_at = Some(now);
+                job.next_attempt_at = now + settlement.replace_after_secs;
+                job.last_error = None;
+                job.transaction_hashes.push(transaction_hash.clone());
+                self.emit(
+                    &job.payer,
+                    &job.resource,
+                    &RequestContext::default(),
+                    PaymentEventKind::SettlementSubmitted {
+                        nonce: job.payment_nonce.clone(),
+                        transaction_hash,
+                    },
+                )
+                .await
+            }
+            Err(e) if job.attempts >= settlement.max_attempts => {
+                self.fail_settlement(job, e.to_string()).await
+            }
+            Err(e) => {
+                let backoff = settlement
+                    .retry_base_secs
+                    .saturating_mul(1u64 << (job.attempts - 1).min(32))
+                    .min(settlement.retry_max_secs);
+                job.next_attempt_at = now + backoff;
+                job.last_error = Some(e.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    async fn fail_settlement(
+        &self,
+        job: &mut SettlementJob,
+        reason: String,
+    ) -> Result<(), EngineError> {
+        job.status = SettlementStatus::Failed;
+        job.last_error = Some(reason.clone());
+        self.emit(
+            &job.payer,
+            &job.resource,
+            &RequestContext::default(),
+            PaymentEventKind::SettlementFailed {
+                nonce: job.payment_nonce.clone(),
+                reason,
+            },
+        )
+        .await
+    }
+
+    fn relayer(&self) -> Result<&Arc<dyn Relayer>, EngineError> {
+        Ok(self
+            .relayer
+            .as_ref()
+            .ok_or_else(|| RelayError::Rejected("no relayer configured".to_string()))?)
+    }
+
+    fn settlement_store(&self) -> Result<&Arc<dyn SettlementStore>, EngineError> {
+        self.settlement_store.as_ref().ok_or_else(|| {
+            EngineError::StoreError(StoreError::Backend(
+                "no settlement store configured".to_string(),
+            ))
         })
     }
 
-    fn store_payment_session(&self, user_address: &str, payment_request: PaymentRequest) {
-        let session = PaymentSession {
-            user_address: user_address.to_string(),
-            payment_request,
-            created_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-            verified: false,
+    /// check an authorization pays the session in full, returns the payer,
+    /// resource and chain of the session
+    fn check_authorization(
+        &self,
+        payment_nonce: &str,
+        authorization: &TransferAuthorization,
+    ) -> Result<(String, Resource, ChainConfig), EngineError> {
+        let (user_address, resource, payment_request) = self
+            .payment_sessions_cache
+            .read(payment_nonce)
+            .get(payment_nonce)
+            .map(|session| {
+                (
+                    session.user_address.clone(),
+                    session.resource.clone(),
+                    session.payment_request.clone(),
+                )
+            })
+            .ok_or(EngineError::InvalidSession)?;
+        let Currency::Token { address, decimals } = &payment_request.currency else {
+            return Err(RelayError::InvalidAuthorization(
+                "session is not payable with a token authorization".to_string(),
+            )
+            .into());
         };
+        let reject =
+            |msg: &str| EngineError::from(RelayError::InvalidAuthorization(msg.to_string()));
+        if !authorization.token.eq_ignore_ascii_case(address) {
+            return Err(reject("token does not match the session currency"));
+        }
+        if !authorization.from.eq_ignore_ascii_case(&user_address) {
+            return Err(EngineError::AddressMismatch);
+        }
+        if !authorization
+            .to
+            .eq_ignore_ascii_case(&payment_request.recipient)
+        {
+            return Err(reject("recipient does not match the session"));
+        }
+        let value =
+            U256::from_dec_str(&authorization.value).map_err(|_| reject("invalid value"))?;
+        let required = U256::from_dec_str(&payment_request.amount)
+            .map_err(|_| reject("invalid amount"))?
+            .saturating_mul(U256::exp10(usize::from(*decimals)));
+        if value < required {
+            return Err(reject("value is below the session amount"));
+        }
+        if authorization.valid_before <= self.clock.now() {
+            return Err(reject("authorization has expired"));
+        }
+        Ok((user_address, resource, payment_request.chain))
+    }
 
-        let mut sessions = self.payment_sessions_cache.write().unwrap();
-        sessions.insert(session.payment_request.nonce.clone(), session);
+    /// count the redemption of the coupon a paid session was issued with
+    async fn redeem_session_coupon(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        payment_nonce: &str,
+        context: &RequestContext,
+    ) -> Result<(), EngineError> {
+        let (Some(coupons), Some(code)) =
+            (&self.coupons, self.challenges.session_coupon(payment_nonce))
+        else {
+            return Ok(());
+        };
+        let kind = match coupons.redeem_reserved(&code, payment_nonce, self.clock.now()) {
+            Ok(_) => PaymentEventKind::CouponRedeemed {
+                nonce: payment_nonce.to_string(),
+                code,
+            },
+            // the session lost its reservation, e.g. it was imported, the
+            // payment matched the discounted challenge so it is still served
+            Err(CouponError::Exhausted) => PaymentEventKind::CouponExhausted {
+                nonce: payment_nonce.to_string(),
+                code,
+            },
+            // the coupon was removed from the book since
+            Err(_) => return Ok(()),
+        };
+        self.emit(user_address, resource, context, kind).await
     }
 
     /// Handles an access request and returns appropriate payment verification result.
@@ -206,13 +1058,17 @@ impl X402 {
     /// # Params
     ///
     /// user_address - Blockchain address of the user requesting access
-    /// resource_path - Path identifier for the requested resource (used in payment description)
+    /// resource_path - Request target of the resource, including the query string, normalized into a [`Resource`]
     /// payment_nonce - Optional payment session identifier from previous 402 response
-    /// custom_amount - Optional custom payment amount overriding default configuration
+    /// custom_amount - Optional custom payment amount overriding default configuration, only
+    /// applied when a new session is issued, verification and retries of an existing session
+    /// always use the amount stored with that session
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// # use x402_sdk::core::X402;
+    /// # async fn example(engine: X402) -> Result<(), Box<dyn std::error::Error>> {
     /// // First request - returns 402 with payment details
     /// let result = engine.handle_access_request(
     ///     "0x1234...",
@@ -235,6 +1091,8 @@ impl X402 {
     ///     // Return 402 response with payment details
     ///     let payment_response = result.x402_response.unwrap();
     /// }
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn handle_access_request(
         &self,
@@ -243,48 +1101,849 @@ impl X402 {
         payment_nonce: Option<&str>,
         custom_amount: Option<&str>,
     ) -> Result<VerificationResult, EngineError> {
+        self.handle_access_request_with_context(
+            user_address,
+            resource_path,
+            payment_nonce,
+            custom_amount,
+            &RequestContext::default(),
+        )
+        .await
+    }
+
+    /// Same as [`handle_access_request_with_coupon`](Self::handle_access_request_with_coupon)
+    /// for an owned request, which is also what the engine serves as a tower
+    /// service.
+    pub async fn handle_request(
+        &self,
+        request: &AccessRequest,
+    ) -> Result<VerificationResult, EngineError> {
+        self.handle_access_request_with_coupon(
+            &request.user_address,
+            &request.resource_path,
+            request.payment_nonce.as_deref(),
+            request.custom_amount.as_deref(),
+            request.coupon_code.as_deref(),
+            &request.context,
+        )
+        .await
+    }
+
+    /// Same as [`handle_access_request`](Self::handle_access_request) with the
+    /// request metadata made available to access policies, the pricing
+    /// provider and event listeners.
+    pub async fn handle_access_request_with_context(
+        &self,
+        user_address: &str,
+        resource_path: &str,
+        payment_nonce: Option<&str>,
+        custom_amount: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<VerificationResult, EngineError> {
+        self.handle_access_request_with_coupon(
+            user_address,
+            resource_path,
+            payment_nonce,
+            custom_amount,
+            None,
+            context,
+        )
+        .await
+    }
+
+    /// Same as [`handle_access_request_with_context`](Self::handle_access_request_with_context)
+    /// with a coupon from the [`CouponBook`] discounting a newly issued
+    /// session. The coupon is redeemed once the session is paid, a full
+    /// discount grants access directly.
+    pub async fn handle_access_request_with_coupon(
+        &self,
+        user_address: &str,
+        resource_path: &str,
+        payment_nonce: Option<&str>,
+        custom_amount: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<VerificationResult, EngineError> {
+        telemetry::in_request_trace(
+            context,
+            self.access_request(
+                user_address,
+                resource_path,
+                payment_nonce,
+                custom_amount,
+                coupon_code,
+                context,
+            ),
+        )
+        .await
+    }
+
+    async fn access_request(
+        &self,
+        user_address: &str,
+        resource_path: &str,
+        payment_nonce: Option<&str>,
+        custom_amount: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<VerificationResult, EngineError> {
+        let resource = Resource::new(&context.method, resource_path)?;
+        for policy in &self.access_policies {
+            match policy.evaluate(user_address, &resource, context).await {
+                AccessDecision::RequirePayment => continue,
+                AccessDecision::Allow => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::AccessGranted,
+                    )
+                    .await?;
+                    return Ok(VerificationResult {
+                        should_serve_content: true,
+                        http_status: 200,
+                        x402_response: None,
+                        verification: None,
+                        receipt: None,
+                        retry_after: None,
+                    });
+                }
+                AccessDecision::Deny(reason) => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::AccessDenied { reason },
+                    )
+                    .await?;
+                    return Ok(VerificationResult {
+                        should_serve_content: false,
+                        http_status: 403,
+                        x402_response: None,
+                        verification: None,
+                        receipt: None,
+                        retry_after: None,
+                    });
+                }
+            }
+        }
+        if let Some(token) = context.header(RECEIPT_HEADER)
+            && let Ok(receipt) = Receipt::from_token_with(token, &self.payload_limits)
+            && receipt.payer == user_address
+            && let Ok(granted) = Resource::from_canonical(&receipt.resource)
+            && self
+                .config_manager
+                .get_config()
+                .sessions
+                .binding
+                .permits(&granted, &resource)
+            && self.validate_receipt(&receipt).await.is_ok()
+        {
+            let config = self.config_manager.get_config();
+            let chain_type = config
+                .chains
+                .values()
+                .find(|chain| chain.chain_id == receipt.chain_id)
+                .map_or(&config.default_chain, |chain| &chain.chain_type);
+            if let Some(denied) = self
+                .screen_payer(user_address, &resource, chain_type, context)
+                .await?
+            {
+                return Ok(denied);
+            }
+            self.emit(
+                user_address,
+                &resource,
+                context,
+                PaymentEventKind::ContentServed {
+                    nonce: receipt.nonce.clone(),
+                },
+            )
+            .await?;
+            return Ok(VerificationResult {
+                should_serve_content: true,
+                http_status: 200,
+                x402_response: None,
+                verification: None,
+                receipt: Some(receipt),
+                retry_after: None,
+            });
+        }
+        // an unpaid verification rides along with the new challenge so the
+        // client can tell why the payment was refused
+        let mut refused = None;
+        let mut requote = None;
         if let Some(nonce) = payment_nonce {
-            if let Ok(verification) = self.verify_payment(user_address, nonce).await {
-                if verification.is_paid {
+            for hooks in &self.hooks {
+                if let HookDecision::Veto(reason) = hooks
+                    .before_verify(user_address, &resource, context, nonce)
+                    .await
+                {
+                    return self
+                        .challenges
+                        .veto(user_address, &resource, context, reason)
+                        .await;
+                }
+            }
+            // another replica is verifying the session, the client retries
+            let _lock = match self.lock(&format!("session:{}", nonce)).await {
+                Err(EngineError::Locked { retry_after }) => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::VerificationThrottled {
+                            nonce: nonce.to_string(),
+                            retry_after,
+                        },
+                    )
+                    .await?;
+                    return Ok(VerificationResult {
+                        should_serve_content: false,
+                        http_status: 202,
+                        x402_response: None,
+                        verification: None,
+                        receipt: None,
+                        retry_after: Some(retry_after),
+                    });
+                }
+                lock => lock?,
+            };
+            self.challenges
+                .recover_derived_session(
+                    user_address,
+                    &resource,
+                    nonce,
+                    custom_amount,
+                    coupon_code,
+                    context,
+                )
+                .await?;
+            match self.verify_payment(user_address, nonce, &resource).await {
+                Ok(verification) if verification.is_paid => {
+                    for hooks in &self.hooks {
+                        if let HookDecision::Veto(reason) = hooks
+                            .after_verify(user_address, &resource, context, &verification)
+                            .await
+                        {
+                            return self
+                                .deny_payment(user_address, &resource, nonce, context, reason)
+                                .await;
+                        }
+                    }
+                    self.record_payment(user_address, &resource, nonce, &verification)
+                        .await?;
+                    if let Some(reason) = self
+                        .screening_denial(user_address, &verification.chain.chain_type)
+                        .await
+                    {
+                        return self
+                            .deny_payment(user_address, &resource, nonce, context, reason)
+                            .await;
+                    }
+                    // only once nothing can refuse the payment any more
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::PaymentVerified {
+                            nonce: nonce.to_string(),
+                            verification: verification.clone(),
+                        },
+                    )
+                    .await?;
+                    self.redeem_session_coupon(user_address, &resource, nonce, context)
+                        .await?;
+                    if let Some(usage_tracker) = &self.usage_tracker {
+                        usage_tracker
+                            .record(user_address)
+                            .await
+                            .map_err(EngineError::StoreError)?;
+                    }
+                    let receipt = self.entitlements.issue_receipt(
+                        user_address,
+                        &resource,
+                        nonce,
+                        &verification,
+                    )?;
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::ContentServed {
+                            nonce: nonce.to_string(),
+                        },
+                    )
+                    .await?;
                     return Ok(VerificationResult {
                         should_serve_content: true,
                         http_status: 200,
                         x402_response: None,
                         verification: Some(verification),
+                        receipt,
+                        retry_after: None,
                     });
                 }
+                Err(EngineError::TooManyAttempts { retry_after }) => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::VerificationThrottled {
+                            nonce: nonce.to_string(),
+                            retry_after,
+                        },
+                    )
+                    .await?;
+                    return Ok(VerificationResult {
+                        should_serve_content: false,
+                        http_status: 429,
+                        x402_response: None,
+                        verification: None,
+                        receipt: None,
+                        retry_after: Some(retry_after),
+                    });
+                }
+                Err(EngineError::VerificationFailed(VerificationError::CircuitOpen {
+                    retry_after,
+                })) => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::VerificationFailed {
+                            nonce: nonce.to_string(),
+                            reason: "chain rpc unavailable".to_string(),
+                        },
+                    )
+                    .await?;
+                    return Ok(VerificationResult {
+                        should_serve_content: false,
+                        http_status: 503,
+                        x402_response: None,
+                        verification: None,
+                        receipt: None,
+                        retry_after: Some(retry_after),
+                    });
+                }
+                // verification is still pending, the client retries the
+                // same nonce once a slot frees up
+                Err(EngineError::VerificationFailed(VerificationError::Saturated {
+                    retry_after,
+                })) => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::VerificationThrottled {
+                            nonce: nonce.to_string(),
+                            retry_after,
+                        },
+                    )
+                    .await?;
+                    return Ok(VerificationResult {
+                        should_serve_content: false,
+                        http_status: 202,
+                        x402_response: None,
+                        verification: None,
+                        receipt: None,
+                        retry_after: Some(retry_after),
+                    });
+                }
+                Ok(verification) => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::VerificationFailed {
+                            nonce: nonce.to_string(),
+                            reason: verification
+                                .failure_reason
+                                .unwrap_or(ErrorReason::NotFound)
+                                .to_string(),
+                        },
+                    )
+                    .await?;
+                    refused = Some(verification);
+                }
+                Err(err @ EngineError::SessionExpired { .. }) => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::VerificationFailed {
+                            nonce: nonce.to_string(),
+                            reason: err.to_string(),
+                        },
+                    )
+                    .await?;
+                    requote = self
+                        .challenges
+                        .session_request(nonce)
+                        .map(|previous| Requote {
+                            replaces: previous.nonce,
+                            reason: "expired".to_string(),
+                            previous_amount: previous.amount,
+                            previous_quote: previous.quote,
+                        });
+                }
+                Err(err) => {
+                    self.emit(
+                        user_address,
+                        &resource,
+                        context,
+                        PaymentEventKind::VerificationFailed {
+                            nonce: nonce.to_string(),
+                            reason: err.to_string(),
+                        },
+                    )
+                    .await?
+                }
+            }
+            let now = self.clock.now();
+            if let Some(locked_until) = self
+                .session_attempts(nonce)
+                .and_then(|attempts| attempts.locked_until)
+                && locked_until > now
+            {
+                self.emit(
+                    user_address,
+                    &resource,
+                    context,
+                    PaymentEventKind::SessionLockedOut {
+                        nonce: nonce.to_string(),
+                        locked_until,
+                    },
+                )
+                .await?;
             }
         }
-        let payment_request =
-            self.create_payment_request(user_address, resource_path, custom_amount)?;
+        let mut result = self
+            .challenges
+            .challenge(
+                user_address,
+                &resource,
+                payment_nonce,
+                custom_amount,
+                coupon_code,
+                context,
+            )
+            .await?;
+        if result.http_status == 402 {
+            result.verification = refused;
+            if let Some(x402_response) = &mut result.x402_response {
+                x402_response.requote = requote;
+            }
+        }
+        Ok(result)
+    }
+
+    async fn record_payment(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        nonce: &str,
+        verification: &PaymentVerification,
+    ) -> Result<(), EngineError> {
+        let Some(payment_store) = &self.payment_store else {
+            return Ok(());
+        };
+        let Some(payment_request) = self
+            .payment_sessions_cache
+            .read(nonce)
+            .get(nonce)
+            .map(|session| session.payment_request.clone())
+        else {
+            return Ok(());
+        };
+        let token = match &payment_request.currency {
+            Currency::Native => payment_request.chain.native_symbol().unwrap_or_default(),
+            Currency::Token { address, .. } => address.clone(),
+            Currency::AnyToken { .. } => verification
+                .conversion
+                .as_ref()
+                .map(|conversion| conversion.token.clone())
+                .unwrap_or_default(),
+        };
+        let record = PaymentRecord {
+            recorded_at: self.clock.now(),
+            nonce: nonce.to_string(),
+            payer: user_address.to_string(),
+            chain: payment_request.chain.chain_type.get_display_name(),
+            chain_id: payment_request.chain.chain_id.clone(),
+            token,
+            gross: verification
+                .conversion
+                .as_ref()
+                .map_or_else(|| payment_request.amount.clone(), |c| c.amount.clone()),
+            fee: "0".to_string(),
+            transaction_hash: verification.transaction_hash.clone(),
+            resource: resource.canonical(),
+            finality: Finality::Provisional,
+            tax: tax_line(&payment_request),
+            invoice: invoice(&payment_request),
+        };
+        payment_store
+            .record_payment(record)
+            .await
+            .map_err(EngineError::StoreError)
+    }
+
+    /// Credits deposits of at least `minimum_deposit` from the owner of a
+    /// budget account to the service address on the default chain, returns
+    /// the amount credited.
+    pub async fn detect_budget_top_ups(
+        &self,
+        account: &str,
+        minimum_deposit: &str,
+    ) -> Result<u128, EngineError> {
+        let ledger = self
+            .ledger
+            .as_ref()
+            .ok_or_else(|| LedgerError::UnknownAccount(account.to_string()))?;
+        let chain = self.config_manager.get_default_chain_config()?;
+        let verifier = self
+            .verifier_registry()
+            .get_verifier(&chain.chain_type)
+            .ok_or_else(|| EngineError::ChainNotSupported(chain.chain_type.clone()))?;
+        let deposit_request = PaymentRequest {
+            amount: minimum_deposit.to_string(),
+            currency: self.challenges.default_currency()?,
+            recipient: self.config_manager.get_service_address(),
+            chain: chain.clone(),
+            description: Some(format!("Budget top-up: {}", account)),
+            expires_at: None,
+            nonce: format!("budget:{}", account),
+            beneficiary: None,
+            reference: None,
+            metadata: PaymentMetadata::default(),
+            quote: None,
+        };
+        Ok(ledger
+            .detect_top_ups(account, verifier, &deposit_request)
+            .await?)
+    }
+
+    /// Exports the payments verified in `[from, to)` for accounting.
+    pub async fn export_payments(
+        &self,
+        from: u64,
+        to: u64,
+        format: ExportFormat,
+    ) -> Result<Vec<u8>, ExportError> {
+        let payment_store = self.payment_store.as_ref().ok_or(ExportError::NoStore)?;
+        let mut records = payment_store.payments_between(from, to).await?;
+        records.retain(|record| record.finality != Finality::Reverted);
+        export::export(&records, format)
+    }
+
+    /// Revenue of the payments recorded in `[from, to)` per chain and
+    /// token, split into provisional and finalized amounts.
+    pub async fn revenue(&self, from: u64, to: u64) -> Result<Vec<RevenueLine>, ExportError> {
+        let payment_store = self.payment_store.as_ref().ok_or(ExportError::NoStore)?;
+        export::revenue(&payment_store.payments_between(from, to).await?)
+    }
+
+    /// Re-checks the provisional payments against their chain, finalizing
+    /// the ones past the chain finality and marking the ones a
+    /// reorganization reverted. Payments on chains without a verifier stay
+    /// provisional.
+    pub async fn update_finality(&self) -> Result<FinalityReport, EngineError> {
+        self.verifications.update_finality().await
+    }
+
+    /// Catalog of the paid resources for [`DISCOVERY_PATH`](crate::discovery::DISCOVERY_PATH),
+    /// listing the prices published by the pricing provider, or the default
+    /// amount for every resource when it publishes none. Free rules, priced
+    /// at zero, are left out.
+    pub fn discovery_document(&self) -> Result<DiscoveryDocument, EngineError> {
+        self.challenges.discovery_document()
+    }
+
+    /// Invoice of a payment session, from the session while it is cached and
+    /// from the payment store once the session is gone.
+    pub async fn invoice(&self, payment_nonce: &str) -> Result<Option<Invoice>, EngineError> {
+        if let Some(session) = self
+            .payment_sessions_cache
+            .read(payment_nonce)
+            .get(payment_nonce)
+        {
+            return Ok(invoice(&session.payment_request));
+        }
+        let Some(payment_store) = &self.payment_store else {
+            return Ok(None);
+        };
+        Ok(payment_store
+            .payment(payment_nonce)
+            .await
+            .map_err(EngineError::StoreError)?
+            .and_then(|record| record.invoice))
+    }
+
+    /// URL of the human-readable page of a receipt, `None` without a
+    /// configured receipt page URL
+    pub fn receipt_url(&self, receipt: &Receipt) -> Result<Option<String>, EngineError> {
+        let Some(page_url) = &self.config_manager.get_config().receipts.page_url else {
+            return Ok(None);
+        };
+        Ok(Some(format!("{}/{}", page_url, receipt.to_token()?)))
+    }
+
+    /// Human-readable receipt for a receipt token, e.g. taken from the
+    /// receipt URL. Only the signature is checked, receipts stay viewable
+    /// after they expire.
+    pub async fn receipt_page(&self, token: &str) -> Result<ReceiptPage, EngineError> {
+        let receipt = Receipt::from_token(token)?;
+        let key_ring = self
+            .entitlements
+            .key_ring()
+            .ok_or(ReceiptError::NoKeyRing)?;
+        let signature = receipt.signature.as_ref().ok_or(ReceiptError::Unsigned)?;
+        key_ring
+            .verify_payload(
+                RECEIPT_DOMAIN,
+                &receipt.unsigned(),
+                signature,
+                self.clock.now(),
+            )
+            .map_err(ReceiptError::from)?;
         let config = self.config_manager.get_config();
-        let x402_response = X402ProtocolResponse {
-            status: 402,
-            payment_required: payment_request.clone(),
-            verification_url: Some(format!(
-                "{}/{}",
-                config.service.base_verification_url, payment_request.nonce
-            )),
+        let chain = config
+            .chains
+            .values()
+            .find(|chain| chain.chain_id == receipt.chain_id);
+        let session_currency = self
+            .payment_sessions_cache
+            .read(&receipt.nonce)
+            .get(&receipt.nonce)
+            .map(|session| session.payment_request.currency.clone());
+        let (currency, decimals) = match (session_currency, chain) {
+            (Some(Currency::Token { address, decimals }), _) => (Some(address), Some(decimals)),
+            (Some(Currency::AnyToken { .. }), _) => (None, None),
+            (_, Some(chain)) => (chain.native_symbol(), chain.native_decimals()),
+            (_, None) => (None, None),
         };
-        self.store_payment_session(user_address, payment_request);
+        Ok(ReceiptPage {
+            receipt_id: receipt.receipt_id.clone(),
+            issued_at: receipt.issued_at,
+            merchant_name: config.service.name.clone(),
+            merchant_description: config.service.description.clone(),
+            merchant_address: self.config_manager.get_service_address(),
+            payer: receipt.payer.clone(),
+            resource: receipt.resource.clone(),
+            chain: chain.map_or_else(
+                || receipt.chain_id.clone(),
+                |chain| chain.chain_type.get_display_name(),
+            ),
+            chain_id: receipt.chain_id.clone(),
+            amount: receipt.amount.clone(),
+            currency,
+            decimals,
+            explorer_url: receipt
+                .transaction_hash
+                .as_deref()
+                .zip(chain)
+                .and_then(|(transaction_hash, chain)| chain.explorer_tx_link(transaction_hash)),
+            transaction_hash: receipt.transaction_hash.clone(),
+            invoice: self.invoice(&receipt.nonce).await?,
+        })
+    }
+
+    /// Validates a receipt presented as access token: signature against the
+    /// published keys, expiry, and revocation of the receipt or its payment.
+    pub async fn validate_receipt(&self, receipt: &Receipt) -> Result<(), EngineError> {
+        self.entitlements.validate_receipt(receipt).await
+    }
+
+    /// revoke a single receipt by id
+    pub async fn revoke_receipt(
+        &self,
+        receipt_id: &str,
+        reason: RevocationReason,
+    ) -> Result<(), EngineError> {
+        self.entitlements.revoke_receipt(receipt_id, reason).await
+    }
+
+    /// revoke every receipt issued for the payment session, e.g. after a
+    /// reorg or refund
+    pub async fn revoke_payment(
+        &self,
+        payment_nonce: &str,
+        reason: RevocationReason,
+    ) -> Result<(), EngineError> {
+        self.entitlements
+            .revoke_payment(payment_nonce, reason)
+            .await
+    }
+
+    /// Revokes all access granted to a payer or for a resource. Matching
+    /// sessions are refused like a manual revoke, receipts issued so far
+    /// are rejected through the revocation store and an
+    /// [`AccessRevoked`](PaymentEventKind::AccessRevoked) event is emitted per
+    /// session. Returns the number of sessions revoked.
+    pub async fn revoke_entitlements(
+        &self,
+        operator: &str,
+        scope: RevocationScope,
+        reason: &str,
+    ) -> Result<usize, EngineError> {
+        self.entitlements
+            .revoke_entitlements(operator, scope, reason)
+            .await
+    }
+
+    /// run the compliance screen, the 403 result to return when the payer
+    /// must not be served
+    async fn screen_payer(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        chain_type: &ChainType,
+        context: &RequestContext,
+    ) -> Result<Option<VerificationResult>, EngineError> {
+        let Some(reason) = self.screening_denial(user_address, chain_type).await else {
+            return Ok(None);
+        };
+        self.emit(
+            user_address,
+            resource,
+            context,
+            PaymentEventKind::AccessDenied { reason },
+        )
+        .await?;
+        Ok(Some(VerificationResult {
+            should_serve_content: false,
+            http_status: 403,
+            x402_response: None,
+            verification: None,
+            receipt: None,
+            retry_after: None,
+        }))
+    }
+
+    /// reason the compliance screen refuses the payer, `None` when cleared
+    async fn screening_denial(&self, user_address: &str, chain_type: &ChainType) -> Option<String> {
+        let screen = self.compliance_screen.as_ref()?;
+        match screen.screen(user_address, chain_type).await {
+            Ok(ScreeningOutcome::Clear) => None,
+            Ok(ScreeningOutcome::Blocked { reason }) => Some(format!("payer screened: {}", reason)),
+            Err(err) => match self.config_manager.get_config().compliance.failure_mode {
+                ScreeningFailureMode::FailOpen => None,
+                ScreeningFailureMode::FailClosed => Some(err.to_string()),
+            },
+        }
+    }
+
+    /// refuse access for a payment found on chain, after a hook veto or the
+    /// compliance screen
+    async fn deny_payment(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        nonce: &str,
+        context: &RequestContext,
+        reason: String,
+    ) -> Result<VerificationResult, EngineError> {
+        self.emit(
+            user_address,
+            resource,
+            context,
+            PaymentEventKind::PaymentDenied {
+                nonce: nonce.to_string(),
+                reason,
+            },
+        )
+        .await?;
         Ok(VerificationResult {
             should_serve_content: false,
-            http_status: 402,
-            x402_response: Some(x402_response),
+            http_status: 403,
+            x402_response: None,
             verification: None,
+            receipt: None,
+            retry_after: None,
         })
     }
 
+    /// take a lock of the lock store, `None` without a lock store
+    async fn lock(&self, key: &str) -> Result<Option<LockGuard>, EngineError> {
+        let Some(lock_store) = &self.lock_store else {
+            return Ok(None);
+        };
+        let ttl_secs = self.config_manager.get_config().sessions.lock_ttl_secs;
+        if !lock_store
+            .try_lock(key, &self.replica_id, ttl_secs, self.clock.now())
+            .await
+            .map_err(EngineError::StoreError)?
+        {
+            return Err(EngineError::Locked {
+                retry_after: ttl_secs,
+            });
+        }
+        Ok(Some(LockGuard {
+            lock_store: lock_store.clone(),
+            key: key.to_string(),
+            owner: self.replica_id.clone(),
+        }))
+    }
+
+    async fn emit(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        context: &RequestContext,
+        kind: PaymentEventKind,
+    ) -> Result<(), EngineError> {
+        self.events
+            .emit(user_address, resource, context, kind)
+            .await
+            .map_err(EngineError::StoreError)
+    }
+
     pub fn config_manager(&self) -> &ConfigManager {
         &self.config_manager
     }
 
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    pub fn messages(&self) -> &MessageCatalog {
+        &self.messages
+    }
+
+    /// localized text for a refused request, for the body of a 402, 403,
+    /// 429 or 503 response, or of a 202 while verification is pending
+    pub fn message_for(
+        &self,
+        result: &VerificationResult,
+        context: &RequestContext,
+    ) -> Option<String> {
+        if result.should_serve_content {
+            return None;
+        }
+        if let Some(response) = &result.x402_response {
+            return response.message.clone();
+        }
+        let retry_after = result.retry_after.unwrap_or_default().to_string();
+        let (key, args): (MessageKey, &[(&str, &str)]) = match result.http_status {
+            202 => (MessageKey::Verifying, &[("retry_after", &retry_after)]),
+            403 => (MessageKey::AccessDenied, &[]),
+            429 => (
+                MessageKey::TooManyAttempts,
+                &[("retry_after", &retry_after)],
+            ),
+            503 => (MessageKey::ServiceUnavailable, &[]),
+            _ => return None,
+        };
+        Some(self.messages.message_for(context, key, args))
+    }
+
     pub fn verifier_registry(&self) -> &VerifierRegistry {
-        &self.verifier_registry
+        self.verifications.verifier_registry()
     }
 
     pub fn verifier_registry_mut(&mut self) -> &mut VerifierRegistry {
-        &mut self.verifier_registry
+        self.verifications.verifier_registry_mut()
     }
 }
 
@@ -293,10 +1952,36 @@ pub enum EngineError {
     ConfigError(ConfigError),
     VerificationError(VerificationError),
     InvalidSession,
+    /// the session is already paid on chain
+    SessionSettled,
+    /// the challenge expired, beyond the grace period, at the given time
+    SessionExpired {
+        expired_at: u64,
+    },
     AddressMismatch,
     ChainNotSupported(ChainType),
     VerificationFailed(VerificationError),
     InvalidCurrencyConfig,
+    ResourceMismatch,
+    /// the requested resource does not parse, e.g. a repeated query key
+    InvalidResource(ResourceError),
+    TooManyAttempts {
+        retry_after: u64,
+    },
+    PricingError(PricingError),
+    SignatureError(SignatureError),
+    ReceiptError(ReceiptError),
+    StoreError(StoreError),
+    AuditError(AuditError),
+    CouponError(CouponError),
+    LedgerError(LedgerError),
+    RelayError(RelayError),
+    /// no open dispute, or one is already open
+    InvalidDispute,
+    /// another replica holds the lock, retry after the given seconds
+    Locked {
+        retry_after: u64,
+    },
 }
 
 impl std::fmt::Display for EngineError {
@@ -305,12 +1990,37 @@ impl std::fmt::Display for EngineError {
             Self::ConfigError(err) => write!(f, "Configuration error: {}", err),
             Self::VerificationError(err) => write!(f, "Verification error: {}", err),
             Self::InvalidSession => write!(f, "Payment session not found"),
+            Self::SessionSettled => write!(f, "Payment session already settled"),
+            Self::SessionExpired { expired_at } => {
+                write!(f, "Payment challenge expired at {}", expired_at)
+            }
             Self::AddressMismatch => write!(f, "User address mismatch"),
             Self::ChainNotSupported(chain_type) => {
                 write!(f, "Chain not supported: {:?}", chain_type)
             }
             Self::VerificationFailed(err) => write!(f, "Verification failed: {}", err),
             Self::InvalidCurrencyConfig => write!(f, "Invalid currency configuration"),
+            Self::TooManyAttempts { retry_after } => {
+                write!(
+                    f,
+                    "Too many verification attempts, retry after {}s",
+                    retry_after
+                )
+            }
+            Self::ResourceMismatch => write!(f, "Session does not cover the requested resource"),
+            Self::InvalidResource(err) => write!(f, "Invalid resource: {}", err),
+            Self::PricingError(err) => write!(f, "Pricing error: {}", err),
+            Self::SignatureError(err) => write!(f, "Signature error: {}", err),
+            Self::ReceiptError(err) => write!(f, "Receipt error: {}", err),
+            Self::StoreError(err) => write!(f, "Store error: {}", err),
+            Self::AuditError(err) => write!(f, "Audit error: {}", err),
+            Self::CouponError(err) => write!(f, "Coupon error: {}", err),
+            Self::LedgerError(err) => write!(f, "Ledger error: {}", err),
+            Self::RelayError(err) => write!(f, "Relay error: {}", err),
+            Self::InvalidDispute => write!(f, "Invalid dispute state"),
+            Self::Locked { retry_after } => {
+                write!(f, "Locked by another replica, retry after {}s", retry_after)
+            }
         }
     }
 }
@@ -329,9 +2039,158 @@ impl From<VerificationError> for EngineError {
     }
 }
 
-struct PaymentSession {
-    user_address: String,
-    payment_request: PaymentRequest,
-    created_at: u64,
-    verified: bool,
+impl From<ResourceError> for EngineError {
+    fn from(err: ResourceError) -> Self {
+        Self::InvalidResource(err)
+    }
+}
+
+impl From<PricingError> for EngineError {
+    fn from(err: PricingError) -> Self {
+        Self::PricingError(err)
+    }
+}
+
+impl From<SignatureError> for EngineError {
+    fn from(err: SignatureError) -> Self {
+        Self::SignatureError(err)
+    }
+}
+
+impl From<CouponError> for EngineError {
+    fn from(err: CouponError) -> Self {
+        Self::CouponError(err)
+    }
+}
+
+impl From<LedgerError> for EngineError {
+    fn from(err: LedgerError) -> Self {
+        Self::LedgerError(err)
+    }
+}
+
+impl From<RelayError> for EngineError {
+    fn from(err: RelayError) -> Self {
+        Self::RelayError(err)
+    }
+}
+
+impl From<ReceiptError> for EngineError {
+    fn from(err: ReceiptError) -> Self {
+        Self::ReceiptError(err)
+    }
+}
+
+fn session_io_error(err: impl std::fmt::Display) -> EngineError {
+    EngineError::StoreError(StoreError::Backend(err.to_string()))
+}
+
+/// Payment session as exported by [`X402::export_sessions`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentSession {
+    pub user_address: String,
+    pub resource: Resource,
+    pub payment_request: PaymentRequest,
+    pub created_at: u64,
+    pub verified: bool,
+    #[serde(default)]
+    pub attempts: SessionAttempts,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dispute: Option<Dispute>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manual_override: Option<ManualOverride>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coupon_code: Option<String>,
+}
+
+/// Outcome of [`X402::import_sessions`], in sessions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SessionImportReport {
+    pub imported: usize,
+    /// already known to the engine
+    pub skipped: usize,
+}
+
+/// Outcome of [`X402::update_finality`], in payments.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FinalityReport {
+    pub finalized: usize,
+    pub reverted: usize,
+    /// still waiting for finality
+    pub provisional: usize,
+}
+
+/// Outcome of [`X402::register_all_configured`].
+#[derive(Debug, Default)]
+pub struct RegistrationReport {
+    pub registered: Vec<ChainType>,
+    pub failed: Vec<(ChainType, EngineError)>,
+}
+
+impl RegistrationReport {
+    /// whether every configured chain got a verifier
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Lock of the lock store, released when dropped.
+struct LockGuard {
+    lock_store: Arc<dyn LockStore>,
+    key: String,
+    owner: String,
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        // an unreleased lock expires after its ttl
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let lock_store = self.lock_store.clone();
+        let key = std::mem::take(&mut self.key);
+        let owner = std::mem::take(&mut self.owner);
+        runtime.spawn(async move {
+            let _ = lock_store.unlock(&key, &owner).await;
+        });
+    }
+}
+
+/// Verification attempt counters of a payment session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionAttempts {
+    pub attempts: u32,
+    /// consecutive failed attempts since the last lockout
+    pub failed_attempts: u32,
+    pub locked_until: Option<u64>,
+    pub next_attempt_at: Option<u64>,
+}
+
+impl SessionAttempts {
+    /// time before which no verification is attempted
+    pub fn blocked_until(&self) -> Option<u64> {
+        self.locked_until.max(self.next_attempt_at)
+    }
+
+    pub(crate) fn record(&mut self, paid: bool, now: u64, limits: &AttemptLimitConfig) {
+        self.attempts += 1;
+        if paid {
+            return;
+        }
+        if self.locked_until.is_some_and(|until| until <= now) {
+            self.failed_attempts = 0;
+            self.locked_until = None;
+        }
+        self.failed_attempts += 1;
+        if limits.max_failed_attempts > 0 && self.failed_attempts >= limits.max_failed_attempts {
+            self.locked_until = Some(now + limits.lockout_secs);
+        } else if limits.backoff_base_secs > 0 {
+            let exponent = (self.failed_attempts - 1).min(31);
+            let delay = limits
+                .backoff_base_secs
+                .saturating_mul(1 << exponent)
+                .min(limits.lockout_secs);
+            self.next_attempt_at = Some(now + delay);
+        }
+    }
 }
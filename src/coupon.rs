@@ -0,0 +1,299 @@
+/// Coupon and promotional pricing module.
+use crate::resource::{Resource, ResourcePattern};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CouponError {
+    Unknown(String),
+    NotYetValid,
+    Expired,
+    Exhausted,
+    /// the coupon does not cover the requested resource
+    NotApplicable,
+    InvalidAmount(String),
+}
+
+impl std::fmt::Display for CouponError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(code) => write!(f, "Unknown coupon: {}", code),
+            Self::NotYetValid => write!(f, "Coupon is not valid yet"),
+            Self::Expired => write!(f, "Coupon has expired"),
+            Self::Exhausted => write!(f, "Coupon has no redemptions left"),
+            Self::NotApplicable => write!(f, "Coupon does not apply to this resource"),
+            Self::InvalidAmount(amount) => write!(f, "Invalid amount: {}", amount),
+        }
+    }
+}
+
+impl std::error::Error for CouponError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Discount {
+    /// percentage off the quoted amount, rounded down in favour of the payer
+    Percent(u8),
+    /// fixed amount off, in the smallest unit of the currency
+    Fixed(u128),
+}
+
+impl Discount {
+    pub fn apply(&self, amount: u128) -> u128 {
+        match self {
+            Self::Percent(percent) => {
+                let keep = 100 - u128::from((*percent).min(100));
+                amount / 100 * keep + amount % 100 * keep / 100
+            }
+            Self::Fixed(off) => amount.saturating_sub(*off),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Coupon {
+    pub code: String,
+    pub discount: Discount,
+    /// first second the coupon can be used
+    pub valid_from: Option<u64>,
+    /// first second the coupon can no longer be used
+    pub valid_until: Option<u64>,
+    pub max_redemptions: Option<u32>,
+    /// resources the coupon applies to, every resource when empty
+    pub routes: Vec<ResourcePattern>,
+}
+
+impl Coupon {
+    pub fn new(code: &str, discount: Discount) -> Self {
+        Self {
+            code: code.to_string(),
+            discount,
+            valid_from: None,
+            valid_until: None,
+            max_redemptions: None,
+            routes: Vec::new(),
+        }
+    }
+
+    pub fn with_validity(mut self, valid_from: u64, valid_until: u64) -> Self {
+        self.valid_from = Some(valid_from);
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    pub fn with_max_redemptions(mut self, max_redemptions: u32) -> Self {
+        self.max_redemptions = Some(max_redemptions);
+        self
+    }
+
+    /// restrict the coupon to resources matching the pattern, see
+    /// [`ResourcePattern::parse`]
+    pub fn with_route(mut self, pattern: &str) -> Self {
+        self.routes.push(ResourcePattern::parse(pattern));
+        self
+    }
+
+    pub fn applies_to(&self, resource: &Resource) -> bool {
+        self.routes.is_empty() || self.routes.iter().any(|route| route.matches(resource))
+    }
+}
+
+struct CouponEntry {
+    coupon: Coupon,
+    redemptions: u32,
+    /// unpaid sessions issued with the coupon, by nonce, with the time
+    /// their slot is released
+    reservations: HashMap<String, Option<u64>>,
+}
+
+impl CouponEntry {
+    /// redemptions and slots still held by unpaid sessions
+    fn used(&self, now: u64) -> usize {
+        self.redemptions as usize
+            + self
+                .reservations
+                .values()
+                .filter(|release_at| release_at.is_none_or(|release_at| now < release_at))
+                .count()
+    }
+
+    fn exhausted(&self, now: u64) -> bool {
+        self.coupon
+            .max_redemptions
+            .is_some_and(|max| self.used(now) >= max as usize)
+    }
+
+    fn release_expired(&mut self, now: u64) {
+        self.reservations
+            .retain(|_, release_at| release_at.is_none_or(|release_at| now < release_at));
+    }
+}
+
+/// Configured coupons with their redemption counters.
+///
+/// A session issued with a coupon reserves one of its redemptions until the
+/// session is paid or expires, so a coupon never discounts more sessions
+/// than it has redemptions.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::coupon::{Coupon, CouponBook, Discount};
+///
+/// let coupons = CouponBook::new()
+///     .with_coupon(Coupon::new("LAUNCH", Discount::Percent(50)).with_max_redemptions(100))
+///     .with_coupon(Coupon::new("DOCS", Discount::Fixed(500)).with_route("/docs/**"));
+/// assert_eq!(coupons.redemptions("LAUNCH"), Some(0));
+/// ```
+#[derive(Default)]
+pub struct CouponBook {
+    coupons: RwLock<HashMap<String, CouponEntry>>,
+}
+
+impl CouponBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_coupon(self, coupon: Coupon) -> Self {
+        self.add_coupon(coupon);
+        self
+    }
+
+    /// add or replace a coupon, replacing keeps the redemption count and the
+    /// reservations
+    pub fn add_coupon(&self, coupon: Coupon) {
+        let mut coupons = self.coupons.write().unwrap();
+        let (redemptions, reservations) = coupons
+            .remove(&coupon.code)
+            .map_or((0, HashMap::new()), |entry| {
+                (entry.redemptions, entry.reservations)
+            });
+        coupons.insert(
+            coupon.code.clone(),
+            CouponEntry {
+                coupon,
+                redemptions,
+                reservations,
+            },
+        );
+    }
+
+    pub fn remove_coupon(&self, code: &str) -> Option<Coupon> {
+        self.coupons
+            .write()
+            .unwrap()
+            .remove(code)
+            .map(|entry| entry.coupon)
+    }
+
+    pub fn redemptions(&self, code: &str) -> Option<u32> {
+        self.coupons
+            .read()
+            .unwrap()
+            .get(code)
+            .map(|entry| entry.redemptions)
+    }
+
+    /// sessions holding a redemption of the coupon, expired ones included
+    /// until the next reservation releases them
+    pub fn reservations(&self, code: &str) -> Option<usize> {
+        self.coupons
+            .read()
+            .unwrap()
+            .get(code)
+            .map(|entry| entry.reservations.len())
+    }
+
+    /// discounted amount for a resource, checks validity window, route and
+    /// remaining redemptions without reserving one, see [`Self::reserve`]
+    pub fn apply(
+        &self,
+        code: &str,
+        resource: &Resource,
+        amount: &str,
+        now: u64,
+    ) -> Result<String, CouponError> {
+        let coupons = self.coupons.read().unwrap();
+        let entry = coupons
+            .get(code)
+            .ok_or_else(|| CouponError::Unknown(code.to_string()))?;
+        let coupon = &entry.coupon;
+        if coupon.valid_from.is_some_and(|from| now < from) {
+            return Err(CouponError::NotYetValid);
+        }
+        if coupon.valid_until.is_some_and(|until| now >= until) {
+            return Err(CouponError::Expired);
+        }
+        if entry.exhausted(now) {
+            return Err(CouponError::Exhausted);
+        }
+        if !coupon.applies_to(resource) {
+            return Err(CouponError::NotApplicable);
+        }
+        let amount: u128 = amount
+            .parse()
+            .map_err(|_| CouponError::InvalidAmount(amount.to_string()))?;
+        Ok(coupon.discount.apply(amount).to_string())
+    }
+
+    /// Holds a redemption for the session `nonce` until it is redeemed,
+    /// released or `release_at` passes. Reserving again for the same
+    /// session only moves its release time.
+    pub fn reserve(
+        &self,
+        code: &str,
+        nonce: &str,
+        now: u64,
+        release_at: Option<u64>,
+    ) -> Result<(), CouponError> {
+        let mut coupons = self.coupons.write().unwrap();
+        let entry = coupons
+            .get_mut(code)
+            .ok_or_else(|| CouponError::Unknown(code.to_string()))?;
+        entry.release_expired(now);
+        if !entry.reservations.contains_key(nonce) && entry.exhausted(now) {
+            return Err(CouponError::Exhausted);
+        }
+        entry.reservations.insert(nonce.to_string(), release_at);
+        Ok(())
+    }
+
+    /// give the redemption held by the session back
+    pub fn release(&self, code: &str, nonce: &str) {
+        if let Some(entry) = self.coupons.write().unwrap().get_mut(code) {
+            entry.reservations.remove(nonce);
+        }
+    }
+
+    /// count a redemption of the coupon, `Exhausted` once the redemptions
+    /// and the unreleased reservations reach the limit
+    pub fn redeem(&self, code: &str) -> Result<u32, CouponError> {
+        let mut coupons = self.coupons.write().unwrap();
+        let entry = coupons
+            .get_mut(code)
+            .ok_or_else(|| CouponError::Unknown(code.to_string()))?;
+        // without a clock every reservation still counts
+        if entry.exhausted(0) {
+            return Err(CouponError::Exhausted);
+        }
+        entry.redemptions += 1;
+        Ok(entry.redemptions)
+    }
+
+    /// count the redemption reserved by the paid session `nonce`, a session
+    /// whose reservation was released redeems like [`Self::redeem`]
+    pub fn redeem_reserved(&self, code: &str, nonce: &str, now: u64) -> Result<u32, CouponError> {
+        let mut coupons = self.coupons.write().unwrap();
+        let entry = coupons
+            .get_mut(code)
+            .ok_or_else(|| CouponError::Unknown(code.to_string()))?;
+        if entry.reservations.remove(nonce).is_none() {
+            entry.release_expired(now);
+            if entry.exhausted(now) {
+                return Err(CouponError::Exhausted);
+            }
+        }
+        entry.redemptions += 1;
+        Ok(entry.redemptions)
+    }
+}
@@ -0,0 +1,215 @@
+/// Signature suite module.
+use crate::keys::Jwk;
+use crate::signing::{ChallengeSigner, SignatureError};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ed25519_dalek::{Signer as _, SigningKey, Verifier as _, VerifyingKey};
+use ethers::utils::hex;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// algorithm identifier of [`Ed25519Suite`] signatures
+pub const ED25519_ALGORITHM: &str = "ed25519";
+
+/// algorithm identifier of [`HmacSha256Suite`] signatures
+pub const HMAC_SHA256_ALGORITHM: &str = "hmac-sha256";
+
+/// Signature primitive behind challenges, receipts and webhooks.
+///
+/// Messages are the domain prefix followed by the canonical JSON of the
+/// payload, see [`crate::canonical`]. Implement it to sign with an HSM or
+/// a FIPS validated module, the key never has to leave it.
+pub trait CryptoSuite: Send + Sync {
+    /// algorithm identifier written to [`ChallengeSignature::algorithm`](crate::types::ChallengeSignature)
+    fn algorithm(&self) -> String;
+
+    /// identity published with signatures, e.g. an address or a public key
+    fn signer(&self) -> String;
+
+    /// `0x` hex signature over the message
+    fn sign(&self, message: &[u8]) -> Result<String, SignatureError>;
+
+    fn verify(&self, message: &[u8], signature: &str) -> Result<(), SignatureError>;
+
+    /// public key published in the key set, `None` for symmetric keys
+    fn jwk(&self, _kid: &str) -> Option<Jwk> {
+        None
+    }
+}
+
+impl<S: CryptoSuite + ?Sized> CryptoSuite for Arc<S> {
+    fn algorithm(&self) -> String {
+        (**self).algorithm()
+    }
+
+    fn signer(&self) -> String {
+        (**self).signer()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<String, SignatureError> {
+        (**self).sign(message)
+    }
+
+    fn verify(&self, message: &[u8], signature: &str) -> Result<(), SignatureError> {
+        (**self).verify(message, signature)
+    }
+
+    fn jwk(&self, kid: &str) -> Option<Jwk> {
+        (**self).jwk(kid)
+    }
+}
+
+/// Suite selected in [`SigningConfig`](crate::config::SigningConfig).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureSuite {
+    /// ECDSA over keccak256, the signer is an Ethereum address
+    #[default]
+    Secp256k1,
+    Ed25519,
+    /// shared secret, only for consumers that hold the same secret
+    HmacSha256,
+}
+
+impl SignatureSuite {
+    /// suite for the key read from `X402_SIGNING_KEY`: a hex private key,
+    /// or the raw secret for HMAC-SHA256 which signs as `key_id`
+    pub fn load(&self, key: &str, key_id: &str) -> Result<Arc<dyn CryptoSuite>, SignatureError> {
+        Ok(match self {
+            Self::Secp256k1 => Arc::new(ChallengeSigner::from_private_key(key)?),
+            Self::Ed25519 => Arc::new(Ed25519Suite::from_secret_key(key)?),
+            Self::HmacSha256 => Arc::new(HmacSha256Suite::new(key_id, key.as_bytes())),
+        })
+    }
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, SignatureError> {
+    hex::decode(value.trim().trim_start_matches("0x"))
+        .map_err(|e| SignatureError::InvalidSignature(e.to_string()))
+}
+
+/// Ed25519 key, the signer is the `0x` hex public key.
+#[derive(Clone)]
+pub struct Ed25519Suite {
+    key: SigningKey,
+}
+
+impl Ed25519Suite {
+    /// load a hex encoded 32 byte secret key
+    pub fn from_secret_key(secret_key: &str) -> Result<Self, SignatureError> {
+        let bytes: [u8; 32] = decode_hex(secret_key)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| SignatureError::InvalidKey("expected 32 hex bytes".to_string()))?;
+        Ok(Self::new(bytes))
+    }
+
+    pub fn new(secret_key: [u8; 32]) -> Self {
+        Self {
+            key: SigningKey::from_bytes(&secret_key),
+        }
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+}
+
+impl CryptoSuite for Ed25519Suite {
+    fn algorithm(&self) -> String {
+        ED25519_ALGORITHM.to_string()
+    }
+
+    fn signer(&self) -> String {
+        format!("0x{}", hex::encode(self.public_key()))
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<String, SignatureError> {
+        Ok(format!(
+            "0x{}",
+            hex::encode(self.key.sign(message).to_bytes())
+        ))
+    }
+
+    fn verify(&self, message: &[u8], signature: &str) -> Result<(), SignatureError> {
+        verify_ed25519(&self.public_key(), message, signature)
+    }
+
+    fn jwk(&self, kid: &str) -> Option<Jwk> {
+        Some(Jwk {
+            kty: "OKP".to_string(),
+            crv: "Ed25519".to_string(),
+            alg: "EdDSA".to_string(),
+            key_use: "sig".to_string(),
+            kid: kid.to_string(),
+            x: URL_SAFE_NO_PAD.encode(self.public_key()),
+            y: String::new(),
+        })
+    }
+}
+
+/// check an Ed25519 signature against a raw public key, e.g. one taken from
+/// the key set
+pub fn verify_ed25519(
+    public_key: &[u8; 32],
+    message: &[u8],
+    signature: &str,
+) -> Result<(), SignatureError> {
+    let key = VerifyingKey::from_bytes(public_key)
+        .map_err(|e| SignatureError::InvalidKey(e.to_string()))?;
+    let signature = ed25519_dalek::Signature::from_slice(&decode_hex(signature)?)
+        .map_err(|e| SignatureError::InvalidSignature(e.to_string()))?;
+    key.verify(message, &signature)
+        .map_err(|e| SignatureError::InvalidSignature(e.to_string()))
+}
+
+/// HMAC-SHA256 with a shared secret, the signer is the key id.
+#[derive(Clone)]
+pub struct HmacSha256Suite {
+    key_id: String,
+    secret: Vec<u8>,
+}
+
+impl HmacSha256Suite {
+    pub fn new(key_id: &str, secret: &[u8]) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            secret: secret.to_vec(),
+        }
+    }
+
+    fn mac(&self, message: &[u8]) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(message);
+        mac
+    }
+}
+
+impl CryptoSuite for HmacSha256Suite {
+    fn algorithm(&self) -> String {
+        HMAC_SHA256_ALGORITHM.to_string()
+    }
+
+    fn signer(&self) -> String {
+        self.key_id.clone()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<String, SignatureError> {
+        Ok(format!(
+            "0x{}",
+            hex::encode(self.mac(message).finalize().into_bytes())
+        ))
+    }
+
+    fn verify(&self, message: &[u8], signature: &str) -> Result<(), SignatureError> {
+        // constant time comparison
+        self.mac(message)
+            .verify_slice(&decode_hex(signature)?)
+            .map_err(|_| SignatureError::InvalidSignature("MAC mismatch".to_string()))
+    }
+}
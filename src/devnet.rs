@@ -0,0 +1,226 @@
+/// Local development network module.
+use crate::types::{ChainType, Currency, PaymentRequest, X402ProtocolResponse};
+use ethers::abi::{Token, encode};
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Bytes, H160, H256, TransactionRequest, U256};
+use ethers::utils::{hex, id, keccak256};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Default RPC of `anvil`.
+pub const ANVIL_RPC_URL: &str = "http://127.0.0.1:8545";
+
+/// gas money given to the throwaway account with every payment, 1 ether
+const GAS_FUNDING: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Debug)]
+pub enum DevnetError {
+    /// the chain or currency cannot be paid on a local node
+    Unsupported(String),
+    InvalidRequest(String),
+    NetworkError(String),
+    /// the payment transaction reverted
+    Rejected(String),
+}
+
+impl std::fmt::Display for DevnetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported(msg) => write!(f, "Unsupported on a local node: {}", msg),
+            Self::InvalidRequest(msg) => write!(f, "Invalid payment request: {}", msg),
+            Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            Self::Rejected(msg) => write!(f, "Payment rejected: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DevnetError {}
+
+/// Storage slot of the balance of `holder` in a token whose balances
+/// mapping is at `slot`, slot 0 for OpenZeppelin `ERC20`.
+pub fn balance_storage_key(holder: H160, slot: u64) -> H256 {
+    H256::from(keccak256(encode(&[
+        Token::Address(holder),
+        Token::Uint(U256::from(slot)),
+    ])))
+}
+
+/// Pays 402 challenges from a throwaway account on a local `anvil` node,
+/// minting the ether and tokens it needs with the `anvil_*` cheat codes.
+///
+/// Example apps and CI integration tests run the real [`EvmVerifier`]
+/// against the node without a funded wallet. Dev only: the cheat codes are
+/// refused by real networks.
+///
+/// Solana challenges are not supported, the Solana verifier reads mainnet
+/// whatever RPC is configured.
+///
+/// [`EvmVerifier`]: crate::verifier::evm::EvmVerifier
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::core::X402;
+/// use x402_sdk::devnet::{ANVIL_RPC_URL, AutoPayer};
+///
+/// # async fn example(engine: X402) -> Result<(), Box<dyn std::error::Error>> {
+/// let payer = AutoPayer::new(ANVIL_RPC_URL)?;
+/// let result = engine
+///     .handle_access_request(&payer.address(), "/premium", None, None)
+///     .await?;
+/// if let Some(challenge) = &result.x402_response {
+///     payer.pay_challenge(challenge).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct AutoPayer {
+    provider: Provider<Http>,
+    wallet: LocalWallet,
+    /// slot of the balances mapping of tokens not laid out like
+    /// OpenZeppelin `ERC20`
+    balance_slots: HashMap<H160, u64>,
+}
+
+impl AutoPayer {
+    /// payer with a new random account, the node is not contacted yet
+    pub fn new(rpc_url: &str) -> Result<Self, DevnetError> {
+        let provider = Provider::<Http>::try_from(rpc_url)
+            .map_err(|e| DevnetError::NetworkError(format!("Failed to create provider: {}", e)))?;
+        Ok(Self {
+            provider,
+            wallet: LocalWallet::new(&mut ethers::core::rand::thread_rng()),
+            balance_slots: HashMap::new(),
+        })
+    }
+
+    /// pay from a fixed account, e.g. one of the prefunded anvil accounts
+    pub fn with_private_key(mut self, private_key: &str) -> Result<Self, DevnetError> {
+        self.wallet = LocalWallet::from_str(private_key.trim_start_matches("0x"))
+            .map_err(|e| DevnetError::InvalidRequest(format!("invalid private key: {}", e)))?;
+        Ok(self)
+    }
+
+    /// slot of the balances mapping of a token, 0 when not set
+    pub fn with_balance_slot(mut self, token: &str, slot: u64) -> Result<Self, DevnetError> {
+        self.balance_slots.insert(parse_address(token)?, slot);
+        Ok(self)
+    }
+
+    /// address the payments come from, the user of the challenged session
+    pub fn address(&self) -> String {
+        format!("{:?}", self.wallet.address())
+    }
+
+    /// set the ether balance of the account
+    pub async fn fund(&self, wei: U256) -> Result<(), DevnetError> {
+        self.provider
+            .request::<_, ()>("anvil_setBalance", (self.wallet.address(), wei))
+            .await
+            .map_err(|e| DevnetError::NetworkError(e.to_string()))
+    }
+
+    /// set the token balance of the account, in base units
+    pub async fn fund_token(&self, token: &str, amount: U256) -> Result<(), DevnetError> {
+        let token = parse_address(token)?;
+        let slot = self.balance_slots.get(&token).copied().unwrap_or_default();
+        let mut value = [0u8; 32];
+        amount.to_big_endian(&mut value);
+        self.provider
+            .request::<_, ()>(
+                "anvil_setStorageAt",
+                (
+                    token,
+                    balance_storage_key(self.wallet.address(), slot),
+                    H256::from(value),
+                ),
+            )
+            .await
+            .map_err(|e| DevnetError::NetworkError(e.to_string()))
+    }
+
+    /// [`Self::pay`] the request of a 402 response
+    pub async fn pay_challenge(
+        &self,
+        response: &X402ProtocolResponse,
+    ) -> Result<String, DevnetError> {
+        self.pay(&response.payment_required).await
+    }
+
+    /// Funds the account and sends the requested payment, returns the
+    /// transaction hash once it is mined. The reference of delegated
+    /// requests is sent along like a wallet would.
+    pub async fn pay(&self, request: &PaymentRequest) -> Result<String, DevnetError> {
+        if !matches!(request.chain.chain_type, ChainType::Evm(_)) {
+            return Err(DevnetError::Unsupported(format!(
+                "{} payments",
+                request.chain.chain_type.get_display_name()
+            )));
+        }
+        let recipient = parse_address(&request.recipient)?;
+        let amount = U256::from_dec_str(&request.amount)
+            .map_err(|e| DevnetError::InvalidRequest(format!("invalid amount: {}", e)))?;
+        let reference = match &request.reference {
+            Some(reference) => hex::decode(reference.trim_start_matches("0x"))
+                .map_err(|e| DevnetError::InvalidRequest(format!("invalid reference: {}", e)))?,
+            None => Vec::new(),
+        };
+        let transaction = match &request.currency {
+            Currency::Native => {
+                self.fund(amount + U256::from(GAS_FUNDING)).await?;
+                TransactionRequest::new()
+                    .to(recipient)
+                    .value(amount)
+                    .data(Bytes::from(reference))
+            }
+            Currency::Token { address, decimals } => {
+                // token challenges are in whole tokens
+                let value = amount * U256::from(10).pow(U256::from(*decimals));
+                self.fund(U256::from(GAS_FUNDING)).await?;
+                self.fund_token(address, value).await?;
+                let mut data = id("transfer(address,uint256)").to_vec();
+                data.extend(encode(&[Token::Address(recipient), Token::Uint(value)]));
+                data.extend(reference);
+                TransactionRequest::new()
+                    .to(parse_address(address)?)
+                    .data(Bytes::from(data))
+            }
+            Currency::AnyToken { .. } => {
+                return Err(DevnetError::Unsupported(
+                    "any-token challenges, pay them in a listed token".to_string(),
+                ));
+            }
+        };
+        let chain_id = self
+            .provider
+            .get_chainid()
+            .await
+            .map_err(|e| DevnetError::NetworkError(e.to_string()))?;
+        let client = SignerMiddleware::new(
+            self.provider.clone(),
+            self.wallet.clone().with_chain_id(chain_id.as_u64()),
+        );
+        let receipt = client
+            .send_transaction(transaction, None)
+            .await
+            .map_err(|e| DevnetError::Rejected(e.to_string()))?
+            .await
+            .map_err(|e| DevnetError::NetworkError(e.to_string()))?
+            .ok_or_else(|| DevnetError::Rejected("transaction dropped".to_string()))?;
+        let transaction_hash = format!("{:?}", receipt.transaction_hash);
+        if receipt.status != Some(1.into()) {
+            return Err(DevnetError::Rejected(format!(
+                "transaction {} reverted",
+                transaction_hash
+            )));
+        }
+        Ok(transaction_hash)
+    }
+}
+
+fn parse_address(address: &str) -> Result<H160, DevnetError> {
+    H160::from_str(address)
+        .map_err(|e| DevnetError::InvalidRequest(format!("invalid address {}: {}", address, e)))
+}
@@ -0,0 +1,159 @@
+/// Paid resource discovery module.
+use crate::config::ServiceConfig;
+use crate::pricing::PricingRule;
+use crate::resource::ResourcePattern;
+use crate::types::{ChainConfig, Currency};
+use serde::{Deserialize, Serialize};
+
+/// path HTTP integrations serve the discovery document at
+pub const DISCOVERY_PATH: &str = "/.well-known/x402";
+
+/// version of the x402 protocol the document describes
+pub const X402_VERSION: u32 = 1;
+
+/// A way to pay for a resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentOption {
+    pub scheme: String,
+    /// display name of the chain
+    pub network: String,
+    pub chain_id: String,
+    /// amount charged per request, in the smallest unit of the asset, the
+    /// actual price may be lower for volume or discounted pricing
+    pub max_amount_required: String,
+    /// native currency symbol or token contract address
+    pub asset: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+    pub pay_to: String,
+    pub max_timeout_seconds: u64,
+}
+
+impl PaymentOption {
+    pub fn new(
+        chain: &ChainConfig,
+        currency: &Currency,
+        amount: &str,
+        pay_to: &str,
+        max_timeout_seconds: u64,
+    ) -> Self {
+        let (asset, decimals) = match currency {
+            Currency::Native => (
+                chain
+                    .native_symbol()
+                    .unwrap_or_else(|| "native".to_string()),
+                chain.native_decimals(),
+            ),
+            Currency::Token { address, decimals } => (address.clone(), Some(*decimals)),
+            Currency::AnyToken { allowlist } if allowlist.is_empty() => ("any".to_string(), None),
+            Currency::AnyToken { allowlist } => (allowlist.join(","), None),
+        };
+        Self {
+            scheme: "exact".to_string(),
+            network: chain.chain_type.get_display_name(),
+            chain_id: chain.chain_id.clone(),
+            max_amount_required: amount.to_string(),
+            asset,
+            decimals,
+            pay_to: pay_to.to_string(),
+            max_timeout_seconds,
+        }
+    }
+}
+
+/// A paid resource listed in the discovery document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryItem {
+    /// path pattern, `*` matches one segment and `**` any number of segments
+    pub resource: String,
+    /// HTTP method, any method when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub accepts: Vec<PaymentOption>,
+}
+
+impl DiscoveryItem {
+    pub fn new(pattern: &ResourcePattern, accepts: Vec<PaymentOption>) -> Self {
+        Self {
+            resource: pattern.path.clone(),
+            method: pattern.method.clone(),
+            kind: "http".to_string(),
+            description: None,
+            accepts,
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+}
+
+/// Machine-readable catalog of the paid resources of a service, for agent
+/// marketplaces and other indexers.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::config::ConfigManager;
+/// use x402_sdk::discovery::{DiscoveryDocument, DiscoveryItem, PaymentOption};
+/// use x402_sdk::resource::ResourcePattern;
+/// use x402_sdk::types::{ChainConfig, ChainType, Currency};
+///
+/// let config = ConfigManager::new().unwrap();
+/// let chain = ChainConfig::from_chain_type(ChainType::ethereum());
+/// let option = PaymentOption::new(&chain, &Currency::Native, "1000", "0x00", 300);
+/// let document = DiscoveryDocument::new(&config.get_config().service).with_item(
+///     DiscoveryItem::new(&ResourcePattern::parse("GET /data/**"), vec![option])
+///         .with_description("market data"),
+/// );
+/// assert!(document.to_json().unwrap().contains("\"maxAmountRequired\":\"1000\""));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryDocument {
+    pub x402_version: u32,
+    pub name: String,
+    pub description: String,
+    pub items: Vec<DiscoveryItem>,
+}
+
+impl DiscoveryDocument {
+    pub fn new(service: &ServiceConfig) -> Self {
+        Self {
+            x402_version: X402_VERSION,
+            name: service.name.clone(),
+            description: service.description.clone(),
+            items: Vec::new(),
+        }
+    }
+
+    pub fn with_item(mut self, item: DiscoveryItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// list a priced resource with the given payment options
+    pub fn with_rule(self, rule: &PricingRule, accepts: &[PaymentOption]) -> Self {
+        let accepts = accepts
+            .iter()
+            .map(|option| PaymentOption {
+                max_amount_required: rule.amount.clone(),
+                ..option.clone()
+            })
+            .collect();
+        let mut item = DiscoveryItem::new(&rule.pattern, accepts);
+        item.description = rule.description.clone();
+        self.with_item(item)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+}
@@ -0,0 +1,54 @@
+/// Payment dispute module.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisputeStatus {
+    Open,
+    Resolved,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisputeNote {
+    pub author: String,
+    pub created_at: u64,
+    pub text: String,
+    /// references to supporting material, e.g. ticket links or file URIs
+    pub evidence: Vec<String>,
+}
+
+/// Dispute raised on a payment session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dispute {
+    pub status: DisputeStatus,
+    pub opened_by: String,
+    pub opened_at: u64,
+    pub resolved_at: Option<u64>,
+    pub notes: Vec<DisputeNote>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverrideDecision {
+    /// treat the session as paid without checking the chain
+    Grant,
+    /// treat the session as unpaid whatever the chain shows, receipts
+    /// issued for it are revoked
+    Revoke,
+}
+
+impl std::fmt::Display for OverrideDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Grant => write!(f, "grant"),
+            Self::Revoke => write!(f, "revoke"),
+        }
+    }
+}
+
+/// Manual verification decision taken by an operator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManualOverride {
+    pub decision: OverrideDecision,
+    pub operator: String,
+    pub reason: String,
+    pub decided_at: u64,
+}
@@ -0,0 +1,274 @@
+/// At-rest encryption module.
+use crate::store::StoreError;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Environment variable read by [`LocalKey::from_env`] by default.
+pub const STORE_KEY_ENV: &str = "X402_STORE_KEY";
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+/// envelopes binding their version and key id as associated data
+const ENVELOPE_VERSION: u8 = 2;
+
+/// Wraps the data keys of [`EnvelopeCipher`] with a master key that never
+/// touches the disk, e.g. one held by a KMS.
+pub trait KeyWrapper: Send + Sync {
+    /// id of the master key, stored with every envelope
+    fn key_id(&self) -> String;
+
+    fn wrap(&self, data_key: &[u8]) -> Result<Vec<u8>, StoreError>;
+
+    /// unwrap a data key wrapped by the master key `key_id`
+    fn unwrap(&self, key_id: &str, wrapped_key: &[u8]) -> Result<Vec<u8>, StoreError>;
+}
+
+fn cipher(key: &[u8]) -> Result<XChaCha20Poly1305, StoreError> {
+    let key: [u8; KEY_LEN] = key
+        .try_into()
+        .map_err(|_| StoreError::Encryption("invalid key".to_string()))?;
+    Ok(XChaCha20Poly1305::new(&Key::from(key)))
+}
+
+fn seal(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<(Vec<u8>, Vec<u8>), StoreError> {
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let ciphertext = cipher(key)?
+        .encrypt(
+            &XNonce::from(nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| StoreError::Encryption("encryption failed".to_string()))?;
+    Ok((nonce.to_vec(), ciphertext))
+}
+
+fn open(key: &[u8], nonce: &[u8], ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>, StoreError> {
+    let nonce: [u8; NONCE_LEN] = nonce
+        .try_into()
+        .map_err(|_| StoreError::Encryption("invalid nonce".to_string()))?;
+    cipher(key)?
+        .decrypt(
+            &XNonce::from(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| StoreError::Encryption("decryption failed, wrong key?".to_string()))
+}
+
+/// associated data of an envelope, so its header cannot be swapped
+fn envelope_aad(version: u8, key_id: &str) -> Vec<u8> {
+    format!("x402-envelope:{}:{}", version, key_id).into_bytes()
+}
+
+/// Master key held by the process, e.g. from the environment.
+pub struct LocalKey {
+    key_id: String,
+    key: [u8; KEY_LEN],
+}
+
+impl LocalKey {
+    pub fn new(key_id: &str, key: [u8; KEY_LEN]) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            key,
+        }
+    }
+
+    /// base64 encoded 32 byte key from the environment variable `var`, e.g.
+    /// [`STORE_KEY_ENV`]
+    pub fn from_env(var: &str) -> Result<Self, StoreError> {
+        let encoded = std::env::var(var)
+            .map_err(|_| StoreError::Encryption(format!("{} is not set", var)))?;
+        let key = STANDARD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|key| <[u8; KEY_LEN]>::try_from(key).ok())
+            .ok_or_else(|| {
+                StoreError::Encryption(format!("{} is not a base64 32 byte key", var))
+            })?;
+        Ok(Self::new(var, key))
+    }
+}
+
+impl KeyWrapper for LocalKey {
+    fn key_id(&self) -> String {
+        self.key_id.clone()
+    }
+
+    fn wrap(&self, data_key: &[u8]) -> Result<Vec<u8>, StoreError> {
+        let (mut nonce, ciphertext) = seal(&self.key, data_key, self.key_id.as_bytes())?;
+        nonce.extend(ciphertext);
+        Ok(nonce)
+    }
+
+    fn unwrap(&self, key_id: &str, wrapped_key: &[u8]) -> Result<Vec<u8>, StoreError> {
+        if key_id != self.key_id {
+            return Err(StoreError::Encryption(format!("unknown key {}", key_id)));
+        }
+        if wrapped_key.len() < NONCE_LEN {
+            return Err(StoreError::Encryption("truncated data key".to_string()));
+        }
+        let (nonce, ciphertext) = wrapped_key.split_at(NONCE_LEN);
+        open(&self.key, nonce, ciphertext, key_id.as_bytes())
+    }
+}
+
+type WrapFn = dyn Fn(&[u8]) -> Result<Vec<u8>, String> + Send + Sync;
+type UnwrapFn = dyn Fn(&str, &[u8]) -> Result<Vec<u8>, String> + Send + Sync;
+
+/// Master key of a KMS, wrapping and unwrapping through callbacks, e.g. the
+/// `Encrypt` and `Decrypt` calls of AWS KMS.
+pub struct KmsKey {
+    key_id: String,
+    wrap: Box<WrapFn>,
+    unwrap: Box<UnwrapFn>,
+}
+
+impl KmsKey {
+    pub fn new(
+        key_id: &str,
+        wrap: impl Fn(&[u8]) -> Result<Vec<u8>, String> + Send + Sync + 'static,
+        unwrap: impl Fn(&str, &[u8]) -> Result<Vec<u8>, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            wrap: Box::new(wrap),
+            unwrap: Box::new(unwrap),
+        }
+    }
+}
+
+impl KeyWrapper for KmsKey {
+    fn key_id(&self) -> String {
+        self.key_id.clone()
+    }
+
+    fn wrap(&self, data_key: &[u8]) -> Result<Vec<u8>, StoreError> {
+        (self.wrap)(data_key).map_err(StoreError::Encryption)
+    }
+
+    fn unwrap(&self, key_id: &str, wrapped_key: &[u8]) -> Result<Vec<u8>, StoreError> {
+        (self.unwrap)(key_id, wrapped_key).map_err(StoreError::Encryption)
+    }
+}
+
+/// Encrypted form of a store file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Envelope {
+    version: u8,
+    key_id: String,
+    /// base64 data key, wrapped by the master key
+    wrapped_key: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Envelope encryption of store files: every write is encrypted with a new
+/// data key, which is stored wrapped by the master key next to the data.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::encryption::{EnvelopeCipher, LocalKey, STORE_KEY_ENV};
+/// use x402_sdk::store::FileSettlementStore;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let cipher = EnvelopeCipher::new(Arc::new(LocalKey::from_env(STORE_KEY_ENV)?));
+/// let store = FileSettlementStore::open_encrypted("settlements.json", cipher)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Files that are not an envelope are refused, unless the cipher is built
+/// [`with_plaintext_migration`](Self::with_plaintext_migration) to open a
+/// store written before encryption was enabled.
+#[derive(Clone)]
+pub struct EnvelopeCipher {
+    wrapper: Arc<dyn KeyWrapper>,
+    migrating: bool,
+}
+
+impl std::fmt::Debug for EnvelopeCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvelopeCipher")
+            .field("key_id", &self.wrapper.key_id())
+            .field("migrating", &self.migrating)
+            .finish()
+    }
+}
+
+impl EnvelopeCipher {
+    pub fn new(wrapper: Arc<dyn KeyWrapper>) -> Self {
+        Self {
+            wrapper,
+            migrating: false,
+        }
+    }
+
+    /// read bytes that are not an envelope as plaintext, they are encrypted
+    /// on the next write. Only for migrating a store that was written before
+    /// encryption was enabled, anyone able to write the file can inject data
+    /// while it is set.
+    pub fn with_plaintext_migration(mut self) -> Self {
+        self.migrating = true;
+        self
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, StoreError> {
+        let data_key: [u8; KEY_LEN] = rand::random();
+        let key_id = self.wrapper.key_id();
+        let aad = envelope_aad(ENVELOPE_VERSION, &key_id);
+        let (nonce, ciphertext) = seal(&data_key, plaintext, &aad)?;
+        let envelope = Envelope {
+            version: ENVELOPE_VERSION,
+            key_id,
+            wrapped_key: STANDARD.encode(self.wrapper.wrap(&data_key)?),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        serde_json::to_vec(&envelope).map_err(|e| StoreError::Encryption(e.to_string()))
+    }
+
+    /// decrypt an envelope, bytes that are not one are refused unless the
+    /// cipher is migrating a plaintext store
+    pub fn decrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, StoreError> {
+        let envelope = match serde_json::from_slice::<Envelope>(bytes) {
+            Ok(envelope) => envelope,
+            Err(_) if self.migrating => return Ok(bytes.to_vec()),
+            Err(_) => {
+                return Err(StoreError::Encryption(
+                    "not an encrypted envelope".to_string(),
+                ));
+            }
+        };
+        if envelope.version != ENVELOPE_VERSION {
+            return Err(StoreError::Encryption(format!(
+                "unsupported envelope version {}",
+                envelope.version
+            )));
+        }
+        let decode = |value: &str| {
+            STANDARD
+                .decode(value)
+                .map_err(|e| StoreError::Encryption(e.to_string()))
+        };
+        let data_key = self
+            .wrapper
+            .unwrap(&envelope.key_id, &decode(&envelope.wrapped_key)?)?;
+        open(
+            &data_key,
+            &decode(&envelope.nonce)?,
+            &decode(&envelope.ciphertext)?,
+            &envelope_aad(envelope.version, &envelope.key_id),
+        )
+    }
+}
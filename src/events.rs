@@ -0,0 +1,189 @@
+/// Payment lifecycle events module.
+use crate::clock::{Clock, SystemClock};
+use crate::context::RequestContext;
+use crate::redaction::Redactor;
+use crate::resource::Resource;
+use crate::store::{OutboxEntry, OutboxStore, StoreError};
+use crate::types::{PaymentRequest, PaymentVerification};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Event emitted by the engine while handling an access request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentEvent {
+    pub timestamp: u64,
+    pub user_address: String,
+    pub resource: Resource,
+    /// the request method and its [`EVENT_HEADERS`](crate::context::EVENT_HEADERS)
+    /// only, see [`RequestContext::for_event`]
+    pub context: RequestContext,
+    pub kind: PaymentEventKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PaymentEventKind {
+    /// a 402 challenge was issued
+    ChallengeIssued { payment_request: PaymentRequest },
+    /// a payment was found on chain for the session and accepted
+    PaymentVerified {
+        nonce: String,
+        verification: PaymentVerification,
+    },
+    /// a payment was found on chain but refused, by an `after_verify` hook
+    /// or the compliance screen, no content is served for it
+    PaymentDenied { nonce: String, reason: String },
+    /// verification ran but did not confirm the payment
+    VerificationFailed { nonce: String, reason: String },
+    /// verification was skipped because the session is locked or backing
+    /// off, or the verification queue of the chain is full
+    VerificationThrottled { nonce: String, retry_after: u64 },
+    /// the session reached the failed attempt limit and is locked
+    SessionLockedOut { nonce: String, locked_until: u64 },
+    /// a coupon was redeemed for the session
+    CouponRedeemed { nonce: String, code: String },
+    /// a session was paid after the redemptions of its coupon ran out, its
+    /// discount was honoured without counting a redemption
+    CouponExhausted { nonce: String, code: String },
+    /// the request was paid from a budget account, amounts in the smallest
+    /// unit of the service currency
+    BudgetDrawn {
+        account: String,
+        amount: String,
+        balance: String,
+    },
+    /// a relay settlement was sent on chain, also on every fee replacement
+    SettlementSubmitted {
+        nonce: String,
+        transaction_hash: String,
+    },
+    /// a relay settlement was given up after its last attempt
+    SettlementFailed { nonce: String, reason: String },
+    /// a recorded payment reached the finality of its chain
+    PaymentFinalized { nonce: String },
+    /// a recorded payment failed or was dropped by a reorganization, it no
+    /// longer counts as revenue
+    PaymentReverted {
+        nonce: String,
+        transaction_hash: Option<String>,
+    },
+    /// access granted for the session was revoked by an operator
+    AccessRevoked { nonce: String, reason: String },
+    /// paid content was served for the session, after verification or on a
+    /// valid receipt
+    ContentServed { nonce: String },
+    /// a free access grant by an access policy
+    AccessGranted,
+    /// the request was refused by an access policy
+    AccessDenied { reason: String },
+}
+
+impl PaymentEventKind {
+    /// snake case name of the event, e.g. `payment_verified`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ChallengeIssued { .. } => "challenge_issued",
+            Self::PaymentVerified { .. } => "payment_verified",
+            Self::PaymentDenied { .. } => "payment_denied",
+            Self::VerificationFailed { .. } => "verification_failed",
+            Self::VerificationThrottled { .. } => "verification_throttled",
+            Self::SessionLockedOut { .. } => "session_locked_out",
+            Self::CouponRedeemed { .. } => "coupon_redeemed",
+            Self::CouponExhausted { .. } => "coupon_exhausted",
+            Self::BudgetDrawn { .. } => "budget_drawn",
+            Self::SettlementSubmitted { .. } => "settlement_submitted",
+            Self::SettlementFailed { .. } => "settlement_failed",
+            Self::PaymentFinalized { .. } => "payment_finalized",
+            Self::PaymentReverted { .. } => "payment_reverted",
+            Self::AccessRevoked { .. } => "access_revoked",
+            Self::ContentServed { .. } => "content_served",
+            Self::AccessGranted => "access_granted",
+            Self::AccessDenied { .. } => "access_denied",
+        }
+    }
+}
+
+/// Receives engine events, called synchronously on the request path so
+/// implementations should hand off slow work.
+pub trait EventListener: Send + Sync {
+    fn on_event(&self, event: &PaymentEvent);
+}
+
+/// Hands events to the outbox and the listeners, shared by the engine and
+/// its services so they all emit through the same sinks.
+#[derive(Clone)]
+pub struct EventEmitter {
+    listeners: Vec<Arc<dyn EventListener>>,
+    outbox_store: Option<Arc<dyn OutboxStore>>,
+    redactor: Redactor,
+    clock: Arc<dyn Clock>,
+}
+
+impl EventEmitter {
+    /// emitter redacting events with the given redactor before they are
+    /// persisted or seen by any listener
+    pub fn new(redactor: Redactor) -> Self {
+        Self {
+            listeners: Vec::new(),
+            outbox_store: None,
+            redactor,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_listener(mut self, listener: Arc<dyn EventListener>) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    /// persist every event to the outbox before the listeners are called
+    pub fn with_outbox_store(mut self, outbox_store: Arc<dyn OutboxStore>) -> Self {
+        self.outbox_store = Some(outbox_store);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Emits an event, the listeners are only called once the outbox took
+    /// it. Nothing is acted on before its event is persisted, the caller
+    /// fails with the store error and the request is retried.
+    pub async fn emit(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        context: &RequestContext,
+        kind: PaymentEventKind,
+    ) -> Result<(), StoreError> {
+        if self.listeners.is_empty() && self.outbox_store.is_none() {
+            return Ok(());
+        }
+        let now = self.clock.now();
+        let event = self.redactor.event(&PaymentEvent {
+            timestamp: now,
+            user_address: user_address.to_string(),
+            resource: resource.clone(),
+            context: context.for_event(),
+            kind,
+        });
+        if let Some(outbox_store) = &self.outbox_store {
+            outbox_store
+                .enqueue(OutboxEntry {
+                    id: Uuid::new_v4().to_string(),
+                    event: event.clone(),
+                    attempts: 0,
+                    next_attempt_at: now,
+                    last_error: None,
+                    failed: false,
+                    created_at: now,
+                })
+                .await?;
+        }
+        for listener in &self.listeners {
+            listener.on_event(&event);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+/// Payment history export module.
+use crate::store::{PaymentRecord, StoreError};
+use crate::types::Finality;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+#[derive(Debug)]
+pub enum ExportError {
+    NoStore,
+    StoreError(StoreError),
+    IoError(String),
+    EncodingError(String),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoStore => write!(f, "No payment store configured"),
+            Self::StoreError(err) => write!(f, "Store error: {}", err),
+            Self::IoError(msg) => write!(f, "IO error: {}", msg),
+            Self::EncodingError(msg) => write!(f, "Encoding error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<StoreError> for ExportError {
+    fn from(err: StoreError) -> Self {
+        Self::StoreError(err)
+    }
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// requires the `parquet` feature
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+/// column order of every export
+pub const EXPORT_COLUMNS: [&str; 14] = [
+    "period",
+    "recorded_at",
+    "payer",
+    "chain",
+    "chain_id",
+    "token",
+    "gross",
+    "fee",
+    "tx_hash",
+    "resource",
+    "finality",
+    "tax",
+    "tax_jurisdiction",
+    "invoice_id",
+];
+
+/// accounting period of a timestamp, as `YYYY-MM` in UTC
+pub fn period_of(timestamp: u64) -> String {
+    let (year, month, _) = civil_date(timestamp);
+    format!("{:04}-{:02}", year, month)
+}
+
+/// date and time of a timestamp, as `YYYY-MM-DD HH:MM:SS UTC`
+pub fn datetime_of(timestamp: u64) -> String {
+    let (year, month, day) = civil_date(timestamp);
+    let seconds = timestamp % 86_400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+/// year, month and day of a timestamp in UTC
+fn civil_date(timestamp: u64) -> (i64, i64, i64) {
+    // civil date from days since the epoch, Howard Hinnant's algorithm
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn row(record: &PaymentRecord) -> [String; 14] {
+    [
+        period_of(record.recorded_at),
+        record.recorded_at.to_string(),
+        record.payer.clone(),
+        record.chain.clone(),
+        record.chain_id.clone(),
+        record.token.clone(),
+        record.gross.clone(),
+        record.fee.clone(),
+        record.transaction_hash.clone().unwrap_or_default(),
+        record.resource.clone(),
+        record.finality.as_str().to_string(),
+        record
+            .tax
+            .as_ref()
+            .map_or_else(|| "0".to_string(), |tax| tax.amount.clone()),
+        record
+            .tax
+            .as_ref()
+            .map(|tax| tax.jurisdiction.clone())
+            .unwrap_or_default(),
+        record
+            .invoice
+            .as_ref()
+            .map(|invoice| invoice.invoice_id.clone())
+            .unwrap_or_default(),
+    ]
+}
+
+/// RFC 4180 CSV with a header row
+pub fn write_csv<W: Write>(records: &[PaymentRecord], mut out: W) -> Result<(), ExportError> {
+    write!(out, "{}\r\n", EXPORT_COLUMNS.join(","))?;
+    for record in records {
+        let fields: Vec<String> = row(record).iter().map(|field| csv_field(field)).collect();
+        write!(out, "{}\r\n", fields.join(","))?;
+    }
+    Ok(())
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Parquet file with one row group, `recorded_at` is an INT64 column and the
+/// other columns are UTF8, `tx_hash` is optional.
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: Write + Send>(
+    records: &[PaymentRecord],
+    out: W,
+) -> Result<(), ExportError> {
+    use parquet::column::writer::ColumnWriter;
+    use parquet::data_type::ByteArray;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::sync::Arc;
+
+    let encoding = |e: parquet::errors::ParquetError| ExportError::EncodingError(e.to_string());
+    let schema = parse_message_type(
+        "message payment {
+            REQUIRED BYTE_ARRAY period (UTF8);
+            REQUIRED INT64 recorded_at;
+            REQUIRED BYTE_ARRAY payer (UTF8);
+            REQUIRED BYTE_ARRAY chain (UTF8);
+            REQUIRED BYTE_ARRAY chain_id (UTF8);
+            REQUIRED BYTE_ARRAY token (UTF8);
+            REQUIRED BYTE_ARRAY gross (UTF8);
+            REQUIRED BYTE_ARRAY fee (UTF8);
+            OPTIONAL BYTE_ARRAY tx_hash (UTF8);
+            REQUIRED BYTE_ARRAY resource (UTF8);
+            REQUIRED BYTE_ARRAY finality (UTF8);
+            REQUIRED BYTE_ARRAY tax (UTF8);
+            REQUIRED BYTE_ARRAY tax_jurisdiction (UTF8);
+            REQUIRED BYTE_ARRAY invoice_id (UTF8);
+        }",
+    )
+    .map_err(encoding)?;
+    let properties = Arc::new(WriterProperties::builder().build());
+    let mut writer =
+        SerializedFileWriter::new(out, Arc::new(schema), properties).map_err(encoding)?;
+    let rows: Vec<[String; 14]> = records.iter().map(row).collect();
+    let mut row_group = writer.next_row_group().map_err(encoding)?;
+    let mut index = 0;
+    while let Some(mut column) = row_group.next_column().map_err(encoding)? {
+        match column.untyped() {
+            ColumnWriter::Int64ColumnWriter(typed) => {
+                let values: Vec<i64> = records.iter().map(|r| r.recorded_at as i64).collect();
+                typed.write_batch(&values, None, None).map_err(encoding)?;
+            }
+            ColumnWriter::ByteArrayColumnWriter(typed) if EXPORT_COLUMNS[index] == "tx_hash" => {
+                let values: Vec<ByteArray> = records
+                    .iter()
+                    .filter_map(|r| r.transaction_hash.as_deref())
+                    .map(ByteArray::from)
+                    .collect();
+                let levels: Vec<i16> = records
+                    .iter()
+                    .map(|r| i16::from(r.transaction_hash.is_some()))
+                    .collect();
+                typed
+                    .write_batch(&values, Some(&levels), None)
+                    .map_err(encoding)?;
+            }
+            ColumnWriter::ByteArrayColumnWriter(typed) => {
+                let values: Vec<ByteArray> = rows
+                    .iter()
+                    .map(|row| ByteArray::from(row[index].as_str()))
+                    .collect();
+                typed.write_batch(&values, None, None).map_err(encoding)?;
+            }
+            _ => {
+                return Err(ExportError::EncodingError(format!(
+                    "unexpected column type for {}",
+                    EXPORT_COLUMNS[index]
+                )));
+            }
+        }
+        column.close().map_err(encoding)?;
+        index += 1;
+    }
+    row_group.close().map_err(encoding)?;
+    writer.close().map_err(encoding)?;
+    Ok(())
+}
+
+/// encode records in the given format
+pub fn export(records: &[PaymentRecord], format: ExportFormat) -> Result<Vec<u8>, ExportError> {
+    let mut out = Vec::new();
+    match format {
+        ExportFormat::Csv => write_csv(records, &mut out)?,
+        #[cfg(feature = "parquet")]
+        ExportFormat::Parquet => write_parquet(records, &mut out)?,
+    }
+    Ok(out)
+}
+
+/// Revenue of one token on one chain, in the smallest unit of the token.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevenueLine {
+    pub chain: String,
+    pub chain_id: String,
+    pub token: String,
+    /// seen on chain, may still be reorganized away
+    pub provisional: u128,
+    pub finalized: u128,
+    /// reverted payments, excluded from the revenue
+    pub reverted: u128,
+}
+
+/// revenue per chain and token, split by finality, sorted by chain id and
+/// token
+pub fn revenue(records: &[PaymentRecord]) -> Result<Vec<RevenueLine>, ExportError> {
+    let mut lines: Vec<RevenueLine> = Vec::new();
+    for record in records {
+        let gross: u128 = record
+            .gross
+            .parse()
+            .map_err(|_| ExportError::EncodingError(format!("invalid amount: {}", record.gross)))?;
+        let index = match lines
+            .iter()
+            .position(|line| line.chain_id == record.chain_id && line.token == record.token)
+        {
+            Some(index) => index,
+            None => {
+                lines.push(RevenueLine {
+                    chain: record.chain.clone(),
+                    chain_id: record.chain_id.clone(),
+                    token: record.token.clone(),
+                    ..RevenueLine::default()
+                });
+                lines.len() - 1
+            }
+        };
+        let line = &mut lines[index];
+        let bucket = match record.finality {
+            Finality::Provisional => &mut line.provisional,
+            Finality::Finalized => &mut line.finalized,
+            Finality::Reverted => &mut line.reverted,
+        };
+        *bucket = bucket.saturating_add(gross);
+    }
+    lines.sort_by(|a, b| a.chain_id.cmp(&b.chain_id).then(a.token.cmp(&b.token)));
+    Ok(lines)
+}
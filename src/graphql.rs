@@ -0,0 +1,305 @@
+/// GraphQL integration module.
+use crate::context::RequestContext;
+use crate::core::X402;
+use crate::receipt::RECEIPT_HEADER;
+use crate::types::VerificationResult;
+use async_graphql::{
+    Context, Error, ErrorExtensionValues, ErrorExtensions, Executor, Guard, Request, Response,
+    ServerError, Value,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// `code` error extension of a field or operation waiting for a payment
+pub const PAYMENT_REQUIRED_CODE: &str = "PAYMENT_REQUIRED";
+/// error extension carrying the x402 challenge
+pub const CHALLENGE_EXTENSION: &str = "x402";
+/// response extension carrying the receipt of a paid operation
+pub const RECEIPT_EXTENSION: &str = "x402Receipt";
+
+/// header carrying the payer address, the same as the authorization server
+const PAYER_HEADER: &str = "X-Payer-Address";
+const NONCE_HEADER: &str = "X-Payment-Nonce";
+const COUPON_HEADER: &str = "X-Payment-Coupon";
+
+/// Payment credentials of a GraphQL request, added to the request data by
+/// the HTTP integration.
+#[derive(Debug, Clone, Default)]
+pub struct GraphqlPayment {
+    pub payer: String,
+    /// nonce of the paid challenge
+    pub nonce: Option<String>,
+    pub coupon: Option<String>,
+}
+
+impl GraphqlPayment {
+    pub fn new(payer: &str) -> Self {
+        Self {
+            payer: payer.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_nonce(mut self, nonce: &str) -> Self {
+        self.nonce = Some(nonce.to_string());
+        self
+    }
+
+    pub fn with_coupon(mut self, coupon: &str) -> Self {
+        self.coupon = Some(coupon.to_string());
+        self
+    }
+
+    /// credentials from the `X-Payer-Address`, `X-Payment-Nonce` and
+    /// `X-Payment-Coupon` headers, `None` without a payer
+    pub fn from_headers<'a>(header: impl Fn(&str) -> Option<&'a str>) -> Option<Self> {
+        Some(Self {
+            payer: header(PAYER_HEADER)?.to_string(),
+            nonce: header(NONCE_HEADER).map(str::to_string),
+            coupon: header(COUPON_HEADER).map(str::to_string),
+        })
+    }
+}
+
+/// error extensions of a denied request, the challenge is the protocol
+/// response of a 402
+fn payment_required_extensions(result: &VerificationResult) -> ErrorExtensionValues {
+    let mut extensions = ErrorExtensionValues::default();
+    extensions.set("code", PAYMENT_REQUIRED_CODE);
+    extensions.set("status", i32::from(result.http_status));
+    if let Some(retry_after) = result.retry_after {
+        extensions.set("retryAfter", retry_after);
+    }
+    if let Some(challenge) = result
+        .x402_response
+        .as_ref()
+        .and_then(|response| Value::from_json(serde_json::json!(response)).ok())
+    {
+        extensions.set(CHALLENGE_EXTENSION, challenge);
+    }
+    extensions
+}
+
+fn payment_error(message: String, result: &VerificationResult) -> Error {
+    let mut error = Error::new(message);
+    error.extensions = Some(payment_required_extensions(result));
+    error
+}
+
+/// Runs the x402 flow for a resource, the verification result when the
+/// payment is verified or an error carrying the challenge.
+async fn authorize(
+    engine: &X402,
+    payment: Option<&GraphqlPayment>,
+    resource_path: &str,
+    price: Option<&str>,
+) -> Result<VerificationResult, Error> {
+    let Some(payment) = payment else {
+        return Err(Error::new(format!("{} is required", PAYER_HEADER))
+            .extend_with(|_, e| e.set("code", PAYMENT_REQUIRED_CODE)));
+    };
+    let result = engine
+        .handle_access_request_with_coupon(
+            &payment.payer,
+            resource_path,
+            payment.nonce.as_deref(),
+            price,
+            payment.coupon.as_deref(),
+            &RequestContext::default(),
+        )
+        .await
+        .map_err(|e| Error::new(e.to_string()))?;
+    if !result.should_serve_content {
+        return Err(payment_error(
+            format!("payment required, status {}", result.http_status),
+            &result,
+        ));
+    }
+    Ok(result)
+}
+
+/// Field guard charging for every resolution of a field.
+///
+/// The engine is taken from the `Arc<X402>` of the schema data and the
+/// payer from the [`GraphqlPayment`] of the request data. A field resolved
+/// without a verified payment fails with a `PAYMENT_REQUIRED` error whose
+/// `x402` extension is the challenge, the client pays it and repeats the
+/// query with the challenge nonce. The receipt is returned in the
+/// `X-Payment-Receipt` header.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::graphql::PaymentGuard;
+///
+/// struct Query;
+///
+/// #[Object]
+/// impl Query {
+///     async fn headline(&self) -> &str {
+///         "free"
+///     }
+///
+///     #[graphql(guard = "PaymentGuard::field(\"report\").with_price(\"1000\")")]
+///     async fn report(&self) -> &str {
+///         "paid"
+///     }
+/// }
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let engine = Arc::new(X402::from_default_config()?);
+/// let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+///     .data(engine)
+///     .finish();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PaymentGuard {
+    pub field: String,
+    /// fixed price of a resolution, the pricing provider of the engine
+    /// decides when unset
+    pub price: Option<String>,
+}
+
+impl PaymentGuard {
+    pub fn field(name: &str) -> Self {
+        Self {
+            field: name.to_string(),
+            price: None,
+        }
+    }
+
+    pub fn with_price(mut self, amount: &str) -> Self {
+        self.price = Some(amount.to_string());
+        self
+    }
+
+    /// resource path paid for when resolving the field
+    pub fn resource_path(&self) -> String {
+        format!("/graphql/fields/{}", self.field)
+    }
+}
+
+impl Guard for PaymentGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        let engine = ctx.data::<Arc<X402>>()?;
+        let result = authorize(
+            engine,
+            ctx.data_opt::<GraphqlPayment>(),
+            &self.resource_path(),
+            self.price.as_deref(),
+        )
+        .await?;
+        if let Some(token) = result.receipt.and_then(|receipt| receipt.to_token().ok()) {
+            ctx.append_http_header(RECEIPT_HEADER, token);
+        }
+        Ok(())
+    }
+}
+
+/// Prices whole operations by name, charged once before the operation is
+/// executed whatever fields it selects.
+///
+/// Operations without a price are executed as they are, so field guards
+/// still apply to them.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use async_graphql::{EmptyMutation, EmptySubscription, Object, Request, Schema};
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::graphql::{GraphqlPayment, OperationPricing};
+///
+/// struct Query;
+///
+/// #[Object]
+/// impl Query {
+///     async fn report(&self) -> &str {
+///         "paid"
+///     }
+/// }
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let engine = Arc::new(X402::from_default_config()?);
+/// let schema = Schema::build(Query, EmptyMutation, EmptySubscription).finish();
+/// let pricing = OperationPricing::new(engine).with_price("Report", "5000");
+/// let payment = GraphqlPayment::new("0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5");
+/// let request = Request::new("query Report { report }").operation_name("Report");
+/// let response = pricing.execute(&schema, request, Some(&payment)).await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct OperationPricing {
+    engine: Arc<X402>,
+    prices: HashMap<String, String>,
+}
+
+impl OperationPricing {
+    pub fn new(engine: Arc<X402>) -> Self {
+        Self {
+            engine,
+            prices: HashMap::new(),
+        }
+    }
+
+    pub fn with_price(mut self, operation: &str, amount: &str) -> Self {
+        self.prices
+            .insert(operation.to_string(), amount.to_string());
+        self
+    }
+
+    pub fn price(&self, operation: &str) -> Option<&str> {
+        self.prices.get(operation).map(String::as_str)
+    }
+
+    /// resource path paid for when executing the operation
+    pub fn resource_path(operation: &str) -> String {
+        format!("/graphql/operations/{}", operation)
+    }
+
+    /// Executes the request once its operation is paid for. The engine and
+    /// the payment are added to the request data for the field guards.
+    pub async fn execute<E: Executor>(
+        &self,
+        executor: &E,
+        mut request: Request,
+        payment: Option<&GraphqlPayment>,
+    ) -> Response {
+        request = request.data(self.engine.clone());
+        if let Some(payment) = payment {
+            request = request.data(payment.clone());
+        }
+        let price = request
+            .operation_name
+            .as_deref()
+            .and_then(|operation| self.price(operation));
+        let Some(price) = price else {
+            return executor.execute(request).await;
+        };
+        let resource_path =
+            Self::resource_path(request.operation_name.as_deref().unwrap_or_default());
+        let result = match authorize(&self.engine, payment, &resource_path, Some(price)).await {
+            Ok(result) => result,
+            Err(error) => {
+                let mut server_error = ServerError::new(error.message, None);
+                server_error.extensions = error.extensions;
+                return Response::from_errors(vec![server_error]);
+            }
+        };
+        let mut response = executor.execute(request).await;
+        if let Some(receipt) = result
+            .receipt
+            .and_then(|receipt| Value::from_json(serde_json::json!(receipt)).ok())
+        {
+            response
+                .extensions
+                .insert(RECEIPT_EXTENSION.to_string(), receipt);
+        }
+        response
+    }
+}
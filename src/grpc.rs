@@ -0,0 +1,264 @@
+/// gRPC integration module.
+use crate::context::RequestContext;
+use crate::core::X402;
+use crate::headers::{HeaderError, decode_payment_required, encode_payment_required};
+use crate::types::{VerificationResult, X402ProtocolResponse};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::{Code, Status};
+
+/// metadata carrying the payer address of a call
+pub const PAYER_METADATA: &str = "x-payer-address";
+/// metadata carrying the nonce of the paid challenge
+pub const NONCE_METADATA: &str = "x-payment-nonce";
+/// metadata carrying an optional coupon code
+pub const COUPON_METADATA: &str = "x-payment-coupon";
+/// metadata of a refused call carrying the challenge as base64 encoded
+/// JSON, the same encoding as the `X-Payment-Required` header
+pub const CHALLENGE_METADATA: &str = "x-payment-required";
+/// metadata of a paid call carrying the receipt
+pub const RECEIPT_METADATA: &str = "x-payment-receipt";
+/// metadata carrying the seconds to wait before retrying
+pub const RETRY_AFTER_METADATA: &str = "retry-after";
+
+/// Add payment credentials to the metadata of an outgoing call.
+pub fn attach_payment(
+    metadata: &mut MetadataMap,
+    payer: &str,
+    nonce: Option<&str>,
+) -> Result<(), HeaderError> {
+    let value = |value: &str| {
+        MetadataValue::try_from(value).map_err(|e| HeaderError::Malformed(e.to_string()))
+    };
+    metadata.insert(PAYER_METADATA, value(payer)?);
+    if let Some(nonce) = nonce {
+        metadata.insert(NONCE_METADATA, value(nonce)?);
+    }
+    Ok(())
+}
+
+/// Decode the challenge of a call refused with `RESOURCE_EXHAUSTED`.
+pub fn decode_challenge(status: &Status) -> Result<X402ProtocolResponse, HeaderError> {
+    let value = status
+        .metadata()
+        .get(CHALLENGE_METADATA)
+        .ok_or_else(|| HeaderError::MissingHeader(CHALLENGE_METADATA.to_string()))?;
+    let value = value
+        .to_str()
+        .map_err(|e| HeaderError::Malformed(e.to_string()))?;
+    decode_payment_required(value)
+}
+
+/// Enforces x402 payments on the methods of a gRPC server.
+///
+/// The resource of a call is its method path, e.g.
+/// `/weather.Forecast/Daily`, priced per method or by the pricing
+/// provider of the engine with `POST` patterns. A call without a verified
+/// payment fails with `RESOURCE_EXHAUSTED` and the challenge in the
+/// `x-payment-required` metadata, the client pays it and repeats the call
+/// with the challenge nonce in the `x-payment-nonce` metadata.
+///
+/// Tonic interceptors are synchronous, so the checks run in a tower layer
+/// around the server, see [`PaymentInterceptor::layer`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::grpc::PaymentInterceptor;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let engine = Arc::new(X402::from_default_config()?);
+/// let layer = PaymentInterceptor::new(engine)
+///     .with_method_price("/weather.Forecast/Daily", "1000")
+///     .with_free_method("/grpc.health.v1.Health/Check")
+///     .layer();
+/// // tonic::transport::Server::builder().layer(layer).add_service(...)
+/// # Ok(())
+/// # }
+/// ```
+pub struct PaymentInterceptor {
+    engine: Arc<X402>,
+    prices: HashMap<String, String>,
+    free_methods: HashSet<String>,
+}
+
+impl PaymentInterceptor {
+    pub fn new(engine: Arc<X402>) -> Self {
+        Self {
+            engine,
+            prices: HashMap::new(),
+            free_methods: HashSet::new(),
+        }
+    }
+
+    /// fixed price of a call to `/package.Service/Method`
+    pub fn with_method_price(mut self, method: &str, amount: &str) -> Self {
+        self.prices.insert(method.to_string(), amount.to_string());
+        self
+    }
+
+    /// serve a method without payment, e.g. health checks or reflection
+    pub fn with_free_method(mut self, method: &str) -> Self {
+        self.free_methods.insert(method.to_string());
+        self
+    }
+
+    pub fn method_price(&self, method: &str) -> Option<&str> {
+        self.prices.get(method).map(String::as_str)
+    }
+
+    pub fn layer(self) -> PaymentLayer {
+        PaymentLayer {
+            interceptor: Arc::new(self),
+        }
+    }
+
+    /// Decide on one call, the metadata to add to the response when it may
+    /// proceed.
+    pub async fn authorize(
+        &self,
+        method: &str,
+        metadata: &MetadataMap,
+    ) -> Result<MetadataMap, Status> {
+        let mut response_metadata = MetadataMap::new();
+        if self.free_methods.contains(method) {
+            return Ok(response_metadata);
+        }
+        let value = |key: &str| metadata.get(key).and_then(|value| value.to_str().ok());
+        let Some(payer) = value(PAYER_METADATA) else {
+            return Err(Status::unauthenticated(format!(
+                "{} metadata is required",
+                PAYER_METADATA
+            )));
+        };
+        let mut context = RequestContext::new("POST");
+        for (name, value) in metadata.clone().into_headers().iter() {
+            if let Ok(value) = value.to_str() {
+                context = context.with_header(name.as_str(), value);
+            }
+        }
+        let result = self
+            .engine
+            .handle_access_request_with_coupon(
+                payer,
+                method,
+                value(NONCE_METADATA),
+                self.method_price(method),
+                value(COUPON_METADATA),
+                &context,
+            )
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        if result.should_serve_content {
+            if let Some(token) = result
+                .receipt
+                .as_ref()
+                .and_then(|receipt| receipt.to_token().ok())
+                .and_then(|token| MetadataValue::try_from(token).ok())
+            {
+                response_metadata.insert(RECEIPT_METADATA, token);
+            }
+            return Ok(response_metadata);
+        }
+        Err(denied_status(&result))
+    }
+}
+
+/// Maps a refused request onto a gRPC status: 402 and 429 onto
+/// `RESOURCE_EXHAUSTED`, a pending verification onto `UNAVAILABLE`.
+fn denied_status(result: &VerificationResult) -> Status {
+    let (code, message) = match result.http_status {
+        402 => (Code::ResourceExhausted, "payment required"),
+        429 => (Code::ResourceExhausted, "too many requests"),
+        202 | 503 => (Code::Unavailable, "payment verification pending"),
+        403 => (Code::PermissionDenied, "access denied"),
+        400 => (Code::InvalidArgument, "invalid payment request"),
+        _ => (Code::Internal, "payment check failed"),
+    };
+    let mut metadata = MetadataMap::new();
+    if let Some(retry_after) = result.retry_after {
+        metadata.insert(RETRY_AFTER_METADATA, MetadataValue::from(retry_after));
+    }
+    if let Some(challenge) = result
+        .x402_response
+        .as_ref()
+        .and_then(|response| encode_payment_required(response).ok())
+        .and_then(|challenge| MetadataValue::try_from(challenge).ok())
+    {
+        metadata.insert(CHALLENGE_METADATA, challenge);
+    }
+    Status::with_metadata(
+        code,
+        format!("{}, status {}", message, result.http_status),
+        metadata,
+    )
+}
+
+/// Tower layer running a [`PaymentInterceptor`] in front of a tonic server.
+#[derive(Clone)]
+pub struct PaymentLayer {
+    interceptor: Arc<PaymentInterceptor>,
+}
+
+impl<S> tower::Layer<S> for PaymentLayer {
+    type Service = PaymentService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PaymentService {
+            inner,
+            interceptor: self.interceptor.clone(),
+        }
+    }
+}
+
+/// Service of a [`PaymentLayer`], refused calls answer with the status
+/// without reaching the inner service.
+#[derive(Clone)]
+pub struct PaymentService<S> {
+    inner: S,
+    interceptor: Arc<PaymentInterceptor>,
+}
+
+impl<S, B> tower::Service<http::Request<B>> for PaymentService<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<tonic::body::BoxBody>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        // the ready inner service serves this call, its clone the next one
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let interceptor = self.interceptor.clone();
+        Box::pin(async move {
+            let metadata = MetadataMap::from_headers(request.headers().clone());
+            match interceptor.authorize(request.uri().path(), &metadata).await {
+                Ok(response_metadata) => {
+                    let mut response = inner.call(request).await?;
+                    response
+                        .headers_mut()
+                        .extend(response_metadata.into_headers());
+                    Ok(response)
+                }
+                Err(status) => Ok(status.into_http()),
+            }
+        })
+    }
+}
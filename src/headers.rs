@@ -0,0 +1,301 @@
+/// HTTP headers module for 402 challenges.
+use crate::limits::{PayloadError, PayloadLimits};
+use crate::types::X402ProtocolResponse;
+use crate::wire::FieldCasing;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::collections::BTreeMap;
+
+pub const PAYMENT_REQUIRED_HEADER: &str = "X-Payment-Required";
+pub const WWW_AUTHENTICATE_HEADER: &str = "WWW-Authenticate";
+pub const AUTH_SCHEME: &str = "X402";
+
+#[derive(Debug)]
+pub enum HeaderError {
+    MissingHeader(String),
+    InvalidBase64(String),
+    InvalidJson(String),
+    InvalidScheme(String),
+    MissingParameter(String),
+    Malformed(String),
+    /// the challenge breaks the [`PayloadLimits`] of the parser
+    Rejected(PayloadError),
+}
+
+impl std::fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingHeader(name) => write!(f, "Missing header: {}", name),
+            Self::InvalidBase64(msg) => write!(f, "Invalid base64: {}", msg),
+            Self::InvalidJson(msg) => write!(f, "Invalid JSON: {}", msg),
+            Self::InvalidScheme(scheme) => write!(f, "Invalid auth scheme: {}", scheme),
+            Self::MissingParameter(name) => write!(f, "Missing parameter: {}", name),
+            Self::Malformed(msg) => write!(f, "Malformed header: {}", msg),
+            Self::Rejected(err) => write!(f, "Rejected header: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+/// Renders a 402 challenge as response headers.
+///
+/// `X-Payment-Required` always carries the full challenge as base64 encoded
+/// JSON, the optional `WWW-Authenticate: X402 ...` header repeats the key
+/// fields as auth-params for clients that only understand plain headers.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::headers::{ChallengeHeaders, parse_challenge_headers};
+/// # fn example(response: &x402_sdk::types::X402ProtocolResponse) -> Result<(), Box<dyn std::error::Error>> {
+/// let headers = ChallengeHeaders::new()
+///     .with_www_authenticate("premium-api")
+///     .render(response)?;
+/// // client side
+/// let challenge = parse_challenge_headers(headers.iter().map(|(k, v)| (k.as_str(), v.as_str())))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChallengeHeaders {
+    realm: Option<String>,
+    casing: FieldCasing,
+}
+
+impl ChallengeHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// also emit a `WWW-Authenticate` header for the given realm
+    pub fn with_www_authenticate(mut self, realm: &str) -> Self {
+        self.realm = Some(realm.to_string());
+        self
+    }
+
+    /// field names of the `X-Payment-Required` JSON, snake_case by default
+    pub fn with_casing(mut self, casing: FieldCasing) -> Self {
+        self.casing = casing;
+        self
+    }
+
+    pub fn render(
+        &self,
+        response: &X402ProtocolResponse,
+    ) -> Result<Vec<(String, String)>, HeaderError> {
+        let mut headers = vec![(
+            PAYMENT_REQUIRED_HEADER.to_string(),
+            encode_payment_required_in(response, self.casing)?,
+        )];
+        if let Some(realm) = &self.realm {
+            headers.push((
+                WWW_AUTHENTICATE_HEADER.to_string(),
+                WwwAuthenticate::from_response(realm, response).to_string(),
+            ));
+        }
+        Ok(headers)
+    }
+}
+
+/// encode the challenge as the `X-Payment-Required` header value
+pub fn encode_payment_required(response: &X402ProtocolResponse) -> Result<String, HeaderError> {
+    encode_payment_required_in(response, FieldCasing::Snake)
+}
+
+/// [`encode_payment_required`] with the fields named in `casing`
+pub fn encode_payment_required_in(
+    response: &X402ProtocolResponse,
+    casing: FieldCasing,
+) -> Result<String, HeaderError> {
+    let value = casing
+        .to_value(response)
+        .map_err(|e| HeaderError::InvalidJson(e.to_string()))?;
+    let json = serde_json::to_vec(&value).map_err(|e| HeaderError::InvalidJson(e.to_string()))?;
+    Ok(STANDARD.encode(json))
+}
+
+/// decode an `X-Payment-Required` header value
+pub fn decode_payment_required(value: &str) -> Result<X402ProtocolResponse, HeaderError> {
+    let json = STANDARD
+        .decode(value.trim())
+        .map_err(|e| HeaderError::InvalidBase64(e.to_string()))?;
+    serde_json::from_slice(&json).map_err(|e| HeaderError::InvalidJson(e.to_string()))
+}
+
+/// decode an `X-Payment-Required` header value from an untrusted server,
+/// within the limits and for an accepted chain and scheme
+pub fn decode_payment_required_with(
+    value: &str,
+    limits: &PayloadLimits,
+) -> Result<X402ProtocolResponse, HeaderError> {
+    let response: X402ProtocolResponse =
+        limits.decode(&STANDARD, value).map_err(|err| match err {
+            PayloadError::InvalidBase64(msg) => HeaderError::InvalidBase64(msg),
+            PayloadError::InvalidJson(msg) => HeaderError::InvalidJson(msg),
+            err => HeaderError::Rejected(err),
+        })?;
+    limits
+        .check_request(&response.payment_required)
+        .map_err(HeaderError::Rejected)?;
+    Ok(response)
+}
+
+/// find and decode the challenge from a list of response headers, header
+/// names are matched case-insensitively
+pub fn parse_challenge_headers<'a, I>(headers: I) -> Result<X402ProtocolResponse, HeaderError>
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    headers
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(PAYMENT_REQUIRED_HEADER))
+        .ok_or_else(|| HeaderError::MissingHeader(PAYMENT_REQUIRED_HEADER.to_string()))
+        .and_then(|(_, value)| decode_payment_required(value))
+}
+
+/// `WWW-Authenticate: X402 ...` challenge parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WwwAuthenticate {
+    pub realm: String,
+    pub nonce: String,
+    pub amount: String,
+    pub recipient: String,
+    pub chain_id: String,
+    pub expires_at: Option<u64>,
+    pub verification_url: Option<String>,
+}
+
+impl WwwAuthenticate {
+    pub fn from_response(realm: &str, response: &X402ProtocolResponse) -> Self {
+        let request = &response.payment_required;
+        Self {
+            realm: realm.to_string(),
+            nonce: request.nonce.clone(),
+            amount: request.amount.clone(),
+            recipient: request.recipient.clone(),
+            chain_id: request.chain.chain_id.clone(),
+            expires_at: request.expires_at,
+            verification_url: response.verification_url.clone(),
+        }
+    }
+
+    /// parse a `WWW-Authenticate` header value using the X402 scheme
+    pub fn parse(value: &str) -> Result<Self, HeaderError> {
+        let value = value.trim();
+        let (scheme, params) = value.split_once(' ').unwrap_or((value, ""));
+        if !scheme.eq_ignore_ascii_case(AUTH_SCHEME) {
+            return Err(HeaderError::InvalidScheme(scheme.to_string()));
+        }
+        let mut params = parse_auth_params(params)?;
+        let mut take = |name: &str| {
+            params
+                .remove(name)
+                .ok_or_else(|| HeaderError::MissingParameter(name.to_string()))
+        };
+        Ok(Self {
+            realm: take("realm")?,
+            nonce: take("nonce")?,
+            amount: take("amount")?,
+            recipient: take("recipient")?,
+            chain_id: take("chain")?,
+            expires_at: take("expires")
+                .ok()
+                .map(|v| {
+                    v.parse()
+                        .map_err(|_| HeaderError::Malformed(format!("invalid expires: {}", v)))
+                })
+                .transpose()?,
+            verification_url: take("verification_url").ok(),
+        })
+    }
+}
+
+impl std::fmt::Display for WwwAuthenticate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} realm={}, nonce={}, amount={}, recipient={}, chain={}",
+            AUTH_SCHEME,
+            quote(&self.realm),
+            quote(&self.nonce),
+            quote(&self.amount),
+            quote(&self.recipient),
+            quote(&self.chain_id)
+        )?;
+        if let Some(expires_at) = self.expires_at {
+            write!(f, ", expires=\"{}\"", expires_at)?;
+        }
+        if let Some(url) = &self.verification_url {
+            write!(f, ", verification_url={}", quote(url))?;
+        }
+        Ok(())
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// parse comma separated `name=value` auth-params, values may be tokens or
+/// quoted strings with backslash escapes
+fn parse_auth_params(input: &str) -> Result<BTreeMap<String, String>, HeaderError> {
+    let mut params = BTreeMap::new();
+    let mut chars = input.chars().peekable();
+    loop {
+        while matches!(chars.peek(), Some(c) if c.is_whitespace() || *c == ',') {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            return Ok(params);
+        }
+        let mut name = String::new();
+        while let Some(c) = chars.peek().copied() {
+            if c == '=' || c.is_whitespace() {
+                break;
+            }
+            name.push(c);
+            chars.next();
+        }
+        while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.next() != Some('=') {
+            return Err(HeaderError::Malformed(format!(
+                "missing value for {}",
+                name
+            )));
+        }
+        while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+            chars.next();
+        }
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('\\') => match chars.next() {
+                        Some(c) => value.push(c),
+                        None => return Err(HeaderError::Malformed("dangling escape".to_string())),
+                    },
+                    Some('"') => break,
+                    Some(c) => value.push(c),
+                    None => {
+                        return Err(HeaderError::Malformed(
+                            "unterminated quoted string".to_string(),
+                        ));
+                    }
+                }
+            }
+        } else {
+            while let Some(c) = chars.peek().copied() {
+                if c == ',' || c.is_whitespace() {
+                    break;
+                }
+                value.push(c);
+                chars.next();
+            }
+        }
+        params.insert(name.to_ascii_lowercase(), value);
+    }
+}
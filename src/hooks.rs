@@ -0,0 +1,96 @@
+/// Engine hooks module.
+use crate::context::RequestContext;
+use crate::resource::Resource;
+use crate::types::{PaymentRequest, PaymentVerification, X402ProtocolResponse};
+use async_trait::async_trait;
+
+/// Outcome of a hook that may stop the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookDecision {
+    Continue,
+    /// refuse the request with status 403 and the given reason
+    Veto(String),
+}
+
+/// Callbacks around the challenge and verification steps of
+/// `handle_access_request`, every method defaults to doing nothing.
+///
+/// Hooks run in registration order, the first veto stops the request.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_trait::async_trait;
+/// use x402_sdk::context::RequestContext;
+/// use x402_sdk::hooks::{EngineHooks, HookDecision};
+/// use x402_sdk::resource::Resource;
+/// use x402_sdk::types::PaymentRequest;
+///
+/// struct TagTenant;
+///
+/// #[async_trait]
+/// impl EngineHooks for TagTenant {
+///     async fn before_challenge(
+///         &self,
+///         _user_address: &str,
+///         _resource: &Resource,
+///         context: &RequestContext,
+///         payment_request: &mut PaymentRequest,
+///     ) -> HookDecision {
+///         if let Some(tenant) = context.header("x-tenant") {
+///             payment_request.metadata = payment_request
+///                 .metadata
+///                 .clone()
+///                 .with_extension("tenant", serde_json::json!(tenant));
+///         }
+///         HookDecision::Continue
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait EngineHooks: Send + Sync {
+    /// called before a challenge is signed and its session stored, changes
+    /// to the request are what the client is asked to pay
+    async fn before_challenge(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+        _payment_request: &mut PaymentRequest,
+    ) -> HookDecision {
+        HookDecision::Continue
+    }
+
+    /// called once a challenge was issued
+    async fn after_challenge(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+        _response: &X402ProtocolResponse,
+    ) {
+    }
+
+    /// called before the payment of a session is looked up
+    async fn before_verify(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+        _payment_nonce: &str,
+    ) -> HookDecision {
+        HookDecision::Continue
+    }
+
+    /// called with a paid verification before the payment is recorded, a
+    /// veto refuses the content although the payment was made
+    async fn after_verify(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+        _verification: &PaymentVerification,
+    ) -> HookDecision {
+        HookDecision::Continue
+    }
+}
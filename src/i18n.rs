@@ -0,0 +1,217 @@
+/// Message catalog module.
+use crate::context::RequestContext;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Locale of the payer, e.g. `de` or `pt-BR`, attached to the request
+/// context by the integration. Without it the `Accept-Language` header is
+/// used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale(pub String);
+
+/// User-facing strings of challenges, denials and receipt pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKey {
+    /// `{amount}` and `{resource}`
+    PaymentRequired,
+    /// default challenge description, `{resource}`
+    AccessTo,
+    AccessDenied,
+    /// `{retry_after}`
+    TooManyAttempts,
+    ServiceUnavailable,
+    /// `{retry_after}`
+    Verifying,
+    ReceiptTitle,
+    Receipt,
+    Date,
+    Merchant,
+    PaidTo,
+    PaidBy,
+    Resource,
+    Chain,
+    Amount,
+    Transaction,
+    Explorer,
+    Invoice,
+    Subtotal,
+    Discount,
+    Total,
+}
+
+impl MessageKey {
+    /// built-in English text
+    pub fn default_text(&self) -> &'static str {
+        match self {
+            Self::PaymentRequired => "Payment of {amount} is required to access {resource}",
+            Self::AccessTo => "Access to: {resource}",
+            Self::AccessDenied => "Access denied",
+            Self::TooManyAttempts => "Too many payment attempts, retry in {retry_after} seconds",
+            Self::ServiceUnavailable => "Payment verification is temporarily unavailable",
+            Self::Verifying => "Payment verification is pending, retry in {retry_after} seconds",
+            Self::ReceiptTitle => "Payment receipt",
+            Self::Receipt => "Receipt",
+            Self::Date => "Date",
+            Self::Merchant => "Merchant",
+            Self::PaidTo => "Paid to",
+            Self::PaidBy => "Paid by",
+            Self::Resource => "Resource",
+            Self::Chain => "Chain",
+            Self::Amount => "Amount",
+            Self::Transaction => "Transaction",
+            Self::Explorer => "Explorer",
+            Self::Invoice => "Invoice",
+            Self::Subtotal => "Subtotal",
+            Self::Discount => "Discount",
+            Self::Total => "Total",
+        }
+    }
+}
+
+/// Translations of the user-facing strings per locale, falling back from
+/// `pt-BR` to `pt`, then to the default locale and the built-in English
+/// text. `{placeholder}` fields are filled in by [`MessageCatalog::message`].
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::i18n::{MessageCatalog, MessageKey};
+///
+/// let catalog = MessageCatalog::new()
+///     .with_message("de", MessageKey::AccessDenied, "Zugriff verweigert")
+///     .with_messages_json(
+///         "fr",
+///         r#"{"access_denied": "Accès refusé", "total": "Total TTC"}"#,
+///     )
+///     .unwrap();
+/// assert_eq!(
+///     catalog.message(Some("de-AT"), MessageKey::AccessDenied, &[]),
+///     "Zugriff verweigert"
+/// );
+/// assert_eq!(
+///     catalog.message(Some("ja"), MessageKey::AccessTo, &[("resource", "/report")]),
+///     "Access to: /report"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageCatalog {
+    default_locale: String,
+    messages: HashMap<String, HashMap<MessageKey, String>>,
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageCatalog {
+    pub fn new() -> Self {
+        Self {
+            default_locale: "en".to_string(),
+            messages: HashMap::new(),
+        }
+    }
+
+    /// locale used when the request names none the catalog has, `en` by
+    /// default
+    pub fn with_default_locale(mut self, locale: &str) -> Self {
+        self.default_locale = locale.to_lowercase();
+        self
+    }
+
+    pub fn with_message(mut self, locale: &str, key: MessageKey, text: &str) -> Self {
+        self.messages
+            .entry(locale.to_lowercase())
+            .or_default()
+            .insert(key, text.to_string());
+        self
+    }
+
+    /// load a locale from a JSON object of snake case keys to texts
+    pub fn with_messages_json(
+        mut self,
+        locale: &str,
+        json: &str,
+    ) -> Result<Self, serde_json::Error> {
+        let messages: HashMap<MessageKey, String> = serde_json::from_str(json)?;
+        self.messages
+            .entry(locale.to_lowercase())
+            .or_default()
+            .extend(messages);
+        Ok(self)
+    }
+
+    /// `locale` then its language
+    fn candidates(locale: &str) -> Vec<String> {
+        let locale = locale.trim().to_lowercase();
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        match language == locale {
+            true => vec![locale],
+            false => vec![locale.clone(), language.to_string()],
+        }
+    }
+
+    /// the catalog locale serving `locale`, the locale itself or its
+    /// language
+    fn known(&self, locale: &str) -> Option<String> {
+        Self::candidates(locale)
+            .into_iter()
+            .find(|locale| self.messages.contains_key(locale))
+    }
+
+    /// locale of a request, the [`Locale`] extension or the most preferred
+    /// `Accept-Language` entry the catalog has, else the default locale
+    pub fn locale_for(&self, context: &RequestContext) -> String {
+        if let Some(locale) = context.extensions.get::<Locale>() {
+            return self
+                .known(&locale.0)
+                .unwrap_or_else(|| self.default_locale.clone());
+        }
+        let mut ranges: Vec<(&str, f32)> = context
+            .header("Accept-Language")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // stable, equal weights keep the header order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges
+            .iter()
+            .find_map(|(tag, _)| self.known(tag))
+            .unwrap_or_else(|| self.default_locale.clone())
+    }
+
+    /// text of `key` in `locale` with `{name}` fields replaced by `args`
+    pub fn message(&self, locale: Option<&str>, key: MessageKey, args: &[(&str, &str)]) -> String {
+        let text = locale
+            .map(Self::candidates)
+            .unwrap_or_default()
+            .into_iter()
+            .chain(std::iter::once(self.default_locale.clone()))
+            .find_map(|locale| self.messages.get(&locale)?.get(&key))
+            .map_or(key.default_text(), String::as_str);
+        args.iter().fold(text.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+    }
+
+    /// text of `key` in the locale of a request
+    pub fn message_for(
+        &self,
+        context: &RequestContext,
+        key: MessageKey,
+        args: &[(&str, &str)],
+    ) -> String {
+        self.message(Some(&self.locale_for(context)), key, args)
+    }
+}
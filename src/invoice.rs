@@ -0,0 +1,117 @@
+/// Invoice module.
+use crate::tax::TaxLine;
+use crate::types::PaymentRequest;
+use serde::{Deserialize, Serialize};
+
+/// metadata extension carrying the invoice of a challenge
+pub const INVOICE_EXTENSION: &str = "invoice";
+
+/// Line of an invoice, amounts in the unit of the challenge amount.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineItem {
+    pub description: String,
+    pub quantity: u64,
+    pub unit_amount: String,
+    pub amount: String,
+}
+
+impl LineItem {
+    pub fn new(description: &str, quantity: u64, unit_amount: u128) -> Self {
+        Self {
+            description: description.to_string(),
+            quantity,
+            unit_amount: unit_amount.to_string(),
+            amount: unit_amount.saturating_mul(u128::from(quantity)).to_string(),
+        }
+    }
+}
+
+/// Itemized bill of a payment session, serialized into the challenge
+/// metadata and kept with the recorded payment.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::invoice::{Invoice, LineItem};
+/// use x402_sdk::pricing::PriceQuote;
+///
+/// let invoice = Invoice::new("INV-2024-0042")
+///     .with_line_item(LineItem::new("API calls", 20, 500))
+///     .with_line_item(LineItem::new("Report export", 1, 2_000));
+/// assert_eq!(invoice.subtotal, "12000");
+/// let quote = PriceQuote::from_invoice(invoice);
+/// assert_eq!(quote.amount, "12000");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Invoice {
+    pub invoice_id: String,
+    pub line_items: Vec<LineItem>,
+    pub subtotal: String,
+    /// taken off the subtotal by a coupon
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discount: Option<String>,
+    /// set by the engine when a tax calculator is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax: Option<TaxLine>,
+    /// amount to pay, the subtotal less the discount plus exclusive tax
+    pub total: String,
+}
+
+impl Invoice {
+    pub fn new(invoice_id: &str) -> Self {
+        Self {
+            invoice_id: invoice_id.to_string(),
+            line_items: Vec::new(),
+            subtotal: "0".to_string(),
+            discount: None,
+            tax: None,
+            total: "0".to_string(),
+        }
+    }
+
+    pub fn with_line_item(mut self, line_item: LineItem) -> Self {
+        self.line_items.push(line_item);
+        self.recompute();
+        self
+    }
+
+    pub fn with_discount(mut self, discount: u128) -> Self {
+        self.discount = Some(discount.to_string());
+        self.recompute();
+        self
+    }
+
+    pub fn with_tax(mut self, tax: TaxLine) -> Self {
+        self.tax = Some(tax);
+        self.recompute();
+        self
+    }
+
+    fn recompute(&mut self) {
+        let subtotal = self
+            .line_items
+            .iter()
+            .map(|item| item.amount.parse::<u128>().unwrap_or(0))
+            .fold(0u128, u128::saturating_add);
+        let discount = self
+            .discount
+            .as_ref()
+            .and_then(|discount| discount.parse::<u128>().ok())
+            .unwrap_or(0);
+        let tax = self.tax.as_ref().map_or(0, TaxLine::surcharge);
+        self.subtotal = subtotal.to_string();
+        self.total = subtotal
+            .saturating_sub(discount)
+            .saturating_add(tax)
+            .to_string();
+    }
+}
+
+/// invoice referenced by a challenge
+pub fn invoice(payment_request: &PaymentRequest) -> Option<Invoice> {
+    payment_request
+        .metadata
+        .extensions
+        .get(INVOICE_EXTENSION)
+        .and_then(|invoice| serde_json::from_value(invoice.clone()).ok())
+}
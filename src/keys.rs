@@ -0,0 +1,311 @@
+/// Signing key management module.
+use crate::crypto::{CryptoSuite, ED25519_ALGORITHM, verify_ed25519};
+use crate::signing::{
+    CHALLENGE_DOMAIN, ChallengeSigner, SignatureError, payload_message, recover_challenge_signer,
+    sign_payload_with, verify_payload_with,
+};
+use crate::types::{ChallengeSignature, X402ProtocolResponse};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ethers::core::k256::ecdsa::VerifyingKey;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// conventional path the HTTP integrations publish the key set at
+pub const JWKS_PATH: &str = "/.well-known/x402/jwks.json";
+
+/// A signing key with the time window it is used for.
+#[derive(Clone)]
+pub struct ManagedKey {
+    pub kid: String,
+    pub signer: Arc<dyn CryptoSuite>,
+    /// first second the key is used for signing
+    pub not_before: u64,
+    /// first second the key is no longer used for signing, it stays published
+    /// for the key ring grace period afterwards
+    pub not_after: Option<u64>,
+}
+
+/// Set of signing keys with scheduled rotation.
+///
+/// The active key is the most recent key whose signing window contains the
+/// current time. Retired keys remain in the published key set for a grace
+/// period, so challenges and receipts signed shortly before a rotation keep
+/// validating.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::keys::KeyRing;
+/// use x402_sdk::signing::ChallengeSigner;
+/// # fn example(now: u64) -> Result<(), Box<dyn std::error::Error>> {
+/// let key_ring = KeyRing::new(86_400);
+/// key_ring.add_key("2026-01", ChallengeSigner::from_private_key("0x...")?, now, None);
+/// // next key takes over in a week, the current one retires at the same time
+/// key_ring.schedule_rotation("2026-02", ChallengeSigner::from_private_key("0x...")?, now + 604_800);
+/// let jwks = key_ring.jwks(now);
+/// # Ok(())
+/// # }
+/// ```
+pub struct KeyRing {
+    keys: RwLock<Vec<ManagedKey>>,
+    grace_secs: u64,
+}
+
+impl KeyRing {
+    pub fn new(grace_secs: u64) -> Self {
+        Self {
+            keys: RwLock::new(Vec::new()),
+            grace_secs,
+        }
+    }
+
+    /// add a key of any suite, e.g. a [`ChallengeSigner`] or an
+    /// [`Ed25519Suite`](crate::crypto::Ed25519Suite)
+    pub fn add_key(
+        &self,
+        kid: &str,
+        signer: impl CryptoSuite + 'static,
+        not_before: u64,
+        not_after: Option<u64>,
+    ) {
+        let mut keys = self.keys.write().unwrap();
+        keys.retain(|key| key.kid != kid);
+        keys.push(ManagedKey {
+            kid: kid.to_string(),
+            signer: Arc::new(signer),
+            not_before,
+            not_after,
+        });
+        keys.sort_by_key(|key| key.not_before);
+    }
+
+    /// add a key that becomes active at `activate_at`, every key active
+    /// before that stops signing at the same moment
+    pub fn schedule_rotation(
+        &self,
+        kid: &str,
+        signer: impl CryptoSuite + 'static,
+        activate_at: u64,
+    ) {
+        {
+            let mut keys = self.keys.write().unwrap();
+            for key in keys.iter_mut() {
+                if key.not_before < activate_at && key.not_after.is_none_or(|t| t > activate_at) {
+                    key.not_after = Some(activate_at);
+                }
+            }
+        }
+        self.add_key(kid, signer, activate_at, None);
+    }
+
+    /// key used for signing at the given time
+    pub fn active_key(&self, now: u64) -> Option<ManagedKey> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|key| key.not_before <= now && key.not_after.is_none_or(|t| now < t))
+            .cloned()
+    }
+
+    /// keys that signatures may currently be verified against
+    pub fn published_keys(&self, now: u64) -> Vec<ManagedKey> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|key| key.not_after.is_none_or(|t| now < t + self.grace_secs))
+            .cloned()
+            .collect()
+    }
+
+    /// drop keys past their grace period, returns how many were removed
+    pub fn prune(&self, now: u64) -> usize {
+        let mut keys = self.keys.write().unwrap();
+        let before = keys.len();
+        keys.retain(|key| key.not_after.is_none_or(|t| now < t + self.grace_secs));
+        before - keys.len()
+    }
+
+    /// sign a 402 response with the active key
+    pub fn sign_response(
+        &self,
+        response: &mut X402ProtocolResponse,
+        now: u64,
+    ) -> Result<(), SignatureError> {
+        response.signature =
+            Some(self.sign_payload(CHALLENGE_DOMAIN, &response.payment_required, now)?);
+        Ok(())
+    }
+
+    /// sign a payload with the active key, the signature carries its key id
+    pub fn sign_payload<T: Serialize>(
+        &self,
+        domain: &[u8],
+        payload: &T,
+        now: u64,
+    ) -> Result<ChallengeSignature, SignatureError> {
+        let key = self
+            .active_key(now)
+            .ok_or_else(|| SignatureError::InvalidKey("no signing key active".to_string()))?;
+        let mut signature = sign_payload_with(key.signer.as_ref(), domain, payload)?;
+        signature.kid = Some(key.kid.clone());
+        Ok(signature)
+    }
+
+    /// verify a payload signature against the published key named by its
+    /// key id
+    pub fn verify_payload<T: Serialize>(
+        &self,
+        domain: &[u8],
+        payload: &T,
+        signature: &ChallengeSignature,
+        now: u64,
+    ) -> Result<(), SignatureError> {
+        let kid = signature
+            .kid
+            .as_deref()
+            .ok_or_else(|| SignatureError::InvalidKey("signature has no key id".to_string()))?;
+        let key = self
+            .published_keys(now)
+            .into_iter()
+            .find(|key| key.kid == kid)
+            .ok_or_else(|| SignatureError::InvalidKey(format!("unknown key id: {}", kid)))?;
+        verify_payload_with(key.signer.as_ref(), domain, payload, signature)
+    }
+
+    /// JWKS document of the published keys
+    pub fn jwks(&self, now: u64) -> JwkSet {
+        JwkSet {
+            keys: self
+                .published_keys(now)
+                .iter()
+                .filter_map(|key| key.signer.jwk(&key.kid))
+                .collect(),
+        }
+    }
+}
+
+/// JSON Web Key for a secp256k1 or Ed25519 public key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub alg: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub kid: String,
+    pub x: String,
+    /// empty for Ed25519 keys
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub y: String,
+}
+
+impl Jwk {
+    pub fn from_signer(kid: &str, signer: &ChallengeSigner) -> Self {
+        let point = signer.public_key_uncompressed();
+        Self {
+            kty: "EC".to_string(),
+            crv: "secp256k1".to_string(),
+            alg: "ES256K".to_string(),
+            key_use: "sig".to_string(),
+            kid: kid.to_string(),
+            x: URL_SAFE_NO_PAD.encode(&point[1..33]),
+            y: URL_SAFE_NO_PAD.encode(&point[33..65]),
+        }
+    }
+
+    /// address derived from the public key, as recovered from signatures
+    pub fn address(&self) -> Result<Address, SignatureError> {
+        if self.kty != "EC" || self.crv != "secp256k1" {
+            return Err(SignatureError::UnsupportedAlgorithm(format!(
+                "{}/{}",
+                self.kty, self.crv
+            )));
+        }
+        let decode = |value: &str| {
+            URL_SAFE_NO_PAD
+                .decode(value)
+                .map_err(|e| SignatureError::InvalidKey(e.to_string()))
+        };
+        let mut point = vec![0x04];
+        point.extend(decode(&self.x)?);
+        point.extend(decode(&self.y)?);
+        let key = VerifyingKey::from_sec1_bytes(&point)
+            .map_err(|e| SignatureError::InvalidKey(e.to_string()))?;
+        Ok(ethers::utils::public_key_to_address(&key))
+    }
+
+    /// raw public key of an `OKP`/`Ed25519` key
+    pub fn ed25519_public_key(&self) -> Result<[u8; 32], SignatureError> {
+        if self.kty != "OKP" || self.crv != "Ed25519" {
+            return Err(SignatureError::UnsupportedAlgorithm(format!(
+                "{}/{}",
+                self.kty, self.crv
+            )));
+        }
+        URL_SAFE_NO_PAD
+            .decode(&self.x)
+            .map_err(|e| SignatureError::InvalidKey(e.to_string()))?
+            .try_into()
+            .map_err(|_| SignatureError::InvalidKey("expected a 32 byte public key".to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl JwkSet {
+    pub fn find(&self, kid: &str) -> Option<&Jwk> {
+        self.keys.iter().find(|key| key.kid == kid)
+    }
+}
+
+/// Verify a received 402 challenge against a published key set, the key is
+/// selected by the `kid` of the signature.
+pub fn verify_challenge_with_jwks(
+    response: &X402ProtocolResponse,
+    jwks: &JwkSet,
+) -> Result<(), SignatureError> {
+    let signature = response
+        .signature
+        .as_ref()
+        .ok_or(SignatureError::MissingSignature)?;
+    let kid = signature
+        .kid
+        .as_deref()
+        .ok_or_else(|| SignatureError::InvalidKey("signature has no key id".to_string()))?;
+    let jwk = jwks
+        .find(kid)
+        .ok_or_else(|| SignatureError::InvalidKey(format!("unknown key id: {}", kid)))?;
+    match jwk.kty.as_str() {
+        "OKP" => {
+            if signature.algorithm != ED25519_ALGORITHM {
+                return Err(SignatureError::UnsupportedAlgorithm(
+                    signature.algorithm.clone(),
+                ));
+            }
+            verify_ed25519(
+                &jwk.ed25519_public_key()?,
+                &payload_message(CHALLENGE_DOMAIN, &response.payment_required)?,
+                &signature.signature,
+            )
+        }
+        _ => {
+            let expected = jwk.address()?;
+            let actual = recover_challenge_signer(&response.payment_required, signature)?;
+            if actual != expected {
+                return Err(SignatureError::SignerMismatch {
+                    expected: ethers::utils::to_checksum(&expected, None),
+                    actual: ethers::utils::to_checksum(&actual, None),
+                });
+            }
+            Ok(())
+        }
+    }
+}
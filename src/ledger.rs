@@ -0,0 +1,375 @@
+/// Off-chain budget ledger module.
+use crate::clock::{Clock, SystemClock};
+use crate::context::RequestContext;
+use crate::types::PaymentRequest;
+use crate::verifier::{PaymentVerifier, VerificationError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/// header carrying the API key of a budget account
+pub const BUDGET_KEY_HEADER: &str = "X-Budget-Key";
+
+/// Proof that a request was made by the owner of a payer address, attached
+/// to the request context by the integration once it checked it, e.g. a
+/// wallet signature over the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedOwner(pub String);
+
+#[derive(Debug)]
+pub enum LedgerError {
+    UnknownAccount(String),
+    AccountExists(String),
+    InsufficientFunds { balance: u128, required: u128 },
+    InvalidAmount(String),
+    VerificationError(VerificationError),
+}
+
+impl std::fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownAccount(name) => write!(f, "Unknown budget account: {}", name),
+            Self::AccountExists(name) => write!(f, "Budget account already exists: {}", name),
+            Self::InsufficientFunds { balance, required } => write!(
+                f,
+                "Insufficient budget: balance {}, required {}",
+                balance, required
+            ),
+            Self::InvalidAmount(amount) => write!(f, "Invalid amount: {}", amount),
+            Self::VerificationError(err) => write!(f, "Verification error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+impl From<VerificationError> for LedgerError {
+    fn from(err: VerificationError) -> Self {
+        Self::VerificationError(err)
+    }
+}
+
+/// Prepaid budget of an agent, funded on chain and drawn down per request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetAccount {
+    pub name: String,
+    /// payer address the account is funded from and draws for
+    pub owner: String,
+    /// in the smallest unit of the service currency
+    pub balance: u128,
+    /// balance under which low balance alerts are sent
+    pub low_balance_threshold: u128,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LedgerEntryKind {
+    Deposit { transaction_hash: String },
+    Draw { resource: String },
+}
+
+/// A balance change of a budget account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub account: String,
+    pub timestamp: u64,
+    pub kind: LedgerEntryKind,
+    pub amount: u128,
+    /// balance after the change
+    pub balance: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LowBalanceAlert {
+    pub account: String,
+    pub owner: String,
+    pub balance: u128,
+    pub threshold: u128,
+    pub timestamp: u64,
+}
+
+/// Receives low balance alerts, called when a draw takes an account under
+/// its threshold.
+pub trait LowBalanceHook: Send + Sync {
+    fn on_low_balance(&self, alert: &LowBalanceAlert);
+}
+
+/// Posts low balance alerts as JSON to a webhook URL.
+///
+/// Requests are sent in the background on the current tokio runtime, alerts
+/// raised outside a runtime are dropped.
+pub struct WebhookHook {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookHook {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl LowBalanceHook for WebhookHook {
+    fn on_low_balance(&self, alert: &LowBalanceAlert) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let request = self.client.post(&self.url).json(alert);
+        runtime.spawn(async move {
+            let _ = request.send().await;
+        });
+    }
+}
+
+#[derive(Default)]
+struct LedgerState {
+    accounts: HashMap<String, BudgetAccount>,
+    credited: HashSet<String>,
+    entries: Vec<LedgerEntry>,
+    /// account names keyed by the SHA-256 of their API key
+    api_keys: HashMap<String, String>,
+}
+
+fn key_hash(api_key: &str) -> String {
+    ethers::utils::hex::encode(Sha256::digest(api_key.as_bytes()))
+}
+
+/// Budget accounts with their balance history.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::ledger::Ledger;
+///
+/// let ledger = Ledger::new();
+/// ledger.open_account("research-agent", "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5", 1000).unwrap();
+/// ledger.deposit("research-agent", 5000, "0xabc").unwrap();
+/// assert_eq!(ledger.draw("research-agent", 1500, "GET /data").unwrap(), 3500);
+/// // handed to the agent, sent in the X-Budget-Key header
+/// let api_key = ledger.issue_api_key("research-agent").unwrap();
+/// ```
+pub struct Ledger {
+    state: RwLock<LedgerState>,
+    hooks: Vec<Arc<dyn LowBalanceHook>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(LedgerState::default()),
+            hooks: Vec::new(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_hook(mut self, hook: Arc<dyn LowBalanceHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    pub fn open_account(
+        &self,
+        name: &str,
+        owner: &str,
+        low_balance_threshold: u128,
+    ) -> Result<(), LedgerError> {
+        let mut state = self.state.write().unwrap();
+        if state.accounts.contains_key(name) {
+            return Err(LedgerError::AccountExists(name.to_string()));
+        }
+        state.accounts.insert(
+            name.to_string(),
+            BudgetAccount {
+                name: name.to_string(),
+                owner: owner.to_string(),
+                balance: 0,
+                low_balance_threshold,
+                created_at: self.clock.now(),
+            },
+        );
+        Ok(())
+    }
+
+    pub fn account(&self, name: &str) -> Option<BudgetAccount> {
+        self.state.read().unwrap().accounts.get(name).cloned()
+    }
+
+    /// account owned by a payer address, see [`Self::authorize`] before
+    /// drawing for a request
+    pub fn account_for(&self, owner: &str) -> Option<BudgetAccount> {
+        self.state
+            .read()
+            .unwrap()
+            .accounts
+            .values()
+            .find(|account| account.owner.eq_ignore_ascii_case(owner))
+            .cloned()
+    }
+
+    /// Issues the API key of an account, proving requests are made by its
+    /// owner. Only its hash is kept, an earlier key of the account stops
+    /// working.
+    pub fn issue_api_key(&self, name: &str) -> Result<String, LedgerError> {
+        let mut state = self.state.write().unwrap();
+        if !state.accounts.contains_key(name) {
+            return Err(LedgerError::UnknownAccount(name.to_string()));
+        }
+        let bytes: [u8; 32] = rand::random();
+        let api_key = format!("bk_{}", ethers::utils::hex::encode(bytes));
+        state.api_keys.retain(|_, account| account != name);
+        state.api_keys.insert(key_hash(&api_key), name.to_string());
+        Ok(api_key)
+    }
+
+    /// Account a request of `owner` may draw from: the account owned by the
+    /// address, when the request carries its API key in
+    /// [`BUDGET_KEY_HEADER`] or a [`VerifiedOwner`] of the address. A payer
+    /// address alone is no proof, anyone can claim it.
+    pub fn authorize(&self, owner: &str, context: &RequestContext) -> Option<BudgetAccount> {
+        let account = self.account_for(owner)?;
+        let verified = context
+            .extensions
+            .get::<VerifiedOwner>()
+            .is_some_and(|verified| verified.0.eq_ignore_ascii_case(owner));
+        let keyed = context.header(BUDGET_KEY_HEADER).is_some_and(|api_key| {
+            self.state.read().unwrap().api_keys.get(&key_hash(api_key)) == Some(&account.name)
+        });
+        (verified || keyed).then_some(account)
+    }
+
+    /// balance history of an account, oldest first
+    pub fn entries(&self, name: &str) -> Vec<LedgerEntry> {
+        self.state
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|entry| entry.account == name)
+            .cloned()
+            .collect()
+    }
+
+    /// credit an on-chain deposit, a transaction is only credited once
+    pub fn deposit(
+        &self,
+        name: &str,
+        amount: u128,
+        transaction_hash: &str,
+    ) -> Result<u128, LedgerError> {
+        let mut state = self.state.write().unwrap();
+        let LedgerState {
+            accounts,
+            credited,
+            entries,
+            ..
+        } = &mut *state;
+        let account = accounts
+            .get_mut(name)
+            .ok_or_else(|| LedgerError::UnknownAccount(name.to_string()))?;
+        if !credited.insert(transaction_hash.to_ascii_lowercase()) {
+            return Ok(account.balance);
+        }
+        account.balance = account.balance.saturating_add(amount);
+        let balance = account.balance;
+        entries.push(LedgerEntry {
+            account: name.to_string(),
+            timestamp: self.clock.now(),
+            kind: LedgerEntryKind::Deposit {
+                transaction_hash: transaction_hash.to_string(),
+            },
+            amount,
+            balance,
+        });
+        Ok(balance)
+    }
+
+    /// draw the price of a request, returns the remaining balance
+    pub fn draw(&self, name: &str, amount: u128, resource: &str) -> Result<u128, LedgerError> {
+        let now = self.clock.now();
+        let (balance, alert) = {
+            let mut state = self.state.write().unwrap();
+            let account = state
+                .accounts
+                .get_mut(name)
+                .ok_or_else(|| LedgerError::UnknownAccount(name.to_string()))?;
+            if account.balance < amount {
+                return Err(LedgerError::InsufficientFunds {
+                    balance: account.balance,
+                    required: amount,
+                });
+            }
+            let was_low = account.balance < account.low_balance_threshold;
+            account.balance -= amount;
+            let account = account.clone();
+            state.entries.push(LedgerEntry {
+                account: name.to_string(),
+                timestamp: now,
+                kind: LedgerEntryKind::Draw {
+                    resource: resource.to_string(),
+                },
+                amount,
+                balance: account.balance,
+            });
+            let crossed = !was_low && account.balance < account.low_balance_threshold;
+            let alert = LowBalanceAlert {
+                account: account.name,
+                owner: account.owner,
+                balance: account.balance,
+                threshold: account.low_balance_threshold,
+                timestamp: now,
+            };
+            (account.balance, crossed.then_some(alert))
+        };
+        if let Some(alert) = alert {
+            for hook in &self.hooks {
+                hook.on_low_balance(&alert);
+            }
+        }
+        Ok(balance)
+    }
+
+    /// look for deposits from the account owner matching `deposit_request`
+    /// and credit the ones not seen yet, returns the amount credited
+    pub async fn detect_top_ups(
+        &self,
+        name: &str,
+        verifier: &dyn PaymentVerifier,
+        deposit_request: &PaymentRequest,
+    ) -> Result<u128, LedgerError> {
+        let owner = self
+            .account(name)
+            .ok_or_else(|| LedgerError::UnknownAccount(name.to_string()))?
+            .owner;
+        let verification = verifier.verify_payment(deposit_request, &owner).await?;
+        if !verification.is_paid {
+            return Ok(0);
+        }
+        let mut credited = 0u128;
+        for log in &verification.transaction_logs {
+            let amount: u128 = log
+                .value
+                .parse()
+                .map_err(|_| LedgerError::InvalidAmount(log.value.clone()))?;
+            let before = self.account(name).map_or(0, |account| account.balance);
+            let after = self.deposit(name, amount, &log.transaction_hash)?;
+            credited += after - before;
+        }
+        Ok(credited)
+    }
+}
@@ -1,4 +1,74 @@
+pub mod address;
+pub mod analytics;
+pub mod anomaly;
+pub mod audit;
+#[cfg(feature = "authz")]
+pub mod authz;
+pub mod cache;
+pub mod canonical;
+pub mod client;
+pub mod clock;
+pub mod compliance;
 pub mod config;
+pub mod context;
 pub mod core;
+pub mod coupon;
+pub mod crypto;
+#[cfg(feature = "devnet")]
+pub mod devnet;
+pub mod discovery;
+pub mod dispute;
+pub mod encryption;
+pub mod events;
+pub mod export;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod headers;
+pub mod hooks;
+pub mod i18n;
+pub mod invoice;
+pub mod keys;
+pub mod ledger;
+pub mod limits;
+#[cfg(feature = "mcp")]
+pub mod mcp;
+pub mod monitor;
+pub mod nonce;
+pub mod notifications;
+pub mod outbox;
+pub mod paid;
+pub mod payment_link;
+pub mod paywall;
+pub mod policy;
+pub mod pricing;
+pub mod primitives;
+pub mod rates;
+pub mod receipt;
+pub mod receipt_page;
+pub mod redaction;
+pub mod relay;
+pub mod resource;
+pub mod revocation;
+#[cfg(feature = "cli")]
+pub mod scaffold;
+pub mod services;
+pub mod session;
+pub mod shard;
+pub mod signing;
+pub mod simulation;
+pub mod store;
+pub mod streaming;
+pub mod tax;
+pub mod telemetry;
+pub mod testing;
+pub mod typed_data;
 pub mod types;
+pub mod usage;
 pub mod verifier;
+pub mod websocket;
+pub mod wire;
+
+#[cfg(feature = "macros")]
+pub use x402_macros::paid;
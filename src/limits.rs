@@ -0,0 +1,170 @@
+/// Untrusted payload limits module.
+use crate::types::{Currency, PaymentRequest};
+use base64::Engine;
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadError {
+    /// the encoded payload is longer than allowed
+    TooLarge {
+        len: usize,
+        max: usize,
+    },
+    InvalidBase64(String),
+    InvalidJson(String),
+    /// a field the schema does not know, by its path
+    UnknownField(String),
+    ChainNotAllowed(String),
+    SchemeNotAllowed(String),
+}
+
+impl std::fmt::Display for PayloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLarge { len, max } => {
+                write!(f, "Payload of {} bytes exceeds the limit of {}", len, max)
+            }
+            Self::InvalidBase64(msg) => write!(f, "Invalid base64: {}", msg),
+            Self::InvalidJson(msg) => write!(f, "Invalid JSON: {}", msg),
+            Self::UnknownField(path) => write!(f, "Unknown field: {}", path),
+            Self::ChainNotAllowed(chain_id) => write!(f, "Chain {} is not accepted", chain_id),
+            Self::SchemeNotAllowed(scheme) => write!(f, "Scheme {} is not accepted", scheme),
+        }
+    }
+}
+
+impl std::error::Error for PayloadError {}
+
+/// payment scheme of a currency, as listed in [`PayloadLimits::with_schemes`]
+pub fn currency_scheme(currency: &Currency) -> &'static str {
+    match currency {
+        Currency::Native => "native",
+        Currency::Token { .. } => "token",
+        Currency::AnyToken { .. } => "any_token",
+    }
+}
+
+/// Limits applied to base64 JSON payloads received from untrusted parties,
+/// e.g. agents sending receipts or servers sending challenges.
+///
+/// The default only caps the encoded size at 16 KiB. [`Self::strict`] also
+/// rejects fields the schema does not know, which a lenient parser would
+/// silently drop.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::limits::PayloadLimits;
+///
+/// let limits = PayloadLimits::strict()
+///     .with_max_encoded_bytes(4096)
+///     .with_chains(&["1", "8453"])
+///     .with_schemes(&["token"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadLimits {
+    pub max_encoded_bytes: usize,
+    pub deny_unknown_fields: bool,
+    /// chain ids of accepted payment requests, any when `None`
+    pub allowed_chains: Option<Vec<String>>,
+    /// see [`currency_scheme`], any when `None`
+    pub allowed_schemes: Option<Vec<String>>,
+}
+
+impl Default for PayloadLimits {
+    fn default() -> Self {
+        Self {
+            max_encoded_bytes: 16 * 1024,
+            deny_unknown_fields: false,
+            allowed_chains: None,
+            allowed_schemes: None,
+        }
+    }
+}
+
+impl PayloadLimits {
+    /// default limits rejecting unknown fields
+    pub fn strict() -> Self {
+        Self {
+            deny_unknown_fields: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_encoded_bytes(mut self, max_encoded_bytes: usize) -> Self {
+        self.max_encoded_bytes = max_encoded_bytes;
+        self
+    }
+
+    pub fn with_chains(mut self, chain_ids: &[&str]) -> Self {
+        self.allowed_chains = Some(chain_ids.iter().map(|id| id.to_string()).collect());
+        self
+    }
+
+    pub fn with_schemes(mut self, schemes: &[&str]) -> Self {
+        self.allowed_schemes = Some(schemes.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    /// Decodes a base64 JSON payload, the length is checked before anything
+    /// is decoded.
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        engine: &impl Engine,
+        value: &str,
+    ) -> Result<T, PayloadError> {
+        let value = value.trim();
+        if value.len() > self.max_encoded_bytes {
+            return Err(PayloadError::TooLarge {
+                len: value.len(),
+                max: self.max_encoded_bytes,
+            });
+        }
+        let json = engine
+            .decode(value)
+            .map_err(|e| PayloadError::InvalidBase64(e.to_string()))?;
+        self.decode_json(&json)
+    }
+
+    /// JSON payload, checked for unknown fields when strict
+    pub fn decode_json<T: DeserializeOwned>(&self, json: &[u8]) -> Result<T, PayloadError> {
+        if json.len() > self.max_encoded_bytes {
+            return Err(PayloadError::TooLarge {
+                len: json.len(),
+                max: self.max_encoded_bytes,
+            });
+        }
+        let mut deserializer = serde_json::Deserializer::from_slice(json);
+        if !self.deny_unknown_fields {
+            return T::deserialize(&mut deserializer)
+                .map_err(|e| PayloadError::InvalidJson(e.to_string()));
+        }
+        let mut unknown = None;
+        let value = serde_ignored::deserialize(&mut deserializer, |path| {
+            unknown.get_or_insert_with(|| path.to_string());
+        })
+        .map_err(|e| PayloadError::InvalidJson(e.to_string()))?;
+        match unknown {
+            Some(path) => Err(PayloadError::UnknownField(path)),
+            None => Ok(value),
+        }
+    }
+
+    /// check the chain and scheme of a payment request are accepted
+    pub fn check_request(&self, request: &PaymentRequest) -> Result<(), PayloadError> {
+        if let Some(chains) = &self.allowed_chains
+            && !chains.contains(&request.chain.chain_id)
+        {
+            return Err(PayloadError::ChainNotAllowed(
+                request.chain.chain_id.clone(),
+            ));
+        }
+        let scheme = currency_scheme(&request.currency);
+        if let Some(schemes) = &self.allowed_schemes
+            && !schemes.iter().any(|allowed| allowed == scheme)
+        {
+            return Err(PayloadError::SchemeNotAllowed(scheme.to_string()));
+        }
+        Ok(())
+    }
+}
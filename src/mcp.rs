@@ -0,0 +1,297 @@
+/// Model Context Protocol server module.
+use crate::context::RequestContext;
+use crate::core::X402;
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// MCP protocol revision implemented by the server
+pub const MCP_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// `_meta` key carrying the payer address of a tool call
+pub const PAYER_META_KEY: &str = "x402/payer";
+/// `_meta` key carrying the nonce of the paid challenge
+pub const NONCE_META_KEY: &str = "x402/nonce";
+/// `_meta` key carrying an optional coupon code
+pub const COUPON_META_KEY: &str = "x402/coupon";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug)]
+pub enum McpError {
+    InvalidArguments(String),
+    ToolFailed(String),
+}
+
+impl std::fmt::Display for McpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidArguments(msg) => write!(f, "Invalid arguments: {}", msg),
+            Self::ToolFailed(msg) => write!(f, "Tool failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for McpError {}
+
+/// Implementation of a paid tool, called once the payment is verified.
+#[async_trait]
+pub trait ToolHandler: Send + Sync {
+    /// structured tool output
+    async fn call(&self, arguments: Value) -> Result<Value, McpError>;
+}
+
+/// A tool exposed to MCP clients behind an x402 payment.
+pub struct PaidTool {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the tool arguments
+    pub input_schema: Value,
+    /// fixed price of a call, the pricing provider of the engine decides
+    /// when unset
+    pub price: Option<String>,
+    handler: Arc<dyn ToolHandler>,
+}
+
+impl PaidTool {
+    pub fn new(name: &str, description: &str, handler: Arc<dyn ToolHandler>) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            input_schema: json!({ "type": "object" }),
+            price: None,
+            handler,
+        }
+    }
+
+    pub fn with_input_schema(mut self, input_schema: Value) -> Self {
+        self.input_schema = input_schema;
+        self
+    }
+
+    pub fn with_price(mut self, amount: &str) -> Self {
+        self.price = Some(amount.to_string());
+        self
+    }
+
+    /// resource path paid for when calling the tool
+    pub fn resource_path(&self) -> String {
+        format!("/mcp/tools/{}", self.name)
+    }
+}
+
+/// MCP server exposing paid tools over JSON-RPC.
+///
+/// Calls carry the payer in `params._meta["x402/payer"]`. A call without a
+/// verified payment returns a tool error whose structured content is the
+/// x402 challenge, the client pays it and repeats the call with the challenge
+/// nonce in `params._meta["x402/nonce"]`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use async_trait::async_trait;
+/// use serde_json::{Value, json};
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::mcp::{McpError, McpServer, PaidTool, ToolHandler};
+///
+/// struct Forecast;
+///
+/// #[async_trait]
+/// impl ToolHandler for Forecast {
+///     async fn call(&self, arguments: Value) -> Result<Value, McpError> {
+///         Ok(json!({ "city": arguments["city"], "forecast": "sunny" }))
+///     }
+/// }
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let engine = Arc::new(X402::from_default_config()?);
+/// let server = McpServer::new(engine)
+///     .with_tool(PaidTool::new("forecast", "Weather forecast", Arc::new(Forecast)).with_price("1000"));
+/// server.serve_stdio().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct McpServer {
+    engine: Arc<X402>,
+    tools: Vec<PaidTool>,
+    name: String,
+    version: String,
+}
+
+impl McpServer {
+    pub fn new(engine: Arc<X402>) -> Self {
+        Self {
+            engine,
+            tools: Vec::new(),
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    pub fn with_tool(mut self, tool: PaidTool) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    /// server name and version reported to clients
+    pub fn with_server_info(mut self, name: &str, version: &str) -> Self {
+        self.name = name.to_string();
+        self.version = version.to_string();
+        self
+    }
+
+    /// handle one JSON-RPC message, `None` for notifications
+    pub async fn handle_message(&self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned()?;
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return Some(error_response(id, INVALID_REQUEST, "missing method"));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": self.name, "version": self.version },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(&params).await,
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method: {}", method))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    /// serve newline-delimited JSON-RPC messages until the reader is closed
+    pub async fn serve<R, W>(&self, reader: R, mut writer: W) -> std::io::Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = reader.lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle_message(&message).await,
+                Err(e) => Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+            };
+            if let Some(response) = response {
+                writer.write_all(response.to_string().as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// serve over stdin and stdout, the MCP stdio transport
+    pub async fn serve_stdio(&self) -> std::io::Result<()> {
+        self.serve(
+            tokio::io::BufReader::new(tokio::io::stdin()),
+            tokio::io::stdout(),
+        )
+        .await
+    }
+
+    fn list_tools(&self) -> Value {
+        let tools: Vec<Value> = self
+            .tools
+            .iter()
+            .map(|tool| {
+                let mut meta = json!({ "x402/resource": tool.resource_path() });
+                if let Some(price) = &tool.price {
+                    meta["x402/price"] = json!(price);
+                }
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "inputSchema": tool.input_schema,
+                    "_meta": meta,
+                })
+            })
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name == name)
+            .ok_or_else(|| (INVALID_PARAMS, format!("unknown tool: {}", name)))?;
+        let meta = params.get("_meta");
+        let meta_str = |key: &str| meta.and_then(|meta| meta.get(key)).and_then(Value::as_str);
+        let Some(payer) = meta_str(PAYER_META_KEY) else {
+            return Ok(tool_error(
+                &format!("{} is required in _meta", PAYER_META_KEY),
+                None,
+            ));
+        };
+        let result = self
+            .engine
+            .handle_access_request_with_coupon(
+                payer,
+                &tool.resource_path(),
+                meta_str(NONCE_META_KEY),
+                tool.price.as_deref(),
+                meta_str(COUPON_META_KEY),
+                &RequestContext::new("POST"),
+            )
+            .await
+            .map_err(|e| (INTERNAL_ERROR, e.to_string()))?;
+        if !result.should_serve_content {
+            let challenge = result.x402_response.map(|response| json!(response));
+            return Ok(tool_error(
+                &format!("payment required, status {}", result.http_status),
+                challenge,
+            ));
+        }
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+        let mut output = match tool.handler.call(arguments).await {
+            Ok(output) => json!({
+                "content": [{ "type": "text", "text": output.to_string() }],
+                "structuredContent": output,
+                "isError": false,
+            }),
+            Err(e) => tool_error(&e.to_string(), None),
+        };
+        if let Some(receipt) = result.receipt {
+            output["_meta"] = json!({ "x402/receipt": receipt });
+        }
+        Ok(output)
+    }
+}
+
+fn tool_error(message: &str, structured_content: Option<Value>) -> Value {
+    let mut result = json!({
+        "content": [{ "type": "text", "text": message }],
+        "isError": true,
+    });
+    if let Some(structured_content) = structured_content {
+        result["structuredContent"] = structured_content;
+    }
+    result
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
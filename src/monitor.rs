@@ -0,0 +1,273 @@
+/// Treasury balance monitoring module.
+use crate::events::{EventListener, PaymentEvent, PaymentEventKind};
+use crate::types::ChainType;
+use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::H160;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+pub enum MonitorError {
+    ChainNotSupported(ChainType),
+    InvalidAddress(String),
+    RpcError(String),
+}
+
+impl std::fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ChainNotSupported(chain) => write!(f, "Chain not supported: {:?}", chain),
+            Self::InvalidAddress(address) => write!(f, "Invalid address: {}", address),
+            Self::RpcError(msg) => write!(f, "RPC error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MonitorError {}
+
+/// Reads the balance of an address in the smallest unit of the native
+/// currency.
+#[async_trait]
+pub trait BalanceSource: Send + Sync {
+    async fn balance(&self, chain_type: &ChainType, address: &str) -> Result<u128, MonitorError>;
+}
+
+/// Balance source for EVM chains backed by JSON-RPC providers.
+#[derive(Default)]
+pub struct EvmBalanceSource {
+    providers: HashMap<ChainType, Arc<Provider<Http>>>,
+}
+
+impl EvmBalanceSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rpc(mut self, chain_type: ChainType, rpc_url: &str) -> Result<Self, MonitorError> {
+        let provider = Provider::<Http>::try_from(rpc_url)
+            .map_err(|e| MonitorError::RpcError(e.to_string()))?;
+        self.providers.insert(chain_type, Arc::new(provider));
+        Ok(self)
+    }
+}
+
+#[async_trait]
+impl BalanceSource for EvmBalanceSource {
+    async fn balance(&self, chain_type: &ChainType, address: &str) -> Result<u128, MonitorError> {
+        let provider = self
+            .providers
+            .get(chain_type)
+            .ok_or_else(|| MonitorError::ChainNotSupported(chain_type.clone()))?;
+        let address = H160::from_str(address)
+            .map_err(|_| MonitorError::InvalidAddress(address.to_string()))?;
+        let balance = provider
+            .get_balance(address, None)
+            .await
+            .map_err(|e| MonitorError::RpcError(e.to_string()))?;
+        Ok(balance.min(u128::MAX.into()).as_u128())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TreasuryAlertKind {
+    /// verified payments exceed what arrived on chain
+    MissingFunds { shortfall: u128 },
+    /// more arrived on chain than verified payments explain
+    UnexplainedInflow { excess: u128 },
+    /// the balance went down, e.g. a withdrawal or sweep
+    BalanceDecreased { amount: u128 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreasuryAlert {
+    pub chain_type: ChainType,
+    pub address: String,
+    pub kind: TreasuryAlertKind,
+}
+
+/// Receives treasury alerts, e.g. to page an operator or call a webhook.
+pub trait AlertHook: Send + Sync {
+    fn on_alert(&self, alert: &TreasuryAlert);
+}
+
+/// Result of one balance check of a watched address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceReport {
+    pub chain_type: ChainType,
+    pub address: String,
+    pub balance: u128,
+    /// balance at the previous check, `None` on the first check
+    pub previous_balance: Option<u128>,
+    /// sum of payments verified on the chain since the previous check
+    pub verified_inflow: u128,
+    pub alerts: Vec<TreasuryAlert>,
+}
+
+struct Watched {
+    address: String,
+    last_balance: Option<u128>,
+    verified_since: u128,
+}
+
+/// Tracks treasury balances per chain and reconciles them with verified
+/// payments.
+///
+/// Balances are read in the native currency, so the monitor suits merchants
+/// charging in the native currency of the watched chains. Register the
+/// monitor as an engine event listener so it sees verified payments, then
+/// call [`TreasuryMonitor::check`] periodically. Differences above the
+/// tolerance fire the alert hooks.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::monitor::{EvmBalanceSource, TreasuryMonitor};
+/// use x402_sdk::types::ChainType;
+/// # async fn example(engine: x402_sdk::core::X402) -> Result<(), Box<dyn std::error::Error>> {
+/// let source = EvmBalanceSource::new().with_rpc(ChainType::ethereum(), "https://rpc.example")?;
+/// let monitor = Arc::new(TreasuryMonitor::new(Arc::new(source)).with_tolerance(1_000));
+/// monitor.watch(ChainType::ethereum(), "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5");
+/// let engine = engine.with_event_listener(monitor.clone());
+/// let reports = monitor.check().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TreasuryMonitor {
+    source: Arc<dyn BalanceSource>,
+    hooks: Vec<Arc<dyn AlertHook>>,
+    /// difference ignored in both directions, absorbs dust and rounding
+    tolerance: u128,
+    /// unexplained inflow that fires an alert, defaults to the tolerance
+    large_inflow_threshold: Option<u128>,
+    watched: Mutex<HashMap<ChainType, Watched>>,
+}
+
+impl TreasuryMonitor {
+    pub fn new(source: Arc<dyn BalanceSource>) -> Self {
+        Self {
+            source,
+            hooks: Vec::new(),
+            tolerance: 0,
+            large_inflow_threshold: None,
+            watched: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_hook(mut self, hook: Arc<dyn AlertHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: u128) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_large_inflow_threshold(mut self, threshold: u128) -> Self {
+        self.large_inflow_threshold = Some(threshold);
+        self
+    }
+
+    /// watch the treasury address of a chain, replacing a previous one
+    pub fn watch(&self, chain_type: ChainType, address: &str) {
+        self.watched.lock().unwrap().insert(
+            chain_type,
+            Watched {
+                address: address.to_string(),
+                last_balance: None,
+                verified_since: 0,
+            },
+        );
+    }
+
+    /// record a verified payment, called from the engine event stream
+    pub fn record_verified(&self, chain_type: &ChainType, amount: u128) {
+        if let Some(watched) = self.watched.lock().unwrap().get_mut(chain_type) {
+            watched.verified_since = watched.verified_since.saturating_add(amount);
+        }
+    }
+
+    /// read the balance of every watched address and reconcile it with the
+    /// payments verified since the previous check
+    pub async fn check(&self) -> Result<Vec<BalanceReport>, MonitorError> {
+        let targets: Vec<(ChainType, String)> = self
+            .watched
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(chain_type, watched)| (chain_type.clone(), watched.address.clone()))
+            .collect();
+        let mut reports = Vec::new();
+        for (chain_type, address) in targets {
+            let balance = self.source.balance(&chain_type, &address).await?;
+            let report = {
+                let mut watched = self.watched.lock().unwrap();
+                let Some(entry) = watched.get_mut(&chain_type) else {
+                    continue;
+                };
+                let report = self.reconcile(&chain_type, entry, balance);
+                entry.last_balance = Some(balance);
+                entry.verified_since = 0;
+                report
+            };
+            for alert in &report.alerts {
+                for hook in &self.hooks {
+                    hook.on_alert(alert);
+                }
+            }
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    fn reconcile(&self, chain_type: &ChainType, entry: &Watched, balance: u128) -> BalanceReport {
+        let mut alerts = Vec::new();
+        let alert = |kind| TreasuryAlert {
+            chain_type: chain_type.clone(),
+            address: entry.address.clone(),
+            kind,
+        };
+        if let Some(previous) = entry.last_balance {
+            let verified = entry.verified_since;
+            if balance < previous {
+                alerts.push(alert(TreasuryAlertKind::BalanceDecreased {
+                    amount: previous - balance,
+                }));
+            } else {
+                let inflow = balance - previous;
+                let threshold = self.large_inflow_threshold.unwrap_or(self.tolerance);
+                if verified > inflow + self.tolerance {
+                    alerts.push(alert(TreasuryAlertKind::MissingFunds {
+                        shortfall: verified - inflow,
+                    }));
+                } else if inflow > verified + threshold {
+                    alerts.push(alert(TreasuryAlertKind::UnexplainedInflow {
+                        excess: inflow - verified,
+                    }));
+                }
+            }
+        }
+        BalanceReport {
+            chain_type: chain_type.clone(),
+            address: entry.address.clone(),
+            balance,
+            previous_balance: entry.last_balance,
+            verified_inflow: entry.verified_since,
+            alerts,
+        }
+    }
+}
+
+impl EventListener for TreasuryMonitor {
+    fn on_event(&self, event: &PaymentEvent) {
+        if let PaymentEventKind::PaymentVerified { verification, .. } = &event.kind
+            && let Ok(amount) = verification.paid_amount.parse::<u128>()
+        {
+            self.record_verified(&verification.chain.chain_type, amount);
+        }
+    }
+}
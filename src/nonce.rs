@@ -0,0 +1,269 @@
+/// Account nonce management module.
+use crate::clock::{Clock, SystemClock};
+use crate::store::{NonceStore, StoreError};
+use ethers::providers::Middleware;
+use ethers::types::{BlockNumber, H160};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug)]
+pub enum NonceError {
+    StoreError(StoreError),
+    NetworkError(String),
+}
+
+impl std::fmt::Display for NonceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StoreError(err) => write!(f, "Store error: {}", err),
+            Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for NonceError {}
+
+impl From<StoreError> for NonceError {
+    fn from(err: StoreError) -> Self {
+        Self::StoreError(err)
+    }
+}
+
+/// A transaction sent with a managed nonce and not yet mined.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InFlightTransaction {
+    pub nonce: u64,
+    /// hash of the latest submission, replacements overwrite it
+    pub transaction_hash: String,
+    pub sent_at: u64,
+}
+
+#[derive(Debug, Default)]
+struct AccountNonces {
+    next: u64,
+    /// reserved nonces whose transaction was never sent, reused first so
+    /// they do not leave a gap blocking later transactions
+    released: BTreeSet<u64>,
+    in_flight: BTreeMap<u64, InFlightTransaction>,
+}
+
+/// Serializes nonce assignment of the accounts sending transactions, e.g.
+/// the relay wallet, so concurrent senders never collide.
+///
+/// The first reservation of an account recovers its next nonce from the
+/// larger of the stored value and the pending transaction count on chain.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethers::providers::{Http, Provider};
+/// use x402_sdk::nonce::NonceManager;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let provider = Provider::<Http>::try_from("https://eth.llamarpc.com")?;
+/// let manager = NonceManager::new();
+/// let wallet = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5".parse()?;
+/// let nonce = manager.reserve(&provider, "1", wallet).await?;
+/// // ... send the transaction, then
+/// manager.record_sent("1", wallet, nonce, "0xabc").await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct NonceManager {
+    accounts: Mutex<HashMap<String, AccountNonces>>,
+    store: Option<Arc<dyn NonceStore>>,
+    clock: Arc<dyn Clock>,
+    stuck_after_secs: u64,
+}
+
+impl Default for NonceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NonceManager {
+    pub fn new() -> Self {
+        Self {
+            accounts: Mutex::new(HashMap::new()),
+            store: None,
+            clock: Arc::new(SystemClock),
+            stuck_after_secs: 120,
+        }
+    }
+
+    /// persist the next nonce of every account, for recovery after a restart
+    pub fn with_store(mut self, store: Arc<dyn NonceStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// age after which an unmined transaction is reported stuck
+    pub fn with_stuck_after(mut self, stuck_after_secs: u64) -> Self {
+        self.stuck_after_secs = stuck_after_secs;
+        self
+    }
+
+    fn key(chain_id: &str, address: H160) -> String {
+        format!("{}:{:?}", chain_id, address)
+    }
+
+    /// reserve the next nonce of an account, to be followed by
+    /// [`record_sent`](Self::record_sent) or [`release`](Self::release)
+    pub async fn reserve<M: Middleware>(
+        &self,
+        client: &M,
+        chain_id: &str,
+        address: H160,
+    ) -> Result<u64, NonceError> {
+        let key = Self::key(chain_id, address);
+        let mut accounts = self.accounts.lock().await;
+        if !accounts.contains_key(&key) {
+            let pending = client
+                .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+                .await
+                .map_err(|e| NonceError::NetworkError(e.to_string()))?
+                .as_u64();
+            let stored = match &self.store {
+                Some(store) => store.next_nonce(&key).await?.unwrap_or(0),
+                None => 0,
+            };
+            accounts.insert(
+                key.clone(),
+                AccountNonces {
+                    next: pending.max(stored),
+                    ..AccountNonces::default()
+                },
+            );
+        }
+        let account = accounts.get_mut(&key).unwrap();
+        if let Some(nonce) = account.released.pop_first() {
+            return Ok(nonce);
+        }
+        let nonce = account.next;
+        if let Some(store) = &self.store {
+            store.save_next_nonce(&key, nonce + 1).await?;
+        }
+        account.next = nonce + 1;
+        Ok(nonce)
+    }
+
+    /// hand back a reserved nonce whose transaction was not sent
+    pub async fn release(&self, chain_id: &str, address: H160, nonce: u64) {
+        let mut accounts = self.accounts.lock().await;
+        if let Some(account) = accounts.get_mut(&Self::key(chain_id, address))
+            && !account.in_flight.contains_key(&nonce)
+        {
+            account.released.insert(nonce);
+        }
+    }
+
+    /// track a sent transaction, also called with the hash of a replacement
+    pub async fn record_sent(
+        &self,
+        chain_id: &str,
+        address: H160,
+        nonce: u64,
+        transaction_hash: &str,
+    ) {
+        let now = self.clock.now();
+        let mut accounts = self.accounts.lock().await;
+        let account = accounts
+            .entry(Self::key(chain_id, address))
+            .or_insert_with(|| AccountNonces {
+                next: nonce + 1,
+                ..AccountNonces::default()
+            });
+        account.released.remove(&nonce);
+        account.in_flight.insert(
+            nonce,
+            InFlightTransaction {
+                nonce,
+                transaction_hash: transaction_hash.to_string(),
+                sent_at: now,
+            },
+        );
+    }
+
+    /// stop tracking a mined transaction, by the hash of any submission
+    pub async fn record_mined(&self, chain_id: &str, address: H160, transaction_hash: &str) {
+        let mut accounts = self.accounts.lock().await;
+        if let Some(account) = accounts.get_mut(&Self::key(chain_id, address)) {
+            account.in_flight.retain(|_, transaction| {
+                !transaction
+                    .transaction_hash
+                    .eq_ignore_ascii_case(transaction_hash)
+            });
+        }
+    }
+
+    /// nonce of an in-flight transaction, to replace it
+    pub async fn in_flight_nonce(
+        &self,
+        chain_id: &str,
+        address: H160,
+        transaction_hash: &str,
+    ) -> Option<u64> {
+        let accounts = self.accounts.lock().await;
+        accounts
+            .get(&Self::key(chain_id, address))?
+            .in_flight
+            .values()
+            .find(|transaction| {
+                transaction
+                    .transaction_hash
+                    .eq_ignore_ascii_case(transaction_hash)
+            })
+            .map(|transaction| transaction.nonce)
+    }
+
+    /// in-flight transactions sent longer than the stuck age ago, lowest
+    /// nonce first, the first one blocks every later one
+    pub async fn stuck(&self, chain_id: &str, address: H160) -> Vec<InFlightTransaction> {
+        let now = self.clock.now();
+        let accounts = self.accounts.lock().await;
+        accounts
+            .get(&Self::key(chain_id, address))
+            .map(|account| {
+                account
+                    .in_flight
+                    .values()
+                    .filter(|transaction| {
+                        now.saturating_sub(transaction.sent_at) >= self.stuck_after_secs
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// drop the in-flight transactions below the mined nonce of the account
+    /// on chain, returns how many were dropped
+    pub async fn reconcile<M: Middleware>(
+        &self,
+        client: &M,
+        chain_id: &str,
+        address: H160,
+    ) -> Result<usize, NonceError> {
+        let mined = client
+            .get_transaction_count(address, Some(BlockNumber::Latest.into()))
+            .await
+            .map_err(|e| NonceError::NetworkError(e.to_string()))?
+            .as_u64();
+        let mut accounts = self.accounts.lock().await;
+        let Some(account) = accounts.get_mut(&Self::key(chain_id, address)) else {
+            return Ok(0);
+        };
+        let before = account.in_flight.len();
+        account.in_flight = account.in_flight.split_off(&mined);
+        account.released = account.released.split_off(&mined);
+        Ok(before - account.in_flight.len())
+    }
+}
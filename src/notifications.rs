@@ -0,0 +1,695 @@
+/// Payment notification delivery module.
+use crate::events::{EventListener, PaymentEvent, PaymentEventKind};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum NotificationError {
+    /// the contact has no address for the channel
+    NoAddress,
+    /// the channel refuses the address, e.g. a webhook on a host that is not
+    /// allowlisted or credentials for a remote relay, not retried
+    AddressRefused(String),
+    DeliveryFailed(String),
+}
+
+impl std::fmt::Display for NotificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoAddress => write!(f, "Contact has no address for the channel"),
+            Self::AddressRefused(msg) => write!(f, "Contact address refused: {}", msg),
+            Self::DeliveryFailed(msg) => write!(f, "Notification delivery failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for NotificationError {}
+
+/// Where the confirmations of a payer go, attached to a session, to an
+/// account or to the request context as extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub webhook_url: Option<String>,
+    /// Slack incoming webhook URL
+    pub slack_webhook_url: Option<String>,
+}
+
+impl Contact {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn with_email(mut self, email: &str) -> Self {
+        self.email = Some(email.to_string());
+        self
+    }
+
+    pub fn with_webhook_url(mut self, webhook_url: &str) -> Self {
+        self.webhook_url = Some(webhook_url.to_string());
+        self
+    }
+
+    pub fn with_slack_webhook_url(mut self, slack_webhook_url: &str) -> Self {
+        self.slack_webhook_url = Some(slack_webhook_url.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoticeKind {
+    /// a payment was verified on chain
+    PaymentConfirmed,
+    /// a request was paid from a budget account
+    BudgetDrawn,
+}
+
+/// Details of a payment, the placeholders of [`MessageTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentNotice {
+    pub kind: NoticeKind,
+    pub timestamp: u64,
+    pub merchant: String,
+    pub payer: String,
+    pub resource: String,
+    /// in the smallest unit of the currency
+    pub amount: String,
+    pub nonce: Option<String>,
+    pub account: Option<String>,
+    pub chain: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub explorer_url: Option<String>,
+}
+
+/// Rendered notification handed to delivery channels.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    pub subject: String,
+    pub body: String,
+    pub notice: PaymentNotice,
+}
+
+/// Subject and body with `{placeholder}` fields, `{merchant}`, `{payer}`,
+/// `{resource}`, `{amount}`, `{nonce}`, `{account}`, `{chain}`,
+/// `{transaction_hash}` and `{explorer_url}`, unknown fields are left as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageTemplate {
+    pub subject: String,
+    pub body: String,
+}
+
+impl Default for MessageTemplate {
+    fn default() -> Self {
+        Self {
+            subject: "Payment received by {merchant}".to_string(),
+            body: "Your payment of {amount} for {resource} was received.\n\n\
+                   Payer: {payer}\nChain: {chain}\nTransaction: {transaction_hash}\n\
+                   {explorer_url}\n"
+                .to_string(),
+        }
+    }
+}
+
+impl MessageTemplate {
+    pub fn new(subject: &str, body: &str) -> Self {
+        Self {
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    fn fill(text: &str, notice: &PaymentNotice) -> String {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        [
+            ("{merchant}", notice.merchant.clone()),
+            ("{payer}", notice.payer.clone()),
+            ("{resource}", notice.resource.clone()),
+            ("{amount}", notice.amount.clone()),
+            ("{nonce}", optional(&notice.nonce)),
+            ("{account}", optional(&notice.account)),
+            ("{chain}", optional(&notice.chain)),
+            ("{transaction_hash}", optional(&notice.transaction_hash)),
+            ("{explorer_url}", optional(&notice.explorer_url)),
+        ]
+        .iter()
+        .fold(text.to_string(), |text, (field, value)| {
+            text.replace(field, value)
+        })
+    }
+
+    pub fn render(&self, notice: &PaymentNotice) -> Notification {
+        Notification {
+            subject: Self::fill(&self.subject, notice),
+            body: Self::fill(&self.body, notice),
+            notice: notice.clone(),
+        }
+    }
+}
+
+/// Sends notifications over one medium, e.g. mail or a chat webhook.
+#[async_trait]
+pub trait DeliveryChannel: Send + Sync {
+    /// [`NotificationError::NoAddress`] when the contact cannot be reached
+    /// over the channel, such contacts are skipped without retry
+    async fn deliver(
+        &self,
+        contact: &Contact,
+        notification: &Notification,
+    ) -> Result<(), NotificationError>;
+}
+
+/// URL of a contact checked to be `https` on one of the allowed hosts, the
+/// contact may come from the request so it must not pick any endpoint
+fn contact_url<'a>(url: &'a str, allowed_hosts: &[String]) -> Result<&'a str, NotificationError> {
+    let parsed =
+        url::Url::parse(url).map_err(|e| NotificationError::AddressRefused(e.to_string()))?;
+    if parsed.scheme() != "https" {
+        return Err(NotificationError::AddressRefused(format!(
+            "{} is not https",
+            url
+        )));
+    }
+    match parsed.host_str() {
+        Some(host)
+            if allowed_hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(host)) =>
+        {
+            Ok(url)
+        }
+        _ => Err(NotificationError::AddressRefused(format!(
+            "{} is not on an allowed host",
+            url
+        ))),
+    }
+}
+
+/// Posts the notification as JSON to the webhook of the contact, or to a
+/// fixed URL for every contact.
+///
+/// Webhooks of contacts have to be `https` on a host allowed with
+/// [`with_allowed_hosts`](Self::with_allowed_hosts), none by default.
+pub struct WebhookChannel {
+    url: Option<String>,
+    allowed_hosts: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl Default for WebhookChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookChannel {
+    pub fn new() -> Self {
+        Self {
+            url: None,
+            allowed_hosts: Vec::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// post every notification to `url`, e.g. a CRM endpoint
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// hosts the webhooks of contacts may point at
+    pub fn with_allowed_hosts(mut self, hosts: &[&str]) -> Self {
+        self.allowed_hosts = hosts.iter().map(|host| host.to_string()).collect();
+        self
+    }
+}
+
+#[async_trait]
+impl DeliveryChannel for WebhookChannel {
+    async fn deliver(
+        &self,
+        contact: &Contact,
+        notification: &Notification,
+    ) -> Result<(), NotificationError> {
+        let url = match (&self.url, &contact.webhook_url) {
+            (Some(url), _) => url.as_str(),
+            (None, Some(url)) => contact_url(url, &self.allowed_hosts)?,
+            (None, None) => return Err(NotificationError::NoAddress),
+        };
+        self.client
+            .post(url)
+            .json(&serde_json::json!({ "contact": contact, "notification": notification }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| NotificationError::DeliveryFailed(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Posts the notification to a Slack incoming webhook, the one of the
+/// contact or a fixed one for every contact.
+///
+/// Webhooks of contacts have to be `https` on [`SLACK_WEBHOOK_HOST`] unless
+/// other hosts are allowed with [`with_allowed_hosts`](Self::with_allowed_hosts).
+pub struct SlackChannel {
+    webhook_url: Option<String>,
+    allowed_hosts: Vec<String>,
+    client: reqwest::Client,
+}
+
+/// host of Slack incoming webhooks
+pub const SLACK_WEBHOOK_HOST: &str = "hooks.slack.com";
+
+impl Default for SlackChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SlackChannel {
+    pub fn new() -> Self {
+        Self {
+            webhook_url: None,
+            allowed_hosts: vec![SLACK_WEBHOOK_HOST.to_string()],
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// post every notification to one channel, e.g. the sales channel
+    pub fn with_webhook_url(mut self, webhook_url: &str) -> Self {
+        self.webhook_url = Some(webhook_url.to_string());
+        self
+    }
+
+    /// hosts the webhooks of contacts may point at, replacing
+    /// [`SLACK_WEBHOOK_HOST`], e.g. for a Slack compatible chat
+    pub fn with_allowed_hosts(mut self, hosts: &[&str]) -> Self {
+        self.allowed_hosts = hosts.iter().map(|host| host.to_string()).collect();
+        self
+    }
+}
+
+#[async_trait]
+impl DeliveryChannel for SlackChannel {
+    async fn deliver(
+        &self,
+        contact: &Contact,
+        notification: &Notification,
+    ) -> Result<(), NotificationError> {
+        let url = match (&self.webhook_url, &contact.slack_webhook_url) {
+            (Some(url), _) => url.as_str(),
+            (None, Some(url)) => contact_url(url, &self.allowed_hosts)?,
+            (None, None) => return Err(NotificationError::NoAddress),
+        };
+        let text = format!("*{}*\n{}", notification.subject, notification.body);
+        self.client
+            .post(url)
+            .json(&serde_json::json!({ "text": text }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| NotificationError::DeliveryFailed(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "native")]
+pub use smtp::SmtpChannel;
+
+#[cfg(feature = "native")]
+mod smtp {
+    use super::{Contact, DeliveryChannel, Notification, NotificationError};
+    use async_trait::async_trait;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpStream;
+
+    /// Sends plain text mail to the contact email through an SMTP relay.
+    ///
+    /// The connection is not encrypted, point it at a relay on a trusted
+    /// network, e.g. a local MTA or a sidecar, that forwards over TLS.
+    /// Credentials are only sent to a relay on a loopback address.
+    pub struct SmtpChannel {
+        /// `host:port` of the relay
+        relay: String,
+        from: String,
+        credentials: Option<(String, String)>,
+        helo_name: String,
+    }
+
+    impl SmtpChannel {
+        pub fn new(relay: &str, from: &str) -> Self {
+            Self {
+                relay: relay.to_string(),
+                from: from.to_string(),
+                credentials: None,
+                helo_name: "localhost".to_string(),
+            }
+        }
+
+        /// authenticate with `AUTH PLAIN`, refused unless the relay is on a
+        /// loopback address as the password would cross the network in clear
+        pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+            self.credentials = Some((username.to_string(), password.to_string()));
+            self
+        }
+
+        pub fn with_helo_name(mut self, helo_name: &str) -> Self {
+            self.helo_name = helo_name.to_string();
+            self
+        }
+
+        async fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), NotificationError> {
+            let failed = |e: std::io::Error| NotificationError::DeliveryFailed(e.to_string());
+            let stream = TcpStream::connect(&self.relay).await.map_err(failed)?;
+            if self.credentials.is_some() && !stream.peer_addr().map_err(failed)?.ip().is_loopback()
+            {
+                return Err(NotificationError::AddressRefused(format!(
+                    "credentials are only sent to a loopback relay, not {}",
+                    self.relay
+                )));
+            }
+            let mut stream = BufReader::new(stream);
+            expect_reply(&mut stream, 220).await?;
+            command(&mut stream, &format!("EHLO {}", self.helo_name), 250).await?;
+            if let Some((username, password)) = &self.credentials {
+                let token = STANDARD.encode(format!("\0{}\0{}", username, password));
+                command(&mut stream, &format!("AUTH PLAIN {}", token), 235).await?;
+            }
+            command(&mut stream, &format!("MAIL FROM:<{}>", self.from), 250).await?;
+            command(&mut stream, &format!("RCPT TO:<{}>", to), 250).await?;
+            command(&mut stream, "DATA", 354).await?;
+            let mut message = format!(
+                "From: <{}>\r\nTo: <{}>\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n",
+                self.from, to, subject
+            );
+            for line in body.lines() {
+                // dot stuffing, a lone dot would end the message
+                if line.starts_with('.') {
+                    message.push('.');
+                }
+                message.push_str(line);
+                message.push_str("\r\n");
+            }
+            message.push('.');
+            command(&mut stream, &message, 250).await?;
+            command(&mut stream, "QUIT", 221).await?;
+            Ok(())
+        }
+    }
+
+    /// send one command line and check the reply code
+    async fn command(
+        stream: &mut BufReader<TcpStream>,
+        line: &str,
+        code: u16,
+    ) -> Result<(), NotificationError> {
+        stream
+            .get_mut()
+            .write_all(format!("{}\r\n", line).as_bytes())
+            .await
+            .map_err(|e| NotificationError::DeliveryFailed(e.to_string()))?;
+        expect_reply(stream, code).await
+    }
+
+    /// read a possibly multi-line SMTP reply and check its code
+    async fn expect_reply<R>(reader: &mut BufReader<R>, code: u16) -> Result<(), NotificationError>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_line(&mut line)
+                .await
+                .map_err(|e| NotificationError::DeliveryFailed(e.to_string()))?;
+            if read == 0 {
+                return Err(NotificationError::DeliveryFailed(
+                    "connection closed by the relay".to_string(),
+                ));
+            }
+            // continuation lines have a dash after the code
+            if line.as_bytes().get(3) != Some(&b'-') {
+                break;
+            }
+        }
+        match line.get(..3).and_then(|reply| reply.parse::<u16>().ok()) {
+            Some(reply) if reply == code => Ok(()),
+            _ => Err(NotificationError::DeliveryFailed(format!(
+                "unexpected reply: {}",
+                line.trim_end()
+            ))),
+        }
+    }
+
+    #[async_trait]
+    impl DeliveryChannel for SmtpChannel {
+        async fn deliver(
+            &self,
+            contact: &Contact,
+            notification: &Notification,
+        ) -> Result<(), NotificationError> {
+            let to = contact.email.as_ref().ok_or(NotificationError::NoAddress)?;
+            // header fields must stay on one line
+            let single_line = |text: &str| text.replace(['\r', '\n'], " ");
+            self.send(
+                &single_line(to),
+                &single_line(&notification.subject),
+                &notification.body,
+            )
+            .await
+        }
+    }
+}
+
+/// Sends payment confirmations to the contact of the payer over every
+/// delivery channel, with retries.
+///
+/// The contact is taken from a [`Contact`] extension of the request context,
+/// then from the contact attached to the session, then from the one attached
+/// to the payer address or budget account. Register the notifier as an
+/// engine event listener, deliveries run in the background on the current
+/// tokio runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::notifications::{Contact, Notifier, SlackChannel, SmtpChannel};
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let notifier = Arc::new(
+///     Notifier::new("Example Data")
+///         .with_channel(Arc::new(SmtpChannel::new("127.0.0.1:25", "billing@example.com")))
+///         .with_channel(Arc::new(SlackChannel::new()))
+///         .with_retries(5, 30),
+/// );
+/// notifier.attach_to_account(
+///     "research-agent",
+///     Contact::new().with_email("finance@example.org"),
+/// );
+/// let engine = X402::from_default_config()?.with_event_listener(notifier.clone());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Notifier {
+    merchant: String,
+    channels: Vec<Arc<dyn DeliveryChannel>>,
+    template: MessageTemplate,
+    max_attempts: u32,
+    retry_base_secs: u64,
+    session_contacts: RwLock<HashMap<String, Contact>>,
+    account_contacts: RwLock<HashMap<String, Contact>>,
+}
+
+impl Notifier {
+    pub fn new(merchant: &str) -> Self {
+        Self {
+            merchant: merchant.to_string(),
+            channels: Vec::new(),
+            template: MessageTemplate::default(),
+            max_attempts: 3,
+            retry_base_secs: 10,
+            session_contacts: RwLock::new(HashMap::new()),
+            account_contacts: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_channel(mut self, channel: Arc<dyn DeliveryChannel>) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    pub fn with_template(mut self, template: MessageTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// attempts per channel, 3 by default, the delay after a failure starts
+    /// at `retry_base_secs` and doubles
+    pub fn with_retries(mut self, max_attempts: u32, retry_base_secs: u64) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_base_secs = retry_base_secs;
+        self
+    }
+
+    /// contact for the confirmation of one payment session
+    pub fn attach_to_session(&self, nonce: &str, contact: Contact) {
+        self.session_contacts
+            .write()
+            .unwrap()
+            .insert(nonce.to_string(), contact);
+    }
+
+    /// contact for every payment of a payer address or budget account
+    pub fn attach_to_account(&self, account: &str, contact: Contact) {
+        self.account_contacts
+            .write()
+            .unwrap()
+            .insert(account.to_lowercase(), contact);
+    }
+
+    fn account_contact(&self, account: &str) -> Option<Contact> {
+        self.account_contacts
+            .read()
+            .unwrap()
+            .get(&account.to_lowercase())
+            .cloned()
+    }
+
+    /// deliver one notification over every channel, retrying failures;
+    /// channels without an address for the contact are skipped
+    pub async fn send(
+        &self,
+        contact: &Contact,
+        notification: &Notification,
+    ) -> Vec<NotificationError> {
+        deliver_all(
+            &self.channels,
+            contact,
+            notification,
+            self.max_attempts,
+            self.retry_base_secs,
+        )
+        .await
+    }
+}
+
+async fn deliver_all(
+    channels: &[Arc<dyn DeliveryChannel>],
+    contact: &Contact,
+    notification: &Notification,
+    max_attempts: u32,
+    retry_base_secs: u64,
+) -> Vec<NotificationError> {
+    let mut errors = Vec::new();
+    for channel in channels {
+        let mut attempt = 0;
+        loop {
+            match channel.deliver(contact, notification).await {
+                Ok(()) | Err(NotificationError::NoAddress) => break,
+                Err(err @ NotificationError::AddressRefused(_)) => {
+                    errors.push(err);
+                    break;
+                }
+                Err(err) => {
+                    attempt += 1;
+                    if attempt >= max_attempts {
+                        errors.push(err);
+                        break;
+                    }
+                    let delay = retry_base_secs.saturating_mul(1 << (attempt - 1).min(16));
+                    tokio::time::sleep(Duration::from_secs(delay)).await;
+                }
+            }
+        }
+    }
+    errors
+}
+
+impl EventListener for Notifier {
+    fn on_event(&self, event: &PaymentEvent) {
+        let (notice, contact) = match &event.kind {
+            PaymentEventKind::PaymentVerified {
+                nonce,
+                verification,
+            } => {
+                let session_contact = self.session_contacts.write().unwrap().remove(nonce);
+                let notice = PaymentNotice {
+                    kind: NoticeKind::PaymentConfirmed,
+                    timestamp: event.timestamp,
+                    merchant: self.merchant.clone(),
+                    payer: event.user_address.clone(),
+                    resource: event.resource.to_string(),
+                    amount: verification.paid_amount.clone(),
+                    nonce: Some(nonce.clone()),
+                    account: None,
+                    chain: Some(verification.chain.chain_type.get_display_name()),
+                    transaction_hash: verification.transaction_hash.clone(),
+                    explorer_url: verification.explorer_url.clone(),
+                };
+                let contact = session_contact.or_else(|| self.account_contact(&event.user_address));
+                (notice, contact)
+            }
+            PaymentEventKind::BudgetDrawn {
+                account, amount, ..
+            } => {
+                let notice = PaymentNotice {
+                    kind: NoticeKind::BudgetDrawn,
+                    timestamp: event.timestamp,
+                    merchant: self.merchant.clone(),
+                    payer: event.user_address.clone(),
+                    resource: event.resource.to_string(),
+                    amount: amount.clone(),
+                    nonce: None,
+                    account: Some(account.clone()),
+                    chain: None,
+                    transaction_hash: None,
+                    explorer_url: None,
+                };
+                let contact = self
+                    .account_contact(account)
+                    .or_else(|| self.account_contact(&event.user_address));
+                (notice, contact)
+            }
+            _ => return,
+        };
+        let Some(contact) = event
+            .context
+            .extensions
+            .get::<Contact>()
+            .cloned()
+            .or(contact)
+        else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let notification = self.template.render(&notice);
+        let channels = self.channels.clone();
+        let (max_attempts, retry_base_secs) = (self.max_attempts, self.retry_base_secs);
+        runtime.spawn(async move {
+            deliver_all(
+                &channels,
+                &contact,
+                &notification,
+                max_attempts,
+                retry_base_secs,
+            )
+            .await;
+        });
+    }
+}
@@ -0,0 +1,240 @@
+/// Event outbox module.
+use crate::canonical;
+use crate::clock::{Clock, SystemClock};
+use crate::crypto::CryptoSuite;
+use crate::signing::SignatureError;
+use crate::store::{OutboxEntry, OutboxStore, StoreError};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// HTTP header carrying the dedupe key of a delivered event.
+pub const EVENT_ID_HEADER: &str = "X-Event-Id";
+
+/// HTTP header carrying `<algorithm>=<signature>` of a signed webhook body.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// domain prefix of webhook signatures, so they cannot be replayed as
+/// challenge or receipt signatures
+pub const WEBHOOK_DOMAIN: &[u8] = b"x402-webhook:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkError {
+    Network(String),
+    /// the consumer refused the event, it is retried like a network failure
+    Rejected(String),
+}
+
+impl std::fmt::Display for SinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Network(msg) => write!(f, "Network error: {}", msg),
+            Self::Rejected(msg) => write!(f, "Event rejected: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SinkError {}
+
+/// Destination of outbox events. An event may be delivered more than once,
+/// consumers dedupe on [`OutboxEntry::id`].
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    async fn deliver(&self, entry: &OutboxEntry) -> Result<(), SinkError>;
+}
+
+/// Body posted by [`WebhookSink`]: the canonical JSON of `{ "id", "event" }`,
+/// so a signature over it survives re-encoding by the consumer.
+pub fn webhook_body(entry: &OutboxEntry) -> Result<Vec<u8>, SinkError> {
+    canonical::to_canonical_vec(&serde_json::json!({ "id": entry.id, "event": entry.event }))
+        .map_err(|e| SinkError::Rejected(e.to_string()))
+}
+
+/// Posts every event as [`webhook_body`] to a URL, with the id also in the
+/// [`EVENT_ID_HEADER`] header.
+pub struct WebhookSink {
+    url: String,
+    client: reqwest::Client,
+    signer: Option<Arc<dyn CryptoSuite>>,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: reqwest::Client::new(),
+            signer: None,
+        }
+    }
+
+    /// sign every body with the suite, see [`verify_webhook`]
+    pub fn with_signer(mut self, signer: Arc<dyn CryptoSuite>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+}
+
+/// [`WEBHOOK_SIGNATURE_HEADER`] value of a body
+pub fn sign_webhook(signer: &dyn CryptoSuite, body: &[u8]) -> Result<String, SignatureError> {
+    let mut message = WEBHOOK_DOMAIN.to_vec();
+    message.extend(body);
+    Ok(format!("{}={}", signer.algorithm(), signer.sign(&message)?))
+}
+
+/// check the [`WEBHOOK_SIGNATURE_HEADER`] of a received body, consumers
+/// hold the same suite with the public key or shared secret
+pub fn verify_webhook(
+    suite: &dyn CryptoSuite,
+    body: &[u8],
+    header: &str,
+) -> Result<(), SignatureError> {
+    let (algorithm, signature) = header
+        .split_once('=')
+        .ok_or_else(|| SignatureError::InvalidSignature(header.to_string()))?;
+    if algorithm != suite.algorithm() {
+        return Err(SignatureError::UnsupportedAlgorithm(algorithm.to_string()));
+    }
+    let mut message = WEBHOOK_DOMAIN.to_vec();
+    message.extend(body);
+    suite.verify(&message, signature)
+}
+
+#[async_trait]
+impl EventSink for WebhookSink {
+    async fn deliver(&self, entry: &OutboxEntry) -> Result<(), SinkError> {
+        let body = webhook_body(entry)?;
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(EVENT_ID_HEADER, &entry.id)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        if let Some(signer) = &self.signer {
+            let signature = sign_webhook(signer.as_ref(), &body)
+                .map_err(|e| SinkError::Rejected(e.to_string()))?;
+            request = request.header(WEBHOOK_SIGNATURE_HEADER, signature);
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| SinkError::Network(e.to_string()))?;
+        response
+            .error_for_status()
+            .map_err(|e| SinkError::Rejected(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Outcome of one [`OutboxDispatcher::drain`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrainReport {
+    pub delivered: usize,
+    /// failed deliveries scheduled for a later attempt
+    pub retried: usize,
+    /// entries given up after their last attempt
+    pub failed: usize,
+}
+
+/// Delivers the events of an outbox to a sink with at-least-once
+/// semantics: an entry is only removed once the sink accepted it, failures
+/// are retried with exponential backoff.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::outbox::{OutboxDispatcher, WebhookSink};
+/// use x402_sdk::store::FileOutboxStore;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let outbox = Arc::new(FileOutboxStore::open("outbox.json")?);
+/// let engine = X402::from_default_config()?.with_outbox_store(outbox.clone());
+/// let dispatcher = OutboxDispatcher::new(
+///     outbox,
+///     Arc::new(WebhookSink::new("https://example.org/x402-events")),
+/// );
+/// // on an interval, e.g. in a worker task
+/// let report = dispatcher.drain().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct OutboxDispatcher {
+    store: Arc<dyn OutboxStore>,
+    sink: Arc<dyn EventSink>,
+    clock: Arc<dyn Clock>,
+    batch_size: usize,
+    max_attempts: u32,
+    retry_base_secs: u64,
+    retry_max_secs: u64,
+}
+
+impl OutboxDispatcher {
+    pub fn new(store: Arc<dyn OutboxStore>, sink: Arc<dyn EventSink>) -> Self {
+        Self {
+            store,
+            sink,
+            clock: Arc::new(SystemClock),
+            batch_size: 100,
+            max_attempts: 10,
+            retry_base_secs: 5,
+            retry_max_secs: 3600,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// entries delivered per drain, 100 by default
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// attempts per entry, 10 by default, the delay after a failure starts
+    /// at `retry_base_secs` and doubles up to `retry_max_secs`
+    pub fn with_retries(
+        mut self,
+        max_attempts: u32,
+        retry_base_secs: u64,
+        retry_max_secs: u64,
+    ) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_base_secs = retry_base_secs;
+        self.retry_max_secs = retry_max_secs;
+        self
+    }
+
+    /// deliver the due entries, oldest first
+    pub async fn drain(&self) -> Result<DrainReport, StoreError> {
+        let now = self.clock.now();
+        let mut report = DrainReport::default();
+        for mut entry in self.store.due(now, self.batch_size).await? {
+            match self.sink.deliver(&entry).await {
+                Ok(()) => {
+                    self.store.acknowledge(&entry.id).await?;
+                    report.delivered += 1;
+                    continue;
+                }
+                Err(err) => {
+                    entry.attempts += 1;
+                    entry.last_error = Some(err.to_string());
+                }
+            }
+            if entry.attempts >= self.max_attempts {
+                entry.failed = true;
+                report.failed += 1;
+            } else {
+                let backoff = self
+                    .retry_base_secs
+                    .saturating_mul(1u64 << (entry.attempts - 1).min(32))
+                    .min(self.retry_max_secs);
+                entry.next_attempt_at = now + backoff;
+                report.retried += 1;
+            }
+            self.store.update(&entry).await?;
+        }
+        Ok(report)
+    }
+}
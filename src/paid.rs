@@ -0,0 +1,164 @@
+/// Declarative paid handler module.
+use crate::pricing::{PricingError, PricingRule};
+use crate::resource::ResourcePattern;
+use crate::types::{ChainType, Currency, EvmChain, SolanaChain};
+
+/// address or mint of a stablecoin priced by its USD value
+fn stablecoin(symbol: &str, chain: &ChainType) -> Option<&'static str> {
+    let address = match (symbol, chain) {
+        ("USDC", ChainType::Evm(EvmChain::Ethereum)) => {
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+        }
+        ("USDC", ChainType::Evm(EvmChain::Base)) => "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+        ("USDC", ChainType::Evm(EvmChain::Polygon)) => "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359",
+        ("USDC", ChainType::Evm(EvmChain::Arbitrum)) => {
+            "0xaf88d065e77c8cC2239327C5EDb3A432268e5831"
+        }
+        ("USDC", ChainType::Evm(EvmChain::Optimism)) => {
+            "0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85"
+        }
+        ("USDC", ChainType::Evm(EvmChain::Avalanche)) => {
+            "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E"
+        }
+        ("USDC", ChainType::Solana(SolanaChain::Mainnet)) => {
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+        }
+        ("USDT", ChainType::Evm(EvmChain::Ethereum)) => {
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7"
+        }
+        ("USDT", ChainType::Evm(EvmChain::Polygon)) => "0xc2132D05D31c914a87C6611C10748AEb04B58e8F",
+        ("USDT", ChainType::Solana(SolanaChain::Mainnet)) => {
+            "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"
+        }
+        _ => return None,
+    };
+    Some(address)
+}
+
+/// chain of a `chain = "..."` argument of `#[paid]`
+pub fn chain_by_name(name: &str) -> Option<ChainType> {
+    let chain = match name.to_ascii_lowercase().as_str() {
+        "ethereum" => ChainType::ethereum(),
+        "polygon" => ChainType::polygon(),
+        "bsc" => ChainType::bsc(),
+        "arbitrum" => ChainType::Evm(EvmChain::Arbitrum),
+        "optimism" => ChainType::Evm(EvmChain::Optimism),
+        "avalanche" => ChainType::Evm(EvmChain::Avalanche),
+        "base" => ChainType::Evm(EvmChain::Base),
+        "solana" => ChainType::solana_mainnet(),
+        _ => return None,
+    };
+    Some(chain)
+}
+
+/// whole units with up to `decimals` decimal places into the smallest unit
+fn parse_units(value: &str, decimals: u8) -> Option<u128> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if whole.is_empty() || !digits(whole) || !digits(fraction) {
+        return None;
+    }
+    if fraction.len() > decimals as usize {
+        return None;
+    }
+    format!("{}{:0<width$}", whole, fraction, width = decimals as usize)
+        .parse()
+        .ok()
+}
+
+/// Resolves a `"<amount> <symbol>"` price into an amount and currency.
+///
+/// The native symbol of the chain is charged in its smallest unit, `USD`
+/// in micro-USD of any token and stablecoins in micro-USD of the
+/// stablecoin, valued by the rate provider of the verifier.
+pub fn parse_price(
+    price: &str,
+    chain: Option<&ChainType>,
+) -> Result<(String, Currency), PricingError> {
+    let invalid = |msg: &str| PricingError::InvalidAmount(format!("{}: {}", price, msg));
+    let (value, symbol) = price
+        .trim()
+        .split_once(' ')
+        .ok_or_else(|| invalid("expected \"<amount> <symbol>\""))?;
+    let symbol = symbol.trim().to_ascii_uppercase();
+    if symbol == "USD" {
+        let micro_usd = parse_units(value, 6).ok_or_else(|| invalid("invalid amount"))?;
+        return Ok((
+            micro_usd.to_string(),
+            Currency::AnyToken { allowlist: vec![] },
+        ));
+    }
+    let chain = chain.ok_or_else(|| invalid("a chain is needed to price in a token"))?;
+    if chain.get_native_symbol().as_deref() == Some(symbol.as_str()) {
+        let decimals = chain
+            .get_native_decimals()
+            .ok_or_else(|| invalid("unknown native decimals"))?;
+        let units = parse_units(value, decimals).ok_or_else(|| invalid("invalid amount"))?;
+        return Ok((units.to_string(), Currency::Native));
+    }
+    let token = stablecoin(&symbol, chain)
+        .ok_or_else(|| invalid("unknown token on the chain"))?
+        .to_string();
+    let micro_usd = parse_units(value, 6).ok_or_else(|| invalid("invalid amount"))?;
+    Ok((
+        micro_usd.to_string(),
+        Currency::AnyToken {
+            allowlist: vec![token],
+        },
+    ))
+}
+
+/// Price of a handler annotated with `#[paid]`, generated as the `PAID`
+/// constant of a module named after the handler.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::paid::PaidHandler;
+/// use x402_sdk::pricing::RulePricing;
+///
+/// const REPORT: PaidHandler = PaidHandler {
+///     name: "report",
+///     price: "0.01 ETH",
+///     chain: Some("base"),
+///     route: Some("GET /reports/**"),
+///     description: None,
+/// };
+/// let pricing = RulePricing::new().with_paid_handler(&REPORT).unwrap();
+/// assert_eq!(pricing.rules()[0].amount, "10000000000000000");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaidHandler {
+    /// name of the handler function
+    pub name: &'static str,
+    /// `"<amount> <symbol>"`, see [`parse_price`]
+    pub price: &'static str,
+    /// the engine default chain when unset, which only `USD` prices allow
+    pub chain: Option<&'static str>,
+    /// `"[METHOD ]/path"` pattern of the handler, see [`ResourcePattern`]
+    pub route: Option<&'static str>,
+    pub description: Option<&'static str>,
+}
+
+impl PaidHandler {
+    /// pricing rule charging the handler price on resources matching the
+    /// pattern
+    pub fn pricing_rule(&self, pattern: &str) -> Result<PricingRule, PricingError> {
+        let chain = match self.chain {
+            Some(name) => Some(chain_by_name(name).ok_or_else(|| {
+                PricingError::InvalidAmount(format!("{}: unknown chain {}", self.name, name))
+            })?),
+            None => None,
+        };
+        let (amount, currency) = parse_price(self.price, chain.as_ref())?;
+        let mut rule =
+            PricingRule::new(ResourcePattern::parse(pattern), &amount).with_currency(currency);
+        if let Some(chain) = chain {
+            rule = rule.with_chain(chain);
+        }
+        if let Some(description) = self.description {
+            rule = rule.with_description(description);
+        }
+        Ok(rule)
+    }
+}
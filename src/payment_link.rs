@@ -0,0 +1,293 @@
+/// Deferred payment link module.
+use crate::core::{EngineError, X402};
+use crate::headers::encode_payment_required;
+use crate::notifications::{Contact, Notifier};
+use crate::receipt_page::escape;
+use crate::types::{Currency, VerificationResult, X402ProtocolResponse};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug)]
+pub enum PaymentLinkError {
+    Malformed(String),
+    InvalidSignature,
+    /// the session of the link expired at the given time
+    Expired {
+        expired_at: u64,
+    },
+    /// the engine answered the link request without a challenge, e.g. the
+    /// resource is free or the payer is refused
+    NotIssued {
+        http_status: u16,
+    },
+    Engine(EngineError),
+}
+
+impl std::fmt::Display for PaymentLinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(msg) => write!(f, "Malformed payment link: {}", msg),
+            Self::InvalidSignature => write!(f, "Invalid payment link signature"),
+            Self::Expired { expired_at } => {
+                write!(f, "Payment link expired at {}", expired_at)
+            }
+            Self::NotIssued { http_status } => {
+                write!(
+                    f,
+                    "No challenge issued for the link, status {}",
+                    http_status
+                )
+            }
+            Self::Engine(err) => write!(f, "Engine error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PaymentLinkError {}
+
+impl From<EngineError> for PaymentLinkError {
+    fn from(err: EngineError) -> Self {
+        Self::Engine(err)
+    }
+}
+
+/// Session encoded in a link token, everything the payment page shows so it
+/// renders without the engine holding the session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkClaims {
+    /// address of the original requester, the one redeeming the receipt
+    pub payer: String,
+    pub resource_path: String,
+    pub challenge: X402ProtocolResponse,
+}
+
+/// Link to a payment session, sent out of band, e.g. by email or chat.
+#[derive(Debug, Clone)]
+pub struct PaymentLink {
+    pub url: String,
+    pub token: String,
+    /// nonce of the session, what the requester redeems
+    pub nonce: String,
+    pub expires_at: Option<u64>,
+    pub challenge: X402ProtocolResponse,
+}
+
+/// Issues payment links for non-interactive purchases: the challenge of a
+/// request is turned into a signed, standalone link, the payment can be
+/// made at any time before the session expires and the original requester
+/// redeems the receipt later with the link token.
+///
+/// A contact attached to the link is notified by the [`Notifier`] once the
+/// payment is verified, e.g. with a webhook.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::notifications::{Contact, Notifier, WebhookChannel};
+/// use x402_sdk::payment_link::PaymentLinks;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let webhooks = WebhookChannel::new().with_allowed_hosts(&["shop.example.com"]);
+/// let notifier = Arc::new(Notifier::new("Example Data").with_channel(Arc::new(webhooks)));
+/// let engine = Arc::new(X402::from_default_config()?.with_event_listener(notifier.clone()));
+/// let links = PaymentLinks::new(engine, "https://pay.example.com/links", b"link-secret")
+///     .with_notifier(notifier);
+/// let link = links
+///     .create(
+///         "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5",
+///         "/reports/q3.pdf",
+///         Some("5000"),
+///         Some(Contact::new().with_webhook_url("https://shop.example.com/paid")),
+///     )
+///     .await?;
+/// println!("pay at {}", link.url);
+/// // later, once the webhook fired
+/// let result = links.redeem(&link.token).await?;
+/// assert!(result.should_serve_content);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PaymentLinks {
+    engine: Arc<X402>,
+    base_url: String,
+    secret: Vec<u8>,
+    notifier: Option<Arc<Notifier>>,
+}
+
+impl PaymentLinks {
+    /// links are `{base_url}/{token}`
+    pub fn new(engine: Arc<X402>, base_url: &str, secret: &[u8]) -> Self {
+        Self {
+            engine,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            secret: secret.to_vec(),
+            notifier: None,
+        }
+    }
+
+    /// notifier the contacts of links are attached to, it has to be an
+    /// event listener of the engine
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    fn mac(&self, payload: &str) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    /// Issues a challenge for `resource_path` and turns it into a link.
+    pub async fn create(
+        &self,
+        payer: &str,
+        resource_path: &str,
+        custom_amount: Option<&str>,
+        contact: Option<Contact>,
+    ) -> Result<PaymentLink, PaymentLinkError> {
+        let result = self
+            .engine
+            .handle_access_request(payer, resource_path, None, custom_amount)
+            .await?;
+        let Some(challenge) = result.x402_response else {
+            return Err(PaymentLinkError::NotIssued {
+                http_status: result.http_status,
+            });
+        };
+        let claims = LinkClaims {
+            payer: payer.to_string(),
+            resource_path: resource_path.to_string(),
+            challenge: challenge.clone(),
+        };
+        let token = self.sign(&claims)?;
+        let nonce = challenge.payment_required.nonce.clone();
+        if let (Some(notifier), Some(contact)) = (&self.notifier, contact) {
+            notifier.attach_to_session(&nonce, contact);
+        }
+        Ok(PaymentLink {
+            url: format!("{}/{}", self.base_url, token),
+            token,
+            nonce,
+            expires_at: challenge.payment_required.expires_at,
+            challenge,
+        })
+    }
+
+    /// token of the claims, base64url JSON and its hex HMAC-SHA256
+    pub fn sign(&self, claims: &LinkClaims) -> Result<String, PaymentLinkError> {
+        let json =
+            serde_json::to_vec(claims).map_err(|e| PaymentLinkError::Malformed(e.to_string()))?;
+        let payload = URL_SAFE_NO_PAD.encode(json);
+        let signature = ethers::utils::hex::encode(self.mac(&payload).finalize().into_bytes());
+        Ok(format!("{}.{}", payload, signature))
+    }
+
+    /// check the signature of a token, in constant time, and decode it
+    pub fn verify(&self, token: &str) -> Result<LinkClaims, PaymentLinkError> {
+        let (payload, signature) = token
+            .trim()
+            .split_once('.')
+            .ok_or_else(|| PaymentLinkError::Malformed("missing signature".to_string()))?;
+        let signature = ethers::utils::hex::decode(signature)
+            .map_err(|_| PaymentLinkError::Malformed("invalid signature".to_string()))?;
+        self.mac(payload)
+            .verify_slice(&signature)
+            .map_err(|_| PaymentLinkError::InvalidSignature)?;
+        let json = URL_SAFE_NO_PAD
+            .decode(payload)
+            .map_err(|e| PaymentLinkError::Malformed(e.to_string()))?;
+        serde_json::from_slice(&json).map_err(|e| PaymentLinkError::Malformed(e.to_string()))
+    }
+
+    /// Standalone HTML payment page of a link, refused once its session
+    /// expired. The challenge is embedded as the `x402-payment-required`
+    /// meta tag, encoded like the `X-Payment-Required` header, for wallets
+    /// opening the page.
+    pub fn page(&self, token: &str) -> Result<String, PaymentLinkError> {
+        let claims = self.verify(token)?;
+        let request = &claims.challenge.payment_required;
+        if let Some(expires_at) = request.expires_at
+            && self.engine.clock().now() >= expires_at
+        {
+            return Err(PaymentLinkError::Expired {
+                expired_at: expires_at,
+            });
+        }
+        let challenge = encode_payment_required(&claims.challenge)
+            .map_err(|e| PaymentLinkError::Malformed(e.to_string()))?;
+        let currency = match &request.currency {
+            Currency::Native => request
+                .chain
+                .chain_type
+                .get_native_symbol()
+                .unwrap_or_default(),
+            Currency::Token { address, .. } => address.clone(),
+            Currency::AnyToken { allowlist } if allowlist.is_empty() => "USD (micro)".to_string(),
+            Currency::AnyToken { allowlist } => format!("USD (micro), in {}", allowlist.join(", ")),
+        };
+        let mut rows = vec![
+            ("Resource", claims.resource_path.clone()),
+            ("Amount", format!("{} {}", request.amount, currency)),
+            ("Pay to", request.recipient.clone()),
+            ("Chain", request.chain.chain_type.get_display_name()),
+            ("Reference", request.nonce.clone()),
+        ];
+        if let Some(expires_at) = request.expires_at {
+            rows.push(("Expires", expires_at.to_string()));
+        }
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"x402-payment-required\" content=\"{}\">\n<title>Payment request</title>\n\
+             <style>body{{font-family:sans-serif;max-width:40em;margin:2em auto}}\
+             td{{padding:.25em 1em .25em 0;vertical-align:top;word-break:break-all}}</style>\n\
+             </head>\n<body>\n<h1>Payment request</h1>\n",
+            escape(&challenge)
+        );
+        if let Some(description) = &request.description {
+            html.push_str(&format!("<p>{}</p>\n", escape(description)));
+        }
+        html.push_str("<table>\n");
+        for (label, value) in rows {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                label,
+                escape(&value)
+            ));
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        Ok(html)
+    }
+
+    /// Checks the payment of a link, e.g. from the payment page once the
+    /// payer sent it, the contact of the link is notified when it is
+    /// verified.
+    pub async fn confirm(&self, token: &str) -> Result<bool, PaymentLinkError> {
+        Ok(self.redeem(token).await?.should_serve_content)
+    }
+
+    /// Redeems the payment of a link for its requester, the engine issues
+    /// the receipt once the payment is verified and a new challenge while
+    /// it is not.
+    pub async fn redeem(&self, token: &str) -> Result<VerificationResult, PaymentLinkError> {
+        let claims = self.verify(token)?;
+        Ok(self
+            .engine
+            .handle_access_request(
+                &claims.payer,
+                &claims.resource_path,
+                Some(&claims.challenge.payment_required.nonce),
+                None,
+            )
+            .await?)
+    }
+}
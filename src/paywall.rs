@@ -0,0 +1,214 @@
+/// Static-site paywall module.
+use crate::cache::{EdgeToken, EdgeTokenError, EdgeTokenIssuer};
+use crate::context::RequestContext;
+use crate::core::{EngineError, X402};
+use crate::resource::Resource;
+use crate::types::VerificationResult;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// query parameter carrying the expiry of a signed URL
+pub const EXPIRES_PARAM: &str = "expires";
+/// query parameter carrying the signature of a signed URL
+pub const SIGNATURE_PARAM: &str = "signature";
+
+#[derive(Debug)]
+pub enum SignedUrlError {
+    Malformed(String),
+    Expired,
+    InvalidSignature,
+}
+
+impl std::fmt::Display for SignedUrlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(msg) => write!(f, "Malformed signed URL: {}", msg),
+            Self::Expired => write!(f, "Signed URL expired"),
+            Self::InvalidSignature => write!(f, "Invalid signed URL signature"),
+        }
+    }
+}
+
+impl std::error::Error for SignedUrlError {}
+
+/// Time-limited URLs to object paths, signed with HMAC-SHA256 over the path
+/// and the expiry and checked by the server holding the objects.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::paywall::UrlSigner;
+///
+/// let signer = UrlSigner::new("https://files.example.com", b"object-secret");
+/// let url = signer.sign("/reports/q3.pdf", 1_700_000_600);
+/// let target = url.trim_start_matches("https://files.example.com");
+/// assert!(signer.verify(target, 1_700_000_000).is_ok());
+/// assert!(signer.verify(target, 1_700_000_600).is_err());
+/// ```
+#[derive(Clone)]
+pub struct UrlSigner {
+    base_url: String,
+    secret: Vec<u8>,
+}
+
+impl UrlSigner {
+    pub fn new(base_url: &str, secret: &[u8]) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            secret: secret.to_vec(),
+        }
+    }
+
+    fn mac(&self, path: &str, expires_at: u64) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(path.as_bytes());
+        mac.update(b"\n");
+        mac.update(expires_at.to_string().as_bytes());
+        mac
+    }
+
+    /// URL granting access to `path` until `expires_at`
+    pub fn sign(&self, path: &str, expires_at: u64) -> String {
+        let path = Resource::path_of(path);
+        let signature =
+            ethers::utils::hex::encode(self.mac(&path, expires_at).finalize().into_bytes());
+        format!(
+            "{}{}?{}={}&{}={}",
+            self.base_url, path, EXPIRES_PARAM, expires_at, SIGNATURE_PARAM, signature
+        )
+    }
+
+    /// check a requested `path?query` target, in constant time
+    pub fn verify(&self, target: &str, now: u64) -> Result<(), SignedUrlError> {
+        let resource =
+            Resource::new("GET", target).map_err(|e| SignedUrlError::Malformed(e.to_string()))?;
+        let expires_at: u64 = resource
+            .query_param(EXPIRES_PARAM)
+            .ok_or_else(|| SignedUrlError::Malformed(format!("missing {}", EXPIRES_PARAM)))?
+            .parse()
+            .map_err(|_| SignedUrlError::Malformed(format!("invalid {}", EXPIRES_PARAM)))?;
+        let signature = resource
+            .query_param(SIGNATURE_PARAM)
+            .ok_or_else(|| SignedUrlError::Malformed(format!("missing {}", SIGNATURE_PARAM)))?;
+        let signature = ethers::utils::hex::decode(signature)
+            .map_err(|_| SignedUrlError::Malformed(format!("invalid {}", SIGNATURE_PARAM)))?;
+        self.mac(&resource.path, expires_at)
+            .verify_slice(&signature)
+            .map_err(|_| SignedUrlError::InvalidSignature)?;
+        if now >= expires_at {
+            return Err(SignedUrlError::Expired);
+        }
+        Ok(())
+    }
+}
+
+impl EdgeTokenIssuer for UrlSigner {
+    fn issue(&self, resource: &Resource, expires_at: u64) -> Result<EdgeToken, EdgeTokenError> {
+        Ok(EdgeToken::SignedUrl(self.sign(&resource.path, expires_at)))
+    }
+}
+
+/// Outcome of a paywalled object request.
+#[derive(Debug, Clone)]
+pub enum PaywallOutcome {
+    /// the payment is verified, redirect the client to the signed URL
+    Granted { url: String, expires_at: u64 },
+    /// no access yet, usually a 402 challenge to return as is
+    Denied(Box<VerificationResult>),
+}
+
+/// Protects static files on object storage behind x402 without an
+/// application server: requests are verified by the engine and answered
+/// with a short-lived signed URL to the object.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::paywall::{PaywallOutcome, StaticPaywall, UrlSigner};
+///
+/// # async fn example(nonce: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+/// let engine = Arc::new(X402::from_default_config()?);
+/// let paywall = StaticPaywall::new(engine, UrlSigner::new("https://files.example.com", b"secret"))
+///     .with_object_prefix("/paid")
+///     .with_ttl(120);
+/// match paywall.request("0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5", "/reports/q3.pdf", nonce, None).await? {
+///     PaywallOutcome::Granted { url, .. } => println!("302 Location: {}", url),
+///     PaywallOutcome::Denied(result) => println!("{}", result.http_status),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct StaticPaywall {
+    engine: Arc<X402>,
+    signer: UrlSigner,
+    object_prefix: String,
+    ttl_secs: u64,
+}
+
+impl StaticPaywall {
+    pub fn new(engine: Arc<X402>, signer: UrlSigner) -> Self {
+        Self {
+            engine,
+            signer,
+            object_prefix: String::new(),
+            ttl_secs: 300,
+        }
+    }
+
+    /// prefix of object paths, prepended to the requested path
+    pub fn with_object_prefix(mut self, object_prefix: &str) -> Self {
+        self.object_prefix = object_prefix.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// lifetime of signed URLs, 300 seconds by default, never beyond the
+    /// receipt of the payment
+    pub fn with_ttl(mut self, ttl_secs: u64) -> Self {
+        self.ttl_secs = ttl_secs;
+        self
+    }
+
+    pub fn signer(&self) -> &UrlSigner {
+        &self.signer
+    }
+
+    /// verify access to the object at `path` and sign a URL for it
+    pub async fn request(
+        &self,
+        payer: &str,
+        path: &str,
+        payment_nonce: Option<&str>,
+        coupon_code: Option<&str>,
+    ) -> Result<PaywallOutcome, EngineError> {
+        let result = self
+            .engine
+            .handle_access_request_with_coupon(
+                payer,
+                path,
+                payment_nonce,
+                None,
+                coupon_code,
+                &RequestContext::default(),
+            )
+            .await?;
+        if !result.should_serve_content {
+            return Ok(PaywallOutcome::Denied(Box::new(result)));
+        }
+        let now = self.engine.clock().now();
+        let mut expires_at = now + self.ttl_secs;
+        if let Some(receipt) = &result.receipt {
+            expires_at = expires_at.min(receipt.expires_at);
+        }
+        let object_path = format!("{}{}", self.object_prefix, Resource::path_of(path));
+        Ok(PaywallOutcome::Granted {
+            url: self.signer.sign(&object_path, expires_at),
+            expires_at,
+        })
+    }
+}
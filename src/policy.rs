@@ -0,0 +1,64 @@
+/// Access policy module.
+use crate::context::RequestContext;
+use crate::resource::Resource;
+use async_trait::async_trait;
+use std::collections::HashSet;
+
+/// Outcome of an access policy evaluation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessDecision {
+    /// continue with the regular payment flow
+    RequirePayment,
+    /// serve the content without payment
+    Allow,
+    /// refuse the request with the given reason
+    Deny(String),
+}
+
+/// Evaluated before any payment handling, policies run in registration order
+/// and the first decision other than `RequirePayment` wins.
+#[async_trait]
+pub trait AccessPolicy: Send + Sync {
+    async fn evaluate(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        context: &RequestContext,
+    ) -> AccessDecision;
+}
+
+/// Grants free access to requests carrying a known API key header.
+#[derive(Debug, Clone)]
+pub struct ApiKeyPolicy {
+    header: String,
+    keys: HashSet<String>,
+}
+
+impl ApiKeyPolicy {
+    pub fn new(header: &str) -> Self {
+        Self {
+            header: header.to_string(),
+            keys: HashSet::new(),
+        }
+    }
+
+    pub fn with_key(mut self, key: &str) -> Self {
+        self.keys.insert(key.to_string());
+        self
+    }
+}
+
+#[async_trait]
+impl AccessPolicy for ApiKeyPolicy {
+    async fn evaluate(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        context: &RequestContext,
+    ) -> AccessDecision {
+        match context.header(&self.header) {
+            Some(key) if self.keys.contains(key) => AccessDecision::Allow,
+            _ => AccessDecision::RequirePayment,
+        }
+    }
+}
@@ -0,0 +1,391 @@
+/// Pricing module.
+use crate::context::RequestContext;
+use crate::invoice::Invoice;
+use crate::paid::PaidHandler;
+use crate::resource::{Resource, ResourcePattern};
+use crate::types::{AmountBounds, ChainType, Currency, PaymentMetadata};
+use crate::usage::UsageTracker;
+use crate::verifier::light_client::LIGHT_CLIENT_EXTENSION;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub enum PricingError {
+    InvalidAmount(String),
+    Unavailable(String),
+    /// a custom amount outside the bounds configured for its currency
+    OutOfBounds {
+        amount: String,
+        bounds: AmountBounds,
+    },
+}
+
+impl std::fmt::Display for PricingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAmount(msg) => write!(f, "Invalid amount: {}", msg),
+            Self::Unavailable(msg) => write!(f, "Price unavailable: {}", msg),
+            Self::OutOfBounds { amount, bounds } => write!(
+                f,
+                "Amount {} outside of the accepted range {}..={}",
+                amount,
+                bounds.min.unwrap_or(0),
+                bounds
+                    .max
+                    .map_or_else(|| "unbounded".to_string(), |max| max.to_string())
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PricingError {}
+
+/// Price quoted for a single access request.
+#[derive(Debug, Clone)]
+pub struct PriceQuote {
+    pub amount: String,
+    pub description: Option<String>,
+    /// currency of the amount, the service default currency when unset
+    pub currency: Option<Currency>,
+    /// chain the challenge is issued on, the configured default chain when
+    /// unset
+    pub chain: Option<ChainType>,
+    /// carried into the challenge, the engine fills in the resource and the
+    /// merchant name when unset
+    pub metadata: PaymentMetadata,
+    /// itemized bill of the quote, carried into the challenge metadata
+    pub invoice: Option<Invoice>,
+}
+
+impl PriceQuote {
+    pub fn new(amount: &str) -> Self {
+        Self {
+            amount: amount.to_string(),
+            description: None,
+            currency: None,
+            chain: None,
+            metadata: PaymentMetadata::default(),
+            invoice: None,
+        }
+    }
+
+    /// quote for the subtotal of an invoice
+    pub fn from_invoice(invoice: Invoice) -> Self {
+        Self::new(&invoice.subtotal).with_invoice(invoice)
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// issue the challenge on a configured chain other than the default one
+    pub fn with_chain(mut self, chain: ChainType) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: PaymentMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn with_invoice(mut self, invoice: Invoice) -> Self {
+        self.invoice = Some(invoice);
+        self
+    }
+}
+
+/// Decides how much a request has to pay.
+///
+/// Returning `None` falls back to the configured default amount, an explicit
+/// `custom_amount` passed to `handle_access_request` always takes precedence.
+#[async_trait]
+pub trait PricingProvider: Send + Sync {
+    async fn quote(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        context: &RequestContext,
+    ) -> Result<Option<PriceQuote>, PricingError>;
+
+    /// prices published in the discovery document
+    fn catalog(&self) -> Vec<PricingRule> {
+        Vec::new()
+    }
+}
+
+/// Price applied to every resource matching the pattern.
+#[derive(Debug, Clone)]
+pub struct PricingRule {
+    pub pattern: ResourcePattern,
+    pub amount: String,
+    pub description: Option<String>,
+    pub currency: Option<Currency>,
+    pub chain: Option<ChainType>,
+    pub metadata: PaymentMetadata,
+}
+
+impl PricingRule {
+    pub fn new(pattern: ResourcePattern, amount: &str) -> Self {
+        Self {
+            pattern,
+            amount: amount.to_string(),
+            description: None,
+            currency: None,
+            chain: None,
+            metadata: PaymentMetadata::default(),
+        }
+    }
+
+    /// charge the rule in another currency than the service default, e.g.
+    /// [`Currency::AnyToken`] with a micro-USD amount
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// charge the rule on another configured chain than the default one,
+    /// e.g. Solana for `/solana-api/**`
+    pub fn with_chain(mut self, chain: ChainType) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// description shown to payers of a locale, e.g. `zh-CN`
+    pub fn with_localized_description(mut self, locale: &str, description: &str) -> Self {
+        self.metadata = self.metadata.with_description(locale, description);
+        self
+    }
+
+    /// metadata carried into challenges for the rule
+    pub fn with_metadata(mut self, metadata: PaymentMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// recheck payments of at least `threshold`, in the units of the
+    /// challenge amount, against the light client of the engine
+    pub fn with_light_client_threshold(mut self, threshold: &str) -> Self {
+        self.metadata = self
+            .metadata
+            .with_extension(LIGHT_CLIENT_EXTENSION, serde_json::json!(threshold));
+        self
+    }
+}
+
+/// Ordered pricing rules, the first rule matching the resource wins.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::pricing::{PricingRule, RulePricing};
+/// use x402_sdk::resource::ResourcePattern;
+///
+/// let pricing = RulePricing::new()
+///     .with_rule(PricingRule::new(
+///         ResourcePattern::parse("GET /data").with_query_range("limit", None, Some(100.0)),
+///         "1000",
+///     ))
+///     .with_price("GET /data", "50000");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RulePricing {
+    rules: Vec<PricingRule>,
+}
+
+impl RulePricing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rule(mut self, rule: PricingRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// shorthand for a rule from a `"[METHOD ]/path"` pattern
+    pub fn with_price(self, pattern: &str, amount: &str) -> Self {
+        self.with_rule(PricingRule::new(ResourcePattern::parse(pattern), amount))
+    }
+
+    /// accept any of the allowlisted tokens worth `micro_usd`, valued at the
+    /// rates pinned when the challenge is issued, see [`Currency::AnyToken`]
+    pub fn with_usd_price(self, pattern: &str, micro_usd: u128, allowlist: &[&str]) -> Self {
+        self.with_rule(
+            PricingRule::new(ResourcePattern::parse(pattern), &micro_usd.to_string())
+                .with_currency(Currency::AnyToken {
+                    allowlist: allowlist.iter().map(|token| token.to_string()).collect(),
+                }),
+        )
+    }
+
+    /// price the route of a `#[paid]` handler
+    pub fn with_paid_handler(self, handler: &PaidHandler) -> Result<Self, PricingError> {
+        let route = handler
+            .route
+            .ok_or_else(|| PricingError::InvalidAmount(format!("{} has no route", handler.name)))?;
+        self.with_paid_route(route, handler)
+    }
+
+    /// price a route with the price of a `#[paid]` handler, for handlers
+    /// without a route or mounted under several
+    pub fn with_paid_route(
+        self,
+        pattern: &str,
+        handler: &PaidHandler,
+    ) -> Result<Self, PricingError> {
+        Ok(self.with_rule(handler.pricing_rule(pattern)?))
+    }
+
+    pub fn rules(&self) -> &[PricingRule] {
+        &self.rules
+    }
+
+    pub fn find_rule(&self, resource: &Resource) -> Option<&PricingRule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.pattern.matches(resource))
+    }
+}
+
+#[async_trait]
+impl PricingProvider for RulePricing {
+    async fn quote(
+        &self,
+        _user_address: &str,
+        resource: &Resource,
+        _context: &RequestContext,
+    ) -> Result<Option<PriceQuote>, PricingError> {
+        Ok(self.find_rule(resource).map(|rule| PriceQuote {
+            amount: rule.amount.clone(),
+            description: rule.description.clone(),
+            currency: rule.currency.clone(),
+            chain: rule.chain.clone(),
+            metadata: rule.metadata.clone(),
+            invoice: None,
+        }))
+    }
+
+    fn catalog(&self) -> Vec<PricingRule> {
+        self.rules.clone()
+    }
+}
+
+/// Price of a band of requests within a billing period.
+#[derive(Debug, Clone)]
+pub struct PriceTier {
+    /// last request number of the band, `None` for the open-ended last band
+    pub up_to: Option<u64>,
+    pub amount: String,
+}
+
+/// Volume pricing per payer, e.g. the first 100 requests at one price and
+/// the following ones at another.
+///
+/// Tiers are checked in order against the number of the next request in the
+/// current billing period of the [`UsageTracker`].
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use x402_sdk::pricing::TieredPricing;
+/// use x402_sdk::store::InMemoryUsageStore;
+/// use x402_sdk::usage::UsageTracker;
+///
+/// let usage = Arc::new(UsageTracker::new(Arc::new(InMemoryUsageStore::new()), 30 * 86_400));
+/// let pricing = TieredPricing::new(usage.clone())
+///     .with_tier(100, "1000")
+///     .with_final_tier("500")
+///     .for_pattern("/api/**");
+/// ```
+pub struct TieredPricing {
+    usage: Arc<UsageTracker>,
+    tiers: Vec<PriceTier>,
+    pattern: Option<ResourcePattern>,
+}
+
+impl TieredPricing {
+    pub fn new(usage: Arc<UsageTracker>) -> Self {
+        Self {
+            usage,
+            tiers: Vec::new(),
+            pattern: None,
+        }
+    }
+
+    /// requests up to and including `up_to` in the period pay `amount`
+    pub fn with_tier(mut self, up_to: u64, amount: &str) -> Self {
+        self.tiers.push(PriceTier {
+            up_to: Some(up_to),
+            amount: amount.to_string(),
+        });
+        self
+    }
+
+    /// price of every request past the bounded tiers
+    pub fn with_final_tier(mut self, amount: &str) -> Self {
+        self.tiers.push(PriceTier {
+            up_to: None,
+            amount: amount.to_string(),
+        });
+        self
+    }
+
+    /// only price resources matching the pattern, others fall through
+    pub fn for_pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(ResourcePattern::parse(pattern));
+        self
+    }
+
+    pub fn tier_for(&self, request_number: u64) -> Option<&PriceTier> {
+        self.tiers
+            .iter()
+            .find(|tier| tier.up_to.is_none_or(|up_to| request_number <= up_to))
+    }
+}
+
+#[async_trait]
+impl PricingProvider for TieredPricing {
+    async fn quote(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        _context: &RequestContext,
+    ) -> Result<Option<PriceQuote>, PricingError> {
+        if self
+            .pattern
+            .as_ref()
+            .is_some_and(|pattern| !pattern.matches(resource))
+        {
+            return Ok(None);
+        }
+        let usage = self
+            .usage
+            .usage(user_address)
+            .await
+            .map_err(|e| PricingError::Unavailable(e.to_string()))?;
+        Ok(self
+            .tier_for(usage + 1)
+            .map(|tier| PriceQuote::new(&tier.amount)))
+    }
+
+    /// the price of the first tier, the most a request pays
+    fn catalog(&self) -> Vec<PricingRule> {
+        let pattern = self
+            .pattern
+            .clone()
+            .unwrap_or_else(|| ResourcePattern::parse("/**"));
+        self.tiers
+            .first()
+            .map(|tier| PricingRule::new(pattern, &tier.amount).with_description("volume pricing"))
+            .into_iter()
+            .collect()
+    }
+}
@@ -0,0 +1,174 @@
+/// Typed primitive accessors module.
+use crate::address::AddressError;
+
+/// Failure to read a canonical string of a verification, see
+/// [`crate::address`], as a primitive type of `ethers` (feature
+/// `ethers-types`), `alloy` (feature `alloy-types`) or Solana (feature
+/// `solana-types`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrimitiveError {
+    Address(AddressError),
+    InvalidAmount(String),
+}
+
+impl std::fmt::Display for PrimitiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Address(err) => write!(f, "{}", err),
+            Self::InvalidAmount(amount) => write!(f, "Invalid amount: {}", amount),
+        }
+    }
+}
+
+impl std::error::Error for PrimitiveError {}
+
+impl From<AddressError> for PrimitiveError {
+    fn from(err: AddressError) -> Self {
+        Self::Address(err)
+    }
+}
+
+#[cfg(feature = "ethers-types")]
+mod ethers_types {
+    use super::PrimitiveError;
+    use crate::address::{AddressError, evm_address, evm_hash};
+    use crate::types::{PaymentVerification, TransactionLog};
+    use ethers::types::{H160, H256, U256};
+    use std::str::FromStr;
+
+    fn address(value: &str) -> Result<H160, PrimitiveError> {
+        let address = evm_address(value)?;
+        H160::from_str(&address).map_err(|_| AddressError::InvalidAddress(value.to_string()).into())
+    }
+
+    fn hash(value: &str) -> Result<H256, PrimitiveError> {
+        let hash = evm_hash(value)?;
+        H256::from_str(&hash).map_err(|_| AddressError::InvalidHash(value.to_string()).into())
+    }
+
+    fn amount(value: &str) -> Result<U256, PrimitiveError> {
+        U256::from_dec_str(value).map_err(|_| PrimitiveError::InvalidAmount(value.to_string()))
+    }
+
+    impl TransactionLog {
+        pub fn ethers_transaction_hash(&self) -> Result<H256, PrimitiveError> {
+            hash(&self.transaction_hash)
+        }
+
+        pub fn ethers_from(&self) -> Result<H160, PrimitiveError> {
+            address(&self.from)
+        }
+
+        pub fn ethers_to(&self) -> Result<H160, PrimitiveError> {
+            address(&self.to)
+        }
+
+        pub fn ethers_value(&self) -> Result<U256, PrimitiveError> {
+            amount(&self.value)
+        }
+    }
+
+    impl PaymentVerification {
+        pub fn ethers_transaction_hash(&self) -> Result<Option<H256>, PrimitiveError> {
+            self.transaction_hash.as_deref().map(hash).transpose()
+        }
+
+        pub fn ethers_paid_amount(&self) -> Result<U256, PrimitiveError> {
+            amount(&self.paid_amount)
+        }
+    }
+}
+
+#[cfg(feature = "alloy-types")]
+mod alloy_types {
+    use super::PrimitiveError;
+    use crate::address::{AddressError, evm_address, evm_hash};
+    use crate::types::{PaymentVerification, TransactionLog};
+    use alloy_primitives::{Address, B256, U256};
+    use std::str::FromStr;
+
+    fn address(value: &str) -> Result<Address, PrimitiveError> {
+        let address = evm_address(value)?;
+        Address::from_str(&address)
+            .map_err(|_| AddressError::InvalidAddress(value.to_string()).into())
+    }
+
+    fn hash(value: &str) -> Result<B256, PrimitiveError> {
+        let hash = evm_hash(value)?;
+        B256::from_str(&hash).map_err(|_| AddressError::InvalidHash(value.to_string()).into())
+    }
+
+    fn amount(value: &str) -> Result<U256, PrimitiveError> {
+        U256::from_str_radix(value, 10)
+            .map_err(|_| PrimitiveError::InvalidAmount(value.to_string()))
+    }
+
+    impl TransactionLog {
+        pub fn alloy_transaction_hash(&self) -> Result<B256, PrimitiveError> {
+            hash(&self.transaction_hash)
+        }
+
+        pub fn alloy_from(&self) -> Result<Address, PrimitiveError> {
+            address(&self.from)
+        }
+
+        pub fn alloy_to(&self) -> Result<Address, PrimitiveError> {
+            address(&self.to)
+        }
+
+        pub fn alloy_value(&self) -> Result<U256, PrimitiveError> {
+            amount(&self.value)
+        }
+    }
+
+    impl PaymentVerification {
+        pub fn alloy_transaction_hash(&self) -> Result<Option<B256>, PrimitiveError> {
+            self.transaction_hash.as_deref().map(hash).transpose()
+        }
+
+        pub fn alloy_paid_amount(&self) -> Result<U256, PrimitiveError> {
+            amount(&self.paid_amount)
+        }
+    }
+}
+
+#[cfg(feature = "solana-types")]
+mod solana_types {
+    use super::PrimitiveError;
+    use crate::address::{AddressError, solana_address};
+    use crate::types::{PaymentVerification, TransactionLog};
+    use solana_pubkey::Pubkey;
+    use std::str::FromStr;
+
+    fn pubkey(value: &str) -> Result<Pubkey, PrimitiveError> {
+        let address = solana_address(value)?;
+        Pubkey::from_str(&address)
+            .map_err(|_| AddressError::InvalidAddress(value.to_string()).into())
+    }
+
+    impl TransactionLog {
+        pub fn solana_from(&self) -> Result<Pubkey, PrimitiveError> {
+            pubkey(&self.from)
+        }
+
+        pub fn solana_to(&self) -> Result<Pubkey, PrimitiveError> {
+            pubkey(&self.to)
+        }
+
+        /// transferred lamports or token base units
+        pub fn solana_value(&self) -> Result<u64, PrimitiveError> {
+            self.value
+                .parse()
+                .map_err(|_| PrimitiveError::InvalidAmount(self.value.clone()))
+        }
+    }
+
+    impl PaymentVerification {
+        /// lamports or token base units paid
+        pub fn solana_paid_amount(&self) -> Result<u64, PrimitiveError> {
+            self.paid_amount
+                .parse()
+                .map_err(|_| PrimitiveError::InvalidAmount(self.paid_amount.clone()))
+        }
+    }
+}
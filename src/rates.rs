@@ -0,0 +1,301 @@
+/// Token exchange rate module.
+use crate::clock::{Clock, SystemClock};
+use crate::types::{ChainType, PaymentRequest};
+use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::{Bytes, H160, TransactionRequest, U256};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// token key the native currency of a chain is priced under
+pub const NATIVE_TOKEN: &str = "native";
+
+/// micro-USD per USD, the unit of [`Currency::AnyToken`](crate::types::Currency::AnyToken) amounts
+pub const MICRO_USD: u128 = 1_000_000;
+
+#[derive(Debug)]
+pub enum RateError {
+    UnknownToken(String),
+    Unavailable(String),
+}
+
+impl std::fmt::Display for RateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownToken(token) => write!(f, "No price for token: {}", token),
+            Self::Unavailable(msg) => write!(f, "Price source unavailable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RateError {}
+
+/// Source of token prices in USD, queried at verification time.
+#[async_trait]
+pub trait RateProvider: Send + Sync {
+    /// USD price of one whole token, `token` is the mint or contract address
+    async fn usd_price(&self, chain_type: &ChainType, token: &str) -> Result<f64, RateError>;
+}
+
+/// USD price a payment of `token` is valued at: the rate pinned in the
+/// quote of the request until it expires, the current price otherwise
+pub async fn request_usd_price(
+    rate_provider: Option<&Arc<dyn RateProvider>>,
+    payment_request: &PaymentRequest,
+    token: &str,
+) -> Result<f64, RateError> {
+    if let Some(rate) = payment_request.quoted_rate(token) {
+        return Ok(rate);
+    }
+    rate_provider
+        .ok_or_else(|| {
+            RateError::Unavailable("no rate provider for any-token payments".to_string())
+        })?
+        .usd_price(&payment_request.chain.chain_type, token)
+        .await
+}
+
+/// value in micro-USD of an amount in the smallest unit of a token
+pub fn usd_value_micros(amount: u128, decimals: u8, usd_price: f64) -> u128 {
+    let whole = amount as f64 / 10f64.powi(i32::from(decimals));
+    (whole * usd_price * MICRO_USD as f64).floor() as u128
+}
+
+/// whether a value covers the required amount less the slippage tolerance,
+/// in basis points
+pub fn within_slippage(value_micros: u128, required_micros: u128, slippage_bps: u32) -> bool {
+    let tolerance = required_micros * u128::from(slippage_bps.min(10_000)) / 10_000;
+    value_micros >= required_micros - tolerance
+}
+
+/// Fixed prices, e.g. for stablecoins pegged to the dollar.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::rates::StaticRateProvider;
+///
+/// let rates = StaticRateProvider::new()
+///     .with_price("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", 1.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StaticRateProvider {
+    prices: HashMap<String, f64>,
+}
+
+impl StaticRateProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_price(mut self, token: &str, usd_price: f64) -> Self {
+        self.prices.insert(token.to_lowercase(), usd_price);
+        self
+    }
+}
+
+#[async_trait]
+impl RateProvider for StaticRateProvider {
+    async fn usd_price(&self, _chain_type: &ChainType, token: &str) -> Result<f64, RateError> {
+        self.prices
+            .get(&token.to_lowercase())
+            .copied()
+            .ok_or_else(|| RateError::UnknownToken(token.to_string()))
+    }
+}
+
+/// Rate providers per chain, for services accepting any-token payments on
+/// several chains.
+#[derive(Default)]
+pub struct ChainRateProviders {
+    providers: HashMap<ChainType, Arc<dyn RateProvider>>,
+}
+
+impl ChainRateProviders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_provider(mut self, chain_type: ChainType, provider: Arc<dyn RateProvider>) -> Self {
+        self.providers.insert(chain_type, provider);
+        self
+    }
+}
+
+#[async_trait]
+impl RateProvider for ChainRateProviders {
+    async fn usd_price(&self, chain_type: &ChainType, token: &str) -> Result<f64, RateError> {
+        self.providers
+            .get(chain_type)
+            .ok_or_else(|| RateError::UnknownToken(token.to_string()))?
+            .usd_price(chain_type, token)
+            .await
+    }
+}
+
+/// default Jupiter price API endpoint
+pub const JUPITER_PRICE_URL: &str = "https://lite-api.jup.ag/price/v3";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterPrice {
+    usd_price: f64,
+}
+
+/// Prices of Solana tokens from the Jupiter price API.
+pub struct JupiterRateProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl JupiterRateProvider {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: JUPITER_PRICE_URL.to_string(),
+        }
+    }
+
+    /// use another deployment of the price API, e.g. a paid endpoint
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+}
+
+impl Default for JupiterRateProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RateProvider for JupiterRateProvider {
+    async fn usd_price(&self, chain_type: &ChainType, token: &str) -> Result<f64, RateError> {
+        if !chain_type.is_solana() {
+            return Err(RateError::UnknownToken(token.to_string()));
+        }
+        let prices: HashMap<String, JupiterPrice> = self
+            .client
+            .get(&self.base_url)
+            .query(&[("ids", token)])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| RateError::Unavailable(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| RateError::Unavailable(e.to_string()))?;
+        prices
+            .get(token)
+            .map(|price| price.usd_price)
+            .ok_or_else(|| RateError::UnknownToken(token.to_string()))
+    }
+}
+
+/// `decimals()` selector, shared by ERC-20 tokens and Chainlink aggregators
+pub(crate) const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+/// `latestRoundData()` selector of Chainlink aggregators
+const LATEST_ROUND_DATA_SELECTOR: [u8; 4] = [0xfe, 0xaf, 0x96, 0x8c];
+
+/// Prices of EVM tokens from Chainlink USD price feeds.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::rates::ChainlinkRateProvider;
+/// use x402_sdk::types::ChainType;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// // USDC/USD on Ethereum
+/// let rates = ChainlinkRateProvider::new(ChainType::ethereum(), "https://eth.llamarpc.com")?
+///     .with_feed(
+///         "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+///         "0x8fFfFfd4AfB6115b954Bd326cbe7B4BA576818f6",
+///     )
+///     .with_max_age(86_400);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ChainlinkRateProvider {
+    provider: Arc<Provider<Http>>,
+    chain_type: ChainType,
+    /// token address, lowercased, to aggregator address
+    feeds: HashMap<String, String>,
+    max_age_secs: Option<u64>,
+    clock: Arc<dyn Clock>,
+}
+
+impl ChainlinkRateProvider {
+    pub fn new(chain_type: ChainType, rpc_url: &str) -> Result<Self, RateError> {
+        let provider = Provider::<Http>::try_from(rpc_url)
+            .map_err(|e| RateError::Unavailable(format!("Failed to create provider: {}", e)))?;
+        Ok(Self {
+            provider: Arc::new(provider),
+            chain_type,
+            feeds: HashMap::new(),
+            max_age_secs: None,
+            clock: Arc::new(SystemClock),
+        })
+    }
+
+    /// price `token` with the USD aggregator at `feed`
+    pub fn with_feed(mut self, token: &str, feed: &str) -> Self {
+        self.feeds.insert(token.to_lowercase(), feed.to_string());
+        self
+    }
+
+    /// reject answers older than `max_age_secs`
+    pub fn with_max_age(mut self, max_age_secs: u64) -> Self {
+        self.max_age_secs = Some(max_age_secs);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    async fn call(&self, to: H160, selector: [u8; 4]) -> Result<Bytes, RateError> {
+        let request = TransactionRequest::new().to(to).data(selector.to_vec());
+        self.provider
+            .call(&request.into(), None)
+            .await
+            .map_err(|e| RateError::Unavailable(format!("Feed call failed: {}", e)))
+    }
+}
+
+#[async_trait]
+impl RateProvider for ChainlinkRateProvider {
+    async fn usd_price(&self, chain_type: &ChainType, token: &str) -> Result<f64, RateError> {
+        let feed = self
+            .feeds
+            .get(&token.to_lowercase())
+            .filter(|_| chain_type == &self.chain_type)
+            .ok_or_else(|| RateError::UnknownToken(token.to_string()))?;
+        let feed = H160::from_str(feed)
+            .map_err(|_| RateError::Unavailable(format!("Invalid feed address: {}", feed)))?;
+        let decimals = self.call(feed, DECIMALS_SELECTOR).await?;
+        let round = self.call(feed, LATEST_ROUND_DATA_SELECTOR).await?;
+        if decimals.len() < 32 || round.len() < 160 {
+            return Err(RateError::Unavailable("Malformed feed answer".to_string()));
+        }
+        let decimals = U256::from_big_endian(&decimals[..32]).low_u32() as i32;
+        let answer = U256::from_big_endian(&round[32..64]);
+        let updated_at = U256::from_big_endian(&round[96..128]).low_u64();
+        // answers are int256, a set sign bit is a negative price
+        if answer.is_zero() || answer.bit(255) {
+            return Err(RateError::Unavailable(
+                "Non-positive feed answer".to_string(),
+            ));
+        }
+        if let Some(max_age) = self.max_age_secs
+            && self.clock.now().saturating_sub(updated_at) > max_age
+        {
+            return Err(RateError::Unavailable("Stale feed answer".to_string()));
+        }
+        Ok(answer.to_string().parse::<f64>().unwrap_or(0.0) / 10f64.powi(decimals))
+    }
+}
@@ -0,0 +1,103 @@
+/// Payment receipt module.
+use crate::limits::PayloadLimits;
+use crate::revocation::{RevocationError, RevocationReason};
+use crate::signing::SignatureError;
+use crate::types::ChallengeSignature;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+
+/// header carrying the receipt token on follow-up requests
+pub const RECEIPT_HEADER: &str = "X-Payment-Receipt";
+
+/// domain prefix of receipt signatures
+pub const RECEIPT_DOMAIN: &[u8] = b"x402-receipt:";
+
+#[derive(Debug)]
+pub enum ReceiptError {
+    Unsigned,
+    Expired,
+    Revoked(RevocationReason),
+    NoKeyRing,
+    SignatureError(SignatureError),
+    RevocationError(RevocationError),
+    Malformed(String),
+}
+
+impl std::fmt::Display for ReceiptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsigned => write!(f, "Receipt is not signed"),
+            Self::Expired => write!(f, "Receipt expired"),
+            Self::Revoked(reason) => write!(f, "Receipt revoked: {}", reason),
+            Self::NoKeyRing => write!(f, "No signing keys configured"),
+            Self::SignatureError(err) => write!(f, "Receipt signature error: {}", err),
+            Self::RevocationError(err) => write!(f, "{}", err),
+            Self::Malformed(msg) => write!(f, "Malformed receipt: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ReceiptError {}
+
+impl From<SignatureError> for ReceiptError {
+    fn from(err: SignatureError) -> Self {
+        Self::SignatureError(err)
+    }
+}
+
+impl From<RevocationError> for ReceiptError {
+    fn from(err: RevocationError) -> Self {
+        Self::RevocationError(err)
+    }
+}
+
+/// Signed proof of a verified payment, usable as an access token until it
+/// expires or is revoked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub receipt_id: String,
+    pub nonce: String,
+    pub payer: String,
+    pub resource: String,
+    pub chain_id: String,
+    pub amount: String,
+    pub transaction_hash: Option<String>,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    /// invoice of the paid session, see [`X402::invoice`](crate::core::X402::invoice)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoice_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ChallengeSignature>,
+}
+
+impl Receipt {
+    /// receipt content covered by the signature
+    pub fn unsigned(&self) -> Receipt {
+        Receipt {
+            signature: None,
+            ..self.clone()
+        }
+    }
+
+    /// encode as a compact token for the `X-Payment-Receipt` header
+    pub fn to_token(&self) -> Result<String, ReceiptError> {
+        let json = serde_json::to_vec(self).map_err(|e| ReceiptError::Malformed(e.to_string()))?;
+        Ok(URL_SAFE_NO_PAD.encode(json))
+    }
+
+    pub fn from_token(token: &str) -> Result<Self, ReceiptError> {
+        let json = URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .map_err(|e| ReceiptError::Malformed(e.to_string()))?;
+        serde_json::from_slice(&json).map_err(|e| ReceiptError::Malformed(e.to_string()))
+    }
+
+    /// decode a token presented by an untrusted client within the limits
+    pub fn from_token_with(token: &str, limits: &PayloadLimits) -> Result<Self, ReceiptError> {
+        limits
+            .decode(&URL_SAFE_NO_PAD, token)
+            .map_err(|e| ReceiptError::Malformed(e.to_string()))
+    }
+}
@@ -0,0 +1,269 @@
+/// Human-readable receipt module.
+use crate::export::datetime_of;
+use crate::i18n::{MessageCatalog, MessageKey};
+use crate::invoice::Invoice;
+use serde::{Deserialize, Serialize};
+
+/// Receipt of a verified payment for people, rendered as HTML or, with the
+/// `pdf` feature, as PDF. Built by [`X402::receipt_page`](crate::core::X402::receipt_page)
+/// from a receipt token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptPage {
+    pub receipt_id: String,
+    pub issued_at: u64,
+    pub merchant_name: String,
+    pub merchant_description: String,
+    /// address the payment was sent to
+    pub merchant_address: String,
+    pub payer: String,
+    pub resource: String,
+    /// display name of the chain
+    pub chain: String,
+    pub chain_id: String,
+    /// paid amount, in the smallest unit of the currency
+    pub amount: String,
+    /// native currency symbol or token address, when known
+    pub currency: Option<String>,
+    /// decimals of the currency, amounts are shown in whole units when known
+    pub decimals: Option<u8>,
+    pub transaction_hash: Option<String>,
+    pub explorer_url: Option<String>,
+    pub invoice: Option<Invoice>,
+}
+
+impl ReceiptPage {
+    /// amount in whole units of the currency, with its symbol when known
+    pub fn display_amount(&self, amount: &str) -> String {
+        let value = match self.decimals {
+            Some(decimals) => format_units(amount, decimals),
+            None => amount.to_string(),
+        };
+        match &self.currency {
+            Some(currency) => format!("{} {}", value, currency),
+            None => value,
+        }
+    }
+
+    /// label and value of every summary row, in display order
+    fn rows(&self) -> Vec<(MessageKey, String)> {
+        let mut rows = vec![
+            (MessageKey::Receipt, self.receipt_id.clone()),
+            (MessageKey::Date, datetime_of(self.issued_at)),
+            (MessageKey::Merchant, self.merchant_name.clone()),
+            (MessageKey::PaidTo, self.merchant_address.clone()),
+            (MessageKey::PaidBy, self.payer.clone()),
+            (MessageKey::Resource, self.resource.clone()),
+            (
+                MessageKey::Chain,
+                format!("{} ({})", self.chain, self.chain_id),
+            ),
+            (MessageKey::Amount, self.display_amount(&self.amount)),
+        ];
+        if let Some(transaction_hash) = &self.transaction_hash {
+            rows.push((MessageKey::Transaction, transaction_hash.clone()));
+        }
+        if let Some(invoice) = &self.invoice {
+            rows.push((MessageKey::Invoice, invoice.invoice_id.clone()));
+        }
+        rows
+    }
+
+    /// invoice lines, then subtotal, discount, tax and total
+    fn invoice_rows(
+        &self,
+        invoice: &Invoice,
+        label: &dyn Fn(MessageKey) -> String,
+    ) -> Vec<(String, String)> {
+        let mut rows: Vec<(String, String)> = invoice
+            .line_items
+            .iter()
+            .map(|item| {
+                (
+                    format!("{} x {}", item.quantity, item.description),
+                    self.display_amount(&item.amount),
+                )
+            })
+            .collect();
+        rows.push((
+            label(MessageKey::Subtotal),
+            self.display_amount(&invoice.subtotal),
+        ));
+        if let Some(discount) = &invoice.discount {
+            rows.push((label(MessageKey::Discount), self.display_amount(discount)));
+        }
+        if let Some(tax) = &invoice.tax {
+            rows.push((
+                format!(
+                    "{} {} ({}%)",
+                    tax.name,
+                    tax.jurisdiction,
+                    format_units(&tax.rate_bps.to_string(), 2)
+                ),
+                self.display_amount(&tax.amount),
+            ));
+        }
+        rows.push((
+            label(MessageKey::Total),
+            self.display_amount(&invoice.total),
+        ));
+        rows
+    }
+
+    /// standalone HTML document
+    pub fn to_html(&self) -> String {
+        self.to_html_in(&MessageCatalog::new(), None)
+    }
+
+    /// HTML document with the labels of `locale` in `catalog`
+    pub fn to_html_in(&self, catalog: &MessageCatalog, locale: Option<&str>) -> String {
+        let label = |key| catalog.message(locale, key, &[]);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{} {}</title>\n<style>body{{font-family:sans-serif;max-width:40em;\
+             margin:2em auto}}td{{padding:.25em 1em .25em 0;vertical-align:top;\
+             word-break:break-all}}</style>\n</head>\n<body>\n<h1>{}</h1>\n<p>{}</p>\n\
+             <h2>{}</h2>\n<table>\n",
+            escape(locale.unwrap_or("en")),
+            escape(&label(MessageKey::Receipt)),
+            escape(&self.receipt_id),
+            escape(&self.merchant_name),
+            escape(&self.merchant_description),
+            escape(&label(MessageKey::ReceiptTitle))
+        );
+        for (key, value) in self.rows() {
+            let value = match (key, &self.explorer_url) {
+                (MessageKey::Transaction, Some(url)) => {
+                    format!("<a href=\"{}\">{}</a>", escape(url), escape(&value))
+                }
+                _ => escape(&value),
+            };
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                escape(&label(key)),
+                value
+            ));
+        }
+        html.push_str("</table>\n");
+        if let Some(invoice) = &self.invoice {
+            html.push_str(&format!(
+                "<h2>{}</h2>\n<table>\n",
+                escape(&label(MessageKey::Invoice))
+            ));
+            for (label, value) in self.invoice_rows(invoice, &label) {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td></tr>\n",
+                    escape(&label),
+                    escape(&value)
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// single page PDF in the standard Helvetica font, characters outside
+    /// ASCII are replaced
+    #[cfg(feature = "pdf")]
+    pub fn to_pdf(&self) -> Vec<u8> {
+        self.to_pdf_in(&MessageCatalog::new(), None)
+    }
+
+    /// PDF with the labels of `locale` in `catalog`
+    #[cfg(feature = "pdf")]
+    pub fn to_pdf_in(&self, catalog: &MessageCatalog, locale: Option<&str>) -> Vec<u8> {
+        let label = |key| catalog.message(locale, key, &[]);
+        let mut lines = vec![
+            self.merchant_name.clone(),
+            self.merchant_description.clone(),
+            String::new(),
+            label(MessageKey::ReceiptTitle),
+        ];
+        for (key, value) in self.rows() {
+            lines.push(format!("{}: {}", label(key), value));
+        }
+        if let Some(url) = &self.explorer_url {
+            lines.push(format!("{}: {}", label(MessageKey::Explorer), url));
+        }
+        if let Some(invoice) = &self.invoice {
+            lines.push(String::new());
+            lines.push(label(MessageKey::Invoice));
+            for (label, value) in self.invoice_rows(invoice, &label) {
+                lines.push(format!("{}: {}", label, value));
+            }
+        }
+        let mut content = String::from("BT\n/F1 10 Tf\n14 TL\n50 800 Td\n");
+        for line in &lines {
+            content.push_str(&format!("({}) '\n", pdf_text(line)));
+        }
+        content.push_str("ET\n");
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] \
+             /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                content.len(),
+                content
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", index + 1, object));
+        }
+        let xref = pdf.len();
+        pdf.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            pdf.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        ));
+        pdf.into_bytes()
+    }
+}
+
+/// integer amount in the smallest unit as a decimal in whole units
+fn format_units(amount: &str, decimals: u8) -> String {
+    let decimals = usize::from(decimals);
+    if decimals == 0 || !amount.bytes().all(|b| b.is_ascii_digit()) {
+        return amount.to_string();
+    }
+    let padded = format!("{:0>width$}", amount, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(feature = "pdf")]
+fn pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
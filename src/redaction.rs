@@ -0,0 +1,204 @@
+/// Sensitive field redaction module.
+use crate::audit::{AuditEntry, AuditError, AuditLog};
+use crate::config::RedactionConfig;
+use crate::events::{PaymentEvent, PaymentEventKind};
+use crate::invoice::INVOICE_EXTENSION;
+use crate::tax::TAX_EXTENSION;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Placeholder of a removed value.
+pub const REDACTED: &str = "[redacted]";
+
+/// How a sensitive value is written to logs, event payloads and audit
+/// records.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum RedactionPolicy {
+    #[default]
+    Keep,
+    /// `sha256:` and the first 16 hex digits of the hash, equal values stay
+    /// correlatable
+    Hash,
+    /// keep the first `prefix` and last `suffix` characters, e.g.
+    /// `0x742E…b4a5`
+    Truncate { prefix: usize, suffix: usize },
+    /// replace the value with [`REDACTED`]
+    Remove,
+}
+
+impl RedactionPolicy {
+    pub fn apply(&self, value: &str) -> String {
+        match self {
+            Self::Keep => value.to_string(),
+            Self::Hash => {
+                let digest: String = Sha256::digest(value.as_bytes())
+                    .iter()
+                    .take(8)
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                format!("sha256:{}", digest)
+            }
+            Self::Truncate { prefix, suffix } => {
+                let chars: Vec<char> = value.chars().collect();
+                if chars.len() <= prefix + suffix {
+                    return value.to_string();
+                }
+                let head: String = chars[..*prefix].iter().collect();
+                let tail: String = chars[chars.len() - suffix..].iter().collect();
+                format!("{}…{}", head, tail)
+            }
+            Self::Remove => REDACTED.to_string(),
+        }
+    }
+}
+
+/// Applies the [`RedactionConfig`] policies to events and audit records.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::config::RedactionConfig;
+/// use x402_sdk::redaction::{RedactionPolicy, Redactor};
+///
+/// let redactor = Redactor::new(RedactionConfig {
+///     payer_address: RedactionPolicy::Truncate { prefix: 6, suffix: 4 },
+///     ..Default::default()
+/// });
+/// assert_eq!(
+///     redactor.payer("0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5"),
+///     "0x742E…b4a5"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    config: RedactionConfig,
+}
+
+impl Redactor {
+    pub fn new(config: RedactionConfig) -> Self {
+        Self { config }
+    }
+
+    /// whether any field is redacted
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    pub fn payer(&self, value: &str) -> String {
+        self.config.payer_address.apply(value)
+    }
+
+    pub fn nonce(&self, value: &str) -> String {
+        self.config.nonce.apply(value)
+    }
+
+    pub fn amount(&self, value: &str) -> String {
+        self.config.amount.apply(value)
+    }
+
+    /// Copy of the event with its payer, nonces and amounts redacted. While
+    /// any policy is set the request headers left on events are also
+    /// replaced with [`REDACTED`], and unless amounts are kept so are the
+    /// rate quote, the invoice and tax extensions and the token conversion.
+    pub fn event(&self, event: &PaymentEvent) -> PaymentEvent {
+        let mut event = event.clone();
+        if !self.is_enabled() {
+            return event;
+        }
+        let keep_amounts = self.config.amount == RedactionPolicy::Keep;
+        event.user_address = self.payer(&event.user_address);
+        for value in event.context.headers.values_mut() {
+            *value = REDACTED.to_string();
+        }
+        match &mut event.kind {
+            PaymentEventKind::ChallengeIssued { payment_request } => {
+                payment_request.nonce = self.nonce(&payment_request.nonce);
+                payment_request.amount = self.amount(&payment_request.amount);
+                payment_request.beneficiary = payment_request
+                    .beneficiary
+                    .as_deref()
+                    .map(|b| self.payer(b));
+                // derived from the nonce
+                payment_request.reference =
+                    payment_request.reference.as_deref().map(|r| self.nonce(r));
+                if !keep_amounts {
+                    payment_request.quote = None;
+                    let extensions = &mut payment_request.metadata.extensions;
+                    extensions.remove(INVOICE_EXTENSION);
+                    extensions.remove(TAX_EXTENSION);
+                }
+            }
+            PaymentEventKind::PaymentVerified {
+                nonce,
+                verification,
+            } => {
+                *nonce = self.nonce(nonce);
+                verification.paid_amount = self.amount(&verification.paid_amount);
+                for log in &mut verification.transaction_logs {
+                    log.from = self.payer(&log.from);
+                    log.value = self.amount(&log.value);
+                }
+                if !keep_amounts {
+                    verification.conversion = None;
+                }
+                // the raw receipt names the payer and amount
+                verification.proof = None;
+            }
+            PaymentEventKind::BudgetDrawn {
+                amount, balance, ..
+            } => {
+                *amount = self.amount(amount);
+                *balance = self.amount(balance);
+            }
+            PaymentEventKind::PaymentDenied { nonce, .. }
+            | PaymentEventKind::VerificationFailed { nonce, .. }
+            | PaymentEventKind::VerificationThrottled { nonce, .. }
+            | PaymentEventKind::SessionLockedOut { nonce, .. }
+            | PaymentEventKind::CouponRedeemed { nonce, .. }
+            | PaymentEventKind::CouponExhausted { nonce, .. }
+            | PaymentEventKind::SettlementSubmitted { nonce, .. }
+            | PaymentEventKind::SettlementFailed { nonce, .. }
+            | PaymentEventKind::PaymentFinalized { nonce }
+            | PaymentEventKind::PaymentReverted { nonce, .. }
+            | PaymentEventKind::AccessRevoked { nonce, .. }
+            | PaymentEventKind::ContentServed { nonce } => *nonce = self.nonce(nonce),
+            PaymentEventKind::AccessGranted | PaymentEventKind::AccessDenied { .. } => {}
+        }
+        event
+    }
+
+    /// copy of the audit entry with its target redacted as a nonce
+    pub fn audit_entry(&self, entry: &AuditEntry) -> AuditEntry {
+        AuditEntry {
+            target: self.nonce(&entry.target),
+            ..entry.clone()
+        }
+    }
+}
+
+/// Audit log storing redacted targets. Lookups are redacted the same way,
+/// so with a hash policy the entries of a nonce are still found.
+pub struct RedactingAuditLog {
+    inner: Arc<dyn AuditLog>,
+    redactor: Redactor,
+}
+
+impl RedactingAuditLog {
+    pub fn new(inner: Arc<dyn AuditLog>, redactor: Redactor) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+#[async_trait]
+impl AuditLog for RedactingAuditLog {
+    async fn append(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        self.inner.append(self.redactor.audit_entry(&entry)).await
+    }
+
+    async fn entries_for(&self, target: &str) -> Result<Vec<AuditEntry>, AuditError> {
+        self.inner.entries_for(&self.redactor.nonce(target)).await
+    }
+}
@@ -0,0 +1,776 @@
+/// Gasless payment relay module.
+use crate::audit::{AuditAction, AuditEntry, AuditLog};
+use crate::clock::{Clock, SystemClock};
+use crate::nonce::NonceManager;
+use crate::rates::{DECIMALS_SELECTOR, NATIVE_TOKEN, RateProvider};
+use crate::types::{ChainConfig, ChainType};
+use async_trait::async_trait;
+use ethers::abi::{Token, encode};
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{
+    Bytes, Eip1559TransactionRequest, H160, H256, Transaction, TransactionRequest, U256,
+};
+use ethers::utils::{hex, id, keccak256, rlp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum RelayError {
+    ChainNotSupported(ChainType),
+    InvalidAuthorization(String),
+    Rejected(String),
+    /// the gas cost exceeds the share of the payment allowed by the fee policy
+    FeeTooHigh(String),
+    NetworkError(String),
+}
+
+impl std::fmt::Display for RelayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ChainNotSupported(chain) => write!(f, "Relay not available on {:?}", chain),
+            Self::InvalidAuthorization(msg) => write!(f, "Invalid authorization: {}", msg),
+            Self::Rejected(msg) => write!(f, "Relay rejected the transfer: {}", msg),
+            Self::FeeTooHigh(msg) => write!(f, "Relay fee too high: {}", msg),
+            Self::NetworkError(msg) => write!(f, "Network error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RelayError {}
+
+/// EIP-3009 `transferWithAuthorization` signed by the payer, the relayer
+/// submits it and pays the gas.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferAuthorization {
+    /// token contract address
+    pub token: String,
+    pub from: String,
+    pub to: String,
+    /// amount in the smallest unit of the token
+    pub value: String,
+    pub valid_after: u64,
+    pub valid_before: u64,
+    /// 32-byte authorization nonce, hex encoded
+    pub nonce: String,
+    /// 65-byte `r || s || v` signature, hex encoded
+    pub signature: String,
+}
+
+impl TransferAuthorization {
+    /// calldata of the `transferWithAuthorization` call
+    pub fn calldata(&self) -> Result<Vec<u8>, RelayError> {
+        let invalid = |field: &str| RelayError::InvalidAuthorization(format!("invalid {}", field));
+        let address = |value: &str, field: &str| H160::from_str(value).map_err(|_| invalid(field));
+        let value = U256::from_dec_str(&self.value).map_err(|_| invalid("value"))?;
+        let nonce = hex::decode(self.nonce.trim_start_matches("0x"))
+            .ok()
+            .filter(|nonce| nonce.len() == 32)
+            .ok_or_else(|| invalid("nonce"))?;
+        let signature = hex::decode(self.signature.trim_start_matches("0x"))
+            .ok()
+            .filter(|signature| signature.len() == 65)
+            .ok_or_else(|| invalid("signature"))?;
+        let selector = id(
+            "transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)",
+        );
+        let arguments = encode(&[
+            Token::Address(address(&self.from, "from")?),
+            Token::Address(address(&self.to, "to")?),
+            Token::Uint(value),
+            Token::Uint(U256::from(self.valid_after)),
+            Token::Uint(U256::from(self.valid_before)),
+            Token::FixedBytes(nonce),
+            Token::Uint(U256::from(signature[64])),
+            Token::FixedBytes(signature[..32].to_vec()),
+            Token::FixedBytes(signature[32..64].to_vec()),
+        ]);
+        Ok([selector.as_slice(), &arguments].concat())
+    }
+}
+
+/// On-chain state of a relayed transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionStatus {
+    Pending,
+    Confirmed,
+    Reverted,
+}
+
+/// Submits payer authorizations on chain on behalf of the merchant.
+#[async_trait]
+pub trait Relayer: Send + Sync {
+    /// submit the authorization, returns the transaction hash
+    async fn submit(
+        &self,
+        chain: &ChainConfig,
+        authorization: &TransferAuthorization,
+    ) -> Result<String, RelayError>;
+
+    /// resubmit an unmined transaction in place of `previous`, with its fee
+    /// raised by `gas_bump_percent`. Resubmitting is safe since the token
+    /// accepts an authorization nonce only once.
+    async fn replace(
+        &self,
+        chain: &ChainConfig,
+        authorization: &TransferAuthorization,
+        _previous: &str,
+        _gas_bump_percent: u32,
+    ) -> Result<String, RelayError> {
+        self.submit(chain, authorization).await
+    }
+
+    /// state of a submitted transaction, relays answering once the transfer
+    /// is mined report it confirmed
+    async fn transaction_status(
+        &self,
+        _chain: &ChainConfig,
+        _transaction_hash: &str,
+    ) -> Result<TransactionStatus, RelayError> {
+        Ok(TransactionStatus::Confirmed)
+    }
+
+    /// relay cost added to token challenge amounts, in the unit of the
+    /// amount
+    async fn gas_surcharge(&self, _chain: &ChainConfig) -> Result<u128, RelayError> {
+        Ok(0)
+    }
+}
+
+/// Fee controls of relayed transactions on one chain, amounts in wei.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeePolicy {
+    /// EIP-1559 fees, a legacy gas price when unset
+    pub eip1559: bool,
+    /// priority fee per gas, the node estimate when unset
+    pub priority_fee_per_gas: Option<u128>,
+    /// cap on the max fee per gas, or on the gas price
+    pub max_fee_per_gas: Option<u128>,
+    /// abort when the worst case gas cost exceeds this share of the
+    /// transferred value, in basis points, needs a rate provider
+    pub max_cost_bps: Option<u32>,
+}
+
+impl FeePolicy {
+    pub fn eip1559() -> Self {
+        Self {
+            eip1559: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn legacy() -> Self {
+        Self::default()
+    }
+
+    pub fn with_priority_fee(mut self, priority_fee_per_gas: u128) -> Self {
+        self.priority_fee_per_gas = Some(priority_fee_per_gas);
+        self
+    }
+
+    pub fn with_max_fee(mut self, max_fee_per_gas: u128) -> Self {
+        self.max_fee_per_gas = Some(max_fee_per_gas);
+        self
+    }
+
+    pub fn with_max_cost_bps(mut self, max_cost_bps: u32) -> Self {
+        self.max_cost_bps = Some(max_cost_bps);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Fees {
+    Legacy { gas_price: U256 },
+    Eip1559 { max_fee: U256, priority_fee: U256 },
+}
+
+impl Fees {
+    fn of(transaction: &Transaction) -> Option<Self> {
+        match (
+            transaction.max_fee_per_gas,
+            transaction.max_priority_fee_per_gas,
+            transaction.gas_price,
+        ) {
+            (Some(max_fee), Some(priority_fee), _) => Some(Self::Eip1559 {
+                max_fee,
+                priority_fee,
+            }),
+            (_, _, Some(gas_price)) => Some(Self::Legacy { gas_price }),
+            _ => None,
+        }
+    }
+
+    fn max_per_gas(&self) -> U256 {
+        match self {
+            Self::Legacy { gas_price } => *gas_price,
+            Self::Eip1559 { max_fee, .. } => *max_fee,
+        }
+    }
+
+    fn priority_per_gas(&self) -> U256 {
+        match self {
+            Self::Legacy { gas_price } => *gas_price,
+            Self::Eip1559 { priority_fee, .. } => *priority_fee,
+        }
+    }
+
+    /// raise to at least `previous` bumped by `percent`, as nodes require of
+    /// a replacement
+    fn replacing(self, previous: Fees, percent: u32) -> Self {
+        let bump = |fee: U256| fee * (100 + percent) / 100;
+        match self {
+            Self::Legacy { gas_price } => Self::Legacy {
+                gas_price: gas_price.max(bump(previous.max_per_gas())),
+            },
+            Self::Eip1559 {
+                max_fee,
+                priority_fee,
+            } => Self::Eip1559 {
+                max_fee: max_fee.max(bump(previous.max_per_gas())),
+                priority_fee: priority_fee.max(bump(previous.priority_per_gas())),
+            },
+        }
+    }
+
+    fn capped(self, cap: Option<u128>) -> Self {
+        let Some(cap) = cap.map(U256::from) else {
+            return self;
+        };
+        match self {
+            Self::Legacy { gas_price } => Self::Legacy {
+                gas_price: gas_price.min(cap),
+            },
+            Self::Eip1559 {
+                max_fee,
+                priority_fee,
+            } => {
+                let max_fee = max_fee.min(cap);
+                Self::Eip1559 {
+                    max_fee,
+                    priority_fee: priority_fee.min(max_fee),
+                }
+            }
+        }
+    }
+}
+
+/// Signs relay transactions, in process or outside the service, e.g. an
+/// HSM, an MPC wallet or a custody API.
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    /// address transactions are sent from
+    fn address(&self) -> H160;
+
+    /// signed raw transaction for a fully populated unsigned one
+    async fn sign_transaction(&self, transaction: &TypedTransaction) -> Result<Bytes, RelayError>;
+}
+
+#[async_trait]
+impl TransactionSigner for LocalWallet {
+    fn address(&self) -> H160 {
+        Signer::address(self)
+    }
+
+    async fn sign_transaction(&self, transaction: &TypedTransaction) -> Result<Bytes, RelayError> {
+        let signature = Signer::sign_transaction(self, transaction)
+            .await
+            .map_err(|e| RelayError::Rejected(format!("signing failed: {}", e)))?;
+        Ok(transaction.rlp_signed(&signature))
+    }
+}
+
+/// Relays authorizations directly with a merchant funded gas wallet.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::relay::{Eip3009Relayer, FeePolicy};
+/// use x402_sdk::types::ChainType;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let relayer = Eip3009Relayer::new("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")?
+///     .with_rpc(ChainType::polygon(), "https://polygon-rpc.com")
+///     .with_fee_policy(
+///         ChainType::polygon(),
+///         FeePolicy::eip1559()
+///             .with_max_fee(500_000_000_000)
+///             .with_max_cost_bps(500),
+///     )
+///     .with_gas_surcharge(1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Eip3009Relayer {
+    signer: Arc<dyn TransactionSigner>,
+    sign_timeout: Duration,
+    audit_log: Option<Arc<dyn AuditLog>>,
+    clock: Arc<dyn Clock>,
+    rpc_urls: HashMap<ChainType, String>,
+    fee_policies: HashMap<ChainType, FeePolicy>,
+    rate_provider: Option<Arc<dyn RateProvider>>,
+    nonces: Arc<NonceManager>,
+    gas_surcharge: u128,
+}
+
+impl Eip3009Relayer {
+    pub fn new(private_key: &str) -> Result<Self, RelayError> {
+        let wallet = LocalWallet::from_str(private_key.trim_start_matches("0x"))
+            .map_err(|e| RelayError::InvalidAuthorization(format!("invalid relay key: {}", e)))?;
+        Ok(Self::from_signer(Arc::new(wallet)))
+    }
+
+    /// relay with a signer holding the key outside the service, the crate
+    /// builds each transaction unsigned and submits the signed raw bytes
+    pub fn from_signer(signer: Arc<dyn TransactionSigner>) -> Self {
+        Self {
+            signer,
+            sign_timeout: Duration::from_secs(30),
+            audit_log: None,
+            clock: Arc::new(SystemClock),
+            rpc_urls: HashMap::new(),
+            fee_policies: HashMap::new(),
+            rate_provider: None,
+            nonces: Arc::new(NonceManager::new()),
+            gas_surcharge: 0,
+        }
+    }
+
+    /// how long the signer may take before the submission is abandoned
+    pub fn with_sign_timeout(mut self, sign_timeout_secs: u64) -> Self {
+        self.sign_timeout = Duration::from_secs(sign_timeout_secs);
+        self
+    }
+
+    /// record every signing request and its outcome, under the
+    /// authorization nonce
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_rpc(mut self, chain_type: ChainType, rpc_url: &str) -> Self {
+        self.rpc_urls.insert(chain_type, rpc_url.to_string());
+        self
+    }
+
+    /// fee controls on a chain, legacy node priced gas when unset
+    pub fn with_fee_policy(mut self, chain_type: ChainType, policy: FeePolicy) -> Self {
+        self.fee_policies.insert(chain_type, policy);
+        self
+    }
+
+    /// prices of the tokens and of [`NATIVE_TOKEN`], used by the
+    /// `max_cost_bps` guard
+    pub fn with_rate_provider(mut self, rate_provider: Arc<dyn RateProvider>) -> Self {
+        self.rate_provider = Some(rate_provider);
+        self
+    }
+
+    /// share account nonces with other senders of the relay wallet
+    pub fn with_nonce_manager(mut self, nonces: Arc<NonceManager>) -> Self {
+        self.nonces = nonces;
+        self
+    }
+
+    /// fixed relay cost, in the unit of challenge amounts
+    pub fn with_gas_surcharge(mut self, gas_surcharge: u128) -> Self {
+        self.gas_surcharge = gas_surcharge;
+        self
+    }
+
+    /// address paying the relay gas
+    pub fn address(&self) -> String {
+        format!("{:?}", self.signer.address())
+    }
+
+    fn client(&self, chain: &ChainConfig) -> Result<Provider<Http>, RelayError> {
+        let rpc_url = self
+            .rpc_urls
+            .get(&chain.chain_type)
+            .ok_or_else(|| RelayError::ChainNotSupported(chain.chain_type.clone()))?;
+        let provider = Provider::<Http>::try_from(rpc_url.as_str())
+            .map_err(|e| RelayError::NetworkError(format!("Failed to create provider: {}", e)))?;
+        Ok(provider)
+    }
+
+    /// sign with a timeout, check the signed transaction is the one built
+    /// and from the relay address, and audit the outcome
+    async fn sign(
+        &self,
+        authorization: &TransferAuthorization,
+        transaction: &TypedTransaction,
+    ) -> Result<Bytes, RelayError> {
+        let signed = match tokio::time::timeout(
+            self.sign_timeout,
+            self.signer.sign_transaction(transaction),
+        )
+        .await
+        {
+            Ok(signed) => signed.and_then(|raw| self.check_signed(transaction, raw)),
+            Err(_) => Err(RelayError::Rejected(format!(
+                "signer timed out after {}s",
+                self.sign_timeout.as_secs()
+            ))),
+        };
+        let action = match &signed {
+            Ok(raw) => AuditAction::TransactionSigned {
+                transaction_hash: format!("{:?}", H256::from(keccak256(raw))),
+            },
+            Err(e) => AuditAction::TransactionSigningFailed {
+                reason: e.to_string(),
+            },
+        };
+        if let Some(audit_log) = &self.audit_log {
+            audit_log
+                .append(AuditEntry {
+                    timestamp: self.clock.now(),
+                    operator: format!("{:?}", self.signer.address()),
+                    action,
+                    target: authorization.nonce.clone(),
+                    note: None,
+                })
+                .await
+                .map_err(|e| RelayError::Rejected(e.to_string()))?;
+        }
+        signed
+    }
+
+    fn check_signed(
+        &self,
+        transaction: &TypedTransaction,
+        raw: Bytes,
+    ) -> Result<Bytes, RelayError> {
+        let mismatch = |msg: &str| RelayError::Rejected(format!("signer returned {}", msg));
+        let (signed, signature) = TypedTransaction::decode_signed(&rlp::Rlp::new(&raw))
+            .map_err(|_| mismatch("an undecodable transaction"))?;
+        if signed.sighash() != transaction.sighash() {
+            return Err(mismatch("a different transaction"));
+        }
+        if signature.recover(signed.sighash()).ok() != Some(self.signer.address()) {
+            return Err(mismatch("a signature of another address"));
+        }
+        Ok(raw)
+    }
+
+    async fn current_fees(client: &Provider<Http>, policy: &FeePolicy) -> Result<Fees, RelayError> {
+        let network = |e: String| RelayError::NetworkError(format!("Fee estimate failed: {}", e));
+        if !policy.eip1559 {
+            let gas_price = client
+                .get_gas_price()
+                .await
+                .map_err(|e| network(e.to_string()))?;
+            return Ok(Fees::Legacy { gas_price });
+        }
+        let (max_fee, estimated_priority_fee) = client
+            .estimate_eip1559_fees(None)
+            .await
+            .map_err(|e| network(e.to_string()))?;
+        let priority_fee = policy
+            .priority_fee_per_gas
+            .map(U256::from)
+            .unwrap_or(estimated_priority_fee);
+        Ok(Fees::Eip1559 {
+            max_fee: max_fee.max(priority_fee),
+            priority_fee,
+        })
+    }
+
+    /// send the transfer with a reserved nonce, or with the nonce of a
+    /// transaction it replaces and at least its fees raised by
+    /// `gas_bump_percent`
+    async fn send(
+        &self,
+        chain: &ChainConfig,
+        authorization: &TransferAuthorization,
+        replacing: Option<(u64, Option<Fees>)>,
+        gas_bump_percent: u32,
+    ) -> Result<String, RelayError> {
+        let client = self.client(chain)?;
+        let address = self.signer.address();
+        let nonce = match replacing {
+            Some((nonce, _)) => nonce,
+            None => self
+                .nonces
+                .reserve(&client, &chain.chain_id, address)
+                .await
+                .map_err(|e| RelayError::NetworkError(e.to_string()))?,
+        };
+        let sent = self
+            .send_with_nonce(
+                &client,
+                chain,
+                authorization,
+                nonce,
+                replacing,
+                gas_bump_percent,
+            )
+            .await;
+        match &sent {
+            Ok(transaction_hash) => {
+                self.nonces
+                    .record_sent(&chain.chain_id, address, nonce, transaction_hash)
+                    .await
+            }
+            Err(_) if replacing.is_none() => {
+                self.nonces.release(&chain.chain_id, address, nonce).await
+            }
+            Err(_) => {}
+        }
+        sent
+    }
+
+    async fn send_with_nonce(
+        &self,
+        client: &Provider<Http>,
+        chain: &ChainConfig,
+        authorization: &TransferAuthorization,
+        nonce: u64,
+        replacing: Option<(u64, Option<Fees>)>,
+        gas_bump_percent: u32,
+    ) -> Result<String, RelayError> {
+        let policy = self
+            .fee_policies
+            .get(&chain.chain_type)
+            .cloned()
+            .unwrap_or_default();
+        let mut fees = Self::current_fees(client, &policy).await?;
+        if let Some((_, Some(previous))) = replacing {
+            fees = fees.replacing(previous, gas_bump_percent);
+        }
+        let fees = fees.capped(policy.max_fee_per_gas);
+        let token = H160::from_str(&authorization.token)
+            .map_err(|_| RelayError::InvalidAuthorization("invalid token".to_string()))?;
+        let data = authorization.calldata()?;
+        let mut transaction: TypedTransaction = match fees {
+            Fees::Legacy { gas_price } => TransactionRequest::new()
+                .to(token)
+                .data(data)
+                .gas_price(gas_price)
+                .into(),
+            Fees::Eip1559 {
+                max_fee,
+                priority_fee,
+            } => Eip1559TransactionRequest::new()
+                .to(token)
+                .data(data)
+                .max_fee_per_gas(max_fee)
+                .max_priority_fee_per_gas(priority_fee)
+                .into(),
+        };
+        let chain_id: u64 = chain
+            .chain_id
+            .parse()
+            .map_err(|_| RelayError::ChainNotSupported(chain.chain_type.clone()))?;
+        transaction
+            .set_from(self.signer.address())
+            .set_chain_id(chain_id)
+            .set_nonce(nonce);
+        let gas = client
+            .estimate_gas(&transaction, None)
+            .await
+            .map_err(|e| RelayError::Rejected(e.to_string()))?;
+        transaction.set_gas(gas);
+        if let Some(max_cost_bps) = policy.max_cost_bps {
+            self.check_cost(
+                client,
+                chain,
+                authorization,
+                gas * fees.max_per_gas(),
+                max_cost_bps,
+            )
+            .await?;
+        }
+        let raw = self.sign(authorization, &transaction).await?;
+        let pending = client
+            .send_raw_transaction(raw)
+            .await
+            .map_err(|e| RelayError::Rejected(e.to_string()))?;
+        Ok(format!("{:?}", pending.tx_hash()))
+    }
+
+    /// reject a transfer whose gas cost exceeds `max_cost_bps` of its value
+    async fn check_cost(
+        &self,
+        client: &Provider<Http>,
+        chain: &ChainConfig,
+        authorization: &TransferAuthorization,
+        cost_wei: U256,
+        max_cost_bps: u32,
+    ) -> Result<(), RelayError> {
+        let unpriced =
+            |e: String| RelayError::FeeTooHigh(format!("cannot value the gas cost: {}", e));
+        let rate_provider = self
+            .rate_provider
+            .as_ref()
+            .ok_or_else(|| unpriced("no rate provider".to_string()))?;
+        let native_usd = rate_provider
+            .usd_price(&chain.chain_type, NATIVE_TOKEN)
+            .await
+            .map_err(|e| unpriced(e.to_string()))?;
+        let token_usd = rate_provider
+            .usd_price(&chain.chain_type, &authorization.token)
+            .await
+            .map_err(|e| unpriced(e.to_string()))?;
+        let token = H160::from_str(&authorization.token)
+            .map_err(|_| RelayError::InvalidAuthorization("invalid token".to_string()))?;
+        let request = TransactionRequest::new()
+            .to(token)
+            .data(DECIMALS_SELECTOR.to_vec());
+        let decimals = client
+            .call(&request.into(), None)
+            .await
+            .map_err(|e| unpriced(e.to_string()))?;
+        if decimals.len() < 32 {
+            return Err(unpriced("malformed decimals".to_string()));
+        }
+        let decimals = U256::from_big_endian(&decimals[..32]).low_u32() as i32;
+        let as_f64 = |amount: U256| amount.to_string().parse::<f64>().unwrap_or(f64::MAX);
+        let value = U256::from_dec_str(&authorization.value)
+            .map_err(|_| RelayError::InvalidAuthorization("invalid value".to_string()))?;
+        let value_usd = as_f64(value) / 10f64.powi(decimals) * token_usd;
+        let native_decimals = i32::from(chain.native_decimals().unwrap_or(18));
+        let cost_usd = as_f64(cost_wei) / 10f64.powi(native_decimals) * native_usd;
+        if cost_usd * 10_000.0 > value_usd * f64::from(max_cost_bps) {
+            return Err(RelayError::FeeTooHigh(format!(
+                "gas ${:.4} for a ${:.4} transfer",
+                cost_usd, value_usd
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn parse_hash(transaction_hash: &str) -> Result<H256, RelayError> {
+    H256::from_str(transaction_hash).map_err(|_| {
+        RelayError::InvalidAuthorization(format!("invalid transaction hash: {}", transaction_hash))
+    })
+}
+
+#[async_trait]
+impl Relayer for Eip3009Relayer {
+    async fn submit(
+        &self,
+        chain: &ChainConfig,
+        authorization: &TransferAuthorization,
+    ) -> Result<String, RelayError> {
+        self.send(chain, authorization, None, 0).await
+    }
+
+    async fn replace(
+        &self,
+        chain: &ChainConfig,
+        authorization: &TransferAuthorization,
+        previous: &str,
+        gas_bump_percent: u32,
+    ) -> Result<String, RelayError> {
+        let transaction = self
+            .client(chain)?
+            .get_transaction(parse_hash(previous)?)
+            .await
+            .map_err(|e| RelayError::NetworkError(e.to_string()))?;
+        let replacing = match transaction {
+            Some(transaction) => Some((transaction.nonce.as_u64(), Fees::of(&transaction))),
+            // dropped from the mempool, reuse its nonce at the current fees
+            None => self
+                .nonces
+                .in_flight_nonce(&chain.chain_id, self.signer.address(), previous)
+                .await
+                .map(|nonce| (nonce, None)),
+        };
+        self.send(chain, authorization, replacing, gas_bump_percent)
+            .await
+    }
+
+    async fn transaction_status(
+        &self,
+        chain: &ChainConfig,
+        transaction_hash: &str,
+    ) -> Result<TransactionStatus, RelayError> {
+        let receipt = self
+            .client(chain)?
+            .get_transaction_receipt(parse_hash(transaction_hash)?)
+            .await
+            .map_err(|e| RelayError::NetworkError(e.to_string()))?;
+        let status = match receipt {
+            None => return Ok(TransactionStatus::Pending),
+            Some(receipt) if receipt.status == Some(1.into()) => TransactionStatus::Confirmed,
+            Some(_) => TransactionStatus::Reverted,
+        };
+        self.nonces
+            .record_mined(&chain.chain_id, self.signer.address(), transaction_hash)
+            .await;
+        Ok(status)
+    }
+
+    async fn gas_surcharge(&self, _chain: &ChainConfig) -> Result<u128, RelayError> {
+        Ok(self.gas_surcharge)
+    }
+}
+
+#[derive(Serialize)]
+struct RelayServiceRequest<'a> {
+    chain_id: &'a str,
+    authorization: &'a TransferAuthorization,
+}
+
+#[derive(Deserialize)]
+struct RelayServiceResponse {
+    transaction_hash: String,
+}
+
+/// Forwards authorizations to an external relay service, which answers
+/// `{"transaction_hash": ...}` to a POST of the chain id and authorization.
+pub struct HttpRelayer {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpRelayer {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Relayer for HttpRelayer {
+    async fn submit(
+        &self,
+        chain: &ChainConfig,
+        authorization: &TransferAuthorization,
+    ) -> Result<String, RelayError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&RelayServiceRequest {
+                chain_id: &chain.chain_id,
+                authorization,
+            })
+            .send()
+            .await
+            .map_err(|e| RelayError::NetworkError(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(RelayError::Rejected(format!(
+                "relay service answered {}",
+                response.status()
+            )));
+        }
+        let response: RelayServiceResponse = response
+            .json()
+            .await
+            .map_err(|e| RelayError::NetworkError(e.to_string()))?;
+        Ok(response.transaction_hash)
+    }
+}
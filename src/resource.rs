@@ -0,0 +1,238 @@
+/// Typed resource identifiers module.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A requested resource made of HTTP method, normalized path and query.
+///
+/// Paths are normalized so that equivalent spellings (`//data/./x/`,
+/// `/data/x`) identify the same resource, query parameters are decoded and
+/// kept sorted so the canonical form is stable. A query naming a key twice
+/// is refused, applications disagree on which of the values counts.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::resource::Resource;
+///
+/// let resource = Resource::new("get", "//data/./reports/?limit=10&sort=asc").unwrap();
+/// assert_eq!(resource.path, "/data/reports");
+/// assert_eq!(resource.query_param("limit"), Some("10"));
+/// assert_eq!(resource.canonical(), "GET /data/reports?limit=10&sort=asc");
+/// assert!(Resource::new("GET", "/data/reports?limit=10&limit=10000").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Resource {
+    pub method: String,
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+}
+
+impl Resource {
+    /// build a resource from a method and a request target (`path?query`)
+    pub fn new(method: &str, target: &str) -> Result<Self, ResourceError> {
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (target, ""),
+        };
+        let mut params = BTreeMap::new();
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()).into_owned() {
+            if params.contains_key(&key) {
+                return Err(ResourceError::DuplicateQueryKey(key));
+            }
+            params.insert(key, value);
+        }
+        Ok(Self {
+            method: method.to_uppercase(),
+            path: Self::normalize_path(path),
+            query: params,
+        })
+    }
+
+    /// parse the output of [`canonical`](Self::canonical) back into a resource
+    pub fn from_canonical(canonical: &str) -> Result<Self, ResourceError> {
+        match canonical.split_once(' ') {
+            Some((method, target)) => Self::new(method, target),
+            None => Self::new("GET", canonical),
+        }
+    }
+
+    pub fn query_param(&self, key: &str) -> Option<&str> {
+        self.query.get(key).map(|v| v.as_str())
+    }
+
+    /// path followed by the sorted, encoded query string
+    pub fn path_with_query(&self) -> String {
+        if self.query.is_empty() {
+            return self.path.clone();
+        }
+        let query: String = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.query.iter())
+            .finish();
+        format!("{}?{}", self.path, query)
+    }
+
+    /// stable identifier used for session storage and nonce derivation
+    pub fn canonical(&self) -> String {
+        format!("{} {}", self.method, self.path_with_query())
+    }
+
+    /// normalized path of a request target, the query is ignored
+    pub fn path_of(target: &str) -> String {
+        Self::normalize_path(target.split_once('?').map_or(target, |(path, _)| path))
+    }
+
+    /// collapse repeated slashes, resolve `.` and `..` segments and drop the
+    /// trailing slash
+    pub fn normalize_path(path: &str) -> String {
+        let mut segments: Vec<&str> = Vec::new();
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+        format!("/{}", segments.join("/"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceError {
+    DuplicateQueryKey(String),
+}
+
+impl std::fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateQueryKey(key) => write!(f, "Query parameter {} is repeated", key),
+        }
+    }
+}
+
+impl std::error::Error for ResourceError {}
+
+impl std::fmt::Display for Resource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.canonical())
+    }
+}
+
+/// Condition on a single query parameter of a [`Resource`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QueryCondition {
+    /// parameter is present with exactly this value
+    Equals(String, String),
+    /// parameter is present with any value
+    Present(String),
+    /// parameter is not present
+    Absent(String),
+    /// parameter is present and parses to a number within the inclusive bounds
+    Range {
+        key: String,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+}
+
+impl QueryCondition {
+    pub fn matches(&self, resource: &Resource) -> bool {
+        match self {
+            Self::Equals(key, value) => resource.query_param(key) == Some(value.as_str()),
+            Self::Present(key) => resource.query.contains_key(key),
+            Self::Absent(key) => !resource.query.contains_key(key),
+            Self::Range { key, min, max } => {
+                match resource
+                    .query_param(key)
+                    .and_then(|v| v.parse::<f64>().ok())
+                {
+                    Some(value) => {
+                        min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+}
+
+/// Pattern matched against resources by pricing rules.
+///
+/// The path pattern is split into segments where `*` matches exactly one
+/// segment and a trailing `**` matches any remaining segments, including none.
+/// An optional method prefix restricts the pattern to one HTTP method.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::resource::{Resource, ResourcePattern};
+///
+/// let pattern = ResourcePattern::parse("GET /data/**").with_query_range("limit", None, Some(100.0));
+/// assert!(pattern.matches(&Resource::new("GET", "/data/reports?limit=10").unwrap()));
+/// assert!(!pattern.matches(&Resource::new("GET", "/data/reports?limit=10000").unwrap()));
+/// assert!(!pattern.matches(&Resource::new("POST", "/data/reports?limit=10").unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePattern {
+    pub method: Option<String>,
+    pub path: String,
+    pub query: Vec<QueryCondition>,
+}
+
+impl ResourcePattern {
+    /// parse `"[METHOD ]/path/pattern"`
+    pub fn parse(pattern: &str) -> Self {
+        let pattern = pattern.trim();
+        let (method, path) = match pattern.split_once(' ') {
+            Some((method, path)) => (Some(method.to_uppercase()), path.trim()),
+            None => (None, pattern),
+        };
+        Self {
+            method,
+            path: Resource::normalize_path(path),
+            query: Vec::new(),
+        }
+    }
+
+    pub fn with_query(mut self, condition: QueryCondition) -> Self {
+        self.query.push(condition);
+        self
+    }
+
+    pub fn with_query_equals(self, key: &str, value: &str) -> Self {
+        self.with_query(QueryCondition::Equals(key.to_string(), value.to_string()))
+    }
+
+    pub fn with_query_range(self, key: &str, min: Option<f64>, max: Option<f64>) -> Self {
+        self.with_query(QueryCondition::Range {
+            key: key.to_string(),
+            min,
+            max,
+        })
+    }
+
+    pub fn matches(&self, resource: &Resource) -> bool {
+        if let Some(method) = &self.method
+            && method != &resource.method
+        {
+            return false;
+        }
+        self.matches_path(&resource.path) && self.query.iter().all(|c| c.matches(resource))
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        let pattern: Vec<&str> = self.path.split('/').filter(|s| !s.is_empty()).collect();
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        for (index, expected) in pattern.iter().enumerate() {
+            if *expected == "**" && index == pattern.len() - 1 {
+                return true;
+            }
+            match segments.get(index) {
+                Some(segment) if *expected == "*" || expected == segment => {}
+                _ => return false,
+            }
+        }
+        pattern.len() == segments.len()
+    }
+}
@@ -0,0 +1,153 @@
+/// Access token revocation module.
+use crate::resource::Resource;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Debug)]
+pub enum RevocationError {
+    Backend(String),
+}
+
+impl std::fmt::Display for RevocationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Backend(msg) => write!(f, "Revocation store error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RevocationError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RevocationReason {
+    Reorged,
+    Refunded,
+    Fraudulent,
+    Other(String),
+}
+
+impl std::fmt::Display for RevocationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Reorged => write!(f, "payment reorged"),
+            Self::Refunded => write!(f, "payment refunded"),
+            Self::Fraudulent => write!(f, "payment fraudulent"),
+            Self::Other(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Everything a bulk revocation applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RevocationScope {
+    /// all access granted to a payer address
+    Payer(String),
+    /// all access to a resource path and the paths below it, e.g. after a
+    /// content takedown
+    Resource(String),
+}
+
+impl RevocationScope {
+    /// revocation store key of the scope
+    pub fn key(&self) -> String {
+        match self {
+            Self::Payer(address) => format!("payer:{}", address.to_lowercase()),
+            Self::Resource(path) => format!("resource:{}", Resource::path_of(path)),
+        }
+    }
+
+    /// whether a grant to `payer` for `resource` falls in the scope
+    pub fn covers(&self, payer: &str, resource: &Resource) -> bool {
+        match self {
+            Self::Payer(address) => address.eq_ignore_ascii_case(payer),
+            Self::Resource(path) => {
+                let path = Resource::path_of(path);
+                path == "/"
+                    || resource.path == path
+                    || resource
+                        .path
+                        .strip_prefix(&path)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+        }
+    }
+
+    /// keys of every scope covering a grant, the payer and the resource path
+    /// with each of its ancestors
+    pub fn keys_covering(payer: &str, resource: &Resource) -> Vec<String> {
+        let mut keys = vec![Self::Payer(payer.to_string()).key()];
+        let mut path = resource.path.as_str();
+        loop {
+            keys.push(Self::Resource(path.to_string()).key());
+            match path.rfind('/') {
+                Some(0) if path.len() > 1 => path = "/",
+                Some(index) if index > 0 => path = &path[..index],
+                _ => break,
+            }
+        }
+        keys
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationEntry {
+    pub reason: RevocationReason,
+    pub revoked_at: u64,
+    /// the entry can be dropped after this time, every token it covers has
+    /// expired by then
+    pub expires_at: u64,
+}
+
+/// Store of revoked receipt ids and payment nonces.
+///
+/// Entries only need to outlive the tokens they revoke, so every entry has an
+/// expiry after which backends may purge it. Shared backends make a
+/// revocation visible to every replica validating receipts.
+#[async_trait]
+pub trait RevocationStore: Send + Sync {
+    async fn revoke(&self, key: &str, entry: RevocationEntry) -> Result<(), RevocationError>;
+
+    /// the revocation entry for a key if it is present and not yet expired
+    async fn get(&self, key: &str, now: u64) -> Result<Option<RevocationEntry>, RevocationError>;
+
+    /// drop expired entries, returns how many were removed
+    async fn purge_expired(&self, now: u64) -> Result<usize, RevocationError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryRevocationStore {
+    entries: RwLock<HashMap<String, RevocationEntry>>,
+}
+
+impl InMemoryRevocationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RevocationStore for InMemoryRevocationStore {
+    async fn revoke(&self, key: &str, entry: RevocationEntry) -> Result<(), RevocationError> {
+        self.entries.write().unwrap().insert(key.to_string(), entry);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str, now: u64) -> Result<Option<RevocationEntry>, RevocationError> {
+        Ok(self
+            .entries
+            .read()
+            .unwrap()
+            .get(key)
+            .filter(|entry| now < entry.expires_at)
+            .cloned())
+    }
+
+    async fn purge_expired(&self, now: u64) -> Result<usize, RevocationError> {
+        let mut entries = self.entries.write().unwrap();
+        let before = entries.len();
+        entries.retain(|_, entry| now < entry.expires_at);
+        Ok(before - entries.len())
+    }
+}
@@ -0,0 +1,197 @@
+/// Project scaffolding module.
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const PAYWALL_PAGE: &str = include_str!("../templates/scaffold/paywall.html");
+const GITIGNORE: &str = include_str!("../templates/scaffold/gitignore");
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    UnknownStack(String),
+    InvalidName(String),
+    /// the target directory exists and is not empty
+    AlreadyExists(PathBuf),
+    Io(String),
+}
+
+impl std::fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownStack(stack) => {
+                write!(f, "Unknown stack {}, expected axum, actix or worker", stack)
+            }
+            Self::InvalidName(name) => write!(f, "Invalid package name: {}", name),
+            Self::AlreadyExists(path) => write!(f, "{} exists and is not empty", path.display()),
+            Self::Io(msg) => write!(f, "IO error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+/// Stack of a generated project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stack {
+    /// axum service with a paywall middleware
+    Axum,
+    /// actix-web service with a paywall middleware
+    Actix,
+    /// authorization worker for nginx `auth_request` or Envoy `ext_authz`
+    /// in front of any upstream, settling payment finality in the
+    /// background
+    Worker,
+}
+
+impl Stack {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Axum => "axum",
+            Self::Actix => "actix",
+            Self::Worker => "worker",
+        }
+    }
+
+    /// files of the stack as `(path, template)`
+    fn templates(&self) -> Vec<(&'static str, &'static str)> {
+        match self {
+            Self::Axum => vec![
+                (
+                    "Cargo.toml",
+                    include_str!("../templates/scaffold/axum/Cargo.toml"),
+                ),
+                (
+                    "src/lib.rs",
+                    include_str!("../templates/scaffold/axum/src/lib.rs"),
+                ),
+                (
+                    "src/main.rs",
+                    include_str!("../templates/scaffold/axum/src/main.rs"),
+                ),
+                (
+                    "tests/flow.rs",
+                    include_str!("../templates/scaffold/axum/tests/flow.rs"),
+                ),
+                ("static/paywall.html", PAYWALL_PAGE),
+            ],
+            Self::Actix => vec![
+                (
+                    "Cargo.toml",
+                    include_str!("../templates/scaffold/actix/Cargo.toml"),
+                ),
+                (
+                    "src/lib.rs",
+                    include_str!("../templates/scaffold/actix/src/lib.rs"),
+                ),
+                (
+                    "src/main.rs",
+                    include_str!("../templates/scaffold/actix/src/main.rs"),
+                ),
+                (
+                    "tests/flow.rs",
+                    include_str!("../templates/scaffold/actix/tests/flow.rs"),
+                ),
+                ("static/paywall.html", PAYWALL_PAGE),
+            ],
+            Self::Worker => vec![
+                (
+                    "Cargo.toml",
+                    include_str!("../templates/scaffold/worker/Cargo.toml"),
+                ),
+                (
+                    "src/lib.rs",
+                    include_str!("../templates/scaffold/worker/src/lib.rs"),
+                ),
+                (
+                    "src/main.rs",
+                    include_str!("../templates/scaffold/worker/src/main.rs"),
+                ),
+                (
+                    "tests/flow.rs",
+                    include_str!("../templates/scaffold/worker/tests/flow.rs"),
+                ),
+            ],
+        }
+    }
+}
+
+impl FromStr for Stack {
+    type Err = ScaffoldError;
+
+    fn from_str(stack: &str) -> Result<Self, Self::Err> {
+        match stack.to_ascii_lowercase().as_str() {
+            "axum" => Ok(Self::Axum),
+            "actix" | "actix-web" => Ok(Self::Actix),
+            "worker" => Ok(Self::Worker),
+            _ => Err(ScaffoldError::UnknownStack(stack.to_string())),
+        }
+    }
+}
+
+/// Generated file, relative to the project root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScaffoldFile {
+    pub path: String,
+    pub contents: String,
+}
+
+/// Files of a runnable project on the stack: the engine config,
+/// the paywall middleware or authorizer, the paywall page and a test of
+/// the paid flow against simulated chains.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::scaffold::{Stack, render};
+///
+/// let files = render(Stack::Axum, "paid-api").unwrap();
+/// assert!(files.iter().any(|file| file.path == "src/lib.rs"));
+/// ```
+pub fn render(stack: Stack, name: &str) -> Result<Vec<ScaffoldFile>, ScaffoldError> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(ScaffoldError::InvalidName(name.to_string()));
+    }
+    let crate_name = name.replace('-', "_");
+    let mut files: Vec<ScaffoldFile> = stack
+        .templates()
+        .into_iter()
+        .map(|(path, template)| ScaffoldFile {
+            path: path.to_string(),
+            contents: template
+                .replace("{{name}}", name)
+                .replace("{{crate}}", &crate_name)
+                .replace("{{version}}", env!("CARGO_PKG_VERSION")),
+        })
+        .collect();
+    files.push(ScaffoldFile {
+        path: ".gitignore".to_string(),
+        contents: GITIGNORE.to_string(),
+    });
+    Ok(files)
+}
+
+/// Writes the project into `dir`, which has to be missing or empty, and
+/// returns the written paths.
+pub fn scaffold(stack: Stack, name: &str, dir: &Path) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let files = render(stack, name)?;
+    let io_error = |e: std::io::Error| ScaffoldError::Io(e.to_string());
+    if dir.exists() && dir.read_dir().map_err(io_error)?.next().is_some() {
+        return Err(ScaffoldError::AlreadyExists(dir.to_path_buf()));
+    }
+    let mut written = Vec::new();
+    for file in files {
+        let path = dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        std::fs::write(&path, file.contents).map_err(io_error)?;
+        written.push(path);
+    }
+    Ok(written)
+}
@@ -0,0 +1,976 @@
+/// Challenge service module.
+use super::ChallengeService;
+use crate::clock::{Clock, SystemClock};
+use crate::config::{ConfigError, ConfigManager, CurrencyConfig, CurrencyType, NonceStrategy};
+use crate::context::RequestContext;
+use crate::core::{EngineError, PaymentSession};
+use crate::coupon::{CouponBook, CouponError};
+use crate::discovery::{DiscoveryDocument, PaymentOption};
+use crate::events::{EventEmitter, PaymentEventKind};
+use crate::hooks::{EngineHooks, HookDecision};
+use crate::i18n::{MessageCatalog, MessageKey};
+use crate::invoice::{INVOICE_EXTENSION, invoice};
+use crate::keys::KeyRing;
+use crate::ledger::{Ledger, LedgerError};
+use crate::pricing::{PriceQuote, PricingError, PricingProvider, PricingRule};
+use crate::rates::{NATIVE_TOKEN, RateProvider};
+use crate::redaction::Redactor;
+use crate::relay::{RelayError, Relayer};
+use crate::resource::{Resource, ResourcePattern};
+use crate::session::SessionDeriver;
+use crate::shard::ShardedMap;
+use crate::tax::{TAX_EXTENSION, TaxCalculator, tax_line};
+use crate::types::{
+    ChainConfig, ChainType, Currency, PaymentMetadata, PaymentRequest, RateQuote,
+    VerificationResult, X402ProtocolResponse, payment_reference,
+};
+use crate::verifier::units;
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Prices requests and issues the 402 challenges of new sessions into the
+/// session table it shares with the verification service. A request that
+/// needs no payment, e.g. with a full discount coupon or a funded budget
+/// account, is granted instead.
+pub struct Challenges {
+    config_manager: Arc<ConfigManager>,
+    sessions: Arc<ShardedMap<PaymentSession>>,
+    clock: Arc<dyn Clock>,
+    session_deriver: Option<SessionDeriver>,
+    pricing_provider: Option<Arc<dyn PricingProvider>>,
+    hooks: Vec<Arc<dyn EngineHooks>>,
+    coupons: Option<Arc<CouponBook>>,
+    ledger: Option<Arc<Ledger>>,
+    rate_provider: Option<Arc<dyn RateProvider>>,
+    relayer: Option<Arc<dyn Relayer>>,
+    tax_calculator: Option<Arc<dyn TaxCalculator>>,
+    messages: MessageCatalog,
+    key_ring: Option<Arc<KeyRing>>,
+    events: EventEmitter,
+}
+
+impl Challenges {
+    pub fn new(
+        config_manager: Arc<ConfigManager>,
+        sessions: Arc<ShardedMap<PaymentSession>>,
+    ) -> Result<Self, EngineError> {
+        let session_deriver = match &config_manager.get_config().sessions.nonce_strategy {
+            NonceStrategy::Random => None,
+            NonceStrategy::Deterministic { secret, epoch_secs } => {
+                if secret.is_empty() {
+                    return Err(EngineError::ConfigError(ConfigError::InvalidConfig(
+                        "deterministic session secret cannot be empty".to_string(),
+                    )));
+                }
+                Some(SessionDeriver::new(secret.as_bytes(), *epoch_secs))
+            }
+        };
+        let redactor = Redactor::new(config_manager.get_config().redaction.clone());
+        Ok(Self {
+            config_manager,
+            sessions,
+            clock: Arc::new(SystemClock),
+            session_deriver,
+            pricing_provider: None,
+            hooks: Vec::new(),
+            coupons: None,
+            ledger: None,
+            rate_provider: None,
+            relayer: None,
+            tax_calculator: None,
+            messages: MessageCatalog::new(),
+            key_ring: None,
+            events: EventEmitter::new(redactor),
+        })
+    }
+
+    /// sessions the challenges are issued into
+    pub fn with_sessions(mut self, sessions: Arc<ShardedMap<PaymentSession>>) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// set the provider deciding the amount charged per request
+    pub fn with_pricing_provider(mut self, pricing_provider: Arc<dyn PricingProvider>) -> Self {
+        self.pricing_provider = Some(pricing_provider);
+        self
+    }
+
+    /// append hooks around challenges, hooks run in registration order
+    pub fn with_hooks(mut self, hooks: Arc<dyn EngineHooks>) -> Self {
+        self.hooks.push(hooks);
+        self
+    }
+
+    /// coupons discounting newly issued sessions
+    pub fn with_coupons(mut self, coupons: Arc<CouponBook>) -> Self {
+        self.coupons = Some(coupons);
+        self
+    }
+
+    /// budget accounts drawn down instead of issuing a challenge when the
+    /// payer proves it owns one with enough balance
+    pub fn with_ledger(mut self, ledger: Arc<Ledger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// prices pinned on any-token challenges
+    pub fn with_rate_provider(mut self, rate_provider: Arc<dyn RateProvider>) -> Self {
+        self.rate_provider = Some(rate_provider);
+        self
+    }
+
+    /// relayer whose gas surcharge is added to token challenges
+    pub fn with_relayer(mut self, relayer: Arc<dyn Relayer>) -> Self {
+        self.relayer = Some(relayer);
+        self
+    }
+
+    /// tax added to or annotated on every quote
+    pub fn with_tax_calculator(mut self, tax_calculator: Arc<dyn TaxCalculator>) -> Self {
+        self.tax_calculator = Some(tax_calculator);
+        self
+    }
+
+    /// translations of challenge descriptions, chosen by the locale of the
+    /// request
+    pub fn with_message_catalog(mut self, messages: MessageCatalog) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// sign issued challenges with the active key of the key ring
+    pub fn with_key_ring(mut self, key_ring: Arc<KeyRing>) -> Self {
+        self.key_ring = Some(key_ring);
+        self
+    }
+
+    /// emitter of the challenge and grant events
+    pub fn with_events(mut self, events: EventEmitter) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// challenge for the resource, a retry of an unpaid session gets its
+    /// challenge back and a retry of another session keeps its amount and
+    /// coupon, a request needing no payment is granted right away
+    pub(crate) async fn challenge(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        payment_nonce: Option<&str>,
+        custom_amount: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<VerificationResult, EngineError> {
+        if let Some(payment_request) =
+            payment_nonce.and_then(|nonce| self.pending_session(user_address, nonce, resource))
+        {
+            return Ok(VerificationResult {
+                should_serve_content: false,
+                http_status: 402,
+                x402_response: Some(self.protocol_response(resource, &payment_request, context)?),
+                verification: None,
+                receipt: None,
+                retry_after: None,
+            });
+        }
+        // a retry for an existing session keeps the amount quoted at issuance,
+        // whatever custom amount the follow-up request carries, a coupon was
+        // already applied to that amount
+        let session_amount =
+            payment_nonce.and_then(|nonce| self.session_amount(user_address, nonce, resource));
+        let (coupon_code, session_coupon) = match session_amount {
+            Some(_) => (
+                None,
+                payment_nonce.and_then(|nonce| self.session_coupon(nonce)),
+            ),
+            None => (coupon_code, coupon_code.map(str::to_string)),
+        };
+        if session_amount.is_none()
+            && let Some(amount) = custom_amount
+        {
+            self.check_custom_amount(amount)?;
+        }
+        let payment_request = self
+            .create_payment_request(
+                user_address,
+                resource,
+                session_amount.as_deref().or(custom_amount),
+                coupon_code,
+                context,
+            )
+            .await?;
+        // a full discount leaves nothing to pay
+        if let Some(code) = coupon_code
+            && payment_request.amount == "0"
+        {
+            if let Some(coupons) = &self.coupons {
+                coupons.redeem_reserved(code, &payment_request.nonce, self.clock.now())?;
+            }
+            self.emit(
+                user_address,
+                resource,
+                context,
+                PaymentEventKind::CouponRedeemed {
+                    nonce: payment_request.nonce.clone(),
+                    code: code.to_string(),
+                },
+            )
+            .await?;
+            self.emit(
+                user_address,
+                resource,
+                context,
+                PaymentEventKind::AccessGranted,
+            )
+            .await?;
+            return Ok(VerificationResult {
+                should_serve_content: true,
+                http_status: 200,
+                x402_response: None,
+                verification: None,
+                receipt: None,
+                retry_after: None,
+            });
+        }
+        // an agent with a funded budget account pays off-chain, once the
+        // request proves it owns the account
+        if let Some(ledger) = &self.ledger
+            && let Some(account) = ledger.authorize(user_address, context)
+        {
+            let amount: u128 = payment_request
+                .amount
+                .parse()
+                .map_err(|_| LedgerError::InvalidAmount(payment_request.amount.clone()))?;
+            match ledger.draw(&account.name, amount, &resource.canonical()) {
+                Ok(balance) => {
+                    self.emit(
+                        user_address,
+                        resource,
+                        context,
+                        PaymentEventKind::BudgetDrawn {
+                            account: account.name,
+                            amount: payment_request.amount,
+                            balance: balance.to_string(),
+                        },
+                    )
+                    .await?;
+                    return Ok(VerificationResult {
+                        should_serve_content: true,
+                        http_status: 200,
+                        x402_response: None,
+                        verification: None,
+                        receipt: None,
+                        retry_after: None,
+                    });
+                }
+                Err(LedgerError::InsufficientFunds { .. }) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        let mut payment_request = payment_request;
+        for hooks in &self.hooks {
+            if let HookDecision::Veto(reason) = hooks
+                .before_challenge(user_address, resource, context, &mut payment_request)
+                .await
+            {
+                return self.veto(user_address, resource, context, reason).await;
+            }
+        }
+        // a re-issued session moves the reservation of the coupon to its new
+        // nonce
+        if let (Some(coupons), Some(code), Some(nonce)) =
+            (&self.coupons, session_coupon.as_deref(), payment_nonce)
+            && nonce != payment_request.nonce
+        {
+            coupons.release(code, nonce);
+        }
+        self.reserve_coupon(session_coupon.as_deref(), &payment_request)?;
+        let x402_response = self.protocol_response(resource, &payment_request, context)?;
+        self.store_payment_session(
+            user_address,
+            resource,
+            payment_request.clone(),
+            session_coupon.as_deref(),
+        );
+        self.emit(
+            user_address,
+            resource,
+            context,
+            PaymentEventKind::ChallengeIssued { payment_request },
+        )
+        .await?;
+        for hooks in &self.hooks {
+            hooks
+                .after_challenge(user_address, resource, context, &x402_response)
+                .await;
+        }
+        Ok(VerificationResult {
+            should_serve_content: false,
+            http_status: 402,
+            x402_response: Some(x402_response),
+            verification: None,
+            receipt: None,
+            retry_after: None,
+        })
+    }
+
+    /// signed 402 response carrying a payment request
+    fn protocol_response(
+        &self,
+        resource: &Resource,
+        payment_request: &PaymentRequest,
+        context: &RequestContext,
+    ) -> Result<X402ProtocolResponse, EngineError> {
+        let config = self.config_manager.get_config();
+        let mut x402_response = X402ProtocolResponse {
+            status: 402,
+            payment_required: payment_request.clone(),
+            verification_url: Some(format!(
+                "{}/{}",
+                config.service.base_verification_url, payment_request.nonce
+            )),
+            signature: None,
+            message: Some(self.messages.message_for(
+                context,
+                MessageKey::PaymentRequired,
+                &[
+                    ("amount", &payment_request.amount),
+                    ("resource", &resource.path_with_query()),
+                ],
+            )),
+            requote: None,
+        };
+        if let Some(key_ring) = &self.key_ring {
+            key_ring.sign_response(&mut x402_response, self.clock.now())?;
+        }
+        Ok(x402_response)
+    }
+
+    /// Unpaid session a retry presents, returned as it was issued so a
+    /// payment already on its way is not stranded on an orphaned nonce.
+    /// Expired sessions and sessions an operator decided are re-quoted.
+    fn pending_session(
+        &self,
+        user_address: &str,
+        payment_nonce: &str,
+        resource: &Resource,
+    ) -> Option<PaymentRequest> {
+        let sessions = self.sessions.read(payment_nonce);
+        sessions
+            .get(payment_nonce)
+            .filter(|session| {
+                !session.verified
+                    && session.manual_override.is_none()
+                    && session.user_address == user_address
+                    && self
+                        .config_manager
+                        .get_config()
+                        .sessions
+                        .binding
+                        .permits(&session.resource, resource)
+                    && self
+                        .config_manager
+                        .get_config()
+                        .payments
+                        .expired_at(&session.payment_request, self.clock.now())
+                        .is_none()
+            })
+            .map(|session| session.payment_request.clone())
+    }
+
+    /// Catalog of the paid resources for [`DISCOVERY_PATH`](crate::discovery::DISCOVERY_PATH),
+    /// listing the prices published by the pricing provider, or the default
+    /// amount for every resource when it publishes none. Free rules, priced
+    /// at zero, are left out.
+    pub fn discovery_document(&self) -> Result<DiscoveryDocument, EngineError> {
+        let config = self.config_manager.get_config();
+        let option = |chain: &ChainConfig, currency: &Currency, amount: &str| {
+            PaymentOption::new(
+                chain,
+                currency,
+                amount,
+                &self.config_manager.get_service_address(),
+                config.payments.expiration_time_secs,
+            )
+        };
+        let mut rules = self
+            .pricing_provider
+            .as_ref()
+            .map(|pricing_provider| pricing_provider.catalog())
+            .unwrap_or_default();
+        if rules.is_empty() {
+            rules.push(PricingRule::new(
+                ResourcePattern::parse("/**"),
+                &config.payments.default_amount,
+            ));
+        }
+        rules.iter().filter(|rule| rule.amount != "0").try_fold(
+            DiscoveryDocument::new(&config.service),
+            |document, rule| {
+                let chain = self.quote_chain(rule.chain.as_ref())?;
+                let currency = match &rule.currency {
+                    Some(currency) => currency.clone(),
+                    None => self.chain_currency(chain)?,
+                };
+                Ok(document.with_rule(rule, &[option(chain, &currency, &rule.amount)]))
+            },
+        )
+    }
+
+    async fn create_payment_request(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        custom_amount: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<PaymentRequest, EngineError> {
+        let config = self.config_manager.get_config();
+        let quote = self
+            .resolve_quote(user_address, resource, custom_amount, context)
+            .await?;
+        let quote = self.apply_coupon(quote, coupon_code, resource)?;
+        let now = self.clock.now();
+        let (nonce, expires_at) = match &self.session_deriver {
+            Some(deriver) => {
+                let epoch = deriver.epoch_at(now);
+                (
+                    deriver.derive_nonce(user_address, &resource.canonical(), &quote.amount, epoch),
+                    deriver.expires_at(epoch),
+                )
+            }
+            None => (
+                Uuid::new_v4().to_string(),
+                now + config.payments.expiration_time_secs,
+            ),
+        };
+        let payment_request =
+            self.build_payment_request(user_address, resource, quote, nonce, expires_at, context)?;
+        let payment_request = self.add_tax(payment_request, resource, context).await?;
+        let payment_request = self.add_relay_surcharge(payment_request).await?;
+        Ok(self.pin_rates(payment_request).await)
+    }
+
+    /// pin the current price of every allowlisted token of an any-token
+    /// challenge, verification values payments at these rates until the
+    /// challenge expires. Tokens without a price are valued at verification
+    /// time.
+    async fn pin_rates(&self, mut payment_request: PaymentRequest) -> PaymentRequest {
+        let (Some(rate_provider), Currency::AnyToken { allowlist }) =
+            (&self.rate_provider, &payment_request.currency)
+        else {
+            return payment_request;
+        };
+        let chain_type = &payment_request.chain.chain_type;
+        let prices = join_all(
+            allowlist
+                .iter()
+                .map(|token| rate_provider.usd_price(chain_type, token)),
+        )
+        .await;
+        let rates: BTreeMap<String, f64> = allowlist
+            .iter()
+            .zip(prices)
+            .filter_map(|(token, price)| Some((token.to_lowercase(), price.ok()?)))
+            .collect();
+        if !rates.is_empty() {
+            payment_request.quote = Some(RateQuote {
+                quoted_at: self.clock.now(),
+                rates,
+            });
+        }
+        payment_request
+    }
+
+    /// add the tax of the quoted amount, after the nonce is derived like the
+    /// relay surcharge, and annotate the challenge with the tax line
+    async fn add_tax(
+        &self,
+        mut payment_request: PaymentRequest,
+        resource: &Resource,
+        context: &RequestContext,
+    ) -> Result<PaymentRequest, EngineError> {
+        let Some(tax_calculator) = &self.tax_calculator else {
+            return Ok(payment_request);
+        };
+        let Some(tax) = tax_calculator
+            .calculate(&payment_request.amount, resource, context)
+            .await?
+        else {
+            return Ok(payment_request);
+        };
+        let amount: u128 = payment_request
+            .amount
+            .parse()
+            .map_err(|_| PricingError::InvalidAmount(payment_request.amount.clone()))?;
+        payment_request.amount = amount.saturating_add(tax.surcharge()).to_string();
+        let encode = |value: serde_json::Result<serde_json::Value>| {
+            value.map_err(|e| PricingError::InvalidAmount(e.to_string()))
+        };
+        if let Some(invoice) = invoice(&payment_request) {
+            let invoice = encode(serde_json::to_value(invoice.with_tax(tax.clone())))?;
+            payment_request.metadata = payment_request
+                .metadata
+                .with_extension(INVOICE_EXTENSION, invoice);
+        }
+        let tax = encode(serde_json::to_value(&tax))?;
+        payment_request.metadata = payment_request.metadata.with_extension(TAX_EXTENSION, tax);
+        Ok(payment_request)
+    }
+
+    /// add the relay gas cost to a token challenge, after the nonce is
+    /// derived so deterministic sessions can still be recovered
+    async fn add_relay_surcharge(
+        &self,
+        mut payment_request: PaymentRequest,
+    ) -> Result<PaymentRequest, EngineError> {
+        let Some(relayer) = &self.relayer else {
+            return Ok(payment_request);
+        };
+        if !matches!(payment_request.currency, Currency::Token { .. })
+            || payment_request.amount == "0"
+        {
+            return Ok(payment_request);
+        }
+        let surcharge = relayer.gas_surcharge(&payment_request.chain).await?;
+        if surcharge > 0 {
+            let amount: u128 = payment_request
+                .amount
+                .parse()
+                .map_err(|_| RelayError::InvalidAuthorization("invalid amount".to_string()))?;
+            payment_request.amount = amount.saturating_add(surcharge).to_string();
+        }
+        Ok(payment_request)
+    }
+
+    /// resolve the price, an explicit custom amount wins over the pricing
+    /// provider which wins over the configured default amount
+    async fn resolve_quote(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        custom_amount: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<PriceQuote, EngineError> {
+        if let Some(amount) = custom_amount {
+            return Ok(PriceQuote::new(amount));
+        }
+        if let Some(pricing_provider) = &self.pricing_provider
+            && let Some(quote) = pricing_provider
+                .quote(user_address, resource, context)
+                .await?
+        {
+            return Ok(quote);
+        }
+        Ok(PriceQuote::new(
+            &self.config_manager.get_config().payments.default_amount,
+        ))
+    }
+
+    /// reject a custom amount that is not a positive amount of the default
+    /// currency, written the way its verifier reads challenges, or falls
+    /// outside its configured bounds
+    fn check_custom_amount(&self, amount: &str) -> Result<(), EngineError> {
+        let currency = self.default_currency()?;
+        let units = self
+            .custom_amount_units(amount, &currency)
+            .filter(|units| *units > 0)
+            .ok_or_else(|| {
+                PricingError::InvalidAmount(format!(
+                    "{} is not a positive amount of the default currency",
+                    amount
+                ))
+            })?;
+        let token = match currency {
+            Currency::Token { address, .. } => address,
+            _ => NATIVE_TOKEN.to_string(),
+        };
+        let amount_bounds = &self.config_manager.get_config().payments.amount_bounds;
+        // EVM addresses are case-insensitive, Solana mints are not
+        let bounds = amount_bounds.get(&token).or_else(|| {
+            amount_bounds
+                .iter()
+                .find(|(key, _)| token.starts_with("0x") && key.eq_ignore_ascii_case(&token))
+                .map(|(_, bounds)| bounds)
+        });
+        match bounds {
+            Some(bounds) if !bounds.contains(units) => Err(PricingError::OutOfBounds {
+                amount: amount.to_string(),
+                bounds: *bounds,
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// discount a quote with a coupon, checked against the coupon book
+    fn apply_coupon(
+        &self,
+        quote: PriceQuote,
+        coupon_code: Option<&str>,
+        resource: &Resource,
+    ) -> Result<PriceQuote, EngineError> {
+        let Some(code) = coupon_code else {
+            return Ok(quote);
+        };
+        let coupons = self
+            .coupons
+            .as_ref()
+            .ok_or_else(|| CouponError::Unknown(code.to_string()))?;
+        let amount = coupons.apply(code, resource, &quote.amount, self.clock.now())?;
+        let invoice = quote.invoice.map(|invoice| {
+            let quoted = quote.amount.parse::<u128>().unwrap_or(0);
+            let discounted = amount.parse::<u128>().unwrap_or(0);
+            invoice.with_discount(quoted.saturating_sub(discounted))
+        });
+        Ok(PriceQuote {
+            amount,
+            invoice,
+            ..quote
+        })
+    }
+
+    /// challenge stored for a session, whatever its payer or state
+    pub(crate) fn session_request(&self, payment_nonce: &str) -> Option<PaymentRequest> {
+        self.sessions
+            .read(payment_nonce)
+            .get(payment_nonce)
+            .map(|session| session.payment_request.clone())
+    }
+
+    /// coupon recorded against a session
+    pub(crate) fn session_coupon(&self, payment_nonce: &str) -> Option<String> {
+        self.sessions
+            .read(payment_nonce)
+            .get(payment_nonce)
+            .and_then(|session| session.coupon_code.clone())
+    }
+
+    /// hold a redemption of the coupon for a session until it is paid or
+    /// expires, payments are accepted until the end of the grace period
+    fn reserve_coupon(
+        &self,
+        coupon_code: Option<&str>,
+        payment_request: &PaymentRequest,
+    ) -> Result<(), EngineError> {
+        let (Some(coupons), Some(code)) = (&self.coupons, coupon_code) else {
+            return Ok(());
+        };
+        let grace = self.config_manager.get_config().payments.expiry_grace_secs;
+        coupons.reserve(
+            code,
+            &payment_request.nonce,
+            self.clock.now(),
+            payment_request
+                .expires_at
+                .map(|expires_at| expires_at.saturating_add(grace).saturating_add(1)),
+        )?;
+        Ok(())
+    }
+
+    fn build_payment_request(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        quote: PriceQuote,
+        nonce: String,
+        expires_at: u64,
+        context: &RequestContext,
+    ) -> Result<PaymentRequest, EngineError> {
+        let config = self.config_manager.get_config();
+        let chain = self.quote_chain(quote.chain.as_ref())?;
+        let metadata = PaymentMetadata::new()
+            .with_resource(&resource.to_string())
+            .with_merchant_name(&config.service.name)
+            .merge(quote.metadata);
+        let metadata = match &quote.invoice {
+            Some(invoice) => metadata.with_extension(
+                INVOICE_EXTENSION,
+                serde_json::to_value(invoice)
+                    .map_err(|e| PricingError::InvalidAmount(e.to_string()))?,
+            ),
+            None => metadata,
+        };
+        Ok(PaymentRequest {
+            amount: quote.amount,
+            currency: match quote.currency {
+                Some(currency) => currency,
+                None => self.chain_currency(chain)?,
+            },
+            recipient: self.config_manager.get_service_address(),
+            chain: chain.clone(),
+            description: Some(quote.description.unwrap_or_else(|| {
+                self.messages.message_for(
+                    context,
+                    MessageKey::AccessTo,
+                    &[("resource", &resource.path_with_query())],
+                )
+            })),
+            expires_at: Some(expires_at),
+            beneficiary: config.payments.delegated.then(|| user_address.to_string()),
+            reference: config.payments.delegated.then(|| payment_reference(&nonce)),
+            nonce,
+            metadata,
+            quote: None,
+        })
+    }
+
+    /// smallest units of an amount of the default chain: ERC-20 amounts are
+    /// whole tokens, Solana amounts lamports or decimal SOL and decimal whole
+    /// tokens, other native amounts are in the smallest unit
+    fn custom_amount_units(&self, amount: &str, currency: &Currency) -> Option<u128> {
+        let integer = || {
+            amount
+                .bytes()
+                .all(|byte| byte.is_ascii_digit())
+                .then(|| amount.parse::<u128>().ok())
+                .flatten()
+        };
+        let chain_type = &self.config_manager.get_config().default_chain;
+        match currency {
+            Currency::Native if chain_type.is_solana() => {
+                units::parse_amount_to_lamports(amount).ok().map(u128::from)
+            }
+            Currency::Token { decimals, .. } if chain_type.is_solana() => {
+                units::parse_token_amount(amount, *decimals).ok()
+            }
+            Currency::Token { decimals, .. } => {
+                integer()?.checked_mul(10u128.checked_pow(u32::from(*decimals))?)
+            }
+            _ => integer(),
+        }
+    }
+
+    /// configured chain a quote is charged on, the default chain unless the
+    /// quote picks another one
+    fn quote_chain(&self, chain_type: Option<&ChainType>) -> Result<&ChainConfig, EngineError> {
+        let Some(chain_type) = chain_type else {
+            return Ok(self.config_manager.get_default_chain_config()?);
+        };
+        self.config_manager
+            .get_config()
+            .chains
+            .get(chain_type)
+            .ok_or_else(|| ConfigError::ChainMissing(chain_type.clone()).into())
+    }
+
+    /// currency of a quote without one, the default currency belongs to the
+    /// default chain so other chains are charged in their native currency
+    fn chain_currency(&self, chain: &ChainConfig) -> Result<Currency, EngineError> {
+        if chain.chain_type == self.config_manager.get_config().default_chain {
+            self.default_currency()
+        } else {
+            Ok(Currency::Native)
+        }
+    }
+
+    /// currency challenges are issued in
+    pub(crate) fn default_currency(&self) -> Result<Currency, EngineError> {
+        let CurrencyConfig {
+            currency_type,
+            address,
+            decimals,
+        } = &self.config_manager.get_config().service.default_currency;
+        let currency = match currency_type {
+            CurrencyType::Native => Currency::Native,
+            CurrencyType::Erc20 => {
+                let token_address = address.clone().ok_or(EngineError::InvalidCurrencyConfig)?;
+                Currency::Token {
+                    address: token_address,
+                    decimals: *decimals,
+                }
+            }
+            _ => Currency::Native,
+        };
+        Ok(currency)
+    }
+
+    /// amount quoted for a stored session the payer may use for the resource,
+    /// before the tax that is added again on re-issuance
+    fn session_amount(
+        &self,
+        user_address: &str,
+        payment_nonce: &str,
+        resource: &Resource,
+    ) -> Option<String> {
+        let sessions = self.sessions.read(payment_nonce);
+        sessions
+            .get(payment_nonce)
+            .filter(|session| {
+                session.user_address == user_address
+                    && self.config_manager.get_config().sessions.binding.permits(&session.resource, resource)
+                    // an expired session is re-quoted at the current price
+                    && self.config_manager.get_config().payments.expired_at(&session.payment_request, self.clock.now())
+                        .is_none()
+            })
+            .map(|session| {
+                let payment_request = &session.payment_request;
+                match (
+                    tax_line(payment_request),
+                    payment_request.amount.parse::<u128>(),
+                ) {
+                    (Some(tax), Ok(amount)) => amount.saturating_sub(tax.surcharge()).to_string(),
+                    _ => payment_request.amount.clone(),
+                }
+            })
+    }
+
+    /// rebuild a session issued by another replica from its deterministic
+    /// nonce, a no-op for random nonces or sessions already stored locally
+    pub(crate) async fn recover_derived_session(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        payment_nonce: &str,
+        custom_amount: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<(), EngineError> {
+        let Some(deriver) = &self.session_deriver else {
+            return Ok(());
+        };
+        if self
+            .sessions
+            .read(payment_nonce)
+            .contains_key(payment_nonce)
+        {
+            return Ok(());
+        }
+        let quote = self
+            .resolve_quote(user_address, resource, custom_amount, context)
+            .await?;
+        let quote = self.apply_coupon(quote, coupon_code, resource)?;
+        let Some(epoch) = deriver.find_epoch(
+            payment_nonce,
+            user_address,
+            &resource.canonical(),
+            &quote.amount,
+            self.clock.now(),
+        ) else {
+            return Ok(());
+        };
+        let payment_request = self.build_payment_request(
+            user_address,
+            resource,
+            quote,
+            payment_nonce.to_string(),
+            deriver.expires_at(epoch),
+            context,
+        )?;
+        let payment_request = self.add_tax(payment_request, resource, context).await?;
+        // the rates pinned by the issuing replica are unknown here, any-token
+        // payments of a recovered session are valued at verification time
+        let payment_request = self.add_relay_surcharge(payment_request).await?;
+        self.reserve_coupon(coupon_code, &payment_request)?;
+        self.store_payment_session(user_address, resource, payment_request, coupon_code);
+        Ok(())
+    }
+
+    fn store_payment_session(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        payment_request: PaymentRequest,
+        coupon_code: Option<&str>,
+    ) {
+        let mut sessions = self.sessions.write(&payment_request.nonce);
+        // a re-issued deterministic nonce keeps its attempt counters, so
+        // retries cannot reset a lockout
+        let previous = sessions.get(&payment_request.nonce);
+        let attempts = previous
+            .map(|session| session.attempts.clone())
+            .unwrap_or_default();
+        let dispute = previous.and_then(|session| session.dispute.clone());
+        let manual_override = previous.and_then(|session| session.manual_override.clone());
+        let coupon_code = coupon_code
+            .map(str::to_string)
+            .or_else(|| previous.and_then(|session| session.coupon_code.clone()));
+        let session = PaymentSession {
+            user_address: user_address.to_string(),
+            resource: resource.clone(),
+            payment_request,
+            created_at: self.clock.now(),
+            verified: false,
+            attempts,
+            dispute,
+            manual_override,
+            coupon_code,
+        };
+        sessions.insert(session.payment_request.nonce.clone(), session);
+    }
+
+    /// 403 for a request stopped by a hook
+    pub(crate) async fn veto(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        context: &RequestContext,
+        reason: String,
+    ) -> Result<VerificationResult, EngineError> {
+        self.emit(
+            user_address,
+            resource,
+            context,
+            PaymentEventKind::AccessDenied { reason },
+        )
+        .await?;
+        Ok(VerificationResult {
+            should_serve_content: false,
+            http_status: 403,
+            x402_response: None,
+            verification: None,
+            receipt: None,
+            retry_after: None,
+        })
+    }
+
+    async fn emit(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        context: &RequestContext,
+        kind: PaymentEventKind,
+    ) -> Result<(), EngineError> {
+        self.events
+            .emit(user_address, resource, context, kind)
+            .await
+            .map_err(EngineError::StoreError)
+    }
+}
+
+#[async_trait]
+impl ChallengeService for Challenges {
+    async fn issue_challenge(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        custom_amount: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<VerificationResult, EngineError> {
+        self.challenge(
+            user_address,
+            resource,
+            None,
+            custom_amount,
+            coupon_code,
+            context,
+        )
+        .await
+    }
+
+    fn discovery_document(&self) -> Result<DiscoveryDocument, EngineError> {
+        Challenges::discovery_document(self)
+    }
+}
@@ -0,0 +1,296 @@
+/// Entitlement service module.
+use super::EntitlementService;
+use crate::audit::{AuditAction, AuditEntry, AuditLog, InMemoryAuditLog};
+use crate::clock::{Clock, SystemClock};
+use crate::config::ConfigManager;
+use crate::context::RequestContext;
+use crate::core::{EngineError, PaymentSession};
+use crate::dispute::{ManualOverride, OverrideDecision};
+use crate::events::{EventEmitter, PaymentEventKind};
+use crate::invoice::invoice;
+use crate::keys::{JwkSet, KeyRing};
+use crate::receipt::{RECEIPT_DOMAIN, Receipt, ReceiptError};
+use crate::redaction::Redactor;
+use crate::resource::Resource;
+use crate::revocation::{
+    InMemoryRevocationStore, RevocationEntry, RevocationReason, RevocationScope, RevocationStore,
+};
+use crate::shard::ShardedMap;
+use crate::types::PaymentVerification;
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Issues receipts for verified payments and decides whether the access
+/// they grant still holds. Validation only needs the published keys and
+/// the revocation store, so it runs anywhere the store is shared.
+pub struct Entitlements {
+    config_manager: Arc<ConfigManager>,
+    key_ring: Option<Arc<KeyRing>>,
+    revocation_store: Arc<dyn RevocationStore>,
+    sessions: Arc<ShardedMap<PaymentSession>>,
+    clock: Arc<dyn Clock>,
+    audit_log: Arc<dyn AuditLog>,
+    events: EventEmitter,
+}
+
+impl Entitlements {
+    pub fn new(
+        config_manager: Arc<ConfigManager>,
+        sessions: Arc<ShardedMap<PaymentSession>>,
+    ) -> Self {
+        let redactor = Redactor::new(config_manager.get_config().redaction.clone());
+        Self {
+            config_manager,
+            key_ring: None,
+            revocation_store: Arc::new(InMemoryRevocationStore::new()),
+            sessions,
+            clock: Arc::new(SystemClock),
+            audit_log: Arc::new(InMemoryAuditLog::new()),
+            events: EventEmitter::new(redactor),
+        }
+    }
+
+    /// keys signing receipts and checking the receipts presented
+    pub fn with_key_ring(mut self, key_ring: Arc<KeyRing>) -> Self {
+        self.key_ring = Some(key_ring);
+        self
+    }
+
+    /// use a shared revocation store so revocations reach every replica
+    pub fn with_revocation_store(mut self, revocation_store: Arc<dyn RevocationStore>) -> Self {
+        self.revocation_store = revocation_store;
+        self
+    }
+
+    /// sessions revoked by [`revoke_entitlements`](Self::revoke_entitlements)
+    pub fn with_sessions(mut self, sessions: Arc<ShardedMap<PaymentSession>>) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// audit log of bulk revocations, entries are appended as given
+    pub fn with_audit_log(mut self, audit_log: Arc<dyn AuditLog>) -> Self {
+        self.audit_log = audit_log;
+        self
+    }
+
+    /// emitter of the revocation events
+    pub fn with_events(mut self, events: EventEmitter) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn key_ring(&self) -> Option<&Arc<KeyRing>> {
+        self.key_ring.as_ref()
+    }
+
+    /// published JWKS document, served by integrations at
+    /// [`JWKS_PATH`](crate::keys::JWKS_PATH)
+    pub fn jwks(&self) -> Option<JwkSet> {
+        self.key_ring
+            .as_ref()
+            .map(|key_ring| key_ring.jwks(self.clock.now()))
+    }
+
+    /// issue a signed receipt for a verified payment, `None` without signing
+    /// keys
+    pub fn issue_receipt(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        nonce: &str,
+        verification: &PaymentVerification,
+    ) -> Result<Option<Receipt>, EngineError> {
+        let Some(key_ring) = &self.key_ring else {
+            return Ok(None);
+        };
+        let now = self.clock.now();
+        let mut receipt = Receipt {
+            receipt_id: Uuid::new_v4().to_string(),
+            nonce: nonce.to_string(),
+            payer: user_address.to_string(),
+            resource: resource.canonical(),
+            chain_id: verification.chain.chain_id.clone(),
+            amount: verification.paid_amount.clone(),
+            transaction_hash: verification.transaction_hash.clone(),
+            issued_at: now,
+            expires_at: now + self.config_manager.get_config().receipts.ttl_secs,
+            invoice_id: self
+                .sessions
+                .read(nonce)
+                .get(nonce)
+                .and_then(|session| invoice(&session.payment_request))
+                .map(|invoice| invoice.invoice_id),
+            signature: None,
+        };
+        receipt.signature = Some(key_ring.sign_payload(RECEIPT_DOMAIN, &receipt, now)?);
+        Ok(Some(receipt))
+    }
+
+    /// Validates a receipt presented as access token: signature against the
+    /// published keys, expiry, and revocation of the receipt or its payment.
+    pub async fn validate_receipt(&self, receipt: &Receipt) -> Result<(), EngineError> {
+        let key_ring = self.key_ring.as_ref().ok_or(ReceiptError::NoKeyRing)?;
+        let signature = receipt.signature.as_ref().ok_or(ReceiptError::Unsigned)?;
+        let now = self.clock.now();
+        if now >= receipt.expires_at {
+            return Err(ReceiptError::Expired.into());
+        }
+        key_ring
+            .verify_payload(RECEIPT_DOMAIN, &receipt.unsigned(), signature, now)
+            .map_err(ReceiptError::from)?;
+        for key in [&receipt.receipt_id, &receipt.nonce] {
+            if let Some(entry) = self
+                .revocation_store
+                .get(key, now)
+                .await
+                .map_err(ReceiptError::from)?
+            {
+                return Err(ReceiptError::Revoked(entry.reason).into());
+            }
+        }
+        // bulk revocations cover the receipts issued before them
+        let resource = Resource::from_canonical(&receipt.resource)
+            .map_err(|e| ReceiptError::Malformed(e.to_string()))?;
+        for key in RevocationScope::keys_covering(&receipt.payer, &resource) {
+            if let Some(entry) = self
+                .revocation_store
+                .get(&key, now)
+                .await
+                .map_err(ReceiptError::from)?
+                && receipt.issued_at <= entry.revoked_at
+            {
+                return Err(ReceiptError::Revoked(entry.reason).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// revoke a single receipt by id
+    pub async fn revoke_receipt(
+        &self,
+        receipt_id: &str,
+        reason: RevocationReason,
+    ) -> Result<(), EngineError> {
+        self.revoke(receipt_id, reason).await
+    }
+
+    /// revoke every receipt issued for the payment session, e.g. after a
+    /// reorg or refund
+    pub async fn revoke_payment(
+        &self,
+        payment_nonce: &str,
+        reason: RevocationReason,
+    ) -> Result<(), EngineError> {
+        self.revoke(payment_nonce, reason).await
+    }
+
+    /// Revokes all access granted to a payer or for a resource. Matching
+    /// sessions are refused like a manual revoke, receipts issued so far
+    /// are rejected through the revocation store and an
+    /// [`AccessRevoked`](PaymentEventKind::AccessRevoked) event is emitted per
+    /// session. Returns the number of sessions revoked.
+    pub async fn revoke_entitlements(
+        &self,
+        operator: &str,
+        scope: RevocationScope,
+        reason: &str,
+    ) -> Result<usize, EngineError> {
+        let now = self.clock.now();
+        let mut revoked: Vec<(String, String, Resource)> = Vec::new();
+        self.sessions.for_each_mut(|nonce, session| {
+            if !scope.covers(&session.user_address, &session.resource) {
+                return;
+            }
+            session.verified = false;
+            session.manual_override = Some(ManualOverride {
+                decision: OverrideDecision::Revoke,
+                operator: operator.to_string(),
+                reason: reason.to_string(),
+                decided_at: now,
+            });
+            revoked.push((
+                nonce.clone(),
+                session.user_address.clone(),
+                session.resource.clone(),
+            ));
+        });
+        self.revoke(
+            &scope.key(),
+            RevocationReason::Other(format!("revoked by {}: {}", operator, reason)),
+        )
+        .await?;
+        for (nonce, user_address, resource) in &revoked {
+            self.events
+                .emit(
+                    user_address,
+                    resource,
+                    &RequestContext::default(),
+                    PaymentEventKind::AccessRevoked {
+                        nonce: nonce.clone(),
+                        reason: reason.to_string(),
+                    },
+                )
+                .await
+                .map_err(EngineError::StoreError)?;
+        }
+        self.audit_log
+            .append(AuditEntry {
+                timestamp: now,
+                operator: operator.to_string(),
+                action: AuditAction::EntitlementsRevoked {
+                    sessions: revoked.len(),
+                },
+                target: scope.key(),
+                note: Some(reason.to_string()).filter(|note| !note.is_empty()),
+            })
+            .await
+            .map_err(EngineError::AuditError)?;
+        Ok(revoked.len())
+    }
+
+    async fn revoke(&self, key: &str, reason: RevocationReason) -> Result<(), EngineError> {
+        let now = self.clock.now();
+        let entry = RevocationEntry {
+            reason,
+            revoked_at: now,
+            // receipts issued before now expire within one receipt lifetime
+            expires_at: now + self.config_manager.get_config().receipts.ttl_secs,
+        };
+        self.revocation_store
+            .revoke(key, entry)
+            .await
+            .map_err(ReceiptError::from)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EntitlementService for Entitlements {
+    async fn validate_receipt(&self, receipt: &Receipt) -> Result<(), EngineError> {
+        Entitlements::validate_receipt(self, receipt).await
+    }
+
+    async fn revoke_payment(
+        &self,
+        payment_nonce: &str,
+        reason: RevocationReason,
+    ) -> Result<(), EngineError> {
+        Entitlements::revoke_payment(self, payment_nonce, reason).await
+    }
+
+    async fn revoke_entitlements(
+        &self,
+        operator: &str,
+        scope: RevocationScope,
+        reason: &str,
+    ) -> Result<usize, EngineError> {
+        Entitlements::revoke_entitlements(self, operator, scope, reason).await
+    }
+}
@@ -0,0 +1,334 @@
+/// Engine services module.
+use crate::context::RequestContext;
+use crate::core::{EngineError, FinalityReport, X402};
+use crate::discovery::DiscoveryDocument;
+use crate::receipt::Receipt;
+use crate::resource::Resource;
+use crate::revocation::{RevocationReason, RevocationScope};
+use crate::types::{PaymentVerification, VerificationResult};
+use async_trait::async_trait;
+
+pub mod challenge;
+pub mod entitlement;
+pub mod verification;
+
+/// Issues payment challenges and publishes prices.
+#[async_trait]
+pub trait ChallengeService: Send + Sync {
+    /// 402 result with a new challenge for the resource, or a grant when
+    /// the request needs no payment, e.g. a full discount coupon or a funded
+    /// budget account
+    async fn issue_challenge(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        custom_amount: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<VerificationResult, EngineError>;
+
+    fn discovery_document(&self) -> Result<DiscoveryDocument, EngineError>;
+}
+
+/// Looks up the payments of issued sessions on chain.
+#[async_trait]
+pub trait VerificationService: Send + Sync {
+    async fn verify_payment(
+        &self,
+        user_address: &str,
+        payment_nonce: &str,
+        resource: &Resource,
+    ) -> Result<PaymentVerification, EngineError>;
+
+    /// recheck recorded payments until they are final or reverted
+    async fn update_finality(&self) -> Result<FinalityReport, EngineError>;
+}
+
+/// Decides on the access granted by payments.
+#[async_trait]
+pub trait EntitlementService: Send + Sync {
+    /// `Ok` when the receipt is signed, unexpired and not revoked
+    async fn validate_receipt(&self, receipt: &Receipt) -> Result<(), EngineError>;
+
+    async fn revoke_payment(
+        &self,
+        payment_nonce: &str,
+        reason: RevocationReason,
+    ) -> Result<(), EngineError>;
+
+    /// returns the number of sessions revoked
+    async fn revoke_entitlements(
+        &self,
+        operator: &str,
+        scope: RevocationScope,
+        reason: &str,
+    ) -> Result<usize, EngineError>;
+}
+
+/// [`X402`] composes a [`Challenges`](challenge::Challenges),
+/// [`Verifications`](verification::Verifications) and
+/// [`Entitlements`](entitlement::Entitlements) service over one session
+/// table. Deployments splitting them build the services they need on a
+/// shared or imported table instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use x402_sdk::config::ConfigManager;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::services::verification::Verifications;
+/// use x402_sdk::services::{ChallengeService, VerificationService};
+///
+/// // the edge only issues challenges, a worker verifies them
+/// let edge = X402::from_default_config().unwrap();
+/// let worker: Arc<dyn VerificationService> = Arc::new(Verifications::new(
+///     Arc::new(ConfigManager::new().unwrap()),
+///     edge.sessions().clone(),
+/// ));
+/// let challenges: Arc<dyn ChallengeService> = Arc::new(edge);
+/// ```
+#[async_trait]
+impl ChallengeService for X402 {
+    async fn issue_challenge(
+        &self,
+        user_address: &str,
+        resource: &Resource,
+        custom_amount: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<VerificationResult, EngineError> {
+        self.challenges()
+            .issue_challenge(user_address, resource, custom_amount, coupon_code, context)
+            .await
+    }
+
+    fn discovery_document(&self) -> Result<DiscoveryDocument, EngineError> {
+        self.challenges().discovery_document()
+    }
+}
+
+#[async_trait]
+impl VerificationService for X402 {
+    async fn verify_payment(
+        &self,
+        user_address: &str,
+        payment_nonce: &str,
+        resource: &Resource,
+    ) -> Result<PaymentVerification, EngineError> {
+        self.verifications()
+            .verify_payment(user_address, payment_nonce, resource)
+            .await
+    }
+
+    async fn update_finality(&self) -> Result<FinalityReport, EngineError> {
+        self.verifications().update_finality().await
+    }
+}
+
+#[async_trait]
+impl EntitlementService for X402 {
+    async fn validate_receipt(&self, receipt: &Receipt) -> Result<(), EngineError> {
+        self.entitlements().validate_receipt(receipt).await
+    }
+
+    async fn revoke_payment(
+        &self,
+        payment_nonce: &str,
+        reason: RevocationReason,
+    ) -> Result<(), EngineError> {
+        self.entitlements()
+            .revoke_payment(payment_nonce, reason)
+            .await
+    }
+
+    async fn revoke_entitlements(
+        &self,
+        operator: &str,
+        scope: RevocationScope,
+        reason: &str,
+    ) -> Result<usize, EngineError> {
+        self.entitlements()
+            .revoke_entitlements(operator, scope, reason)
+            .await
+    }
+}
+
+/// Access request handled by the engine, the owned arguments of
+/// [`X402::handle_access_request_with_coupon`], see
+/// [`X402::handle_request`].
+#[derive(Debug, Clone)]
+pub struct AccessRequest {
+    pub user_address: String,
+    /// path and query of the requested resource
+    pub resource_path: String,
+    pub payment_nonce: Option<String>,
+    pub custom_amount: Option<String>,
+    pub coupon_code: Option<String>,
+    pub context: RequestContext,
+}
+
+impl AccessRequest {
+    pub fn new(user_address: &str, resource_path: &str) -> Self {
+        Self {
+            user_address: user_address.to_string(),
+            resource_path: resource_path.to_string(),
+            payment_nonce: None,
+            custom_amount: None,
+            coupon_code: None,
+            context: RequestContext::default(),
+        }
+    }
+
+    pub fn with_nonce(mut self, payment_nonce: &str) -> Self {
+        self.payment_nonce = Some(payment_nonce.to_string());
+        self
+    }
+
+    pub fn with_amount(mut self, custom_amount: &str) -> Self {
+        self.custom_amount = Some(custom_amount.to_string());
+        self
+    }
+
+    pub fn with_coupon(mut self, coupon_code: &str) -> Self {
+        self.coupon_code = Some(coupon_code.to_string());
+        self
+    }
+
+    pub fn with_context(mut self, context: RequestContext) -> Self {
+        self.context = context;
+        self
+    }
+}
+
+#[cfg(feature = "tower")]
+pub use service::{JwksLayer, JwksService};
+
+#[cfg(feature = "tower")]
+mod service {
+    use super::AccessRequest;
+    use crate::core::{EngineError, X402};
+    use crate::keys::JWKS_PATH;
+    use crate::types::VerificationResult;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+
+    /// The shared engine as a tower service, so it can be wrapped in
+    /// timeout, retry or load-shed layers. The engine is always ready,
+    /// back pressure on the chains is applied by its verification pools.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tower::{ServiceBuilder, ServiceExt};
+    /// use x402_sdk::core::X402;
+    /// use x402_sdk::services::AccessRequest;
+    ///
+    /// # async fn example() -> Result<(), tower::BoxError> {
+    /// let engine = Arc::new(X402::from_default_config()?);
+    /// let service = ServiceBuilder::new()
+    ///     .timeout(Duration::from_secs(10))
+    ///     .service(engine);
+    /// let result = service
+    ///     .oneshot(AccessRequest::new("0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5", "/premium"))
+    ///     .await?;
+    /// assert_eq!(result.http_status, 402);
+    /// # Ok(())
+    /// # }
+    /// ```
+    impl tower::Service<AccessRequest> for Arc<X402> {
+        type Response = VerificationResult;
+        type Error = EngineError;
+        type Future = Pin<Box<dyn Future<Output = Result<VerificationResult, EngineError>> + Send>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: AccessRequest) -> Self::Future {
+            let engine = self.clone();
+            Box::pin(async move { engine.handle_request(&request).await })
+        }
+    }
+
+    /// Tower layer publishing the signing keys of the engine at
+    /// [`JWKS_PATH`], other requests reach the inner HTTP service. The
+    /// response body is built from the JSON bytes, e.g. an axum `Body`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let app = Router::new()
+    ///     .route("/premium", get(premium))
+    ///     .layer(JwksLayer::new(engine));
+    /// ```
+    #[derive(Clone)]
+    pub struct JwksLayer {
+        engine: Arc<X402>,
+    }
+
+    impl JwksLayer {
+        pub fn new(engine: Arc<X402>) -> Self {
+            Self { engine }
+        }
+    }
+
+    impl<S> tower::Layer<S> for JwksLayer {
+        type Service = JwksService<S>;
+
+        fn layer(&self, inner: S) -> Self::Service {
+            JwksService {
+                inner,
+                engine: self.engine.clone(),
+            }
+        }
+    }
+
+    /// Service of a [`JwksLayer`], answers `GET` requests of the key set
+    /// itself, with 404 when the engine signs nothing.
+    #[derive(Clone)]
+    pub struct JwksService<S> {
+        inner: S,
+        engine: Arc<X402>,
+    }
+
+    impl<S, B, R> tower::Service<http::Request<B>> for JwksService<S>
+    where
+        S: tower::Service<http::Request<B>, Response = http::Response<R>>,
+        S::Error: Send + 'static,
+        S::Future: Send + 'static,
+        R: From<Vec<u8>> + Send + 'static,
+    {
+        type Response = http::Response<R>;
+        type Error = S::Error;
+        type Future = Pin<Box<dyn Future<Output = Result<http::Response<R>, S::Error>> + Send>>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, request: http::Request<B>) -> Self::Future {
+            if request.method() != http::Method::GET || request.uri().path() != JWKS_PATH {
+                return Box::pin(self.inner.call(request));
+            }
+            let body = self
+                .engine
+                .jwks()
+                .and_then(|jwks| serde_json::to_vec(&jwks).ok());
+            let response = match body {
+                Some(body) => http::Response::builder()
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(R::from(body)),
+                None => http::Response::builder()
+                    .status(http::StatusCode::NOT_FOUND)
+                    .body(R::from(Vec::new())),
+            }
+            .expect("static response parts are valid");
+            Box::pin(async move { Ok(response) })
+        }
+    }
+}
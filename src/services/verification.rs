@@ -0,0 +1,500 @@
+/// Payment verification service module.
+use super::VerificationService;
+use crate::clock::{Clock, SystemClock};
+use crate::config::{ConfigError, ConfigManager};
+use crate::context::RequestContext;
+use crate::core::{EngineError, FinalityReport, PaymentSession, RegistrationReport};
+use crate::dispute::OverrideDecision;
+use crate::events::{EventEmitter, PaymentEventKind};
+use crate::rates::RateProvider;
+use crate::redaction::Redactor;
+use crate::resource::Resource;
+use crate::shard::ShardedMap;
+use crate::simulation::SimulatedVerifier;
+use crate::store::PaymentStore;
+use crate::types::{ChainType, ErrorReason, Finality, PaymentVerification};
+use crate::verifier::breaker::{CircuitBreakerVerifier, CircuitStatus};
+use crate::verifier::light_client::LightClient;
+use crate::verifier::pool::PooledVerifier;
+use crate::verifier::quorum::QuorumVerifier;
+use crate::verifier::{PaymentVerifier, VerificationError, VerifierRegistry};
+use async_trait::async_trait;
+use futures::future::{join_all, try_join_all};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Verifies the payments of issued sessions against the chain verifiers it
+/// owns. The session table is injected, so a worker can verify the sessions
+/// an edge issued by sharing or importing its table.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use x402_sdk::config::ConfigManager;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::services::verification::Verifications;
+///
+/// let edge = X402::from_default_config().unwrap();
+/// let worker = Verifications::new(
+///     Arc::new(ConfigManager::new().unwrap()),
+///     edge.sessions().clone(),
+/// );
+/// ```
+pub struct Verifications {
+    config_manager: Arc<ConfigManager>,
+    verifier_registry: VerifierRegistry,
+    sessions: Arc<ShardedMap<PaymentSession>>,
+    clock: Arc<dyn Clock>,
+    payment_store: Option<Arc<dyn PaymentStore>>,
+    rate_provider: Option<Arc<dyn RateProvider>>,
+    light_client: Option<Arc<dyn LightClient>>,
+    events: EventEmitter,
+}
+
+impl Verifications {
+    pub fn new(
+        config_manager: Arc<ConfigManager>,
+        sessions: Arc<ShardedMap<PaymentSession>>,
+    ) -> Self {
+        let redactor = Redactor::new(config_manager.get_config().redaction.clone());
+        Self {
+            config_manager,
+            verifier_registry: VerifierRegistry::new(),
+            sessions,
+            clock: Arc::new(SystemClock),
+            payment_store: None,
+            rate_provider: None,
+            light_client: None,
+            events: EventEmitter::new(redactor),
+        }
+    }
+
+    /// replace the clock, verifiers registered afterwards share it
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// payments whose finality [`update_finality`](Self::update_finality)
+    /// tracks
+    pub fn with_payment_store(mut self, payment_store: Arc<dyn PaymentStore>) -> Self {
+        self.payment_store = Some(payment_store);
+        self
+    }
+
+    /// prices used to value any-token payments, set before registering the
+    /// chain verifiers
+    pub fn with_rate_provider(mut self, rate_provider: Arc<dyn RateProvider>) -> Self {
+        self.rate_provider = Some(rate_provider);
+        self
+    }
+
+    /// light client rechecking EVM payments above the light-client threshold
+    /// of their pricing rule, set before registering the chain verifiers
+    pub fn with_light_client(mut self, light_client: Arc<dyn LightClient>) -> Self {
+        self.light_client = Some(light_client);
+        self
+    }
+
+    /// sessions verified by the service, shared with the issuer
+    pub fn with_sessions(mut self, sessions: Arc<ShardedMap<PaymentSession>>) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    /// emitter of the finality events
+    pub fn with_events(mut self, events: EventEmitter) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn verifier_registry(&self) -> &VerifierRegistry {
+        &self.verifier_registry
+    }
+
+    pub fn verifier_registry_mut(&mut self) -> &mut VerifierRegistry {
+        &mut self.verifier_registry
+    }
+
+    /// register chain verifier by chain type
+    pub async fn register_chain_verifier(
+        &mut self,
+        chain_type: ChainType,
+        rpc_url: String,
+    ) -> Result<(), EngineError> {
+        let verifier = self.build_chain_verifier(&chain_type, rpc_url).await?;
+        self.verifier_registry
+            .register_verifier(chain_type, verifier);
+        Ok(())
+    }
+
+    /// register a verifier built outside the engine, e.g. for Sui or Aptos,
+    /// once the RPC it reads is checked to serve the network of the chain
+    pub async fn register_custom_verifier(
+        &mut self,
+        chain_type: ChainType,
+        rpc_url: &str,
+        verifier: Box<dyn PaymentVerifier>,
+    ) -> Result<(), EngineError> {
+        if !matches!(chain_type, ChainType::Evm(_)) {
+            crate::verifier::network::check_network(rpc_url, &chain_type)
+                .await
+                .map_err(EngineError::VerificationError)?;
+        }
+        self.verifier_registry
+            .register_verifier(chain_type, verifier);
+        Ok(())
+    }
+
+    /// Registers a verifier for every configured chain with an RPC URL,
+    /// constructing them concurrently. Chains that fail are reported and
+    /// left unregistered, the others are usable. In simulation mode every
+    /// chain is registered, an RPC URL is not needed.
+    pub async fn register_all_configured(&mut self) -> RegistrationReport {
+        let config = self.config_manager.get_config();
+        let simulated = config.simulation.is_some();
+        let chains: Vec<(ChainType, Option<String>)> = config
+            .chains
+            .iter()
+            .map(|(chain_type, chain)| {
+                let rpc_url = chain.rpc_url.clone().or(simulated.then(String::new));
+                (chain_type.clone(), rpc_url)
+            })
+            .collect();
+        let service = &*self;
+        let builds = chains.into_iter().map(|(chain_type, rpc_url)| async move {
+            let result = match rpc_url {
+                Some(rpc_url) => service.build_chain_verifier(&chain_type, rpc_url).await,
+                None => Err(EngineError::ConfigError(ConfigError::InvalidConfig(
+                    format!("no rpc url for {}", chain_type.get_display_name()),
+                ))),
+            };
+            (chain_type, result)
+        });
+        let results = join_all(builds).await;
+        let mut report = RegistrationReport::default();
+        for (chain_type, result) in results {
+            match result {
+                Ok(verifier) => {
+                    self.verifier_registry
+                        .register_verifier(chain_type.clone(), verifier);
+                    report.registered.push(chain_type);
+                }
+                Err(err) => report.failed.push((chain_type, err)),
+            }
+        }
+        report
+    }
+
+    async fn build_chain_verifier(
+        &self,
+        chain_type: &ChainType,
+        rpc_url: String,
+    ) -> Result<Box<dyn PaymentVerifier>, EngineError> {
+        let chain = self
+            .config_manager
+            .get_chain_config(chain_type)
+            .ok_or_else(|| EngineError::ChainNotSupported(chain_type.clone()))?;
+        if let Some(rule) = &self.config_manager.get_config().simulation {
+            return Ok(Box::new(
+                SimulatedVerifier::new(rule.clone()).with_clock(self.clock.clone()),
+            ));
+        }
+        let verifier = if chain.quorum_rpc_urls.is_empty() {
+            self.build_rpc_verifier(chain_type, rpc_url).await?
+        } else {
+            // every provider gets its own breaker, a failing one only
+            // counts as a missing vote
+            let builds = std::iter::once(rpc_url)
+                .chain(chain.quorum_rpc_urls.iter().cloned())
+                .map(|rpc_url| self.build_rpc_verifier(chain_type, rpc_url));
+            let verifiers = try_join_all(builds).await?;
+            let quorum = chain.quorum.unwrap_or(verifiers.len());
+            Box::new(QuorumVerifier::new(verifiers, quorum))
+        };
+        // queued verifications never reach the breaker, so a full queue is
+        // not mistaken for a failing RPC
+        let pool = self.config_manager.get_config().verification_pool.clone();
+        if pool.max_concurrent == 0 {
+            return Ok(verifier);
+        }
+        Ok(Box::new(PooledVerifier::new(verifier, pool)))
+    }
+
+    /// verifier of one RPC provider behind its circuit breaker
+    async fn build_rpc_verifier(
+        &self,
+        chain_type: &ChainType,
+        rpc_url: String,
+    ) -> Result<Box<dyn PaymentVerifier>, EngineError> {
+        // EVM chain ids are checked by the verifier itself
+        if !matches!(chain_type, ChainType::Evm(_)) {
+            crate::verifier::network::check_network(&rpc_url, chain_type)
+                .await
+                .map_err(EngineError::VerificationError)?;
+        }
+        let verifier: Box<dyn PaymentVerifier> = match chain_type {
+            ChainType::Evm(_) => {
+                use crate::verifier::evm::EvmVerifier;
+                let mut evm_verifier = EvmVerifier::new(rpc_url, chain_type.clone())
+                    .await
+                    .map_err(EngineError::VerificationError)?
+                    .with_clock(self.clock.clone())
+                    .with_proofs(self.config_manager.get_config().payments.include_proofs)
+                    .with_amount_tolerances(
+                        self.config_manager
+                            .get_config()
+                            .payments
+                            .amount_tolerances
+                            .clone(),
+                    );
+                if let Some(rate_provider) = &self.rate_provider {
+                    evm_verifier = evm_verifier.with_rate_provider(
+                        rate_provider.clone(),
+                        self.config_manager.get_config().payments.slippage_bps,
+                    );
+                }
+                if let Some(light_client) = &self.light_client {
+                    evm_verifier = evm_verifier.with_light_client(light_client.clone());
+                }
+                Box::new(evm_verifier)
+            }
+            #[cfg(feature = "native")]
+            ChainType::Solana(_) => {
+                use crate::verifier::solana::SolanaVerifier;
+                let mut solana_verifier = SolanaVerifier::new()
+                    .with_rpc_url(&rpc_url)
+                    .with_clock(self.clock.clone())
+                    .with_proofs(self.config_manager.get_config().payments.include_proofs)
+                    .with_amount_tolerances(
+                        self.config_manager
+                            .get_config()
+                            .payments
+                            .amount_tolerances
+                            .clone(),
+                    );
+                if let Some(rate_provider) = &self.rate_provider {
+                    solana_verifier = solana_verifier.with_rate_provider(
+                        rate_provider.clone(),
+                        self.config_manager.get_config().payments.slippage_bps,
+                    );
+                }
+                Box::new(solana_verifier)
+            }
+            _ => {
+                return Err(EngineError::ChainNotSupported(chain_type.clone()));
+            }
+        };
+        let breaker = self.config_manager.get_config().circuit_breaker.clone();
+        if breaker.failure_threshold == 0 {
+            return Ok(verifier);
+        }
+        Ok(Box::new(CircuitBreakerVerifier::new(
+            verifier,
+            breaker,
+            self.clock.clone(),
+        )))
+    }
+
+    /// circuit breaker status of every registered verifier that has one
+    pub fn chain_health(&self) -> HashMap<ChainType, CircuitStatus> {
+        self.verifier_registry
+            .supported_chains()
+            .into_iter()
+            .filter_map(|chain_type| {
+                let status = self
+                    .verifier_registry
+                    .get_verifier(&chain_type)?
+                    .circuit_status()?;
+                Some((chain_type, status))
+            })
+            .collect()
+    }
+
+    /// Verifies the payment of a session for the requested resource, the
+    /// resource has to be permitted by the configured
+    /// [`SessionBinding`](crate::config::SessionBinding).
+    pub async fn verify_payment(
+        &self,
+        user_address: &str,
+        payment_nonce: &str,
+        resource: &Resource,
+    ) -> Result<PaymentVerification, EngineError> {
+        let config = self.config_manager.get_config();
+        let now = self.clock.now();
+        let (chain_type, payment_request) = {
+            let sessions = self.sessions.read(payment_nonce);
+            let session = sessions
+                .get(payment_nonce)
+                .ok_or(EngineError::InvalidSession)?;
+
+            if session.user_address != user_address {
+                return Err(EngineError::AddressMismatch);
+            }
+            if !config.sessions.binding.permits(&session.resource, resource) {
+                return Err(EngineError::ResourceMismatch);
+            }
+            // an operator decision replaces the chain lookup
+            if let Some(manual_override) = &session.manual_override {
+                let is_paid = manual_override.decision == OverrideDecision::Grant;
+                return Ok(PaymentVerification {
+                    is_paid,
+                    paid_amount: if is_paid {
+                        session.payment_request.amount.clone()
+                    } else {
+                        "0".to_string()
+                    },
+                    transaction_hash: None,
+                    verified_at: now,
+                    chain: session.payment_request.chain.clone(),
+                    transaction_logs: Vec::new(),
+                    explorer_url: None,
+                    conversion: None,
+                    paid_currency: None,
+                    failure_reason: (!is_paid).then_some(ErrorReason::Revoked),
+                    proof: None,
+                });
+            }
+            // a stale challenge is priced at old rates, the client pays the
+            // reissued one instead
+            if let Some(expired_at) = config.payments.expired_at(&session.payment_request, now) {
+                return Err(EngineError::SessionExpired { expired_at });
+            }
+            if let Some(until) = session.attempts.blocked_until()
+                && until > now
+            {
+                return Err(EngineError::TooManyAttempts {
+                    retry_after: until - now,
+                });
+            }
+
+            (
+                session.payment_request.chain.chain_type.clone(),
+                session.payment_request.clone(),
+            )
+        };
+        let verifier = self
+            .verifier_registry
+            .get_verifier(&chain_type)
+            .ok_or(EngineError::ChainNotSupported(chain_type))?;
+        // a delegated session is paid by whoever sends the referenced payment
+        let result = match payment_request.beneficiary {
+            Some(_) => verifier.verify_payment_by_reference(&payment_request).await,
+            None => {
+                verifier
+                    .verify_payment(&payment_request, user_address)
+                    .await
+            }
+        }
+        .map(|verification| {
+            verification
+                .with_accepted_currency(&payment_request.currency)
+                .with_bounded_logs(config.payments.max_transaction_logs)
+        })
+        .map_err(EngineError::VerificationFailed);
+        // a fail-fast rejection never reached the chain and does not count
+        // against the client
+        if matches!(
+            result,
+            Err(EngineError::VerificationFailed(
+                VerificationError::CircuitOpen { .. } | VerificationError::Saturated { .. }
+            ))
+        ) {
+            return result;
+        }
+        let paid = matches!(&result, Ok(verification) if verification.is_paid);
+        let limits = &config.sessions.attempts;
+        let mut sessions = self.sessions.write(payment_nonce);
+        if let Some(session) = sessions.get_mut(payment_nonce) {
+            session.attempts.record(paid, now, limits);
+            if paid {
+                session.verified = true;
+            }
+        }
+        result
+    }
+
+    /// Re-checks the provisional payments against their chain, finalizing
+    /// the ones past the chain finality and marking the ones a
+    /// reorganization reverted. Payments on chains without a verifier stay
+    /// provisional.
+    pub async fn update_finality(&self) -> Result<FinalityReport, EngineError> {
+        let mut report = FinalityReport::default();
+        let Some(payment_store) = &self.payment_store else {
+            return Ok(report);
+        };
+        let records = payment_store
+            .provisional_payments()
+            .await
+            .map_err(EngineError::StoreError)?;
+        let config = self.config_manager.get_config();
+        for record in records {
+            let chain = config
+                .chains
+                .values()
+                .find(|chain| chain.chain_id == record.chain_id);
+            let finality = match (&record.transaction_hash, chain) {
+                // granted off chain, e.g. by a manual override
+                (None, _) => Finality::Finalized,
+                (Some(transaction_hash), Some(chain)) => {
+                    match self.verifier_registry.get_verifier(&chain.chain_type) {
+                        Some(verifier) => verifier
+                            .transaction_finality(chain, transaction_hash)
+                            .await
+                            .unwrap_or(Finality::Provisional),
+                        None => Finality::Provisional,
+                    }
+                }
+                (Some(_), None) => Finality::Provisional,
+            };
+            match finality {
+                Finality::Provisional => {
+                    report.provisional += 1;
+                    continue;
+                }
+                Finality::Finalized => report.finalized += 1,
+                Finality::Reverted => report.reverted += 1,
+            }
+            payment_store
+                .set_finality(&record.nonce, finality)
+                .await
+                .map_err(EngineError::StoreError)?;
+            let kind = match finality {
+                Finality::Reverted => PaymentEventKind::PaymentReverted {
+                    nonce: record.nonce.clone(),
+                    transaction_hash: record.transaction_hash.clone(),
+                },
+                _ => PaymentEventKind::PaymentFinalized {
+                    nonce: record.nonce.clone(),
+                },
+            };
+            self.events
+                .emit(
+                    &record.payer,
+                    &Resource::from_canonical(&record.resource)?,
+                    &RequestContext::default(),
+                    kind,
+                )
+                .await
+                .map_err(EngineError::StoreError)?;
+        }
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl VerificationService for Verifications {
+    async fn verify_payment(
+        &self,
+        user_address: &str,
+        payment_nonce: &str,
+        resource: &Resource,
+    ) -> Result<PaymentVerification, EngineError> {
+        Verifications::verify_payment(self, user_address, payment_nonce, resource).await
+    }
+
+    async fn update_finality(&self) -> Result<FinalityReport, EngineError> {
+        Verifications::update_finality(self).await
+    }
+}
@@ -0,0 +1,105 @@
+/// Deterministic session derivation module.
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Derives payment nonces from a shared secret instead of random UUIDs.
+///
+/// The nonce is an HMAC-SHA256 over the payer address, the resource, the
+/// quoted amount and the price epoch. Every replica configured with the same
+/// secret issues the same nonce for the same inputs and can re-derive a
+/// session it never stored, so no shared session storage is required.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::session::SessionDeriver;
+///
+/// let deriver = SessionDeriver::new(b"shared-secret", 300);
+/// let epoch = deriver.epoch_at(1_700_000_000);
+/// let nonce = deriver.derive_nonce("0xabc", "/premium", "1000", epoch);
+/// assert!(deriver.matches(&nonce, "0xabc", "/premium", "1000", epoch));
+/// assert!(!deriver.matches(&nonce, "0xabc", "/premium", "1", epoch));
+/// ```
+#[derive(Clone)]
+pub struct SessionDeriver {
+    secret: Vec<u8>,
+    epoch_secs: u64,
+}
+
+impl SessionDeriver {
+    pub fn new(secret: &[u8], epoch_secs: u64) -> Self {
+        Self {
+            secret: secret.to_vec(),
+            epoch_secs: epoch_secs.max(1),
+        }
+    }
+
+    pub fn epoch_secs(&self) -> u64 {
+        self.epoch_secs
+    }
+
+    /// price epoch containing the given unix timestamp
+    pub fn epoch_at(&self, timestamp: u64) -> u64 {
+        timestamp / self.epoch_secs
+    }
+
+    /// last second (exclusive) at which a nonce derived in `epoch` is still
+    /// re-derivable, nonces are accepted in their own epoch and the next one
+    pub fn expires_at(&self, epoch: u64) -> u64 {
+        (epoch + 2) * self.epoch_secs
+    }
+
+    /// derive the hex encoded nonce for the given session inputs
+    pub fn derive_nonce(&self, payer: &str, resource: &str, amount: &str, epoch: u64) -> String {
+        let mac = self.mac(payer, resource, amount, epoch);
+        ethers::utils::hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// check a presented nonce against the session inputs in constant time
+    pub fn matches(
+        &self,
+        nonce: &str,
+        payer: &str,
+        resource: &str,
+        amount: &str,
+        epoch: u64,
+    ) -> bool {
+        let Ok(bytes) = ethers::utils::hex::decode(nonce) else {
+            return false;
+        };
+        self.mac(payer, resource, amount, epoch)
+            .verify_slice(&bytes)
+            .is_ok()
+    }
+
+    /// find the epoch a nonce was derived in, checking the current epoch and
+    /// the previous one
+    pub fn find_epoch(
+        &self,
+        nonce: &str,
+        payer: &str,
+        resource: &str,
+        amount: &str,
+        now: u64,
+    ) -> Option<u64> {
+        let current = self.epoch_at(now);
+        [Some(current), current.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .find(|epoch| self.matches(nonce, payer, resource, amount, *epoch))
+    }
+
+    fn mac(&self, payer: &str, resource: &str, amount: &str, epoch: u64) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any size");
+        // length prefix every field so distinct inputs never share an encoding
+        for field in [payer.as_bytes(), resource.as_bytes(), amount.as_bytes()] {
+            mac.update(&(field.len() as u32).to_be_bytes());
+            mac.update(field);
+        }
+        mac.update(&epoch.to_be_bytes());
+        mac
+    }
+}
@@ -0,0 +1,104 @@
+/// Sharded map module.
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// shards of the engine session map unless configured otherwise
+pub const DEFAULT_SHARDS: usize = 16;
+
+/// String keyed map split over independently locked shards.
+///
+/// A key always lives in the same shard, so reads and writes of different
+/// keys mostly take different locks instead of serializing on a single one.
+/// Operations spanning every key lock the shards one after the other and
+/// never see a consistent snapshot of the whole map.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::shard::ShardedMap;
+///
+/// let map = ShardedMap::new(4);
+/// map.write("nonce").insert("nonce".to_string(), 1);
+/// assert_eq!(map.read("nonce").get("nonce"), Some(&1));
+/// assert_eq!(map.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct ShardedMap<V> {
+    shards: Vec<RwLock<HashMap<String, V>>>,
+    hasher: RandomState,
+}
+
+impl<V> ShardedMap<V> {
+    /// map with the given number of shards, at least one
+    pub fn new(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// read lock on the shard holding `key`
+    pub fn read(&self, key: &str) -> RwLockReadGuard<'_, HashMap<String, V>> {
+        self.shard(key).read().unwrap()
+    }
+
+    /// write lock on the shard holding `key`
+    pub fn write(&self, key: &str) -> RwLockWriteGuard<'_, HashMap<String, V>> {
+        self.shard(key).write().unwrap()
+    }
+
+    /// update every entry, one shard at a time
+    pub fn for_each_mut(&self, mut update: impl FnMut(&String, &mut V)) {
+        for shard in &self.shards {
+            for (key, value) in shard.write().unwrap().iter_mut() {
+                update(key, value);
+            }
+        }
+    }
+
+    /// copies of the entries, one shard at a time, so only the shard being
+    /// copied is locked
+    pub fn snapshots(&self) -> impl Iterator<Item = Vec<(String, V)>> + '_
+    where
+        V: Clone,
+    {
+        self.shards.iter().map(|shard| {
+            shard
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap().len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn shard(&self, key: &str) -> &RwLock<HashMap<String, V>> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+}
+
+impl<V> Default for ShardedMap<V> {
+    fn default() -> Self {
+        Self::new(DEFAULT_SHARDS)
+    }
+}
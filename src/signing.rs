@@ -0,0 +1,246 @@
+/// Challenge signing module.
+use crate::canonical;
+use crate::crypto::CryptoSuite;
+use crate::keys::Jwk;
+use crate::types::{ChallengeSignature, PaymentRequest, X402ProtocolResponse};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, H256, Signature};
+use ethers::utils::keccak256;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// algorithm identifier: secp256k1 ECDSA over keccak256 of the canonical
+/// encoding
+pub const CHALLENGE_SIGNATURE_ALGORITHM: &str = "secp256k1-keccak256";
+
+/// domain prefix mixed into the digest so challenge signatures cannot be
+/// replayed as signatures over other payloads
+pub const CHALLENGE_DOMAIN: &[u8] = b"x402-challenge:";
+
+#[derive(Debug)]
+pub enum SignatureError {
+    MissingSignature,
+    InvalidKey(String),
+    InvalidSignature(String),
+    UnsupportedAlgorithm(String),
+    SignerMismatch { expected: String, actual: String },
+    EncodingError(String),
+}
+
+impl std::fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSignature => write!(f, "Challenge is not signed"),
+            Self::InvalidKey(msg) => write!(f, "Invalid signing key: {}", msg),
+            Self::InvalidSignature(msg) => write!(f, "Invalid signature: {}", msg),
+            Self::UnsupportedAlgorithm(alg) => write!(f, "Unsupported algorithm: {}", alg),
+            Self::SignerMismatch { expected, actual } => {
+                write!(f, "Signer mismatch: expected {}, got {}", expected, actual)
+            }
+            Self::EncodingError(msg) => write!(f, "Encoding error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Signs issued payment requests with the merchant key so clients can detect
+/// a tampered challenge, e.g. a swapped recipient address.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::signing::{ChallengeSigner, verify_challenge_signature};
+/// # fn example(mut response: x402_sdk::types::X402ProtocolResponse) -> Result<(), Box<dyn std::error::Error>> {
+/// let signer = ChallengeSigner::from_private_key("0x...")?;
+/// signer.sign_response(&mut response)?;
+/// // client side, with the signer address published by the merchant
+/// verify_challenge_signature(&response, &signer.address())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ChallengeSigner {
+    wallet: LocalWallet,
+}
+
+impl ChallengeSigner {
+    /// load a hex encoded secp256k1 private key
+    pub fn from_private_key(private_key: &str) -> Result<Self, SignatureError> {
+        let wallet = LocalWallet::from_str(private_key.trim_start_matches("0x"))
+            .map_err(|e| SignatureError::InvalidKey(e.to_string()))?;
+        Ok(Self { wallet })
+    }
+
+    /// signer identity published to clients, as a checksummed address
+    pub fn address(&self) -> String {
+        ethers::utils::to_checksum(&self.wallet.address(), None)
+    }
+
+    /// SEC1 uncompressed public key, `0x04 || x || y`
+    pub fn public_key_uncompressed(&self) -> Vec<u8> {
+        self.wallet
+            .signer()
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec()
+    }
+
+    pub fn sign(&self, request: &PaymentRequest) -> Result<ChallengeSignature, SignatureError> {
+        self.sign_payload(CHALLENGE_DOMAIN, request)
+    }
+
+    /// sign any serializable payload under the given domain prefix
+    pub fn sign_payload<T: Serialize>(
+        &self,
+        domain: &[u8],
+        payload: &T,
+    ) -> Result<ChallengeSignature, SignatureError> {
+        sign_payload_with(self, domain, payload)
+    }
+
+    /// sign the payment request of a 402 response in place
+    pub fn sign_response(&self, response: &mut X402ProtocolResponse) -> Result<(), SignatureError> {
+        response.signature = Some(self.sign(&response.payment_required)?);
+        Ok(())
+    }
+}
+
+impl CryptoSuite for ChallengeSigner {
+    fn algorithm(&self) -> String {
+        CHALLENGE_SIGNATURE_ALGORITHM.to_string()
+    }
+
+    fn signer(&self) -> String {
+        self.address()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<String, SignatureError> {
+        let signature = self
+            .wallet
+            .sign_hash(H256::from(keccak256(message)))
+            .map_err(|e| SignatureError::InvalidSignature(e.to_string()))?;
+        Ok(format!("0x{}", signature))
+    }
+
+    fn verify(&self, message: &[u8], signature: &str) -> Result<(), SignatureError> {
+        let parsed = Signature::from_str(signature.trim_start_matches("0x"))
+            .map_err(|e| SignatureError::InvalidSignature(e.to_string()))?;
+        let actual = parsed
+            .recover(H256::from(keccak256(message)))
+            .map_err(|e| SignatureError::InvalidSignature(e.to_string()))?;
+        let actual = ethers::utils::to_checksum(&actual, None);
+        if actual != self.address() {
+            return Err(SignatureError::SignerMismatch {
+                expected: self.address(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    fn jwk(&self, kid: &str) -> Option<Jwk> {
+        Some(Jwk::from_signer(kid, self))
+    }
+}
+
+/// sign a payload under the given domain prefix with any suite
+pub fn sign_payload_with<T: Serialize>(
+    suite: &dyn CryptoSuite,
+    domain: &[u8],
+    payload: &T,
+) -> Result<ChallengeSignature, SignatureError> {
+    Ok(ChallengeSignature {
+        algorithm: suite.algorithm(),
+        signer: suite.signer(),
+        signature: suite.sign(&payload_message(domain, payload)?)?,
+        kid: None,
+    })
+}
+
+/// verify a payload signature made by `suite`, the algorithm has to match
+pub fn verify_payload_with<T: Serialize>(
+    suite: &dyn CryptoSuite,
+    domain: &[u8],
+    payload: &T,
+    signature: &ChallengeSignature,
+) -> Result<(), SignatureError> {
+    if signature.algorithm != suite.algorithm() {
+        return Err(SignatureError::UnsupportedAlgorithm(
+            signature.algorithm.clone(),
+        ));
+    }
+    suite.verify(&payload_message(domain, payload)?, &signature.signature)
+}
+
+/// digest signed for a payment request
+pub fn challenge_digest(request: &PaymentRequest) -> Result<H256, SignatureError> {
+    payload_digest(CHALLENGE_DOMAIN, request)
+}
+
+/// bytes signed for a payload: the domain prefix followed by the canonical
+/// JSON of the payload, see [`canonical`]
+pub fn payload_message<T: Serialize>(
+    domain: &[u8],
+    payload: &T,
+) -> Result<Vec<u8>, SignatureError> {
+    let mut bytes = domain.to_vec();
+    bytes.extend(
+        canonical::to_canonical_vec(payload)
+            .map_err(|e| SignatureError::EncodingError(e.to_string()))?,
+    );
+    Ok(bytes)
+}
+
+/// keccak256 of the [`payload_message`], as signed by [`ChallengeSigner`]
+pub fn payload_digest<T: Serialize>(domain: &[u8], payload: &T) -> Result<H256, SignatureError> {
+    Ok(H256::from(keccak256(payload_message(domain, payload)?)))
+}
+
+/// Verify a received 402 challenge was signed by the expected merchant signer.
+pub fn verify_challenge_signature(
+    response: &X402ProtocolResponse,
+    expected_signer: &str,
+) -> Result<(), SignatureError> {
+    let signature = response
+        .signature
+        .as_ref()
+        .ok_or(SignatureError::MissingSignature)?;
+    let actual = recover_challenge_signer(&response.payment_required, signature)?;
+    let expected = Address::from_str(expected_signer)
+        .map_err(|e| SignatureError::InvalidKey(e.to_string()))?;
+    if actual != expected {
+        return Err(SignatureError::SignerMismatch {
+            expected: ethers::utils::to_checksum(&expected, None),
+            actual: ethers::utils::to_checksum(&actual, None),
+        });
+    }
+    Ok(())
+}
+
+/// recover the address that produced a challenge signature
+pub fn recover_challenge_signer(
+    request: &PaymentRequest,
+    signature: &ChallengeSignature,
+) -> Result<Address, SignatureError> {
+    recover_payload_signer(CHALLENGE_DOMAIN, request, signature)
+}
+
+/// recover the address that signed a payload under the given domain prefix
+pub fn recover_payload_signer<T: Serialize>(
+    domain: &[u8],
+    payload: &T,
+    signature: &ChallengeSignature,
+) -> Result<Address, SignatureError> {
+    if signature.algorithm != CHALLENGE_SIGNATURE_ALGORITHM {
+        return Err(SignatureError::UnsupportedAlgorithm(
+            signature.algorithm.clone(),
+        ));
+    }
+    let parsed = Signature::from_str(signature.signature.trim_start_matches("0x"))
+        .map_err(|e| SignatureError::InvalidSignature(e.to_string()))?;
+    parsed
+        .recover(payload_digest(domain, payload)?)
+        .map_err(|e| SignatureError::InvalidSignature(e.to_string()))
+}
@@ -0,0 +1,165 @@
+/// Simulation module.
+use crate::clock::{Clock, SystemClock};
+use crate::types::{
+    ChainConfig, ChainType, ErrorReason, Finality, PaymentRequest, PaymentVerification,
+    TransactionLog,
+};
+use crate::verifier::{PaymentVerifier, VerificationError};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Decides the outcome of verifications in simulation mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum SimulationRule {
+    /// every session is paid
+    AlwaysPay,
+    /// no session is ever paid
+    NeverPay,
+    /// a session is paid `secs` seconds after it was first verified, like a
+    /// wallet taking its time to send the transaction
+    PayAfter { secs: u64 },
+    /// the given percentage of sessions is paid, a session keeps its
+    /// outcome across verifications
+    PayPercent { percent: u8 },
+}
+
+/// Verifier of the simulation mode, deciding payments by a
+/// [`SimulationRule`] without calling any chain.
+///
+/// The engine registers it for every chain when the configuration has a
+/// simulation rule, so frontends and integrations can run the full 402
+/// flow without wallets or testnets.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::config::{ConfigBuilder, ConfigManager};
+/// use x402_sdk::core::X402;
+/// use x402_sdk::simulation::SimulationRule;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let config = ConfigBuilder::new()
+///     .with_simulation(SimulationRule::PayAfter { secs: 10 })
+///     .build();
+/// let mut engine = X402::new(ConfigManager::from_config(config))?;
+/// // no RPC is contacted
+/// engine.register_all_configured().await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SimulatedVerifier {
+    rule: SimulationRule,
+    clock: Arc<dyn Clock>,
+    /// first verification of every session nonce
+    first_seen: Mutex<HashMap<String, u64>>,
+}
+
+impl SimulatedVerifier {
+    pub fn new(rule: SimulationRule) -> Self {
+        Self {
+            rule,
+            clock: Arc::new(SystemClock),
+            first_seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn is_paid(&self, nonce: &str, now: u64) -> bool {
+        match &self.rule {
+            SimulationRule::AlwaysPay => true,
+            SimulationRule::NeverPay => false,
+            SimulationRule::PayAfter { secs } => {
+                let mut first_seen = self.first_seen.lock().unwrap();
+                let first_seen = *first_seen.entry(nonce.to_string()).or_insert(now);
+                now >= first_seen + secs
+            }
+            SimulationRule::PayPercent { percent } => {
+                let digest = Sha256::digest(nonce.as_bytes());
+                let bucket = u64::from_be_bytes(digest[..8].try_into().unwrap()) % 100;
+                bucket < u64::from(*percent)
+            }
+        }
+    }
+
+    fn verify(&self, payment_request: &PaymentRequest, payer_address: &str) -> PaymentVerification {
+        let now = self.clock.now();
+        let is_paid = self.is_paid(&payment_request.nonce, now);
+        let transaction_hash = simulated_transaction_hash(&payment_request.nonce);
+        let transaction_logs = match is_paid {
+            true => vec![TransactionLog {
+                transaction_hash: transaction_hash.clone(),
+                from: payer_address.to_string(),
+                to: payment_request.recipient.clone(),
+                value: payment_request.amount.clone(),
+                block_number: 0,
+                log_index: 0,
+                data: None,
+                explorer_url: None,
+            }],
+            false => Vec::new(),
+        };
+        PaymentVerification {
+            is_paid,
+            paid_amount: match is_paid {
+                true => payment_request.amount.clone(),
+                false => "0".to_string(),
+            },
+            transaction_hash: is_paid.then_some(transaction_hash),
+            verified_at: now,
+            chain: payment_request.chain.clone(),
+            transaction_logs,
+            explorer_url: None,
+            conversion: None,
+            paid_currency: payment_request.currency.paid_with(None).filter(|_| is_paid),
+            failure_reason: (!is_paid).then_some(ErrorReason::NotFound),
+            proof: None,
+        }
+    }
+}
+
+/// transaction hash reported for a simulated payment, stable per session
+pub fn simulated_transaction_hash(nonce: &str) -> String {
+    let digest = Sha256::digest(format!("x402-simulated:{}", nonce).as_bytes());
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("0x{}", hex)
+}
+
+#[async_trait]
+impl PaymentVerifier for SimulatedVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        Ok(self.verify(payment_request, payer_address))
+    }
+
+    /// the beneficiary of the request is taken as payer
+    async fn verify_payment_by_reference(
+        &self,
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let payer = payment_request.beneficiary.clone().unwrap_or_default();
+        Ok(self.verify(payment_request, &payer))
+    }
+
+    async fn transaction_finality(
+        &self,
+        _chain: &ChainConfig,
+        _transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        Ok(Finality::Finalized)
+    }
+
+    fn supports_chain(&self, _chain_type: &ChainType) -> bool {
+        true
+    }
+}
@@ -0,0 +1,778 @@
+use crate::encryption::EnvelopeCipher;
+use crate::events::PaymentEvent;
+use crate::invoice::Invoice;
+/// Payment history store module.
+use crate::relay::TransferAuthorization;
+use crate::resource::Resource;
+use crate::tax::TaxLine;
+use crate::types::{ChainConfig, Finality};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub use self::instrumented::{
+    InstrumentedStore, LATENCY_BUCKETS_MS, OperationStats, StoreErrorClass, StoreMetrics,
+    StoreObserver, StoreSample,
+};
+
+mod instrumented;
+#[cfg(feature = "sled")]
+mod sled;
+#[cfg(feature = "sled")]
+pub use self::sled::SledStore;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::{SQLITE_SCHEMA_VERSION, SqliteStore};
+
+#[derive(Debug)]
+pub enum StoreError {
+    Backend(String),
+    /// a record could not be encrypted or decrypted, see [`crate::encryption`]
+    Encryption(String),
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Backend(msg) => write!(f, "Store error: {}", msg),
+            Self::Encryption(msg) => write!(f, "Store encryption error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+/// A verified payment as recorded for accounting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRecord {
+    pub recorded_at: u64,
+    pub nonce: String,
+    pub payer: String,
+    /// display name of the chain
+    pub chain: String,
+    pub chain_id: String,
+    /// native currency symbol or token contract address
+    pub token: String,
+    /// amount charged, in the smallest unit of the token
+    pub gross: String,
+    /// fees withheld from the gross amount, in the same unit
+    pub fee: String,
+    pub transaction_hash: Option<String>,
+    /// canonical form of the paid resource
+    pub resource: String,
+    #[serde(default)]
+    pub finality: Finality,
+    /// tax contained in the gross amount, in the unit of the challenge amount
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax: Option<TaxLine>,
+    /// invoice of the session, see [`Invoice`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<Invoice>,
+}
+
+/// Store of verified payments backing exports and admin tooling.
+#[async_trait]
+pub trait PaymentStore: Send + Sync {
+    async fn record_payment(&self, record: PaymentRecord) -> Result<(), StoreError>;
+
+    /// payments recorded in `[from, to)`, oldest first
+    async fn payments_between(&self, from: u64, to: u64) -> Result<Vec<PaymentRecord>, StoreError>;
+
+    /// payments not final yet, oldest first
+    async fn provisional_payments(&self) -> Result<Vec<PaymentRecord>, StoreError>;
+
+    async fn set_finality(&self, nonce: &str, finality: Finality) -> Result<(), StoreError>;
+
+    /// payment of a session, the default scans every recorded payment
+    async fn payment(&self, nonce: &str) -> Result<Option<PaymentRecord>, StoreError> {
+        Ok(self
+            .payments_between(0, u64::MAX)
+            .await?
+            .into_iter()
+            .find(|record| record.nonce == nonce))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryPaymentStore {
+    records: RwLock<Vec<PaymentRecord>>,
+}
+
+impl InMemoryPaymentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PaymentStore for InMemoryPaymentStore {
+    async fn record_payment(&self, record: PaymentRecord) -> Result<(), StoreError> {
+        let mut records = self.records.write().unwrap();
+        let index = records.partition_point(|r| r.recorded_at <= record.recorded_at);
+        records.insert(index, record);
+        Ok(())
+    }
+
+    async fn payments_between(&self, from: u64, to: u64) -> Result<Vec<PaymentRecord>, StoreError> {
+        Ok(self
+            .records
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|record| from <= record.recorded_at && record.recorded_at < to)
+            .cloned()
+            .collect())
+    }
+
+    async fn provisional_payments(&self) -> Result<Vec<PaymentRecord>, StoreError> {
+        Ok(self
+            .records
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|record| record.finality == Finality::Provisional)
+            .cloned()
+            .collect())
+    }
+
+    async fn set_finality(&self, nonce: &str, finality: Finality) -> Result<(), StoreError> {
+        for record in self.records.write().unwrap().iter_mut() {
+            if record.nonce == nonce {
+                record.finality = finality;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Store of per-key usage counters.
+#[async_trait]
+pub trait UsageStore: Send + Sync {
+    /// increment a counter and return the new value
+    async fn increment(&self, key: &str) -> Result<u64, StoreError>;
+
+    async fn get(&self, key: &str) -> Result<u64, StoreError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryUsageStore {
+    counters: RwLock<HashMap<String, u64>>,
+}
+
+impl InMemoryUsageStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl UsageStore for InMemoryUsageStore {
+    async fn increment(&self, key: &str) -> Result<u64, StoreError> {
+        let mut counters = self.counters.write().unwrap();
+        let counter = counters.entry(key.to_string()).or_insert(0);
+        *counter += 1;
+        Ok(*counter)
+    }
+
+    async fn get(&self, key: &str) -> Result<u64, StoreError> {
+        Ok(self.counters.read().unwrap().get(key).copied().unwrap_or(0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementStatus {
+    /// waiting for its first submission
+    Pending,
+    /// sent, waiting to be mined or replaced
+    Submitted,
+    Confirmed,
+    /// gave up, see `last_error`
+    Failed,
+}
+
+impl SettlementStatus {
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Confirmed | Self::Failed)
+    }
+}
+
+/// A relayed transfer queued until it is mined.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementJob {
+    /// idempotency key, a key is only ever queued once
+    pub key: String,
+    pub payment_nonce: String,
+    pub payer: String,
+    pub resource: Resource,
+    pub chain: ChainConfig,
+    pub authorization: TransferAuthorization,
+    pub status: SettlementStatus,
+    /// submissions, replacements and failed checks so far
+    pub attempts: u32,
+    /// hashes of every submission, the latest last
+    pub transaction_hashes: Vec<String>,
+    pub submitted_at: Option<u64>,
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+    pub created_at: u64,
+}
+
+/// Durable queue of relay settlements.
+#[async_trait]
+pub trait SettlementStore: Send + Sync {
+    /// queue a job, returns the stored job when its key is already known
+    async fn enqueue(&self, job: SettlementJob) -> Result<SettlementJob, StoreError>;
+
+    async fn update(&self, job: &SettlementJob) -> Result<(), StoreError>;
+
+    async fn job(&self, key: &str) -> Result<Option<SettlementJob>, StoreError>;
+
+    /// non-terminal jobs whose next attempt is due at `now`, oldest first
+    async fn due(&self, now: u64) -> Result<Vec<SettlementJob>, StoreError>;
+}
+
+fn due_jobs<'a>(jobs: impl Iterator<Item = &'a SettlementJob>, now: u64) -> Vec<SettlementJob> {
+    let mut due: Vec<SettlementJob> = jobs
+        .filter(|job| !job.status.is_terminal() && job.next_attempt_at <= now)
+        .cloned()
+        .collect();
+    due.sort_by_key(|job| job.created_at);
+    due
+}
+
+#[derive(Debug, Default)]
+pub struct InMemorySettlementStore {
+    jobs: RwLock<HashMap<String, SettlementJob>>,
+}
+
+impl InMemorySettlementStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SettlementStore for InMemorySettlementStore {
+    async fn enqueue(&self, job: SettlementJob) -> Result<SettlementJob, StoreError> {
+        let mut jobs = self.jobs.write().unwrap();
+        Ok(jobs.entry(job.key.clone()).or_insert(job).clone())
+    }
+
+    async fn update(&self, job: &SettlementJob) -> Result<(), StoreError> {
+        self.jobs
+            .write()
+            .unwrap()
+            .insert(job.key.clone(), job.clone());
+        Ok(())
+    }
+
+    async fn job(&self, key: &str) -> Result<Option<SettlementJob>, StoreError> {
+        Ok(self.jobs.read().unwrap().get(key).cloned())
+    }
+
+    async fn due(&self, now: u64) -> Result<Vec<SettlementJob>, StoreError> {
+        Ok(due_jobs(self.jobs.read().unwrap().values(), now))
+    }
+}
+
+/// Settlement queue kept in a JSON file, rewritten through a temporary file
+/// on every change so a crash never leaves it half written.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::store::FileSettlementStore;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let store = FileSettlementStore::open("settlements.json")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FileSettlementStore {
+    path: PathBuf,
+    cipher: Option<EnvelopeCipher>,
+    jobs: RwLock<HashMap<String, SettlementJob>>,
+}
+
+impl FileSettlementStore {
+    /// open the queue at `path`, created on the first change if missing
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::open_with(path.as_ref(), None)
+    }
+
+    /// open the queue at `path`, written encrypted with `cipher`
+    pub fn open_encrypted(
+        path: impl AsRef<Path>,
+        cipher: EnvelopeCipher,
+    ) -> Result<Self, StoreError> {
+        Self::open_with(path.as_ref(), Some(cipher))
+    }
+
+    fn open_with(path: &Path, cipher: Option<EnvelopeCipher>) -> Result<Self, StoreError> {
+        let jobs = match read_file(path, cipher.as_ref())? {
+            Some(bytes) => serde_json::from_slice::<Vec<SettlementJob>>(&bytes)
+                .map_err(|e| StoreError::Backend(e.to_string()))?
+                .into_iter()
+                .map(|job| (job.key.clone(), job))
+                .collect(),
+            None => HashMap::new(),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            cipher,
+            jobs: RwLock::new(jobs),
+        })
+    }
+
+    fn persist(&self, jobs: &HashMap<String, SettlementJob>) -> Result<(), StoreError> {
+        let mut jobs: Vec<&SettlementJob> = jobs.values().collect();
+        jobs.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.key.cmp(&b.key)));
+        let bytes =
+            serde_json::to_vec_pretty(&jobs).map_err(|e| StoreError::Backend(e.to_string()))?;
+        write_file(&self.path, &bytes, self.cipher.as_ref())
+    }
+}
+
+/// contents of a store file, decrypted with `cipher`, `None` when missing
+fn read_file(path: &Path, cipher: Option<&EnvelopeCipher>) -> Result<Option<Vec<u8>>, StoreError> {
+    match std::fs::read(path) {
+        Ok(bytes) => match cipher {
+            Some(cipher) => cipher.decrypt(&bytes).map(Some),
+            None => Ok(Some(bytes)),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(StoreError::Backend(e.to_string())),
+    }
+}
+
+/// replace a store file, encrypted with `cipher`
+fn write_file(
+    path: &Path,
+    bytes: &[u8],
+    cipher: Option<&EnvelopeCipher>,
+) -> Result<(), StoreError> {
+    match cipher {
+        Some(cipher) => write_atomically(path, &cipher.encrypt(bytes)?),
+        None => write_atomically(path, bytes),
+    }
+}
+
+/// replace a file through a temporary file so it is never half written
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), StoreError> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| StoreError::Backend(e.to_string()))
+}
+
+#[async_trait]
+impl SettlementStore for FileSettlementStore {
+    async fn enqueue(&self, job: SettlementJob) -> Result<SettlementJob, StoreError> {
+        let mut jobs = self.jobs.write().unwrap();
+        if let Some(existing) = jobs.get(&job.key) {
+            return Ok(existing.clone());
+        }
+        jobs.insert(job.key.clone(), job.clone());
+        if let Err(e) = self.persist(&jobs) {
+            jobs.remove(&job.key);
+            return Err(e);
+        }
+        Ok(job)
+    }
+
+    async fn update(&self, job: &SettlementJob) -> Result<(), StoreError> {
+        let mut jobs = self.jobs.write().unwrap();
+        let previous = jobs.insert(job.key.clone(), job.clone());
+        if let Err(e) = self.persist(&jobs) {
+            match previous {
+                Some(previous) => jobs.insert(job.key.clone(), previous),
+                None => jobs.remove(&job.key),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    async fn job(&self, key: &str) -> Result<Option<SettlementJob>, StoreError> {
+        Ok(self.jobs.read().unwrap().get(key).cloned())
+    }
+
+    async fn due(&self, now: u64) -> Result<Vec<SettlementJob>, StoreError> {
+        Ok(due_jobs(self.jobs.read().unwrap().values(), now))
+    }
+}
+
+/// An engine event kept until a sink acknowledged its delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    /// dedupe key, delivered with the event so consumers can drop an event
+    /// they already processed
+    pub id: String,
+    pub event: PaymentEvent,
+    /// failed deliveries so far
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+    pub last_error: Option<String>,
+    /// delivery was given up after its last attempt, see `last_error`
+    pub failed: bool,
+    pub created_at: u64,
+}
+
+/// Durable queue of engine events waiting for delivery, written before the
+/// event listeners are called so no event is lost when the process stops.
+#[async_trait]
+pub trait OutboxStore: Send + Sync {
+    async fn enqueue(&self, entry: OutboxEntry) -> Result<(), StoreError>;
+
+    async fn update(&self, entry: &OutboxEntry) -> Result<(), StoreError>;
+
+    /// remove a delivered entry
+    async fn acknowledge(&self, id: &str) -> Result<(), StoreError>;
+
+    /// entries not given up whose next attempt is due at `now`, oldest
+    /// first and at most `limit`
+    async fn due(&self, now: u64, limit: usize) -> Result<Vec<OutboxEntry>, StoreError>;
+
+    /// entries given up after their last attempt
+    async fn failed(&self) -> Result<Vec<OutboxEntry>, StoreError>;
+}
+
+fn due_entries<'a>(
+    entries: impl Iterator<Item = &'a OutboxEntry>,
+    now: u64,
+    limit: usize,
+) -> Vec<OutboxEntry> {
+    let mut due: Vec<OutboxEntry> = entries
+        .filter(|entry| !entry.failed && entry.next_attempt_at <= now)
+        .cloned()
+        .collect();
+    due.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    due.truncate(limit);
+    due
+}
+
+fn failed_entries<'a>(entries: impl Iterator<Item = &'a OutboxEntry>) -> Vec<OutboxEntry> {
+    let mut failed: Vec<OutboxEntry> = entries.filter(|entry| entry.failed).cloned().collect();
+    failed.sort_by_key(|entry| entry.created_at);
+    failed
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryOutboxStore {
+    entries: RwLock<HashMap<String, OutboxEntry>>,
+}
+
+impl InMemoryOutboxStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OutboxStore for InMemoryOutboxStore {
+    async fn enqueue(&self, entry: OutboxEntry) -> Result<(), StoreError> {
+        self.entries
+            .write()
+            .unwrap()
+            .entry(entry.id.clone())
+            .or_insert(entry);
+        Ok(())
+    }
+
+    async fn update(&self, entry: &OutboxEntry) -> Result<(), StoreError> {
+        self.entries
+            .write()
+            .unwrap()
+            .insert(entry.id.clone(), entry.clone());
+        Ok(())
+    }
+
+    async fn acknowledge(&self, id: &str) -> Result<(), StoreError> {
+        self.entries.write().unwrap().remove(id);
+        Ok(())
+    }
+
+    async fn due(&self, now: u64, limit: usize) -> Result<Vec<OutboxEntry>, StoreError> {
+        Ok(due_entries(
+            self.entries.read().unwrap().values(),
+            now,
+            limit,
+        ))
+    }
+
+    async fn failed(&self) -> Result<Vec<OutboxEntry>, StoreError> {
+        Ok(failed_entries(self.entries.read().unwrap().values()))
+    }
+}
+
+/// Outbox kept in a JSON file, rewritten through a temporary file on every
+/// change like [`FileSettlementStore`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::store::FileOutboxStore;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let store = FileOutboxStore::open("outbox.json")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FileOutboxStore {
+    path: PathBuf,
+    cipher: Option<EnvelopeCipher>,
+    entries: RwLock<HashMap<String, OutboxEntry>>,
+}
+
+impl FileOutboxStore {
+    /// open the outbox at `path`, created on the first change if missing
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::open_with(path.as_ref(), None)
+    }
+
+    /// open the outbox at `path`, written encrypted with `cipher`
+    pub fn open_encrypted(
+        path: impl AsRef<Path>,
+        cipher: EnvelopeCipher,
+    ) -> Result<Self, StoreError> {
+        Self::open_with(path.as_ref(), Some(cipher))
+    }
+
+    fn open_with(path: &Path, cipher: Option<EnvelopeCipher>) -> Result<Self, StoreError> {
+        let entries = match read_file(path, cipher.as_ref())? {
+            Some(bytes) => serde_json::from_slice::<Vec<OutboxEntry>>(&bytes)
+                .map_err(|e| StoreError::Backend(e.to_string()))?
+                .into_iter()
+                .map(|entry| (entry.id.clone(), entry))
+                .collect(),
+            None => HashMap::new(),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            cipher,
+            entries: RwLock::new(entries),
+        })
+    }
+
+    fn persist(&self, entries: &HashMap<String, OutboxEntry>) -> Result<(), StoreError> {
+        let mut entries: Vec<&OutboxEntry> = entries.values().collect();
+        entries.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        let bytes =
+            serde_json::to_vec_pretty(&entries).map_err(|e| StoreError::Backend(e.to_string()))?;
+        write_file(&self.path, &bytes, self.cipher.as_ref())
+    }
+
+    /// apply a change and persist it, undoing the change when the file
+    /// cannot be written
+    fn change(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut HashMap<String, OutboxEntry>),
+    ) -> Result<(), StoreError> {
+        let mut entries = self.entries.write().unwrap();
+        let previous = entries.get(id).cloned();
+        change(&mut entries);
+        if let Err(e) = self.persist(&entries) {
+            match previous {
+                Some(previous) => entries.insert(id.to_string(), previous),
+                None => entries.remove(id),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutboxStore for FileOutboxStore {
+    async fn enqueue(&self, entry: OutboxEntry) -> Result<(), StoreError> {
+        if self.entries.read().unwrap().contains_key(&entry.id) {
+            return Ok(());
+        }
+        let id = entry.id.clone();
+        self.change(&id, |entries| {
+            entries.insert(entry.id.clone(), entry);
+        })
+    }
+
+    async fn update(&self, entry: &OutboxEntry) -> Result<(), StoreError> {
+        self.change(&entry.id, |entries| {
+            entries.insert(entry.id.clone(), entry.clone());
+        })
+    }
+
+    async fn acknowledge(&self, id: &str) -> Result<(), StoreError> {
+        self.change(id, |entries| {
+            entries.remove(id);
+        })
+    }
+
+    async fn due(&self, now: u64, limit: usize) -> Result<Vec<OutboxEntry>, StoreError> {
+        Ok(due_entries(
+            self.entries.read().unwrap().values(),
+            now,
+            limit,
+        ))
+    }
+
+    async fn failed(&self) -> Result<Vec<OutboxEntry>, StoreError> {
+        Ok(failed_entries(self.entries.read().unwrap().values()))
+    }
+}
+
+/// Store of the next account nonce of sending wallets, keyed by chain id
+/// and address.
+#[async_trait]
+pub trait NonceStore: Send + Sync {
+    async fn next_nonce(&self, key: &str) -> Result<Option<u64>, StoreError>;
+
+    async fn save_next_nonce(&self, key: &str, next: u64) -> Result<(), StoreError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryNonceStore {
+    nonces: RwLock<HashMap<String, u64>>,
+}
+
+impl InMemoryNonceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl NonceStore for InMemoryNonceStore {
+    async fn next_nonce(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        Ok(self.nonces.read().unwrap().get(key).copied())
+    }
+
+    async fn save_next_nonce(&self, key: &str, next: u64) -> Result<(), StoreError> {
+        self.nonces.write().unwrap().insert(key.to_string(), next);
+        Ok(())
+    }
+}
+
+/// Account nonces kept in a JSON file.
+#[derive(Debug)]
+pub struct FileNonceStore {
+    path: PathBuf,
+    cipher: Option<EnvelopeCipher>,
+    nonces: RwLock<HashMap<String, u64>>,
+}
+
+impl FileNonceStore {
+    /// open the store at `path`, created on the first change if missing
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::open_with(path.as_ref(), None)
+    }
+
+    /// open the store at `path`, written encrypted with `cipher`
+    pub fn open_encrypted(
+        path: impl AsRef<Path>,
+        cipher: EnvelopeCipher,
+    ) -> Result<Self, StoreError> {
+        Self::open_with(path.as_ref(), Some(cipher))
+    }
+
+    fn open_with(path: &Path, cipher: Option<EnvelopeCipher>) -> Result<Self, StoreError> {
+        let nonces = match read_file(path, cipher.as_ref())? {
+            Some(bytes) => {
+                serde_json::from_slice(&bytes).map_err(|e| StoreError::Backend(e.to_string()))?
+            }
+            None => HashMap::new(),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            cipher,
+            nonces: RwLock::new(nonces),
+        })
+    }
+}
+
+#[async_trait]
+impl NonceStore for FileNonceStore {
+    async fn next_nonce(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        Ok(self.nonces.read().unwrap().get(key).copied())
+    }
+
+    async fn save_next_nonce(&self, key: &str, next: u64) -> Result<(), StoreError> {
+        let mut nonces = self.nonces.write().unwrap();
+        let previous = nonces.insert(key.to_string(), next);
+        let bytes =
+            serde_json::to_vec_pretty(&*nonces).map_err(|e| StoreError::Backend(e.to_string()))?;
+        if let Err(e) = write_file(&self.path, &bytes, self.cipher.as_ref()) {
+            match previous {
+                Some(previous) => nonces.insert(key.to_string(), previous),
+                None => nonces.remove(key),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+/// Advisory locks shared by the replicas of a deployment, e.g. Redis
+/// `SET key owner NX PX ttl` or Postgres `pg_try_advisory_lock`. A lock
+/// expires after its ttl so a crashed replica cannot hold it forever.
+#[async_trait]
+pub trait LockStore: Send + Sync {
+    /// take the lock for `owner` until `now + ttl_secs`, `false` when
+    /// another owner holds it
+    async fn try_lock(
+        &self,
+        key: &str,
+        owner: &str,
+        ttl_secs: u64,
+        now: u64,
+    ) -> Result<bool, StoreError>;
+
+    /// release the lock if `owner` still holds it
+    async fn unlock(&self, key: &str, owner: &str) -> Result<(), StoreError>;
+}
+
+/// Locks of a single process, for tests and single replica deployments.
+#[derive(Debug, Default)]
+pub struct InMemoryLockStore {
+    /// owner and expiry of every held lock
+    locks: RwLock<HashMap<String, (String, u64)>>,
+}
+
+impl InMemoryLockStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl LockStore for InMemoryLockStore {
+    async fn try_lock(
+        &self,
+        key: &str,
+        owner: &str,
+        ttl_secs: u64,
+        now: u64,
+    ) -> Result<bool, StoreError> {
+        let mut locks = self.locks.write().unwrap();
+        if let Some((holder, expires_at)) = locks.get(key)
+            && holder != owner
+            && *expires_at > now
+        {
+            return Ok(false);
+        }
+        locks.insert(key.to_string(), (owner.to_string(), now + ttl_secs));
+        Ok(true)
+    }
+
+    async fn unlock(&self, key: &str, owner: &str) -> Result<(), StoreError> {
+        let mut locks = self.locks.write().unwrap();
+        if locks.get(key).is_some_and(|(holder, _)| holder == owner) {
+            locks.remove(key);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+/// Store instrumentation module.
+use super::{
+    LockStore, NonceStore, OutboxEntry, OutboxStore, PaymentRecord, PaymentStore, SettlementJob,
+    SettlementStore, StoreError, UsageStore,
+};
+use crate::audit::{AuditEntry, AuditError, AuditLog};
+use crate::revocation::{RevocationEntry, RevocationError, RevocationStore};
+use crate::types::Finality;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the latency buckets, in milliseconds, the last bucket
+/// takes everything above.
+pub const LATENCY_BUCKETS_MS: [u64; 9] = [1, 5, 10, 25, 50, 100, 250, 500, 1000];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreErrorClass {
+    /// the backend failed, e.g. a lost connection or a full disk
+    Backend,
+    /// a record could not be encrypted or decrypted
+    Encryption,
+}
+
+trait Classify {
+    fn class(&self) -> StoreErrorClass;
+}
+
+impl Classify for StoreError {
+    fn class(&self) -> StoreErrorClass {
+        match self {
+            Self::Backend(_) => StoreErrorClass::Backend,
+            Self::Encryption(_) => StoreErrorClass::Encryption,
+        }
+    }
+}
+
+impl Classify for RevocationError {
+    fn class(&self) -> StoreErrorClass {
+        StoreErrorClass::Backend
+    }
+}
+
+impl Classify for AuditError {
+    fn class(&self) -> StoreErrorClass {
+        StoreErrorClass::Backend
+    }
+}
+
+/// One store call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreSample {
+    /// name the store was instrumented with
+    pub store: String,
+    /// trait method, e.g. `record_payment`
+    pub operation: &'static str,
+    pub latency: Duration,
+    pub error: Option<StoreErrorClass>,
+    /// the call took at least the slow threshold
+    pub slow: bool,
+}
+
+/// Receives a sample for every instrumented store call, e.g. to export it
+/// as OpenTelemetry or Prometheus metrics.
+pub trait StoreObserver: Send + Sync {
+    fn record(&self, sample: &StoreSample);
+}
+
+/// Counters of one operation of a store.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationStats {
+    pub store: String,
+    pub operation: String,
+    pub calls: u64,
+    pub errors: HashMap<StoreErrorClass, u64>,
+    pub slow_calls: u64,
+    /// calls per bucket of [`LATENCY_BUCKETS_MS`], plus one for slower calls
+    pub latency_buckets: Vec<u64>,
+    pub total_latency_micros: u64,
+}
+
+impl OperationStats {
+    pub fn error_count(&self) -> u64 {
+        self.errors.values().sum()
+    }
+
+    pub fn mean_latency(&self) -> Option<Duration> {
+        (self.calls > 0).then(|| Duration::from_micros(self.total_latency_micros / self.calls))
+    }
+}
+
+/// Observer aggregating samples in memory, read with
+/// [`StoreMetrics::snapshot`].
+#[derive(Debug, Default)]
+pub struct StoreMetrics {
+    operations: RwLock<HashMap<(String, &'static str), OperationStats>>,
+}
+
+impl StoreMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// counters of every operation seen so far, sorted by store and
+    /// operation
+    pub fn snapshot(&self) -> Vec<OperationStats> {
+        let mut stats: Vec<OperationStats> =
+            self.operations.read().unwrap().values().cloned().collect();
+        stats.sort_by(|a, b| (&a.store, &a.operation).cmp(&(&b.store, &b.operation)));
+        stats
+    }
+
+    pub fn operation(&self, store: &str, operation: &str) -> Option<OperationStats> {
+        self.operations
+            .read()
+            .unwrap()
+            .values()
+            .find(|stats| stats.store == store && stats.operation == operation)
+            .cloned()
+    }
+}
+
+impl StoreObserver for StoreMetrics {
+    fn record(&self, sample: &StoreSample) {
+        let mut operations = self.operations.write().unwrap();
+        let stats = operations
+            .entry((sample.store.clone(), sample.operation))
+            .or_insert_with(|| OperationStats {
+                store: sample.store.clone(),
+                operation: sample.operation.to_string(),
+                latency_buckets: vec![0; LATENCY_BUCKETS_MS.len() + 1],
+                ..Default::default()
+            });
+        stats.calls += 1;
+        if let Some(class) = sample.error {
+            *stats.errors.entry(class).or_insert(0) += 1;
+        }
+        if sample.slow {
+            stats.slow_calls += 1;
+        }
+        let millis = sample.latency.as_millis() as u64;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| millis <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        stats.latency_buckets[bucket] += 1;
+        stats.total_latency_micros += sample.latency.as_micros() as u64;
+    }
+}
+
+/// Wraps any store of this module, the revocation store or the audit log
+/// and reports every call to an observer with its latency and error class.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::store::{InMemoryPaymentStore, InstrumentedStore, StoreMetrics};
+///
+/// let metrics = Arc::new(StoreMetrics::new());
+/// let payments = InstrumentedStore::new(
+///     "payments",
+///     Arc::new(InMemoryPaymentStore::new()),
+///     metrics.clone(),
+/// );
+/// let engine = X402::from_default_config()
+///     .unwrap()
+///     .with_payment_store(Arc::new(payments));
+/// ```
+pub struct InstrumentedStore<S: ?Sized> {
+    name: String,
+    inner: Arc<S>,
+    observer: Arc<dyn StoreObserver>,
+    slow_threshold: Duration,
+}
+
+impl<S: ?Sized> InstrumentedStore<S> {
+    pub fn new(name: &str, inner: Arc<S>, observer: Arc<dyn StoreObserver>) -> Self {
+        Self {
+            name: name.to_string(),
+            inner,
+            observer,
+            slow_threshold: Duration::from_millis(100),
+        }
+    }
+
+    /// calls taking at least `threshold` are flagged slow, 100ms by default
+    pub fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = threshold;
+        self
+    }
+
+    async fn observe<T, E: Classify>(
+        &self,
+        operation: &'static str,
+        call: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let started = Instant::now();
+        let result = call.await;
+        let latency = started.elapsed();
+        self.observer.record(&StoreSample {
+            store: self.name.clone(),
+            operation,
+            latency,
+            error: result.as_ref().err().map(Classify::class),
+            slow: latency >= self.slow_threshold,
+        });
+        result
+    }
+}
+
+#[async_trait]
+impl<S: PaymentStore + ?Sized> PaymentStore for InstrumentedStore<S> {
+    async fn record_payment(&self, record: PaymentRecord) -> Result<(), StoreError> {
+        self.observe("record_payment", self.inner.record_payment(record))
+            .await
+    }
+
+    async fn payments_between(&self, from: u64, to: u64) -> Result<Vec<PaymentRecord>, StoreError> {
+        self.observe("payments_between", self.inner.payments_between(from, to))
+            .await
+    }
+
+    async fn provisional_payments(&self) -> Result<Vec<PaymentRecord>, StoreError> {
+        self.observe("provisional_payments", self.inner.provisional_payments())
+            .await
+    }
+
+    async fn set_finality(&self, nonce: &str, finality: Finality) -> Result<(), StoreError> {
+        self.observe("set_finality", self.inner.set_finality(nonce, finality))
+            .await
+    }
+
+    async fn payment(&self, nonce: &str) -> Result<Option<PaymentRecord>, StoreError> {
+        self.observe("payment", self.inner.payment(nonce)).await
+    }
+}
+
+#[async_trait]
+impl<S: UsageStore + ?Sized> UsageStore for InstrumentedStore<S> {
+    async fn increment(&self, key: &str) -> Result<u64, StoreError> {
+        self.observe("increment", self.inner.increment(key)).await
+    }
+
+    async fn get(&self, key: &str) -> Result<u64, StoreError> {
+        self.observe("get", self.inner.get(key)).await
+    }
+}
+
+#[async_trait]
+impl<S: SettlementStore + ?Sized> SettlementStore for InstrumentedStore<S> {
+    async fn enqueue(&self, job: SettlementJob) -> Result<SettlementJob, StoreError> {
+        self.observe("enqueue", self.inner.enqueue(job)).await
+    }
+
+    async fn update(&self, job: &SettlementJob) -> Result<(), StoreError> {
+        self.observe("update", self.inner.update(job)).await
+    }
+
+    async fn job(&self, key: &str) -> Result<Option<SettlementJob>, StoreError> {
+        self.observe("job", self.inner.job(key)).await
+    }
+
+    async fn due(&self, now: u64) -> Result<Vec<SettlementJob>, StoreError> {
+        self.observe("due", self.inner.due(now)).await
+    }
+}
+
+#[async_trait]
+impl<S: OutboxStore + ?Sized> OutboxStore for InstrumentedStore<S> {
+    async fn enqueue(&self, entry: OutboxEntry) -> Result<(), StoreError> {
+        self.observe("enqueue", self.inner.enqueue(entry)).await
+    }
+
+    async fn update(&self, entry: &OutboxEntry) -> Result<(), StoreError> {
+        self.observe("update", self.inner.update(entry)).await
+    }
+
+    async fn acknowledge(&self, id: &str) -> Result<(), StoreError> {
+        self.observe("acknowledge", self.inner.acknowledge(id))
+            .await
+    }
+
+    async fn due(&self, now: u64, limit: usize) -> Result<Vec<OutboxEntry>, StoreError> {
+        self.observe("due", self.inner.due(now, limit)).await
+    }
+
+    async fn failed(&self) -> Result<Vec<OutboxEntry>, StoreError> {
+        self.observe("failed", self.inner.failed()).await
+    }
+}
+
+#[async_trait]
+impl<S: NonceStore + ?Sized> NonceStore for InstrumentedStore<S> {
+    async fn next_nonce(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.observe("next_nonce", self.inner.next_nonce(key)).await
+    }
+
+    async fn save_next_nonce(&self, key: &str, next: u64) -> Result<(), StoreError> {
+        self.observe("save_next_nonce", self.inner.save_next_nonce(key, next))
+            .await
+    }
+}
+
+#[async_trait]
+impl<S: LockStore + ?Sized> LockStore for InstrumentedStore<S> {
+    async fn try_lock(
+        &self,
+        key: &str,
+        owner: &str,
+        ttl_secs: u64,
+        now: u64,
+    ) -> Result<bool, StoreError> {
+        self.observe("try_lock", self.inner.try_lock(key, owner, ttl_secs, now))
+            .await
+    }
+
+    async fn unlock(&self, key: &str, owner: &str) -> Result<(), StoreError> {
+        self.observe("unlock", self.inner.unlock(key, owner)).await
+    }
+}
+
+#[async_trait]
+impl<S: RevocationStore + ?Sized> RevocationStore for InstrumentedStore<S> {
+    async fn revoke(&self, key: &str, entry: RevocationEntry) -> Result<(), RevocationError> {
+        self.observe("revoke", self.inner.revoke(key, entry)).await
+    }
+
+    async fn get(&self, key: &str, now: u64) -> Result<Option<RevocationEntry>, RevocationError> {
+        self.observe("get", self.inner.get(key, now)).await
+    }
+
+    async fn purge_expired(&self, now: u64) -> Result<usize, RevocationError> {
+        self.observe("purge_expired", self.inner.purge_expired(now))
+            .await
+    }
+}
+
+#[async_trait]
+impl<S: AuditLog + ?Sized> AuditLog for InstrumentedStore<S> {
+    async fn append(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        self.observe("append", self.inner.append(entry)).await
+    }
+
+    async fn entries_for(&self, target: &str) -> Result<Vec<AuditEntry>, AuditError> {
+        self.observe("entries_for", self.inner.entries_for(target))
+            .await
+    }
+}
@@ -0,0 +1,354 @@
+/// Sled store backend module.
+use super::{
+    NonceStore, OutboxEntry, OutboxStore, PaymentRecord, PaymentStore, SettlementJob,
+    SettlementStore, StoreError, UsageStore, due_entries, due_jobs, failed_entries,
+};
+use crate::encryption::EnvelopeCipher;
+use crate::revocation::{RevocationEntry, RevocationError, RevocationStore};
+use crate::types::Finality;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+fn backend(e: impl std::fmt::Display) -> StoreError {
+    StoreError::Backend(e.to_string())
+}
+
+/// Embedded store keeping payments, settlements, account nonces, the event
+/// outbox, usage counters and revocations in one sled database, for single
+/// node deployments without an external database. Every write is flushed
+/// before it returns, so an acknowledged change survives a crash.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::store::SledStore;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let store = Arc::new(SledStore::open("x402.sled")?);
+/// let engine = X402::from_default_config()?
+///     .with_payment_store(store.clone())
+///     .with_settlement_store(store.clone())
+///     .with_outbox_store(store.clone())
+///     .with_revocation_store(store);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SledStore {
+    db: sled::Db,
+    /// keyed by record time and nonce so ranges come out in time order
+    payments: sled::Tree,
+    /// nonce to key in `payments`
+    payment_keys: sled::Tree,
+    settlements: sled::Tree,
+    nonces: sled::Tree,
+    outbox: sled::Tree,
+    usage: sled::Tree,
+    revocations: sled::Tree,
+    cipher: Option<EnvelopeCipher>,
+}
+
+impl SledStore {
+    /// open the database at `path`, created if missing
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::from_db(sled::open(path).map_err(backend)?, None)
+    }
+
+    /// open the database at `path` with every record encrypted with
+    /// `cipher`, keys stay readable
+    pub fn open_encrypted(
+        path: impl AsRef<Path>,
+        cipher: EnvelopeCipher,
+    ) -> Result<Self, StoreError> {
+        Self::from_db(sled::open(path).map_err(backend)?, Some(cipher))
+    }
+
+    /// store in an open database, e.g. a temporary one for tests
+    pub fn from_db(db: sled::Db, cipher: Option<EnvelopeCipher>) -> Result<Self, StoreError> {
+        let tree = |name: &str| db.open_tree(name).map_err(backend);
+        Ok(Self {
+            payments: tree("payments")?,
+            payment_keys: tree("payment_keys")?,
+            settlements: tree("settlements")?,
+            nonces: tree("nonces")?,
+            outbox: tree("outbox")?,
+            usage: tree("usage")?,
+            revocations: tree("revocations")?,
+            db,
+            cipher,
+        })
+    }
+
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, StoreError> {
+        let bytes = serde_json::to_vec(value).map_err(backend)?;
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(&bytes),
+            None => Ok(bytes),
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, StoreError> {
+        let bytes = match &self.cipher {
+            Some(cipher) => cipher.decrypt(bytes)?,
+            None => bytes.to_vec(),
+        };
+        serde_json::from_slice(&bytes).map_err(backend)
+    }
+
+    fn values<T: DeserializeOwned>(&self, tree: &sled::Tree) -> Result<Vec<T>, StoreError> {
+        tree.iter()
+            .values()
+            .map(|value| self.decode(&value.map_err(backend)?))
+            .collect()
+    }
+
+    async fn flush(&self) -> Result<(), StoreError> {
+        self.db.flush_async().await.map_err(backend)?;
+        Ok(())
+    }
+
+    fn payment_key(record: &PaymentRecord) -> Vec<u8> {
+        let mut key = record.recorded_at.to_be_bytes().to_vec();
+        key.extend_from_slice(record.nonce.as_bytes());
+        key
+    }
+}
+
+#[async_trait]
+impl PaymentStore for SledStore {
+    async fn record_payment(&self, record: PaymentRecord) -> Result<(), StoreError> {
+        let key = Self::payment_key(&record);
+        self.payments
+            .insert(&key, self.encode(&record)?)
+            .map_err(backend)?;
+        self.payment_keys
+            .insert(record.nonce.as_bytes(), key)
+            .map_err(backend)?;
+        self.flush().await
+    }
+
+    async fn payments_between(&self, from: u64, to: u64) -> Result<Vec<PaymentRecord>, StoreError> {
+        self.payments
+            .range(from.to_be_bytes()..to.to_be_bytes())
+            .values()
+            .map(|value| self.decode(&value.map_err(backend)?))
+            .collect()
+    }
+
+    async fn provisional_payments(&self) -> Result<Vec<PaymentRecord>, StoreError> {
+        Ok(self
+            .values::<PaymentRecord>(&self.payments)?
+            .into_iter()
+            .filter(|record| record.finality == Finality::Provisional)
+            .collect())
+    }
+
+    async fn set_finality(&self, nonce: &str, finality: Finality) -> Result<(), StoreError> {
+        let Some(mut record) = self.payment(nonce).await? else {
+            return Ok(());
+        };
+        record.finality = finality;
+        self.payments
+            .insert(Self::payment_key(&record), self.encode(&record)?)
+            .map_err(backend)?;
+        self.flush().await
+    }
+
+    async fn payment(&self, nonce: &str) -> Result<Option<PaymentRecord>, StoreError> {
+        let Some(key) = self.payment_keys.get(nonce.as_bytes()).map_err(backend)? else {
+            return Ok(None);
+        };
+        self.payments
+            .get(key)
+            .map_err(backend)?
+            .map(|value| self.decode(&value))
+            .transpose()
+    }
+}
+
+#[async_trait]
+impl SettlementStore for SledStore {
+    async fn enqueue(&self, job: SettlementJob) -> Result<SettlementJob, StoreError> {
+        let inserted = self
+            .settlements
+            .compare_and_swap(
+                job.key.as_bytes(),
+                None as Option<&[u8]>,
+                Some(self.encode(&job)?),
+            )
+            .map_err(backend)?;
+        match inserted {
+            Ok(()) => {
+                self.flush().await?;
+                Ok(job)
+            }
+            Err(existing) => {
+                self.decode(&existing.current.ok_or_else(|| {
+                    StoreError::Backend(format!("settlement {} vanished", job.key))
+                })?)
+            }
+        }
+    }
+
+    async fn update(&self, job: &SettlementJob) -> Result<(), StoreError> {
+        self.settlements
+            .insert(job.key.as_bytes(), self.encode(job)?)
+            .map_err(backend)?;
+        self.flush().await
+    }
+
+    async fn job(&self, key: &str) -> Result<Option<SettlementJob>, StoreError> {
+        self.settlements
+            .get(key.as_bytes())
+            .map_err(backend)?
+            .map(|value| self.decode(&value))
+            .transpose()
+    }
+
+    async fn due(&self, now: u64) -> Result<Vec<SettlementJob>, StoreError> {
+        Ok(due_jobs(
+            self.values::<SettlementJob>(&self.settlements)?.iter(),
+            now,
+        ))
+    }
+}
+
+#[async_trait]
+impl NonceStore for SledStore {
+    async fn next_nonce(&self, key: &str) -> Result<Option<u64>, StoreError> {
+        self.nonces
+            .get(key.as_bytes())
+            .map_err(backend)?
+            .map(|value| self.decode(&value))
+            .transpose()
+    }
+
+    async fn save_next_nonce(&self, key: &str, next: u64) -> Result<(), StoreError> {
+        self.nonces
+            .insert(key.as_bytes(), self.encode(&next)?)
+            .map_err(backend)?;
+        self.flush().await
+    }
+}
+
+#[async_trait]
+impl OutboxStore for SledStore {
+    async fn enqueue(&self, entry: OutboxEntry) -> Result<(), StoreError> {
+        // a known id keeps its delivery state
+        let _ = self
+            .outbox
+            .compare_and_swap(
+                entry.id.as_bytes(),
+                None as Option<&[u8]>,
+                Some(self.encode(&entry)?),
+            )
+            .map_err(backend)?;
+        self.flush().await
+    }
+
+    async fn update(&self, entry: &OutboxEntry) -> Result<(), StoreError> {
+        self.outbox
+            .insert(entry.id.as_bytes(), self.encode(entry)?)
+            .map_err(backend)?;
+        self.flush().await
+    }
+
+    async fn acknowledge(&self, id: &str) -> Result<(), StoreError> {
+        self.outbox.remove(id.as_bytes()).map_err(backend)?;
+        self.flush().await
+    }
+
+    async fn due(&self, now: u64, limit: usize) -> Result<Vec<OutboxEntry>, StoreError> {
+        Ok(due_entries(
+            self.values::<OutboxEntry>(&self.outbox)?.iter(),
+            now,
+            limit,
+        ))
+    }
+
+    async fn failed(&self) -> Result<Vec<OutboxEntry>, StoreError> {
+        Ok(failed_entries(
+            self.values::<OutboxEntry>(&self.outbox)?.iter(),
+        ))
+    }
+}
+
+#[async_trait]
+impl UsageStore for SledStore {
+    /// counters are stored as plain big endian integers, they carry no
+    /// payment metadata
+    async fn increment(&self, key: &str) -> Result<u64, StoreError> {
+        let value = self
+            .usage
+            .update_and_fetch(key.as_bytes(), |old| {
+                let count = old
+                    .and_then(|old| <[u8; 8]>::try_from(old).ok())
+                    .map(u64::from_be_bytes)
+                    .unwrap_or(0);
+                Some((count + 1).to_be_bytes().to_vec())
+            })
+            .map_err(backend)?;
+        self.flush().await?;
+        Ok(value
+            .and_then(|value| <[u8; 8]>::try_from(value.as_ref()).ok())
+            .map(u64::from_be_bytes)
+            .unwrap_or(0))
+    }
+
+    async fn get(&self, key: &str) -> Result<u64, StoreError> {
+        Ok(self
+            .usage
+            .get(key.as_bytes())
+            .map_err(backend)?
+            .and_then(|value| <[u8; 8]>::try_from(value.as_ref()).ok())
+            .map(u64::from_be_bytes)
+            .unwrap_or(0))
+    }
+}
+
+#[async_trait]
+impl RevocationStore for SledStore {
+    async fn revoke(&self, key: &str, entry: RevocationEntry) -> Result<(), RevocationError> {
+        let revoke = async {
+            self.revocations
+                .insert(key.as_bytes(), self.encode(&entry)?)
+                .map_err(backend)?;
+            self.flush().await
+        };
+        revoke
+            .await
+            .map_err(|e| RevocationError::Backend(e.to_string()))
+    }
+
+    async fn get(&self, key: &str, now: u64) -> Result<Option<RevocationEntry>, RevocationError> {
+        let entry: Option<RevocationEntry> = self
+            .revocations
+            .get(key.as_bytes())
+            .map_err(backend)
+            .and_then(|value| value.map(|value| self.decode(&value)).transpose())
+            .map_err(|e| RevocationError::Backend(e.to_string()))?;
+        Ok(entry.filter(|entry| now < entry.expires_at))
+    }
+
+    async fn purge_expired(&self, now: u64) -> Result<usize, RevocationError> {
+        let purge = async {
+            let mut purged = 0;
+            for item in self.revocations.iter() {
+                let (key, value) = item.map_err(backend)?;
+                let entry: RevocationEntry = self.decode(&value)?;
+                if now >= entry.expires_at {
+                    self.revocations.remove(key).map_err(backend)?;
+                    purged += 1;
+                }
+            }
+            self.flush().await?;
+            Ok::<_, StoreError>(purged)
+        };
+        purge
+            .await
+            .map_err(|e| RevocationError::Backend(e.to_string()))
+    }
+}
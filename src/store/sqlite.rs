@@ -0,0 +1,329 @@
+/// SQLite store backend module.
+use super::{PaymentRecord, PaymentStore, StoreError, UsageStore};
+use crate::audit::{AuditEntry, AuditError, AuditLog};
+use crate::revocation::{RevocationEntry, RevocationError, RevocationStore};
+use crate::types::Finality;
+use async_trait::async_trait;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Schema changes in order, `PRAGMA user_version` holds how many ran.
+/// Append new migrations, never edit a released one.
+const MIGRATIONS: &[&str] = &["CREATE TABLE payments (
+        nonce TEXT PRIMARY KEY,
+        recorded_at INTEGER NOT NULL,
+        provisional INTEGER NOT NULL,
+        record TEXT NOT NULL
+    );
+    CREATE INDEX payments_recorded_at ON payments (recorded_at);
+    CREATE TABLE revocations (
+        key TEXT PRIMARY KEY,
+        expires_at INTEGER NOT NULL,
+        entry TEXT NOT NULL
+    );
+    CREATE TABLE audit_log (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        target TEXT NOT NULL,
+        entry TEXT NOT NULL
+    );
+    CREATE INDEX audit_log_target ON audit_log (target);
+    CREATE TABLE usage (
+        key TEXT PRIMARY KEY,
+        count INTEGER NOT NULL
+    );"];
+
+/// version of the schema created by this release
+pub const SQLITE_SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+fn backend(e: impl std::fmt::Display) -> StoreError {
+    StoreError::Backend(e.to_string())
+}
+
+/// run the migrations the database has not seen, in one transaction
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let transaction = connection.transaction()?;
+    let version: usize = transaction.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for migration in MIGRATIONS.iter().skip(version) {
+        transaction.execute_batch(migration)?;
+    }
+    transaction.pragma_update(None, "user_version", MIGRATIONS.len())?;
+    transaction.commit()
+}
+
+/// Store of payments, revocations, the audit log and usage counters in a
+/// SQLite database, for single node paywalls.
+///
+/// The database runs in WAL mode, so readers never block the writer, and
+/// waits up to the busy timeout, 5 seconds by default, for a lock held by
+/// another connection, e.g. a second process on the same file. Queries run
+/// on the blocking thread pool.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::store::SqliteStore;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let store = Arc::new(SqliteStore::open("x402.db")?);
+/// let engine = X402::from_default_config()?
+///     .with_payment_store(store.clone())
+///     .with_revocation_store(store.clone())
+///     .with_audit_log(store);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SqliteStore {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// open the database at `path`, created and migrated if needed
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::open_with_timeout(path, Duration::from_secs(5))
+    }
+
+    pub fn open_with_timeout(
+        path: impl AsRef<Path>,
+        busy_timeout: Duration,
+    ) -> Result<Self, StoreError> {
+        let mut connection = Connection::open(path).map_err(backend)?;
+        connection.busy_timeout(busy_timeout).map_err(backend)?;
+        let _: String = connection
+            .query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))
+            .map_err(backend)?;
+        connection
+            .pragma_update(None, "synchronous", "NORMAL")
+            .map_err(backend)?;
+        migrate(&mut connection).map_err(backend)?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// schema version of the database, see [`SQLITE_SCHEMA_VERSION`]
+    pub async fn schema_version(&self) -> Result<usize, StoreError> {
+        self.run(|connection| connection.query_row("PRAGMA user_version", [], |row| row.get(0)))
+            .await
+    }
+
+    async fn run<T, F>(&self, query: F) -> Result<T, StoreError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || query(&mut connection.lock().unwrap()))
+            .await
+            .map_err(backend)?
+            .map_err(backend)
+    }
+
+    fn records(rows: Vec<String>) -> Result<Vec<PaymentRecord>, StoreError> {
+        rows.iter()
+            .map(|row| serde_json::from_str(row).map_err(backend))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PaymentStore for SqliteStore {
+    async fn record_payment(&self, record: PaymentRecord) -> Result<(), StoreError> {
+        let json = serde_json::to_string(&record).map_err(backend)?;
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO payments (nonce, recorded_at, provisional, record)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    record.nonce,
+                    record.recorded_at as i64,
+                    record.finality == Finality::Provisional,
+                    json
+                ],
+            )
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn payments_between(&self, from: u64, to: u64) -> Result<Vec<PaymentRecord>, StoreError> {
+        let (from, to) = (
+            from.min(i64::MAX as u64) as i64,
+            to.min(i64::MAX as u64) as i64,
+        );
+        let rows = self
+            .run(move |connection| {
+                connection
+                    .prepare(
+                        "SELECT record FROM payments WHERE recorded_at >= ?1 AND recorded_at < ?2
+                         ORDER BY recorded_at, rowid",
+                    )?
+                    .query_map(params![from, to], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+        Self::records(rows)
+    }
+
+    async fn provisional_payments(&self) -> Result<Vec<PaymentRecord>, StoreError> {
+        let rows = self
+            .run(|connection| {
+                connection
+                    .prepare(
+                        "SELECT record FROM payments WHERE provisional = 1
+                         ORDER BY recorded_at, rowid",
+                    )?
+                    .query_map([], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+        Self::records(rows)
+    }
+
+    async fn set_finality(&self, nonce: &str, finality: Finality) -> Result<(), StoreError> {
+        let Some(mut record) = self.payment(nonce).await? else {
+            return Ok(());
+        };
+        record.finality = finality;
+        self.record_payment(record).await
+    }
+
+    async fn payment(&self, nonce: &str) -> Result<Option<PaymentRecord>, StoreError> {
+        let nonce = nonce.to_string();
+        let row: Option<String> = self
+            .run(move |connection| {
+                connection
+                    .query_row(
+                        "SELECT record FROM payments WHERE nonce = ?1",
+                        params![nonce],
+                        |row| row.get(0),
+                    )
+                    .optional()
+            })
+            .await?;
+        row.map(|row| serde_json::from_str(&row).map_err(backend))
+            .transpose()
+    }
+}
+
+#[async_trait]
+impl UsageStore for SqliteStore {
+    async fn increment(&self, key: &str) -> Result<u64, StoreError> {
+        let key = key.to_string();
+        let count: i64 = self
+            .run(move |connection| {
+                connection.query_row(
+                    "INSERT INTO usage (key, count) VALUES (?1, 1)
+                     ON CONFLICT (key) DO UPDATE SET count = count + 1
+                     RETURNING count",
+                    params![key],
+                    |row| row.get(0),
+                )
+            })
+            .await?;
+        Ok(count as u64)
+    }
+
+    async fn get(&self, key: &str) -> Result<u64, StoreError> {
+        let key = key.to_string();
+        let count: Option<i64> = self
+            .run(move |connection| {
+                connection
+                    .query_row(
+                        "SELECT count FROM usage WHERE key = ?1",
+                        params![key],
+                        |row| row.get(0),
+                    )
+                    .optional()
+            })
+            .await?;
+        Ok(count.unwrap_or(0) as u64)
+    }
+}
+
+#[async_trait]
+impl RevocationStore for SqliteStore {
+    async fn revoke(&self, key: &str, entry: RevocationEntry) -> Result<(), RevocationError> {
+        let key = key.to_string();
+        let expires_at = entry.expires_at.min(i64::MAX as u64) as i64;
+        let json =
+            serde_json::to_string(&entry).map_err(|e| RevocationError::Backend(e.to_string()))?;
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO revocations (key, expires_at, entry) VALUES (?1, ?2, ?3)",
+                params![key, expires_at, json],
+            )
+        })
+        .await
+        .map_err(|e| RevocationError::Backend(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str, now: u64) -> Result<Option<RevocationEntry>, RevocationError> {
+        let key = key.to_string();
+        let now = now.min(i64::MAX as u64) as i64;
+        let row: Option<String> = self
+            .run(move |connection| {
+                connection
+                    .query_row(
+                        "SELECT entry FROM revocations WHERE key = ?1 AND expires_at > ?2",
+                        params![key, now],
+                        |row| row.get(0),
+                    )
+                    .optional()
+            })
+            .await
+            .map_err(|e| RevocationError::Backend(e.to_string()))?;
+        row.map(|row| {
+            serde_json::from_str(&row).map_err(|e| RevocationError::Backend(e.to_string()))
+        })
+        .transpose()
+    }
+
+    async fn purge_expired(&self, now: u64) -> Result<usize, RevocationError> {
+        let now = now.min(i64::MAX as u64) as i64;
+        self.run(move |connection| {
+            connection.execute(
+                "DELETE FROM revocations WHERE expires_at <= ?1",
+                params![now],
+            )
+        })
+        .await
+        .map_err(|e| RevocationError::Backend(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl AuditLog for SqliteStore {
+    async fn append(&self, entry: AuditEntry) -> Result<(), AuditError> {
+        let json = serde_json::to_string(&entry).map_err(|e| AuditError::Backend(e.to_string()))?;
+        self.run(move |connection| {
+            connection.execute(
+                "INSERT INTO audit_log (target, entry) VALUES (?1, ?2)",
+                params![entry.target, json],
+            )
+        })
+        .await
+        .map_err(|e| AuditError::Backend(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn entries_for(&self, target: &str) -> Result<Vec<AuditEntry>, AuditError> {
+        let target = target.to_string();
+        let rows: Vec<String> = self
+            .run(move |connection| {
+                connection
+                    .prepare("SELECT entry FROM audit_log WHERE target = ?1 ORDER BY id")?
+                    .query_map(params![target], |row| row.get(0))?
+                    .collect()
+            })
+            .await
+            .map_err(|e| AuditError::Backend(e.to_string()))?;
+        rows.iter()
+            .map(|row| serde_json::from_str(row).map_err(|e| AuditError::Backend(e.to_string())))
+            .collect()
+    }
+}
@@ -0,0 +1,193 @@
+/// Event streaming module.
+use crate::events::PaymentEvent;
+use crate::store::OutboxEntry;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaSink;
+#[cfg(feature = "nats")]
+pub use nats::NatsSink;
+
+/// Schema of [`EventEnvelope`], bumped on breaking changes of the payload.
+pub const EVENT_SCHEMA: &str = "x402.payment_event.v1";
+
+/// Payload published to streaming platforms for every outbox event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    /// [`EVENT_SCHEMA`] of the payload
+    pub schema: String,
+    /// dedupe key of the event
+    pub id: String,
+    /// see [`PaymentEventKind::name`](crate::events::PaymentEventKind::name)
+    pub event_type: String,
+    pub event: PaymentEvent,
+}
+
+impl EventEnvelope {
+    pub fn new(entry: &OutboxEntry) -> Self {
+        Self {
+            schema: EVENT_SCHEMA.to_string(),
+            id: entry.id.clone(),
+            event_type: entry.event.kind.name().to_string(),
+            event: entry.event.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use super::EventEnvelope;
+    use crate::outbox::{EventSink, SinkError};
+    use crate::store::OutboxEntry;
+    use async_trait::async_trait;
+    use rdkafka::config::ClientConfig;
+    use rdkafka::message::{Header, OwnedHeaders};
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+    use std::time::Duration;
+
+    /// Publishes every event to one Kafka topic as an [`EventEnvelope`],
+    /// keyed by the payer so the events of a payer stay in order. The event
+    /// id and type are also sent as the `event_id` and `event_type` headers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::sync::Arc;
+    /// use x402_sdk::outbox::OutboxDispatcher;
+    /// use x402_sdk::store::InMemoryOutboxStore;
+    /// use x402_sdk::streaming::KafkaSink;
+    ///
+    /// let sink = KafkaSink::new("localhost:9092", "x402.events").unwrap();
+    /// let dispatcher =
+    ///     OutboxDispatcher::new(Arc::new(InMemoryOutboxStore::new()), Arc::new(sink));
+    /// ```
+    pub struct KafkaSink {
+        producer: FutureProducer,
+        topic: String,
+        timeout: Duration,
+    }
+
+    impl KafkaSink {
+        /// idempotent producer for the comma separated `brokers`
+        pub fn new(brokers: &str, topic: &str) -> Result<Self, SinkError> {
+            let mut config = ClientConfig::new();
+            config
+                .set("bootstrap.servers", brokers)
+                .set("enable.idempotence", "true");
+            Self::from_config(&config, topic)
+        }
+
+        /// producer with custom settings, e.g. SASL credentials
+        pub fn from_config(config: &ClientConfig, topic: &str) -> Result<Self, SinkError> {
+            let producer = config
+                .create()
+                .map_err(|e| SinkError::Network(e.to_string()))?;
+            Ok(Self {
+                producer,
+                topic: topic.to_string(),
+                timeout: Duration::from_secs(5),
+            })
+        }
+
+        /// time to wait for a full producer queue, 5 seconds by default
+        pub fn with_timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = timeout;
+            self
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for KafkaSink {
+        async fn deliver(&self, entry: &OutboxEntry) -> Result<(), SinkError> {
+            let envelope = EventEnvelope::new(entry);
+            let payload = envelope.to_json();
+            let headers = OwnedHeaders::new()
+                .insert(Header {
+                    key: "event_id",
+                    value: Some(&envelope.id),
+                })
+                .insert(Header {
+                    key: "event_type",
+                    value: Some(&envelope.event_type),
+                });
+            let record = FutureRecord::to(&self.topic)
+                .key(&entry.event.user_address)
+                .payload(&payload)
+                .headers(headers);
+            self.producer
+                .send(record, self.timeout)
+                .await
+                .map_err(|(e, _)| SinkError::Network(e.to_string()))?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "nats")]
+mod nats {
+    use super::EventEnvelope;
+    use crate::outbox::{EventSink, SinkError};
+    use crate::store::OutboxEntry;
+    use async_nats::HeaderMap;
+    use async_nats::jetstream::{self, Context};
+    use async_trait::async_trait;
+
+    /// Publishes every event as an [`EventEnvelope`] to the JetStream
+    /// subject `<prefix>.<event type>`, e.g. `x402.payment_verified`. The
+    /// event id is sent as `Nats-Msg-Id` so the stream drops redeliveries
+    /// within its duplicate window.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use x402_sdk::streaming::NatsSink;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let sink = NatsSink::connect("nats://localhost:4222", "x402").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub struct NatsSink {
+        jetstream: Context,
+        prefix: String,
+    }
+
+    impl NatsSink {
+        pub async fn connect(url: &str, prefix: &str) -> Result<Self, SinkError> {
+            let client = async_nats::connect(url)
+                .await
+                .map_err(|e| SinkError::Network(e.to_string()))?;
+            Ok(Self::new(jetstream::new(client), prefix))
+        }
+
+        pub fn new(jetstream: Context, prefix: &str) -> Self {
+            Self {
+                jetstream,
+                prefix: prefix.trim_end_matches('.').to_string(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for NatsSink {
+        async fn deliver(&self, entry: &OutboxEntry) -> Result<(), SinkError> {
+            let envelope = EventEnvelope::new(entry);
+            let subject = format!("{}.{}", self.prefix, envelope.event_type);
+            let mut headers = HeaderMap::new();
+            headers.insert("Nats-Msg-Id", envelope.id.as_str());
+            headers.insert("Event-Type", envelope.event_type.as_str());
+            // wait for the stream to acknowledge the message
+            self.jetstream
+                .publish_with_headers(subject, headers, envelope.to_json().into())
+                .await
+                .map_err(|e| SinkError::Network(e.to_string()))?
+                .await
+                .map_err(|e| SinkError::Rejected(e.to_string()))?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,173 @@
+/// Sales tax module.
+use crate::context::RequestContext;
+use crate::pricing::PricingError;
+use crate::resource::Resource;
+use crate::types::PaymentRequest;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// metadata extension carrying the tax line of a challenge
+pub const TAX_EXTENSION: &str = "tax";
+
+/// Tax jurisdiction of the payer, e.g. `DE` or `US-CA`, attached to the
+/// request context by the integration, for instance from billing details or
+/// geolocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaxJurisdiction(pub String);
+
+/// jurisdiction attached to the request context
+pub fn jurisdiction(context: &RequestContext) -> Option<&str> {
+    context
+        .extensions
+        .get::<TaxJurisdiction>()
+        .map(|jurisdiction| jurisdiction.0.as_str())
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaxTreatment {
+    /// added on top of the quoted amount
+    #[default]
+    Exclusive,
+    /// already part of the quoted amount, only annotated
+    Inclusive,
+}
+
+/// Tax component of a quote, in the unit of the quoted amount.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxLine {
+    pub jurisdiction: String,
+    /// e.g. `VAT` or `sales tax`
+    pub name: String,
+    pub rate_bps: u32,
+    pub amount: String,
+    pub treatment: TaxTreatment,
+}
+
+impl TaxLine {
+    /// tax at `rate_bps` on a net amount, or contained in a gross amount for
+    /// inclusive treatment, rounded half up
+    pub fn compute(
+        jurisdiction: &str,
+        name: &str,
+        rate_bps: u32,
+        treatment: TaxTreatment,
+        amount: u128,
+    ) -> Self {
+        let rate = u128::from(rate_bps);
+        let tax = match treatment {
+            TaxTreatment::Exclusive => {
+                amount / 10_000 * rate + (amount % 10_000 * rate + 5_000) / 10_000
+            }
+            TaxTreatment::Inclusive => {
+                let total = 10_000 + rate;
+                let net = amount / total * 10_000 + (amount % total * 10_000 + total / 2) / total;
+                amount - net
+            }
+        };
+        Self {
+            jurisdiction: jurisdiction.to_string(),
+            name: name.to_string(),
+            rate_bps,
+            amount: tax.to_string(),
+            treatment,
+        }
+    }
+
+    /// amount added to the quote, zero for inclusive tax
+    pub fn surcharge(&self) -> u128 {
+        match self.treatment {
+            TaxTreatment::Exclusive => self.amount.parse().unwrap_or(0),
+            TaxTreatment::Inclusive => 0,
+        }
+    }
+}
+
+/// tax line annotated on a challenge
+pub fn tax_line(payment_request: &PaymentRequest) -> Option<TaxLine> {
+    payment_request
+        .metadata
+        .extensions
+        .get(TAX_EXTENSION)
+        .and_then(|tax| serde_json::from_value(tax.clone()).ok())
+}
+
+/// Computes the tax of a quote, see
+/// [`X402::with_tax_calculator`](crate::core::X402::with_tax_calculator).
+///
+/// Returning `None` leaves the quote untaxed.
+#[async_trait]
+pub trait TaxCalculator: Send + Sync {
+    async fn calculate(
+        &self,
+        amount: &str,
+        resource: &Resource,
+        context: &RequestContext,
+    ) -> Result<Option<TaxLine>, PricingError>;
+}
+
+/// Fixed tax rates per jurisdiction.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::tax::{TaxRateTable, TaxTreatment};
+///
+/// let rates = TaxRateTable::new()
+///     .with_rate("DE", "VAT", 1_900)
+///     .with_rate("US-CA", "sales tax", 725)
+///     .with_treatment(TaxTreatment::Exclusive);
+/// assert_eq!(rates.tax_for("DE", 10_000).unwrap().amount, "1900");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TaxRateTable {
+    rates: HashMap<String, (String, u32)>,
+    treatment: TaxTreatment,
+}
+
+impl TaxRateTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// jurisdictions are matched case-insensitively
+    pub fn with_rate(mut self, jurisdiction: &str, name: &str, rate_bps: u32) -> Self {
+        self.rates
+            .insert(jurisdiction.to_uppercase(), (name.to_string(), rate_bps));
+        self
+    }
+
+    pub fn with_treatment(mut self, treatment: TaxTreatment) -> Self {
+        self.treatment = treatment;
+        self
+    }
+
+    pub fn tax_for(&self, jurisdiction: &str, amount: u128) -> Option<TaxLine> {
+        let (name, rate_bps) = self.rates.get(&jurisdiction.to_uppercase())?;
+        Some(TaxLine::compute(
+            jurisdiction,
+            name,
+            *rate_bps,
+            self.treatment,
+            amount,
+        ))
+    }
+}
+
+#[async_trait]
+impl TaxCalculator for TaxRateTable {
+    async fn calculate(
+        &self,
+        amount: &str,
+        _resource: &Resource,
+        context: &RequestContext,
+    ) -> Result<Option<TaxLine>, PricingError> {
+        let Some(jurisdiction) = jurisdiction(context) else {
+            return Ok(None);
+        };
+        let amount: u128 = amount
+            .parse()
+            .map_err(|_| PricingError::InvalidAmount(amount.to_string()))?;
+        Ok(self.tax_for(jurisdiction, amount))
+    }
+}
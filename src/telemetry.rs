@@ -0,0 +1,367 @@
+/// Telemetry module.
+use crate::context::RequestContext;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::future::Future;
+
+#[cfg(feature = "otel")]
+pub use otel::{OtelConfig, OtelError, OtelLayer, OtelProviders, TracedHttp, init_otlp};
+
+/// W3C trace context header propagated from incoming requests to RPC calls.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+tokio::task_local! {
+    static TRACE_PARENT: RefCell<Option<String>>;
+}
+
+/// run a request with the trace context of its `traceparent` header, read
+/// back by [`current_trace_parent`] in the RPC calls made for the request
+pub async fn in_request_trace<F: Future>(context: &RequestContext, request: F) -> F::Output {
+    let trace_parent = context.header(TRACEPARENT_HEADER).map(str::to_string);
+    TRACE_PARENT
+        .scope(RefCell::new(trace_parent), request)
+        .await
+}
+
+/// trace context of the request being handled, `None` outside a request or
+/// for a request without one
+pub fn current_trace_parent() -> Option<String> {
+    TRACE_PARENT
+        .try_with(|trace_parent| trace_parent.borrow().clone())
+        .ok()
+        .flatten()
+}
+
+/// replace the trace context of the request being handled, e.g. with the
+/// one of a child span, ignored outside a request
+pub fn set_trace_parent(trace_parent: Option<String>) {
+    let _ = TRACE_PARENT.try_with(|current| *current.borrow_mut() = trace_parent);
+}
+
+/// short hash of a nonce, identifying a session in telemetry without
+/// exposing the nonce itself
+pub fn nonce_hash(nonce: &str) -> String {
+    Sha256::digest(nonce.as_bytes())
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// order of magnitude of an amount in the smallest unit, e.g. `1e6` for
+/// 1_500_000, keeping attribute cardinality low
+pub fn amount_bucket(amount: &str) -> String {
+    match amount.parse::<u128>() {
+        Ok(0) => "0".to_string(),
+        Ok(amount) => format!("1e{}", amount.ilog10()),
+        Err(_) => "invalid".to_string(),
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use super::{
+        TRACEPARENT_HEADER, amount_bucket, current_trace_parent, nonce_hash, set_trace_parent,
+    };
+    use crate::context::RequestContext;
+    use crate::events::{EventListener, PaymentEvent, PaymentEventKind};
+    use crate::hooks::{EngineHooks, HookDecision};
+    use crate::resource::Resource;
+    use async_trait::async_trait;
+    use ethers::providers::{HttpClientError, JsonRpcClient, JsonRpcError};
+    use opentelemetry::metrics::{Counter, Histogram};
+    use opentelemetry::trace::{Span, TraceContextExt, Tracer};
+    use opentelemetry::{Context, KeyValue, global};
+    use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::runtime;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Instant;
+    use url::Url;
+
+    const INSTRUMENTATION: &str = "x402-sdk";
+
+    #[derive(Debug)]
+    pub enum OtelError {
+        Exporter(String),
+    }
+
+    impl std::fmt::Display for OtelError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::Exporter(msg) => write!(f, "OTLP exporter error: {}", msg),
+            }
+        }
+    }
+
+    impl std::error::Error for OtelError {}
+
+    #[derive(Debug, Clone)]
+    pub struct OtelConfig {
+        /// OTLP gRPC endpoint of the collector
+        pub endpoint: String,
+        pub service_name: String,
+    }
+
+    impl Default for OtelConfig {
+        fn default() -> Self {
+            Self {
+                endpoint: "http://localhost:4317".to_string(),
+                service_name: "x402".to_string(),
+            }
+        }
+    }
+
+    /// Installed providers, shut them down before the process exits so the
+    /// last batches are exported.
+    pub struct OtelProviders {
+        pub tracer_provider: TracerProvider,
+        pub meter_provider: SdkMeterProvider,
+    }
+
+    impl OtelProviders {
+        pub fn shutdown(&self) {
+            let _ = self.tracer_provider.shutdown();
+            let _ = self.meter_provider.shutdown();
+        }
+    }
+
+    /// install global OTLP trace and metric providers and the W3C trace
+    /// context propagator
+    pub fn init_otlp(config: &OtelConfig) -> Result<OtelProviders, OtelError> {
+        let resource = opentelemetry_sdk::Resource::new(vec![KeyValue::new(
+            "service.name",
+            config.service_name.clone(),
+        )]);
+        let span_exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(&config.endpoint)
+            .build()
+            .map_err(|e| OtelError::Exporter(e.to_string()))?;
+        let tracer_provider = TracerProvider::builder()
+            .with_batch_exporter(span_exporter, runtime::Tokio)
+            .with_resource(resource.clone())
+            .build();
+        let metric_exporter = MetricExporter::builder()
+            .with_tonic()
+            .with_endpoint(&config.endpoint)
+            .build()
+            .map_err(|e| OtelError::Exporter(e.to_string()))?;
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(metric_exporter, runtime::Tokio).build())
+            .with_resource(resource)
+            .build();
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        global::set_tracer_provider(tracer_provider.clone());
+        global::set_meter_provider(meter_provider.clone());
+        Ok(OtelProviders {
+            tracer_provider,
+            meter_provider,
+        })
+    }
+
+    fn parent_context(context: &RequestContext) -> Context {
+        global::get_text_map_propagator(|propagator| propagator.extract(&context.headers))
+    }
+
+    fn trace_parent(cx: &Context) -> Option<String> {
+        let mut carrier = HashMap::new();
+        global::get_text_map_propagator(|propagator| propagator.inject_context(cx, &mut carrier));
+        carrier.remove(TRACEPARENT_HEADER)
+    }
+
+    /// Spans and metrics of the engine, registered both as hooks and as an
+    /// event listener.
+    ///
+    /// Every verification gets an `x402.verify` span, child of the trace of
+    /// the incoming request, and RPC calls made while it runs carry its
+    /// trace context. Attributes are `x402.chain`, `x402.nonce_hash`,
+    /// `x402.amount_bucket` and `x402.result`, never the raw nonce or amount.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::sync::Arc;
+    /// use x402_sdk::core::X402;
+    /// use x402_sdk::telemetry::{OtelConfig, OtelLayer, init_otlp};
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let providers = init_otlp(&OtelConfig::default())?;
+    /// let otel = Arc::new(OtelLayer::new());
+    /// let engine = X402::from_default_config()?
+    ///     .with_hooks(otel.clone())
+    ///     .with_event_listener(otel);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub struct OtelLayer {
+        events: Counter<u64>,
+        verify_duration: Histogram<f64>,
+        /// open verification spans by nonce
+        verifications: Mutex<HashMap<String, (Context, Instant)>>,
+    }
+
+    impl Default for OtelLayer {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl OtelLayer {
+        pub fn new() -> Self {
+            let meter = global::meter(INSTRUMENTATION);
+            Self {
+                events: meter
+                    .u64_counter("x402.events")
+                    .with_description("payment lifecycle events")
+                    .build(),
+                verify_duration: meter
+                    .f64_histogram("x402.verify.duration")
+                    .with_unit("s")
+                    .build(),
+                verifications: Mutex::new(HashMap::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl EngineHooks for OtelLayer {
+        async fn before_verify(
+            &self,
+            _user_address: &str,
+            resource: &Resource,
+            context: &RequestContext,
+            payment_nonce: &str,
+        ) -> HookDecision {
+            let parent = parent_context(context);
+            let mut span =
+                global::tracer(INSTRUMENTATION).start_with_context("x402.verify", &parent);
+            span.set_attribute(KeyValue::new("x402.nonce_hash", nonce_hash(payment_nonce)));
+            span.set_attribute(KeyValue::new("x402.resource", resource.canonical()));
+            let cx = parent.with_span(span);
+            set_trace_parent(trace_parent(&cx));
+            self.verifications
+                .lock()
+                .unwrap()
+                .insert(payment_nonce.to_string(), (cx, Instant::now()));
+            HookDecision::Continue
+        }
+    }
+
+    impl EventListener for OtelLayer {
+        fn on_event(&self, event: &PaymentEvent) {
+            let mut attributes = vec![KeyValue::new("x402.result", event.kind.name())];
+            let (nonce, chain, amount) = match &event.kind {
+                PaymentEventKind::ChallengeIssued { payment_request } => (
+                    Some(&payment_request.nonce),
+                    Some(payment_request.chain.chain_type.get_display_name()),
+                    Some(&payment_request.amount),
+                ),
+                PaymentEventKind::PaymentVerified {
+                    nonce,
+                    verification,
+                } => (
+                    Some(nonce),
+                    Some(verification.chain.chain_type.get_display_name()),
+                    Some(&verification.paid_amount),
+                ),
+                PaymentEventKind::VerificationFailed { nonce, .. }
+                | PaymentEventKind::VerificationThrottled { nonce, .. }
+                | PaymentEventKind::SessionLockedOut { nonce, .. } => (Some(nonce), None, None),
+                _ => (None, None, None),
+            };
+            if let Some(chain) = chain {
+                attributes.push(KeyValue::new("x402.chain", chain));
+            }
+            if let Some(amount) = amount {
+                attributes.push(KeyValue::new("x402.amount_bucket", amount_bucket(amount)));
+            }
+            self.events.add(1, &attributes);
+
+            if let PaymentEventKind::ChallengeIssued { .. } = &event.kind {
+                let parent = parent_context(&event.context);
+                let mut span =
+                    global::tracer(INSTRUMENTATION).start_with_context("x402.challenge", &parent);
+                span.set_attributes(attributes.iter().cloned());
+                if let Some(nonce) = nonce {
+                    span.set_attribute(KeyValue::new("x402.nonce_hash", nonce_hash(nonce)));
+                }
+                span.end();
+                return;
+            }
+            let Some(nonce) = nonce else {
+                return;
+            };
+            let Some((cx, started)) = self.verifications.lock().unwrap().remove(nonce) else {
+                return;
+            };
+            self.verify_duration
+                .record(started.elapsed().as_secs_f64(), &attributes);
+            let span = cx.span();
+            span.set_attributes(attributes);
+            span.end();
+        }
+    }
+
+    /// JSON-RPC over HTTP sending the trace context of the request being
+    /// handled as `traceparent`, used by the EVM verifier with `otel`.
+    #[derive(Debug)]
+    pub struct TracedHttp {
+        url: Url,
+        client: reqwest::Client,
+        next_id: AtomicU64,
+    }
+
+    impl TracedHttp {
+        pub fn new(url: Url) -> Self {
+            Self {
+                url,
+                client: reqwest::Client::new(),
+                next_id: AtomicU64::new(1),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl JsonRpcClient for TracedHttp {
+        type Error = HttpClientError;
+
+        async fn request<T, R>(&self, method: &str, params: T) -> Result<R, HttpClientError>
+        where
+            T: std::fmt::Debug + Serialize + Send + Sync,
+            R: DeserializeOwned + Send,
+        {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            let mut request = self.client.post(self.url.clone()).json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params,
+            }));
+            if let Some(trace_parent) = current_trace_parent() {
+                request = request.header(TRACEPARENT_HEADER, trace_parent);
+            }
+            let text = request.send().await?.text().await?;
+            let parse = |err| HttpClientError::SerdeJson {
+                err,
+                text: text.clone(),
+            };
+            let mut response: serde_json::Value = serde_json::from_str(&text).map_err(parse)?;
+            if let Some(error) = response.get_mut("error").map(serde_json::Value::take) {
+                let error: JsonRpcError = serde_json::from_value(error).map_err(parse)?;
+                return Err(HttpClientError::JsonRpcError(error));
+            }
+            let result = response
+                .get_mut("result")
+                .map(serde_json::Value::take)
+                .unwrap_or_default();
+            serde_json::from_value(result).map_err(parse)
+        }
+    }
+}
@@ -0,0 +1,477 @@
+/// Testing utilities module.
+use crate::clock::{Clock, SystemClock};
+use crate::config::{ConfigManager, X402Config};
+use crate::core::X402;
+use crate::rates::NATIVE_TOKEN;
+use crate::types::{
+    AmountTolerance, ChainConfig, ChainType, Currency, ErrorReason, Finality, PaymentRequest,
+    PaymentVerification, TransactionLog,
+};
+use crate::verifier::breaker::CircuitStatus;
+use crate::verifier::{PaymentVerifier, VerificationError, minimum_amount};
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Manually driven clock for deterministic tests.
+///
+/// Clones share the same underlying time, so a handle kept by the test can
+/// advance the clock seen by the engine and its verifiers.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use x402_sdk::clock::Clock;
+/// use x402_sdk::testing::MockClock;
+///
+/// let clock = MockClock::new(1_700_000_000);
+/// let engine_clock: Arc<dyn Clock> = Arc::new(clock.clone());
+/// clock.advance(60);
+/// assert_eq!(engine_clock.now(), 1_700_000_060);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    now: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new(now: u64) -> Self {
+        Self {
+            now: Arc::new(AtomicU64::new(now)),
+        }
+    }
+
+    /// set the current timestamp
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    /// move the clock forward by the given number of seconds
+    pub fn advance(&self, secs: u64) {
+        self.now.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+/// In-memory verifier simulating a payer that has paid a fixed amount.
+///
+/// A payment is reported as paid when the simulated paid amount covers the
+/// amount of the verified request. Clones share state, so a handle kept by
+/// the test can change the paid amount and inspect the requests the engine
+/// asked to verify.
+#[derive(Clone)]
+pub struct MockVerifier {
+    paid_amount: Arc<Mutex<Option<u128>>>,
+    paid_currency: Arc<Mutex<Option<Currency>>>,
+    finality: Arc<Mutex<Finality>>,
+    requests: Arc<Mutex<Vec<PaymentRequest>>>,
+    clock: Arc<dyn Clock>,
+    amount_tolerances: HashMap<String, AmountTolerance>,
+}
+
+impl MockVerifier {
+    /// verifier for which nothing has been paid yet
+    pub fn new() -> Self {
+        Self {
+            paid_amount: Arc::new(Mutex::new(None)),
+            paid_currency: Arc::new(Mutex::new(None)),
+            finality: Arc::new(Mutex::new(Finality::Finalized)),
+            requests: Arc::new(Mutex::new(Vec::new())),
+            clock: Arc::new(SystemClock),
+            amount_tolerances: HashMap::new(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// accept simulated payments short of the amount by the tolerance of
+    /// the requested currency
+    pub fn with_amount_tolerances(
+        mut self,
+        amount_tolerances: HashMap<String, AmountTolerance>,
+    ) -> Self {
+        self.amount_tolerances = amount_tolerances;
+        self
+    }
+
+    /// simulate an on-chain payment of the given amount in base units
+    pub fn set_paid_amount(&self, amount: Option<u128>) {
+        *self.paid_amount.lock().unwrap() = amount;
+    }
+
+    /// simulate the payment being made in another currency than the
+    /// requested one, `None` pays in the requested currency
+    pub fn set_paid_currency(&self, currency: Option<Currency>) {
+        *self.paid_currency.lock().unwrap() = currency;
+    }
+
+    /// finality reported for every transaction, finalized by default
+    pub fn set_finality(&self, finality: Finality) {
+        *self.finality.lock().unwrap() = finality;
+    }
+
+    /// payment requests received for verification, oldest first
+    pub fn verified_requests(&self) -> Vec<PaymentRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Default for MockVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PaymentVerifier for MockVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        self.requests.lock().unwrap().push(payment_request.clone());
+        let required: u128 = payment_request
+            .amount
+            .parse()
+            .map_err(|_| VerificationError::ParseError(payment_request.amount.clone()))?;
+        // any-token amounts are in micro-USD, without a token to look up
+        let required = match &payment_request.currency {
+            Currency::Native => minimum_amount(&self.amount_tolerances, NATIVE_TOKEN, required),
+            Currency::Token { address, .. } => {
+                minimum_amount(&self.amount_tolerances, address, required)
+            }
+            Currency::AnyToken { .. } => required,
+        };
+        let paid = *self.paid_amount.lock().unwrap();
+        let is_paid = paid.is_some_and(|paid| paid >= required);
+        let paid_currency = self
+            .paid_currency
+            .lock()
+            .unwrap()
+            .clone()
+            .or_else(|| payment_request.currency.paid_with(None));
+        let transaction_logs = match paid {
+            Some(paid) => vec![TransactionLog {
+                transaction_hash: format!("0x{:064x}", paid),
+                from: payer_address.to_string(),
+                to: payment_request.recipient.clone(),
+                value: paid.to_string(),
+                block_number: 1,
+                log_index: 0,
+                data: None,
+                explorer_url: None,
+            }],
+            None => Vec::new(),
+        };
+        Ok(PaymentVerification {
+            is_paid,
+            paid_amount: if is_paid {
+                payment_request.amount.clone()
+            } else {
+                "0".to_string()
+            },
+            transaction_hash: transaction_logs
+                .first()
+                .filter(|_| is_paid)
+                .map(|log| log.transaction_hash.clone()),
+            verified_at: self.clock.now(),
+            chain: payment_request.chain.clone(),
+            transaction_logs,
+            explorer_url: None,
+            conversion: None,
+            paid_currency: paid_currency.filter(|_| is_paid),
+            failure_reason: match paid {
+                _ if is_paid => None,
+                Some(_) => Some(ErrorReason::Underpaid),
+                None => Some(ErrorReason::NotFound),
+            },
+            proof: None,
+        }
+        .with_explorer_links())
+    }
+
+    /// the paid amount is attributed to the beneficiary of the request
+    async fn verify_payment_by_reference(
+        &self,
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let payer = payment_request.beneficiary.clone().unwrap_or_default();
+        self.verify_payment(payment_request, &payer).await
+    }
+
+    async fn transaction_finality(
+        &self,
+        _chain: &ChainConfig,
+        _transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        Ok(*self.finality.lock().unwrap())
+    }
+
+    fn supports_chain(&self, _chain_type: &ChainType) -> bool {
+        true
+    }
+}
+
+/// Engine with the given configuration and a [`MockVerifier`] registered for
+/// Ethereum, the chain of the default configuration. The verifier handle
+/// drives the payments the engine sees.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::config::ConfigBuilder;
+/// use x402_sdk::testing::mock_engine;
+///
+/// # async fn example() {
+/// let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+/// verifier.set_paid_amount(Some(5000));
+/// let result = engine
+///     .handle_access_request("0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5", "/premium", None, None)
+///     .await
+///     .unwrap();
+/// assert_eq!(result.http_status, 402);
+/// # }
+/// ```
+pub fn mock_engine(config: X402Config) -> (X402, MockVerifier) {
+    let verifier = MockVerifier::new();
+    let mut engine =
+        X402::new(ConfigManager::from_config(config)).expect("the mock engine config is valid");
+    engine
+        .verifier_registry_mut()
+        .register_verifier(ChainType::ethereum(), Box::new(verifier.clone()));
+    (engine, verifier)
+}
+
+/// Faults injected by a [`FaultInjectingVerifier`] so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultStats {
+    pub calls: u64,
+    /// calls held back by injected latency
+    pub delayed: u64,
+    /// calls failed by an error burst
+    pub errors: u64,
+    /// calls answered with stale chain data
+    pub stale: u64,
+}
+
+struct FaultState {
+    rng: StdRng,
+    /// calls left in the running error burst
+    burst_remaining: u32,
+    stats: FaultStats,
+}
+
+enum Fault {
+    None,
+    Error(VerificationError),
+    Stale,
+}
+
+/// Wraps a verifier and degrades it like unreliable chain infrastructure:
+/// random latency, bursts of RPC errors and stale data from a node lagging
+/// behind the chain head, so the 402 handling of an application can be
+/// exercised before a production incident does it.
+///
+/// Faults are drawn from a seedable generator, a fixed seed replays the same
+/// sequence.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use x402_sdk::testing::{FaultInjectingVerifier, MockVerifier};
+///
+/// let verifier = FaultInjectingVerifier::new(Box::new(MockVerifier::new()))
+///     .with_latency(Duration::from_millis(50), Duration::from_millis(500))
+///     .with_error_bursts(0.05, 3)
+///     .with_stale_data(0.1)
+///     .with_seed(42);
+/// ```
+pub struct FaultInjectingVerifier {
+    inner: Box<dyn PaymentVerifier>,
+    latency: Option<(Duration, Duration)>,
+    error_rate: f64,
+    burst_length: u32,
+    stale_rate: f64,
+    state: Mutex<FaultState>,
+}
+
+impl FaultInjectingVerifier {
+    /// wrapper injecting no faults until configured
+    pub fn new(inner: Box<dyn PaymentVerifier>) -> Self {
+        Self {
+            inner,
+            latency: None,
+            error_rate: 0.0,
+            burst_length: 1,
+            stale_rate: 0.0,
+            state: Mutex::new(FaultState {
+                rng: StdRng::from_os_rng(),
+                burst_remaining: 0,
+                stats: FaultStats::default(),
+            }),
+        }
+    }
+
+    /// delay every call by a random duration between `min` and `max`
+    pub fn with_latency(mut self, min: Duration, max: Duration) -> Self {
+        self.latency = Some((min, max.max(min)));
+        self
+    }
+
+    /// start a burst of `length` failing calls with the given probability
+    /// per call
+    pub fn with_error_bursts(mut self, probability: f64, length: u32) -> Self {
+        self.error_rate = probability.clamp(0.0, 1.0);
+        self.burst_length = length.max(1);
+        self
+    }
+
+    /// answer calls with the given probability as a node that has not seen
+    /// the payment yet
+    pub fn with_stale_data(mut self, probability: f64) -> Self {
+        self.stale_rate = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.state.lock().unwrap().rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn stats(&self) -> FaultStats {
+        self.state.lock().unwrap().stats
+    }
+
+    /// latency and fault of the next call
+    fn draw(&self) -> (Duration, Fault) {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        state.stats.calls += 1;
+        let delay = match self.latency {
+            Some((min, max)) => state.rng.random_range(min..=max),
+            None => Duration::ZERO,
+        };
+        if !delay.is_zero() {
+            state.stats.delayed += 1;
+        }
+        if state.burst_remaining == 0 && state.rng.random_bool(self.error_rate) {
+            state.burst_remaining = self.burst_length;
+        }
+        if state.burst_remaining > 0 {
+            state.burst_remaining -= 1;
+            state.stats.errors += 1;
+            let error = match state.rng.random_range(0..3) {
+                0 => VerificationError::NetworkError("injected fault: connection reset".into()),
+                1 => VerificationError::RpcError("injected fault: 429 Too Many Requests".into()),
+                _ => VerificationError::Timeout,
+            };
+            return (delay, Fault::Error(error));
+        }
+        if state.rng.random_bool(self.stale_rate) {
+            state.stats.stale += 1;
+            return (delay, Fault::Stale);
+        }
+        (delay, Fault::None)
+    }
+
+    async fn inject(&self) -> Fault {
+        let (delay, fault) = self.draw();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        fault
+    }
+
+    /// the verification as seen by a node behind the payment block
+    fn stale(mut verification: PaymentVerification) -> PaymentVerification {
+        verification.is_paid = false;
+        verification.paid_amount = "0".to_string();
+        verification.transaction_hash = None;
+        verification.transaction_logs.clear();
+        verification.explorer_url = None;
+        verification.conversion = None;
+        verification.paid_currency = None;
+        verification.proof = None;
+        verification.failure_reason = Some(ErrorReason::NotFound);
+        verification
+    }
+}
+
+#[async_trait]
+impl PaymentVerifier for FaultInjectingVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let fault = self.inject().await;
+        if let Fault::Error(error) = fault {
+            return Err(error);
+        }
+        let verification = self
+            .inner
+            .verify_payment(payment_request, payer_address)
+            .await?;
+        match fault {
+            Fault::Stale => Ok(Self::stale(verification)),
+            _ => Ok(verification),
+        }
+    }
+
+    async fn verify_payment_by_reference(
+        &self,
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let fault = self.inject().await;
+        if let Fault::Error(error) = fault {
+            return Err(error);
+        }
+        let verification = self
+            .inner
+            .verify_payment_by_reference(payment_request)
+            .await?;
+        match fault {
+            Fault::Stale => Ok(Self::stale(verification)),
+            _ => Ok(verification),
+        }
+    }
+
+    /// stale nodes report transactions provisional
+    async fn transaction_finality(
+        &self,
+        chain: &ChainConfig,
+        transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        match self.inject().await {
+            Fault::Error(error) => Err(error),
+            Fault::Stale => Ok(Finality::Provisional),
+            Fault::None => {
+                self.inner
+                    .transaction_finality(chain, transaction_hash)
+                    .await
+            }
+        }
+    }
+
+    fn supports_chain(&self, chain_type: &ChainType) -> bool {
+        self.inner.supports_chain(chain_type)
+    }
+
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.inner.circuit_status()
+    }
+}
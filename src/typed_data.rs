@@ -0,0 +1,296 @@
+/// EIP-712 typed data module.
+use crate::address::{evm_address, to_hex};
+use crate::relay::TransferAuthorization;
+use ethers::abi::{Token, encode};
+use ethers::types::{H160, H256, Signature, U256};
+use ethers::utils::{hex, keccak256};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypedDataError {
+    InvalidField(String),
+    InvalidSignature(String),
+}
+
+impl std::fmt::Display for TypedDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidField(field) => write!(f, "Invalid typed data field: {}", field),
+            Self::InvalidSignature(msg) => write!(f, "Invalid typed data signature: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TypedDataError {}
+
+/// Struct signed as EIP-712 typed data.
+///
+/// The type string, and so the type hash, is derived from [`FIELDS`], which
+/// must list the members in the order of the contract definition.
+///
+/// [`FIELDS`]: TypedMessage::FIELDS
+pub trait TypedMessage {
+    /// name of the struct type, the `primaryType` of the typed data
+    const PRIMARY_TYPE: &'static str;
+    /// `(name, type)` of every member
+    const FIELDS: &'static [(&'static str, &'static str)];
+
+    /// one ABI word per member, in the order of [`FIELDS`](Self::FIELDS)
+    fn encode_fields(&self) -> Result<Vec<Token>, TypedDataError>;
+
+    /// member values as sent to the wallet
+    fn message(&self) -> Value;
+
+    /// `keccak256` of the type string
+    fn type_hash() -> [u8; 32]
+    where
+        Self: Sized,
+    {
+        keccak256(encode_type(Self::PRIMARY_TYPE, Self::FIELDS))
+    }
+
+    /// `hashStruct` of the message
+    fn struct_hash(&self) -> Result<[u8; 32], TypedDataError>
+    where
+        Self: Sized,
+    {
+        let mut tokens = vec![Token::FixedBytes(Self::type_hash().to_vec())];
+        tokens.extend(self.encode_fields()?);
+        Ok(keccak256(encode(&tokens)))
+    }
+}
+
+/// `Name(type1 name1,type2 name2)`
+pub fn encode_type(name: &str, fields: &[(&str, &str)]) -> String {
+    let members: Vec<String> = fields
+        .iter()
+        .map(|(name, kind)| format!("{} {}", kind, name))
+        .collect();
+    format!("{}({})", name, members.join(","))
+}
+
+/// Signing domain of a token contract.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::typed_data::Eip712Domain;
+/// use x402_sdk::relay::TransferAuthorization;
+///
+/// let domain = Eip712Domain::new(
+///     "USD Coin",
+///     "2",
+///     1,
+///     "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+/// );
+/// let authorization = TransferAuthorization {
+///     token: domain.verifying_contract.clone(),
+///     from: "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5".to_string(),
+///     to: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+///     value: "1000000".to_string(),
+///     valid_after: 0,
+///     valid_before: 1_700_000_600,
+///     nonce: format!("0x{}", "11".repeat(32)),
+///     signature: String::new(),
+/// };
+/// // payload for `eth_signTypedData_v4`
+/// let typed_data = domain.typed_data(&authorization);
+/// assert_eq!(typed_data["primaryType"], "TransferWithAuthorization");
+/// let hash = domain.signing_hash(&authorization).unwrap();
+/// assert_eq!(hash.len(), 32);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    pub name: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub verifying_contract: String,
+}
+
+impl Eip712Domain {
+    pub const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("name", "string"),
+        ("version", "string"),
+        ("chainId", "uint256"),
+        ("verifyingContract", "address"),
+    ];
+
+    pub fn new(name: &str, version: &str, chain_id: u64, verifying_contract: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            chain_id,
+            verifying_contract: verifying_contract.to_string(),
+        }
+    }
+
+    /// `keccak256` of the `EIP712Domain` type string
+    pub fn type_hash() -> [u8; 32] {
+        keccak256(encode_type("EIP712Domain", Self::FIELDS))
+    }
+
+    /// `DOMAIN_SEPARATOR` of the contract
+    pub fn separator(&self) -> Result<[u8; 32], TypedDataError> {
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash().to_vec()),
+            Token::FixedBytes(keccak256(&self.name).to_vec()),
+            Token::FixedBytes(keccak256(&self.version).to_vec()),
+            Token::Uint(U256::from(self.chain_id)),
+            address_token(&self.verifying_contract, "verifyingContract")?,
+        ])))
+    }
+
+    /// digest signed by the wallet, `keccak256(0x1901 || separator || hashStruct)`
+    pub fn signing_hash<M: TypedMessage>(&self, message: &M) -> Result<[u8; 32], TypedDataError> {
+        Ok(keccak256(
+            [
+                [0x19, 0x01].as_slice(),
+                &self.separator()?,
+                &message.struct_hash()?,
+            ]
+            .concat(),
+        ))
+    }
+
+    /// typed data of the message in the JSON layout of `eth_signTypedData_v4`
+    pub fn typed_data<M: TypedMessage>(&self, message: &M) -> Value {
+        let members = |fields: &[(&str, &str)]| -> Value {
+            fields
+                .iter()
+                .map(|(name, kind)| json!({ "name": name, "type": kind }))
+                .collect()
+        };
+        let mut types = Map::new();
+        types.insert("EIP712Domain".to_string(), members(Self::FIELDS));
+        types.insert(M::PRIMARY_TYPE.to_string(), members(M::FIELDS));
+        json!({
+            "types": types,
+            "primaryType": M::PRIMARY_TYPE,
+            "domain": self,
+            "message": message.message(),
+        })
+    }
+
+    /// address that signed the message, as lowercase hex. Signatures are
+    /// 65-byte `r || s || v` hex, `v` may be 0/1 or 27/28.
+    pub fn recover_signer<M: TypedMessage>(
+        &self,
+        message: &M,
+        signature: &str,
+    ) -> Result<String, TypedDataError> {
+        let invalid = |msg: &str| TypedDataError::InvalidSignature(msg.to_string());
+        let bytes = hex::decode(signature.trim().trim_start_matches("0x"))
+            .ok()
+            .filter(|bytes| bytes.len() == 65)
+            .ok_or_else(|| invalid("expected 65 bytes of hex"))?;
+        let mut signature =
+            Signature::try_from(bytes.as_slice()).map_err(|e| invalid(&e.to_string()))?;
+        if signature.v < 27 {
+            signature.v += 27;
+        }
+        let signer = signature
+            .recover(H256::from(self.signing_hash(message)?))
+            .map_err(|e| invalid(&e.to_string()))?;
+        Ok(to_hex(signer))
+    }
+}
+
+/// EIP-2612 `Permit` of an ERC-20 allowance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Permit {
+    pub owner: String,
+    pub spender: String,
+    /// allowance in the smallest unit of the token
+    pub value: String,
+    /// `nonces(owner)` of the token
+    pub nonce: u64,
+    pub deadline: u64,
+}
+
+impl TypedMessage for Permit {
+    const PRIMARY_TYPE: &'static str = "Permit";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("owner", "address"),
+        ("spender", "address"),
+        ("value", "uint256"),
+        ("nonce", "uint256"),
+        ("deadline", "uint256"),
+    ];
+
+    fn encode_fields(&self) -> Result<Vec<Token>, TypedDataError> {
+        Ok(vec![
+            address_token(&self.owner, "owner")?,
+            address_token(&self.spender, "spender")?,
+            uint_token(&self.value, "value")?,
+            Token::Uint(U256::from(self.nonce)),
+            Token::Uint(U256::from(self.deadline)),
+        ])
+    }
+
+    fn message(&self) -> Value {
+        json!({
+            "owner": self.owner,
+            "spender": self.spender,
+            "value": self.value,
+            "nonce": self.nonce.to_string(),
+            "deadline": self.deadline.to_string(),
+        })
+    }
+}
+
+/// EIP-3009 `TransferWithAuthorization`, the signature field of the
+/// authorization is not part of the message.
+impl TypedMessage for TransferAuthorization {
+    const PRIMARY_TYPE: &'static str = "TransferWithAuthorization";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("from", "address"),
+        ("to", "address"),
+        ("value", "uint256"),
+        ("validAfter", "uint256"),
+        ("validBefore", "uint256"),
+        ("nonce", "bytes32"),
+    ];
+
+    fn encode_fields(&self) -> Result<Vec<Token>, TypedDataError> {
+        let nonce = hex::decode(self.nonce.trim_start_matches("0x"))
+            .ok()
+            .filter(|nonce| nonce.len() == 32)
+            .ok_or_else(|| TypedDataError::InvalidField("nonce".to_string()))?;
+        Ok(vec![
+            address_token(&self.from, "from")?,
+            address_token(&self.to, "to")?,
+            uint_token(&self.value, "value")?,
+            Token::Uint(U256::from(self.valid_after)),
+            Token::Uint(U256::from(self.valid_before)),
+            Token::FixedBytes(nonce),
+        ])
+    }
+
+    fn message(&self) -> Value {
+        json!({
+            "from": self.from,
+            "to": self.to,
+            "value": self.value,
+            "validAfter": self.valid_after.to_string(),
+            "validBefore": self.valid_before.to_string(),
+            "nonce": self.nonce,
+        })
+    }
+}
+
+fn address_token(value: &str, field: &str) -> Result<Token, TypedDataError> {
+    evm_address(value)
+        .ok()
+        .and_then(|address| H160::from_str(&address).ok())
+        .map(Token::Address)
+        .ok_or_else(|| TypedDataError::InvalidField(field.to_string()))
+}
+
+fn uint_token(value: &str, field: &str) -> Result<Token, TypedDataError> {
+    U256::from_dec_str(value)
+        .map(Token::Uint)
+        .map_err(|_| TypedDataError::InvalidField(field.to_string()))
+}
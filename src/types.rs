@@ -1,5 +1,8 @@
 /// Type definitions for global use.
+use crate::address::{normalize_address, normalize_transaction_hash};
+use crate::receipt::Receipt;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ChainType {
@@ -123,6 +126,86 @@ impl ChainType {
         }
     }
 
+    /// transaction link template of the main block explorer, `{tx}` is
+    /// replaced by the transaction hash
+    pub fn get_standard_explorer_tx_url(&self) -> Option<String> {
+        let template = match self {
+            ChainType::Evm(evm_chain) => match evm_chain {
+                EvmChain::Ethereum => "https://etherscan.io/tx/{tx}",
+                EvmChain::Polygon => "https://polygonscan.com/tx/{tx}",
+                EvmChain::BinanceSmartChain => "https://bscscan.com/tx/{tx}",
+                EvmChain::Arbitrum => "https://arbiscan.io/tx/{tx}",
+                EvmChain::Optimism => "https://optimistic.etherscan.io/tx/{tx}",
+                EvmChain::Avalanche => "https://snowtrace.io/tx/{tx}",
+                EvmChain::Base => "https://basescan.org/tx/{tx}",
+                EvmChain::Custom(_) => return None,
+            },
+            ChainType::Aptos(aptos_chain) => match aptos_chain {
+                AptosChain::Mainnet => "https://explorer.aptoslabs.com/txn/{tx}?network=mainnet",
+                AptosChain::Testnet => "https://explorer.aptoslabs.com/txn/{tx}?network=testnet",
+                AptosChain::Devnet => "https://explorer.aptoslabs.com/txn/{tx}?network=devnet",
+                AptosChain::Custom(_) => return None,
+            },
+            ChainType::Sui(sui_chain) => match sui_chain {
+                SuiChain::Mainnet => "https://suivision.xyz/txblock/{tx}",
+                SuiChain::Testnet => "https://testnet.suivision.xyz/txblock/{tx}",
+                SuiChain::Devnet => "https://devnet.suivision.xyz/txblock/{tx}",
+                SuiChain::Custom(_) => return None,
+            },
+            ChainType::Solana(solana_chain) => match solana_chain {
+                SolanaChain::Mainnet => "https://solscan.io/tx/{tx}",
+                SolanaChain::Testnet => "https://solscan.io/tx/{tx}?cluster=testnet",
+                SolanaChain::Devnet => "https://solscan.io/tx/{tx}?cluster=devnet",
+                SolanaChain::Custom(_) => return None,
+            },
+            ChainType::Custom(_) => return None,
+        };
+        Some(template.to_string())
+    }
+
+    pub fn get_native_symbol(&self) -> Option<String> {
+        let symbol = match self {
+            ChainType::Evm(evm_chain) => match evm_chain {
+                EvmChain::Polygon => "POL",
+                EvmChain::BinanceSmartChain => "BNB",
+                EvmChain::Avalanche => "AVAX",
+                EvmChain::Custom(_) => return None,
+                _ => "ETH",
+            },
+            ChainType::Aptos(_) => "APT",
+            ChainType::Sui(_) => "SUI",
+            ChainType::Solana(_) => "SOL",
+            ChainType::Custom(_) => return None,
+        };
+        Some(symbol.to_string())
+    }
+
+    pub fn get_native_decimals(&self) -> Option<u8> {
+        match self {
+            ChainType::Evm(_) => Some(18),
+            ChainType::Aptos(_) => Some(8),
+            ChainType::Sui(_) | ChainType::Solana(_) => Some(9),
+            ChainType::Custom(_) => None,
+        }
+    }
+
+    /// average block time in milliseconds
+    pub fn get_block_time_ms(&self) -> Option<u64> {
+        match self {
+            ChainType::Evm(evm_chain) => match evm_chain {
+                EvmChain::Ethereum => Some(12_000),
+                EvmChain::BinanceSmartChain => Some(3_000),
+                EvmChain::Arbitrum => Some(250),
+                EvmChain::Custom(_) => None,
+                _ => Some(2_000),
+            },
+            ChainType::Aptos(_) => Some(250),
+            ChainType::Sui(_) => Some(500),
+            ChainType::Solana(_) => Some(400),
+            ChainType::Custom(_) => None,
+        }
+    }
+
     pub fn is_evm(&self) -> bool {
         matches!(self, ChainType::Evm(_))
     }
@@ -166,11 +249,52 @@ impl ChainType {
     }
 }
 
+/// default number of confirmations a payment needs
+pub const DEFAULT_MIN_CONFIRMATIONS: u64 = 1;
+/// default blocks scanned for a payment on EVM chains
+pub const DEFAULT_EVM_SCAN_WINDOW: u64 = 100;
+/// default transactions scanned for a payment on Solana
+pub const DEFAULT_SOLANA_SCAN_WINDOW: u64 = 50;
+
+/// Chain settings, the optional operational settings fall back to the
+/// standard values of the chain type when unset.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainConfig {
+    #[serde(alias = "chainType")]
     pub chain_type: ChainType,
+    #[serde(alias = "chainId")]
     pub chain_id: String,
+    #[serde(alias = "rpcUrl")]
     pub rpc_url: Option<String>,
+    /// transaction link template, `{tx}` is replaced by the transaction hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "explorerTxUrl")]
+    pub explorer_tx_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "nativeSymbol")]
+    pub native_symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "nativeDecimals")]
+    pub native_decimals: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "blockTimeMs")]
+    pub block_time_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "minConfirmations")]
+    pub min_confirmations: Option<u64>,
+    /// how far back verifiers look for a payment, in blocks on EVM chains
+    /// and in transactions on Solana
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "maxScanWindow")]
+    pub max_scan_window: Option<u64>,
+    /// further RPC providers verifying every payment along with `rpc_url`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[serde(alias = "quorumRpcUrls")]
+    pub quorum_rpc_urls: Vec<String>,
+    /// providers that have to report a payment before it is accepted, all
+    /// of them by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum: Option<usize>,
 }
 
 impl ChainConfig {
@@ -180,29 +304,284 @@ impl ChainConfig {
             chain_type,
             chain_id,
             rpc_url,
+            explorer_tx_url: None,
+            native_symbol: None,
+            native_decimals: None,
+            block_time_ms: None,
+            min_confirmations: None,
+            max_scan_window: None,
+            quorum_rpc_urls: Vec::new(),
+            quorum: None,
         }
     }
 
     pub fn from_chain_type(chain_type: ChainType) -> Self {
         Self::new(chain_type, None)
     }
+
+    pub fn with_explorer_tx_url(mut self, template: &str) -> Self {
+        self.explorer_tx_url = Some(template.to_string());
+        self
+    }
+
+    pub fn with_min_confirmations(mut self, confirmations: u64) -> Self {
+        self.min_confirmations = Some(confirmations);
+        self
+    }
+
+    pub fn with_max_scan_window(mut self, window: u64) -> Self {
+        self.max_scan_window = Some(window);
+        self
+    }
+
+    /// verify payments with the further RPC providers as well and accept
+    /// them once `quorum` providers report them
+    pub fn with_quorum(mut self, rpc_urls: &[&str], quorum: usize) -> Self {
+        self.quorum_rpc_urls = rpc_urls.iter().map(|url| url.to_string()).collect();
+        self.quorum = Some(quorum);
+        self
+    }
+
+    /// explorer link of a transaction
+    pub fn explorer_tx_link(&self, transaction_hash: &str) -> Option<String> {
+        self.explorer_tx_url
+            .clone()
+            .or_else(|| self.chain_type.get_standard_explorer_tx_url())
+            .map(|template| template.replace("{tx}", transaction_hash))
+    }
+
+    pub fn native_symbol(&self) -> Option<String> {
+        self.native_symbol
+            .clone()
+            .or_else(|| self.chain_type.get_native_symbol())
+    }
+
+    pub fn native_decimals(&self) -> Option<u8> {
+        self.native_decimals
+            .or_else(|| self.chain_type.get_native_decimals())
+    }
+
+    pub fn block_time_ms(&self) -> Option<u64> {
+        self.block_time_ms
+            .or_else(|| self.chain_type.get_block_time_ms())
+    }
+
+    pub fn min_confirmations(&self) -> u64 {
+        self.min_confirmations.unwrap_or(DEFAULT_MIN_CONFIRMATIONS)
+    }
+
+    pub fn max_scan_window(&self) -> u64 {
+        self.max_scan_window.unwrap_or(match self.chain_type {
+            ChainType::Solana(_) => DEFAULT_SOLANA_SCAN_WINDOW,
+            _ => DEFAULT_EVM_SCAN_WINDOW,
+        })
+    }
 }
 
+/// Payment asked for by a challenge.
+///
+/// Fields are serialized, and signed, in snake_case. The camelCase names of
+/// other x402 implementations (`maxAmountRequired`, `payTo`) are read as
+/// well, as are those of the nested protocol types, and written with
+/// [`FieldCasing::Camel`](crate::wire::FieldCasing).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentRequest {
+    #[serde(alias = "maxAmountRequired")]
     pub amount: String,
     pub currency: Currency,
+    #[serde(alias = "payTo")]
     pub recipient: String,
     pub chain: ChainConfig,
     pub description: Option<String>,
+    #[serde(alias = "expiresAt")]
     pub expires_at: Option<u64>,
     pub nonce: String,
+    /// address the access is granted to when someone else pays
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beneficiary: Option<String>,
+    /// value a delegated payment has to carry, e.g. in the calldata of an
+    /// EVM transaction, to be matched to the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    #[serde(default, skip_serializing_if = "PaymentMetadata::is_empty")]
+    pub metadata: PaymentMetadata,
+    /// token rates the amount is valued at until the challenge expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<RateQuote>,
+}
+
+impl PaymentRequest {
+    /// USD price of a token pinned in the quote, matched case-insensitively
+    pub fn quoted_rate(&self, token: &str) -> Option<f64> {
+        self.quote
+            .as_ref()
+            .and_then(|quote| quote.rates.get(&token.to_lowercase()))
+            .copied()
+    }
+}
+
+/// Token prices pinned when a [`Currency::AnyToken`] challenge is issued, so
+/// the quoted amount is honored until expiry even if rates move.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateQuote {
+    #[serde(alias = "quotedAt")]
+    pub quoted_at: u64,
+    /// USD price of one whole token, keyed by lowercase token address or
+    /// mint
+    pub rates: BTreeMap<String, f64>,
+}
+
+/// Earlier challenge a reissued one replaces, so clients can tell why the
+/// amount or the rates changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Requote {
+    /// nonce of the replaced challenge
+    pub replaces: String,
+    /// e.g. `expired`
+    pub reason: String,
+    #[serde(alias = "previousAmount")]
+    pub previous_amount: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "previousQuote")]
+    pub previous_quote: Option<RateQuote>,
+}
+
+/// Structured details of a payment for wallets and paywall pages to render.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::types::PaymentMetadata;
+///
+/// let metadata = PaymentMetadata::new()
+///     .with_description("en", "Market data")
+///     .with_description("zh-CN", "市场数据")
+///     .with_extension("plan", serde_json::json!("pro"));
+/// assert_eq!(metadata.description_for("zh-CN"), Some("市场数据"));
+/// assert_eq!(metadata.description_for("en-GB"), Some("Market data"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaymentMetadata {
+    /// resource paid for, e.g. `GET /data?limit=10`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "merchantName")]
+    pub merchant_name: Option<String>,
+    /// descriptions keyed by BCP 47 locale, e.g. `en` or `zh-CN`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub descriptions: BTreeMap<String, String>,
+    /// fields specific to the service, passed through untouched
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, serde_json::Value>,
+}
+
+impl PaymentMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_resource(mut self, resource: &str) -> Self {
+        self.resource = Some(resource.to_string());
+        self
+    }
+
+    pub fn with_merchant_name(mut self, merchant_name: &str) -> Self {
+        self.merchant_name = Some(merchant_name.to_string());
+        self
+    }
+
+    pub fn with_description(mut self, locale: &str, description: &str) -> Self {
+        self.descriptions
+            .insert(locale.to_string(), description.to_string());
+        self
+    }
+
+    pub fn with_extension(mut self, key: &str, value: serde_json::Value) -> Self {
+        self.extensions.insert(key.to_string(), value);
+        self
+    }
+
+    /// description for a locale, falling back to its language, e.g. `en`
+    /// for `en-GB`
+    pub fn description_for(&self, locale: &str) -> Option<&str> {
+        let find = |locale: &str| {
+            self.descriptions
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(locale))
+                .map(|(_, description)| description.as_str())
+        };
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        find(locale).or_else(|| find(language))
+    }
+
+    /// fields of `other` take precedence
+    pub fn merge(mut self, other: PaymentMetadata) -> Self {
+        self.resource = other.resource.or(self.resource);
+        self.merchant_name = other.merchant_name.or(self.merchant_name);
+        self.descriptions.extend(other.descriptions);
+        self.extensions.extend(other.extensions);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// reference of a delegated payment session, the first 16 bytes of the
+/// keccak256 of the nonce as 0x prefixed hex
+pub fn payment_reference(nonce: &str) -> String {
+    let digest = ethers::utils::keccak256(nonce.as_bytes());
+    format!("0x{}", ethers::utils::hex::encode(&digest[..16]))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Currency {
     Native,
-    Token { address: String, decimals: u8 },
+    Token {
+        address: String,
+        decimals: u8,
+    },
+    /// any token worth the request amount, which is in micro-USD, valued by
+    /// the rate provider of the verifier, an empty allowlist accepts any
+    /// token
+    AnyToken {
+        allowlist: Vec<String>,
+    },
+}
+
+impl Currency {
+    /// whether a payment detected in `paid` settles a request in this
+    /// currency, EVM addresses compare case-insensitively, Solana mints do not
+    pub fn accepts(&self, paid: &Currency) -> bool {
+        let same_token = |expected: &str, actual: &str| {
+            expected == actual
+                || (expected.starts_with("0x") && expected.eq_ignore_ascii_case(actual))
+        };
+        match (self, paid) {
+            (Self::Native, Self::Native) => true,
+            (Self::Token { address, .. }, Self::Token { address: paid, .. }) => {
+                same_token(address, paid)
+            }
+            (Self::AnyToken { allowlist }, Self::Token { address: paid, .. }) => {
+                allowlist.is_empty() || allowlist.iter().any(|token| same_token(token, paid))
+            }
+            _ => false,
+        }
+    }
+
+    /// currency a payment of a request in this currency was made in, the
+    /// received token of an any-token payment is taken from its conversion
+    pub fn paid_with(&self, conversion: Option<&TokenConversion>) -> Option<Currency> {
+        match self {
+            Self::AnyToken { .. } => conversion.map(|conversion| Self::Token {
+                address: conversion.token.clone(),
+                decimals: conversion.decimals,
+            }),
+            currency => Some(currency.clone()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -213,13 +592,193 @@ pub struct PaymentVerification {
     pub verified_at: u64,
     pub chain: ChainConfig,
     pub transaction_logs: Vec<TransactionLog>,
+    /// block explorer link of the payment transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
+    /// valuation of the received token for [`Currency::AnyToken`] requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<TokenConversion>,
+    /// asset the payment was detected in, `None` while unpaid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paid_currency: Option<Currency>,
+    /// why the payment was not accepted, `None` once paid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<ErrorReason>,
+    /// evidence of the payment, set by verifiers with proofs enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<PaymentProof>,
+}
+
+/// Evidence for a third party to confirm a payment against the chain
+/// without trusting the verifier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PaymentProof {
+    Evm {
+        block_number: u64,
+        block_hash: String,
+        /// root of the receipt trie of the block, the receipt is its leaf at
+        /// `transaction_index`
+        receipts_root: String,
+        transaction_index: u64,
+        /// receipt as returned by `eth_getTransactionReceipt`
+        receipt: serde_json::Value,
+    },
+    Solana {
+        slot: u64,
+        signature: String,
+        /// commitment level the transaction was read at
+        commitment: String,
+    },
+}
+
+/// Reason a payment was not accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorReason {
+    /// no transfer from the payer to the recipient in the scanned blocks
+    NotFound,
+    /// a transfer of the requested currency was found but is short of the
+    /// amount
+    Underpaid,
+    /// the payer sent a token other than the requested currency
+    WrongToken,
+    /// an operator revoked the payment
+    Revoked,
+    /// the light client could not confirm the payment reported by the RPC
+    /// provider
+    Unconfirmed,
+    /// fewer RPC providers than the quorum report the payment
+    NoQuorum,
+}
+
+impl std::fmt::Display for ErrorReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "payment not found"),
+            Self::Underpaid => write!(f, "payment short of the amount"),
+            Self::WrongToken => write!(f, "payment in the wrong token"),
+            Self::Revoked => write!(f, "payment revoked"),
+            Self::Unconfirmed => write!(f, "payment not confirmed by the light client"),
+            Self::NoQuorum => write!(f, "payment not confirmed by enough RPC providers"),
+        }
+    }
+}
+
+/// Conversion of a received token to the USD amount of a request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenConversion {
+    /// mint or contract address of the received token
+    pub token: String,
+    /// received amount, in the smallest unit of the token
+    pub amount: String,
+    pub decimals: u8,
+    /// USD price of one whole token at verification time
+    pub usd_price: f64,
+    pub usd_value_micros: u128,
+    pub required_usd_micros: u128,
+    pub slippage_bps: u32,
+}
+
+impl PaymentVerification {
+    /// rewrite the transaction hashes and addresses of the payment in the
+    /// canonical form of the chain, values that do not parse are kept
+    pub fn with_canonical_identifiers(mut self) -> Self {
+        let chain_type = &self.chain.chain_type;
+        let hash = |hash: &str| {
+            normalize_transaction_hash(chain_type, hash).unwrap_or_else(|_| hash.to_string())
+        };
+        let address = |address: &str| {
+            normalize_address(chain_type, address).unwrap_or_else(|_| address.to_string())
+        };
+        self.transaction_hash = self.transaction_hash.as_deref().map(hash);
+        for log in &mut self.transaction_logs {
+            log.transaction_hash = hash(&log.transaction_hash);
+            log.from = address(&log.from);
+            log.to = address(&log.to);
+        }
+        self
+    }
+
+    /// refuse a payment detected in an asset `currency` does not accept,
+    /// so a session is never settled by another currency than it was
+    /// issued in
+    pub fn with_accepted_currency(mut self, currency: &Currency) -> Self {
+        if self.is_paid
+            && let Some(paid) = &self.paid_currency
+            && !currency.accepts(paid)
+        {
+            self.is_paid = false;
+            self.paid_amount = "0".to_string();
+            self.failure_reason = Some(ErrorReason::WrongToken);
+        }
+        self
+    }
+
+    /// drop logs seen twice in overlapping scans, keyed by transaction hash
+    /// and log index, and order the rest by block and log index. Beyond
+    /// `max_logs` the oldest logs are dropped, except those of the payment
+    /// transaction, `0` keeps every log.
+    pub fn with_bounded_logs(mut self, max_logs: usize) -> Self {
+        let mut seen = HashSet::new();
+        self.transaction_logs
+            .retain(|log| seen.insert((log.transaction_hash.clone(), log.log_index)));
+        self.transaction_logs
+            .sort_by_key(|log| (log.block_number, log.log_index));
+        if max_logs > 0 && self.transaction_logs.len() > max_logs {
+            let mut excess = self.transaction_logs.len() - max_logs;
+            let payment = self.transaction_hash.as_deref();
+            self.transaction_logs.retain(|log| {
+                if excess == 0 || payment == Some(log.transaction_hash.as_str()) {
+                    return true;
+                }
+                excess -= 1;
+                false
+            });
+        }
+        self
+    }
+
+    /// fill the explorer links of the payment and its transaction logs from
+    /// the explorer template of the chain
+    pub fn with_explorer_links(mut self) -> Self {
+        self.explorer_url = self
+            .transaction_hash
+            .as_deref()
+            .and_then(|hash| self.chain.explorer_tx_link(hash));
+        for log in &mut self.transaction_logs {
+            log.explorer_url = self.chain.explorer_tx_link(&log.transaction_hash);
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct X402ProtocolResponse {
     pub status: u16,
+    #[serde(alias = "paymentRequired")]
     pub payment_required: PaymentRequest,
+    #[serde(alias = "verificationUrl")]
     pub verification_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ChallengeSignature>,
+    /// payment required text in the locale of the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// challenge this one replaces, set when an expired quote is reissued
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requote: Option<Requote>,
+}
+
+/// Merchant signature over the `payment_required` section of a challenge.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChallengeSignature {
+    pub algorithm: String,
+    pub signer: String,
+    pub signature: String,
+    /// id of the signing key in the published key set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -227,7 +786,14 @@ pub struct VerificationResult {
     pub should_serve_content: bool,
     pub http_status: u16,
     pub x402_response: Option<X402ProtocolResponse>,
+    /// verification of the payment, also set with status 402 when a payment
+    /// was refused, see [`PaymentVerification::failure_reason`]
     pub verification: Option<PaymentVerification>,
+    /// signed receipt issued when access was granted for a payment
+    pub receipt: Option<Receipt>,
+    /// seconds the client should wait before retrying, set with status
+    /// 202, 429 and 503
+    pub retry_after: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -238,6 +804,78 @@ pub struct X402Config {
     pub cache_ttl: u64,
 }
 
+/// How far below the required amount a payment may fall and still count,
+/// absorbing the dust left by exchange-rate conversions and decimal
+/// mismatches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmountTolerance {
+    /// in the smallest unit of the currency
+    Absolute(u128),
+    BasisPoints(u32),
+}
+
+impl AmountTolerance {
+    /// lowest amount accepted for the required one
+    pub fn minimum(&self, required: u128) -> u128 {
+        let tolerance = match self {
+            Self::Absolute(units) => *units,
+            Self::BasisPoints(bps) => {
+                let bps = u128::from((*bps).min(10_000));
+                required
+                    .checked_mul(bps)
+                    .map_or(required / 10_000 * bps, |scaled| scaled / 10_000)
+            }
+        };
+        required.saturating_sub(tolerance)
+    }
+}
+
+/// Range a custom amount of a currency has to fall in, catching typos
+/// before a challenge is issued. Both ends are inclusive and in the smallest
+/// unit of the currency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmountBounds {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<u128>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<u128>,
+}
+
+impl AmountBounds {
+    pub fn new(min: u128, max: u128) -> Self {
+        Self {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    pub fn contains(&self, amount: u128) -> bool {
+        self.min.is_none_or(|min| amount >= min) && self.max.is_none_or(|max| amount <= max)
+    }
+}
+
+/// Settlement finality of a recorded payment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Finality {
+    /// seen on chain, may still be reorganized away
+    #[default]
+    Provisional,
+    Finalized,
+    /// failed or dropped by a reorganization
+    Reverted,
+}
+
+impl Finality {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Provisional => "provisional",
+            Self::Finalized => "finalized",
+            Self::Reverted => "reverted",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionLog {
     pub transaction_hash: String,
@@ -247,4 +885,6 @@ pub struct TransactionLog {
     pub block_number: u64,
     pub log_index: u64,
     pub data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
 }
@@ -0,0 +1,49 @@
+/// Payer usage tracking module.
+use crate::clock::{Clock, SystemClock};
+use crate::store::{StoreError, UsageStore};
+use std::sync::Arc;
+
+/// Counts paid requests per payer address and billing period.
+///
+/// The engine records a request every time a payment is verified, pricing
+/// providers such as [`TieredPricing`](crate::pricing::TieredPricing) read the
+/// count of the current period.
+pub struct UsageTracker {
+    store: Arc<dyn UsageStore>,
+    /// length of a billing period, 0 counts over the whole lifetime
+    period_secs: u64,
+    clock: Arc<dyn Clock>,
+}
+
+impl UsageTracker {
+    pub fn new(store: Arc<dyn UsageStore>, period_secs: u64) -> Self {
+        Self {
+            store,
+            period_secs,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn key(&self, payer: &str) -> String {
+        let payer = payer.to_lowercase();
+        match self.period_secs {
+            0 => payer,
+            period => format!("{}:{}", payer, self.clock.now() / period),
+        }
+    }
+
+    /// record a paid request, returns the usage of the current period
+    pub async fn record(&self, payer: &str) -> Result<u64, StoreError> {
+        self.store.increment(&self.key(payer)).await
+    }
+
+    /// paid requests of the payer in the current period
+    pub async fn usage(&self, payer: &str) -> Result<u64, StoreError> {
+        self.store.get(&self.key(payer)).await
+    }
+}
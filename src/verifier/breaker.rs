@@ -0,0 +1,183 @@
+/// Circuit breaker module.
+use crate::clock::Clock;
+use crate::config::CircuitBreakerConfig;
+use crate::types::{ChainConfig, ChainType, Finality, PaymentRequest, PaymentVerification};
+use crate::verifier::{PaymentVerifier, VerificationError};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CircuitState {
+    /// verifications go through
+    Closed,
+    /// verifications fail fast until the given time
+    Open { until: u64 },
+    /// a single probe verification is let through to test the RPC
+    HalfOpen,
+}
+
+/// Health of a chain verifier as seen by its circuit breaker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitStatus {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// how many times the circuit opened since the verifier was created
+    pub times_opened: u64,
+    /// verifications rejected without calling the RPC
+    pub rejected: u64,
+}
+
+struct BreakerState {
+    status: CircuitStatus,
+    /// start of the running probe, a probe that never finished is replaced
+    /// after `open_secs`
+    probe_started: Option<u64>,
+}
+
+/// Wraps a verifier and stops calling it after repeated RPC failures.
+///
+/// After `failure_threshold` consecutive network, RPC or timeout errors the
+/// circuit opens and verifications fail with
+/// [`VerificationError::CircuitOpen`] for `open_secs`. Then one probe is let
+/// through, its success closes the circuit, its failure opens it again.
+pub struct CircuitBreakerVerifier {
+    inner: Box<dyn PaymentVerifier>,
+    config: CircuitBreakerConfig,
+    clock: Arc<dyn Clock>,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreakerVerifier {
+    pub fn new(
+        inner: Box<dyn PaymentVerifier>,
+        config: CircuitBreakerConfig,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        Self {
+            inner,
+            config,
+            clock,
+            state: Mutex::new(BreakerState {
+                status: CircuitStatus {
+                    state: CircuitState::Closed,
+                    consecutive_failures: 0,
+                    times_opened: 0,
+                    rejected: 0,
+                },
+                probe_started: None,
+            }),
+        }
+    }
+
+    pub fn status(&self) -> CircuitStatus {
+        self.state.lock().unwrap().status.clone()
+    }
+
+    /// decide whether a verification may call the RPC
+    fn admit(&self, now: u64) -> Result<(), VerificationError> {
+        let mut state = self.state.lock().unwrap();
+        match state.status.state {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open { until } if now < until => {
+                state.status.rejected += 1;
+                Err(VerificationError::CircuitOpen {
+                    retry_after: until - now,
+                })
+            }
+            _ if state
+                .probe_started
+                .is_some_and(|started| now < started + self.config.open_secs) =>
+            {
+                state.status.rejected += 1;
+                Err(VerificationError::CircuitOpen { retry_after: 1 })
+            }
+            _ => {
+                state.status.state = CircuitState::HalfOpen;
+                state.probe_started = Some(now);
+                Ok(())
+            }
+        }
+    }
+
+    fn record_result<T>(&self, result: &Result<T, VerificationError>) {
+        let rpc_failed = matches!(
+            result,
+            Err(VerificationError::NetworkError(_)
+                | VerificationError::RpcError(_)
+                | VerificationError::Timeout)
+        );
+        self.record(rpc_failed, self.clock.now());
+    }
+
+    fn record(&self, rpc_failed: bool, now: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.probe_started = None;
+        if !rpc_failed {
+            state.status.state = CircuitState::Closed;
+            state.status.consecutive_failures = 0;
+            return;
+        }
+        state.status.consecutive_failures += 1;
+        if state.status.state == CircuitState::HalfOpen
+            || state.status.consecutive_failures >= self.config.failure_threshold
+        {
+            state.status.state = CircuitState::Open {
+                until: now + self.config.open_secs,
+            };
+            state.status.times_opened += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl PaymentVerifier for CircuitBreakerVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        self.admit(self.clock.now())?;
+        let result = self
+            .inner
+            .verify_payment(payment_request, payer_address)
+            .await;
+        self.record_result(&result);
+        result
+    }
+
+    async fn verify_payment_by_reference(
+        &self,
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        self.admit(self.clock.now())?;
+        let result = self
+            .inner
+            .verify_payment_by_reference(payment_request)
+            .await;
+        self.record_result(&result);
+        result
+    }
+
+    async fn transaction_finality(
+        &self,
+        chain: &ChainConfig,
+        transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        self.admit(self.clock.now())?;
+        let result = self
+            .inner
+            .transaction_finality(chain, transaction_hash)
+            .await;
+        self.record_result(&result);
+        result
+    }
+
+    fn supports_chain(&self, chain_type: &ChainType) -> bool {
+        self.inner.supports_chain(chain_type)
+    }
+
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        Some(self.status())
+    }
+}
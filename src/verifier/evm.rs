@@ -1,25 +1,105 @@
 /// Verification module for evm network.
+use crate::address::to_hex;
+use crate::clock::{Clock, SystemClock};
+use crate::rates::{
+    DECIMALS_SELECTOR, NATIVE_TOKEN, RateProvider, request_usd_price, usd_value_micros,
+    within_slippage,
+};
 use crate::types::{
-    ChainType, Currency, EvmChain, PaymentRequest, PaymentVerification, TransactionLog,
+    AmountTolerance, ChainConfig, ChainType, Currency, ErrorReason, EvmChain, Finality,
+    PaymentProof, PaymentRequest, PaymentVerification, TokenConversion, TransactionLog,
 };
-use crate::verifier::{PaymentVerifier, VerificationError};
+use crate::verifier::light_client::{LightClient, light_client_required};
+use crate::verifier::{PaymentVerifier, VerificationError, minimum_amount};
 use async_trait::async_trait;
-use ethers::types::{H256, ValueOrArray};
-use ethers::utils::hex;
+#[cfg(not(feature = "otel"))]
+use ethers::providers::Http;
+use ethers::types::{H256, Log, ValueOrArray};
+use ethers::utils::{hex, keccak256};
 use ethers::{
-    providers::{Http, Middleware, Provider},
-    types::{BlockNumber, Filter, H160, U64, U256},
+    providers::{Middleware, Provider},
+    types::{BlockNumber, Filter, H160, TransactionRequest, U64, U256},
 };
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
+/// blocks after which a transaction counts as final on chains without the
+/// `finalized` block tag
+pub const DEFAULT_EVM_FINALITY_DEPTH: u64 = 64;
+
+/// blocks requested per `eth_getLogs` call, within the range limit of most
+/// RPC providers
+pub const DEFAULT_LOG_PAGE_SIZE: u64 = 2_000;
+
+/// RPC transport, sending the trace context of the request with `otel`
+#[cfg(not(feature = "otel"))]
+type RpcTransport = Http;
+#[cfg(feature = "otel")]
+type RpcTransport = crate::telemetry::TracedHttp;
+
+fn rpc_provider(rpc_url: &str) -> Result<Provider<RpcTransport>, VerificationError> {
+    #[cfg(not(feature = "otel"))]
+    let transport = Http::from_str(rpc_url);
+    #[cfg(feature = "otel")]
+    let transport = url::Url::parse(rpc_url).map(RpcTransport::new);
+    let transport = transport.map_err(|e| {
+        VerificationError::NetworkError(format!("Failed to create provider: {}", e))
+    })?;
+    Ok(Provider::new(transport))
+}
+
+/// Logs of a filter over a block range, fetched a page of blocks at a time
+/// so only one page of RPC results is held in memory.
+struct LogPages {
+    filter: Filter,
+    next_block: U64,
+    to_block: U64,
+    page_size: u64,
+}
+
+impl LogPages {
+    fn new(filter: Filter, from_block: U64, to_block: U64, page_size: u64) -> Self {
+        Self {
+            filter,
+            next_block: from_block,
+            to_block,
+            page_size: page_size.max(1),
+        }
+    }
+
+    async fn next(
+        &mut self,
+        provider: &Provider<RpcTransport>,
+    ) -> Result<Option<Vec<Log>>, VerificationError> {
+        if self.next_block > self.to_block {
+            return Ok(None);
+        }
+        let last_block = self
+            .next_block
+            .saturating_add(U64::from(self.page_size - 1))
+            .min(self.to_block);
+        let filter = self
+            .filter
+            .clone()
+            .from_block(BlockNumber::Number(self.next_block))
+            .to_block(BlockNumber::Number(last_block));
+        let logs = provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| VerificationError::RpcError(format!("Failed to get logs: {}", e)))?;
+        self.next_block = last_block + 1;
+        Ok(Some(logs))
+    }
+}
+
 /// EVM compatible blockchain payment verification module.
 ///
 /// # Examples
 ///
-/// ```rust
-/// use x402::types::{ChainType, EvmChain};
-/// use x402::verifier::evm::EvmVerifier;
+/// ```rust,no_run
+/// use x402_sdk::types::{ChainType, EvmChain};
+/// use x402_sdk::verifier::evm::EvmVerifier;
 ///
 /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
 /// let verifier = EvmVerifier::new(
@@ -31,16 +111,20 @@ use std::sync::Arc;
 /// ```
 ///
 pub struct EvmVerifier {
-    provider: Arc<Provider<Http>>,
+    provider: Arc<Provider<RpcTransport>>,
     chain_type: ChainType,
+    clock: Arc<dyn Clock>,
+    rate_provider: Option<Arc<dyn RateProvider>>,
+    slippage_bps: u32,
+    amount_tolerances: HashMap<String, AmountTolerance>,
+    log_page_size: u64,
+    include_proofs: bool,
+    light_client: Option<Arc<dyn LightClient>>,
 }
 
 impl EvmVerifier {
     pub async fn new(rpc_url: String, chain_type: ChainType) -> Result<Self, VerificationError> {
-        let provider = Provider::<Http>::try_from(&rpc_url).map_err(|e| {
-            VerificationError::NetworkError(format!("Failed to create provider: {}", e))
-        })?;
-        let provider = Arc::new(provider);
+        let provider = Arc::new(rpc_provider(&rpc_url)?);
         // real chain id
         let real_chain_id = provider.get_chainid().await.map_err(|e| {
             VerificationError::NetworkError(format!("Failed to get chain ID: {}", e))
@@ -62,17 +146,82 @@ impl EvmVerifier {
             _ => return Err(VerificationError::ChainNotSupported),
         };
         if real_chain_id.as_u64() != expected_chain_id {
-            return Err(VerificationError::NetworkError(format!(
-                "Chain ID mismatch: expected {}, got {}",
-                expected_chain_id, real_chain_id
-            )));
+            return Err(VerificationError::WrongNetwork {
+                expected: expected_chain_id.to_string(),
+                actual: real_chain_id.to_string(),
+            });
         }
         Ok(Self {
             provider,
             chain_type,
+            clock: Arc::new(SystemClock),
+            rate_provider: None,
+            slippage_bps: 0,
+            amount_tolerances: HashMap::new(),
+            log_page_size: DEFAULT_LOG_PAGE_SIZE,
+            include_proofs: false,
+            light_client: None,
         })
     }
 
+    /// replace the clock used for verification timestamps
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// value any-token payments with the rate provider, accepting payments
+    /// up to `slippage_bps` basis points below the requested USD amount
+    pub fn with_rate_provider(
+        mut self,
+        rate_provider: Arc<dyn RateProvider>,
+        slippage_bps: u32,
+    ) -> Self {
+        self.rate_provider = Some(rate_provider);
+        self.slippage_bps = slippage_bps;
+        self
+    }
+
+    /// accept payments short of the amount by the tolerance of their
+    /// currency, keyed by token address or [`NATIVE_TOKEN`]
+    pub fn with_amount_tolerances(
+        mut self,
+        amount_tolerances: HashMap<String, AmountTolerance>,
+    ) -> Self {
+        self.amount_tolerances = amount_tolerances;
+        self
+    }
+
+    /// blocks per log query, lower it for providers with a smaller range
+    /// limit or for recipients with heavy transfer volume
+    pub fn with_log_page_size(mut self, log_page_size: u64) -> Self {
+        self.log_page_size = log_page_size;
+        self
+    }
+
+    /// attach the receipt and block of the payment transaction to paid
+    /// verifications, at two extra RPC calls per payment
+    pub fn with_proofs(mut self, include_proofs: bool) -> Self {
+        self.include_proofs = include_proofs;
+        self
+    }
+
+    /// recheck payments of requests above their light-client threshold, see
+    /// [`LIGHT_CLIENT_EXTENSION`](crate::verifier::light_client::LIGHT_CLIENT_EXTENSION),
+    /// against consensus-verified data
+    pub fn with_light_client(mut self, light_client: Arc<dyn LightClient>) -> Self {
+        self.light_client = Some(light_client);
+        self
+    }
+
+    fn log_pages(&self, filter: Filter, from_block: U64, to_block: U64) -> LogPages {
+        LogPages::new(filter, from_block, to_block, self.log_page_size)
+    }
+
+    pub fn chain_type(&self) -> &ChainType {
+        &self.chain_type
+    }
+
     async fn verify_payment_internal(
         &self,
         payment_request: &PaymentRequest,
@@ -81,10 +230,23 @@ impl EvmVerifier {
         let payer = Self::parse_address(payer_address)?;
         let recipient = Self::parse_address(&payment_request.recipient)?;
         let required_amount = Self::parse_amount(&payment_request.amount)?;
+        let mut conversion = None;
         let (is_paid, transaction_logs) = match &payment_request.currency {
             Currency::Native => {
-                self.verify_native_payment(payer, recipient, required_amount)
-                    .await?
+                self.verify_native_payment(
+                    payer,
+                    recipient,
+                    required_amount,
+                    &payment_request.chain,
+                )
+                .await?
+            }
+            Currency::AnyToken { allowlist } => {
+                let (is_paid, transaction_logs, converted) = self
+                    .verify_any_token_payment(payer, recipient, payment_request, allowlist)
+                    .await?;
+                conversion = converted;
+                (is_paid, transaction_logs)
             }
             Currency::Token { address, decimals } => {
                 let token_address = Self::parse_address(address)?;
@@ -94,11 +256,24 @@ impl EvmVerifier {
                     token_address,
                     required_amount,
                     *decimals,
+                    &payment_request.chain,
                 )
                 .await?
             }
         };
-        Ok(PaymentVerification {
+        // a transfer from the payer that did not count was short of the amount
+        let failure_reason = if is_paid {
+            None
+        } else if transaction_logs.iter().any(|log| log.from == to_hex(payer)) {
+            Some(ErrorReason::Underpaid)
+        } else {
+            Some(ErrorReason::NotFound)
+        };
+        let paid_currency = payment_request
+            .currency
+            .paid_with(conversion.as_ref())
+            .filter(|_| is_paid);
+        let verification = PaymentVerification {
             is_paid,
             paid_amount: if is_paid {
                 payment_request.amount.clone()
@@ -108,10 +283,115 @@ impl EvmVerifier {
             transaction_hash: transaction_logs
                 .first()
                 .map(|log| log.transaction_hash.clone()),
-            verified_at: Self::current_timestamp(),
+            verified_at: self.clock.now(),
             chain: payment_request.chain.clone(),
             transaction_logs,
-        })
+            explorer_url: None,
+            conversion,
+            paid_currency,
+            failure_reason,
+            proof: None,
+        }
+        .with_canonical_identifiers()
+        .with_explorer_links();
+        let verification = self
+            .confirm_with_light_client(payment_request, verification)
+            .await?;
+        self.with_proof(verification).await
+    }
+
+    /// find a transfer of an allowlisted token from the payer worth the
+    /// micro-USD amount of the request
+    async fn verify_any_token_payment(
+        &self,
+        payer: H160,
+        recipient: H160,
+        payment_request: &PaymentRequest,
+        allowlist: &[String],
+    ) -> Result<(bool, Vec<TransactionLog>, Option<TokenConversion>), VerificationError> {
+        if allowlist.is_empty() {
+            return Err(VerificationError::Error(
+                "any-token payments on EVM need a token allowlist".to_string(),
+            ));
+        }
+        let required: u128 = payment_request.amount.parse().map_err(|_| {
+            VerificationError::ParseError(format!("Invalid USD amount: {}", payment_request.amount))
+        })?;
+        let (from_block, to_block) = self.scan_range(&payment_request.chain).await?;
+        // allowlisted transfers worth less than the request
+        let mut short_transfers = Vec::new();
+        for token in allowlist {
+            let token_address = Self::parse_address(token)?;
+            let filter = Self::create_erc20_transfer_filter(payer, recipient, token_address);
+            let mut pages = self.log_pages(filter, from_block, to_block);
+            // looked up with the first transfer of the token
+            let mut valuation = None;
+            while let Some(logs) = pages.next(&self.provider).await? {
+                if logs.is_empty() {
+                    continue;
+                }
+                let (decimals, usd_price) = match valuation {
+                    Some(valuation) => valuation,
+                    None => {
+                        let decimals = self.token_decimals(token_address).await?;
+                        let usd_price =
+                            request_usd_price(self.rate_provider.as_ref(), payment_request, token)
+                                .await
+                                .map_err(|e| VerificationError::RpcError(e.to_string()))?;
+                        *valuation.insert((decimals, usd_price))
+                    }
+                };
+                for log in logs {
+                    let (Some(tx_hash), Some(data)) = (log.transaction_hash, log.data.get(0..32))
+                    else {
+                        continue;
+                    };
+                    let amount = U256::from_big_endian(data);
+                    let value = usd_value_micros(amount.low_u128(), decimals, usd_price);
+                    let transaction_log = TransactionLog {
+                        transaction_hash: to_hex(tx_hash),
+                        from: to_hex(payer),
+                        to: to_hex(recipient),
+                        value: amount.to_string(),
+                        block_number: log.block_number.unwrap_or_default().as_u64(),
+                        log_index: log.log_index.unwrap_or_default().as_u64(),
+                        data: Some(hex::encode(data)),
+                        explorer_url: None,
+                    };
+                    if amount.bits() > 128 || !within_slippage(value, required, self.slippage_bps) {
+                        short_transfers.push(transaction_log);
+                        continue;
+                    }
+                    let conversion = TokenConversion {
+                        token: token.clone(),
+                        amount: amount.to_string(),
+                        decimals,
+                        usd_price,
+                        usd_value_micros: value,
+                        required_usd_micros: required,
+                        slippage_bps: self.slippage_bps,
+                    };
+                    return Ok((true, vec![transaction_log], Some(conversion)));
+                }
+            }
+        }
+        Ok((false, short_transfers, None))
+    }
+
+    /// `decimals()` of an ERC-20 token
+    async fn token_decimals(&self, token: H160) -> Result<u8, VerificationError> {
+        let request = TransactionRequest::new()
+            .to(token)
+            .data(DECIMALS_SELECTOR.to_vec());
+        let output = self
+            .provider
+            .call(&request.into(), None)
+            .await
+            .map_err(|e| VerificationError::RpcError(format!("Failed to get decimals: {}", e)))?;
+        output
+            .get(..32)
+            .map(|word| U256::from_big_endian(word).low_u32() as u8)
+            .ok_or_else(|| VerificationError::ParseError("Malformed decimals".to_string()))
     }
 
     async fn verify_native_payment(
@@ -119,8 +399,10 @@ impl EvmVerifier {
         payer: H160,
         recipient: H160,
         required_amount: U256,
+        chain: &ChainConfig,
     ) -> Result<(bool, Vec<TransactionLog>), VerificationError> {
-        self.check_recent_transactions(payer, recipient, required_amount)
+        let minimum = self.minimum_amount(NATIVE_TOKEN, required_amount);
+        self.check_recent_transactions(payer, recipient, minimum, chain)
             .await
     }
 
@@ -131,33 +413,41 @@ impl EvmVerifier {
         token_address: H160,
         required_amount: U256,
         decimals: u8,
+        chain: &ChainConfig,
     ) -> Result<(bool, Vec<TransactionLog>), VerificationError> {
-        let adjusted_amount = required_amount * U256::from(10).pow(U256::from(decimals));
+        let adjusted_amount = self.minimum_amount(
+            &to_hex(token_address),
+            required_amount * U256::from(10).pow(U256::from(decimals)),
+        );
         // search ERC20 Transfer events
-        let filter = self
-            .create_erc20_transfer_filter(payer, recipient, token_address)
-            .await?;
-        let logs =
-            self.provider.get_logs(&filter).await.map_err(|e| {
-                VerificationError::RpcError(format!("Failed to get ERC20 logs: {}", e))
-            })?;
+        let (from_block, to_block) = self.scan_range(chain).await?;
+        let filter = Self::create_erc20_transfer_filter(payer, recipient, token_address);
+        let mut pages = self.log_pages(filter, from_block, to_block);
         let mut found_payment = false;
         let mut transaction_logs = Vec::new();
-        for log in logs {
-            if let (Some(tx_hash), Some(data)) = (log.transaction_hash, log.data.get(0..32)) {
-                let amount = U256::from_big_endian(data);
-                let log_entry = TransactionLog {
-                    transaction_hash: format!("{:?}", tx_hash),
-                    from: format!("{:?}", payer),
-                    to: format!("{:?}", recipient),
-                    value: amount.to_string(),
-                    block_number: log.block_number.unwrap_or_default().as_u64(),
-                    log_index: log.log_index.unwrap_or_default().as_u64(),
-                    data: Some(hex::encode(data)),
-                };
-                transaction_logs.push(log_entry);
-                if amount >= adjusted_amount {
-                    found_payment = true;
+        while let Some(logs) = pages.next(&self.provider).await? {
+            for log in logs {
+                // a provider ignoring the address filter must not pass off
+                // another token's transfer as the payment
+                if log.address != token_address {
+                    continue;
+                }
+                if let (Some(tx_hash), Some(data)) = (log.transaction_hash, log.data.get(0..32)) {
+                    let amount = U256::from_big_endian(data);
+                    let log_entry = TransactionLog {
+                        transaction_hash: to_hex(tx_hash),
+                        from: to_hex(payer),
+                        to: to_hex(recipient),
+                        value: amount.to_string(),
+                        block_number: log.block_number.unwrap_or_default().as_u64(),
+                        log_index: log.log_index.unwrap_or_default().as_u64(),
+                        data: Some(hex::encode(data)),
+                        explorer_url: None,
+                    };
+                    transaction_logs.push(log_entry);
+                    if amount >= adjusted_amount {
+                        found_payment = true;
+                    }
                 }
             }
         }
@@ -169,35 +459,27 @@ impl EvmVerifier {
         payer: H160,
         recipient: H160,
         required_amount: U256,
+        chain: &ChainConfig,
     ) -> Result<(bool, Vec<TransactionLog>), VerificationError> {
-        let latest_block = self.provider.get_block_number().await.map_err(|e| {
-            VerificationError::RpcError(format!("Failed to get block number: {}", e))
-        })?;
-        let from_block = latest_block
-            .checked_sub(U64::from(100))
-            .unwrap_or(U64::zero());
-        let filter = Filter::new()
-            .from_block(BlockNumber::Number(from_block))
-            .to_block(BlockNumber::Number(latest_block))
-            .address(recipient);
-        let logs = self
-            .provider
-            .get_logs(&filter)
-            .await
-            .map_err(|e| VerificationError::RpcError(format!("Rpc Error: {:?}", e)))?;
+        let (from_block, to_block) = self.scan_range(chain).await?;
+        let filter = Filter::new().address(recipient);
+        let mut pages = self.log_pages(filter, from_block, to_block);
         let mut found_payment = false;
         let mut transaction_logs = Vec::new();
-        for log in logs {
-            if let Some(tx_hash) = log.transaction_hash {
-                if let Ok(Some(tx)) = self.provider.get_transaction(tx_hash).await {
+        while let Some(logs) = pages.next(&self.provider).await? {
+            for log in logs {
+                if let Some(tx_hash) = log.transaction_hash
+                    && let Ok(Some(tx)) = self.provider.get_transaction(tx_hash).await
+                {
                     let log_entry = TransactionLog {
-                        transaction_hash: format!("{:?}", tx_hash),
-                        from: format!("{:?}", tx.from),
-                        to: format!("{:?}", tx.to.unwrap_or_default()),
+                        transaction_hash: to_hex(tx_hash),
+                        from: to_hex(tx.from),
+                        to: to_hex(tx.to.unwrap_or_default()),
                         value: tx.value.to_string(),
                         block_number: log.block_number.unwrap_or_default().as_u64(),
                         log_index: log.log_index.unwrap_or_default().as_u64(),
                         data: None,
+                        explorer_url: None,
                     };
                     transaction_logs.push(log_entry);
                     if tx.from == payer && tx.value >= required_amount {
@@ -209,26 +491,278 @@ impl EvmVerifier {
         Ok((found_payment, transaction_logs))
     }
 
-    async fn create_erc20_transfer_filter(
+    /// find a payment from any payer whose calldata carries the request
+    /// reference, native transfers carry it as the whole calldata and token
+    /// transfers append it to the `transfer` call
+    async fn verify_referenced_payment(
         &self,
-        from: H160,
-        to: H160,
-        token_address: H160,
-    ) -> Result<Filter, VerificationError> {
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let reference = payment_request
+            .reference
+            .as_deref()
+            .ok_or_else(|| VerificationError::Error("request has no reference".to_string()))?;
+        let reference = hex::decode(reference.trim_start_matches("0x"))
+            .map_err(|e| VerificationError::ParseError(format!("Invalid reference: {}", e)))?;
+        let recipient = Self::parse_address(&payment_request.recipient)?;
+        let required_amount = Self::parse_amount(&payment_request.amount)?;
+        let (from_block, to_block) = self.scan_range(&payment_request.chain).await?;
+        let minimum = self.minimum_amount(NATIVE_TOKEN, required_amount);
+        let mut transaction_logs = Vec::new();
+        let mut is_paid = false;
+        match &payment_request.currency {
+            Currency::Native => {
+                let mut block_number = to_block;
+                while block_number >= from_block && !is_paid {
+                    let block = self
+                        .provider
+                        .get_block_with_txs(block_number)
+                        .await
+                        .map_err(|e| {
+                            VerificationError::RpcError(format!("Failed to get block: {}", e))
+                        })?;
+                    for tx in block.map(|block| block.transactions).unwrap_or_default() {
+                        if tx.to == Some(recipient)
+                            && tx.input.as_ref() == reference.as_slice()
+                            && tx.value >= minimum
+                        {
+                            transaction_logs.push(TransactionLog {
+                                transaction_hash: to_hex(tx.hash),
+                                from: to_hex(tx.from),
+                                to: to_hex(recipient),
+                                value: tx.value.to_string(),
+                                block_number: block_number.as_u64(),
+                                log_index: 0,
+                                data: Some(hex::encode(&reference)),
+                                explorer_url: None,
+                            });
+                            is_paid = true;
+                            break;
+                        }
+                    }
+                    if block_number.is_zero() {
+                        break;
+                    }
+                    block_number -= U64::one();
+                }
+            }
+            Currency::Token { address, decimals } => {
+                let token_address = Self::parse_address(address)?;
+                let adjusted_amount = self.minimum_amount(
+                    &to_hex(token_address),
+                    required_amount * U256::from(10).pow(U256::from(*decimals)),
+                );
+                let filter = Filter::new()
+                    .address(token_address)
+                    .event("Transfer(address,address,uint256)")
+                    .topic2(ValueOrArray::Value(H256::from(recipient)));
+                let mut pages = self.log_pages(filter, from_block, to_block);
+                while !is_paid && let Some(logs) = pages.next(&self.provider).await? {
+                    for log in logs {
+                        let (Some(tx_hash), Some(data)) =
+                            (log.transaction_hash, log.data.get(0..32))
+                        else {
+                            continue;
+                        };
+                        if log.address != token_address {
+                            continue;
+                        }
+                        let amount = U256::from_big_endian(data);
+                        if amount < adjusted_amount {
+                            continue;
+                        }
+                        if let Ok(Some(tx)) = self.provider.get_transaction(tx_hash).await
+                            && tx.input.as_ref().ends_with(&reference)
+                        {
+                            transaction_logs.push(TransactionLog {
+                                transaction_hash: to_hex(tx_hash),
+                                from: to_hex(tx.from),
+                                to: to_hex(recipient),
+                                value: amount.to_string(),
+                                block_number: log.block_number.unwrap_or_default().as_u64(),
+                                log_index: log.log_index.unwrap_or_default().as_u64(),
+                                data: Some(hex::encode(data)),
+                                explorer_url: None,
+                            });
+                            is_paid = true;
+                            break;
+                        }
+                    }
+                }
+            }
+            Currency::AnyToken { .. } => {
+                return Err(VerificationError::Error(
+                    "any-token payments are not supported on EVM".to_string(),
+                ));
+            }
+        }
+        let verification = PaymentVerification {
+            is_paid,
+            paid_amount: if is_paid {
+                payment_request.amount.clone()
+            } else {
+                "0".to_string()
+            },
+            transaction_hash: transaction_logs
+                .first()
+                .map(|log| log.transaction_hash.clone()),
+            verified_at: self.clock.now(),
+            chain: payment_request.chain.clone(),
+            transaction_logs,
+            explorer_url: None,
+            conversion: None,
+            paid_currency: payment_request.currency.paid_with(None).filter(|_| is_paid),
+            failure_reason: (!is_paid).then_some(ErrorReason::NotFound),
+            proof: None,
+        }
+        .with_canonical_identifiers()
+        .with_explorer_links();
+        let verification = self
+            .confirm_with_light_client(payment_request, verification)
+            .await?;
+        self.with_proof(verification).await
+    }
+
+    /// refuse a paid verification above the light-client threshold unless
+    /// the light client confirms its payment log
+    async fn confirm_with_light_client(
+        &self,
+        payment_request: &PaymentRequest,
+        mut verification: PaymentVerification,
+    ) -> Result<PaymentVerification, VerificationError> {
+        if !verification.is_paid || !light_client_required(payment_request) {
+            return Ok(verification);
+        }
+        let light_client = self.light_client.as_ref().ok_or_else(|| {
+            VerificationError::Error("no light client for light-client verification".to_string())
+        })?;
+        let token = match &payment_request.currency {
+            Currency::Native => None,
+            Currency::Token { address, .. } => Some(Self::parse_address(address)?),
+            Currency::AnyToken { .. } => verification
+                .conversion
+                .as_ref()
+                .map(|conversion| Self::parse_address(&conversion.token))
+                .transpose()?,
+        };
+        let recipient = Self::parse_address(&payment_request.recipient)?;
+        let payment_log = verification
+            .transaction_logs
+            .iter()
+            .find(|log| verification.transaction_hash.as_ref() == Some(&log.transaction_hash));
+        let confirmed = match payment_log {
+            Some(log) => Self::confirms(light_client.as_ref(), log, recipient, token).await?,
+            None => false,
+        };
+        if !confirmed {
+            verification.is_paid = false;
+            verification.paid_amount = "0".to_string();
+            verification.failure_reason = Some(ErrorReason::Unconfirmed);
+        }
+        Ok(verification)
+    }
+
+    /// whether the verified receipt, and the verified transaction for native
+    /// payments, carry the transfer of the payment log
+    async fn confirms(
+        light_client: &dyn LightClient,
+        log: &TransactionLog,
+        recipient: H160,
+        token: Option<H160>,
+    ) -> Result<bool, VerificationError> {
+        let hash = H256::from_str(&log.transaction_hash)
+            .map_err(|_| VerificationError::ParseError(log.transaction_hash.clone()))?;
+        let Some(receipt) = light_client.verified_receipt(hash).await? else {
+            return Ok(false);
+        };
+        if receipt.status != Some(1.into())
+            || receipt.block_number.map(|number| number.as_u64()) != Some(log.block_number)
+        {
+            return Ok(false);
+        }
+        let Some(token) = token else {
+            let Some(transaction) = light_client.verified_transaction(hash).await? else {
+                return Ok(false);
+            };
+            return Ok(transaction.to == Some(recipient)
+                && to_hex(transaction.from) == log.from
+                && transaction.value.to_string() == log.value);
+        };
+        let transfer = H256::from(keccak256("Transfer(address,address,uint256)"));
+        Ok(receipt.logs.iter().any(|receipt_log| {
+            receipt_log.address == token
+                && receipt_log.topics.first() == Some(&transfer)
+                && receipt_log.topics.get(2) == Some(&H256::from(recipient))
+                && receipt_log
+                    .data
+                    .get(0..32)
+                    .is_some_and(|data| U256::from_big_endian(data).to_string() == log.value)
+        }))
+    }
+
+    async fn with_proof(
+        &self,
+        mut verification: PaymentVerification,
+    ) -> Result<PaymentVerification, VerificationError> {
+        if self.include_proofs
+            && verification.is_paid
+            && let Some(transaction_hash) = &verification.transaction_hash
+        {
+            verification.proof = Some(self.payment_proof(transaction_hash).await?);
+        }
+        Ok(verification)
+    }
+
+    /// receipt of the transaction and the receipt root of its block
+    async fn payment_proof(
+        &self,
+        transaction_hash: &str,
+    ) -> Result<PaymentProof, VerificationError> {
+        let hash = H256::from_str(transaction_hash)
+            .map_err(|_| VerificationError::ParseError(transaction_hash.to_string()))?;
+        let receipt = self
+            .provider
+            .get_transaction_receipt(hash)
+            .await
+            .map_err(|e| VerificationError::RpcError(format!("Failed to get receipt: {}", e)))?
+            .ok_or(VerificationError::TransactionNotFound)?;
+        let block_hash = receipt
+            .block_hash
+            .ok_or(VerificationError::TransactionNotFound)?;
+        let block = self
+            .provider
+            .get_block(block_hash)
+            .await
+            .map_err(|e| VerificationError::RpcError(format!("Failed to get block: {}", e)))?
+            .ok_or(VerificationError::TransactionNotFound)?;
+        Ok(PaymentProof::Evm {
+            block_number: receipt.block_number.unwrap_or_default().as_u64(),
+            block_hash: to_hex(block_hash),
+            receipts_root: to_hex(block.receipts_root),
+            transaction_index: receipt.transaction_index.as_u64(),
+            receipt: serde_json::to_value(&receipt)
+                .map_err(|e| VerificationError::ParseError(e.to_string()))?,
+        })
+    }
+
+    /// blocks to scan for a payment, the newest block is the latest one with
+    /// the configured number of confirmations
+    async fn scan_range(&self, chain: &ChainConfig) -> Result<(U64, U64), VerificationError> {
         let latest_block = self.provider.get_block_number().await.map_err(|e| {
-            VerificationError::RpcError(format!("Failed to get block number: {:?}", e))
+            VerificationError::RpcError(format!("Failed to get block number: {}", e))
         })?;
-        let from_block = latest_block
-            .checked_sub(U64::from(100))
-            .unwrap_or(U64::zero());
-        let filter = Filter::new()
-            .from_block(BlockNumber::Number(from_block))
-            .to_block(BlockNumber::Number(latest_block))
+        let confirmations = chain.min_confirmations().saturating_sub(1);
+        let to_block = latest_block.saturating_sub(U64::from(confirmations));
+        let from_block = to_block.saturating_sub(U64::from(chain.max_scan_window()));
+        Ok((from_block, to_block))
+    }
+
+    fn create_erc20_transfer_filter(from: H160, to: H160, token_address: H160) -> Filter {
+        Filter::new()
             .address(token_address)
             .event("Transfer(address,address,uint256)")
             .topic1(ValueOrArray::Value(H256::from(from)))
-            .topic2(ValueOrArray::Value(H256::from(to)));
-        Ok(filter)
+            .topic2(ValueOrArray::Value(H256::from(to)))
     }
 
     /// parse address
@@ -236,19 +770,24 @@ impl EvmVerifier {
         H160::from_str(address).map_err(|_| VerificationError::InvalidAddress)
     }
 
+    /// lowest accepted amount in base units, amounts beyond 128 bits are
+    /// compared exactly
+    fn minimum_amount(&self, token: &str, required: U256) -> U256 {
+        if required.bits() > 128 {
+            return required;
+        }
+        U256::from(minimum_amount(
+            &self.amount_tolerances,
+            token,
+            required.as_u128(),
+        ))
+    }
+
     /// parse amount
     fn parse_amount(amount: &str) -> Result<U256, VerificationError> {
         U256::from_dec_str(amount)
             .map_err(|e| VerificationError::ParseError(format!("Parse Error: {:?}", e)))
     }
-
-    /// current timestamp
-    fn current_timestamp() -> u64 {
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-    }
 }
 
 #[async_trait]
@@ -262,7 +801,63 @@ impl PaymentVerifier for EvmVerifier {
             .await
     }
 
+    async fn verify_payment_by_reference(
+        &self,
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        self.verify_referenced_payment(payment_request).await
+    }
+
+    /// final once its block is at or below the `finalized` tag, reverted
+    /// when it failed or a reorganization dropped it
+    async fn transaction_finality(
+        &self,
+        _chain: &ChainConfig,
+        transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        let hash = H256::from_str(transaction_hash)
+            .map_err(|_| VerificationError::ParseError(transaction_hash.to_string()))?;
+        let rpc = |e: ethers::providers::ProviderError| VerificationError::RpcError(e.to_string());
+        let Some(receipt) = self
+            .provider
+            .get_transaction_receipt(hash)
+            .await
+            .map_err(rpc)?
+        else {
+            // back in the mempool after a reorganization, or dropped
+            let pending = self.provider.get_transaction(hash).await.map_err(rpc)?;
+            return Ok(if pending.is_some() {
+                Finality::Provisional
+            } else {
+                Finality::Reverted
+            });
+        };
+        if receipt.status != Some(1.into()) {
+            return Ok(Finality::Reverted);
+        }
+        let Some(block_number) = receipt.block_number else {
+            return Ok(Finality::Provisional);
+        };
+        let finalized = match self.provider.get_block(BlockNumber::Finalized).await {
+            Ok(Some(block)) => block.number.unwrap_or_default(),
+            _ => self
+                .provider
+                .get_block_number()
+                .await
+                .map_err(rpc)?
+                .saturating_sub(DEFAULT_EVM_FINALITY_DEPTH.into()),
+        };
+        Ok(if block_number <= finalized {
+            Finality::Finalized
+        } else {
+            Finality::Provisional
+        })
+    }
+
+    /// the chain of the RPC, also when named by its chain id, e.g.
+    /// `EvmChain::Custom("137")` for Polygon
     fn supports_chain(&self, chain_type: &ChainType) -> bool {
         matches!(chain_type, ChainType::Evm(_))
+            && chain_type.get_standard_chain_id() == self.chain_type.get_standard_chain_id()
     }
 }
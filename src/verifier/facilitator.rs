@@ -0,0 +1,145 @@
+/// Facilitator verifier module.
+use crate::types::{ChainType, PaymentRequest, PaymentVerification};
+use crate::verifier::{PaymentVerifier, VerificationError, VerifierRegistry};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// path of the verification endpoint under the facilitator URL
+pub const VERIFY_PATH: &str = "/verify";
+
+/// Body posted to the facilitator, `payer_address` is `None` for a payment
+/// matched by its reference.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacilitatorRequest {
+    pub payment_request: PaymentRequest,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payer_address: Option<String>,
+}
+
+impl FacilitatorRequest {
+    /// Verifies the request with the verifiers of a facilitator, which
+    /// answers with the verification as JSON, or with the
+    /// [`VerificationError`] as JSON and a non-2xx status.
+    pub async fn verify(
+        &self,
+        registry: &VerifierRegistry,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let verifier = registry
+            .get_verifier(&self.payment_request.chain.chain_type)
+            .ok_or(VerificationError::ChainNotSupported)?;
+        match &self.payer_address {
+            Some(payer_address) => {
+                verifier
+                    .verify_payment(&self.payment_request, payer_address)
+                    .await
+            }
+            None => {
+                verifier
+                    .verify_payment_by_reference(&self.payment_request)
+                    .await
+            }
+        }
+    }
+}
+
+/// Verifies payments through a remote facilitator instead of the chain RPC,
+/// for runtimes without direct RPC access such as Cloudflare Workers or
+/// Fastly Compute.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::core::X402;
+/// use x402_sdk::types::ChainType;
+/// use x402_sdk::verifier::facilitator::FacilitatorVerifier;
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut engine = X402::from_default_config()?;
+/// engine.verifier_registry_mut().register_verifier(
+///     ChainType::ethereum(),
+///     Box::new(FacilitatorVerifier::new("https://facilitator.example.org")),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct FacilitatorVerifier {
+    url: String,
+    client: reqwest::Client,
+    chains: Vec<ChainType>,
+}
+
+impl FacilitatorVerifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+            chains: Vec::new(),
+        }
+    }
+
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// chains the facilitator verifies, every chain when none are given
+    pub fn with_chains(mut self, chains: Vec<ChainType>) -> Self {
+        self.chains = chains;
+        self
+    }
+
+    async fn verify(
+        &self,
+        request: &FacilitatorRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let response = self
+            .client
+            .post(format!("{}{}", self.url, VERIFY_PATH))
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| VerificationError::NetworkError(e.to_string()))?;
+        let status = response.status();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| VerificationError::NetworkError(e.to_string()))?;
+        if status.is_success() {
+            return serde_json::from_slice(&body)
+                .map_err(|e| VerificationError::ParseError(e.to_string()));
+        }
+        Err(serde_json::from_slice(&body).unwrap_or_else(|_| {
+            VerificationError::NetworkError(format!("facilitator answered {}", status))
+        }))
+    }
+}
+
+#[async_trait]
+impl PaymentVerifier for FacilitatorVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        self.verify(&FacilitatorRequest {
+            payment_request: payment_request.clone(),
+            payer_address: Some(payer_address.to_string()),
+        })
+        .await
+    }
+
+    fn supports_chain(&self, chain_type: &ChainType) -> bool {
+        self.chains.is_empty() || self.chains.contains(chain_type)
+    }
+
+    async fn verify_payment_by_reference(
+        &self,
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        self.verify(&FacilitatorRequest {
+            payment_request: payment_request.clone(),
+            payer_address: None,
+        })
+        .await
+    }
+}
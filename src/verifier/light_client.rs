@@ -0,0 +1,93 @@
+/// Light-client verification module.
+use crate::types::PaymentRequest;
+use crate::verifier::VerificationError;
+use async_trait::async_trait;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::{H256, Transaction, TransactionReceipt};
+
+/// Metadata extension of a request holding the amount from which its
+/// payment is checked against a light client, in the units of the request.
+pub const LIGHT_CLIENT_EXTENSION: &str = "light_client_threshold";
+
+/// Source of transactions and receipts checked against consensus-verified
+/// block headers instead of taken on trust from an RPC provider.
+#[async_trait]
+pub trait LightClient: Send + Sync {
+    /// receipt of the transaction, `None` when it is not in a verified block
+    async fn verified_receipt(
+        &self,
+        transaction_hash: H256,
+    ) -> Result<Option<TransactionReceipt>, VerificationError>;
+
+    /// transaction, `None` when it is not in a verified block
+    async fn verified_transaction(
+        &self,
+        transaction_hash: H256,
+    ) -> Result<Option<Transaction>, VerificationError>;
+}
+
+/// Light client backed by the JSON-RPC endpoint of a local Helios node,
+/// which proves every response against the consensus it follows.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use x402_sdk::verifier::light_client::HeliosClient;
+///
+/// let light_client = Arc::new(HeliosClient::new("http://127.0.0.1:8545").unwrap());
+/// ```
+pub struct HeliosClient {
+    provider: Provider<Http>,
+}
+
+impl HeliosClient {
+    pub fn new(rpc_url: &str) -> Result<Self, VerificationError> {
+        let provider = Provider::<Http>::try_from(rpc_url).map_err(|e| {
+            VerificationError::NetworkError(format!("Failed to create provider: {}", e))
+        })?;
+        Ok(Self { provider })
+    }
+}
+
+#[async_trait]
+impl LightClient for HeliosClient {
+    async fn verified_receipt(
+        &self,
+        transaction_hash: H256,
+    ) -> Result<Option<TransactionReceipt>, VerificationError> {
+        self.provider
+            .get_transaction_receipt(transaction_hash)
+            .await
+            .map_err(|e| VerificationError::RpcError(format!("Light client: {}", e)))
+    }
+
+    async fn verified_transaction(
+        &self,
+        transaction_hash: H256,
+    ) -> Result<Option<Transaction>, VerificationError> {
+        self.provider
+            .get_transaction(transaction_hash)
+            .await
+            .map_err(|e| VerificationError::RpcError(format!("Light client: {}", e)))
+    }
+}
+
+/// whether the amount of the request reaches its light-client threshold, a
+/// threshold that does not parse always applies
+pub fn light_client_required(payment_request: &PaymentRequest) -> bool {
+    let Some(threshold) = payment_request
+        .metadata
+        .extensions
+        .get(LIGHT_CLIENT_EXTENSION)
+    else {
+        return false;
+    };
+    let threshold = threshold
+        .as_str()
+        .and_then(|value| value.parse::<u128>().ok());
+    match (threshold, payment_request.amount.parse::<u128>()) {
+        (Some(threshold), Ok(amount)) => amount >= threshold,
+        _ => true,
+    }
+}
@@ -1,11 +1,23 @@
-use crate::types::{ChainType, PaymentRequest, PaymentVerification};
+use crate::types::{
+    AmountTolerance, ChainConfig, ChainType, Finality, PaymentRequest, PaymentVerification,
+};
+use crate::verifier::breaker::CircuitStatus;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub mod breaker;
 pub mod evm;
+pub mod facilitator;
+pub mod light_client;
+pub mod network;
+pub mod pool;
+pub mod quorum;
+#[cfg(feature = "native")]
 pub mod solana;
+pub mod units;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VerificationError {
     NetworkError(String),
     InvalidAddress,
@@ -16,6 +28,20 @@ pub enum VerificationError {
     InvalidCurrency,
     Timeout,
     ParseError(String),
+    /// the chain RPC is failing, retry after the given seconds
+    CircuitOpen {
+        retry_after: u64,
+    },
+    /// the verification queue of the chain is full, retry after the given
+    /// seconds
+    Saturated {
+        retry_after: u64,
+    },
+    /// the RPC serves another network than the chain it is registered for
+    WrongNetwork {
+        expected: String,
+        actual: String,
+    },
     Error(String),
 }
 
@@ -31,6 +57,17 @@ impl std::fmt::Display for VerificationError {
             Self::InvalidCurrency => write!(f, "Invalid currency"),
             Self::Timeout => write!(f, "Verification timeout"),
             Self::ParseError(msg) => write!(f, "Parse error: {}", msg),
+            Self::CircuitOpen { retry_after } => {
+                write!(f, "Chain RPC unavailable, retry after {}s", retry_after)
+            }
+            Self::Saturated { retry_after } => {
+                write!(f, "Verification queue full, retry after {}s", retry_after)
+            }
+            Self::WrongNetwork { expected, actual } => write!(
+                f,
+                "RPC serves the wrong network: expected {}, got {}",
+                expected, actual
+            ),
             Self::Error(msg) => write!(f, "Error: {}", msg),
         }
     }
@@ -38,6 +75,23 @@ impl std::fmt::Display for VerificationError {
 
 impl std::error::Error for VerificationError {}
 
+/// lowest accepted amount of `token` for the required amount, tolerances
+/// keyed by token address or mint, or [`NATIVE_TOKEN`](crate::rates::NATIVE_TOKEN)
+pub fn minimum_amount(
+    tolerances: &HashMap<String, AmountTolerance>,
+    token: &str,
+    required: u128,
+) -> u128 {
+    // EVM addresses are case-insensitive, Solana mints are not
+    let tolerance = tolerances.get(token).or_else(|| {
+        tolerances
+            .iter()
+            .find(|(key, _)| token.starts_with("0x") && key.eq_ignore_ascii_case(token))
+            .map(|(_, tolerance)| tolerance)
+    });
+    tolerance.map_or(required, |tolerance| tolerance.minimum(required))
+}
+
 #[async_trait]
 pub trait PaymentVerifier: Send + Sync {
     async fn verify_payment(
@@ -46,37 +100,86 @@ pub trait PaymentVerifier: Send + Sync {
         payer_address: &str,
     ) -> Result<PaymentVerification, VerificationError>;
 
+    /// whether the verifier can verify payments on the chain, used to
+    /// resolve chains without a verifier registered under their own key
     fn supports_chain(&self, chain_type: &ChainType) -> bool;
+
+    /// verify a payment from any payer carrying the reference of the
+    /// request, used for delegated payments
+    async fn verify_payment_by_reference(
+        &self,
+        _payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        Err(VerificationError::Error(
+            "reference matching not supported".to_string(),
+        ))
+    }
+
+    /// finality of a payment transaction, verifiers of chains without
+    /// reorganizations report every transaction final
+    async fn transaction_finality(
+        &self,
+        _chain: &ChainConfig,
+        _transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        Ok(Finality::Finalized)
+    }
+
+    /// circuit breaker health, `None` for verifiers without a breaker
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        None
+    }
 }
 
 pub struct VerifierRegistry {
     verifiers: HashMap<ChainType, Box<dyn PaymentVerifier>>,
+    /// registration order, the fallback resolution picks the first match
+    order: Vec<ChainType>,
 }
 
 impl VerifierRegistry {
     pub fn new() -> Self {
         Self {
             verifiers: HashMap::new(),
+            order: Vec::new(),
         }
     }
 
     pub fn register_verifier(&mut self, chain_type: ChainType, verifier: Box<dyn PaymentVerifier>) {
-        self.verifiers.insert(chain_type, verifier);
+        if self
+            .verifiers
+            .insert(chain_type.clone(), verifier)
+            .is_none()
+        {
+            self.order.push(chain_type);
+        }
     }
 
+    /// Verifier registered for the chain, otherwise the first registered
+    /// verifier whose [`PaymentVerifier::supports_chain`] accepts it, e.g. a
+    /// verifier serving several EVM chains registered once.
     pub fn get_verifier(&self, chain_type: &ChainType) -> Option<&dyn PaymentVerifier> {
-        self.verifiers.get(chain_type).map(|v| v.as_ref())
+        if let Some(verifier) = self.verifiers.get(chain_type) {
+            return Some(verifier.as_ref());
+        }
+        self.order
+            .iter()
+            .filter_map(|registered| self.verifiers.get(registered))
+            .find(|verifier| verifier.supports_chain(chain_type))
+            .map(|v| v.as_ref())
     }
 
     pub fn has_verifier(&self, chain_type: &ChainType) -> bool {
-        self.verifiers.contains_key(chain_type)
+        self.get_verifier(chain_type).is_some()
     }
 
+    /// chains verifiers were registered for, in registration order
     pub fn supported_chains(&self) -> Vec<ChainType> {
-        self.verifiers.keys().cloned().collect()
+        self.order.clone()
     }
 
     pub fn remove_verifier(&mut self, chain_type: &ChainType) -> Option<Box<dyn PaymentVerifier>> {
+        self.order.retain(|registered| registered != chain_type);
         self.verifiers.remove(chain_type)
     }
 }
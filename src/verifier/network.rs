@@ -0,0 +1,151 @@
+/// Network identity module.
+use crate::types::{AptosChain, ChainType, SolanaChain, SuiChain};
+use crate::verifier::VerificationError;
+use serde_json::{Value, json};
+
+/// genesis hash of Solana mainnet-beta
+pub const SOLANA_MAINNET_GENESIS: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+/// genesis hash of Solana testnet
+pub const SOLANA_TESTNET_GENESIS: &str = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+/// genesis hash of Solana devnet
+pub const SOLANA_DEVNET_GENESIS: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+/// chain identifier of Sui mainnet, the first bytes of its genesis checkpoint
+pub const SUI_MAINNET_CHAIN_IDENTIFIER: &str = "35834a8a";
+/// chain identifier of Sui testnet
+pub const SUI_TESTNET_CHAIN_IDENTIFIER: &str = "4c78adac";
+
+/// Identity the RPC of a non-EVM chain has to report: the genesis hash on
+/// Solana, the chain identifier on Sui and the numeric chain id on Aptos.
+///
+/// `None` when the network has no stable identity, e.g. Sui and Aptos
+/// devnets are reset with a new one. A custom chain is checked when its
+/// name has the form of an identity, e.g. the genesis hash of a private
+/// Solana cluster. EVM chain ids are checked by
+/// [`EvmVerifier::new`](crate::verifier::evm::EvmVerifier::new).
+pub fn expected_identity(chain_type: &ChainType) -> Option<String> {
+    match chain_type {
+        ChainType::Solana(chain) => match chain {
+            SolanaChain::Mainnet => Some(SOLANA_MAINNET_GENESIS.to_string()),
+            SolanaChain::Testnet => Some(SOLANA_TESTNET_GENESIS.to_string()),
+            SolanaChain::Devnet => Some(SOLANA_DEVNET_GENESIS.to_string()),
+            SolanaChain::Custom(id) => bs58::decode(id)
+                .into_vec()
+                .is_ok_and(|hash| hash.len() == 32)
+                .then(|| id.clone()),
+        },
+        ChainType::Sui(chain) => match chain {
+            SuiChain::Mainnet => Some(SUI_MAINNET_CHAIN_IDENTIFIER.to_string()),
+            SuiChain::Testnet => Some(SUI_TESTNET_CHAIN_IDENTIFIER.to_string()),
+            SuiChain::Devnet => None,
+            SuiChain::Custom(id) => (id.len() == 8 && id.chars().all(|c| c.is_ascii_hexdigit()))
+                .then(|| id.to_lowercase()),
+        },
+        ChainType::Aptos(chain) => match chain {
+            AptosChain::Mainnet => Some("1".to_string()),
+            AptosChain::Testnet => Some("2".to_string()),
+            AptosChain::Devnet => None,
+            AptosChain::Custom(id) => id.parse::<u8>().ok().map(|id| id.to_string()),
+        },
+        ChainType::Evm(_) | ChainType::Custom(_) => None,
+    }
+}
+
+/// compare the identity reported by an RPC with the expected one
+pub fn check_identity(chain_type: &ChainType, actual: &str) -> Result<(), VerificationError> {
+    match expected_identity(chain_type) {
+        Some(expected) if !expected.eq_ignore_ascii_case(actual) => {
+            Err(VerificationError::WrongNetwork {
+                expected,
+                actual: actual.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check the RPC of a Solana, Sui or Aptos chain serves the intended
+/// network, done when a verifier is registered through the engine, also by
+/// [`X402::register_custom_verifier`](crate::core::X402::register_custom_verifier).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::types::{ChainType, SolanaChain};
+/// use x402_sdk::verifier::network::check_network;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// check_network("https://api.devnet.solana.com", &ChainType::Solana(SolanaChain::Devnet)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn check_network(rpc_url: &str, chain_type: &ChainType) -> Result<(), VerificationError> {
+    if expected_identity(chain_type).is_none() {
+        return Ok(());
+    }
+    let actual = network_identity(rpc_url, chain_type).await?;
+    check_identity(chain_type, &actual)
+}
+
+/// identity reported by the RPC, see [`expected_identity`]
+pub async fn network_identity(
+    rpc_url: &str,
+    chain_type: &ChainType,
+) -> Result<String, VerificationError> {
+    let client = reqwest::Client::new();
+    let identity = match chain_type {
+        ChainType::Solana(_) => json_rpc(&client, rpc_url, "getGenesisHash").await?,
+        ChainType::Sui(_) => json_rpc(&client, rpc_url, "sui_getChainIdentifier").await?,
+        ChainType::Aptos(_) => {
+            // ledger info of the REST API, the URL may already name the version
+            let base = rpc_url.trim_end_matches('/');
+            let url = if base.ends_with("/v1") {
+                base.to_string()
+            } else {
+                format!("{}/v1", base)
+            };
+            let ledger: Value = client
+                .get(url)
+                .send()
+                .await
+                .map_err(|e| VerificationError::NetworkError(e.to_string()))?
+                .json()
+                .await
+                .map_err(|e| VerificationError::RpcError(e.to_string()))?;
+            ledger.get("chain_id").cloned().unwrap_or(Value::Null)
+        }
+        ChainType::Evm(_) | ChainType::Custom(_) => {
+            return Err(VerificationError::ChainNotSupported);
+        }
+    };
+    match identity {
+        Value::String(identity) => Ok(identity),
+        Value::Number(identity) => Ok(identity.to_string()),
+        other => Err(VerificationError::RpcError(format!(
+            "Unexpected network identity: {}",
+            other
+        ))),
+    }
+}
+
+async fn json_rpc(
+    client: &reqwest::Client,
+    rpc_url: &str,
+    method: &str,
+) -> Result<Value, VerificationError> {
+    let response: Value = client
+        .post(rpc_url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] }))
+        .send()
+        .await
+        .map_err(|e| VerificationError::NetworkError(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| VerificationError::RpcError(e.to_string()))?;
+    if let Some(error) = response.get("error") {
+        return Err(VerificationError::RpcError(error.to_string()));
+    }
+    response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| VerificationError::RpcError(format!("{} returned no result", method)))
+}
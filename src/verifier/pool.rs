@@ -0,0 +1,135 @@
+/// Verification worker pool module.
+use crate::config::VerificationPoolConfig;
+use crate::types::{ChainConfig, ChainType, Finality, PaymentRequest, PaymentVerification};
+use crate::verifier::breaker::CircuitStatus;
+use crate::verifier::{PaymentVerifier, VerificationError};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Load of a chain verification pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// verifications calling the chain
+    pub running: usize,
+    /// verifications waiting for a slot
+    pub queued: usize,
+    /// verifications turned away since the pool was created
+    pub rejected: u64,
+}
+
+/// Wraps a verifier and bounds the verifications it runs at once.
+///
+/// At most `max_concurrent` verifications call the inner verifier, up to
+/// `max_queued` more wait for a slot for at most `queue_timeout_ms`. Others
+/// fail with [`VerificationError::Saturated`] without reaching the RPC, so a
+/// burst of traffic cannot exhaust the quota of the provider.
+pub struct PooledVerifier {
+    inner: Box<dyn PaymentVerifier>,
+    config: VerificationPoolConfig,
+    permits: Semaphore,
+    queued: AtomicUsize,
+    rejected: AtomicU64,
+}
+
+impl PooledVerifier {
+    pub fn new(inner: Box<dyn PaymentVerifier>, config: VerificationPoolConfig) -> Self {
+        Self {
+            inner,
+            permits: Semaphore::new(config.max_concurrent.max(1)),
+            config,
+            queued: AtomicUsize::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    pub fn status(&self) -> PoolStatus {
+        PoolStatus {
+            running: self.config.max_concurrent.max(1) - self.permits.available_permits(),
+            queued: self.queued.load(Ordering::SeqCst),
+            rejected: self.rejected.load(Ordering::SeqCst),
+        }
+    }
+
+    /// wait for a slot, queueing behind running verifications while the
+    /// queue has room
+    async fn admit(&self) -> Result<SemaphorePermit<'_>, VerificationError> {
+        if let Ok(permit) = self.permits.try_acquire() {
+            return Ok(permit);
+        }
+        if self.queued.fetch_add(1, Ordering::SeqCst) >= self.config.max_queued {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+            return Err(self.reject());
+        }
+        // leave the queue even when the waiting request is dropped
+        let _queued = QueueSlot(&self.queued);
+        match tokio::time::timeout(
+            Duration::from_millis(self.config.queue_timeout_ms),
+            self.permits.acquire(),
+        )
+        .await
+        {
+            Ok(Ok(permit)) => Ok(permit),
+            _ => Err(self.reject()),
+        }
+    }
+
+    fn reject(&self) -> VerificationError {
+        self.rejected.fetch_add(1, Ordering::SeqCst);
+        VerificationError::Saturated {
+            retry_after: self.config.retry_after_secs,
+        }
+    }
+}
+
+struct QueueSlot<'a>(&'a AtomicUsize);
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl PaymentVerifier for PooledVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let _permit = self.admit().await?;
+        self.inner
+            .verify_payment(payment_request, payer_address)
+            .await
+    }
+
+    async fn verify_payment_by_reference(
+        &self,
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let _permit = self.admit().await?;
+        self.inner
+            .verify_payment_by_reference(payment_request)
+            .await
+    }
+
+    async fn transaction_finality(
+        &self,
+        chain: &ChainConfig,
+        transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        let _permit = self.admit().await?;
+        self.inner
+            .transaction_finality(chain, transaction_hash)
+            .await
+    }
+
+    fn supports_chain(&self, chain_type: &ChainType) -> bool {
+        self.inner.supports_chain(chain_type)
+    }
+
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.inner.circuit_status()
+    }
+}
@@ -0,0 +1,191 @@
+/// Quorum verification module.
+use crate::types::{
+    ChainConfig, ChainType, ErrorReason, Finality, PaymentRequest, PaymentVerification,
+};
+use crate::verifier::breaker::{CircuitState, CircuitStatus};
+use crate::verifier::{PaymentVerifier, VerificationError};
+use async_trait::async_trait;
+use futures::future::join_all;
+
+/// Verifies every payment with several verifiers of the same chain, e.g. one
+/// per RPC provider, and accepts it once `quorum` of them report the same
+/// transaction paid.
+///
+/// A single faulty or malicious provider cannot grant access on its own.
+/// Without a quorum the payment is reported unpaid with
+/// [`ErrorReason::NoQuorum`], errors are only returned when no verifier
+/// answered.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use x402_sdk::types::{ChainType, EvmChain};
+/// use x402_sdk::verifier::PaymentVerifier;
+/// use x402_sdk::verifier::evm::EvmVerifier;
+/// use x402_sdk::verifier::quorum::QuorumVerifier;
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let chain = ChainType::Evm(EvmChain::Ethereum);
+/// let mut verifiers: Vec<Box<dyn PaymentVerifier>> = Vec::new();
+/// for rpc_url in ["https://eth.llamarpc.com", "https://rpc.ankr.com/eth", "https://cloudflare-eth.com"] {
+///     verifiers.push(Box::new(EvmVerifier::new(rpc_url.to_string(), chain.clone()).await?));
+/// }
+/// let verifier = QuorumVerifier::new(verifiers, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct QuorumVerifier {
+    verifiers: Vec<Box<dyn PaymentVerifier>>,
+    quorum: usize,
+}
+
+impl QuorumVerifier {
+    /// `quorum` is kept between one and the number of verifiers
+    pub fn new(verifiers: Vec<Box<dyn PaymentVerifier>>, quorum: usize) -> Self {
+        let quorum = quorum.clamp(1, verifiers.len().max(1));
+        Self { verifiers, quorum }
+    }
+
+    pub fn quorum(&self) -> usize {
+        self.quorum
+    }
+
+    /// Largest group of verifiers reporting the same transaction paid if it
+    /// reaches the quorum, otherwise an unpaid verification.
+    fn decide(
+        &self,
+        results: Vec<Result<PaymentVerification, VerificationError>>,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let mut votes: Vec<(String, Vec<PaymentVerification>)> = Vec::new();
+        let mut unpaid = None;
+        let mut error = None;
+        for result in results {
+            match result {
+                Ok(verification) if verification.is_paid => {
+                    let transaction = verification
+                        .transaction_hash
+                        .clone()
+                        .unwrap_or_default()
+                        .to_lowercase();
+                    match votes.iter_mut().find(|(hash, _)| *hash == transaction) {
+                        Some((_, agreeing)) => agreeing.push(verification),
+                        None => votes.push((transaction, vec![verification])),
+                    }
+                }
+                Ok(verification) => {
+                    unpaid.get_or_insert(verification);
+                }
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        let agreeing = votes
+            .into_iter()
+            .map(|(_, agreeing)| agreeing)
+            .max_by_key(|agreeing| agreeing.len());
+        match agreeing {
+            Some(agreeing) if agreeing.len() >= self.quorum => {
+                Ok(agreeing.into_iter().next().expect("votes are never empty"))
+            }
+            Some(agreeing) => {
+                let mut verification = agreeing.into_iter().next().expect("votes are never empty");
+                verification.is_paid = false;
+                verification.paid_amount = "0".to_string();
+                verification.failure_reason = Some(ErrorReason::NoQuorum);
+                Ok(verification)
+            }
+            None => match (unpaid, error) {
+                (Some(verification), _) => Ok(verification),
+                (None, Some(err)) => Err(err),
+                (None, None) => Err(VerificationError::ChainNotSupported),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl PaymentVerifier for QuorumVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let results = join_all(
+            self.verifiers
+                .iter()
+                .map(|verifier| verifier.verify_payment(payment_request, payer_address)),
+        )
+        .await;
+        self.decide(results)
+    }
+
+    async fn verify_payment_by_reference(
+        &self,
+        payment_request: &PaymentRequest,
+    ) -> Result<PaymentVerification, VerificationError> {
+        let results = join_all(
+            self.verifiers
+                .iter()
+                .map(|verifier| verifier.verify_payment_by_reference(payment_request)),
+        )
+        .await;
+        self.decide(results)
+    }
+
+    /// finality reported by a quorum, provisional while the verifiers
+    /// disagree
+    async fn transaction_finality(
+        &self,
+        chain: &ChainConfig,
+        transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        let results = join_all(
+            self.verifiers
+                .iter()
+                .map(|verifier| verifier.transaction_finality(chain, transaction_hash)),
+        )
+        .await;
+        let mut error = None;
+        let mut finalities = Vec::new();
+        for result in results {
+            match result {
+                Ok(finality) => finalities.push(finality),
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        if finalities.is_empty() {
+            return Err(error.unwrap_or(VerificationError::ChainNotSupported));
+        }
+        let agreed = [Finality::Finalized, Finality::Reverted]
+            .into_iter()
+            .find(|agreed| finalities.iter().filter(|f| *f == agreed).count() >= self.quorum);
+        Ok(agreed.unwrap_or(Finality::Provisional))
+    }
+
+    /// chains every verifier supports
+    fn supports_chain(&self, chain_type: &ChainType) -> bool {
+        !self.verifiers.is_empty()
+            && self
+                .verifiers
+                .iter()
+                .all(|verifier| verifier.supports_chain(chain_type))
+    }
+
+    /// the first open circuit, verifications still go through while a
+    /// quorum of verifiers answers
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        let statuses: Vec<CircuitStatus> = self
+            .verifiers
+            .iter()
+            .filter_map(|verifier| verifier.circuit_status())
+            .collect();
+        statuses
+            .iter()
+            .find(|status| status.state != CircuitState::Closed)
+            .or(statuses.first())
+            .cloned()
+    }
+}
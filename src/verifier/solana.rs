@@ -1,14 +1,54 @@
-use crate::types::{ChainType, PaymentRequest, PaymentVerification, TransactionLog};
-use crate::verifier::{PaymentVerifier, VerificationError};
+use crate::clock::{Clock, SystemClock};
+use crate::rates::{
+    NATIVE_TOKEN, RateProvider, request_usd_price, usd_value_micros, within_slippage,
+};
+use crate::types::{
+    AmountTolerance, ChainConfig, ChainType, Currency, ErrorReason, Finality, PaymentProof,
+    PaymentRequest, PaymentVerification, SolanaChain, TokenConversion, TransactionLog,
+};
+use crate::verifier::{PaymentVerifier, VerificationError, minimum_amount, units};
 use async_trait::async_trait;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_network_sdk::Solana;
 use solana_network_sdk::tool::address::is_valid_address;
-use solana_network_sdk::trade::TransactionInfo;
+use solana_network_sdk::trade::{TokenBalance, TransactionInfo};
 use solana_network_sdk::types::Mode;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// SPL Token program
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+/// SPL Token Extensions (Token-2022) program
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// Token movement of a transaction, amounts in the smallest unit of the mint.
+/// Payments are credited with `received`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenTransfer {
+    /// token program that moved the tokens
+    pub program_id: String,
+    /// amount debited from the payer
+    pub sent: u128,
+    /// amount credited to the recipient, lower than `sent` when a Token-2022
+    /// transfer fee was withheld
+    pub received: u128,
+    pub decimals: u8,
+}
+
+impl TokenTransfer {
+    /// transfer fee withheld by the token program
+    pub fn fee(&self) -> u128 {
+        self.sent - self.received
+    }
+}
+
 pub struct SolanaVerifier {
     client: Arc<Solana>,
+    clock: Arc<dyn Clock>,
+    rate_provider: Option<Arc<dyn RateProvider>>,
+    slippage_bps: u32,
+    amount_tolerances: HashMap<String, AmountTolerance>,
+    include_proofs: bool,
 }
 
 impl SolanaVerifier {
@@ -16,9 +56,57 @@ impl SolanaVerifier {
         let client = Solana::new(Mode::MAIN).unwrap();
         Self {
             client: Arc::new(client),
+            clock: Arc::new(SystemClock),
+            rate_provider: None,
+            slippage_bps: 0,
+            amount_tolerances: HashMap::new(),
+            include_proofs: false,
         }
     }
 
+    /// read the chain through `rpc_url` instead of the public mainnet endpoint
+    pub fn with_rpc_url(mut self, rpc_url: &str) -> Self {
+        let mut client = Solana::new(Mode::MAIN).unwrap();
+        client.client = Some(Arc::new(RpcClient::new(rpc_url.to_string())));
+        self.client = Arc::new(client);
+        self
+    }
+
+    /// attach the slot and signature of the payment transaction to paid
+    /// verifications
+    pub fn with_proofs(mut self, include_proofs: bool) -> Self {
+        self.include_proofs = include_proofs;
+        self
+    }
+
+    /// value any-token payments with the rate provider, accepting payments
+    /// up to `slippage_bps` basis points below the requested USD amount
+    pub fn with_rate_provider(
+        mut self,
+        rate_provider: Arc<dyn RateProvider>,
+        slippage_bps: u32,
+    ) -> Self {
+        self.rate_provider = Some(rate_provider);
+        self.slippage_bps = slippage_bps;
+        self
+    }
+
+    /// replace the clock used for verification timestamps
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// accept payments short of the amount by the tolerance of their
+    /// currency, keyed by mint or [`NATIVE_TOKEN`]
+    pub fn with_amount_tolerances(
+        mut self,
+        amount_tolerances: HashMap<String, AmountTolerance>,
+    ) -> Self {
+        self.amount_tolerances = amount_tolerances;
+        self
+    }
+
     /// check whether a single transaction meets the payment conditions
     fn check_transaction_payment(
         &self,
@@ -36,39 +124,139 @@ impl SolanaVerifier {
         }
         // parse the required amount (supports SOL and Lamports formats)
         let required_lamports = Self::parse_amount_to_lamports(required_amount)
-            .map_err(|e| VerificationError::ParseError(e))?;
+            .map_err(VerificationError::ParseError)?;
+        let required_lamports = minimum_amount(
+            &self.amount_tolerances,
+            NATIVE_TOKEN,
+            u128::from(required_lamports),
+        );
         // check whether the payment amount meets the requirements
         let paid_lamports = transaction.get_payment_amount();
-        if paid_lamports >= required_lamports {
+        if u128::from(paid_lamports) >= required_lamports {
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
-    /// Parse amount string into lamports
-    fn parse_amount_to_lamports(amount: &str) -> Result<u64, String> {
-        const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
-        let amount = amount.trim().replace(',', "");
-        if amount.is_empty() {
-            return Err("Amount cannot be empty".to_string());
+    /// token movement between payer and recipient for a mint, from the token
+    /// balances of the transaction so transfer-fee deductions are reflected
+    pub fn token_transfer(
+        transaction: &TransactionInfo,
+        payer: &str,
+        recipient: &str,
+        mint: &str,
+    ) -> Option<TokenTransfer> {
+        let balance = |balances: &[TokenBalance], owner: &str| -> u128 {
+            balances
+                .iter()
+                .filter(|balance| balance.owner == owner && balance.mint == mint)
+                .filter_map(|balance| balance.ui_token_amount.amount.parse::<u128>().ok())
+                .sum()
+        };
+        let received = balance(&transaction.post_token_balances, recipient)
+            .saturating_sub(balance(&transaction.pre_token_balances, recipient));
+        let decimals = transaction
+            .post_token_balances
+            .iter()
+            .find(|balance| balance.mint == mint)
+            .map_or(0, |balance| balance.ui_token_amount.decimals);
+        if received == 0 {
+            return None;
         }
-        if amount.contains('.') {
-            let sol_amount: f64 = amount
-                .parse()
-                .map_err(|_| format!("Invalid SOL amount format: {}", amount))?;
+        let sent = balance(&transaction.pre_token_balances, payer)
+            .saturating_sub(balance(&transaction.post_token_balances, payer));
+        let program_id = transaction
+            .instructions
+            .iter()
+            .chain(
+                transaction
+                    .inner_instructions
+                    .iter()
+                    .flat_map(|inner| inner.instructions.iter()),
+            )
+            .map(|instruction| instruction.program_id.as_str())
+            .find(|program_id| *program_id == TOKEN_2022_PROGRAM_ID)
+            .unwrap_or(TOKEN_PROGRAM_ID)
+            .to_string();
+        Some(TokenTransfer {
+            program_id,
+            sent: sent.max(received),
+            received,
+            decimals,
+        })
+    }
+
+    /// mints credited to the recipient by a transaction
+    fn received_mints(transaction: &TransactionInfo, recipient: &str) -> Vec<String> {
+        let mut mints: Vec<String> = transaction
+            .post_token_balances
+            .iter()
+            .filter(|balance| balance.owner == recipient)
+            .map(|balance| balance.mint.clone())
+            .collect();
+        mints.sort();
+        mints.dedup();
+        mints
+    }
 
-            if sol_amount < 0.0 {
-                return Err("The amount cannot be negative".to_string());
+    /// value the tokens received by a transaction against the micro-USD
+    /// amount of the request, returns the first allowed token that covers it
+    async fn convert_payment(
+        &self,
+        transaction: &TransactionInfo,
+        payment_request: &PaymentRequest,
+        payer: &str,
+        allowlist: &[String],
+    ) -> Result<Option<(TokenTransfer, TokenConversion)>, VerificationError> {
+        let required: u128 = payment_request.amount.parse().map_err(|_| {
+            VerificationError::ParseError(format!("Invalid USD amount: {}", payment_request.amount))
+        })?;
+        for mint in Self::received_mints(transaction, &payment_request.recipient) {
+            if !allowlist.is_empty() && !allowlist.contains(&mint) {
+                continue;
+            }
+            let Some(transfer) =
+                Self::token_transfer(transaction, payer, &payment_request.recipient, &mint)
+            else {
+                continue;
+            };
+            let usd_price = request_usd_price(self.rate_provider.as_ref(), payment_request, &mint)
+                .await
+                .map_err(|e| VerificationError::RpcError(e.to_string()))?;
+            let value = usd_value_micros(transfer.received, transfer.decimals, usd_price);
+            if within_slippage(value, required, self.slippage_bps) {
+                let conversion = TokenConversion {
+                    token: mint,
+                    amount: transfer.received.to_string(),
+                    decimals: transfer.decimals,
+                    usd_price,
+                    usd_value_micros: value,
+                    required_usd_micros: required,
+                    slippage_bps: self.slippage_bps,
+                };
+                return Ok(Some((transfer, conversion)));
             }
-            let lamports = (sol_amount * LAMPORTS_PER_SOL).round() as u64;
-            Ok(lamports)
-        } else {
-            let lamports: u64 = amount
-                .parse()
-                .map_err(|_| format!("Invalid lamports amount format: {}", amount))?;
-            Ok(lamports)
         }
+        Ok(None)
+    }
+
+    /// whole-token amount, decimal or integer, into the smallest unit of the
+    /// mint, see [`units::parse_token_amount`]
+    pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<u128, String> {
+        units::parse_token_amount(amount, decimals)
+    }
+
+    /// Parse amount string into lamports, see
+    /// [`units::parse_amount_to_lamports`]
+    pub fn parse_amount_to_lamports(amount: &str) -> Result<u64, String> {
+        units::parse_amount_to_lamports(amount)
+    }
+}
+
+impl Default for SolanaVerifier {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -92,24 +280,131 @@ impl PaymentVerifier for SolanaVerifier {
             .get_transactions_by_recipient_and_payer_strict(
                 &payment_request.recipient,
                 payer_address,
-                50,
+                payment_request.chain.max_scan_window() as usize,
             )
             .await;
         let mut found_payment = false;
         let mut transaction_logs = Vec::new();
         let mut paid_amount = "0".to_string();
         let mut transaction_hash = None;
+        let mut conversion = None;
+        // the closest miss, a short transfer outranks a wrong token
+        let mut failure_reason = ErrorReason::NotFound;
+        let mut note = |reason: ErrorReason| {
+            if failure_reason != ErrorReason::Underpaid {
+                failure_reason = reason;
+            }
+        };
         match transactions {
             Ok(transactions) => {
                 for transaction in transactions {
+                    let details = trade
+                        .get_transaction_details(&transaction.signature)
+                        .await
+                        .map_err(|e| VerificationError::RpcError(format!("{:?}", e)))?;
                     let transaction_info = TransactionInfo::from_encoded_transaction(
-                        &trade
-                            .get_transaction_details(&transaction.signature)
-                            .await
-                            .unwrap(),
+                        &details,
                         &transaction.signature,
                         "solana",
                     );
+                    if let Currency::AnyToken { allowlist } = &payment_request.currency {
+                        if !transaction_info.is_successful() {
+                            continue;
+                        }
+                        if let Some((transfer, converted)) = self
+                            .convert_payment(
+                                &transaction_info,
+                                payment_request,
+                                payer_address,
+                                allowlist,
+                            )
+                            .await?
+                        {
+                            found_payment = true;
+                            paid_amount = payment_request.amount.clone();
+                            transaction_hash = Some(transaction.signature.clone());
+                            transaction_logs.push(TransactionLog {
+                                transaction_hash: transaction_info.transaction_hash,
+                                from: payer_address.to_string(),
+                                to: payment_request.recipient.clone(),
+                                value: transfer.received.to_string(),
+                                block_number: transaction_info.block_number,
+                                log_index: transaction_info.log_index,
+                                data: Some(format!(
+                                    "program={} mint={} fee={}",
+                                    transfer.program_id,
+                                    converted.token,
+                                    transfer.fee()
+                                )),
+                                explorer_url: None,
+                            });
+                            conversion = Some(converted);
+                            break;
+                        }
+                        let mints =
+                            Self::received_mints(&transaction_info, &payment_request.recipient);
+                        if mints
+                            .iter()
+                            .any(|mint| allowlist.is_empty() || allowlist.contains(mint))
+                        {
+                            note(ErrorReason::Underpaid);
+                        } else if !mints.is_empty() {
+                            note(ErrorReason::WrongToken);
+                        }
+                        continue;
+                    }
+                    if let Currency::Token { address, decimals } = &payment_request.currency {
+                        let required = Self::parse_token_amount(&payment_request.amount, *decimals)
+                            .map_err(VerificationError::ParseError)?;
+                        let required = minimum_amount(&self.amount_tolerances, address, required);
+                        let transfer = Self::token_transfer(
+                            &transaction_info,
+                            payer_address,
+                            &payment_request.recipient,
+                            address,
+                        );
+                        if !transaction_info.is_successful() {
+                            continue;
+                        }
+                        match &transfer {
+                            Some(transfer) if transfer.received < required => {
+                                note(ErrorReason::Underpaid)
+                            }
+                            None if !Self::received_mints(
+                                &transaction_info,
+                                &payment_request.recipient,
+                            )
+                            .is_empty() =>
+                            {
+                                note(ErrorReason::WrongToken)
+                            }
+                            _ => {}
+                        }
+                        if let Some(transfer) = transfer
+                            && transfer.received >= required
+                        {
+                            found_payment = true;
+                            paid_amount = transfer.received.to_string();
+                            transaction_hash = Some(transaction.signature.clone());
+                            transaction_logs.push(TransactionLog {
+                                transaction_hash: transaction_info.transaction_hash,
+                                from: payer_address.to_string(),
+                                to: payment_request.recipient.clone(),
+                                value: transfer.received.to_string(),
+                                block_number: transaction_info.block_number,
+                                log_index: transaction_info.log_index,
+                                data: Some(format!(
+                                    "program={} mint={} fee={}",
+                                    transfer.program_id,
+                                    address,
+                                    transfer.fee()
+                                )),
+                                explorer_url: None,
+                            });
+                            break;
+                        }
+                        continue;
+                    }
                     if self.check_transaction_payment(
                         &transaction_info,
                         &payment_request.recipient,
@@ -126,27 +421,87 @@ impl PaymentVerifier for SolanaVerifier {
                             block_number: transaction_info.block_number,
                             log_index: transaction_info.log_index,
                             data: transaction_info.data,
+                            explorer_url: None,
                         });
                         break;
                     }
+                    if transaction_info.is_successful()
+                        && transaction_info.is_recipient(&payment_request.recipient)
+                    {
+                        if transaction_info.get_payment_amount() > 0 {
+                            note(ErrorReason::Underpaid);
+                        } else if !Self::received_mints(
+                            &transaction_info,
+                            &payment_request.recipient,
+                        )
+                        .is_empty()
+                        {
+                            note(ErrorReason::WrongToken);
+                        }
+                    }
                 }
             }
-            Err(_) => todo!(),
+            Err(e) => return Err(VerificationError::RpcError(format!("{:?}", e))),
         }
+        // the client reads at finalized commitment
+        let proof = transaction_logs
+            .first()
+            .filter(|_| self.include_proofs && found_payment)
+            .map(|log| PaymentProof::Solana {
+                slot: log.block_number,
+                signature: log.transaction_hash.clone(),
+                commitment: "finalized".to_string(),
+            });
+        let paid_currency = payment_request
+            .currency
+            .paid_with(conversion.as_ref())
+            .filter(|_| found_payment);
         Ok(PaymentVerification {
             is_paid: found_payment,
             paid_amount,
             transaction_hash,
-            verified_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            verified_at: self.clock.now(),
             chain: payment_request.chain.clone(),
             transaction_logs,
+            explorer_url: None,
+            conversion,
+            paid_currency,
+            failure_reason: (!found_payment).then_some(failure_reason),
+            proof,
+        }
+        .with_canonical_identifiers()
+        .with_explorer_links())
+    }
+
+    /// the client reads at finalized commitment, a transaction it cannot
+    /// see yet is provisional
+    async fn transaction_finality(
+        &self,
+        _chain: &ChainConfig,
+        transaction_hash: &str,
+    ) -> Result<Finality, VerificationError> {
+        // the client panics on malformed signatures
+        let is_base58 = transaction_hash
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() && !"0OIl".contains(c));
+        if !is_base58 || !(64..=88).contains(&transaction_hash.len()) {
+            return Err(VerificationError::ParseError(transaction_hash.to_string()));
+        }
+        let trade = self.client.create_trade();
+        let Ok(details) = trade.get_transaction_details(transaction_hash).await else {
+            return Ok(Finality::Provisional);
+        };
+        let transaction =
+            TransactionInfo::from_encoded_transaction(&details, transaction_hash, "solana");
+        Ok(if transaction.is_successful() {
+            Finality::Finalized
+        } else {
+            Finality::Reverted
         })
     }
 
+    /// the client is connected to mainnet
     fn supports_chain(&self, chain_type: &ChainType) -> bool {
-        matches!(chain_type, ChainType::Solana(_))
+        matches!(chain_type, ChainType::Solana(SolanaChain::Mainnet))
     }
 }
@@ -0,0 +1,38 @@
+/// whole-token amount, decimal or integer, into the smallest unit of the
+/// mint, the same convention as ERC-20 amounts on EVM
+pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<u128, String> {
+    let amount = amount.trim().replace(',', "");
+    let (whole, fraction) = amount.split_once('.').unwrap_or((&amount, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err("Amount cannot be empty".to_string());
+    }
+    if fraction.len() > decimals as usize {
+        return Err(format!("Too many decimal places for token: {}", amount));
+    }
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    digits
+        .parse()
+        .map_err(|_| format!("Invalid token amount format: {}", amount))
+}
+
+/// Parse amount string into lamports, decimal amounts are SOL with at
+/// most 9 decimal places
+pub fn parse_amount_to_lamports(amount: &str) -> Result<u64, String> {
+    let amount = amount.trim().replace(',', "");
+    if amount.is_empty() {
+        return Err("Amount cannot be empty".to_string());
+    }
+    if amount.contains('.') {
+        if amount.starts_with('-') {
+            return Err("The amount cannot be negative".to_string());
+        }
+        // exact, a float loses lamports on large amounts
+        let lamports = parse_token_amount(&amount, 9)?;
+        u64::try_from(lamports).map_err(|_| format!("Invalid SOL amount format: {}", amount))
+    } else {
+        let lamports: u64 = amount
+            .parse()
+            .map_err(|_| format!("Invalid lamports amount format: {}", amount))?;
+        Ok(lamports)
+    }
+}
@@ -0,0 +1,342 @@
+/// WebSocket paywall module.
+use crate::clock::Clock;
+use crate::context::RequestContext;
+use crate::core::{EngineError, X402};
+use crate::types::VerificationResult;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Close code sent when the entitlement of a socket runs out, in the range
+/// reserved for applications.
+pub const PAYMENT_REQUIRED_CLOSE_CODE: u16 = 4402;
+
+/// How much of a socket one payment buys, unlimited when neither is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SocketQuota {
+    pub max_duration: Option<Duration>,
+    /// messages the payer may send, or receive when counted by the server
+    pub max_messages: Option<u64>,
+}
+
+/// Why a socket has to be terminated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminateReason {
+    DurationElapsed,
+    MessagesExhausted,
+}
+
+impl TerminateReason {
+    /// close code of the WebSocket close frame
+    pub fn close_code(&self) -> u16 {
+        PAYMENT_REQUIRED_CLOSE_CODE
+    }
+
+    /// reason of the WebSocket close frame
+    pub fn close_reason(&self) -> &'static str {
+        match self {
+            Self::DurationElapsed => "paid duration elapsed",
+            Self::MessagesExhausted => "paid messages exhausted",
+        }
+    }
+}
+
+impl std::fmt::Display for TerminateReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.close_reason())
+    }
+}
+
+type TerminateHook = dyn Fn(&SocketEntitlement, TerminateReason) + Send + Sync;
+
+/// Decision on an upgrade request.
+#[derive(Debug)]
+pub enum Upgrade {
+    /// answer with `101 Switching Protocols` and serve the socket within
+    /// the entitlement
+    Accept(SocketEntitlement),
+    /// answer with the result instead of upgrading, e.g. a 402 challenge
+    Refuse(VerificationResult),
+}
+
+/// Charges for WebSocket sessions: the upgrade request goes through the
+/// x402 flow like any other request, the paid socket is then served
+/// within a [`SocketQuota`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use x402_sdk::context::RequestContext;
+/// use x402_sdk::core::X402;
+/// use x402_sdk::websocket::{Upgrade, WebSocketPaywall};
+///
+/// # async fn example(nonce: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+/// let engine = Arc::new(X402::from_default_config()?);
+/// let paywall = WebSocketPaywall::new(engine)
+///     .with_price("5000")
+///     .with_max_duration(Duration::from_secs(3600))
+///     .with_terminate_hook(|entitlement, reason| {
+///         println!("closing the socket of {}: {}", entitlement.payer(), reason);
+///     });
+/// let payer = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+/// match paywall
+///     .authorize_upgrade(payer, "/ws/prices", nonce, None, &RequestContext::new("GET"))
+///     .await?
+/// {
+///     Upgrade::Accept(entitlement) => {
+///         // upgrade, then for every message
+///         if let Err(reason) = entitlement.record_message() {
+///             // close the socket with reason.close_code()
+///         }
+///     }
+///     Upgrade::Refuse(result) => {
+///         // answer the upgrade request with result.http_status and the challenge
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct WebSocketPaywall {
+    engine: Arc<X402>,
+    price: Option<String>,
+    quota: SocketQuota,
+    terminate_hook: Option<Arc<TerminateHook>>,
+}
+
+impl WebSocketPaywall {
+    pub fn new(engine: Arc<X402>) -> Self {
+        Self {
+            engine,
+            price: None,
+            quota: SocketQuota::default(),
+            terminate_hook: None,
+        }
+    }
+
+    /// fixed price of a socket, the pricing provider of the engine decides
+    /// when unset
+    pub fn with_price(mut self, amount: &str) -> Self {
+        self.price = Some(amount.to_string());
+        self
+    }
+
+    pub fn with_quota(mut self, quota: SocketQuota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.quota.max_duration = Some(max_duration);
+        self
+    }
+
+    pub fn with_max_messages(mut self, max_messages: u64) -> Self {
+        self.quota.max_messages = Some(max_messages);
+        self
+    }
+
+    /// called once when the entitlement of a socket runs out, e.g. to close
+    /// it from outside of the task serving it
+    pub fn with_terminate_hook(
+        mut self,
+        hook: impl Fn(&SocketEntitlement, TerminateReason) + Send + Sync + 'static,
+    ) -> Self {
+        self.terminate_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Runs the x402 flow for an upgrade request of `resource_path`.
+    pub async fn authorize_upgrade(
+        &self,
+        user_address: &str,
+        resource_path: &str,
+        payment_nonce: Option<&str>,
+        coupon_code: Option<&str>,
+        context: &RequestContext,
+    ) -> Result<Upgrade, EngineError> {
+        let result = self
+            .engine
+            .handle_access_request_with_coupon(
+                user_address,
+                resource_path,
+                payment_nonce,
+                self.price.as_deref(),
+                coupon_code,
+                context,
+            )
+            .await?;
+        if !result.should_serve_content {
+            return Ok(Upgrade::Refuse(result));
+        }
+        Ok(Upgrade::Accept(SocketEntitlement::new(
+            user_address,
+            resource_path,
+            result,
+            self.quota,
+            self.engine.clock().clone(),
+            self.terminate_hook.clone(),
+        )))
+    }
+}
+
+/// What a paid socket may still use, shared by the tasks serving it.
+pub struct SocketEntitlement {
+    payer: String,
+    resource_path: String,
+    result: VerificationResult,
+    quota: SocketQuota,
+    /// unix timestamp the socket has to be closed at
+    expires_at: Option<u64>,
+    messages: AtomicU64,
+    terminated: AtomicBool,
+    notify: Notify,
+    clock: Arc<dyn Clock>,
+    terminate_hook: Option<Arc<TerminateHook>>,
+}
+
+impl std::fmt::Debug for SocketEntitlement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SocketEntitlement")
+            .field("payer", &self.payer)
+            .field("resource_path", &self.resource_path)
+            .field("quota", &self.quota)
+            .field("expires_at", &self.expires_at)
+            .field("messages", &self.messages)
+            .finish()
+    }
+}
+
+impl SocketEntitlement {
+    fn new(
+        payer: &str,
+        resource_path: &str,
+        result: VerificationResult,
+        quota: SocketQuota,
+        clock: Arc<dyn Clock>,
+        terminate_hook: Option<Arc<TerminateHook>>,
+    ) -> Self {
+        let expires_at = quota
+            .max_duration
+            .map(|duration| clock.now().saturating_add(duration.as_secs()));
+        Self {
+            payer: payer.to_string(),
+            resource_path: resource_path.to_string(),
+            result,
+            quota,
+            expires_at,
+            messages: AtomicU64::new(0),
+            terminated: AtomicBool::new(false),
+            notify: Notify::new(),
+            clock,
+            terminate_hook,
+        }
+    }
+
+    pub fn payer(&self) -> &str {
+        &self.payer
+    }
+
+    pub fn resource_path(&self) -> &str {
+        &self.resource_path
+    }
+
+    /// result of the upgrade request, with the receipt of the payment
+    pub fn result(&self) -> &VerificationResult {
+        &self.result
+    }
+
+    pub fn quota(&self) -> SocketQuota {
+        self.quota
+    }
+
+    pub fn expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
+
+    pub fn messages(&self) -> u64 {
+        self.messages.load(Ordering::SeqCst)
+    }
+
+    /// messages left, `None` without a message quota
+    pub fn remaining_messages(&self) -> Option<u64> {
+        self.quota
+            .max_messages
+            .map(|max| max.saturating_sub(self.messages()))
+    }
+
+    fn duration_elapsed(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| self.clock.now() >= expires_at)
+    }
+
+    /// `Err` once the socket has to be terminated
+    pub fn check(&self) -> Result<(), TerminateReason> {
+        let reason = if self.duration_elapsed() {
+            TerminateReason::DurationElapsed
+        } else if self.remaining_messages() == Some(0) {
+            TerminateReason::MessagesExhausted
+        } else {
+            return Ok(());
+        };
+        self.terminate(reason);
+        Err(reason)
+    }
+
+    /// Counts a message against the quota, `Err` when it is past the quota
+    /// and must not be delivered. The last message of the quota is
+    /// delivered and terminates the socket.
+    pub fn record_message(&self) -> Result<(), TerminateReason> {
+        if self.duration_elapsed() {
+            self.terminate(TerminateReason::DurationElapsed);
+            return Err(TerminateReason::DurationElapsed);
+        }
+        let count = self.messages.fetch_add(1, Ordering::SeqCst) + 1;
+        match self.quota.max_messages {
+            Some(max) if count > max => {
+                self.terminate(TerminateReason::MessagesExhausted);
+                Err(TerminateReason::MessagesExhausted)
+            }
+            Some(max) if count == max => {
+                self.terminate(TerminateReason::MessagesExhausted);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolves once the socket has to be terminated, for a `select!` next
+    /// to the socket reads.
+    pub async fn exhausted(&self) -> TerminateReason {
+        loop {
+            let notified = self.notify.notified();
+            if let Err(reason) = self.check() {
+                return reason;
+            }
+            match self.expires_at {
+                Some(expires_at) => {
+                    let wait = expires_at.saturating_sub(self.clock.now()).max(1);
+                    tokio::select! {
+                        _ = notified => {}
+                        _ = tokio::time::sleep(Duration::from_secs(wait)) => {}
+                    }
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    /// run the terminate hook and wake the waiters of [`Self::exhausted`],
+    /// once per socket
+    fn terminate(&self, reason: TerminateReason) {
+        if self.terminated.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        if let Some(hook) = &self.terminate_hook {
+            hook(self, reason);
+        }
+        self.notify.notify_waiters();
+    }
+}
@@ -0,0 +1,288 @@
+/// Wire format module for 402 bodies.
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+#[derive(Debug)]
+pub enum WireError {
+    UnsupportedMediaType(String),
+    EncodingError(String),
+    DecodingError(String),
+}
+
+impl std::fmt::Display for WireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedMediaType(media_type) => {
+                write!(f, "Unsupported media type: {}", media_type)
+            }
+            Self::EncodingError(msg) => write!(f, "Encoding error: {}", msg),
+            Self::DecodingError(msg) => write!(f, "Decoding error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WireError {}
+
+/// Encoding of payment-required bodies and payment payloads, negotiated from
+/// the `Accept` and `Content-Type` headers.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::wire::WireFormat;
+///
+/// let format = WireFormat::negotiate(Some("application/cbor;q=0.5, application/json"));
+/// assert_eq!(format, WireFormat::Json);
+/// let body = format.encode(&serde_json::json!({ "amount": "1000" })).unwrap();
+/// let value: serde_json::Value = format.decode(&body).unwrap();
+/// assert_eq!(value["amount"], "1000");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WireFormat {
+    #[default]
+    Json,
+    /// requires the `cbor` feature
+    #[cfg(feature = "cbor")]
+    Cbor,
+    /// requires the `msgpack` feature
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl WireFormat {
+    /// formats compiled in, in order of preference on equal quality
+    pub fn supported() -> Vec<Self> {
+        vec![
+            Self::Json,
+            #[cfg(feature = "cbor")]
+            Self::Cbor,
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack,
+        ]
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => JSON_CONTENT_TYPE,
+            #[cfg(feature = "cbor")]
+            Self::Cbor => CBOR_CONTENT_TYPE,
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => MSGPACK_CONTENT_TYPE,
+        }
+    }
+
+    /// format of a `Content-Type` header value, parameters are ignored
+    pub fn from_content_type(content_type: &str) -> Result<Self, WireError> {
+        let media_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        Self::supported()
+            .into_iter()
+            .find(|format| format.matches(&media_type))
+            .ok_or(WireError::UnsupportedMediaType(media_type))
+    }
+
+    /// best format for an `Accept` header value by quality, JSON when the
+    /// header is missing or accepts none of the supported formats
+    pub fn negotiate(accept: Option<&str>) -> Self {
+        let Some(accept) = accept else {
+            return Self::Json;
+        };
+        let mut best = None;
+        for range in accept.split(',') {
+            let mut parts = range.split(';');
+            let media_type = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality <= 0.0 {
+                continue;
+            }
+            let format = match media_type.as_str() {
+                "*/*" | "application/*" => Some(Self::Json),
+                _ => Self::supported()
+                    .into_iter()
+                    .find(|format| format.matches(&media_type)),
+            };
+            if let Some(format) = format
+                && best.is_none_or(|(_, best_quality)| quality > best_quality)
+            {
+                best = Some((format, quality));
+            }
+        }
+        best.map_or(Self::Json, |(format, _)| format)
+    }
+
+    fn matches(&self, media_type: &str) -> bool {
+        match self {
+            Self::Json => media_type == JSON_CONTENT_TYPE,
+            #[cfg(feature = "cbor")]
+            Self::Cbor => media_type == CBOR_CONTENT_TYPE,
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => matches!(
+                media_type,
+                MSGPACK_CONTENT_TYPE | "application/x-msgpack" | "application/vnd.msgpack"
+            ),
+        }
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, WireError> {
+        match self {
+            Self::Json => {
+                serde_json::to_vec(value).map_err(|e| WireError::EncodingError(e.to_string()))
+            }
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)
+                    .map_err(|e| WireError::EncodingError(e.to_string()))?;
+                Ok(bytes)
+            }
+            // named fields keep the structure of the JSON body
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => {
+                rmp_serde::to_vec_named(value).map_err(|e| WireError::EncodingError(e.to_string()))
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, WireError> {
+        match self {
+            Self::Json => {
+                serde_json::from_slice(bytes).map_err(|e| WireError::DecodingError(e.to_string()))
+            }
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                ciborium::from_reader(bytes).map_err(|e| WireError::DecodingError(e.to_string()))
+            }
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => {
+                rmp_serde::from_slice(bytes).map_err(|e| WireError::DecodingError(e.to_string()))
+            }
+        }
+    }
+}
+
+/// protocol fields named differently by other x402 implementations
+const CAMEL_CASE_FIELDS: &[(&str, &str)] = &[
+    ("amount", "maxAmountRequired"),
+    ("recipient", "payTo"),
+    ("expires_at", "expiresAt"),
+    ("payment_required", "paymentRequired"),
+    ("verification_url", "verificationUrl"),
+    ("merchant_name", "merchantName"),
+    ("quoted_at", "quotedAt"),
+    ("previous_amount", "previousAmount"),
+    ("previous_quote", "previousQuote"),
+    ("chain_type", "chainType"),
+    ("chain_id", "chainId"),
+    ("rpc_url", "rpcUrl"),
+    ("quorum_rpc_urls", "quorumRpcUrls"),
+    ("explorer_tx_url", "explorerTxUrl"),
+    ("native_symbol", "nativeSymbol"),
+    ("native_decimals", "nativeDecimals"),
+    ("block_time_ms", "blockTimeMs"),
+    ("min_confirmations", "minConfirmations"),
+    ("max_scan_window", "maxScanWindow"),
+];
+
+/// fields keyed by the service or by token, never renamed
+const OPAQUE_FIELDS: &[&str] = &["extensions", "descriptions", "rates"];
+
+/// Field names written to clients. Challenges are always signed over their
+/// snake_case form, the casing only changes the bytes sent, and either one
+/// is read back.
+///
+/// # Examples
+///
+/// ```rust
+/// use x402_sdk::wire::FieldCasing;
+///
+/// let value = FieldCasing::Camel
+///     .to_value(&serde_json::json!({ "amount": "1000", "expires_at": 60 }))
+///     .unwrap();
+/// assert_eq!(value["maxAmountRequired"], "1000");
+/// assert_eq!(value["expiresAt"], 60);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldCasing {
+    #[default]
+    Snake,
+    /// names of other x402 implementations, e.g. `maxAmountRequired` and
+    /// `payTo`
+    Camel,
+}
+
+impl FieldCasing {
+    /// JSON value of a protocol type with its fields named in this casing
+    pub fn to_value<T: Serialize>(&self, value: &T) -> Result<Value, WireError> {
+        let mut value =
+            serde_json::to_value(value).map_err(|e| WireError::EncodingError(e.to_string()))?;
+        if *self == Self::Camel {
+            rename_fields(&mut value);
+        }
+        Ok(value)
+    }
+}
+
+fn rename_fields(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            let renamed = std::mem::take(fields).into_iter().map(|(name, mut field)| {
+                if !OPAQUE_FIELDS.contains(&name.as_str()) {
+                    rename_fields(&mut field);
+                }
+                let name = CAMEL_CASE_FIELDS
+                    .iter()
+                    .find(|(snake, _)| *snake == name)
+                    .map_or(name, |(_, camel)| camel.to_string());
+                (name, field)
+            });
+            fields.extend(renamed);
+        }
+        Value::Array(items) => items.iter_mut().for_each(rename_fields),
+        _ => {}
+    }
+}
+
+/// encode a payment-required body in the format negotiated from the
+/// `Accept` header, returns the `Content-Type` and the body
+pub fn encode_negotiated<T: Serialize>(
+    accept: Option<&str>,
+    value: &T,
+) -> Result<(&'static str, Vec<u8>), WireError> {
+    let format = WireFormat::negotiate(accept);
+    Ok((format.content_type(), format.encode(value)?))
+}
+
+/// [`encode_negotiated`] with the fields named in `casing`
+pub fn encode_negotiated_in<T: Serialize>(
+    accept: Option<&str>,
+    casing: FieldCasing,
+    value: &T,
+) -> Result<(&'static str, Vec<u8>), WireError> {
+    encode_negotiated(accept, &casing.to_value(value)?)
+}
+
+/// decode a payment payload in the format of its `Content-Type` header,
+/// JSON when the header is missing
+pub fn decode_content<T: DeserializeOwned>(
+    content_type: Option<&str>,
+    bytes: &[u8],
+) -> Result<T, WireError> {
+    let format = match content_type {
+        Some(content_type) => WireFormat::from_content_type(content_type)?,
+        None => WireFormat::Json,
+    };
+    format.decode(bytes)
+}
@@ -0,0 +1,130 @@
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::http::header::{ACCEPT, HeaderMap};
+use actix_web::middleware::{Next, from_fn};
+use actix_web::{HttpRequest, HttpResponse, web};
+use std::sync::Arc;
+use x402_sdk::config::{ConfigBuilder, ConfigManager, X402Config};
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::headers::ChallengeHeaders;
+use x402_sdk::keys::JWKS_PATH;
+use x402_sdk::simulation::SimulationRule;
+use x402_sdk::types::X402ProtocolResponse;
+
+/// header carrying the wallet address of the payer
+pub const PAYER_HEADER: &str = "x-payer-address";
+/// header carrying the nonce of the paid challenge
+pub const NONCE_HEADER: &str = "x-payment-nonce";
+
+const PAYWALL_PAGE: &str = include_str!("../static/paywall.html");
+
+/// Engine configuration, edit it to set your chains, prices and service
+/// address. With `X402_SIMULATE` set every challenge counts as paid, so the
+/// flow can be tried without a wallet.
+pub fn config() -> X402Config {
+    let builder = ConfigBuilder::new().with_expiration_time(600);
+    match std::env::var("X402_SIMULATE") {
+        Ok(_) => builder.with_simulation(SimulationRule::AlwaysPay).build(),
+        Err(_) => builder.build(),
+    }
+}
+
+/// engine with a verifier for every configured chain
+pub async fn engine(config: X402Config) -> Result<Arc<X402>, EngineError> {
+    let mut engine = X402::new(ConfigManager::from_config(config))?;
+    let report = engine.register_all_configured().await;
+    for (chain, err) in &report.failed {
+        eprintln!("no verifier for {}: {}", chain.get_display_name(), err);
+    }
+    Ok(Arc::new(engine))
+}
+
+/// routes of the service, everything under `/premium` needs a payment and
+/// the receipt signing keys are published at [`JWKS_PATH`]
+pub fn configure(engine: Arc<X402>) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |config| {
+        config
+            .app_data(web::Data::from(engine))
+            .route("/", web::get().to(|| async { "ok" }))
+            .route(JWKS_PATH, web::get().to(jwks))
+            .service(
+                web::scope("/premium")
+                    .wrap(from_fn(paywall))
+                    .default_service(web::get().to(premium_content)),
+            );
+    }
+}
+
+/// keys checking the receipts the service issues, 404 when it signs none
+async fn jwks(engine: web::Data<X402>) -> HttpResponse {
+    match engine.jwks() {
+        Some(jwks) => HttpResponse::Ok().json(jwks),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+async fn premium_content(request: HttpRequest) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "content": format!("paid content of {}", request.path())
+    }))
+}
+
+/// lets paid requests through and answers the others with a 402 challenge
+async fn paywall(
+    request: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let engine = request
+        .app_data::<web::Data<X402>>()
+        .cloned()
+        .expect("engine is registered by configure");
+    let headers = request.headers();
+    let wants_html = headers
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+    let Some(payer) = header(headers, PAYER_HEADER) else {
+        return Ok(request.into_response(payment_required(None, wants_html)));
+    };
+    let result = engine
+        .handle_access_request(
+            payer,
+            request.path(),
+            header(headers, NONCE_HEADER),
+            None)
+        .await;
+    let response = match result {
+        Ok(result) if result.should_serve_content => {
+            return Ok(next.call(request).await?.map_into_boxed_body());
+        }
+        Ok(result) => match result.x402_response {
+            Some(challenge) => payment_required(Some(&challenge), wants_html),
+            None => HttpResponse::new(
+                StatusCode::from_u16(result.http_status).unwrap_or(StatusCode::FORBIDDEN),
+            ),
+        },
+        Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+    };
+    Ok(request.into_response(response))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// the challenge as `X-Payment-Required` header and as paywall page or JSON
+fn payment_required(challenge: Option<&X402ProtocolResponse>, wants_html: bool) -> HttpResponse {
+    let json = serde_json::to_string(&challenge).unwrap_or_else(|_| "null".to_string());
+    let mut response = HttpResponse::PaymentRequired();
+    let rendered = challenge.map(|challenge| ChallengeHeaders::new().render(challenge));
+    for header in rendered.and_then(Result::ok).unwrap_or_default() {
+        response.insert_header(header);
+    }
+    match wants_html {
+        true => response
+            .content_type("text/html; charset=utf-8")
+            .body(PAYWALL_PAGE.replace("__X402_CHALLENGE__", &json)),
+        false => response.content_type("application/json").body(json),
+    }
+}
@@ -0,0 +1,12 @@
+use actix_web::{App, HttpServer};
+
+#[actix_web::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let engine = {{crate}}::engine({{crate}}::config()).await?;
+    println!("listening on http://0.0.0.0:3000");
+    HttpServer::new(move || App::new().configure({{crate}}::configure(engine.clone())))
+        .bind(("0.0.0.0", 3000))?
+        .run()
+        .await?;
+    Ok(())
+}
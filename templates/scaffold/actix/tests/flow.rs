@@ -0,0 +1,46 @@
+use actix_web::http::StatusCode;
+use actix_web::{App, test};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::headers::PAYMENT_REQUIRED_HEADER;
+use x402_sdk::keys::JWKS_PATH;
+use x402_sdk::simulation::SimulationRule;
+use {{crate}}::{NONCE_HEADER, PAYER_HEADER, configure, engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+#[actix_web::test]
+async fn premium_content_is_served_once_paid() {
+    // simulated chains, every challenge is paid
+    let config = ConfigBuilder::new()
+        .with_simulation(SimulationRule::AlwaysPay)
+        .build();
+    let engine = engine(config).await.unwrap();
+    let app = test::init_service(App::new().configure(configure(engine))).await;
+
+    let request = test::TestRequest::get()
+        .uri("/premium/article")
+        .insert_header((PAYER_HEADER, PAYER))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+    assert!(response.headers().contains_key(PAYMENT_REQUIRED_HEADER));
+    let challenge: serde_json::Value = test::read_body_json(response).await;
+    let nonce = challenge["payment_required"]["nonce"].as_str().unwrap();
+
+    let request = test::TestRequest::get()
+        .uri("/premium/article")
+        .insert_header((PAYER_HEADER, PAYER))
+        .insert_header((NONCE_HEADER, nonce))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn no_keys_are_published_before_signing_is_configured() {
+    let engine = engine(ConfigBuilder::new().build()).await.unwrap();
+    let app = test::init_service(App::new().configure(configure(engine))).await;
+    let request = test::TestRequest::get().uri(JWKS_PATH).to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,119 @@
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use std::sync::Arc;
+use x402_sdk::config::{ConfigBuilder, ConfigManager, X402Config};
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::headers::ChallengeHeaders;
+use x402_sdk::keys::JWKS_PATH;
+use x402_sdk::simulation::SimulationRule;
+use x402_sdk::types::X402ProtocolResponse;
+
+/// header carrying the wallet address of the payer
+pub const PAYER_HEADER: &str = "x-payer-address";
+/// header carrying the nonce of the paid challenge
+pub const NONCE_HEADER: &str = "x-payment-nonce";
+
+const PAYWALL_PAGE: &str = include_str!("../static/paywall.html");
+
+/// Engine configuration, edit it to set your chains, prices and service
+/// address. With `X402_SIMULATE` set every challenge counts as paid, so the
+/// flow can be tried without a wallet.
+pub fn config() -> X402Config {
+    let builder = ConfigBuilder::new().with_expiration_time(600);
+    match std::env::var("X402_SIMULATE") {
+        Ok(_) => builder.with_simulation(SimulationRule::AlwaysPay).build(),
+        Err(_) => builder.build(),
+    }
+}
+
+/// engine with a verifier for every configured chain
+pub async fn engine(config: X402Config) -> Result<Arc<X402>, EngineError> {
+    let mut engine = X402::new(ConfigManager::from_config(config))?;
+    let report = engine.register_all_configured().await;
+    for (chain, err) in &report.failed {
+        eprintln!("no verifier for {}: {}", chain.get_display_name(), err);
+    }
+    Ok(Arc::new(engine))
+}
+
+/// routes of the service, everything under `/premium` needs a payment and
+/// the receipt signing keys are published at [`JWKS_PATH`]
+pub fn app(engine: Arc<X402>) -> Router {
+    let premium = Router::new()
+        .route("/premium/{*path}", get(premium_content))
+        .route_layer(middleware::from_fn_with_state(engine.clone(), paywall));
+    Router::new()
+        .route("/", get(|| async { "ok" }))
+        .route(JWKS_PATH, get(jwks))
+        .with_state(engine)
+        .merge(premium)
+}
+
+/// keys checking the receipts the service issues, 404 when it signs none
+async fn jwks(State(engine): State<Arc<X402>>) -> Response {
+    match engine.jwks() {
+        Some(jwks) => Json(jwks).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn premium_content(request: Request) -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "content": format!("paid content of {}", request.uri().path()) }))
+}
+
+/// lets paid requests through and answers the others with a 402 challenge
+async fn paywall(State(engine): State<Arc<X402>>, request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let wants_html = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+    let Some(payer) = header(headers, PAYER_HEADER) else {
+        return payment_required(None, wants_html);
+    };
+    let result = engine
+        .handle_access_request(
+            payer,
+            request.uri().path(),
+            header(headers, NONCE_HEADER),
+            None)
+        .await;
+    match result {
+        Ok(result) if result.should_serve_content => next.run(request).await,
+        Ok(result) => match result.x402_response {
+            Some(challenge) => payment_required(Some(&challenge), wants_html),
+            None => StatusCode::from_u16(result.http_status)
+                .unwrap_or(StatusCode::FORBIDDEN)
+                .into_response(),
+        },
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// the challenge as `X-Payment-Required` header and as paywall page or JSON
+fn payment_required(challenge: Option<&X402ProtocolResponse>, wants_html: bool) -> Response {
+    let json = serde_json::to_string(&challenge).unwrap_or_else(|_| "null".to_string());
+    let mut response = match wants_html {
+        true => Html(PAYWALL_PAGE.replace("__X402_CHALLENGE__", &json)).into_response(),
+        false => ([(header::CONTENT_TYPE, "application/json")], json).into_response(),
+    };
+    *response.status_mut() = StatusCode::PAYMENT_REQUIRED;
+    let rendered = challenge.map(|challenge| ChallengeHeaders::new().render(challenge));
+    for (name, value) in rendered.and_then(Result::ok).unwrap_or_default() {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name),
+            HeaderValue::try_from(value),
+        ) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    response
+}
@@ -0,0 +1,8 @@
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let engine = {{crate}}::engine({{crate}}::config()).await?;
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+    println!("listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, {{crate}}::app(engine)).await?;
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use tower::ServiceExt;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::headers::PAYMENT_REQUIRED_HEADER;
+use x402_sdk::keys::JWKS_PATH;
+use x402_sdk::simulation::SimulationRule;
+use {{crate}}::{NONCE_HEADER, PAYER_HEADER, app, engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+#[tokio::test]
+async fn premium_content_is_served_once_paid() {
+    // simulated chains, every challenge is paid
+    let config = ConfigBuilder::new()
+        .with_simulation(SimulationRule::AlwaysPay)
+        .build();
+    let app = app(engine(config).await.unwrap());
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::get("/premium/article")
+                .header(PAYER_HEADER, PAYER)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+    assert!(response.headers().contains_key(PAYMENT_REQUIRED_HEADER));
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let challenge: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let nonce = challenge["payment_required"]["nonce"].as_str().unwrap();
+
+    let response = app
+        .oneshot(
+            Request::get("/premium/article")
+                .header(PAYER_HEADER, PAYER)
+                .header(NONCE_HEADER, nonce)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn no_keys_are_published_before_signing_is_configured() {
+    let app = app(engine(ConfigBuilder::new().build()).await.unwrap());
+    let response = app
+        .oneshot(Request::get(JWKS_PATH).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use std::time::Duration;
+use x402_sdk::authz::AuthzServer;
+use x402_sdk::config::{ConfigBuilder, ConfigManager, X402Config};
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::simulation::SimulationRule;
+
+/// Engine configuration, edit it to set your chains, prices and service
+/// address. With `X402_SIMULATE` set every challenge counts as paid, so the
+/// flow can be tried without a wallet.
+pub fn config() -> X402Config {
+    let builder = ConfigBuilder::new().with_expiration_time(600);
+    match std::env::var("X402_SIMULATE") {
+        Ok(_) => builder.with_simulation(SimulationRule::AlwaysPay).build(),
+        Err(_) => builder.build(),
+    }
+}
+
+/// engine with a verifier for every configured chain
+pub async fn engine(config: X402Config) -> Result<Arc<X402>, EngineError> {
+    let mut engine = X402::new(ConfigManager::from_config(config))?;
+    let report = engine.register_all_configured().await;
+    for (chain, err) in &report.failed {
+        eprintln!("no verifier for {}: {}", chain.get_display_name(), err);
+    }
+    Ok(Arc::new(engine))
+}
+
+/// Authorization worker for a reverse proxy: nginx `auth_request` or the
+/// Envoy `ext_authz` filter send every request of the paid upstream here
+/// and pass it through on a 2xx answer. A direct `GET` of
+/// [`JWKS_PATH`](x402_sdk::keys::JWKS_PATH) answers with the receipt signing
+/// keys, route it to the worker to publish them.
+pub fn authorizer(engine: Arc<X402>) -> AuthzServer {
+    AuthzServer::new(engine).with_www_authenticate("{{name}}")
+}
+
+/// settle the finality of provisional payments every `interval`
+pub async fn finality_loop(engine: Arc<X402>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match engine.update_finality().await {
+            Ok(report) if report.reverted > 0 => {
+                eprintln!("{} payments reverted", report.reverted);
+            }
+            Ok(_) => {}
+            Err(err) => eprintln!("finality update failed: {}", err),
+        }
+    }
+}
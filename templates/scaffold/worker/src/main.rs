@@ -0,0 +1,15 @@
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let engine = {{crate}}::engine({{crate}}::config()).await?;
+    tokio::spawn({{crate}}::finality_loop(
+        engine.clone(),
+        Duration::from_secs(30),
+    ));
+    let listener = TcpListener::bind("127.0.0.1:9402").await?;
+    println!("authorizing on http://{}", listener.local_addr()?);
+    {{crate}}::authorizer(engine).serve(listener).await?;
+    Ok(())
+}
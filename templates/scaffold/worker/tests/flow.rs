@@ -0,0 +1,46 @@
+use x402_sdk::authz::{AuthzRequest, NONCE_HEADER, ORIGINAL_URI_HEADER, PAYER_HEADER};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::simulation::SimulationRule;
+use {{crate}}::{authorizer, engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn check(headers: &[(&str, &str)]) -> AuthzRequest {
+    AuthzRequest {
+        method: "GET".to_string(),
+        target: "/auth".to_string(),
+        headers: headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        client_ip: None,
+    }
+}
+
+#[tokio::test]
+async fn proxied_requests_pass_once_paid() {
+    // simulated chains, every challenge is paid
+    let config = ConfigBuilder::new()
+        .with_simulation(SimulationRule::AlwaysPay)
+        .build();
+    let authorizer = authorizer(engine(config).await.unwrap());
+
+    let denied = authorizer
+        .authorize(&check(&[
+            (ORIGINAL_URI_HEADER, "/premium/article"),
+            (PAYER_HEADER, PAYER),
+        ]))
+        .await;
+    assert_eq!(denied.status, 402);
+    let challenge: serde_json::Value = serde_json::from_slice(&denied.body).unwrap();
+    let nonce = challenge["payment_required"]["nonce"].as_str().unwrap();
+
+    let allowed = authorizer
+        .authorize(&check(&[
+            (ORIGINAL_URI_HEADER, "/premium/article"),
+            (PAYER_HEADER, PAYER),
+            (NONCE_HEADER, nonce),
+        ]))
+        .await;
+    assert_eq!(allowed.status, 200);
+}
@@ -0,0 +1,100 @@
+use x402_sdk::address::{
+    checksum_address, evm_address, evm_hash, normalize_address, normalize_transaction_hash,
+    solana_address, solana_signature, to_hex,
+};
+use x402_sdk::types::{ChainConfig, ChainType, PaymentVerification, SolanaChain, TransactionLog};
+
+/// test vectors of EIP-55
+const CHECKSUMMED: [&str; 4] = [
+    "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+    "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+    "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+    "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+];
+
+#[test]
+fn evm_addresses_round_trip_through_lowercase_and_checksum() {
+    for checksummed in CHECKSUMMED {
+        let lowercase = evm_address(checksummed).unwrap();
+        assert_eq!(lowercase, checksummed.to_lowercase());
+        assert_eq!(
+            evm_address(&checksummed.to_uppercase()[2..]).unwrap(),
+            lowercase
+        );
+        assert_eq!(checksum_address(&lowercase).unwrap(), checksummed);
+        assert_eq!(
+            evm_address(&checksum_address(&lowercase).unwrap()).unwrap(),
+            lowercase
+        );
+    }
+}
+
+#[test]
+fn evm_identifiers_reject_wrong_lengths_and_digits() {
+    assert!(evm_address("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA").is_err());
+    assert!(evm_address("0xzzAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+    assert!(evm_address("0x5aAe...BeAed").is_err());
+    assert!(evm_hash(CHECKSUMMED[0]).is_err());
+    let hash = format!("0x{}", "AB".repeat(32));
+    assert_eq!(evm_hash(&hash).unwrap(), hash.to_lowercase());
+    assert_eq!(to_hex([0xab; 32]), hash.to_lowercase());
+}
+
+#[test]
+fn solana_identifiers_round_trip_through_base58() {
+    for address in [
+        "11111111111111111111111111111111",
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    ] {
+        assert_eq!(solana_address(address).unwrap(), address);
+        assert_eq!(
+            normalize_address(
+                &ChainType::Solana(SolanaChain::Mainnet),
+                &format!(" {} ", address)
+            )
+            .unwrap(),
+            address
+        );
+    }
+    let signature = bs58::encode([7; 64]).into_string();
+    assert_eq!(solana_signature(&signature).unwrap(), signature);
+    assert!(solana_signature("11111111111111111111111111111111").is_err());
+    assert!(solana_address("0OIl").is_err());
+}
+
+#[test]
+fn verification_identifiers_are_made_canonical() {
+    let hash = format!("0x{}", "CD".repeat(32));
+    let verification = PaymentVerification {
+        is_paid: true,
+        paid_amount: "1".to_string(),
+        transaction_hash: Some(hash.clone()),
+        verified_at: 0,
+        chain: ChainConfig::new(ChainType::ethereum(), None),
+        transaction_logs: vec![TransactionLog {
+            transaction_hash: hash.clone(),
+            from: CHECKSUMMED[0].to_string(),
+            to: "not an address".to_string(),
+            value: "1".to_string(),
+            block_number: 1,
+            log_index: 0,
+            data: None,
+            explorer_url: None,
+        }],
+        explorer_url: None,
+        conversion: None,
+        paid_currency: None,
+        failure_reason: None,
+        proof: None,
+    }
+    .with_canonical_identifiers();
+    assert_eq!(verification.transaction_hash, Some(hash.to_lowercase()));
+    let log = &verification.transaction_logs[0];
+    assert_eq!(log.transaction_hash, hash.to_lowercase());
+    assert_eq!(log.from, CHECKSUMMED[0].to_lowercase());
+    assert_eq!(log.to, "not an address");
+    assert_eq!(
+        normalize_transaction_hash(&ChainType::ethereum(), &hash).unwrap(),
+        hash.to_lowercase()
+    );
+}
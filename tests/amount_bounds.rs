@@ -0,0 +1,111 @@
+use x402_sdk::config::{ConfigBuilder, ConfigManager, CurrencyConfig, CurrencyType};
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::pricing::PricingError;
+use x402_sdk::rates::NATIVE_TOKEN;
+use x402_sdk::types::{AmountBounds, ChainConfig, ChainType};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn engine() -> X402 {
+    let config = ConfigBuilder::new()
+        .with_amount_bounds(NATIVE_TOKEN, AmountBounds::new(1_000, 1_000_000))
+        .build();
+    X402::new(ConfigManager::from_config(config)).unwrap()
+}
+
+async fn challenge(engine: &X402, amount: &str) -> Result<String, EngineError> {
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some(amount))
+        .await?;
+    Ok(result.x402_response.unwrap().payment_required.amount)
+}
+
+#[tokio::test]
+async fn custom_amounts_within_bounds_are_issued() {
+    let engine = engine();
+    assert_eq!(challenge(&engine, "1000").await.unwrap(), "1000");
+    assert_eq!(challenge(&engine, "1000000").await.unwrap(), "1000000");
+}
+
+#[tokio::test]
+async fn custom_amounts_outside_bounds_are_rejected() {
+    let engine = engine();
+    for amount in ["999", "1000001"] {
+        assert!(matches!(
+            challenge(&engine, amount).await,
+            Err(EngineError::PricingError(PricingError::OutOfBounds { .. }))
+        ));
+    }
+}
+
+const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+const SOLANA_USDC: &str = "EPjFWJd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn token_engine(chain_type: ChainType, token: &str, bounds: AmountBounds) -> X402 {
+    let mut config = ConfigBuilder::new()
+        .with_chain(
+            chain_type.clone(),
+            ChainConfig::from_chain_type(chain_type.clone()),
+        )
+        .with_default_chain(chain_type)
+        .with_amount_bounds(token, bounds)
+        .build();
+    config.service.default_currency = CurrencyConfig {
+        currency_type: CurrencyType::Erc20,
+        address: Some(token.to_string()),
+        decimals: 6,
+    };
+    X402::new(ConfigManager::from_config(config)).unwrap()
+}
+
+#[tokio::test]
+async fn erc20_amounts_are_whole_tokens() {
+    // 1 to 100 USDC in the smallest unit
+    let engine = token_engine(
+        ChainType::ethereum(),
+        USDC,
+        AmountBounds::new(1_000_000, 100_000_000),
+    );
+    assert_eq!(challenge(&engine, "1").await.unwrap(), "1");
+    assert_eq!(challenge(&engine, "100").await.unwrap(), "100");
+    assert!(matches!(
+        challenge(&engine, "101").await,
+        Err(EngineError::PricingError(PricingError::OutOfBounds { .. }))
+    ));
+    // the verifier reads ERC-20 amounts as integers
+    assert!(matches!(
+        challenge(&engine, "1.5").await,
+        Err(EngineError::PricingError(PricingError::InvalidAmount(_)))
+    ));
+}
+
+#[tokio::test]
+async fn solana_amounts_may_be_decimal() {
+    let engine = token_engine(
+        ChainType::solana_mainnet(),
+        SOLANA_USDC,
+        AmountBounds::new(1_000_000, 100_000_000),
+    );
+    assert_eq!(challenge(&engine, "1.5").await.unwrap(), "1.5");
+    assert!(matches!(
+        challenge(&engine, "0.5").await,
+        Err(EngineError::PricingError(PricingError::OutOfBounds { .. }))
+    ));
+    for amount in ["0", "0.0000001", "-1.5", "1.5.0"] {
+        assert!(matches!(
+            challenge(&engine, amount).await,
+            Err(EngineError::PricingError(PricingError::InvalidAmount(_)))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn custom_amounts_must_be_positive_smallest_units() {
+    let engine = X402::from_default_config().unwrap();
+    for amount in ["0", "0.10", "-5", "1e18", ""] {
+        assert!(matches!(
+            challenge(&engine, amount).await,
+            Err(EngineError::PricingError(PricingError::InvalidAmount(_)))
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use x402_sdk::config::{ConfigBuilder, ConfigManager};
+use x402_sdk::core::X402;
+use x402_sdk::rates::NATIVE_TOKEN;
+use x402_sdk::testing::MockVerifier;
+use x402_sdk::types::{AmountTolerance, ChainType};
+use x402_sdk::verifier::minimum_amount;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn tolerances() -> HashMap<String, AmountTolerance> {
+    HashMap::from([
+        (NATIVE_TOKEN.to_string(), AmountTolerance::Absolute(10)),
+        (USDC.to_string(), AmountTolerance::BasisPoints(50)),
+        (USDC_MINT.to_string(), AmountTolerance::Absolute(3)),
+    ])
+}
+
+/// whether `paid` covers `required` of `token`
+fn accepts(token: &str, required: u128, paid: u128) -> bool {
+    paid >= minimum_amount(&tolerances(), token, required)
+}
+
+#[test]
+fn tolerances_lower_the_minimum() {
+    assert_eq!(AmountTolerance::Absolute(10).minimum(1000), 990);
+    assert_eq!(AmountTolerance::Absolute(10).minimum(4), 0);
+    assert_eq!(AmountTolerance::BasisPoints(50).minimum(1_000_000), 995_000);
+    // fractions of a unit are not tolerated
+    assert_eq!(AmountTolerance::BasisPoints(50).minimum(199), 199);
+    assert_eq!(AmountTolerance::BasisPoints(50).minimum(200), 199);
+    // beyond 100% everything is tolerated, not more
+    assert_eq!(AmountTolerance::BasisPoints(20_000).minimum(1000), 0);
+    // scaling does not overflow on large amounts
+    assert_eq!(
+        AmountTolerance::BasisPoints(50).minimum(u128::MAX),
+        u128::MAX - u128::MAX / 10_000 * 50
+    );
+}
+
+#[test]
+fn every_currency_is_accepted_at_its_tolerance_and_not_one_unit_over() {
+    // native: 10 units
+    assert!(accepts(NATIVE_TOKEN, 1000, 990));
+    assert!(!accepts(NATIVE_TOKEN, 1000, 989));
+    // EVM token: 0.5%
+    assert!(accepts(USDC, 1_000_000, 995_000));
+    assert!(!accepts(USDC, 1_000_000, 994_999));
+    // Solana mint: 3 units
+    assert!(accepts(USDC_MINT, 1000, 997));
+    assert!(!accepts(USDC_MINT, 1000, 996));
+    // other currencies are compared exactly
+    assert!(accepts(
+        "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+        1000,
+        1000
+    ));
+    assert!(!accepts(
+        "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+        1000,
+        999
+    ));
+}
+
+#[test]
+fn token_keys_follow_the_address_case_rules_of_their_chain() {
+    // EVM addresses match whatever their case
+    assert!(accepts(&USDC.to_lowercase(), 1_000_000, 995_000));
+    assert!(!accepts(&USDC.to_lowercase(), 1_000_000, 994_999));
+    // Solana mints are case-sensitive
+    assert!(!accepts(&USDC_MINT.to_lowercase(), 1000, 997));
+}
+
+#[tokio::test]
+async fn configured_tolerances_decide_access() {
+    let config = ConfigBuilder::new()
+        .with_amount_tolerance(NATIVE_TOKEN, AmountTolerance::Absolute(10))
+        .build();
+    let verifier =
+        MockVerifier::new().with_amount_tolerances(config.payments.amount_tolerances.clone());
+    let mut engine = X402::new(ConfigManager::from_config(config)).unwrap();
+    engine
+        .verifier_registry_mut()
+        .register_verifier(ChainType::ethereum(), Box::new(verifier.clone()));
+
+    for (paid, served) in [(989, false), (990, true)] {
+        let result = engine
+            .handle_access_request(PAYER, "/premium", None, Some("1000"))
+            .await
+            .unwrap();
+        let nonce = result.x402_response.unwrap().payment_required.nonce;
+        verifier.set_paid_amount(Some(paid));
+        let result = engine
+            .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+            .await
+            .unwrap();
+        assert_eq!(result.should_serve_content, served, "paid {}", paid);
+    }
+}
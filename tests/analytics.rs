@@ -0,0 +1,141 @@
+use std::sync::Arc;
+use x402_sdk::analytics::{Analytics, Funnel, ResourceRevenue};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const NOW: u64 = 1_700_000_000;
+
+fn engine(analytics: Arc<Analytics>, clock: &MockClock) -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine
+        .with_clock(Arc::new(clock.clone()))
+        .with_event_listener(analytics);
+    (engine, verifier)
+}
+
+/// issue a challenge for a resource, returns the session nonce
+async fn challenge(engine: &X402, path: &str, amount: &str) -> String {
+    let result = engine
+        .handle_access_request(PAYER, path, None, Some(amount))
+        .await
+        .unwrap();
+    result.x402_response.unwrap().payment_required.nonce
+}
+
+/// present a payment of `paid` for a session
+async fn pay(engine: &X402, verifier: &MockVerifier, path: &str, nonce: &str, paid: u128) {
+    verifier.set_paid_amount(Some(paid));
+    engine
+        .handle_access_request(PAYER, path, Some(nonce), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn funnels_count_each_session_once_per_step() {
+    let clock = MockClock::new(NOW);
+    let analytics = Arc::new(Analytics::new().with_clock(Arc::new(clock.clone())));
+    let (engine, verifier) = engine(analytics.clone(), &clock);
+
+    let paid = challenge(&engine, "/a", "1000").await;
+    let short = challenge(&engine, "/b", "500").await;
+    challenge(&engine, "/c", "700").await;
+    challenge(&engine, "/d", "900").await;
+
+    clock.advance(30);
+    pay(&engine, &verifier, "/b", &short, 100).await;
+    pay(&engine, &verifier, "/b", &short, 100).await;
+    pay(&engine, &verifier, "/a", &paid, 1000).await;
+
+    let funnel = analytics.funnel(3_600);
+    assert_eq!(
+        funnel,
+        Funnel {
+            challenges_issued: 4,
+            payments_seen: 2,
+            payments_verified: 1,
+            content_served: 1,
+        }
+    );
+    assert_eq!(funnel.conversion_rate(), 0.25);
+    assert_eq!(analytics.average_time_to_pay(3_600), Some(30.0));
+    assert_eq!(
+        analytics.revenue_by_resource(3_600),
+        vec![ResourceRevenue {
+            resource: "GET /a".to_string(),
+            chain_id: "1".to_string(),
+            payments: 1,
+            amount: 1000,
+        }]
+    );
+
+    let report = analytics.report(3_600);
+    assert_eq!(report.funnel, funnel);
+    assert_eq!(report.conversion_rate, 0.25);
+}
+
+#[tokio::test]
+async fn windows_cover_only_recent_activity() {
+    let clock = MockClock::new(NOW);
+    let analytics = Arc::new(Analytics::new().with_clock(Arc::new(clock.clone())));
+    let (engine, verifier) = engine(analytics.clone(), &clock);
+
+    let first = challenge(&engine, "/a", "1000").await;
+    clock.advance(100);
+    let second = challenge(&engine, "/b", "2000").await;
+    pay(&engine, &verifier, "/b", &second, 2000).await;
+    clock.advance(100);
+    pay(&engine, &verifier, "/a", &first, 1000).await;
+
+    // the last minute holds the late payment only
+    let funnel = analytics.funnel(60);
+    assert_eq!(funnel.challenges_issued, 0);
+    assert_eq!(funnel.payments_verified, 1);
+    // its challenge is still retained for the time to pay
+    assert_eq!(analytics.average_time_to_pay(60), Some(200.0));
+    assert_eq!(analytics.revenue_by_resource(60)[0].resource, "GET /a");
+
+    let funnel = analytics.funnel(150);
+    assert_eq!(funnel.challenges_issued, 1);
+    assert_eq!(funnel.payments_verified, 2);
+    assert_eq!(analytics.average_time_to_pay(150), Some(100.0));
+    assert_eq!(analytics.funnel(200).challenges_issued, 2);
+    // highest revenue first
+    let revenue = analytics.revenue_by_resource(150);
+    assert_eq!(
+        revenue
+            .iter()
+            .map(|line| (line.resource.as_str(), line.amount))
+            .collect::<Vec<_>>(),
+        vec![("GET /b", 2000), ("GET /a", 1000)]
+    );
+}
+
+#[tokio::test]
+async fn events_past_the_retention_are_evicted() {
+    let clock = MockClock::new(NOW);
+    let analytics = Arc::new(
+        Analytics::new()
+            .with_clock(Arc::new(clock.clone()))
+            .with_retention(120),
+    );
+    let (engine, verifier) = engine(analytics.clone(), &clock);
+
+    let early = challenge(&engine, "/a", "1000").await;
+    clock.advance(121);
+    // eviction happens as new events come in
+    assert_eq!(analytics.funnel(u64::MAX).challenges_issued, 1);
+    pay(&engine, &verifier, "/a", &early, 1000).await;
+
+    let funnel = analytics.funnel(u64::MAX);
+    assert_eq!(funnel.challenges_issued, 0);
+    assert_eq!(funnel.payments_verified, 1);
+    // the challenge is gone, and with it the time to pay
+    assert_eq!(analytics.average_time_to_pay(u64::MAX), None);
+
+    let empty = Analytics::new();
+    assert_eq!(empty.funnel(3_600), Funnel::default());
+    assert_eq!(empty.report(3_600).conversion_rate, 0.0);
+}
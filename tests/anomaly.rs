@@ -0,0 +1,212 @@
+use std::sync::{Arc, Mutex};
+use x402_sdk::anomaly::{
+    AnomalyDetector, AnomalyHook, AnomalyKind, AnomalySignal, StaticScreeningList,
+};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const OTHER_PAYER: &str = "0x8589427373D6D84E98730D7795D8f6f8731FDA16";
+const NOW: u64 = 1_700_000_000;
+
+#[derive(Default)]
+struct RecordingHook {
+    signals: Mutex<Vec<AnomalySignal>>,
+}
+
+impl RecordingHook {
+    fn kinds(&self) -> Vec<AnomalyKind> {
+        self.signals
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|signal| signal.kind.clone())
+            .collect()
+    }
+}
+
+impl AnomalyHook for RecordingHook {
+    fn on_signal(&self, signal: &AnomalySignal) {
+        self.signals.lock().unwrap().push(signal.clone());
+    }
+}
+
+fn engine(detector: Arc<AnomalyDetector>, clock: &MockClock) -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine
+        .with_clock(Arc::new(clock.clone()))
+        .with_event_listener(detector.clone())
+        .with_access_policy(detector);
+    (engine, verifier)
+}
+
+/// issue a challenge for `path`, returns the session nonce
+async fn challenge(engine: &X402, payer: &str, path: &str) -> String {
+    let result = engine
+        .handle_access_request(payer, path, None, Some("1000"))
+        .await
+        .unwrap();
+    result.x402_response.unwrap().payment_required.nonce
+}
+
+/// pay a session, the mock verifier reports the same transaction for the
+/// same amount
+async fn pay(engine: &X402, verifier: &MockVerifier, payer: &str, path: &str, nonce: &str) {
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(payer, path, Some(nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+}
+
+#[tokio::test]
+async fn unpaid_sessions_fire_at_the_threshold() {
+    let clock = MockClock::new(NOW);
+    let hook = Arc::new(RecordingHook::default());
+    let detector = Arc::new(
+        AnomalyDetector::new()
+            .with_hook(hook.clone())
+            .with_clock(Arc::new(clock.clone()))
+            .with_unpaid_threshold(3, 60),
+    );
+    let (engine, _verifier) = engine(detector.clone(), &clock);
+
+    challenge(&engine, PAYER, "/a").await;
+    challenge(&engine, PAYER, "/b").await;
+    // sessions of other payers do not add up
+    challenge(&engine, OTHER_PAYER, "/c").await;
+    assert!(hook.kinds().is_empty());
+    assert!(!detector.is_flagged(PAYER));
+
+    challenge(&engine, PAYER, "/c").await;
+    assert_eq!(
+        hook.kinds(),
+        vec![AnomalyKind::UnpaidSessions {
+            sessions: 3,
+            window_secs: 60,
+        }]
+    );
+    assert!(detector.is_flagged(PAYER));
+    assert!(!detector.is_flagged(OTHER_PAYER));
+    // fired once until cleared
+    challenge(&engine, PAYER, "/d").await;
+    assert_eq!(hook.kinds().len(), 1);
+    assert_eq!(hook.signals.lock().unwrap()[0].timestamp, NOW);
+
+    detector.clear(PAYER);
+    assert!(!detector.is_flagged(PAYER));
+    challenge(&engine, PAYER, "/e").await;
+    assert_eq!(hook.kinds().len(), 1);
+}
+
+#[tokio::test]
+async fn unpaid_sessions_outside_the_window_or_paid_do_not_count() {
+    let clock = MockClock::new(NOW);
+    let hook = Arc::new(RecordingHook::default());
+    let detector = Arc::new(
+        AnomalyDetector::new()
+            .with_hook(hook.clone())
+            .with_clock(Arc::new(clock.clone()))
+            .with_unpaid_threshold(3, 60),
+    );
+    let (engine, verifier) = engine(detector.clone(), &clock);
+
+    challenge(&engine, PAYER, "/a").await;
+    challenge(&engine, PAYER, "/b").await;
+    clock.advance(61);
+    challenge(&engine, PAYER, "/c").await;
+    assert!(hook.kinds().is_empty());
+
+    let nonce = challenge(&engine, PAYER, "/d").await;
+    pay(&engine, &verifier, PAYER, "/d", &nonce).await;
+    challenge(&engine, PAYER, "/e").await;
+    assert!(hook.kinds().is_empty());
+    // the third unpaid session in the window
+    challenge(&engine, PAYER, "/f").await;
+    assert!(matches!(
+        hook.kinds().as_slice(),
+        [AnomalyKind::UnpaidSessions { sessions: 3, .. }]
+    ));
+}
+
+#[tokio::test]
+async fn a_transaction_paying_several_sessions_is_probed() {
+    let clock = MockClock::new(NOW);
+    let hook = Arc::new(RecordingHook::default());
+    let detector = Arc::new(
+        AnomalyDetector::new()
+            .with_hook(hook.clone())
+            .with_clock(Arc::new(clock.clone()))
+            .with_probe_threshold(3),
+    );
+    let (engine, verifier) = engine(detector.clone(), &clock);
+
+    for path in ["/a", "/b"] {
+        let nonce = challenge(&engine, PAYER, path).await;
+        pay(&engine, &verifier, PAYER, path, &nonce).await;
+    }
+    assert!(hook.kinds().is_empty());
+
+    let nonce = challenge(&engine, PAYER, "/c").await;
+    pay(&engine, &verifier, PAYER, "/c", &nonce).await;
+    assert_eq!(
+        hook.kinds(),
+        vec![AnomalyKind::TransactionProbed {
+            transaction_hash: format!("0x{:064x}", 1000),
+            sessions: 3,
+        }]
+    );
+    assert_eq!(hook.signals.lock().unwrap()[0].payer, PAYER);
+}
+
+#[tokio::test]
+async fn screened_payers_are_signaled_and_denied_when_blocking() {
+    let clock = MockClock::new(NOW);
+    let screening = Arc::new(StaticScreeningList::new().with_address(OTHER_PAYER, "sanctioned"));
+
+    // without blocking the payer is only signaled
+    let hook = Arc::new(RecordingHook::default());
+    let detector = Arc::new(
+        AnomalyDetector::new()
+            .with_hook(hook.clone())
+            .with_screening(screening.clone()),
+    );
+    let (observing, _verifier) = engine(detector, &clock);
+    let result = observing
+        .handle_access_request(OTHER_PAYER, "/a", None, Some("1000"))
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 402);
+    assert_eq!(
+        hook.kinds(),
+        vec![AnomalyKind::ScreenedAddress {
+            reason: "sanctioned".to_string()
+        }]
+    );
+
+    let hook = Arc::new(RecordingHook::default());
+    let detector = Arc::new(
+        AnomalyDetector::new()
+            .with_hook(hook.clone())
+            .with_screening(screening)
+            .with_unpaid_threshold(1, 60)
+            .with_blocking(true),
+    );
+    let (blocking, _verifier) = engine(detector, &clock);
+    let result = blocking
+        .handle_access_request(OTHER_PAYER, "/a", None, Some("1000"))
+        .await
+        .unwrap();
+    assert!(!result.should_serve_content);
+    assert_eq!(result.http_status, 403);
+
+    // flagged payers are refused as well
+    challenge(&blocking, PAYER, "/a").await;
+    let result = blocking
+        .handle_access_request(PAYER, "/b", None, Some("1000"))
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 403);
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "authz")]
+
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use x402_sdk::authz::{
+    AuthzRequest, AuthzResponse, AuthzServer, NONCE_HEADER, ORIGINAL_METHOD_HEADER,
+    ORIGINAL_URI_HEADER, PAYER_HEADER,
+};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::crypto::Ed25519Suite;
+use x402_sdk::headers::WWW_AUTHENTICATE_HEADER;
+use x402_sdk::keys::{JWKS_PATH, JwkSet, KeyRing};
+use x402_sdk::testing::mock_engine;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn engine() -> X402 {
+    mock_engine(ConfigBuilder::new().build()).0
+}
+
+fn get(target: &str) -> AuthzRequest {
+    AuthzRequest {
+        method: "GET".to_string(),
+        target: target.to_string(),
+        ..Default::default()
+    }
+}
+
+/// nonce of the challenge in a denied response
+fn challenge_nonce(response: &AuthzResponse) -> String {
+    let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+    body["payment_required"]["nonce"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+fn header<'a>(response: &'a AuthzResponse, name: &str) -> Option<&'a str> {
+    response
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+#[tokio::test]
+async fn the_signing_keys_are_published() {
+    let key_ring = KeyRing::new(0);
+    key_ring.add_key("2026-01", Ed25519Suite::new([7; 32]), 0, None);
+    let server = AuthzServer::new(Arc::new(engine().with_key_ring(Arc::new(key_ring))));
+
+    let response = server.authorize(&get(JWKS_PATH)).await;
+    assert_eq!(response.status, 200);
+    assert!(
+        response
+            .headers
+            .iter()
+            .any(|(name, value)| name == "Content-Type" && value == "application/json")
+    );
+    let jwks: JwkSet = serde_json::from_slice(&response.body).unwrap();
+    assert_eq!(jwks.keys[0].kid, "2026-01");
+}
+
+#[tokio::test]
+async fn no_keys_are_published_without_a_key_ring() {
+    let server = AuthzServer::new(Arc::new(engine()));
+    assert_eq!(server.authorize(&get(JWKS_PATH)).await.status, 404);
+}
+
+#[tokio::test]
+async fn proxied_requests_of_the_key_path_are_authorized() {
+    let server = AuthzServer::new(Arc::new(engine()));
+    let mut request = get("/auth");
+    request
+        .headers
+        .push((ORIGINAL_URI_HEADER.to_string(), JWKS_PATH.to_string()));
+    // no payer, the original request needs a payment like any other
+    assert_eq!(server.authorize(&request).await.status, 402);
+}
+
+#[tokio::test]
+async fn requests_without_a_payer_are_denied() {
+    let server = AuthzServer::new(Arc::new(engine()));
+    let response = server.authorize(&get("/premium")).await;
+    assert_eq!(response.status, 402);
+    assert!(
+        String::from_utf8(response.body)
+            .unwrap()
+            .contains(PAYER_HEADER)
+    );
+}
+
+#[tokio::test]
+async fn paid_challenges_let_the_original_request_through() {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let server = AuthzServer::new(Arc::new(engine));
+    let mut request = get("/auth");
+    request.headers = vec![
+        (ORIGINAL_METHOD_HEADER.to_string(), "POST".to_string()),
+        (
+            ORIGINAL_URI_HEADER.to_string(),
+            "/premium?page=2".to_string(),
+        ),
+        (PAYER_HEADER.to_string(), PAYER.to_string()),
+    ];
+
+    let response = server.authorize(&request).await;
+    assert_eq!(response.status, 402);
+    let nonce = challenge_nonce(&response);
+
+    request
+        .headers
+        .push((NONCE_HEADER.to_string(), nonce.clone()));
+    let response = server.authorize(&request).await;
+    assert_eq!(response.status, 402);
+
+    verifier.set_paid_amount(Some(u128::MAX));
+    let response = server.authorize(&request).await;
+    assert_eq!(response.status, 200);
+    assert!(response.body.is_empty());
+    assert_eq!(verifier.verified_requests().last().unwrap().nonce, nonce);
+}
+
+#[tokio::test]
+async fn the_path_prefix_is_stripped_from_check_requests() {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let server = AuthzServer::new(Arc::new(engine)).with_path_prefix("/x402-authz/");
+    let mut request = get("/x402-authz/premium");
+    request
+        .headers
+        .push((PAYER_HEADER.to_string(), PAYER.to_string()));
+    let nonce = challenge_nonce(&server.authorize(&request).await);
+
+    // the session belongs to the stripped path, as the proxy forwards it
+    verifier.set_paid_amount(Some(u128::MAX));
+    let mut proxied = get("/auth");
+    proxied.headers = vec![
+        (ORIGINAL_URI_HEADER.to_string(), "/premium".to_string()),
+        (PAYER_HEADER.to_string(), PAYER.to_string()),
+        (NONCE_HEADER.to_string(), nonce),
+    ];
+    assert_eq!(server.authorize(&proxied).await.status, 200);
+}
+
+#[tokio::test]
+async fn nginx_setups_deny_with_a_challenge_header() {
+    let server = AuthzServer::new(Arc::new(engine()))
+        .with_denied_status(401)
+        .with_www_authenticate("premium-api");
+    let mut request = get("/premium");
+    request
+        .headers
+        .push((PAYER_HEADER.to_string(), PAYER.to_string()));
+
+    let response = server.authorize(&request).await;
+    assert_eq!(response.status, 401);
+    let challenge = header(&response, WWW_AUTHENTICATE_HEADER).unwrap();
+    assert!(challenge.starts_with("X402 "));
+    assert!(challenge.contains("premium-api"));
+    assert!(challenge.contains(&challenge_nonce(&response)));
+}
+
+#[tokio::test]
+async fn the_server_answers_over_http() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(AuthzServer::new(Arc::new(engine())).serve(listener));
+
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    let request = format!(
+        "GET /premium HTTP/1.1\r\nHost: authz\r\n{}: {}\r\nContent-Length: 2\r\n\r\n{{}}",
+        PAYER_HEADER, PAYER
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 402 Payment Required\r\n"));
+    assert!(response.contains("payment_required"));
+
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream.write_all(b"garbage\r\n\r\n").await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+}
@@ -0,0 +1,158 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use x402_sdk::cache::{
+    CACHE_CONTROL_HEADER, CloudflareSignedUrls, EdgeToken, EdgeTokenError, EdgeTokenIssuer,
+    VARY_HEADER, cache_headers, edge_token, entitlement_cache_control,
+};
+use x402_sdk::receipt::{RECEIPT_HEADER, Receipt};
+use x402_sdk::resource::Resource;
+use x402_sdk::types::VerificationResult;
+
+const NOW: u64 = 1_700_000_000;
+
+fn receipt(resource: &str, expires_at: u64) -> Receipt {
+    Receipt {
+        receipt_id: "r-1".to_string(),
+        nonce: "nonce-1".to_string(),
+        payer: "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5".to_string(),
+        resource: resource.to_string(),
+        chain_id: "1".to_string(),
+        amount: "1000".to_string(),
+        transaction_hash: None,
+        issued_at: NOW,
+        expires_at,
+        invoice_id: None,
+        signature: None,
+    }
+}
+
+fn result(
+    should_serve_content: bool,
+    http_status: u16,
+    receipt: Option<Receipt>,
+) -> VerificationResult {
+    VerificationResult {
+        should_serve_content,
+        http_status,
+        x402_response: None,
+        verification: None,
+        receipt,
+        retry_after: None,
+    }
+}
+
+fn cache_control(result: &VerificationResult) -> String {
+    let headers = cache_headers(result, NOW);
+    assert!(headers.contains(&(VARY_HEADER.to_string(), RECEIPT_HEADER.to_string())));
+    headers
+        .into_iter()
+        .find(|(name, _)| name == CACHE_CONTROL_HEADER)
+        .unwrap()
+        .1
+}
+
+fn canonical(target: &str) -> String {
+    Resource::new("GET", target).unwrap().canonical()
+}
+
+#[test]
+fn unpaid_responses_are_never_stored() {
+    assert_eq!(cache_control(&result(false, 402, None)), "no-store");
+    assert_eq!(cache_control(&result(false, 403, None)), "no-store");
+    // even when a receipt was presented
+    let receipt = receipt(&canonical("/videos/1.mp4"), NOW + 600);
+    assert_eq!(
+        cache_control(&result(false, 403, Some(receipt))),
+        "no-store"
+    );
+    // grants without a receipt are revalidated every time
+    assert_eq!(cache_control(&result(true, 200, None)), "private, no-cache");
+}
+
+#[test]
+fn max_age_stops_at_the_entitlement_expiry() {
+    assert_eq!(
+        entitlement_cache_control(NOW + 600, NOW),
+        "private, max-age=600"
+    );
+    assert_eq!(
+        entitlement_cache_control(NOW + 1, NOW),
+        "private, max-age=1"
+    );
+    assert_eq!(entitlement_cache_control(NOW, NOW), "private, no-store");
+    assert_eq!(
+        entitlement_cache_control(NOW - 10, NOW),
+        "private, no-store"
+    );
+
+    let receipt = receipt(&canonical("/videos/1.mp4"), NOW + 90);
+    assert_eq!(
+        cache_control(&result(true, 200, Some(receipt))),
+        "private, max-age=90"
+    );
+}
+
+#[test]
+fn cloudflare_urls_carry_the_hmac_of_path_and_expiry() {
+    let issuer = CloudflareSignedUrls::new("https://cdn.example.com/", "edge-secret");
+    let resource = Resource::new("GET", "/videos/1.mp4").unwrap();
+    let EdgeToken::SignedUrl(url) = issuer.issue(&resource, NOW + 600).unwrap() else {
+        panic!("Cloudflare issues signed URLs");
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"edge-secret").unwrap();
+    mac.update(b"/videos/1.mp41700000600");
+    let token = format!(
+        "1700000600-{}",
+        STANDARD.encode(mac.finalize().into_bytes())
+    );
+    let expected = format!(
+        "https://cdn.example.com/videos/1.mp4?verify={}",
+        url::form_urlencoded::byte_serialize(token.as_bytes()).collect::<String>()
+    );
+    assert_eq!(url, expected);
+
+    // the query is kept, the token goes after it under the configured name
+    let resource = Resource::new("GET", "/videos/1.mp4?quality=hd").unwrap();
+    let EdgeToken::SignedUrl(url) = issuer
+        .with_param("token")
+        .issue(&resource, NOW + 600)
+        .unwrap()
+    else {
+        panic!("Cloudflare issues signed URLs");
+    };
+    let (prefix, signed) = url.split_once("&token=").unwrap();
+    assert_eq!(prefix, "https://cdn.example.com/videos/1.mp4?quality=hd");
+    // only the path is signed
+    assert_eq!(signed, expected.split_once("?verify=").unwrap().1);
+}
+
+#[test]
+fn edge_tokens_are_issued_for_granted_receipts_only() {
+    let issuer = CloudflareSignedUrls::new("https://cdn.example.com", "edge-secret");
+    let paid = receipt(&canonical("/videos/1.mp4"), NOW + 600);
+
+    let token = edge_token(&issuer, &result(true, 200, Some(paid.clone())))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        token,
+        issuer
+            .issue(&Resource::new("GET", "/videos/1.mp4").unwrap(), NOW + 600)
+            .unwrap()
+    );
+    assert_eq!(
+        edge_token(&issuer, &result(false, 403, Some(paid))).unwrap(),
+        None
+    );
+    assert_eq!(edge_token(&issuer, &result(true, 200, None)).unwrap(), None);
+
+    // a resource repeating a query key is refused
+    let tampered = receipt("GET /videos/1.mp4?a=1&a=2", NOW + 600);
+    assert!(matches!(
+        edge_token(&issuer, &result(true, 200, Some(tampered))),
+        Err(EdgeTokenError::InvalidResource(_))
+    ));
+}
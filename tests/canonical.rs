@@ -0,0 +1,131 @@
+use serde_json::{Value, json};
+use x402_sdk::canonical::{to_canonical_string, to_canonical_vec};
+use x402_sdk::context::RequestContext;
+use x402_sdk::events::{PaymentEvent, PaymentEventKind};
+use x402_sdk::outbox::webhook_body;
+use x402_sdk::resource::Resource;
+use x402_sdk::signing::ChallengeSigner;
+use x402_sdk::store::OutboxEntry;
+
+fn canonical(input: &str) -> String {
+    to_canonical_string(&serde_json::from_str::<Value>(input).unwrap()).unwrap()
+}
+
+/// RFC 8785 section 3.2.2
+#[test]
+fn rfc_8785_example() {
+    let input = r#"{
+        "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+        "literals": [null, true, false]
+    }"#;
+    assert_eq!(
+        canonical(input),
+        r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+    );
+}
+
+/// RFC 8785 section 3.2.3, keys sorted by UTF-16 code units
+#[test]
+fn keys_are_sorted_by_utf16_code_units() {
+    let input = r#"{
+        "€": "Euro Sign",
+        "\r": "Carriage Return",
+        "דּ": "Hebrew Letter Dalet With Dagesh",
+        "1": "One",
+        "😀": "Emoji: Grinning Face",
+        "\u0080": "Control",
+        "ö": "Latin Small Letter O With Diaeresis"
+    }"#;
+    let value: Value = serde_json::from_str(&canonical(input)).unwrap();
+    let expected = [
+        "Carriage Return",
+        "One",
+        "Control",
+        "Latin Small Letter O With Diaeresis",
+        "Euro Sign",
+        "Emoji: Grinning Face",
+        "Hebrew Letter Dalet With Dagesh",
+    ];
+    let output = canonical(input);
+    let positions: Vec<usize> = expected
+        .iter()
+        .map(|name| output.find(name).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(value.as_object().unwrap().len(), expected.len());
+}
+
+/// RFC 8785 appendix B
+#[test]
+fn numbers_follow_ecmascript() {
+    let vectors = [
+        ("0", "0"),
+        ("-0.0", "0"),
+        ("1e21", "1e+21"),
+        ("1e20", "100000000000000000000"),
+        ("4.5", "4.5"),
+        ("0.000001", "0.000001"),
+        ("1e-7", "1e-7"),
+        ("-1.5e-7", "-1.5e-7"),
+        ("9007199254740992", "9007199254740992"),
+        ("9007199254740993", "9007199254740992"),
+        ("-9007199254740993", "-9007199254740992"),
+        ("18446744073709551615", "18446744073709552000"),
+        ("295147905179352830000", "295147905179352830000"),
+        ("1.7976931348623157e308", "1.7976931348623157e+308"),
+        ("5e-324", "5e-324"),
+    ];
+    for (input, expected) in vectors {
+        assert_eq!(canonical(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn encoding_ignores_source_formatting() {
+    let compact = canonical(r#"{"b":{"y":1,"x":[true]},"a":"A"}"#);
+    let spaced = canonical("{ \"a\" : \"A\",\n \"b\" : { \"x\" : [ true ], \"y\" : 1.0 } }");
+    assert_eq!(compact, r#"{"a":"A","b":{"x":[true],"y":1}}"#);
+    assert_eq!(compact, spaced);
+}
+
+#[test]
+fn signatures_cover_the_canonical_encoding() {
+    let signer = ChallengeSigner::from_private_key(
+        "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+    )
+    .unwrap();
+    let reordered: Value = serde_json::from_str(r#"{"amount":"1000","payer":"0xabc"}"#).unwrap();
+    let original = json!({"payer": "0xabc", "amount": "1000"});
+    assert_eq!(
+        signer.sign_payload(b"test:", &original).unwrap().signature,
+        signer.sign_payload(b"test:", &reordered).unwrap().signature
+    );
+    assert_eq!(
+        to_canonical_vec(&original).unwrap(),
+        br#"{"amount":"1000","payer":"0xabc"}"#
+    );
+}
+
+#[test]
+fn webhook_bodies_are_canonical() {
+    let entry = OutboxEntry {
+        id: "evt-1".to_string(),
+        event: PaymentEvent {
+            timestamp: 1,
+            user_address: "0xabc".to_string(),
+            resource: Resource::new("GET", "/premium").unwrap(),
+            context: RequestContext::default(),
+            kind: PaymentEventKind::AccessGranted,
+        },
+        attempts: 0,
+        next_attempt_at: 0,
+        last_error: None,
+        failed: false,
+        created_at: 0,
+    };
+    let body = webhook_body(&entry).unwrap();
+    assert!(body.starts_with(br#"{"event":{"context":"#));
+    let reparsed: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(to_canonical_vec(&reparsed).unwrap(), body);
+}
@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+use x402_sdk::config::{CircuitBreakerConfig, ConfigBuilder};
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+use x402_sdk::types::{ChainType, PaymentRequest, PaymentVerification};
+use x402_sdk::verifier::breaker::{CircuitBreakerVerifier, CircuitState};
+use x402_sdk::verifier::{PaymentVerifier, VerificationError};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const NOW: u64 = 1_700_000_000;
+
+const UP: u8 = 0;
+const DOWN: u8 = 1;
+const REFUSING: u8 = 2;
+
+/// Verifier whose RPC can be taken down, counting the calls reaching it.
+#[derive(Clone, Default)]
+struct FlakyVerifier {
+    inner: MockVerifier,
+    mode: Arc<AtomicU8>,
+    calls: Arc<AtomicU32>,
+}
+
+impl FlakyVerifier {
+    fn set_mode(&self, mode: u8) {
+        self.mode.store(mode, Ordering::SeqCst);
+    }
+
+    fn calls(&self) -> u32 {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl PaymentVerifier for FlakyVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match self.mode.load(Ordering::SeqCst) {
+            DOWN => Err(VerificationError::RpcError("502 Bad Gateway".to_string())),
+            REFUSING => Err(VerificationError::InsufficientAmount),
+            _ => {
+                self.inner
+                    .verify_payment(payment_request, payer_address)
+                    .await
+            }
+        }
+    }
+
+    fn supports_chain(&self, chain_type: &ChainType) -> bool {
+        self.inner.supports_chain(chain_type)
+    }
+}
+
+fn breaker(verifier: &FlakyVerifier, clock: &MockClock) -> CircuitBreakerVerifier {
+    CircuitBreakerVerifier::new(
+        Box::new(verifier.clone()),
+        CircuitBreakerConfig {
+            failure_threshold: 3,
+            open_secs: 30,
+        },
+        Arc::new(clock.clone()),
+    )
+}
+
+async fn payment_request() -> PaymentRequest {
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+}
+
+#[tokio::test]
+async fn repeated_rpc_failures_open_the_circuit() {
+    let clock = MockClock::new(NOW);
+    let verifier = FlakyVerifier::default();
+    let breaker = breaker(&verifier, &clock);
+    let request = payment_request().await;
+
+    verifier.set_mode(DOWN);
+    for _ in 0..3 {
+        assert!(matches!(
+            breaker.verify_payment(&request, PAYER).await,
+            Err(VerificationError::RpcError(_))
+        ));
+    }
+    assert_eq!(
+        breaker.status().state,
+        CircuitState::Open { until: NOW + 30 }
+    );
+
+    // failing fast, the RPC is left alone
+    clock.advance(10);
+    assert_eq!(
+        breaker.verify_payment(&request, PAYER).await.unwrap_err(),
+        VerificationError::CircuitOpen { retry_after: 20 }
+    );
+    assert_eq!(verifier.calls(), 3);
+    assert_eq!(breaker.status().rejected, 1);
+
+    // one probe once the circuit times out, its success closes the circuit
+    clock.advance(20);
+    verifier.set_mode(UP);
+    breaker.verify_payment(&request, PAYER).await.unwrap();
+    let status = breaker.status();
+    assert_eq!(status.state, CircuitState::Closed);
+    assert_eq!(status.consecutive_failures, 0);
+    assert_eq!(status.times_opened, 1);
+}
+
+#[tokio::test]
+async fn a_failed_probe_opens_the_circuit_again() {
+    let clock = MockClock::new(NOW);
+    let verifier = FlakyVerifier::default();
+    let breaker = breaker(&verifier, &clock);
+    let request = payment_request().await;
+
+    verifier.set_mode(DOWN);
+    for _ in 0..3 {
+        let _ = breaker.verify_payment(&request, PAYER).await;
+    }
+    clock.advance(30);
+    assert!(breaker.verify_payment(&request, PAYER).await.is_err());
+    let status = breaker.status();
+    assert_eq!(status.state, CircuitState::Open { until: NOW + 60 });
+    assert_eq!(status.times_opened, 2);
+    assert_eq!(verifier.calls(), 4);
+}
+
+#[tokio::test]
+async fn refused_payments_do_not_count_as_rpc_failures() {
+    let clock = MockClock::new(NOW);
+    let verifier = FlakyVerifier::default();
+    let breaker = breaker(&verifier, &clock);
+    let request = payment_request().await;
+
+    verifier.set_mode(REFUSING);
+    for _ in 0..5 {
+        assert_eq!(
+            breaker.verify_payment(&request, PAYER).await.unwrap_err(),
+            VerificationError::InsufficientAmount
+        );
+    }
+    assert_eq!(breaker.status().state, CircuitState::Closed);
+
+    // failures have to be consecutive
+    verifier.set_mode(DOWN);
+    let _ = breaker.verify_payment(&request, PAYER).await;
+    let _ = breaker.verify_payment(&request, PAYER).await;
+    verifier.set_mode(UP);
+    breaker.verify_payment(&request, PAYER).await.unwrap();
+    verifier.set_mode(DOWN);
+    let _ = breaker.verify_payment(&request, PAYER).await;
+    assert_eq!(breaker.status().state, CircuitState::Closed);
+    assert_eq!(breaker.status().consecutive_failures, 1);
+}
+
+#[tokio::test]
+async fn an_open_circuit_answers_503_with_retry_after() {
+    let clock = MockClock::new(NOW);
+    let verifier = FlakyVerifier::default();
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    let mut engine = engine.with_clock(Arc::new(clock.clone()));
+    engine
+        .verifier_registry_mut()
+        .register_verifier(ChainType::ethereum(), Box::new(breaker(&verifier, &clock)));
+
+    let nonce = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce;
+    verifier.set_mode(DOWN);
+    for _ in 0..3 {
+        let _ = engine
+            .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+            .await;
+    }
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 503);
+    assert_eq!(result.retry_after, Some(30));
+
+    let health = engine.chain_health();
+    assert_eq!(
+        health[&ChainType::ethereum()].state,
+        CircuitState::Open { until: NOW + 30 }
+    );
+}
+
+#[cfg(feature = "native")]
+#[tokio::test]
+async fn solana_rpc_failures_open_the_circuit() {
+    use x402_sdk::verifier::solana::SolanaVerifier;
+
+    // a port nothing listens on
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let rpc_url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let clock = MockClock::new(NOW);
+    let breaker = CircuitBreakerVerifier::new(
+        Box::new(SolanaVerifier::new().with_rpc_url(&rpc_url)),
+        CircuitBreakerConfig {
+            failure_threshold: 2,
+            open_secs: 30,
+        },
+        Arc::new(clock.clone()),
+    );
+    let mut request = payment_request().await;
+    request.recipient = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T".to_string();
+    let payer = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    for _ in 0..2 {
+        assert!(matches!(
+            breaker.verify_payment(&request, payer).await,
+            Err(VerificationError::RpcError(_))
+        ));
+    }
+    assert_eq!(
+        breaker.status().state,
+        CircuitState::Open { until: NOW + 30 }
+    );
+}
@@ -0,0 +1,227 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use x402_sdk::anomaly::StaticScreeningList;
+use x402_sdk::compliance::{
+    ComplianceError, ComplianceScreen, HttpComplianceScreen, ScreeningApi, ScreeningFailureMode,
+    ScreeningOutcome,
+};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::events::{EventListener, PaymentEvent, PaymentEventKind};
+use x402_sdk::testing::mock_engine;
+use x402_sdk::types::{ChainType, VerificationResult};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+#[derive(Default)]
+struct RecordingListener {
+    events: Mutex<Vec<PaymentEventKind>>,
+}
+
+impl EventListener for RecordingListener {
+    fn on_event(&self, event: &PaymentEvent) {
+        self.events.lock().unwrap().push(event.kind.clone());
+    }
+}
+
+/// Screen whose provider is down.
+struct UnavailableScreen;
+
+#[async_trait]
+impl ComplianceScreen for UnavailableScreen {
+    async fn screen(
+        &self,
+        _address: &str,
+        _chain_type: &ChainType,
+    ) -> Result<ScreeningOutcome, ComplianceError> {
+        Err(ComplianceError::RequestFailed("timed out".to_string()))
+    }
+}
+
+/// pay for a resource with the screen in place
+async fn pay(
+    screen: Arc<dyn ComplianceScreen>,
+    failure_mode: ScreeningFailureMode,
+    listener: Arc<RecordingListener>,
+) -> VerificationResult {
+    let config = ConfigBuilder::new()
+        .with_screening_failure_mode(failure_mode)
+        .build();
+    let (engine, verifier) = mock_engine(config);
+    let engine: X402 = engine
+        .with_compliance_screen(screen)
+        .with_event_listener(listener);
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    let nonce = result.x402_response.unwrap().payment_required.nonce;
+    verifier.set_paid_amount(Some(1000));
+    engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap()
+}
+
+/// Screening API answering every request with `status` and `body`.
+async fn fake_api(status: u16, body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            // complete once the head and the announced body are in
+            loop {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, rest)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if rest.len() >= length {
+                        break;
+                    }
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn flagged_payers_are_denied_the_content() {
+    let listener = Arc::new(RecordingListener::default());
+    let screen = StaticScreeningList::new().with_address(&PAYER.to_lowercase(), "OFAC SDN");
+    let result = pay(
+        Arc::new(screen),
+        ScreeningFailureMode::FailOpen,
+        listener.clone(),
+    )
+    .await;
+    assert!(!result.should_serve_content);
+    assert_eq!(result.http_status, 403);
+    assert!(result.receipt.is_none());
+
+    let events = listener.events.lock().unwrap();
+    assert!(events.iter().any(|kind| matches!(
+        kind,
+        PaymentEventKind::PaymentDenied { reason, .. } if reason == "payer screened: OFAC SDN"
+    )));
+    assert!(
+        !events
+            .iter()
+            .any(|kind| matches!(kind, PaymentEventKind::PaymentVerified { .. }))
+    );
+}
+
+#[tokio::test]
+async fn clear_payers_are_served() {
+    let listener = Arc::new(RecordingListener::default());
+    let screen = StaticScreeningList::new().with_address("0xdead", "OFAC SDN");
+    let result = pay(Arc::new(screen), ScreeningFailureMode::FailClosed, listener).await;
+    assert!(result.should_serve_content);
+}
+
+#[tokio::test]
+async fn an_unavailable_screen_fails_closed_by_default() {
+    let listener = Arc::new(RecordingListener::default());
+    let result = pay(
+        Arc::new(UnavailableScreen),
+        ScreeningFailureMode::default(),
+        listener.clone(),
+    )
+    .await;
+    assert!(!result.should_serve_content);
+    assert_eq!(result.http_status, 403);
+    assert!(listener.events.lock().unwrap().iter().any(|kind| matches!(
+        kind,
+        PaymentEventKind::PaymentDenied { reason, .. } if reason.contains("timed out")
+    )));
+}
+
+#[tokio::test]
+async fn an_unavailable_screen_can_fail_open() {
+    let listener = Arc::new(RecordingListener::default());
+    let result = pay(
+        Arc::new(UnavailableScreen),
+        ScreeningFailureMode::FailOpen,
+        listener,
+    )
+    .await;
+    assert!(result.should_serve_content);
+}
+
+#[tokio::test]
+async fn hosted_screens_parse_the_provider_answer() {
+    let chain = ChainType::ethereum();
+
+    let url = fake_api(
+        200,
+        r#"{"identifications":[{"category":"sanctions","name":"SANCTIONS: OFAC SDN"}]}"#,
+    )
+    .await;
+    let screen = HttpComplianceScreen::new(ScreeningApi::Chainalysis, &url, "key");
+    assert_eq!(
+        screen.screen(PAYER, &chain).await.unwrap(),
+        ScreeningOutcome::Blocked {
+            reason: "SANCTIONS: OFAC SDN".to_string()
+        }
+    );
+    let url = fake_api(200, r#"{"identifications":[]}"#).await;
+    let screen = HttpComplianceScreen::new(ScreeningApi::Chainalysis, &url, "key");
+    assert_eq!(
+        screen.screen(PAYER, &chain).await.unwrap(),
+        ScreeningOutcome::Clear
+    );
+
+    let url = fake_api(200, r#"[{"address":"0x742e","isSanctioned":true}]"#).await;
+    let screen = HttpComplianceScreen::new(ScreeningApi::Trm, &url, "key");
+    assert!(matches!(
+        screen.screen(PAYER, &chain).await.unwrap(),
+        ScreeningOutcome::Blocked { .. }
+    ));
+    let url = fake_api(200, "[]").await;
+    let screen = HttpComplianceScreen::new(ScreeningApi::Trm, &url, "key");
+    assert!(matches!(
+        screen.screen(PAYER, &chain).await,
+        Err(ComplianceError::InvalidResponse(_))
+    ));
+}
+
+#[tokio::test]
+async fn hosted_screen_errors_follow_the_failure_mode() {
+    let url = fake_api(503, "").await;
+    let screen: Arc<dyn ComplianceScreen> = Arc::new(HttpComplianceScreen::new(
+        ScreeningApi::Chainalysis,
+        &url,
+        "key",
+    ));
+    assert!(matches!(
+        screen.screen(PAYER, &ChainType::ethereum()).await,
+        Err(ComplianceError::RequestFailed(_))
+    ));
+
+    let listener = Arc::new(RecordingListener::default());
+    let result = pay(screen.clone(), ScreeningFailureMode::FailClosed, listener).await;
+    assert_eq!(result.http_status, 403);
+    let listener = Arc::new(RecordingListener::default());
+    let result = pay(screen, ScreeningFailureMode::FailOpen, listener).await;
+    assert!(result.should_serve_content);
+}
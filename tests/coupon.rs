@@ -0,0 +1,192 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::context::RequestContext;
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::coupon::{Coupon, CouponBook, CouponError, Discount};
+use x402_sdk::resource::Resource;
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+use x402_sdk::types::VerificationResult;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const OTHER_PAYER: &str = "0x8ba1f109551bD432803012645Ac136ddd64DBA72";
+
+fn engine(coupons: Arc<CouponBook>, clock: Arc<MockClock>) -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(
+        ConfigBuilder::new()
+            .with_expiration_time(600)
+            .with_expiry_grace(30)
+            .build(),
+    );
+    (engine.with_coupons(coupons).with_clock(clock), verifier)
+}
+
+async fn request(
+    engine: &X402,
+    payer: &str,
+    nonce: Option<&str>,
+    coupon: &str,
+) -> Result<VerificationResult, EngineError> {
+    engine
+        .handle_access_request_with_coupon(
+            payer,
+            "/premium",
+            nonce,
+            Some("5000"),
+            Some(coupon),
+            &RequestContext::default(),
+        )
+        .await
+}
+
+#[test]
+fn discounts_round_in_favour_of_the_payer() {
+    assert_eq!(Discount::Percent(50).apply(5001), 2500);
+    assert_eq!(Discount::Percent(100).apply(5000), 0);
+    assert_eq!(Discount::Fixed(6000).apply(5000), 0);
+}
+
+#[test]
+fn coupons_check_their_window_and_routes() {
+    let coupons = CouponBook::new().with_coupon(
+        Coupon::new("DOCS", Discount::Fixed(500))
+            .with_validity(100, 200)
+            .with_route("/docs/**"),
+    );
+    let docs = Resource::new("GET", "/docs/intro").unwrap();
+    assert_eq!(
+        coupons.apply("DOCS", &docs, "5000", 99),
+        Err(CouponError::NotYetValid)
+    );
+    assert_eq!(coupons.apply("DOCS", &docs, "5000", 100).unwrap(), "4500");
+    assert_eq!(
+        coupons.apply("DOCS", &docs, "5000", 200),
+        Err(CouponError::Expired)
+    );
+    assert_eq!(
+        coupons.apply(
+            "DOCS",
+            &Resource::new("GET", "/premium").unwrap(),
+            "5000",
+            150
+        ),
+        Err(CouponError::NotApplicable)
+    );
+    assert_eq!(
+        coupons.apply("NOPE", &docs, "5000", 150),
+        Err(CouponError::Unknown("NOPE".to_string()))
+    );
+}
+
+#[test]
+fn reservations_hold_redemptions_until_released() {
+    let coupons = CouponBook::new()
+        .with_coupon(Coupon::new("LAUNCH", Discount::Percent(50)).with_max_redemptions(1));
+    let resource = Resource::new("GET", "/premium").unwrap();
+    coupons.reserve("LAUNCH", "one", 100, Some(200)).unwrap();
+    // reserving again for the same session keeps its slot
+    coupons.reserve("LAUNCH", "one", 110, Some(300)).unwrap();
+    assert_eq!(
+        coupons.apply("LAUNCH", &resource, "5000", 120),
+        Err(CouponError::Exhausted)
+    );
+    assert_eq!(
+        coupons.reserve("LAUNCH", "two", 120, Some(300)),
+        Err(CouponError::Exhausted)
+    );
+
+    coupons.release("LAUNCH", "one");
+    coupons.reserve("LAUNCH", "two", 130, Some(300)).unwrap();
+    assert_eq!(coupons.redeem_reserved("LAUNCH", "two", 140), Ok(1));
+    assert_eq!(coupons.reservations("LAUNCH"), Some(0));
+    assert_eq!(coupons.redeem("LAUNCH"), Err(CouponError::Exhausted));
+    assert_eq!(coupons.redemptions("LAUNCH"), Some(1));
+}
+
+#[test]
+fn expired_reservations_free_their_redemption() {
+    let coupons = CouponBook::new()
+        .with_coupon(Coupon::new("LAUNCH", Discount::Percent(50)).with_max_redemptions(1));
+    coupons.reserve("LAUNCH", "one", 100, Some(200)).unwrap();
+    assert_eq!(
+        coupons.reserve("LAUNCH", "two", 199, Some(400)),
+        Err(CouponError::Exhausted)
+    );
+    coupons.reserve("LAUNCH", "two", 200, Some(400)).unwrap();
+    assert_eq!(coupons.reservations("LAUNCH"), Some(1));
+    // the released session no longer redeems past the limit
+    assert_eq!(
+        coupons.redeem_reserved("LAUNCH", "one", 210),
+        Err(CouponError::Exhausted)
+    );
+}
+
+#[tokio::test]
+async fn a_single_use_coupon_discounts_one_open_session() {
+    let coupons = Arc::new(
+        CouponBook::new()
+            .with_coupon(Coupon::new("LAUNCH", Discount::Percent(50)).with_max_redemptions(1)),
+    );
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, verifier) = engine(coupons.clone(), clock);
+
+    let challenge = request(&engine, PAYER, None, "LAUNCH").await.unwrap();
+    let payment_request = challenge.x402_response.unwrap().payment_required;
+    assert_eq!(payment_request.amount, "2500");
+    assert_eq!(coupons.reservations("LAUNCH"), Some(1));
+    assert!(matches!(
+        request(&engine, OTHER_PAYER, None, "LAUNCH").await,
+        Err(EngineError::CouponError(CouponError::Exhausted))
+    ));
+
+    verifier.set_paid_amount(Some(2500));
+    let result = request(&engine, PAYER, Some(&payment_request.nonce), "LAUNCH")
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+    assert_eq!(coupons.redemptions("LAUNCH"), Some(1));
+    assert_eq!(coupons.reservations("LAUNCH"), Some(0));
+    assert!(matches!(
+        request(&engine, OTHER_PAYER, None, "LAUNCH").await,
+        Err(EngineError::CouponError(CouponError::Exhausted))
+    ));
+}
+
+#[tokio::test]
+async fn expired_sessions_give_their_coupon_back() {
+    let coupons = Arc::new(
+        CouponBook::new()
+            .with_coupon(Coupon::new("LAUNCH", Discount::Percent(50)).with_max_redemptions(1)),
+    );
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, _verifier) = engine(coupons.clone(), clock.clone());
+    request(&engine, PAYER, None, "LAUNCH").await.unwrap();
+
+    // still payable during the grace period
+    clock.set(1_630);
+    assert!(request(&engine, OTHER_PAYER, None, "LAUNCH").await.is_err());
+    clock.set(1_631);
+    let challenge = request(&engine, OTHER_PAYER, None, "LAUNCH").await.unwrap();
+    assert_eq!(
+        challenge.x402_response.unwrap().payment_required.amount,
+        "2500"
+    );
+    assert_eq!(coupons.redemptions("LAUNCH"), Some(0));
+}
+
+#[tokio::test]
+async fn full_discounts_grant_access_within_the_limit() {
+    let coupons = Arc::new(
+        CouponBook::new()
+            .with_coupon(Coupon::new("FREE", Discount::Percent(100)).with_max_redemptions(1)),
+    );
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, _verifier) = engine(coupons.clone(), clock);
+
+    let result = request(&engine, PAYER, None, "FREE").await.unwrap();
+    assert!(result.should_serve_content);
+    assert_eq!(coupons.redemptions("FREE"), Some(1));
+    assert!(matches!(
+        request(&engine, OTHER_PAYER, None, "FREE").await,
+        Err(EngineError::CouponError(CouponError::Exhausted))
+    ));
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+use x402_sdk::core::X402;
+use x402_sdk::crypto::{
+    CryptoSuite, ED25519_ALGORITHM, Ed25519Suite, HMAC_SHA256_ALGORITHM, HmacSha256Suite,
+    SignatureSuite,
+};
+use x402_sdk::keys::KeyRing;
+use x402_sdk::outbox::{sign_webhook, verify_webhook};
+use x402_sdk::signing::{
+    CHALLENGE_DOMAIN, ChallengeSigner, SignatureError, sign_payload_with, verify_payload_with,
+};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const SECP256K1_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+fn suites() -> Vec<Arc<dyn CryptoSuite>> {
+    vec![
+        Arc::new(ChallengeSigner::from_private_key(SECP256K1_KEY).unwrap()),
+        Arc::new(Ed25519Suite::new([7; 32])),
+        Arc::new(HmacSha256Suite::new("webhooks-2026", b"shared secret")),
+    ]
+}
+
+#[test]
+fn every_suite_round_trips() {
+    let payload = serde_json::json!({"nonce": "n1", "amount": "1000"});
+    for suite in suites() {
+        let signature = sign_payload_with(suite.as_ref(), b"test:", &payload).unwrap();
+        assert_eq!(signature.algorithm, suite.algorithm());
+        assert_eq!(signature.signer, suite.signer());
+        verify_payload_with(suite.as_ref(), b"test:", &payload, &signature).unwrap();
+
+        let tampered = serde_json::json!({"nonce": "n1", "amount": "1"});
+        assert!(verify_payload_with(suite.as_ref(), b"test:", &tampered, &signature).is_err());
+        assert!(verify_payload_with(suite.as_ref(), b"other:", &payload, &signature).is_err());
+    }
+}
+
+#[test]
+fn signatures_of_another_suite_are_refused() {
+    let payload = serde_json::json!({"nonce": "n1"});
+    let ed25519 = Ed25519Suite::new([7; 32]);
+    let hmac = HmacSha256Suite::new("k", b"secret");
+    let signature = sign_payload_with(&ed25519, b"test:", &payload).unwrap();
+    assert!(matches!(
+        verify_payload_with(&hmac, b"test:", &payload, &signature),
+        Err(SignatureError::UnsupportedAlgorithm(alg)) if alg == ED25519_ALGORITHM
+    ));
+}
+
+#[test]
+fn secp256k1_suite_matches_the_challenge_signer() {
+    let signer = ChallengeSigner::from_private_key(SECP256K1_KEY).unwrap();
+    let payload = serde_json::json!({"nonce": "n1"});
+    assert_eq!(
+        signer.sign_payload(CHALLENGE_DOMAIN, &payload).unwrap(),
+        sign_payload_with(&signer, CHALLENGE_DOMAIN, &payload).unwrap()
+    );
+}
+
+#[test]
+fn key_ring_mixes_suites() {
+    let key_ring = KeyRing::new(60);
+    key_ring.add_key("2026-01", Ed25519Suite::new([1; 32]), 0, None);
+    key_ring.schedule_rotation(
+        "2026-02",
+        ChallengeSigner::from_private_key(SECP256K1_KEY).unwrap(),
+        100,
+    );
+
+    let payload = serde_json::json!({"nonce": "n1"});
+    let old = key_ring.sign_payload(b"test:", &payload, 50).unwrap();
+    assert_eq!(old.algorithm, ED25519_ALGORITHM);
+    let new = key_ring.sign_payload(b"test:", &payload, 100).unwrap();
+    key_ring
+        .verify_payload(b"test:", &payload, &old, 120)
+        .unwrap();
+    key_ring
+        .verify_payload(b"test:", &payload, &new, 120)
+        .unwrap();
+
+    let jwks = key_ring.jwks(120);
+    assert_eq!(jwks.find("2026-01").unwrap().crv, "Ed25519");
+    assert_eq!(jwks.find("2026-02").unwrap().crv, "secp256k1");
+}
+
+#[test]
+fn hmac_keys_are_not_published() {
+    let key_ring = KeyRing::new(0);
+    key_ring.add_key("hmac", HmacSha256Suite::new("hmac", b"secret"), 0, None);
+    assert!(key_ring.jwks(0).keys.is_empty());
+}
+
+#[test]
+fn suites_load_from_config_keys() {
+    let suite = SignatureSuite::HmacSha256.load("secret", "k1").unwrap();
+    assert_eq!(suite.algorithm(), HMAC_SHA256_ALGORITHM);
+    assert_eq!(suite.signer(), "k1");
+    let suite = SignatureSuite::Ed25519
+        .load(&format!("0x{}", "07".repeat(32)), "unused")
+        .unwrap();
+    assert_eq!(suite.signer(), Ed25519Suite::new([7; 32]).signer());
+    assert!(SignatureSuite::Ed25519.load("0x1234", "unused").is_err());
+    assert_eq!(
+        serde_json::to_string(&SignatureSuite::HmacSha256).unwrap(),
+        "\"hmac-sha256\""
+    );
+}
+
+#[test]
+fn webhook_signatures_cover_the_body() {
+    let suite = HmacSha256Suite::new("webhooks", b"secret");
+    let header = sign_webhook(&suite, br#"{"id":"evt-1"}"#).unwrap();
+    assert!(header.starts_with("hmac-sha256=0x"));
+    verify_webhook(&suite, br#"{"id":"evt-1"}"#, &header).unwrap();
+    assert!(verify_webhook(&suite, br#"{"id":"evt-2"}"#, &header).is_err());
+}
+
+#[tokio::test]
+async fn engine_signs_challenges_with_the_configured_suite() {
+    let suite: Arc<dyn CryptoSuite> = Arc::new(Ed25519Suite::new([9; 32]));
+    let engine = X402::from_default_config()
+        .unwrap()
+        .with_crypto_suite(suite.clone());
+    let response = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap();
+    let signature = response.signature.unwrap();
+    assert_eq!(signature.algorithm, ED25519_ALGORITHM);
+    assert_eq!(signature.kid.as_deref(), Some("default"));
+    verify_payload_with(
+        suite.as_ref(),
+        CHALLENGE_DOMAIN,
+        &response.payment_required,
+        &signature,
+    )
+    .unwrap();
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "devnet")]
+
+use x402_sdk::config::{ConfigBuilder, ConfigManager};
+use x402_sdk::core::X402;
+use x402_sdk::devnet::{ANVIL_RPC_URL, AutoPayer, DevnetError};
+use x402_sdk::types::{
+    ChainConfig, ChainType, Currency, EvmChain, PaymentMetadata, PaymentRequest, SolanaChain,
+};
+
+fn request(chain_type: ChainType, currency: Currency) -> PaymentRequest {
+    PaymentRequest {
+        amount: "1000".to_string(),
+        currency,
+        recipient: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+        chain: ChainConfig::new(chain_type, None),
+        description: None,
+        expires_at: None,
+        nonce: "nonce".to_string(),
+        beneficiary: None,
+        reference: None,
+        metadata: PaymentMetadata::new(),
+        quote: None,
+    }
+}
+
+#[tokio::test]
+async fn unsupported_challenges_fail_before_reaching_the_node() {
+    // nothing listens on the port, the requests are refused up front
+    let payer = AutoPayer::new("http://127.0.0.1:9").unwrap();
+    let solana = request(ChainType::Solana(SolanaChain::Mainnet), Currency::Native);
+    assert!(matches!(
+        payer.pay(&solana).await,
+        Err(DevnetError::Unsupported(_))
+    ));
+    let any_token = request(
+        ChainType::ethereum(),
+        Currency::AnyToken {
+            allowlist: Vec::new(),
+        },
+    );
+    assert!(matches!(
+        payer.pay(&any_token).await,
+        Err(DevnetError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn every_payer_gets_its_own_account() {
+    let first = AutoPayer::new(ANVIL_RPC_URL).unwrap();
+    let second = AutoPayer::new(ANVIL_RPC_URL).unwrap();
+    assert_ne!(first.address(), second.address());
+    assert_eq!(first.address().len(), 42);
+}
+
+#[tokio::test]
+#[ignore = "needs anvil listening on 127.0.0.1:8545"]
+async fn challenges_are_paid_on_anvil() {
+    let chain = ChainType::Evm(EvmChain::Custom("31337".to_string()));
+    let config = ConfigBuilder::new()
+        .with_chain(
+            chain.clone(),
+            ChainConfig::new(chain.clone(), Some(ANVIL_RPC_URL.to_string())),
+        )
+        .with_default_chain(chain.clone())
+        .build();
+    let mut engine = X402::new(ConfigManager::from_config(config)).unwrap();
+    engine
+        .register_chain_verifier(chain, ANVIL_RPC_URL.to_string())
+        .await
+        .unwrap();
+
+    let payer = AutoPayer::new(ANVIL_RPC_URL).unwrap();
+    let challenge = engine
+        .handle_access_request(&payer.address(), "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap();
+    payer.pay_challenge(&challenge).await.unwrap();
+
+    let nonce = challenge.payment_required.nonce;
+    let result = engine
+        .handle_access_request(&payer.address(), "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+}
@@ -0,0 +1,121 @@
+use serde_json::Value;
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::discovery::{DiscoveryDocument, X402_VERSION};
+use x402_sdk::pricing::{PricingRule, RulePricing};
+use x402_sdk::resource::ResourcePattern;
+use x402_sdk::testing::mock_engine;
+use x402_sdk::types::Currency;
+
+const TOKEN: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+fn engine(pricing: Option<RulePricing>) -> X402 {
+    let (engine, _verifier) = mock_engine(
+        ConfigBuilder::new()
+            .with_service_name("Example API")
+            .with_payment_amount("500")
+            .with_expiration_time(600)
+            .build(),
+    );
+    match pricing {
+        Some(pricing) => engine.with_pricing_provider(Arc::new(pricing)),
+        None => engine,
+    }
+}
+
+fn discovery(pricing: Option<RulePricing>) -> DiscoveryDocument {
+    engine(pricing).discovery_document().unwrap()
+}
+
+#[test]
+fn priced_resources_are_listed_with_their_payment_option() {
+    let document = discovery(Some(
+        RulePricing::new()
+            .with_rule(
+                PricingRule::new(ResourcePattern::parse("GET /reports/*"), "2000")
+                    .with_description("daily report"),
+            )
+            .with_rule(
+                PricingRule::new(ResourcePattern::parse("/data/**"), "3000000").with_currency(
+                    Currency::Token {
+                        address: TOKEN.to_string(),
+                        decimals: 6,
+                    },
+                ),
+            ),
+    ));
+    assert_eq!(document.x402_version, X402_VERSION);
+    assert_eq!(document.name, "Example API");
+    assert_eq!(document.items.len(), 2);
+
+    // patterns are published as written, the method only when set
+    let report = &document.items[0];
+    assert_eq!(report.resource, "/reports/*");
+    assert_eq!(report.method.as_deref(), Some("GET"));
+    assert_eq!(report.kind, "http");
+    assert_eq!(report.description.as_deref(), Some("daily report"));
+    let option = &report.accepts[0];
+    assert_eq!(option.scheme, "exact");
+    assert_eq!(option.chain_id, "1");
+    assert_eq!(option.max_amount_required, "2000");
+    assert_eq!(option.asset, "ETH");
+    assert_eq!(option.decimals, Some(18));
+    assert_eq!(option.pay_to, "0x0000000000000000000000000000000000000000");
+    assert_eq!(option.max_timeout_seconds, 600);
+
+    let data = &document.items[1];
+    assert_eq!(data.resource, "/data/**");
+    assert_eq!(data.method, None);
+    assert_eq!(data.description, None);
+    assert_eq!(data.accepts[0].max_amount_required, "3000000");
+    assert_eq!(data.accepts[0].asset, TOKEN);
+    assert_eq!(data.accepts[0].decimals, Some(6));
+}
+
+#[test]
+fn free_resources_are_left_out() {
+    let listed = discovery(Some(
+        RulePricing::new()
+            .with_price("/public/**", "0")
+            .with_price("/premium", "1000"),
+    ));
+    assert_eq!(listed.items.len(), 1);
+    assert_eq!(listed.items[0].resource, "/premium");
+
+    // a catalog of free resources lists nothing, not the default price
+    let listed = discovery(Some(RulePricing::new().with_price("/**", "0")));
+    assert!(listed.items.is_empty());
+}
+
+#[test]
+fn without_published_prices_every_resource_has_the_default_amount() {
+    let document = discovery(None);
+    assert_eq!(document.items.len(), 1);
+    assert_eq!(document.items[0].resource, "/**");
+    assert_eq!(document.items[0].method, None);
+    assert_eq!(document.items[0].accepts[0].max_amount_required, "500");
+}
+
+#[test]
+fn the_document_serializes_in_the_discovery_format() {
+    let json = discovery(Some(RulePricing::new().with_price("POST /jobs", "1000")))
+        .to_json()
+        .unwrap();
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["x402Version"], X402_VERSION);
+    assert_eq!(value["name"], "Example API");
+    let item = &value["items"][0];
+    assert_eq!(item["resource"], "/jobs");
+    assert_eq!(item["method"], "POST");
+    assert_eq!(item["type"], "http");
+    assert!(item.get("description").is_none());
+    let option = &item["accepts"][0];
+    assert_eq!(option["maxAmountRequired"], "1000");
+    assert_eq!(option["chainId"], "1");
+    assert_eq!(
+        option["payTo"],
+        "0x0000000000000000000000000000000000000000"
+    );
+    assert_eq!(option["maxTimeoutSeconds"], 600);
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+use x402_sdk::audit::{AuditAction, InMemoryAuditLog};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::dispute::OverrideDecision;
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const OPERATOR: &str = "ops@example.com";
+const NOW: u64 = 1_700_000_000;
+
+fn engine(audit_log: Arc<InMemoryAuditLog>) -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine
+        .with_clock(Arc::new(MockClock::new(NOW)))
+        .with_audit_log(audit_log);
+    (engine, verifier)
+}
+
+/// challenge for a resource, returns the session nonce
+async fn challenge(engine: &X402) -> String {
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 402);
+    result.x402_response.unwrap().payment_required.nonce
+}
+
+#[tokio::test]
+async fn granted_sessions_are_served_without_a_payment() {
+    let audit_log = Arc::new(InMemoryAuditLog::new());
+    let (engine, _verifier) = engine(audit_log.clone());
+    let nonce = challenge(&engine).await;
+
+    engine
+        .override_verification(&nonce, OPERATOR, OverrideDecision::Grant, "paid by wire")
+        .await
+        .unwrap();
+    let manual_override = engine.manual_override(&nonce).unwrap();
+    assert_eq!(manual_override.decision, OverrideDecision::Grant);
+    assert_eq!(manual_override.operator, OPERATOR);
+    assert_eq!(manual_override.decided_at, NOW);
+
+    // the mock verifier sees no payment
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+
+    let entries = engine.audit_log().entries_for(&nonce).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].operator, OPERATOR);
+    assert_eq!(
+        entries[0].action,
+        AuditAction::VerificationOverridden {
+            decision: "grant".to_string()
+        }
+    );
+    assert_eq!(entries[0].note.as_deref(), Some("paid by wire"));
+    assert_eq!(entries[0].timestamp, NOW);
+}
+
+#[tokio::test]
+async fn revoked_sessions_are_refused_whatever_the_chain_shows() {
+    let audit_log = Arc::new(InMemoryAuditLog::new());
+    let (engine, verifier) = engine(audit_log.clone());
+    let nonce = challenge(&engine).await;
+
+    engine
+        .override_verification(&nonce, OPERATOR, OverrideDecision::Revoke, "chargeback")
+        .await
+        .unwrap();
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(!result.should_serve_content);
+    assert_eq!(
+        engine.audit_log().entries_for(&nonce).await.unwrap()[0].action,
+        AuditAction::VerificationOverridden {
+            decision: "revoke".to_string()
+        }
+    );
+}
+
+#[tokio::test]
+async fn unknown_sessions_cannot_be_overridden() {
+    let audit_log = Arc::new(InMemoryAuditLog::new());
+    let (engine, _verifier) = engine(audit_log.clone());
+
+    assert!(matches!(
+        engine
+            .override_verification("missing", OPERATOR, OverrideDecision::Grant, "typo")
+            .await,
+        Err(EngineError::InvalidSession)
+    ));
+    assert!(engine.manual_override("missing").is_none());
+    assert!(
+        engine
+            .audit_log()
+            .entries_for("missing")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn settled_sessions_cannot_be_granted() {
+    let audit_log = Arc::new(InMemoryAuditLog::new());
+    let (engine, verifier) = engine(audit_log.clone());
+    let nonce = challenge(&engine).await;
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+
+    assert!(matches!(
+        engine
+            .override_verification(&nonce, OPERATOR, OverrideDecision::Grant, "double")
+            .await,
+        Err(EngineError::SessionSettled)
+    ));
+    assert!(engine.manual_override(&nonce).is_none());
+    assert!(
+        engine
+            .audit_log()
+            .entries_for(&nonce)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
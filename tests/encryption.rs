@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use x402_sdk::encryption::{EnvelopeCipher, KeyWrapper, KmsKey, LocalKey};
+use x402_sdk::store::{FileNonceStore, NonceStore, StoreError};
+
+fn temp_path() -> PathBuf {
+    std::env::temp_dir().join(format!("x402-{}.json", uuid::Uuid::new_v4()))
+}
+
+fn cipher(key: u8) -> EnvelopeCipher {
+    EnvelopeCipher::new(Arc::new(LocalKey::new("test", [key; 32])))
+}
+
+#[test]
+fn envelopes_round_trip_and_need_the_key() {
+    let sealed = cipher(1).encrypt(b"payment metadata").unwrap();
+    assert!(!String::from_utf8_lossy(&sealed).contains("payment metadata"));
+    assert_eq!(cipher(1).decrypt(&sealed).unwrap(), b"payment metadata");
+    assert!(cipher(2).decrypt(&sealed).is_err());
+    // a new data key for every write
+    assert_ne!(sealed, cipher(1).encrypt(b"payment metadata").unwrap());
+}
+
+#[test]
+fn kms_callbacks_wrap_the_data_key() {
+    // stands in for the master key held by the KMS
+    let kms = Arc::new(LocalKey::new("kms-key", [7; 32]));
+    let (wrapping, unwrapping) = (kms.clone(), kms);
+    let cipher = EnvelopeCipher::new(Arc::new(KmsKey::new(
+        "kms-key",
+        move |data_key| wrapping.wrap(data_key).map_err(|e| e.to_string()),
+        move |key_id, wrapped| {
+            unwrapping
+                .unwrap(key_id, wrapped)
+                .map_err(|e| e.to_string())
+        },
+    )));
+    let sealed = cipher.encrypt(b"ledger").unwrap();
+    assert_eq!(cipher.decrypt(&sealed).unwrap(), b"ledger");
+}
+
+#[tokio::test]
+async fn encrypted_file_stores_reopen_with_the_key() {
+    let path = temp_path();
+    let store = FileNonceStore::open_encrypted(&path, cipher(3)).unwrap();
+    store.save_next_nonce("1:0xrelay", 42).await.unwrap();
+
+    let raw = std::fs::read_to_string(&path).unwrap();
+    assert!(!raw.contains("0xrelay"));
+    let reopened = FileNonceStore::open_encrypted(&path, cipher(3)).unwrap();
+    assert_eq!(reopened.next_nonce("1:0xrelay").await.unwrap(), Some(42));
+    assert!(FileNonceStore::open_encrypted(&path, cipher(4)).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn plaintext_files_are_encrypted_on_the_next_write() {
+    let path = temp_path();
+    let store = FileNonceStore::open(&path).unwrap();
+    store.save_next_nonce("1:0xrelay", 1).await.unwrap();
+
+    // refused unless migrating, it could have been written by anyone
+    assert!(matches!(
+        FileNonceStore::open_encrypted(&path, cipher(5)),
+        Err(StoreError::Encryption(_))
+    ));
+    let store =
+        FileNonceStore::open_encrypted(&path, cipher(5).with_plaintext_migration()).unwrap();
+    assert_eq!(store.next_nonce("1:0xrelay").await.unwrap(), Some(1));
+    store.save_next_nonce("1:0xrelay", 2).await.unwrap();
+    assert!(!std::fs::read_to_string(&path).unwrap().contains("0xrelay"));
+    let reopened = FileNonceStore::open_encrypted(&path, cipher(5)).unwrap();
+    assert_eq!(reopened.next_nonce("1:0xrelay").await.unwrap(), Some(2));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn envelope_headers_are_authenticated() {
+    // a KMS handing out the same data key under either id
+    let kms = Arc::new(LocalKey::new("kms-key", [8; 32]));
+    let (wrapping, unwrapping) = (kms.clone(), kms);
+    let cipher = EnvelopeCipher::new(Arc::new(KmsKey::new(
+        "kms-key",
+        move |data_key| wrapping.wrap(data_key).map_err(|e| e.to_string()),
+        move |_, wrapped| {
+            unwrapping
+                .unwrap("kms-key", wrapped)
+                .map_err(|e| e.to_string())
+        },
+    )));
+    let sealed = cipher.encrypt(b"ledger").unwrap();
+    let mut envelope: serde_json::Value = serde_json::from_slice(&sealed).unwrap();
+
+    envelope["key_id"] = "other-key".into();
+    let swapped = serde_json::to_vec(&envelope).unwrap();
+    assert!(matches!(
+        cipher.decrypt(&swapped),
+        Err(StoreError::Encryption(_))
+    ));
+
+    envelope["key_id"] = "kms-key".into();
+    envelope["version"] = 1.into();
+    let downgraded = serde_json::to_vec(&envelope).unwrap();
+    assert!(matches!(
+        cipher.decrypt(&downgraded),
+        Err(StoreError::Encryption(_))
+    ));
+}
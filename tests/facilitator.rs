@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use x402_sdk::config::{ConfigBuilder, ConfigManager};
+use x402_sdk::core::X402;
+use x402_sdk::testing::{MockVerifier, mock_engine};
+use x402_sdk::types::ChainType;
+use x402_sdk::verifier::facilitator::{FacilitatorRequest, FacilitatorVerifier};
+use x402_sdk::verifier::{VerificationError, VerifierRegistry};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+/// facilitator answering one verification per connection from the registry
+async fn facilitator(registry: VerifierRegistry) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let registry = Arc::new(registry);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let registry = registry.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                // the body is the JSON after the head, complete once it parses
+                let body = loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((_, body)) = text.split_once("\r\n\r\n")
+                        && let Ok(body) = serde_json::from_str::<FacilitatorRequest>(body)
+                    {
+                        break body;
+                    }
+                };
+                let (status, body) = match body.verify(&registry).await {
+                    Ok(verification) => ("200 OK", serde_json::to_vec(&verification)),
+                    Err(err) => ("422 Unprocessable Entity", serde_json::to_vec(&err)),
+                };
+                let body = body.unwrap();
+                let head = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(&body).await.unwrap();
+            });
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn an_engine_without_rpc_verifies_through_a_facilitator() {
+    let verifier = MockVerifier::new();
+    let mut registry = VerifierRegistry::new();
+    registry.register_verifier(ChainType::ethereum(), Box::new(verifier.clone()));
+    let url = facilitator(registry).await;
+
+    let mut engine = X402::new(ConfigManager::from_config(ConfigBuilder::new().build())).unwrap();
+    engine.verifier_registry_mut().register_verifier(
+        ChainType::ethereum(),
+        Box::new(FacilitatorVerifier::new(&url)),
+    );
+
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    let nonce = result.x402_response.unwrap().payment_required.nonce;
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(!result.should_serve_content);
+
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+    assert_eq!(verifier.verified_requests().last().unwrap().nonce, nonce);
+}
+
+#[tokio::test]
+async fn facilitator_errors_reach_the_verifier() {
+    // no verifier for the chain on the facilitator side
+    let url = facilitator(VerifierRegistry::new()).await;
+    let mut registry = VerifierRegistry::new();
+    registry.register_verifier(
+        ChainType::ethereum(),
+        Box::new(FacilitatorVerifier::new(&url).with_chains(vec![ChainType::ethereum()])),
+    );
+    assert!(!registry.has_verifier(&ChainType::polygon()));
+
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    let payment_request = result.x402_response.unwrap().payment_required;
+    let verifier = registry.get_verifier(&ChainType::ethereum()).unwrap();
+    assert_eq!(
+        verifier
+            .verify_payment(&payment_request, PAYER)
+            .await
+            .unwrap_err(),
+        VerificationError::ChainNotSupported
+    );
+}
@@ -0,0 +1,45 @@
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::testing::mock_engine;
+use x402_sdk::types::ErrorReason;
+use x402_sdk::verifier::VerificationError;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+async fn failure_reason(paid: Option<u128>) -> Option<ErrorReason> {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let challenge = engine
+        .handle_access_request(PAYER, "/premium", None, Some("5000"))
+        .await
+        .unwrap();
+    let nonce = challenge.x402_response.unwrap().payment_required.nonce;
+    verifier.set_paid_amount(paid);
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    result.verification.unwrap().failure_reason
+}
+
+#[tokio::test]
+async fn verifications_report_why_payment_was_refused() {
+    assert_eq!(failure_reason(None).await, Some(ErrorReason::NotFound));
+    assert_eq!(
+        failure_reason(Some(1000)).await,
+        Some(ErrorReason::Underpaid)
+    );
+    assert_eq!(failure_reason(Some(5000)).await, None);
+}
+
+#[test]
+fn reasons_and_errors_round_trip_through_json() {
+    assert_eq!(
+        serde_json::to_string(&ErrorReason::WrongToken).unwrap(),
+        "\"wrong_token\""
+    );
+    let error = VerificationError::CircuitOpen { retry_after: 30 };
+    let json = serde_json::to_string(&error).unwrap();
+    assert_eq!(
+        serde_json::from_str::<VerificationError>(&json).unwrap(),
+        error
+    );
+}
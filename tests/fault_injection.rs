@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+use x402_sdk::testing::{FaultInjectingVerifier, FaultStats, MockVerifier};
+use x402_sdk::types::{
+    ChainConfig, ChainType, Currency, ErrorReason, Finality, PaymentMetadata, PaymentRequest,
+};
+use x402_sdk::verifier::PaymentVerifier;
+
+const PAYER: &str = "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5";
+
+fn request() -> PaymentRequest {
+    PaymentRequest {
+        amount: "1000".to_string(),
+        currency: Currency::Native,
+        recipient: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+        chain: ChainConfig::new(ChainType::ethereum(), None),
+        description: None,
+        expires_at: None,
+        nonce: "nonce".to_string(),
+        beneficiary: None,
+        reference: None,
+        metadata: PaymentMetadata::new(),
+        quote: None,
+    }
+}
+
+fn paid_verifier() -> Box<MockVerifier> {
+    let verifier = MockVerifier::new();
+    verifier.set_paid_amount(Some(1000));
+    Box::new(verifier)
+}
+
+#[tokio::test]
+async fn no_faults_are_injected_by_default() {
+    let verifier = FaultInjectingVerifier::new(paid_verifier());
+    assert!(
+        verifier
+            .verify_payment(&request(), PAYER)
+            .await
+            .unwrap()
+            .is_paid
+    );
+    assert_eq!(
+        verifier.stats(),
+        FaultStats {
+            calls: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn error_bursts_fail_consecutive_calls() {
+    let verifier = FaultInjectingVerifier::new(paid_verifier())
+        .with_error_bursts(1.0, 3)
+        .with_seed(7);
+    for _ in 0..3 {
+        assert!(verifier.verify_payment(&request(), PAYER).await.is_err());
+    }
+    let chain = ChainConfig::new(ChainType::ethereum(), None);
+    assert!(
+        verifier
+            .transaction_finality(&chain, "0xabc")
+            .await
+            .is_err()
+    );
+    assert_eq!(verifier.stats().errors, 4);
+}
+
+#[tokio::test]
+async fn stale_nodes_have_not_seen_the_payment() {
+    let verifier = FaultInjectingVerifier::new(paid_verifier()).with_stale_data(1.0);
+    let verification = verifier.verify_payment(&request(), PAYER).await.unwrap();
+    assert!(!verification.is_paid);
+    assert_eq!(verification.transaction_hash, None);
+    assert_eq!(verification.failure_reason, Some(ErrorReason::NotFound));
+
+    let chain = ChainConfig::new(ChainType::ethereum(), None);
+    assert_eq!(
+        verifier
+            .transaction_finality(&chain, "0xabc")
+            .await
+            .unwrap(),
+        Finality::Provisional
+    );
+    assert_eq!(verifier.stats().stale, 2);
+}
+
+#[tokio::test]
+async fn latency_holds_calls_back() {
+    let delay = Duration::from_millis(30);
+    let verifier = FaultInjectingVerifier::new(paid_verifier()).with_latency(delay, delay);
+    let started = Instant::now();
+    assert!(
+        verifier
+            .verify_payment(&request(), PAYER)
+            .await
+            .unwrap()
+            .is_paid
+    );
+    assert!(started.elapsed() >= delay);
+    assert_eq!(verifier.stats().delayed, 1);
+}
+
+#[tokio::test]
+async fn a_seed_replays_the_same_faults() {
+    async fn outcomes(seed: u64) -> Vec<Option<bool>> {
+        let verifier = FaultInjectingVerifier::new(paid_verifier())
+            .with_error_bursts(0.3, 2)
+            .with_stale_data(0.3)
+            .with_seed(seed);
+        let mut outcomes = Vec::new();
+        for _ in 0..20 {
+            let result = verifier.verify_payment(&request(), PAYER).await;
+            outcomes.push(result.ok().map(|verification| verification.is_paid));
+        }
+        outcomes
+    }
+    assert_eq!(outcomes(42).await, outcomes(42).await);
+}
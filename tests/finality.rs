@@ -0,0 +1,153 @@
+use std::sync::{Arc, Mutex};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::{FinalityReport, X402};
+use x402_sdk::events::{EventListener, PaymentEvent, PaymentEventKind};
+use x402_sdk::export::ExportFormat;
+use x402_sdk::store::{InMemoryPaymentStore, PaymentStore};
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+use x402_sdk::types::Finality;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const NOW: u64 = 1_700_000_000;
+
+#[derive(Default)]
+struct RecordingListener {
+    events: Mutex<Vec<PaymentEventKind>>,
+}
+
+impl EventListener for RecordingListener {
+    fn on_event(&self, event: &PaymentEvent) {
+        self.events.lock().unwrap().push(event.kind.clone());
+    }
+}
+
+fn engine(
+    store: Arc<InMemoryPaymentStore>,
+    listener: Arc<RecordingListener>,
+) -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine
+        .with_clock(Arc::new(MockClock::new(NOW)))
+        .with_payment_store(store)
+        .with_event_listener(listener);
+    (engine, verifier)
+}
+
+/// pay for a resource, returns the session nonce
+async fn pay(engine: &X402, verifier: &MockVerifier) -> String {
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    let nonce = result.x402_response.unwrap().payment_required.nonce;
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+    nonce
+}
+
+fn finality_events(listener: &RecordingListener) -> Vec<PaymentEventKind> {
+    listener
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|kind| {
+            matches!(
+                kind,
+                PaymentEventKind::PaymentFinalized { .. }
+                    | PaymentEventKind::PaymentReverted { .. }
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+#[tokio::test]
+async fn payments_finalize_once_their_chain_does() {
+    let store = Arc::new(InMemoryPaymentStore::new());
+    let listener = Arc::new(RecordingListener::default());
+    let (engine, verifier) = engine(store.clone(), listener.clone());
+    let nonce = pay(&engine, &verifier).await;
+
+    verifier.set_finality(Finality::Provisional);
+    let report = engine.update_finality().await.unwrap();
+    assert_eq!(
+        report,
+        FinalityReport {
+            provisional: 1,
+            ..FinalityReport::default()
+        }
+    );
+    let revenue = engine.revenue(NOW, NOW + 1).await.unwrap();
+    assert_eq!((revenue[0].provisional, revenue[0].finalized), (1000, 0));
+    assert!(finality_events(&listener).is_empty());
+
+    verifier.set_finality(Finality::Finalized);
+    let report = engine.update_finality().await.unwrap();
+    assert_eq!(report.finalized, 1);
+    let revenue = engine.revenue(NOW, NOW + 1).await.unwrap();
+    assert_eq!((revenue[0].provisional, revenue[0].finalized), (0, 1000));
+    assert!(matches!(
+        finality_events(&listener).as_slice(),
+        [PaymentEventKind::PaymentFinalized { nonce: finalized }] if *finalized == nonce
+    ));
+
+    // finalized payments are not checked again
+    assert!(store.provisional_payments().await.unwrap().is_empty());
+    assert_eq!(
+        engine.update_finality().await.unwrap(),
+        FinalityReport::default()
+    );
+}
+
+#[tokio::test]
+async fn reverted_payments_leave_the_revenue() {
+    let store = Arc::new(InMemoryPaymentStore::new());
+    let listener = Arc::new(RecordingListener::default());
+    let (engine, verifier) = engine(store.clone(), listener.clone());
+    let nonce = pay(&engine, &verifier).await;
+    let transaction_hash = store.payments_between(NOW, NOW + 1).await.unwrap()[0]
+        .transaction_hash
+        .clone();
+
+    verifier.set_finality(Finality::Reverted);
+    assert_eq!(engine.update_finality().await.unwrap().reverted, 1);
+    assert!(matches!(
+        finality_events(&listener).as_slice(),
+        [PaymentEventKind::PaymentReverted {
+            nonce: reverted,
+            transaction_hash: reverted_hash,
+        }] if *reverted == nonce && *reverted_hash == transaction_hash
+    ));
+    let revenue = engine.revenue(NOW, NOW + 1).await.unwrap();
+    assert_eq!(
+        (
+            revenue[0].provisional,
+            revenue[0].finalized,
+            revenue[0].reverted
+        ),
+        (0, 0, 1000)
+    );
+
+    // exports only carry the header
+    let csv = engine
+        .export_payments(NOW, NOW + 1, ExportFormat::Csv)
+        .await
+        .unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 1);
+}
+
+#[tokio::test]
+async fn engines_without_a_payment_store_track_no_finality() {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    pay(&engine, &verifier).await;
+    assert_eq!(
+        engine.update_finality().await.unwrap(),
+        FinalityReport::default()
+    );
+    assert!(engine.revenue(0, u64::MAX).await.is_err());
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "graphql")]
+
+use async_graphql::{EmptyMutation, EmptySubscription, Object, Request, Schema, Value};
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::graphql::{
+    CHALLENGE_EXTENSION, GraphqlPayment, OperationPricing, PAYMENT_REQUIRED_CODE, PaymentGuard,
+};
+use x402_sdk::testing::{MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn headline(&self) -> &str {
+        "free"
+    }
+
+    #[graphql(guard = "PaymentGuard::field(\"report\").with_price(\"5000\")")]
+    async fn report(&self) -> &str {
+        "paid"
+    }
+}
+
+type TestSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+fn engine() -> (Arc<X402>, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    (Arc::new(engine), verifier)
+}
+
+fn schema(engine: &Arc<X402>) -> TestSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(engine.clone())
+        .finish()
+}
+
+/// nonce of the challenge carried by the first error of a response
+fn challenge_nonce(response: &async_graphql::Response) -> String {
+    let extensions = response.errors[0].extensions.as_ref().unwrap();
+    assert_eq!(
+        extensions.get("code"),
+        Some(&Value::from(PAYMENT_REQUIRED_CODE))
+    );
+    let challenge = extensions.get(CHALLENGE_EXTENSION).unwrap().clone();
+    let challenge = challenge.into_json().unwrap();
+    challenge["payment_required"]["nonce"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn unpaid_fields_fail_with_the_challenge() {
+    let (engine, _verifier) = engine();
+    let response = schema(&engine)
+        .execute(Request::new("{ headline report }").data(GraphqlPayment::new(PAYER)))
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    assert!(!challenge_nonce(&response).is_empty());
+}
+
+#[tokio::test]
+async fn free_fields_need_no_payment() {
+    let (engine, _verifier) = engine();
+    let response = schema(&engine).execute("{ headline }").await;
+    assert!(response.errors.is_empty());
+}
+
+#[tokio::test]
+async fn paid_fields_resolve_with_the_challenge_nonce() {
+    let (engine, verifier) = engine();
+    let schema = schema(&engine);
+    let response = schema
+        .execute(Request::new("{ report }").data(GraphqlPayment::new(PAYER)))
+        .await;
+    let nonce = challenge_nonce(&response);
+
+    verifier.set_paid_amount(Some(5000));
+    let response = schema
+        .execute(Request::new("{ report }").data(GraphqlPayment::new(PAYER).with_nonce(&nonce)))
+        .await;
+    assert!(response.errors.is_empty());
+    assert_eq!(
+        response.data.into_json().unwrap()["report"],
+        serde_json::json!("paid")
+    );
+}
+
+#[tokio::test]
+async fn priced_operations_are_charged_before_execution() {
+    let (engine, verifier) = engine();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription).finish();
+    let pricing = OperationPricing::new(engine).with_price("Headlines", "5000");
+    let payment = GraphqlPayment::new(PAYER);
+    let request = || Request::new("query Headlines { headline }").operation_name("Headlines");
+
+    let response = pricing.execute(&schema, request(), Some(&payment)).await;
+    assert_eq!(response.data, Value::Null);
+    let nonce = challenge_nonce(&response);
+
+    verifier.set_paid_amount(Some(5000));
+    let payment = payment.with_nonce(&nonce);
+    let response = pricing.execute(&schema, request(), Some(&payment)).await;
+    assert!(response.errors.is_empty());
+    assert_eq!(
+        response.data.into_json().unwrap()["headline"],
+        serde_json::json!("free")
+    );
+}
+
+#[tokio::test]
+async fn unpriced_operations_run_as_they_are() {
+    let (engine, _verifier) = engine();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription).finish();
+    let pricing = OperationPricing::new(engine).with_price("Reports", "5000");
+    let response = pricing
+        .execute(&schema, Request::new("{ headline }"), None)
+        .await;
+    assert!(response.errors.is_empty());
+}
+
+#[test]
+fn credentials_are_read_from_headers() {
+    let headers = [("X-Payer-Address", PAYER), ("X-Payment-Nonce", "nonce-1")];
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    };
+    let payment = GraphqlPayment::from_headers(header).unwrap();
+    assert_eq!(payment.payer, PAYER);
+    assert_eq!(payment.nonce.as_deref(), Some("nonce-1"));
+    assert!(payment.coupon.is_none());
+    assert!(GraphqlPayment::from_headers(|_| None).is_none());
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "grpc")]
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use tonic::Code;
+use tonic::metadata::MetadataMap;
+use tower::{Layer, ServiceExt};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::grpc::{PaymentInterceptor, attach_payment, decode_challenge};
+use x402_sdk::testing::{MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const DAILY: &str = "/weather.Forecast/Daily";
+const HEALTH: &str = "/grpc.health.v1.Health/Check";
+
+fn engine() -> (Arc<X402>, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    (Arc::new(engine), verifier)
+}
+
+fn interceptor(engine: Arc<X402>) -> PaymentInterceptor {
+    PaymentInterceptor::new(engine)
+        .with_method_price(DAILY, "5000")
+        .with_free_method(HEALTH)
+}
+
+fn metadata(nonce: Option<&str>) -> MetadataMap {
+    let mut metadata = MetadataMap::new();
+    attach_payment(&mut metadata, PAYER, nonce).unwrap();
+    metadata
+}
+
+#[tokio::test]
+async fn unpaid_calls_are_refused_with_the_challenge() {
+    let (engine, _verifier) = engine();
+    let status = interceptor(engine)
+        .authorize(DAILY, &metadata(None))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    let challenge = decode_challenge(&status).unwrap();
+    assert_eq!(challenge.payment_required.amount, "5000");
+}
+
+#[tokio::test]
+async fn calls_proceed_once_the_challenge_is_paid() {
+    let (engine, verifier) = engine();
+    let interceptor = interceptor(engine);
+    let status = interceptor
+        .authorize(DAILY, &metadata(None))
+        .await
+        .unwrap_err();
+    let nonce = decode_challenge(&status).unwrap().payment_required.nonce;
+
+    verifier.set_paid_amount(Some(5000));
+    assert!(
+        interceptor
+            .authorize(DAILY, &metadata(Some(&nonce)))
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn calls_without_a_payer_are_unauthenticated() {
+    let (engine, _verifier) = engine();
+    let status = interceptor(engine)
+        .authorize(DAILY, &MetadataMap::new())
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn free_methods_need_no_payment() {
+    let (engine, _verifier) = engine();
+    assert!(
+        interceptor(engine)
+            .authorize(HEALTH, &MetadataMap::new())
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn the_layer_answers_refused_calls_with_the_status() {
+    let (engine, _verifier) = engine();
+    let inner = tower::service_fn(|_request: http::Request<()>| async {
+        Ok::<_, Infallible>(http::Response::new(tonic::body::empty_body()))
+    });
+    let service = interceptor(engine).layer().layer(inner);
+
+    let request = http::Request::post(DAILY)
+        .header("x-payer-address", PAYER)
+        .body(())
+        .unwrap();
+    let response = service.clone().oneshot(request).await.unwrap();
+    let status = tonic::Status::from_header_map(response.headers()).unwrap();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert!(decode_challenge(&status).is_ok());
+
+    let request = http::Request::post(HEALTH).body(()).unwrap();
+    let response = service.oneshot(request).await.unwrap();
+    assert!(tonic::Status::from_header_map(response.headers()).is_none());
+}
@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::context::RequestContext;
+use x402_sdk::core::X402;
+use x402_sdk::events::{EventListener, PaymentEvent, PaymentEventKind};
+use x402_sdk::hooks::{EngineHooks, HookDecision};
+use x402_sdk::resource::Resource;
+use x402_sdk::testing::{MockVerifier, mock_engine};
+use x402_sdk::types::{PaymentRequest, PaymentVerification, X402ProtocolResponse};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+#[derive(Default)]
+struct Hooks {
+    challenges: AtomicUsize,
+    veto_verify: bool,
+    veto_paid: bool,
+}
+
+#[async_trait]
+impl EngineHooks for Hooks {
+    async fn before_challenge(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+        payment_request: &mut PaymentRequest,
+    ) -> HookDecision {
+        payment_request.description = Some("tagged".to_string());
+        HookDecision::Continue
+    }
+
+    async fn after_challenge(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+        _response: &X402ProtocolResponse,
+    ) {
+        self.challenges.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn before_verify(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+        _payment_nonce: &str,
+    ) -> HookDecision {
+        match self.veto_verify {
+            true => HookDecision::Veto("maintenance".to_string()),
+            false => HookDecision::Continue,
+        }
+    }
+
+    async fn after_verify(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+        _verification: &PaymentVerification,
+    ) -> HookDecision {
+        match self.veto_paid {
+            true => HookDecision::Veto("flagged".to_string()),
+            false => HookDecision::Continue,
+        }
+    }
+}
+
+#[derive(Default)]
+struct RecordingListener {
+    events: Mutex<Vec<PaymentEventKind>>,
+}
+
+impl EventListener for RecordingListener {
+    fn on_event(&self, event: &PaymentEvent) {
+        self.events.lock().unwrap().push(event.kind.clone());
+    }
+}
+
+fn build_engine(hooks: Arc<Hooks>) -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    (engine.with_hooks(hooks), verifier)
+}
+
+async fn challenge_nonce(engine: &X402) -> String {
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    let request = result.x402_response.unwrap().payment_required;
+    assert_eq!(request.description.as_deref(), Some("tagged"));
+    request.nonce
+}
+
+#[tokio::test]
+async fn challenges_pass_through_the_hooks() {
+    let hooks = Arc::new(Hooks::default());
+    let (engine, verifier) = build_engine(hooks.clone());
+    let nonce = challenge_nonce(&engine).await;
+    assert_eq!(hooks.challenges.load(Ordering::SeqCst), 1);
+
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+}
+
+#[tokio::test]
+async fn hooks_veto_verification_and_paid_content() {
+    let hooks = Arc::new(Hooks {
+        veto_verify: true,
+        ..Hooks::default()
+    });
+    let (engine, verifier) = build_engine(hooks);
+    let nonce = challenge_nonce(&engine).await;
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 403);
+    assert!(verifier.verified_requests().is_empty());
+
+    let hooks = Arc::new(Hooks {
+        veto_paid: true,
+        ..Hooks::default()
+    });
+    let (engine, verifier) = build_engine(hooks);
+    let nonce = challenge_nonce(&engine).await;
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 403);
+    assert!(!result.should_serve_content);
+}
+
+#[tokio::test]
+async fn vetoed_payments_are_not_reported_verified() {
+    let hooks = Arc::new(Hooks {
+        veto_paid: true,
+        ..Hooks::default()
+    });
+    let listener = Arc::new(RecordingListener::default());
+    let (engine, verifier) = build_engine(hooks);
+    let engine = engine.with_event_listener(listener.clone());
+    let nonce = challenge_nonce(&engine).await;
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 403);
+
+    let events = listener.events.lock().unwrap();
+    assert!(
+        !events
+            .iter()
+            .any(|kind| matches!(kind, PaymentEventKind::PaymentVerified { .. }))
+    );
+    assert!(matches!(
+        events.last(),
+        Some(PaymentEventKind::PaymentDenied { nonce: denied, reason })
+            if *denied == nonce && reason == "flagged"
+    ));
+}
@@ -0,0 +1,105 @@
+use x402_sdk::context::RequestContext;
+use x402_sdk::i18n::{Locale, MessageCatalog, MessageKey};
+
+fn catalog() -> MessageCatalog {
+    MessageCatalog::new()
+        .with_message("de", MessageKey::AccessDenied, "Zugriff verweigert")
+        .with_message("pt", MessageKey::AccessDenied, "Acesso negado")
+        .with_message("pt-BR", MessageKey::AccessDenied, "Acesso recusado")
+        .with_messages_json("fr", r#"{"access_denied": "Accès refusé"}"#)
+        .unwrap()
+}
+
+fn accepting(languages: &str) -> RequestContext {
+    RequestContext::new("GET").with_header("Accept-Language", languages)
+}
+
+#[test]
+fn messages_fall_back_from_region_to_language_to_default() {
+    let catalog = catalog();
+    let denied = |locale| catalog.message(locale, MessageKey::AccessDenied, &[]);
+    assert_eq!(denied(Some("pt-BR")), "Acesso recusado");
+    assert_eq!(denied(Some("pt-PT")), "Acesso negado");
+    assert_eq!(denied(Some("DE-at")), "Zugriff verweigert");
+    assert_eq!(denied(Some("fr")), "Accès refusé");
+    // unknown locales and missing keys get the built-in English text
+    assert_eq!(denied(Some("ja")), "Access denied");
+    assert_eq!(denied(None), "Access denied");
+    assert_eq!(catalog.message(Some("de"), MessageKey::Total, &[]), "Total");
+
+    let catalog = catalog.with_default_locale("DE");
+    assert_eq!(
+        catalog.message(Some("ja"), MessageKey::AccessDenied, &[]),
+        "Zugriff verweigert"
+    );
+    assert_eq!(
+        catalog.message(None, MessageKey::AccessDenied, &[]),
+        "Zugriff verweigert"
+    );
+}
+
+#[test]
+fn placeholders_are_filled_in() {
+    let catalog = MessageCatalog::new().with_message(
+        "de",
+        MessageKey::PaymentRequired,
+        "{amount} für {resource}",
+    );
+    let args = [("amount", "1000"), ("resource", "/report")];
+    assert_eq!(
+        catalog.message(Some("de"), MessageKey::PaymentRequired, &args),
+        "1000 für /report"
+    );
+    assert_eq!(
+        catalog.message(None, MessageKey::PaymentRequired, &args),
+        "Payment of 1000 is required to access /report"
+    );
+    assert!(
+        MessageCatalog::new()
+            .with_messages_json("de", r#"{"no_such_key": "x"}"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn accept_language_picks_the_most_preferred_known_locale() {
+    let catalog = catalog();
+    assert_eq!(catalog.locale_for(&accepting("pt-BR,de;q=0.8")), "pt-br");
+    // region unknown, its language served
+    assert_eq!(catalog.locale_for(&accepting("pt-PT")), "pt");
+    // quality wins over order, unknown tags are skipped
+    assert_eq!(
+        catalog.locale_for(&accepting("ja, de;q=0.5, fr;q=0.9")),
+        "fr"
+    );
+    // equal weights keep the header order
+    assert_eq!(catalog.locale_for(&accepting("de, fr")), "de");
+    // refused and wildcard ranges never match
+    assert_eq!(catalog.locale_for(&accepting("de;q=0, *")), "en");
+    assert_eq!(catalog.locale_for(&accepting("ja")), "en");
+    assert_eq!(catalog.locale_for(&RequestContext::new("GET")), "en");
+    assert_eq!(
+        catalog
+            .with_default_locale("fr")
+            .locale_for(&accepting("ja")),
+        "fr"
+    );
+}
+
+#[test]
+fn the_locale_extension_overrides_the_header() {
+    let catalog = catalog();
+    let context = accepting("fr").with_extension(Locale("de-CH".to_string()));
+    assert_eq!(catalog.locale_for(&context), "de");
+    assert_eq!(
+        catalog.message_for(&context, MessageKey::AccessDenied, &[]),
+        "Zugriff verweigert"
+    );
+    // an unknown extension locale falls back to the default, not the header
+    let context = accepting("fr").with_extension(Locale("ja".to_string()));
+    assert_eq!(catalog.locale_for(&context), "en");
+    assert_eq!(
+        catalog.message_for(&context, MessageKey::AccessDenied, &[]),
+        "Access denied"
+    );
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::crypto::Ed25519Suite;
+use x402_sdk::keys::{KeyRing, verify_challenge_with_jwks};
+use x402_sdk::receipt::{Receipt, ReceiptError};
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const NOW: u64 = 1_700_000_000;
+
+async fn paid_receipt(engine: &X402, verifier: &MockVerifier) -> Receipt {
+    let nonce = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce;
+    verifier.set_paid_amount(Some(1000));
+    engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap()
+        .receipt
+        .unwrap()
+}
+
+#[test]
+fn rotated_keys_stay_published_for_the_grace_period() {
+    let key_ring = KeyRing::new(600);
+    key_ring.add_key("2026-01", Ed25519Suite::new([1; 32]), 0, None);
+    key_ring.schedule_rotation("2026-02", Ed25519Suite::new([2; 32]), NOW);
+
+    assert_eq!(key_ring.active_key(NOW - 1).unwrap().kid, "2026-01");
+    assert_eq!(key_ring.active_key(NOW).unwrap().kid, "2026-02");
+
+    let payload = serde_json::json!({ "nonce": "n1" });
+    let old = key_ring.sign_payload(b"test:", &payload, NOW - 1).unwrap();
+    assert_eq!(old.kid.as_deref(), Some("2026-01"));
+    key_ring
+        .verify_payload(b"test:", &payload, &old, NOW + 599)
+        .unwrap();
+    assert!(key_ring.jwks(NOW + 599).find("2026-01").is_some());
+
+    assert!(
+        key_ring
+            .verify_payload(b"test:", &payload, &old, NOW + 600)
+            .is_err()
+    );
+    let jwks = key_ring.jwks(NOW + 600);
+    assert!(jwks.find("2026-01").is_none());
+    assert!(jwks.find("2026-02").is_some());
+
+    assert_eq!(key_ring.prune(NOW + 600), 1);
+    assert_eq!(key_ring.published_keys(NOW + 600).len(), 1);
+}
+
+#[test]
+fn nothing_is_signed_before_the_first_key_activates() {
+    let key_ring = KeyRing::new(0);
+    key_ring.add_key("2026-01", Ed25519Suite::new([1; 32]), NOW, None);
+    assert!(key_ring.active_key(NOW - 1).is_none());
+    assert!(
+        key_ring
+            .sign_payload(b"test:", &"payload", NOW - 1)
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn ed25519_challenges_verify_against_the_published_key_set() {
+    let key_ring = Arc::new(KeyRing::new(0));
+    key_ring.add_key("2026-01", Ed25519Suite::new([1; 32]), 0, None);
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine
+        .with_clock(Arc::new(MockClock::new(NOW)))
+        .with_key_ring(key_ring.clone());
+
+    let mut response = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap();
+    let jwks = key_ring.jwks(NOW);
+    assert_eq!(jwks.find("2026-01").unwrap().kty, "OKP");
+    verify_challenge_with_jwks(&response, &jwks).unwrap();
+
+    response.payment_required.amount = "1".to_string();
+    assert!(verify_challenge_with_jwks(&response, &jwks).is_err());
+}
+
+#[tokio::test]
+async fn receipts_outlive_a_rotation_by_the_grace_period() {
+    let clock = MockClock::new(NOW);
+    let key_ring = KeyRing::new(600);
+    key_ring.add_key("2026-01", Ed25519Suite::new([1; 32]), 0, None);
+    key_ring.schedule_rotation("2026-02", Ed25519Suite::new([2; 32]), NOW + 60);
+    let config = ConfigBuilder::new().with_receipt_ttl(3600).build();
+    let (engine, verifier) = mock_engine(config);
+    let engine = engine
+        .with_clock(Arc::new(clock.clone()))
+        .with_key_ring(Arc::new(key_ring));
+
+    let receipt = paid_receipt(&engine, &verifier).await;
+    assert_eq!(
+        receipt.signature.as_ref().unwrap().kid.as_deref(),
+        Some("2026-01")
+    );
+
+    clock.set(NOW + 60 + 599);
+    engine.validate_receipt(&receipt).await.unwrap();
+    assert_eq!(engine.jwks().unwrap().keys.len(), 2);
+
+    clock.set(NOW + 60 + 600);
+    assert!(matches!(
+        engine.validate_receipt(&receipt).await,
+        Err(EngineError::ReceiptError(ReceiptError::SignatureError(_)))
+    ));
+}
@@ -0,0 +1,209 @@
+use std::sync::{Arc, Mutex};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::context::RequestContext;
+use x402_sdk::core::X402;
+use x402_sdk::ledger::{
+    BUDGET_KEY_HEADER, Ledger, LedgerEntryKind, LedgerError, LowBalanceAlert, LowBalanceHook,
+    VerifiedOwner,
+};
+use x402_sdk::testing::{MockClock, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const OTHER_PAYER: &str = "0x8ba1f109551bD432803012645Ac136ddd64DBA72";
+const ACCOUNT: &str = "research-agent";
+
+fn engine() -> (X402, Arc<Ledger>) {
+    let ledger = Arc::new(Ledger::new());
+    ledger.open_account(ACCOUNT, PAYER, 1_000).unwrap();
+    ledger.deposit(ACCOUNT, 10_000, "0xdeposit").unwrap();
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    (engine.with_ledger(ledger.clone()), ledger)
+}
+
+async fn request(engine: &X402, payer: &str, context: &RequestContext) -> u16 {
+    engine
+        .handle_access_request_with_context(payer, "/premium", None, Some("1000"), context)
+        .await
+        .unwrap()
+        .http_status
+}
+
+fn balance(ledger: &Ledger) -> u128 {
+    ledger.account(ACCOUNT).unwrap().balance
+}
+
+#[tokio::test]
+async fn unauthenticated_callers_cannot_draw_a_budget() {
+    let (engine, ledger) = engine();
+    let api_key = ledger.issue_api_key(ACCOUNT).unwrap();
+
+    // the payer address alone is only a claim
+    assert_eq!(
+        request(&engine, PAYER, &RequestContext::new("GET")).await,
+        402
+    );
+    let forged = RequestContext::new("GET").with_header(BUDGET_KEY_HEADER, "bk_guessed");
+    assert_eq!(request(&engine, PAYER, &forged).await, 402);
+    // the key of the account does not unlock it for another address
+    let keyed = RequestContext::new("GET").with_header(BUDGET_KEY_HEADER, &api_key);
+    assert_eq!(request(&engine, OTHER_PAYER, &keyed).await, 402);
+    let verified_other =
+        RequestContext::new("GET").with_extension(VerifiedOwner(OTHER_PAYER.to_string()));
+    assert_eq!(request(&engine, PAYER, &verified_other).await, 402);
+
+    assert_eq!(balance(&ledger), 10_000);
+    assert_eq!(ledger.entries(ACCOUNT).len(), 1);
+}
+
+#[tokio::test]
+async fn the_account_key_draws_the_budget() {
+    let (engine, ledger) = engine();
+    let api_key = ledger.issue_api_key(ACCOUNT).unwrap();
+    let context = RequestContext::new("GET").with_header(BUDGET_KEY_HEADER, &api_key);
+
+    assert_eq!(request(&engine, PAYER, &context).await, 200);
+    assert_eq!(balance(&ledger), 9_000);
+
+    // reissuing revokes the earlier key
+    let reissued = ledger.issue_api_key(ACCOUNT).unwrap();
+    assert_ne!(reissued, api_key);
+    assert_eq!(request(&engine, PAYER, &context).await, 402);
+    let context = RequestContext::new("GET").with_header(BUDGET_KEY_HEADER, &reissued);
+    assert_eq!(request(&engine, PAYER, &context).await, 200);
+    assert_eq!(balance(&ledger), 8_000);
+}
+
+#[tokio::test]
+async fn a_verified_owner_draws_the_budget() {
+    let (engine, ledger) = engine();
+    let context = RequestContext::new("GET").with_extension(VerifiedOwner(PAYER.to_lowercase()));
+    assert_eq!(request(&engine, PAYER, &context).await, 200);
+    assert_eq!(balance(&ledger), 9_000);
+}
+
+/// keeps the alerts it receives
+#[derive(Default)]
+struct RecordingHook {
+    alerts: Mutex<Vec<LowBalanceAlert>>,
+}
+
+impl LowBalanceHook for RecordingHook {
+    fn on_low_balance(&self, alert: &LowBalanceAlert) {
+        self.alerts.lock().unwrap().push(alert.clone());
+    }
+}
+
+#[test]
+fn deposits_and_draws_are_recorded_in_order() {
+    let clock = MockClock::new(1_700_000_000);
+    let ledger = Ledger::new().with_clock(Arc::new(clock.clone()));
+    ledger.open_account(ACCOUNT, PAYER, 0).unwrap();
+    assert!(matches!(
+        ledger.open_account(ACCOUNT, OTHER_PAYER, 0),
+        Err(LedgerError::AccountExists(_))
+    ));
+
+    assert_eq!(ledger.deposit(ACCOUNT, 5_000, "0xabc").unwrap(), 5_000);
+    // a transaction is credited once, whatever the casing of its hash
+    assert_eq!(ledger.deposit(ACCOUNT, 5_000, "0xABC").unwrap(), 5_000);
+    clock.advance(60);
+    assert_eq!(ledger.draw(ACCOUNT, 1_500, "GET /data").unwrap(), 3_500);
+
+    let entries = ledger.entries(ACCOUNT);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(
+        entries[0].kind,
+        LedgerEntryKind::Deposit {
+            transaction_hash: "0xabc".to_string()
+        }
+    );
+    assert_eq!(
+        entries[1].kind,
+        LedgerEntryKind::Draw {
+            resource: "GET /data".to_string()
+        }
+    );
+    assert_eq!(entries[1].balance, 3_500);
+    assert_eq!(entries[1].timestamp, 1_700_000_060);
+    assert_eq!(
+        ledger.account_for(&PAYER.to_lowercase()).unwrap().name,
+        ACCOUNT
+    );
+}
+
+#[test]
+fn draws_beyond_the_balance_are_refused() {
+    let ledger = Ledger::new();
+    ledger.open_account(ACCOUNT, PAYER, 0).unwrap();
+    ledger.deposit(ACCOUNT, 1_000, "0xdeposit").unwrap();
+
+    match ledger.draw(ACCOUNT, 1_001, "GET /data") {
+        Err(LedgerError::InsufficientFunds { balance, required }) => {
+            assert_eq!((balance, required), (1_000, 1_001));
+        }
+        other => panic!("unexpected draw: {:?}", other),
+    }
+    assert_eq!(ledger.draw(ACCOUNT, 1_000, "GET /data").unwrap(), 0);
+    assert!(matches!(
+        ledger.draw("unknown", 1, "GET /data"),
+        Err(LedgerError::UnknownAccount(_))
+    ));
+    assert_eq!(ledger.entries(ACCOUNT).len(), 2);
+}
+
+#[test]
+fn low_balance_is_alerted_once_when_crossing_the_threshold() {
+    let hook = Arc::new(RecordingHook::default());
+    let ledger = Ledger::new().with_hook(hook.clone());
+    ledger.open_account(ACCOUNT, PAYER, 1_000).unwrap();
+    ledger.deposit(ACCOUNT, 2_500, "0xdeposit").unwrap();
+
+    ledger.draw(ACCOUNT, 1_000, "GET /data").unwrap();
+    assert!(hook.alerts.lock().unwrap().is_empty());
+    ledger.draw(ACCOUNT, 1_000, "GET /data").unwrap();
+    ledger.draw(ACCOUNT, 100, "GET /data").unwrap();
+
+    let alerts = hook.alerts.lock().unwrap();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].account, ACCOUNT);
+    assert_eq!(alerts[0].owner, PAYER);
+    assert_eq!(alerts[0].balance, 500);
+    assert_eq!(alerts[0].threshold, 1_000);
+}
+
+#[tokio::test]
+async fn an_exhausted_budget_falls_back_to_a_payment() {
+    let (engine, ledger) = engine();
+    let context = RequestContext::new("GET").with_extension(VerifiedOwner(PAYER.to_string()));
+    ledger.draw(ACCOUNT, 9_500, "GET /elsewhere").unwrap();
+
+    assert_eq!(request(&engine, PAYER, &context).await, 402);
+    assert_eq!(balance(&ledger), 500);
+}
+
+#[tokio::test]
+async fn on_chain_top_ups_are_credited_once() {
+    let ledger = Arc::new(Ledger::new());
+    ledger.open_account(ACCOUNT, PAYER, 0).unwrap();
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine.with_ledger(ledger.clone());
+
+    assert_eq!(
+        engine.detect_budget_top_ups(ACCOUNT, "1000").await.unwrap(),
+        0
+    );
+    verifier.set_paid_amount(Some(4_000));
+    assert_eq!(
+        engine.detect_budget_top_ups(ACCOUNT, "1000").await.unwrap(),
+        4_000
+    );
+    assert_eq!(
+        engine.detect_budget_top_ups(ACCOUNT, "1000").await.unwrap(),
+        0
+    );
+    assert_eq!(balance(&ledger), 4_000);
+    assert_eq!(
+        verifier.verified_requests()[0].nonce,
+        format!("budget:{}", ACCOUNT)
+    );
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use x402_sdk::core::X402;
+use x402_sdk::pricing::{PricingRule, RulePricing};
+use x402_sdk::resource::ResourcePattern;
+use x402_sdk::testing::MockVerifier;
+use x402_sdk::types::{ChainType, PaymentRequest};
+use x402_sdk::verifier::light_client::{LIGHT_CLIENT_EXTENSION, light_client_required};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+async fn challenge(path: &str, custom_amount: Option<&str>) -> PaymentRequest {
+    let pricing = RulePricing::new()
+        .with_rule(
+            PricingRule::new(ResourcePattern::parse("/vault/**"), "1000")
+                .with_light_client_threshold("5000"),
+        )
+        .with_price("/**", "1000");
+    let mut engine = X402::from_default_config()
+        .unwrap()
+        .with_pricing_provider(Arc::new(pricing));
+    engine
+        .verifier_registry_mut()
+        .register_verifier(ChainType::ethereum(), Box::new(MockVerifier::new()));
+    engine
+        .handle_access_request(PAYER, path, None, custom_amount)
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+}
+
+#[tokio::test]
+async fn rule_threshold_marks_high_value_challenges() {
+    let request = challenge("/vault/gold", None).await;
+    assert_eq!(
+        request.metadata.extensions[LIGHT_CLIENT_EXTENSION],
+        serde_json::json!("5000")
+    );
+    assert!(!light_client_required(&request));
+
+    let mut high_value = request.clone();
+    high_value.amount = "5000".to_string();
+    assert!(light_client_required(&high_value));
+
+    let request = challenge("/public", None).await;
+    assert!(!light_client_required(&request));
+}
+
+#[tokio::test]
+async fn malformed_threshold_always_applies() {
+    let mut request = challenge("/vault/gold", None).await;
+    request.metadata = request
+        .metadata
+        .with_extension(LIGHT_CLIENT_EXTENSION, serde_json::json!(5000));
+    assert!(light_client_required(&request));
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::store::{InMemoryLockStore, LockStore};
+use x402_sdk::testing::mock_engine;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+#[tokio::test]
+async fn locks_belong_to_one_owner_until_they_expire() {
+    let locks = InMemoryLockStore::new();
+    assert!(locks.try_lock("session:a", "one", 30, 100).await.unwrap());
+    assert!(locks.try_lock("session:a", "one", 30, 110).await.unwrap());
+    assert!(!locks.try_lock("session:a", "two", 30, 120).await.unwrap());
+    assert!(locks.try_lock("session:a", "two", 30, 140).await.unwrap());
+
+    locks.unlock("session:a", "one").await.unwrap();
+    assert!(!locks.try_lock("session:a", "one", 30, 150).await.unwrap());
+    locks.unlock("session:a", "two").await.unwrap();
+    assert!(locks.try_lock("session:a", "one", 30, 150).await.unwrap());
+}
+
+#[tokio::test]
+async fn session_verified_elsewhere_is_retried_later() {
+    let locks = Arc::new(InMemoryLockStore::new());
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine.with_lock_store(locks.clone());
+    let nonce = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce;
+    verifier.set_paid_amount(Some(1000));
+
+    let key = format!("session:{}", nonce);
+    assert!(
+        locks
+            .try_lock(&key, "other replica", 30, u64::MAX - 60)
+            .await
+            .unwrap()
+    );
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 202);
+    assert_eq!(result.retry_after, Some(30));
+    assert!(verifier.verified_requests().is_empty());
+
+    locks.unlock(&key, "other replica").await.unwrap();
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+}
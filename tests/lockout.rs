@@ -0,0 +1,144 @@
+use std::sync::{Arc, Mutex};
+use x402_sdk::clock::Clock;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::events::{EventListener, PaymentEvent, PaymentEventKind};
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const NOW: u64 = 1_700_000_000;
+
+#[derive(Default)]
+struct RecordingListener {
+    events: Mutex<Vec<PaymentEvent>>,
+}
+
+impl EventListener for RecordingListener {
+    fn on_event(&self, event: &PaymentEvent) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+fn engine(
+    max_failed_attempts: u32,
+    lockout_secs: u64,
+    backoff_base_secs: u64,
+    clock: &MockClock,
+) -> (X402, MockVerifier) {
+    let config = ConfigBuilder::new()
+        .with_expiration_time(3600)
+        .with_attempt_limits(max_failed_attempts, lockout_secs, backoff_base_secs)
+        .build();
+    let (engine, verifier) = mock_engine(config);
+    (engine.with_clock(Arc::new(clock.clone())), verifier)
+}
+
+async fn challenge(engine: &X402) -> String {
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce
+}
+
+/// status and retry-after of a verification attempt
+async fn attempt(engine: &X402, nonce: &str) -> (u16, Option<u64>) {
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(nonce), None)
+        .await
+        .unwrap();
+    (result.http_status, result.retry_after)
+}
+
+#[tokio::test]
+async fn failed_attempts_lock_the_session() {
+    let clock = MockClock::new(NOW);
+    let (engine, verifier) = engine(3, 300, 0, &clock);
+    let listener = Arc::new(RecordingListener::default());
+    let engine = engine.with_event_listener(listener.clone());
+    let nonce = challenge(&engine).await;
+
+    for _ in 0..3 {
+        assert_ne!(attempt(&engine, &nonce).await.0, 200);
+    }
+    let attempts = engine.session_attempts(&nonce).unwrap();
+    assert_eq!(attempts.attempts, 3);
+    assert_eq!(attempts.failed_attempts, 3);
+    assert_eq!(attempts.locked_until, Some(NOW + 300));
+    assert!(listener.events.lock().unwrap().iter().any(|event| matches!(
+        &event.kind,
+        PaymentEventKind::SessionLockedOut { locked_until, .. } if *locked_until == NOW + 300
+    )));
+
+    // a payment made meanwhile is not even looked up until the lockout ends
+    verifier.set_paid_amount(Some(1000));
+    let verified = verifier.verified_requests().len();
+    clock.advance(100);
+    assert_eq!(attempt(&engine, &nonce).await, (429, Some(200)));
+    assert_eq!(verifier.verified_requests().len(), verified);
+
+    clock.advance(200);
+    assert_eq!(attempt(&engine, &nonce).await.0, 200);
+}
+
+#[tokio::test]
+async fn the_counter_restarts_after_a_lockout() {
+    let clock = MockClock::new(NOW);
+    let (engine, _verifier) = engine(2, 60, 0, &clock);
+    let nonce = challenge(&engine).await;
+    attempt(&engine, &nonce).await;
+    attempt(&engine, &nonce).await;
+    assert_eq!(attempt(&engine, &nonce).await.0, 429);
+
+    clock.advance(60);
+    attempt(&engine, &nonce).await;
+    let attempts = engine.session_attempts(&nonce).unwrap();
+    assert_eq!(attempts.failed_attempts, 1);
+    assert_eq!(attempts.locked_until, None);
+}
+
+#[tokio::test]
+async fn failed_attempts_back_off_exponentially() {
+    let clock = MockClock::new(NOW);
+    let (engine, _verifier) = engine(0, 60, 5, &clock);
+    let nonce = challenge(&engine).await;
+
+    attempt(&engine, &nonce).await;
+    assert_eq!(
+        engine.session_attempts(&nonce).unwrap().next_attempt_at,
+        Some(NOW + 5)
+    );
+    assert_eq!(attempt(&engine, &nonce).await, (429, Some(5)));
+
+    clock.advance(5);
+    attempt(&engine, &nonce).await;
+    assert_eq!(
+        engine.session_attempts(&nonce).unwrap().blocked_until(),
+        Some(NOW + 5 + 10)
+    );
+
+    // capped at the lockout duration, the lockout itself is disabled
+    for _ in 0..5 {
+        let blocked_until = engine.session_attempts(&nonce).unwrap().blocked_until();
+        clock.set(blocked_until.unwrap());
+        attempt(&engine, &nonce).await;
+    }
+    let attempts = engine.session_attempts(&nonce).unwrap();
+    assert_eq!(attempts.locked_until, None);
+    assert_eq!(attempts.next_attempt_at, Some(clock.now() + 60));
+}
+
+#[tokio::test]
+async fn attempts_are_unlimited_without_limits() {
+    let clock = MockClock::new(NOW);
+    let (engine, verifier) = engine(0, 0, 0, &clock);
+    let nonce = challenge(&engine).await;
+    for _ in 0..20 {
+        assert_ne!(attempt(&engine, &nonce).await.0, 429);
+    }
+    verifier.set_paid_amount(Some(1000));
+    assert_eq!(attempt(&engine, &nonce).await.0, 200);
+}
@@ -0,0 +1,185 @@
+#![cfg(feature = "mcp")]
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::mcp::{McpError, McpServer, NONCE_META_KEY, PAYER_META_KEY, PaidTool, ToolHandler};
+use x402_sdk::testing::{MockVerifier, mock_engine};
+use x402_sdk::types::X402ProtocolResponse;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+/// Tool echoing its arguments, recording the calls it served.
+#[derive(Default)]
+struct Echo {
+    calls: Mutex<Vec<Value>>,
+}
+
+#[async_trait]
+impl ToolHandler for Echo {
+    async fn call(&self, arguments: Value) -> Result<Value, McpError> {
+        self.calls.lock().unwrap().push(arguments.clone());
+        Ok(json!({ "echo": arguments }))
+    }
+}
+
+fn server(echo: Arc<Echo>) -> (McpServer, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let server = McpServer::new(Arc::new(engine))
+        .with_tool(
+            PaidTool::new("echo", "Echo the arguments", echo)
+                .with_input_schema(json!({
+                    "type": "object",
+                    "properties": { "text": { "type": "string" } },
+                }))
+                .with_price("1000"),
+        )
+        .with_server_info("test-server", "1.0.0");
+    (server, verifier)
+}
+
+fn call(id: u64, meta: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": {
+            "name": "echo",
+            "arguments": { "text": "hello" },
+            "_meta": meta,
+        },
+    })
+}
+
+#[tokio::test]
+async fn tools_are_listed_with_their_price() {
+    let (server, _verifier) = server(Arc::default());
+    let response = server
+        .handle_message(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+        .await
+        .unwrap();
+    assert_eq!(response["id"], 1);
+    let tools = response["result"]["tools"].as_array().unwrap();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0]["name"], "echo");
+    assert_eq!(tools[0]["description"], "Echo the arguments");
+    assert_eq!(
+        tools[0]["inputSchema"]["properties"]["text"]["type"],
+        "string"
+    );
+    assert_eq!(tools[0]["_meta"]["x402/resource"], "/mcp/tools/echo");
+    assert_eq!(tools[0]["_meta"]["x402/price"], "1000");
+
+    let response = server
+        .handle_message(&json!({ "jsonrpc": "2.0", "id": 2, "method": "initialize" }))
+        .await
+        .unwrap();
+    assert_eq!(response["result"]["serverInfo"]["name"], "test-server");
+    // notifications are not answered
+    assert!(
+        server
+            .handle_message(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn unpaid_calls_return_the_challenge() {
+    let echo = Arc::new(Echo::default());
+    let (server, _verifier) = server(echo.clone());
+
+    let response = server
+        .handle_message(&call(1, json!({ PAYER_META_KEY: PAYER })))
+        .await
+        .unwrap();
+    let result = &response["result"];
+    assert_eq!(result["isError"], true);
+    assert_eq!(result["content"][0]["text"], "payment required, status 402");
+    let challenge: X402ProtocolResponse =
+        serde_json::from_value(result["structuredContent"].clone()).unwrap();
+    assert_eq!(challenge.status, 402);
+    assert_eq!(challenge.payment_required.amount, "1000");
+    assert!(echo.calls.lock().unwrap().is_empty());
+
+    // a call without a payer is refused before the engine
+    let response = server.handle_message(&call(2, json!({}))).await.unwrap();
+    assert_eq!(response["result"]["isError"], true);
+    assert!(response["result"].get("structuredContent").is_none());
+}
+
+#[tokio::test]
+async fn paid_calls_run_the_tool() {
+    let echo = Arc::new(Echo::default());
+    let (server, verifier) = server(echo.clone());
+
+    let response = server
+        .handle_message(&call(1, json!({ PAYER_META_KEY: PAYER })))
+        .await
+        .unwrap();
+    let challenge: X402ProtocolResponse =
+        serde_json::from_value(response["result"]["structuredContent"].clone()).unwrap();
+    let nonce = challenge.payment_required.nonce;
+    verifier.set_paid_amount(Some(1000));
+
+    let response = server
+        .handle_message(&call(
+            2,
+            json!({ PAYER_META_KEY: PAYER, NONCE_META_KEY: nonce }),
+        ))
+        .await
+        .unwrap();
+    let result = &response["result"];
+    assert_eq!(result["isError"], false);
+    assert_eq!(
+        result["structuredContent"],
+        json!({ "echo": { "text": "hello" } })
+    );
+    assert_eq!(
+        *echo.calls.lock().unwrap(),
+        vec![json!({ "text": "hello" })]
+    );
+}
+
+#[tokio::test]
+async fn unknown_tools_and_methods_are_protocol_errors() {
+    let (server, _verifier) = server(Arc::default());
+    let response = server
+        .handle_message(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": "missing" },
+        }))
+        .await
+        .unwrap();
+    assert_eq!(response["error"]["code"], -32602);
+    let response = server
+        .handle_message(&json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" }))
+        .await
+        .unwrap();
+    assert_eq!(response["error"]["code"], -32601);
+}
+
+#[tokio::test]
+async fn messages_are_served_line_by_line() {
+    let (server, _verifier) = server(Arc::default());
+    let input = concat!(
+        r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+        "\n\n",
+        "not json\n",
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        "\n",
+    );
+    let mut output = Vec::new();
+    server.serve(input.as_bytes(), &mut output).await.unwrap();
+    let responses: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["result"], json!({}));
+    assert_eq!(responses[1]["error"]["code"], -32700);
+}
@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::monitor::{
+    AlertHook, BalanceSource, MonitorError, TreasuryAlert, TreasuryAlertKind, TreasuryMonitor,
+};
+use x402_sdk::testing::mock_engine;
+use x402_sdk::types::ChainType;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const TREASURY: &str = "0x1111111111111111111111111111111111111111";
+
+/// Balance the test sets, the same on every chain.
+#[derive(Default)]
+struct StubBalance {
+    balance: Mutex<u128>,
+}
+
+impl StubBalance {
+    fn set(&self, balance: u128) {
+        *self.balance.lock().unwrap() = balance;
+    }
+}
+
+#[async_trait]
+impl BalanceSource for StubBalance {
+    async fn balance(&self, _chain_type: &ChainType, _address: &str) -> Result<u128, MonitorError> {
+        Ok(*self.balance.lock().unwrap())
+    }
+}
+
+#[derive(Default)]
+struct RecordingHook {
+    alerts: Mutex<Vec<TreasuryAlert>>,
+}
+
+impl RecordingHook {
+    fn take(&self) -> Vec<TreasuryAlertKind> {
+        self.alerts
+            .lock()
+            .unwrap()
+            .drain(..)
+            .map(|alert| alert.kind)
+            .collect()
+    }
+}
+
+impl AlertHook for RecordingHook {
+    fn on_alert(&self, alert: &TreasuryAlert) {
+        self.alerts.lock().unwrap().push(alert.clone());
+    }
+}
+
+/// monitor watching Ethereum with a tolerance of 10 units, after a first
+/// check at a balance of 1000
+async fn monitor(
+    source: Arc<StubBalance>,
+    hook: Arc<RecordingHook>,
+    large_inflow_threshold: Option<u128>,
+) -> TreasuryMonitor {
+    let mut monitor = TreasuryMonitor::new(source.clone())
+        .with_hook(hook.clone())
+        .with_tolerance(10);
+    if let Some(threshold) = large_inflow_threshold {
+        monitor = monitor.with_large_inflow_threshold(threshold);
+    }
+    monitor.watch(ChainType::ethereum(), TREASURY);
+    source.set(1000);
+    let reports = monitor.check().await.unwrap();
+    // nothing to reconcile against yet
+    assert_eq!(reports[0].previous_balance, None);
+    assert!(reports[0].alerts.is_empty());
+    assert!(hook.take().is_empty());
+    monitor
+}
+
+#[tokio::test]
+async fn differences_within_the_tolerance_raise_no_alert() {
+    let source = Arc::new(StubBalance::default());
+    let hook = Arc::new(RecordingHook::default());
+    let monitor = monitor(source.clone(), hook.clone(), None).await;
+
+    for (verified, balance) in [(500, 1500), (510, 2000), (490, 2500)] {
+        monitor.record_verified(&ChainType::ethereum(), verified);
+        source.set(balance);
+        let reports = monitor.check().await.unwrap();
+        assert_eq!(reports[0].verified_inflow, verified);
+        assert!(reports[0].alerts.is_empty(), "verified {}", verified);
+    }
+    assert!(hook.take().is_empty());
+    assert_eq!(
+        monitor.check().await.unwrap()[0].previous_balance,
+        Some(2500)
+    );
+}
+
+#[tokio::test]
+async fn shortfalls_report_the_missing_funds() {
+    let source = Arc::new(StubBalance::default());
+    let hook = Arc::new(RecordingHook::default());
+    let monitor = monitor(source.clone(), hook.clone(), None).await;
+
+    monitor.record_verified(&ChainType::ethereum(), 511);
+    source.set(1500);
+    let reports = monitor.check().await.unwrap();
+    assert_eq!(
+        reports[0].alerts[0].kind,
+        TreasuryAlertKind::MissingFunds { shortfall: 11 }
+    );
+    assert_eq!(reports[0].alerts[0].address, TREASURY);
+    assert_eq!(
+        hook.take(),
+        vec![TreasuryAlertKind::MissingFunds { shortfall: 11 }]
+    );
+
+    // verified payments are reconciled once
+    let reports = monitor.check().await.unwrap();
+    assert_eq!(reports[0].verified_inflow, 0);
+    assert!(hook.take().is_empty());
+}
+
+#[tokio::test]
+async fn surpluses_report_the_unexplained_inflow() {
+    let source = Arc::new(StubBalance::default());
+    let hook = Arc::new(RecordingHook::default());
+    let monitor = monitor(source.clone(), hook.clone(), None).await;
+
+    monitor.record_verified(&ChainType::ethereum(), 500);
+    source.set(1511);
+    monitor.check().await.unwrap();
+    assert_eq!(
+        hook.take(),
+        vec![TreasuryAlertKind::UnexplainedInflow { excess: 11 }]
+    );
+
+    // withdrawals are reported as such
+    source.set(1200);
+    monitor.check().await.unwrap();
+    assert_eq!(
+        hook.take(),
+        vec![TreasuryAlertKind::BalanceDecreased { amount: 311 }]
+    );
+}
+
+#[tokio::test]
+async fn small_inflows_pass_below_the_large_inflow_threshold() {
+    let source = Arc::new(StubBalance::default());
+    let hook = Arc::new(RecordingHook::default());
+    let monitor = monitor(source.clone(), hook.clone(), Some(100)).await;
+
+    source.set(1100);
+    monitor.check().await.unwrap();
+    assert!(hook.take().is_empty());
+    source.set(1201);
+    monitor.check().await.unwrap();
+    assert_eq!(
+        hook.take(),
+        vec![TreasuryAlertKind::UnexplainedInflow { excess: 101 }]
+    );
+}
+
+#[tokio::test]
+async fn verified_payments_reach_the_monitor_through_engine_events() {
+    let source = Arc::new(StubBalance::default());
+    let hook = Arc::new(RecordingHook::default());
+    let monitor = Arc::new(monitor(source.clone(), hook.clone(), None).await);
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine.with_event_listener(monitor.clone());
+
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    let nonce = result.x402_response.unwrap().payment_required.nonce;
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+
+    source.set(2000);
+    let reports = monitor.check().await.unwrap();
+    assert_eq!(reports[0].verified_inflow, 1000);
+    assert!(hook.take().is_empty());
+}
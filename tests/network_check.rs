@@ -0,0 +1,244 @@
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use x402_sdk::config::{ConfigBuilder, ConfigManager};
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::testing::MockVerifier;
+use x402_sdk::types::{AptosChain, ChainConfig, ChainType, EvmChain, SolanaChain, SuiChain};
+use x402_sdk::verifier::VerificationError;
+use x402_sdk::verifier::network::{
+    SOLANA_DEVNET_GENESIS, SOLANA_MAINNET_GENESIS, SUI_MAINNET_CHAIN_IDENTIFIER, check_identity,
+    check_network, expected_identity, network_identity,
+};
+
+/// Node answering every request with `body`, returns its URL and the
+/// request heads and bodies it received.
+async fn fake_node(body: serde_json::Value) -> (String, Arc<Mutex<Vec<(String, String)>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let recorded = received.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            // complete once the head and the announced body are in
+            let (head, body_text) = loop {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, rest)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if rest.len() >= length {
+                        break (head.to_string(), rest.to_string());
+                    }
+                }
+            };
+            recorded.lock().unwrap().push((head, body_text));
+            let body = body.to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    (url, received)
+}
+
+#[test]
+fn public_networks_have_a_known_identity() {
+    assert_eq!(
+        expected_identity(&ChainType::Solana(SolanaChain::Mainnet)).as_deref(),
+        Some(SOLANA_MAINNET_GENESIS)
+    );
+    assert_eq!(
+        expected_identity(&ChainType::Sui(SuiChain::Mainnet)).as_deref(),
+        Some("35834a8a")
+    );
+    assert_eq!(
+        expected_identity(&ChainType::Aptos(AptosChain::Testnet)).as_deref(),
+        Some("2")
+    );
+    // reset networks and EVM chains are not checked here
+    assert_eq!(expected_identity(&ChainType::Sui(SuiChain::Devnet)), None);
+    assert_eq!(expected_identity(&ChainType::Evm(EvmChain::Ethereum)), None);
+}
+
+#[test]
+fn custom_chains_are_checked_when_named_by_identity() {
+    let private = ChainType::Solana(SolanaChain::Custom(SOLANA_DEVNET_GENESIS.to_string()));
+    assert_eq!(
+        expected_identity(&private).as_deref(),
+        Some(SOLANA_DEVNET_GENESIS)
+    );
+    let localnet = ChainType::Solana(SolanaChain::Custom("localnet".to_string()));
+    assert_eq!(expected_identity(&localnet), None);
+    let aptos = ChainType::Aptos(AptosChain::Custom("4".to_string()));
+    assert_eq!(expected_identity(&aptos).as_deref(), Some("4"));
+}
+
+#[test]
+fn mismatched_identity_names_both_networks() {
+    let mainnet = ChainType::Solana(SolanaChain::Mainnet);
+    assert!(check_identity(&mainnet, SOLANA_MAINNET_GENESIS).is_ok());
+
+    let err = check_identity(&mainnet, SOLANA_DEVNET_GENESIS).unwrap_err();
+    assert_eq!(
+        err,
+        VerificationError::WrongNetwork {
+            expected: SOLANA_MAINNET_GENESIS.to_string(),
+            actual: SOLANA_DEVNET_GENESIS.to_string(),
+        }
+    );
+    assert!(err.to_string().contains(SOLANA_DEVNET_GENESIS));
+}
+
+#[tokio::test]
+async fn solana_reports_its_genesis_hash() {
+    let (url, received) = fake_node(
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": SOLANA_DEVNET_GENESIS }),
+    )
+    .await;
+    let solana = ChainType::Solana(SolanaChain::Devnet);
+    assert_eq!(
+        network_identity(&url, &solana).await.unwrap(),
+        SOLANA_DEVNET_GENESIS
+    );
+    check_network(&url, &solana).await.unwrap();
+    assert_eq!(
+        check_network(&url, &ChainType::Solana(SolanaChain::Mainnet))
+            .await
+            .unwrap_err(),
+        VerificationError::WrongNetwork {
+            expected: SOLANA_MAINNET_GENESIS.to_string(),
+            actual: SOLANA_DEVNET_GENESIS.to_string(),
+        }
+    );
+    let (_, body) = &received.lock().unwrap()[0];
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["method"], "getGenesisHash");
+}
+
+#[tokio::test]
+async fn sui_reports_its_chain_identifier() {
+    let (url, received) = fake_node(
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": SUI_MAINNET_CHAIN_IDENTIFIER }),
+    )
+    .await;
+    let sui = ChainType::Sui(SuiChain::Mainnet);
+    assert_eq!(
+        network_identity(&url, &sui).await.unwrap(),
+        SUI_MAINNET_CHAIN_IDENTIFIER
+    );
+    check_network(&url, &sui).await.unwrap();
+    assert!(matches!(
+        check_network(&url, &ChainType::Sui(SuiChain::Testnet)).await,
+        Err(VerificationError::WrongNetwork { .. })
+    ));
+    let (_, body) = &received.lock().unwrap()[0];
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["method"], "sui_getChainIdentifier");
+}
+
+#[tokio::test]
+async fn aptos_reports_its_chain_id_in_the_ledger_info() {
+    let (url, received) =
+        fake_node(serde_json::json!({ "chain_id": 2, "epoch": "100", "ledger_version": "7" }))
+            .await;
+    let aptos = ChainType::Aptos(AptosChain::Testnet);
+    assert_eq!(network_identity(&url, &aptos).await.unwrap(), "2");
+    // the URL may already name the API version
+    check_network(&format!("{}/v1/", url), &aptos)
+        .await
+        .unwrap();
+    assert!(matches!(
+        check_network(&url, &ChainType::Aptos(AptosChain::Mainnet)).await,
+        Err(VerificationError::WrongNetwork { .. })
+    ));
+    let received = received.lock().unwrap();
+    assert!(received[0].0.starts_with("GET /v1 "));
+    assert!(received[1].0.starts_with("GET /v1 "));
+}
+
+#[tokio::test]
+async fn rpc_errors_and_odd_identities_are_refused() {
+    let (url, _) = fake_node(
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32601, "message": "Method not found" } }),
+    )
+    .await;
+    assert!(matches!(
+        network_identity(&url, &ChainType::Solana(SolanaChain::Mainnet)).await,
+        Err(VerificationError::RpcError(_))
+    ));
+
+    let (url, _) =
+        fake_node(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": null })).await;
+    assert!(matches!(
+        network_identity(&url, &ChainType::Sui(SuiChain::Mainnet)).await,
+        Err(VerificationError::RpcError(_))
+    ));
+    assert_eq!(
+        network_identity(&url, &ChainType::Evm(EvmChain::Ethereum))
+            .await
+            .unwrap_err(),
+        VerificationError::ChainNotSupported
+    );
+}
+
+#[tokio::test]
+async fn registration_refuses_an_rpc_of_another_network() {
+    let (url, _) =
+        fake_node(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "4c78adac" })).await;
+    let sui = ChainType::Sui(SuiChain::Mainnet);
+    let mut engine = X402::from_default_config().unwrap();
+    let result = engine
+        .register_custom_verifier(sui.clone(), &url, Box::new(MockVerifier::new()))
+        .await;
+    assert!(matches!(
+        result,
+        Err(EngineError::VerificationError(
+            VerificationError::WrongNetwork { .. }
+        ))
+    ));
+    assert!(engine.verifier_registry().supported_chains().is_empty());
+
+    let testnet = ChainType::Sui(SuiChain::Testnet);
+    engine
+        .register_custom_verifier(testnet.clone(), &url, Box::new(MockVerifier::new()))
+        .await
+        .unwrap();
+    assert_eq!(engine.verifier_registry().supported_chains(), vec![testnet]);
+}
+
+#[tokio::test]
+async fn configured_solana_chains_check_their_rpc() {
+    let (url, _) = fake_node(
+        serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": SOLANA_DEVNET_GENESIS }),
+    )
+    .await;
+    let mainnet = ChainType::Solana(SolanaChain::Mainnet);
+    let config = ConfigBuilder::new()
+        .with_chain(
+            mainnet.clone(),
+            ChainConfig::new(mainnet.clone(), Some(url.clone())),
+        )
+        .build();
+    let mut engine = X402::new(ConfigManager::from_config(config)).unwrap();
+    assert!(matches!(
+        engine.register_chain_verifier(mainnet, url).await,
+        Err(EngineError::VerificationError(
+            VerificationError::WrongNetwork { .. }
+        ))
+    ));
+    assert!(engine.verifier_registry().supported_chains().is_empty());
+}
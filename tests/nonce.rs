@@ -0,0 +1,151 @@
+use ethers::providers::Provider;
+use ethers::types::{H160, U256};
+use std::sync::Arc;
+use x402_sdk::nonce::NonceManager;
+use x402_sdk::store::{InMemoryNonceStore, NonceStore};
+use x402_sdk::testing::MockClock;
+
+const CHAIN: &str = "1";
+const NOW: u64 = 1_700_000_000;
+
+fn wallet() -> H160 {
+    "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5"
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn nonces_continue_from_the_pending_count() {
+    let (provider, mock) = Provider::mocked();
+    mock.push(U256::from(7)).unwrap();
+    let manager = NonceManager::new();
+
+    assert_eq!(
+        manager.reserve(&provider, CHAIN, wallet()).await.unwrap(),
+        7
+    );
+    // later reservations do not ask the node again
+    assert_eq!(
+        manager.reserve(&provider, CHAIN, wallet()).await.unwrap(),
+        8
+    );
+    assert_eq!(
+        manager.reserve(&provider, CHAIN, wallet()).await.unwrap(),
+        9
+    );
+}
+
+#[tokio::test]
+async fn released_nonces_are_reused_first() {
+    let (provider, mock) = Provider::mocked();
+    mock.push(U256::from(0)).unwrap();
+    let manager = NonceManager::new();
+
+    let first = manager.reserve(&provider, CHAIN, wallet()).await.unwrap();
+    let second = manager.reserve(&provider, CHAIN, wallet()).await.unwrap();
+    manager
+        .record_sent(CHAIN, wallet(), second, "0xsecond")
+        .await;
+    manager.release(CHAIN, wallet(), first).await;
+    // a sent nonce is not handed out again
+    manager.release(CHAIN, wallet(), second).await;
+
+    assert_eq!(
+        manager.reserve(&provider, CHAIN, wallet()).await.unwrap(),
+        first
+    );
+    assert_eq!(
+        manager.reserve(&provider, CHAIN, wallet()).await.unwrap(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn the_stored_nonce_wins_over_a_lagging_node() {
+    let store = Arc::new(InMemoryNonceStore::new());
+    let (provider, mock) = Provider::mocked();
+    mock.push(U256::from(3)).unwrap();
+    let manager = NonceManager::new().with_store(store.clone());
+    assert_eq!(
+        manager.reserve(&provider, CHAIN, wallet()).await.unwrap(),
+        3
+    );
+    assert_eq!(
+        manager.reserve(&provider, CHAIN, wallet()).await.unwrap(),
+        4
+    );
+
+    // after a restart, with a node not seeing the sent transactions yet
+    let (provider, mock) = Provider::mocked();
+    mock.push(U256::from(3)).unwrap();
+    let manager = NonceManager::new().with_store(store.clone());
+    assert_eq!(
+        manager.reserve(&provider, CHAIN, wallet()).await.unwrap(),
+        5
+    );
+    let key = format!("{}:{:?}", CHAIN, wallet());
+    assert_eq!(store.next_nonce(&key).await.unwrap(), Some(6));
+}
+
+#[tokio::test]
+async fn unmined_transactions_are_reported_stuck() {
+    let clock = MockClock::new(NOW);
+    let manager = NonceManager::new()
+        .with_clock(Arc::new(clock.clone()))
+        .with_stuck_after(60);
+    manager.record_sent(CHAIN, wallet(), 4, "0xfour").await;
+    clock.advance(30);
+    manager.record_sent(CHAIN, wallet(), 5, "0xfive").await;
+
+    clock.advance(30);
+    let stuck = manager.stuck(CHAIN, wallet()).await;
+    assert_eq!(stuck.len(), 1);
+    assert_eq!(stuck[0].nonce, 4);
+    assert_eq!(stuck[0].sent_at, NOW);
+
+    // a replacement keeps the nonce under its new hash
+    manager
+        .record_sent(CHAIN, wallet(), 4, "0xFOUR-replaced")
+        .await;
+    assert_eq!(
+        manager
+            .in_flight_nonce(CHAIN, wallet(), "0xfour-replaced")
+            .await,
+        Some(4)
+    );
+    assert_eq!(
+        manager.in_flight_nonce(CHAIN, wallet(), "0xfour").await,
+        None
+    );
+
+    manager
+        .record_mined(CHAIN, wallet(), "0xfour-replaced")
+        .await;
+    clock.advance(60);
+    let stuck = manager.stuck(CHAIN, wallet()).await;
+    assert_eq!(stuck.len(), 1);
+    assert_eq!(stuck[0].nonce, 5);
+}
+
+#[tokio::test]
+async fn reconciling_drops_what_the_chain_mined() {
+    let manager = NonceManager::new();
+    for (nonce, hash) in [(4, "0xfour"), (5, "0xfive"), (6, "0xsix")] {
+        manager.record_sent(CHAIN, wallet(), nonce, hash).await;
+    }
+
+    let (provider, mock) = Provider::mocked();
+    mock.push(U256::from(6)).unwrap();
+    assert_eq!(
+        manager.reconcile(&provider, CHAIN, wallet()).await.unwrap(),
+        2
+    );
+    assert_eq!(
+        manager.in_flight_nonce(CHAIN, wallet(), "0xfive").await,
+        None
+    );
+    assert_eq!(
+        manager.in_flight_nonce(CHAIN, wallet(), "0xsix").await,
+        Some(6)
+    );
+}
@@ -0,0 +1,397 @@
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::context::RequestContext;
+use x402_sdk::core::X402;
+use x402_sdk::ledger::{Ledger, VerifiedOwner};
+use x402_sdk::notifications::{
+    Contact, DeliveryChannel, MessageTemplate, NoticeKind, Notification, NotificationError,
+    Notifier, PaymentNotice, SlackChannel, SmtpChannel, WebhookChannel,
+};
+use x402_sdk::testing::{MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+/// Channel handing the deliveries to the test, failing the first
+/// `failures` attempts.
+struct RecordingChannel {
+    deliveries: UnboundedSender<(Contact, Notification)>,
+    failures: AtomicU32,
+    attempts: AtomicU32,
+}
+
+impl RecordingChannel {
+    fn new(failures: u32) -> (Arc<Self>, UnboundedReceiver<(Contact, Notification)>) {
+        let (deliveries, received) = unbounded_channel();
+        let channel = Arc::new(Self {
+            deliveries,
+            failures: AtomicU32::new(failures),
+            attempts: AtomicU32::new(0),
+        });
+        (channel, received)
+    }
+}
+
+#[async_trait]
+impl DeliveryChannel for RecordingChannel {
+    async fn deliver(
+        &self,
+        contact: &Contact,
+        notification: &Notification,
+    ) -> Result<(), NotificationError> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        if contact.email.is_none() {
+            return Err(NotificationError::NoAddress);
+        }
+        if self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            })
+            .is_ok()
+        {
+            return Err(NotificationError::DeliveryFailed(
+                "mailbox busy".to_string(),
+            ));
+        }
+        self.deliveries
+            .send((contact.clone(), notification.clone()))
+            .unwrap();
+        Ok(())
+    }
+}
+
+fn notice() -> PaymentNotice {
+    PaymentNotice {
+        kind: NoticeKind::PaymentConfirmed,
+        timestamp: 1_700_000_000,
+        merchant: "Example Data".to_string(),
+        payer: PAYER.to_string(),
+        resource: "GET /premium".to_string(),
+        amount: "1000".to_string(),
+        nonce: Some("nonce-1".to_string()),
+        account: None,
+        chain: Some("Ethereum".to_string()),
+        transaction_hash: Some("0xabc".to_string()),
+        explorer_url: None,
+    }
+}
+
+fn engine(notifier: &Arc<Notifier>) -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    (engine.with_event_listener(notifier.clone()), verifier)
+}
+
+async fn challenge(engine: &X402) -> String {
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce
+}
+
+async fn next(
+    received: &mut UnboundedReceiver<(Contact, Notification)>,
+) -> (Contact, Notification) {
+    tokio::time::timeout(Duration::from_secs(5), received.recv())
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn templates_fill_the_notice_fields() {
+    let template = MessageTemplate::new(
+        "{merchant}: {amount} paid",
+        "{payer} paid for {resource} in {transaction_hash}{explorer_url}, {unknown}",
+    );
+    let notification = template.render(&notice());
+    assert_eq!(notification.subject, "Example Data: 1000 paid");
+    assert_eq!(
+        notification.body,
+        format!("{} paid for GET /premium in 0xabc, {{unknown}}", PAYER)
+    );
+    assert_eq!(notification.notice, notice());
+
+    let default = MessageTemplate::default().render(&notice());
+    assert_eq!(default.subject, "Payment received by Example Data");
+    assert!(default.body.contains("Chain: Ethereum"));
+}
+
+#[tokio::test]
+async fn the_session_contact_is_told_once() {
+    let (channel, mut received) = RecordingChannel::new(0);
+    let notifier = Arc::new(Notifier::new("Example Data").with_channel(channel.clone()));
+    let (engine, verifier) = engine(&notifier);
+    let nonce = challenge(&engine).await;
+    notifier.attach_to_session(&nonce, Contact::new().with_email("agent@example.org"));
+
+    verifier.set_paid_amount(Some(1000));
+    for _ in 0..2 {
+        let result = engine
+            .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+            .await
+            .unwrap();
+        assert!(result.should_serve_content);
+    }
+
+    let (contact, notification) = next(&mut received).await;
+    assert_eq!(contact.email.as_deref(), Some("agent@example.org"));
+    assert_eq!(notification.subject, "Payment received by Example Data");
+    assert_eq!(notification.notice.kind, NoticeKind::PaymentConfirmed);
+    assert_eq!(notification.notice.nonce, Some(nonce));
+    assert_eq!(notification.notice.payer, PAYER);
+    assert_eq!(notification.notice.amount, "1000");
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(received.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn budget_draws_notify_the_account_contact() {
+    let (channel, mut received) = RecordingChannel::new(0);
+    let notifier = Arc::new(Notifier::new("Example Data").with_channel(channel));
+    notifier.attach_to_account(
+        "research-agent",
+        Contact::new().with_email("finance@example.org"),
+    );
+    let ledger = Arc::new(Ledger::new());
+    ledger.open_account("research-agent", PAYER, 0).unwrap();
+    ledger
+        .deposit("research-agent", 5_000, "0xdeposit")
+        .unwrap();
+    let (engine, _verifier) = engine(&notifier);
+    let engine = engine.with_ledger(ledger);
+
+    let context = RequestContext::new("GET").with_extension(VerifiedOwner(PAYER.to_string()));
+    let result = engine
+        .handle_access_request_with_context(PAYER, "/premium", None, Some("1000"), &context)
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 200);
+
+    let (contact, notification) = next(&mut received).await;
+    assert_eq!(contact.email.as_deref(), Some("finance@example.org"));
+    assert_eq!(notification.notice.kind, NoticeKind::BudgetDrawn);
+    assert_eq!(
+        notification.notice.account.as_deref(),
+        Some("research-agent")
+    );
+    assert_eq!(notification.notice.amount, "1000");
+}
+
+#[tokio::test]
+async fn a_contact_on_the_request_wins_over_the_payer_contact() {
+    let (channel, mut received) = RecordingChannel::new(0);
+    let notifier = Arc::new(Notifier::new("Example Data").with_channel(channel));
+    // payer addresses match in any casing
+    notifier.attach_to_account(
+        &PAYER.to_uppercase(),
+        Contact::new().with_email("payer@example.org"),
+    );
+    let (engine, verifier) = engine(&notifier);
+    let nonce = challenge(&engine).await;
+    verifier.set_paid_amount(Some(1000));
+
+    let context =
+        RequestContext::new("GET").with_extension(Contact::new().with_email("request@example.org"));
+    engine
+        .handle_access_request_with_context(PAYER, "/premium", Some(&nonce), None, &context)
+        .await
+        .unwrap();
+    let (contact, _) = next(&mut received).await;
+    assert_eq!(contact.email.as_deref(), Some("request@example.org"));
+
+    let nonce = challenge(&engine).await;
+    engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    let (contact, _) = next(&mut received).await;
+    assert_eq!(contact.email.as_deref(), Some("payer@example.org"));
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried() {
+    let (channel, mut received) = RecordingChannel::new(2);
+    let notifier = Notifier::new("Example Data")
+        .with_channel(channel.clone())
+        .with_retries(3, 0);
+    let contact = Contact::new().with_email("agent@example.org");
+    let notification = MessageTemplate::default().render(&notice());
+
+    assert!(notifier.send(&contact, &notification).await.is_empty());
+    assert_eq!(channel.attempts.load(Ordering::SeqCst), 3);
+    assert_eq!(next(&mut received).await.1, notification);
+
+    let (channel, _received) = RecordingChannel::new(u32::MAX);
+    let notifier = Notifier::new("Example Data")
+        .with_channel(channel.clone())
+        .with_retries(2, 0);
+    let errors = notifier.send(&contact, &notification).await;
+    assert!(matches!(
+        errors.as_slice(),
+        [NotificationError::DeliveryFailed(_)]
+    ));
+    assert_eq!(channel.attempts.load(Ordering::SeqCst), 2);
+
+    // contacts without an address for the channel are skipped at once
+    let (channel, _received) = RecordingChannel::new(0);
+    let notifier = Notifier::new("Example Data").with_channel(channel.clone());
+    assert!(
+        notifier
+            .send(&Contact::new(), &notification)
+            .await
+            .is_empty()
+    );
+    assert_eq!(channel.attempts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn webhooks_receive_the_contact_and_notification() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hooks/payments", listener.local_addr().unwrap());
+    let posted = Arc::new(Mutex::new(None));
+    let recorded = posted.clone();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        // the body is the JSON after the head, complete once it parses
+        let body = loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((_, body)) = text.split_once("\r\n\r\n")
+                && let Ok(body) = serde_json::from_str::<serde_json::Value>(body)
+            {
+                break body;
+            }
+        };
+        *recorded.lock().unwrap() = Some(body);
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+    });
+
+    let contact = Contact::new().with_name("Agent");
+    let notification = MessageTemplate::default().render(&notice());
+    WebhookChannel::new()
+        .with_url(&url)
+        .deliver(&contact, &notification)
+        .await
+        .unwrap();
+    let body = posted.lock().unwrap().take().unwrap();
+    assert_eq!(
+        serde_json::from_value::<Contact>(body["contact"].clone()).unwrap(),
+        contact
+    );
+    assert_eq!(
+        serde_json::from_value::<Notification>(body["notification"].clone()).unwrap(),
+        notification
+    );
+
+    assert!(matches!(
+        WebhookChannel::new()
+            .deliver(&Contact::new(), &notification)
+            .await,
+        Err(NotificationError::NoAddress)
+    ));
+}
+
+#[tokio::test]
+async fn contact_webhooks_need_https_on_an_allowed_host() {
+    let notification = MessageTemplate::default().render(&notice());
+    let refused = |url: &str| Contact::new().with_webhook_url(url);
+    let channel = WebhookChannel::new().with_allowed_hosts(&["crm.example.com"]);
+    for contact in [
+        refused("http://crm.example.com/paid"),
+        refused("https://169.254.169.254/latest/meta-data"),
+        refused("https://crm.example.com.evil.example/paid"),
+        refused("not a url"),
+    ] {
+        assert!(matches!(
+            channel.deliver(&contact, &notification).await,
+            Err(NotificationError::AddressRefused(_))
+        ));
+    }
+    // no host is allowed by default
+    assert!(matches!(
+        WebhookChannel::new()
+            .deliver(&refused("https://crm.example.com/paid"), &notification)
+            .await,
+        Err(NotificationError::AddressRefused(_))
+    ));
+
+    let contact = Contact::new().with_slack_webhook_url("https://attacker.example/hook");
+    assert!(matches!(
+        SlackChannel::new().deliver(&contact, &notification).await,
+        Err(NotificationError::AddressRefused(_))
+    ));
+
+    // refused addresses are reported without retrying
+    let notifier = Notifier::new("Example Data")
+        .with_channel(Arc::new(channel))
+        .with_retries(3, 0);
+    let errors = notifier
+        .send(&refused("http://crm.example.com/paid"), &notification)
+        .await;
+    assert!(matches!(
+        errors.as_slice(),
+        [NotificationError::AddressRefused(_)]
+    ));
+}
+
+#[tokio::test]
+async fn smtp_credentials_go_to_a_loopback_relay() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let relay = listener.local_addr().unwrap().to_string();
+    let commands = Arc::new(Mutex::new(Vec::new()));
+    let recorded = commands.clone();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"220 relay ready\r\n").await.unwrap();
+        let mut in_data = false;
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let reply: &[u8] = if in_data {
+                if line != "." {
+                    continue;
+                }
+                in_data = false;
+                b"250 queued\r\n"
+            } else if line.starts_with("AUTH") {
+                b"235 authenticated\r\n"
+            } else if line == "DATA" {
+                in_data = true;
+                b"354 go ahead\r\n"
+            } else if line == "QUIT" {
+                b"221 bye\r\n"
+            } else {
+                b"250 ok\r\n"
+            };
+            recorded.lock().unwrap().push(line);
+            writer.write_all(reply).await.unwrap();
+        }
+    });
+
+    let contact = Contact::new().with_email("agent@example.org");
+    let notification = MessageTemplate::default().render(&notice());
+    SmtpChannel::new(&relay, "billing@example.org")
+        .with_credentials("billing", "secret")
+        .deliver(&contact, &notification)
+        .await
+        .unwrap();
+    let commands = commands.lock().unwrap();
+    assert!(commands.iter().any(|line| line.starts_with("AUTH PLAIN ")));
+    assert_eq!(commands.last().map(String::as_str), Some("QUIT"));
+}
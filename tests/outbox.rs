@@ -0,0 +1,209 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use x402_sdk::context::RequestContext;
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::events::{EventListener, PaymentEvent, PaymentEventKind};
+use x402_sdk::outbox::{DrainReport, EventSink, OutboxDispatcher, SinkError};
+use x402_sdk::store::{InMemoryOutboxStore, OutboxEntry, OutboxStore, StoreError};
+use x402_sdk::testing::MockClock;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+/// sink failing the first `failures` deliveries
+#[derive(Default)]
+struct FlakySink {
+    failures: Mutex<u32>,
+    delivered: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl EventSink for FlakySink {
+    async fn deliver(&self, entry: &OutboxEntry) -> Result<(), SinkError> {
+        let mut failures = self.failures.lock().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(SinkError::Network("connection refused".to_string()));
+        }
+        self.delivered.lock().unwrap().push(entry.id.clone());
+        Ok(())
+    }
+}
+
+/// outbox whose backend is down
+struct UnavailableOutbox;
+
+#[async_trait]
+impl OutboxStore for UnavailableOutbox {
+    async fn enqueue(&self, _entry: OutboxEntry) -> Result<(), StoreError> {
+        Err(StoreError::Backend("connection refused".to_string()))
+    }
+
+    async fn update(&self, _entry: &OutboxEntry) -> Result<(), StoreError> {
+        Err(StoreError::Backend("connection refused".to_string()))
+    }
+
+    async fn acknowledge(&self, _id: &str) -> Result<(), StoreError> {
+        Err(StoreError::Backend("connection refused".to_string()))
+    }
+
+    async fn due(&self, _now: u64, _limit: usize) -> Result<Vec<OutboxEntry>, StoreError> {
+        Err(StoreError::Backend("connection refused".to_string()))
+    }
+
+    async fn failed(&self) -> Result<Vec<OutboxEntry>, StoreError> {
+        Err(StoreError::Backend("connection refused".to_string()))
+    }
+}
+
+#[derive(Default)]
+struct CountingListener {
+    events: Mutex<u32>,
+}
+
+impl EventListener for CountingListener {
+    fn on_event(&self, _event: &PaymentEvent) {
+        *self.events.lock().unwrap() += 1;
+    }
+}
+
+#[tokio::test]
+async fn events_are_persisted_before_delivery() {
+    let outbox = Arc::new(InMemoryOutboxStore::new());
+    let engine = X402::from_default_config()
+        .unwrap()
+        .with_outbox_store(outbox.clone());
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+
+    let due = outbox.due(u64::MAX, 10).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert!(matches!(
+        due[0].event.kind,
+        PaymentEventKind::ChallengeIssued { .. }
+    ));
+    assert!(!due[0].id.is_empty());
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried_with_the_same_id() {
+    let outbox = Arc::new(InMemoryOutboxStore::new());
+    let clock = Arc::new(MockClock::new(1_000));
+    let engine = X402::from_default_config()
+        .unwrap()
+        .with_clock(clock.clone())
+        .with_outbox_store(outbox.clone());
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    let id = outbox.due(1_000, 10).await.unwrap()[0].id.clone();
+
+    let sink = Arc::new(FlakySink {
+        failures: Mutex::new(1),
+        ..Default::default()
+    });
+    let dispatcher = OutboxDispatcher::new(outbox.clone(), sink.clone())
+        .with_clock(clock.clone())
+        .with_retries(3, 10, 60);
+    let report = dispatcher.drain().await.unwrap();
+    assert_eq!(
+        report,
+        DrainReport {
+            delivered: 0,
+            retried: 1,
+            failed: 0
+        }
+    );
+    // backing off
+    assert_eq!(dispatcher.drain().await.unwrap(), DrainReport::default());
+
+    clock.set(1_010);
+    assert_eq!(dispatcher.drain().await.unwrap().delivered, 1);
+    assert_eq!(*sink.delivered.lock().unwrap(), vec![id]);
+    assert!(outbox.due(u64::MAX, 10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn entries_are_given_up_after_the_last_attempt() {
+    let outbox = Arc::new(InMemoryOutboxStore::new());
+    let clock = Arc::new(MockClock::new(1_000));
+    let engine = X402::from_default_config()
+        .unwrap()
+        .with_clock(clock.clone())
+        .with_outbox_store(outbox.clone());
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+
+    let sink = Arc::new(FlakySink {
+        failures: Mutex::new(u32::MAX),
+        ..Default::default()
+    });
+    let dispatcher = OutboxDispatcher::new(outbox.clone(), sink)
+        .with_clock(clock.clone())
+        .with_retries(2, 10, 60);
+    assert_eq!(dispatcher.drain().await.unwrap().retried, 1);
+    clock.set(1_010);
+    assert_eq!(dispatcher.drain().await.unwrap().failed, 1);
+
+    let failed = outbox.failed().await.unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].attempts, 2);
+    assert!(outbox.due(u64::MAX, 10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn requests_fail_when_the_outbox_does_not_take_their_event() {
+    let listener = Arc::new(CountingListener::default());
+    let engine = X402::from_default_config()
+        .unwrap()
+        .with_outbox_store(Arc::new(UnavailableOutbox))
+        .with_event_listener(listener.clone());
+    let err = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        EngineError::StoreError(StoreError::Backend(_))
+    ));
+    assert_eq!(*listener.events.lock().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn credentials_never_reach_the_outbox() {
+    // the default config redacts nothing
+    let outbox = Arc::new(InMemoryOutboxStore::new());
+    let engine = X402::from_default_config()
+        .unwrap()
+        .with_outbox_store(outbox.clone());
+    let context = RequestContext::new("POST")
+        .with_header("Authorization", "Bearer secret-token")
+        .with_header("Cookie", "session=secret-cookie")
+        .with_header("X-Budget-Key", "bk_secret")
+        .with_header("Content-Type", "application/json")
+        .with_client_ip("203.0.113.7".parse().unwrap());
+    engine
+        .handle_access_request_with_context(PAYER, "/premium", None, Some("1000"), &context)
+        .await
+        .unwrap();
+
+    let entry = outbox.due(u64::MAX, 10).await.unwrap().remove(0);
+    let event_context = &entry.event.context;
+    assert_eq!(event_context.method, "POST");
+    assert_eq!(event_context.header("authorization"), None);
+    assert_eq!(event_context.header("cookie"), None);
+    assert_eq!(event_context.header("x-budget-key"), None);
+    assert_eq!(
+        event_context.header("content-type"),
+        Some("application/json")
+    );
+    assert_eq!(event_context.client_ip, None);
+    let json = serde_json::to_string(&entry).unwrap();
+    for secret in ["secret-token", "secret-cookie", "bk_secret", "203.0.113.7"] {
+        assert!(!json.contains(secret), "{} reached the outbox", secret);
+    }
+}
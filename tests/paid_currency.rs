@@ -0,0 +1,80 @@
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::resource::Resource;
+use x402_sdk::testing::{MockVerifier, mock_engine};
+use x402_sdk::types::{Currency, ErrorReason};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+fn engine() -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    verifier.set_paid_amount(Some(5000));
+    (engine, verifier)
+}
+
+async fn issue(engine: &X402) -> String {
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("5000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce
+}
+
+#[tokio::test]
+async fn verifications_record_the_paying_currency() {
+    let (engine, _verifier) = engine();
+    let nonce = issue(&engine).await;
+    let verification = engine
+        .verify_payment(PAYER, &nonce, &Resource::new("GET", "/premium").unwrap())
+        .await
+        .unwrap();
+    assert!(verification.is_paid);
+    assert!(matches!(verification.paid_currency, Some(Currency::Native)));
+}
+
+#[tokio::test]
+async fn payments_in_another_currency_are_refused() {
+    let (engine, verifier) = engine();
+    let nonce = issue(&engine).await;
+    verifier.set_paid_currency(Some(Currency::Token {
+        address: USDC.to_string(),
+        decimals: 6,
+    }));
+    let verification = engine
+        .verify_payment(PAYER, &nonce, &Resource::new("GET", "/premium").unwrap())
+        .await
+        .unwrap();
+    assert!(!verification.is_paid);
+    assert_eq!(verification.paid_amount, "0");
+    assert_eq!(verification.failure_reason, Some(ErrorReason::WrongToken));
+}
+
+#[test]
+fn token_currencies_compare_by_address() {
+    let usdc = Currency::Token {
+        address: USDC.to_lowercase(),
+        decimals: 6,
+    };
+    let paid = Currency::Token {
+        address: USDC.to_string(),
+        decimals: 6,
+    };
+    assert!(usdc.accepts(&paid));
+    assert!(!Currency::Native.accepts(&paid));
+    assert!(!usdc.accepts(&Currency::Native));
+
+    let allowlisted = Currency::AnyToken {
+        allowlist: vec![USDC.to_string()],
+    };
+    assert!(allowlisted.accepts(&paid));
+    assert!(Currency::AnyToken { allowlist: vec![] }.accepts(&paid));
+    let other = Currency::Token {
+        address: "0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string(),
+        decimals: 6,
+    };
+    assert!(!allowlisted.accepts(&other));
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "macros")]
+
+use std::sync::Arc;
+use x402_sdk::core::X402;
+use x402_sdk::paid;
+use x402_sdk::pricing::RulePricing;
+use x402_sdk::types::{ChainType, Currency, EvmChain};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+#[paid(
+    amount = "0.001 ETH",
+    chain = "ethereum",
+    route = "GET /reports/**",
+    description = "Quarterly report"
+)]
+async fn report() -> &'static str {
+    "paid report"
+}
+
+#[paid(amount = "0.10 USDC", chain = "base")]
+async fn search(query: String) -> String {
+    query
+}
+
+#[tokio::test]
+async fn handlers_keep_working() {
+    assert_eq!(report().await, "paid report");
+    assert_eq!(search("x402".to_string()).await, "x402");
+}
+
+#[test]
+fn handlers_carry_their_price() {
+    assert_eq!(report::PAID.name, "report");
+    assert_eq!(report::PAID.price, "0.001 ETH");
+    assert_eq!(report::PAID.route, Some("GET /reports/**"));
+    assert_eq!(search::PAID.chain, Some("base"));
+    assert_eq!(search::PAID.route, None);
+}
+
+#[test]
+fn stablecoin_prices_are_charged_in_usd() {
+    let pricing = RulePricing::new()
+        .with_paid_route("/search", &search::PAID)
+        .unwrap();
+    let rule = &pricing.rules()[0];
+    assert_eq!(rule.amount, "100000");
+    assert_eq!(rule.chain, Some(ChainType::Evm(EvmChain::Base)));
+    assert!(matches!(
+        &rule.currency,
+        Some(Currency::AnyToken { allowlist }) if allowlist.len() == 1
+    ));
+    // without a route the handler can only be mounted explicitly
+    assert!(RulePricing::new().with_paid_handler(&search::PAID).is_err());
+}
+
+#[tokio::test]
+async fn the_engine_charges_paid_routes() {
+    let pricing = RulePricing::new().with_paid_handler(&report::PAID).unwrap();
+    let engine = X402::from_default_config()
+        .unwrap()
+        .with_pricing_provider(Arc::new(pricing));
+    let challenge = engine
+        .handle_access_request(PAYER, "/reports/q3", None, None)
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap();
+    assert_eq!(challenge.payment_required.amount, "1000000000000000");
+    assert!(matches!(
+        challenge.payment_required.currency,
+        Currency::Native
+    ));
+    assert_eq!(
+        challenge.payment_required.description.as_deref(),
+        Some("Quarterly report")
+    );
+}
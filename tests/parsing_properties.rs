@@ -0,0 +1,153 @@
+use proptest::prelude::*;
+use x402_sdk::headers::{
+    WwwAuthenticate, decode_payment_required, encode_payment_required, parse_challenge_headers,
+};
+use x402_sdk::types::{
+    ChainConfig, ChainType, Currency, PaymentMetadata, PaymentRequest, X402ProtocolResponse,
+};
+use x402_sdk::verifier::solana::SolanaVerifier;
+
+fn challenge(
+    amount: u128,
+    nonce: String,
+    recipient: String,
+    description: Option<String>,
+    expires_at: Option<u64>,
+) -> X402ProtocolResponse {
+    X402ProtocolResponse {
+        status: 402,
+        payment_required: PaymentRequest {
+            amount: amount.to_string(),
+            currency: Currency::Native,
+            recipient,
+            chain: ChainConfig::new(ChainType::ethereum(), None),
+            description,
+            expires_at,
+            nonce,
+            beneficiary: None,
+            reference: None,
+            metadata: PaymentMetadata::new(),
+            quote: None,
+        },
+        verification_url: Some("https://example.com/verify".to_string()),
+        signature: None,
+        message: None,
+        requote: None,
+    }
+}
+
+proptest! {
+    #[test]
+    fn lamports_parse_integers_exactly(lamports: u64) {
+        prop_assert_eq!(
+            SolanaVerifier::parse_amount_to_lamports(&lamports.to_string()),
+            Ok(lamports)
+        );
+    }
+
+    #[test]
+    fn lamports_parse_sol_decimals_exactly(lamports: u64, places in 1usize..=9) {
+        let sol = lamports / 1_000_000_000;
+        let fraction = format!("{:09}", lamports % 1_000_000_000);
+        let amount = format!("{}.{}", sol, &fraction[..places]);
+        let truncated = lamports - lamports % 10u64.pow(9 - places as u32);
+        prop_assert_eq!(SolanaVerifier::parse_amount_to_lamports(&amount), Ok(truncated));
+    }
+
+    #[test]
+    fn lamports_reject_negative_and_overflowing_amounts(
+        lamports in 1u64..,
+        extra in 1u128..1_000_000_000,
+    ) {
+        let overflow = u128::from(u64::MAX) + extra;
+        let sol = overflow / 1_000_000_000 + 1;
+        for amount in [
+            format!("-{}", lamports),
+            format!("-{}.5", lamports),
+            overflow.to_string(),
+            format!("{}.0", sol),
+        ] {
+            prop_assert!(SolanaVerifier::parse_amount_to_lamports(&amount).is_err());
+        }
+    }
+
+    #[test]
+    fn lamports_never_panic(amount in "\\PC*") {
+        let _ = SolanaVerifier::parse_amount_to_lamports(&amount);
+    }
+
+    #[test]
+    fn token_amounts_round_trip(value: u128, decimals in 0u8..=38) {
+        let digits = format!("{:0>width$}", value, width = usize::from(decimals) + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - usize::from(decimals));
+        let amount = match fraction.is_empty() {
+            true => whole.to_string(),
+            false => format!("{}.{}", whole, fraction),
+        };
+        prop_assert_eq!(SolanaVerifier::parse_token_amount(&amount, decimals), Ok(value));
+    }
+
+    #[test]
+    fn token_amounts_never_panic(amount in "[0-9.,+-]{0,48}|\\PC*", decimals: u8) {
+        let _ = SolanaVerifier::parse_token_amount(&amount, decimals);
+    }
+
+    #[test]
+    fn payment_required_header_round_trips(
+        amount: u128,
+        nonce in "\\PC{1,64}",
+        recipient in "0x[0-9a-f]{40}",
+        description in proptest::option::of("\\PC*"),
+        expires_at: Option<u64>,
+    ) {
+        let response = challenge(amount, nonce, recipient, description, expires_at);
+        let header = encode_payment_required(&response).unwrap();
+        prop_assert!(header.is_ascii());
+        let decoded = decode_payment_required(&header).unwrap();
+        prop_assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&response).unwrap()
+        );
+        let parsed = parse_challenge_headers([("x-payment-required", header.as_str())]).unwrap();
+        prop_assert_eq!(parsed.payment_required.nonce, response.payment_required.nonce);
+    }
+
+    #[test]
+    fn payment_required_header_never_panics(value in "\\PC*") {
+        let _ = decode_payment_required(&value);
+    }
+
+    #[test]
+    fn payment_required_json_never_panics(json in "\\PC*") {
+        use base64::Engine;
+        let value = base64::engine::general_purpose::STANDARD.encode(json.as_bytes());
+        let _ = decode_payment_required(&value);
+    }
+
+    #[test]
+    fn www_authenticate_round_trips(
+        realm in "\\PC*",
+        nonce in "\\PC*",
+        amount in "[0-9]{1,40}",
+        recipient in "\\PC*",
+        chain_id in "\\PC*",
+        expires_at: Option<u64>,
+        verification_url in proptest::option::of("\\PC*"),
+    ) {
+        let header = WwwAuthenticate {
+            realm,
+            nonce,
+            amount,
+            recipient,
+            chain_id,
+            expires_at,
+            verification_url,
+        };
+        prop_assert_eq!(WwwAuthenticate::parse(&header.to_string()).unwrap(), header);
+    }
+
+    #[test]
+    fn www_authenticate_never_panics(value in "(?i:x402) [a-z_=\",\\\\ ]{0,64}|\\PC*") {
+        let _ = WwwAuthenticate::parse(&value);
+    }
+}
@@ -0,0 +1,88 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use x402_sdk::headers::{
+    HeaderError, decode_payment_required, decode_payment_required_with, encode_payment_required,
+};
+use x402_sdk::limits::{PayloadError, PayloadLimits};
+use x402_sdk::types::{
+    ChainConfig, ChainType, Currency, PaymentMetadata, PaymentRequest, X402ProtocolResponse,
+};
+
+fn challenge() -> X402ProtocolResponse {
+    X402ProtocolResponse {
+        status: 402,
+        payment_required: PaymentRequest {
+            amount: "1000".to_string(),
+            currency: Currency::Native,
+            recipient: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+            chain: ChainConfig::new(ChainType::ethereum(), None),
+            description: None,
+            expires_at: None,
+            nonce: "nonce".to_string(),
+            beneficiary: None,
+            reference: None,
+            metadata: PaymentMetadata::new(),
+            quote: None,
+        },
+        verification_url: None,
+        signature: None,
+        message: None,
+        requote: None,
+    }
+}
+
+/// the challenge header with an extra field in the payment request
+fn with_unknown_field() -> String {
+    let mut json = serde_json::to_value(challenge()).unwrap();
+    json["payment_required"]["injected"] = serde_json::json!("value");
+    STANDARD.encode(serde_json::to_vec(&json).unwrap())
+}
+
+#[test]
+fn strict_parsing_rejects_unknown_fields() {
+    let header = with_unknown_field();
+    // the lenient parsers drop the field
+    assert!(decode_payment_required(&header).is_ok());
+    assert!(decode_payment_required_with(&header, &PayloadLimits::default()).is_ok());
+
+    match decode_payment_required_with(&header, &PayloadLimits::strict()) {
+        Err(HeaderError::Rejected(PayloadError::UnknownField(path))) => {
+            assert_eq!(path, "payment_required.injected")
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    let header = encode_payment_required(&challenge()).unwrap();
+    assert!(decode_payment_required_with(&header, &PayloadLimits::strict()).is_ok());
+}
+
+#[test]
+fn oversized_payloads_are_refused_before_decoding() {
+    let header = "A".repeat(64);
+    let limits = PayloadLimits::default().with_max_encoded_bytes(32);
+    assert!(matches!(
+        decode_payment_required_with(&header, &limits),
+        Err(HeaderError::Rejected(PayloadError::TooLarge {
+            len: 64,
+            max: 32
+        }))
+    ));
+}
+
+#[test]
+fn chains_and_schemes_can_be_whitelisted() {
+    let header = encode_payment_required(&challenge()).unwrap();
+    let base = PayloadLimits::strict().with_chains(&["8453"]);
+    assert!(matches!(
+        decode_payment_required_with(&header, &base),
+        Err(HeaderError::Rejected(PayloadError::ChainNotAllowed(chain))) if chain == "1"
+    ));
+    let tokens_only = PayloadLimits::strict()
+        .with_chains(&["1"])
+        .with_schemes(&["token"]);
+    assert!(matches!(
+        decode_payment_required_with(&header, &tokens_only),
+        Err(HeaderError::Rejected(PayloadError::SchemeNotAllowed(_)))
+    ));
+    let native = PayloadLimits::strict().with_schemes(&["native", "token"]);
+    assert!(decode_payment_required_with(&header, &native).is_ok());
+}
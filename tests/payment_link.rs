@@ -0,0 +1,168 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use x402_sdk::config::{ConfigBuilder, ConfigManager};
+use x402_sdk::core::X402;
+use x402_sdk::notifications::{
+    Contact, DeliveryChannel, Notification, NotificationError, Notifier,
+};
+use x402_sdk::payment_link::{PaymentLinkError, PaymentLinks};
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const WEBHOOK: &str = "https://shop.example.com/paid";
+
+#[derive(Clone, Default)]
+struct RecordingChannel {
+    delivered: Arc<Mutex<Vec<(Contact, Notification)>>>,
+}
+
+#[async_trait]
+impl DeliveryChannel for RecordingChannel {
+    async fn deliver(
+        &self,
+        contact: &Contact,
+        notification: &Notification,
+    ) -> Result<(), NotificationError> {
+        self.delivered
+            .lock()
+            .unwrap()
+            .push((contact.clone(), notification.clone()));
+        Ok(())
+    }
+}
+
+struct Setup {
+    links: PaymentLinks,
+    verifier: MockVerifier,
+    channel: RecordingChannel,
+    clock: Arc<MockClock>,
+}
+
+fn setup() -> Setup {
+    let clock = Arc::new(MockClock::new(1_000));
+    let channel = RecordingChannel::default();
+    let notifier = Arc::new(Notifier::new("Example Data").with_channel(Arc::new(channel.clone())));
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().with_expiration_time(600).build());
+    let engine = engine
+        .with_clock(clock.clone())
+        .with_event_listener(notifier.clone());
+    let links = PaymentLinks::new(
+        Arc::new(engine),
+        "https://pay.example.com/links/",
+        b"secret",
+    )
+    .with_notifier(notifier);
+    Setup {
+        links,
+        verifier,
+        channel,
+        clock,
+    }
+}
+
+#[tokio::test]
+async fn links_encode_the_session() {
+    let setup = setup();
+    let link = setup
+        .links
+        .create(PAYER, "/reports/q3.pdf", Some("5000"), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        link.url,
+        format!("https://pay.example.com/links/{}", link.token)
+    );
+    assert_eq!(link.expires_at, Some(1_600));
+    let claims = setup.links.verify(&link.token).unwrap();
+    assert_eq!(claims.payer, PAYER);
+    assert_eq!(claims.resource_path, "/reports/q3.pdf");
+    assert_eq!(claims.challenge.payment_required.nonce, link.nonce);
+    assert_eq!(claims.challenge.payment_required.amount, "5000");
+}
+
+#[tokio::test]
+async fn tampered_links_are_rejected() {
+    let setup = setup();
+    let link = setup
+        .links
+        .create(PAYER, "/reports/q3.pdf", Some("5000"), None)
+        .await
+        .unwrap();
+    let (payload, signature) = link.token.split_once('.').unwrap();
+    let forged = format!("{}A.{}", payload, signature);
+    assert!(matches!(
+        setup.links.verify(&forged),
+        Err(PaymentLinkError::InvalidSignature)
+    ));
+    let other = PaymentLinks::new(
+        Arc::new(X402::new(ConfigManager::from_config(ConfigBuilder::new().build())).unwrap()),
+        "https://pay.example.com/links",
+        b"other-secret",
+    );
+    assert!(matches!(
+        other.verify(&link.token),
+        Err(PaymentLinkError::InvalidSignature)
+    ));
+    assert!(matches!(
+        setup.links.verify("not-a-token"),
+        Err(PaymentLinkError::Malformed(_))
+    ));
+}
+
+#[tokio::test]
+async fn the_page_shows_the_payment_until_the_session_expires() {
+    let setup = setup();
+    let link = setup
+        .links
+        .create(PAYER, "/reports/q3.pdf", Some("5000"), None)
+        .await
+        .unwrap();
+    let page = setup.links.page(&link.token).unwrap();
+    assert!(page.contains("x402-payment-required"));
+    assert!(page.contains("/reports/q3.pdf"));
+    assert!(page.contains(&link.nonce));
+
+    setup.clock.set(1_600);
+    assert!(matches!(
+        setup.links.page(&link.token),
+        Err(PaymentLinkError::Expired { expired_at: 1_600 })
+    ));
+}
+
+#[tokio::test]
+async fn paid_links_fire_the_webhook_and_redeem_later() {
+    let setup = setup();
+    let link = setup
+        .links
+        .create(
+            PAYER,
+            "/reports/q3.pdf",
+            Some("5000"),
+            Some(Contact::new().with_webhook_url(WEBHOOK)),
+        )
+        .await
+        .unwrap();
+    assert!(!setup.links.confirm(&link.token).await.unwrap());
+
+    setup.verifier.set_paid_amount(Some(5000));
+    assert!(setup.links.confirm(&link.token).await.unwrap());
+    for _ in 0..50 {
+        if !setup.channel.delivered.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    {
+        let delivered = setup.channel.delivered.lock().unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].0.webhook_url.as_deref(), Some(WEBHOOK));
+        assert_eq!(
+            delivered[0].1.notice.nonce.as_deref(),
+            Some(link.nonce.as_str())
+        );
+    }
+
+    let result = setup.links.redeem(&link.token).await.unwrap();
+    assert!(result.should_serve_content);
+}
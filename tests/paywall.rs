@@ -0,0 +1,150 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::crypto::Ed25519Suite;
+use x402_sdk::keys::KeyRing;
+use x402_sdk::paywall::{PaywallOutcome, SignedUrlError, StaticPaywall, UrlSigner};
+use x402_sdk::testing::{MockClock, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const NOW: u64 = 1_700_000_000;
+const BASE_URL: &str = "https://files.example.com";
+
+fn signer() -> UrlSigner {
+    UrlSigner::new(BASE_URL, b"object-secret")
+}
+
+/// `path?query` of a signed URL, as the object server receives it
+fn target(url: &str) -> &str {
+    url.strip_prefix(BASE_URL).unwrap()
+}
+
+#[test]
+fn signed_urls_verify_until_they_expire() {
+    let url = signer().sign("/reports/q3.pdf", NOW + 60);
+    assert!(url.starts_with("https://files.example.com/reports/q3.pdf?expires=1700000060&"));
+    signer().verify(target(&url), NOW).unwrap();
+    signer().verify(target(&url), NOW + 59).unwrap();
+    assert!(matches!(
+        signer().verify(target(&url), NOW + 60),
+        Err(SignedUrlError::Expired)
+    ));
+
+    // another secret does not accept it
+    assert!(matches!(
+        UrlSigner::new(BASE_URL, b"other-secret").verify(target(&url), NOW),
+        Err(SignedUrlError::InvalidSignature)
+    ));
+}
+
+#[test]
+fn tampered_urls_are_refused() {
+    let url = signer().sign("/reports/q3.pdf", NOW + 60);
+    let query = target(&url).split_once('?').unwrap().1;
+
+    let other_object = format!("/reports/q4.pdf?{}", query);
+    assert!(matches!(
+        signer().verify(&other_object, NOW),
+        Err(SignedUrlError::InvalidSignature)
+    ));
+    let extended = target(&url).replace("expires=1700000060", "expires=1800000000");
+    assert!(matches!(
+        signer().verify(&extended, NOW),
+        Err(SignedUrlError::InvalidSignature)
+    ));
+    assert!(matches!(
+        signer().verify("/reports/q3.pdf?expires=1700000060", NOW),
+        Err(SignedUrlError::Malformed(_))
+    ));
+    assert!(matches!(
+        signer().verify(&format!("{}&signature=00", target(&url)), NOW),
+        Err(SignedUrlError::Malformed(_))
+    ));
+}
+
+#[test]
+fn dot_segments_do_not_bypass_the_signature() {
+    let url = signer().sign("/a/../b", NOW + 60);
+    assert!(url.starts_with("https://files.example.com/b?"));
+    let query = target(&url).split_once('?').unwrap().1;
+
+    // spellings of the signed path are the same object
+    signer().verify(&format!("/b?{}", query), NOW).unwrap();
+    signer().verify(&format!("/a/../b?{}", query), NOW).unwrap();
+    signer().verify(&format!("//b/./?{}", query), NOW).unwrap();
+
+    // a signature for a public object does not reach past it
+    let public = signer().sign("/public/a", NOW + 60);
+    let query = target(&public).split_once('?').unwrap().1;
+    for path in [
+        "/public/a/../../private/b",
+        "/public/../private/b",
+        "/public/a/..",
+    ] {
+        assert!(matches!(
+            signer().verify(&format!("{}?{}", path, query), NOW),
+            Err(SignedUrlError::InvalidSignature)
+        ));
+    }
+}
+
+#[tokio::test]
+async fn paid_requests_get_a_url_bounded_by_the_receipt() {
+    let key_ring = KeyRing::new(0);
+    key_ring.add_key("2026-01", Ed25519Suite::new([1; 32]), 0, None);
+    let config = ConfigBuilder::new().with_receipt_ttl(120).build();
+    let (engine, verifier) = mock_engine(config);
+    let engine = engine
+        .with_clock(Arc::new(MockClock::new(NOW)))
+        .with_key_ring(Arc::new(key_ring));
+    let paywall = StaticPaywall::new(Arc::new(engine), signer())
+        .with_object_prefix("/paid/")
+        .with_ttl(600);
+
+    let PaywallOutcome::Denied(result) = paywall
+        .request(PAYER, "/reports/q3.pdf", None, None)
+        .await
+        .unwrap()
+    else {
+        panic!("unpaid requests are challenged");
+    };
+    assert_eq!(result.http_status, 402);
+    let request = result.x402_response.unwrap().payment_required;
+
+    verifier.set_paid_amount(Some(request.amount.parse().unwrap()));
+    let PaywallOutcome::Granted { url, expires_at } = paywall
+        .request(PAYER, "/reports/q3.pdf", Some(&request.nonce), None)
+        .await
+        .unwrap()
+    else {
+        panic!("paid requests are granted");
+    };
+    assert_eq!(expires_at, NOW + 120);
+    assert!(url.starts_with("https://files.example.com/paid/reports/q3.pdf?"));
+    paywall.signer().verify(target(&url), NOW).unwrap();
+}
+
+#[tokio::test]
+async fn object_paths_stay_under_the_prefix() {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine.with_clock(Arc::new(MockClock::new(NOW)));
+    let paywall = StaticPaywall::new(Arc::new(engine), signer())
+        .with_object_prefix("/paid")
+        .with_ttl(60);
+    let path = "/../../secrets/key";
+    let PaywallOutcome::Denied(result) = paywall.request(PAYER, path, None, None).await.unwrap()
+    else {
+        panic!("unpaid requests are challenged");
+    };
+    let request = result.x402_response.unwrap().payment_required;
+    verifier.set_paid_amount(Some(request.amount.parse().unwrap()));
+    let PaywallOutcome::Granted { url, expires_at } = paywall
+        .request(PAYER, path, Some(&request.nonce), None)
+        .await
+        .unwrap()
+    else {
+        panic!("paid requests are granted");
+    };
+    // without a receipt the URL lives for the paywall TTL
+    assert_eq!(expires_at, NOW + 60);
+    assert!(url.starts_with("https://files.example.com/paid/secrets/key?"));
+}
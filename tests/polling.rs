@@ -0,0 +1,92 @@
+use std::time::Duration;
+use tokio::time::Instant;
+use x402_sdk::client::{Backoff, PollError, PollTarget, Poller};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::resource::Resource;
+use x402_sdk::testing::{MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn engine() -> (X402, MockVerifier) {
+    mock_engine(ConfigBuilder::new().build())
+}
+
+async fn issue(engine: &X402) -> String {
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("5000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce
+}
+
+fn poller() -> Poller {
+    Poller::new(Backoff {
+        initial: Duration::from_millis(10),
+        max: Duration::from_millis(40),
+        multiplier: 2,
+        jitter_percent: 20,
+    })
+}
+
+#[test]
+fn delays_grow_exponentially_up_to_the_cap() {
+    let backoff = Backoff::default();
+    let delays: Vec<u64> = (0..7)
+        .map(|attempt| backoff.base_delay(attempt).as_secs())
+        .collect();
+    assert_eq!(delays, vec![1, 2, 4, 8, 16, 30, 30]);
+    assert_eq!(backoff.base_delay(u32::MAX), Duration::from_secs(30));
+    for _ in 0..100 {
+        let delay = backoff.delay(2);
+        assert!(delay >= Duration::from_millis(3_200) && delay <= Duration::from_millis(4_800));
+    }
+}
+
+#[tokio::test]
+async fn polling_returns_once_the_payment_lands() {
+    let (engine, verifier) = engine();
+    let nonce = issue(&engine).await;
+    let resource = Resource::new("GET", "/premium").unwrap();
+
+    let payer = verifier.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        payer.set_paid_amount(Some(5000));
+    });
+    let target = PollTarget::Engine {
+        engine: &engine,
+        user_address: PAYER,
+        resource: &resource,
+    };
+    let paid = poller()
+        .poll_until_paid(target, &nonce, Instant::now() + Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert!(paid.attempts > 1);
+    assert!(paid.verification.unwrap().is_paid);
+    // backing off, not hot-looping
+    assert!(verifier.verified_requests().len() < 10);
+}
+
+#[tokio::test]
+async fn polling_gives_up_at_the_deadline() {
+    let (engine, _verifier) = engine();
+    let nonce = issue(&engine).await;
+    let resource = Resource::new("GET", "/premium").unwrap();
+    let target = PollTarget::Engine {
+        engine: &engine,
+        user_address: PAYER,
+        resource: &resource,
+    };
+    let started = Instant::now();
+    let err = poller()
+        .poll_until_paid(target, &nonce, started + Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, PollError::Timeout { attempts } if attempts >= 2));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
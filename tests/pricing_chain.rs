@@ -0,0 +1,97 @@
+use std::sync::Arc;
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::pricing::{PricingRule, RulePricing};
+use x402_sdk::resource::{ResourceError, ResourcePattern};
+use x402_sdk::testing::MockVerifier;
+use x402_sdk::types::{ChainType, Currency, EvmChain, PaymentRequest, SolanaChain};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn engine(pricing: RulePricing) -> X402 {
+    let mut engine = X402::from_default_config()
+        .unwrap()
+        .with_pricing_provider(Arc::new(pricing));
+    for chain in [ChainType::ethereum(), ChainType::Evm(EvmChain::Polygon)] {
+        engine
+            .verifier_registry_mut()
+            .register_verifier(chain, Box::new(MockVerifier::new()));
+    }
+    engine
+}
+
+async fn challenge(engine: &X402, path: &str) -> PaymentRequest {
+    engine
+        .handle_access_request(PAYER, path, None, None)
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+}
+
+#[tokio::test]
+async fn matched_rule_picks_its_chain() {
+    let engine = engine(
+        RulePricing::new()
+            .with_rule(
+                PricingRule::new(ResourcePattern::parse("/polygon-api/**"), "2000")
+                    .with_chain(ChainType::Evm(EvmChain::Polygon)),
+            )
+            .with_price("/**", "1000"),
+    );
+
+    let request = challenge(&engine, "/polygon-api/quotes").await;
+    assert_eq!(request.chain.chain_type, ChainType::Evm(EvmChain::Polygon));
+    assert!(matches!(request.currency, Currency::Native));
+    assert_eq!(request.amount, "2000");
+
+    let request = challenge(&engine, "/premium").await;
+    assert_eq!(request.chain.chain_type, ChainType::ethereum());
+    assert_eq!(request.amount, "1000");
+
+    let document = engine.discovery_document().unwrap();
+    assert_ne!(
+        document.items[0].accepts[0].chain_id,
+        document.items[1].accepts[0].chain_id
+    );
+}
+
+#[tokio::test]
+async fn rule_on_unconfigured_chain_is_a_config_error() {
+    let engine = engine(
+        RulePricing::new().with_rule(
+            PricingRule::new(ResourcePattern::parse("/**"), "1000")
+                .with_chain(ChainType::Solana(SolanaChain::Mainnet)),
+        ),
+    );
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, None)
+        .await;
+    assert!(matches!(result, Err(EngineError::ConfigError(_))));
+}
+
+#[tokio::test]
+async fn repeated_query_keys_are_refused() {
+    let engine = engine(
+        RulePricing::new()
+            .with_rule(PricingRule::new(
+                ResourcePattern::parse("/reports").with_query_equals("tier", "basic"),
+                "10",
+            ))
+            .with_price("/**", "1000"),
+    );
+    assert_eq!(challenge(&engine, "/reports?tier=basic").await.amount, "10");
+    assert_eq!(
+        challenge(&engine, "/reports?tier=full").await.amount,
+        "1000"
+    );
+
+    // the rule would see one of the values, the application maybe the other
+    let result = engine
+        .handle_access_request(PAYER, "/reports?tier=basic&tier=full", None, None)
+        .await;
+    assert!(matches!(
+        result,
+        Err(EngineError::InvalidResource(ResourceError::DuplicateQueryKey(key))) if key == "tier"
+    ));
+}
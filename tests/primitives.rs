@@ -0,0 +1,72 @@
+#![cfg(feature = "ethers-types")]
+
+use ethers::types::{H160, H256, U256};
+use std::str::FromStr;
+use x402_sdk::primitives::PrimitiveError;
+use x402_sdk::types::{ChainConfig, ChainType, PaymentVerification, TransactionLog};
+
+fn verification(value: &str) -> PaymentVerification {
+    let hash = format!("0x{}", "ab".repeat(32));
+    PaymentVerification {
+        is_paid: true,
+        paid_amount: value.to_string(),
+        transaction_hash: Some(hash.clone()),
+        verified_at: 1_700_000_000,
+        chain: ChainConfig::new(ChainType::ethereum(), None),
+        transaction_logs: vec![TransactionLog {
+            transaction_hash: hash,
+            from: "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5".to_string(),
+            to: "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(),
+            value: value.to_string(),
+            block_number: 1,
+            log_index: 0,
+            data: None,
+            explorer_url: None,
+        }],
+        explorer_url: None,
+        conversion: None,
+        paid_currency: None,
+        failure_reason: None,
+        proof: None,
+    }
+}
+
+#[test]
+fn ethers_accessors_parse_canonical_strings() {
+    let verification = verification(
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+    );
+    let log = &verification.transaction_logs[0];
+    assert_eq!(
+        log.ethers_from().unwrap(),
+        H160::from_str("0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5").unwrap()
+    );
+    assert_eq!(
+        log.ethers_to().unwrap(),
+        H160::from_str("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap()
+    );
+    assert_eq!(log.ethers_value().unwrap(), U256::MAX);
+    assert_eq!(
+        log.ethers_transaction_hash().unwrap(),
+        H256::repeat_byte(0xab)
+    );
+    assert_eq!(
+        verification.ethers_transaction_hash().unwrap(),
+        Some(H256::repeat_byte(0xab))
+    );
+    assert_eq!(verification.ethers_paid_amount().unwrap(), U256::MAX);
+}
+
+#[test]
+fn ethers_accessors_reject_malformed_strings() {
+    let mut verification = verification("1.5");
+    assert_eq!(
+        verification.ethers_paid_amount(),
+        Err(PrimitiveError::InvalidAmount("1.5".to_string()))
+    );
+    verification.transaction_logs[0].from = "0x742e...b4a5".to_string();
+    assert!(matches!(
+        verification.transaction_logs[0].ethers_from(),
+        Err(PrimitiveError::Address(_))
+    ));
+}
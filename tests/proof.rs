@@ -0,0 +1,41 @@
+use serde_json::json;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::types::{ChainConfig, ChainType, PaymentProof, PaymentVerification};
+
+#[test]
+fn proofs_are_tagged_by_chain_kind() {
+    let proof = PaymentProof::Solana {
+        slot: 250_000_000,
+        signature: "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW"
+            .to_string(),
+        commitment: "finalized".to_string(),
+    };
+    let value = serde_json::to_value(&proof).unwrap();
+    assert_eq!(value["kind"], "solana");
+    assert_eq!(value["slot"], 250_000_000);
+    assert_eq!(
+        serde_json::from_value::<PaymentProof>(value).unwrap(),
+        proof
+    );
+}
+
+#[test]
+fn verifications_without_proof_omit_the_section() {
+    let verification: PaymentVerification = serde_json::from_value(json!({
+        "is_paid": true,
+        "paid_amount": "1000",
+        "transaction_hash": null,
+        "verified_at": 0,
+        "chain": ChainConfig::new(ChainType::ethereum(), None),
+        "transaction_logs": [],
+    }))
+    .unwrap();
+    assert!(verification.proof.is_none());
+    assert!(
+        serde_json::to_value(&verification)
+            .unwrap()
+            .get("proof")
+            .is_none()
+    );
+    assert!(!ConfigBuilder::new().build().payments.include_proofs);
+}
@@ -0,0 +1,115 @@
+use x402_sdk::testing::MockVerifier;
+use x402_sdk::types::{
+    ChainConfig, ChainType, Currency, ErrorReason, Finality, PaymentMetadata, PaymentRequest,
+};
+use x402_sdk::verifier::PaymentVerifier;
+use x402_sdk::verifier::quorum::QuorumVerifier;
+
+const PAYER: &str = "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5";
+
+fn request() -> PaymentRequest {
+    PaymentRequest {
+        amount: "1000".to_string(),
+        currency: Currency::Native,
+        recipient: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+        chain: ChainConfig::new(ChainType::ethereum(), None),
+        description: None,
+        expires_at: None,
+        nonce: "nonce".to_string(),
+        beneficiary: None,
+        reference: None,
+        metadata: PaymentMetadata::new(),
+        quote: None,
+    }
+}
+
+/// one mock per provider, each reporting the given payment; mocks report
+/// the same transaction for the same amount
+fn providers(paid: &[Option<u128>]) -> Vec<MockVerifier> {
+    paid.iter()
+        .map(|paid| {
+            let verifier = MockVerifier::new();
+            verifier.set_paid_amount(*paid);
+            verifier
+        })
+        .collect()
+}
+
+fn quorum(providers: &[MockVerifier], quorum: usize) -> QuorumVerifier {
+    QuorumVerifier::new(
+        providers
+            .iter()
+            .map(|verifier| Box::new(verifier.clone()) as Box<dyn PaymentVerifier>)
+            .collect(),
+        quorum,
+    )
+}
+
+#[tokio::test]
+async fn payment_is_accepted_once_a_quorum_reports_it() {
+    let providers = providers(&[Some(1000), Some(1000), None]);
+    let verification = quorum(&providers, 2)
+        .verify_payment(&request(), PAYER)
+        .await
+        .unwrap();
+    assert!(verification.is_paid);
+    assert_eq!(verification.paid_amount, "1000");
+    // every provider was asked
+    assert!(providers.iter().all(|p| p.verified_requests().len() == 1));
+}
+
+#[tokio::test]
+async fn a_single_provider_cannot_grant_access() {
+    let providers = providers(&[Some(1000), None, None]);
+    let verification = quorum(&providers, 2)
+        .verify_payment(&request(), PAYER)
+        .await
+        .unwrap();
+    assert!(!verification.is_paid);
+    assert_eq!(verification.paid_amount, "0");
+    assert_eq!(verification.failure_reason, Some(ErrorReason::NoQuorum));
+}
+
+#[tokio::test]
+async fn providers_have_to_agree_on_the_transaction() {
+    let disagreeing = providers(&[Some(1000), Some(2000)]);
+    let verification = quorum(&disagreeing, 2)
+        .verify_payment(&request(), PAYER)
+        .await
+        .unwrap();
+    assert!(!verification.is_paid);
+    assert_eq!(verification.failure_reason, Some(ErrorReason::NoQuorum));
+
+    // without payments the reason of the providers is kept
+    let unpaid = providers(&[None, None]);
+    let verification = quorum(&unpaid, 2)
+        .verify_payment(&request(), PAYER)
+        .await
+        .unwrap();
+    assert_eq!(verification.failure_reason, Some(ErrorReason::NotFound));
+}
+
+#[tokio::test]
+async fn finality_needs_a_quorum_too() {
+    let providers = providers(&[Some(1000), Some(1000), Some(1000)]);
+    providers[2].set_finality(Finality::Reverted);
+    let chain = ChainConfig::new(ChainType::ethereum(), None);
+
+    let finality = quorum(&providers, 2)
+        .transaction_finality(&chain, "0xabc")
+        .await
+        .unwrap();
+    assert_eq!(finality, Finality::Finalized);
+    let finality = quorum(&providers, 3)
+        .transaction_finality(&chain, "0xabc")
+        .await
+        .unwrap();
+    assert_eq!(finality, Finality::Provisional);
+}
+
+#[test]
+fn quorum_is_bounded_by_the_providers() {
+    let providers = providers(&[None, None]);
+    assert_eq!(quorum(&providers, 5).quorum(), 2);
+    assert_eq!(quorum(&providers, 0).quorum(), 1);
+}
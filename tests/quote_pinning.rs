@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use x402_sdk::core::X402;
+use x402_sdk::pricing::RulePricing;
+use x402_sdk::rates::{RateError, RateProvider, request_usd_price};
+use x402_sdk::testing::MockClock;
+use x402_sdk::types::{ChainType, PaymentRequest, X402ProtocolResponse};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+/// price moving between requests
+struct MovingRate(Mutex<f64>);
+
+#[async_trait]
+impl RateProvider for MovingRate {
+    async fn usd_price(&self, _chain_type: &ChainType, _token: &str) -> Result<f64, RateError> {
+        Ok(*self.0.lock().unwrap())
+    }
+}
+
+fn engine(rates: Arc<MovingRate>, clock: Arc<MockClock>) -> X402 {
+    X402::from_default_config()
+        .unwrap()
+        .with_clock(clock)
+        .with_rate_provider(rates)
+        .with_pricing_provider(Arc::new(RulePricing::new().with_usd_price(
+            "/premium",
+            1_000_000,
+            &[USDC],
+        )))
+}
+
+async fn challenge(engine: &X402, nonce: Option<&str>) -> X402ProtocolResponse {
+    engine
+        .handle_access_request(PAYER, "/premium", nonce, None)
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+}
+
+#[tokio::test]
+async fn rates_are_pinned_at_issuance() {
+    let rates = Arc::new(MovingRate(Mutex::new(0.98)));
+    let engine = engine(rates.clone(), Arc::new(MockClock::new(1_000)));
+    let request = challenge(&engine, None).await.payment_required;
+
+    let quote = request.quote.clone().unwrap();
+    assert_eq!(quote.quoted_at, 1_000);
+    assert_eq!(request.quoted_rate(USDC), Some(0.98));
+
+    // verification values the payment at the quoted rate, not the new one
+    *rates.0.lock().unwrap() = 1.02;
+    let provider: Arc<dyn RateProvider> = rates;
+    assert_eq!(
+        request_usd_price(Some(&provider), &request, USDC)
+            .await
+            .unwrap(),
+        0.98
+    );
+    let unpinned = PaymentRequest {
+        quote: None,
+        ..request
+    };
+    assert_eq!(
+        request_usd_price(Some(&provider), &unpinned, USDC)
+            .await
+            .unwrap(),
+        1.02
+    );
+    assert!(request_usd_price(None, &unpinned, USDC).await.is_err());
+}
+
+#[tokio::test]
+async fn expired_quotes_are_requoted_with_fresh_rates() {
+    let rates = Arc::new(MovingRate(Mutex::new(0.98)));
+    let clock = Arc::new(MockClock::new(1_000));
+    let engine = engine(rates.clone(), clock.clone());
+    let first = challenge(&engine, None).await;
+    assert!(first.requote.is_none());
+    let nonce = first.payment_required.nonce;
+
+    *rates.0.lock().unwrap() = 1.02;
+    clock.set(1_000 + 3_600 + 61);
+    let reissued = challenge(&engine, Some(&nonce)).await;
+    assert_ne!(reissued.payment_required.nonce, nonce);
+    assert_eq!(reissued.payment_required.quoted_rate(USDC), Some(1.02));
+
+    let requote = reissued.requote.unwrap();
+    assert_eq!(requote.replaces, nonce);
+    assert_eq!(requote.reason, "expired");
+    assert_eq!(requote.previous_amount, "1000000");
+    let previous = requote.previous_quote.unwrap();
+    assert_eq!(previous.rates[&USDC.to_lowercase()], 0.98);
+}
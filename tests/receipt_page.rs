@@ -0,0 +1,97 @@
+use x402_sdk::i18n::{MessageCatalog, MessageKey};
+use x402_sdk::invoice::{Invoice, LineItem};
+use x402_sdk::receipt_page::ReceiptPage;
+
+fn page() -> ReceiptPage {
+    ReceiptPage {
+        receipt_id: "r-1".to_string(),
+        issued_at: 1_700_000_000,
+        merchant_name: "Example".to_string(),
+        merchant_description: "Premium reports".to_string(),
+        merchant_address: "0x1111111111111111111111111111111111111111".to_string(),
+        payer: "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5".to_string(),
+        resource: "GET /reports/1".to_string(),
+        chain: "Ethereum".to_string(),
+        chain_id: "1".to_string(),
+        amount: "1500000".to_string(),
+        currency: Some("USDC".to_string()),
+        decimals: Some(6),
+        transaction_hash: None,
+        explorer_url: None,
+        invoice: None,
+    }
+}
+
+#[test]
+fn amounts_are_shown_in_whole_units() {
+    let mut page = page();
+    assert_eq!(page.display_amount("1500000"), "1.5 USDC");
+    assert_eq!(page.display_amount("2000000"), "2 USDC");
+    assert_eq!(page.display_amount("5"), "0.000005 USDC");
+    // not an integer, shown as is
+    assert_eq!(page.display_amount("1.5"), "1.5 USDC");
+
+    page.decimals = None;
+    page.currency = None;
+    assert_eq!(page.display_amount("1500000"), "1500000");
+}
+
+#[test]
+fn html_lists_the_payment() {
+    let mut page = page();
+    page.transaction_hash = Some("0xabc".to_string());
+    page.explorer_url = Some("https://etherscan.io/tx/0xabc".to_string());
+    let html = page.to_html();
+
+    assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+    assert!(html.contains("<h1>Example</h1>"));
+    assert!(html.contains("<tr><th>Date</th><td>2023-11-14 22:13:20 UTC</td></tr>"));
+    assert!(html.contains("<tr><th>Chain</th><td>Ethereum (1)</td></tr>"));
+    assert!(html.contains("<tr><th>Amount</th><td>1.5 USDC</td></tr>"));
+    assert!(html.contains(
+        "<tr><th>Transaction</th><td><a href=\"https://etherscan.io/tx/0xabc\">0xabc</a></td></tr>"
+    ));
+    // no invoice section without an invoice
+    assert!(!html.contains("<h2>Invoice</h2>"));
+}
+
+#[test]
+fn payer_and_resource_are_escaped() {
+    let mut page = page();
+    page.resource = "GET /<script>alert(1)</script>".to_string();
+    page.payer = "\"payer\" & 'co'".to_string();
+    page.explorer_url = Some("https://example.com/\"><script>".to_string());
+    page.transaction_hash = Some("0xabc".to_string());
+    let html = page.to_html();
+
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("GET /&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(html.contains("&quot;payer&quot; &amp; &#39;co&#39;"));
+    assert!(html.contains("href=\"https://example.com/&quot;&gt;&lt;script&gt;\""));
+}
+
+#[test]
+fn invoices_and_labels_follow_the_catalog() {
+    let mut page = page();
+    page.invoice = Some(
+        Invoice::new("inv-1")
+            .with_line_item(LineItem::new("<b>Report</b>", 2, 1_000_000))
+            .with_discount(500_000),
+    );
+    let html = page.to_html();
+    assert!(html.contains("<tr><th>Invoice</th><td>inv-1</td></tr>"));
+    assert!(html.contains("<tr><td>2 x &lt;b&gt;Report&lt;/b&gt;</td><td>2 USDC</td></tr>"));
+    assert!(html.contains("<tr><td>Subtotal</td><td>2 USDC</td></tr>"));
+    assert!(html.contains("<tr><td>Discount</td><td>0.5 USDC</td></tr>"));
+    assert!(html.contains("<tr><td>Total</td><td>1.5 USDC</td></tr>"));
+
+    let catalog = MessageCatalog::new()
+        .with_message("de", MessageKey::Amount, "Betrag")
+        .with_message("de", MessageKey::Total, "Gesamt");
+    let html = page.to_html_in(&catalog, Some("de"));
+    assert!(html.contains("<html lang=\"de\">"));
+    assert!(html.contains("<tr><th>Betrag</th><td>1.5 USDC</td></tr>"));
+    assert!(html.contains("<tr><td>Gesamt</td><td>1.5 USDC</td></tr>"));
+    // labels missing in the locale fall back to English
+    assert!(html.contains("<tr><th>Chain</th><td>Ethereum (1)</td></tr>"));
+}
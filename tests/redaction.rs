@@ -0,0 +1,238 @@
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use x402_sdk::config::{ConfigBuilder, ConfigManager, RedactionConfig};
+use x402_sdk::context::RequestContext;
+use x402_sdk::core::X402;
+use x402_sdk::events::{EventListener, PaymentEvent, PaymentEventKind};
+use x402_sdk::invoice::{INVOICE_EXTENSION, Invoice, LineItem};
+use x402_sdk::pricing::{PriceQuote, PricingError, PricingProvider};
+use x402_sdk::redaction::{REDACTED, RedactionPolicy, Redactor};
+use x402_sdk::resource::Resource;
+use x402_sdk::store::{InMemoryOutboxStore, OutboxStore};
+use x402_sdk::tax::TAX_EXTENSION;
+use x402_sdk::types::{PaymentMetadata, RateQuote};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const INVOICE_ID: &str = "INV-2024-0042";
+const TOKEN: &str = "Bearer sk-live-secret";
+const CLIENT_IP: &str = "203.0.113.7";
+
+#[derive(Default)]
+struct RecordingListener {
+    events: Mutex<Vec<PaymentEvent>>,
+}
+
+impl EventListener for RecordingListener {
+    fn on_event(&self, event: &PaymentEvent) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+/// invoices every request, with a tax line in the metadata
+struct InvoicePricing;
+
+#[async_trait]
+impl PricingProvider for InvoicePricing {
+    async fn quote(
+        &self,
+        _user_address: &str,
+        _resource: &Resource,
+        _context: &RequestContext,
+    ) -> Result<Option<PriceQuote>, PricingError> {
+        let invoice = Invoice::new(INVOICE_ID).with_line_item(LineItem::new("API calls", 2, 500));
+        let metadata = PaymentMetadata::new()
+            .with_extension(TAX_EXTENSION, serde_json::json!({ "jurisdiction": "DE" }));
+        Ok(Some(
+            PriceQuote::from_invoice(invoice).with_metadata(metadata),
+        ))
+    }
+}
+
+fn redaction() -> RedactionConfig {
+    RedactionConfig {
+        payer_address: RedactionPolicy::Truncate {
+            prefix: 6,
+            suffix: 4,
+        },
+        nonce: RedactionPolicy::Hash,
+        amount: RedactionPolicy::Remove,
+    }
+}
+
+#[test]
+fn policies_redact_values() {
+    assert_eq!(RedactionPolicy::Keep.apply("abc"), "abc");
+    assert_eq!(RedactionPolicy::Remove.apply("abc"), REDACTED);
+    let hashed = RedactionPolicy::Hash.apply("abc");
+    assert!(hashed.starts_with("sha256:"));
+    assert_eq!(hashed.len(), "sha256:".len() + 16);
+    assert_eq!(hashed, RedactionPolicy::Hash.apply("abc"));
+    let truncate = RedactionPolicy::Truncate {
+        prefix: 6,
+        suffix: 4,
+    };
+    assert_eq!(truncate.apply(PAYER), "0x742E…b4a5");
+    assert_eq!(truncate.apply("short"), "short");
+}
+
+#[test]
+fn events_are_redacted_per_field() {
+    let redactor = Redactor::new(redaction());
+    let event = PaymentEvent {
+        timestamp: 1,
+        user_address: PAYER.to_string(),
+        resource: Resource::new("GET", "/premium").unwrap(),
+        context: RequestContext::default(),
+        kind: PaymentEventKind::BudgetDrawn {
+            account: "research-agent".to_string(),
+            amount: "1000".to_string(),
+            balance: "9000".to_string(),
+        },
+    };
+    let redacted = redactor.event(&event);
+    assert_eq!(redacted.user_address, "0x742E…b4a5");
+    match redacted.kind {
+        PaymentEventKind::BudgetDrawn {
+            account,
+            amount,
+            balance,
+        } => {
+            assert_eq!(account, "research-agent");
+            assert_eq!(amount, REDACTED);
+            assert_eq!(balance, REDACTED);
+        }
+        kind => panic!("unexpected event {:?}", kind),
+    }
+
+    let served = redactor.event(&PaymentEvent {
+        kind: PaymentEventKind::ContentServed {
+            nonce: "nonce-1".to_string(),
+        },
+        ..event
+    });
+    assert!(matches!(
+        served.kind,
+        PaymentEventKind::ContentServed { nonce } if nonce == redactor.nonce("nonce-1")
+    ));
+}
+
+#[test]
+fn redaction_is_off_by_default() {
+    assert!(!Redactor::default().is_enabled());
+    assert!(Redactor::new(redaction()).is_enabled());
+}
+
+#[tokio::test]
+async fn audit_targets_are_hashed_and_still_found() {
+    let config = ConfigBuilder::new().with_redaction(redaction()).build();
+    let engine = X402::new(ConfigManager::from_config(config)).unwrap();
+    let nonce = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce;
+    engine
+        .open_dispute(&nonce, "support@example.org", "charged twice")
+        .await
+        .unwrap();
+
+    let entries = engine.audit_log().entries_for(&nonce).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_ne!(entries[0].target, nonce);
+    assert_eq!(entries[0].target, engine.redactor().nonce(&nonce));
+}
+
+#[tokio::test]
+async fn events_are_redacted_before_they_reach_a_sink() {
+    let config = ConfigBuilder::new()
+        .with_redaction(redaction())
+        .with_delegated_payments(true)
+        .build();
+    let outbox = Arc::new(InMemoryOutboxStore::new());
+    let listener = Arc::new(RecordingListener::default());
+    let engine = X402::new(ConfigManager::from_config(config))
+        .unwrap()
+        .with_pricing_provider(Arc::new(InvoicePricing))
+        .with_outbox_store(outbox.clone())
+        .with_event_listener(listener.clone());
+    let context = RequestContext::new("GET")
+        .with_header("Authorization", TOKEN)
+        .with_client_ip(CLIENT_IP.parse::<IpAddr>().unwrap());
+    let challenge = engine
+        .handle_access_request_with_context(PAYER, "/premium", None, None, &context)
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required;
+    let reference = challenge.reference.clone().unwrap();
+    assert!(
+        challenge
+            .metadata
+            .extensions
+            .contains_key(INVOICE_EXTENSION)
+    );
+
+    let persisted = outbox.due(u64::MAX, 10).await.unwrap();
+    let listened = listener.events.lock().unwrap().clone();
+    assert_eq!(persisted.len(), 1);
+    assert_eq!(listened.len(), 1);
+    let events = persisted
+        .into_iter()
+        .map(|entry| entry.event)
+        .chain(listened);
+    for event in events {
+        let json = serde_json::to_string(&event).unwrap();
+        for secret in [
+            PAYER,
+            &challenge.nonce,
+            &reference,
+            INVOICE_ID,
+            TOKEN,
+            CLIENT_IP,
+        ] {
+            assert!(
+                !json.contains(secret),
+                "{} reached a sink: {}",
+                secret,
+                json
+            );
+        }
+        assert!(!event.context.headers.contains_key("authorization"));
+        assert_eq!(event.context.client_ip, None);
+        let PaymentEventKind::ChallengeIssued { payment_request } = event.kind else {
+            panic!("unexpected event {:?}", event.kind);
+        };
+        assert_eq!(payment_request.amount, REDACTED);
+        assert_eq!(payment_request.beneficiary.as_deref(), Some("0x742E…b4a5"));
+        assert!(
+            !payment_request
+                .metadata
+                .extensions
+                .contains_key(TAX_EXTENSION)
+        );
+    }
+
+    // rates of a quote are amounts too
+    let mut payment_request = challenge;
+    payment_request.quote = Some(RateQuote {
+        quoted_at: 1,
+        rates: BTreeMap::from([("0xtoken".to_string(), 1.0)]),
+    });
+    let quoted = engine.redactor().event(&PaymentEvent {
+        timestamp: 1,
+        user_address: PAYER.to_string(),
+        resource: Resource::new("GET", "/premium").unwrap(),
+        context: RequestContext::default(),
+        kind: PaymentEventKind::ChallengeIssued { payment_request },
+    });
+    assert!(matches!(
+        quoted.kind,
+        PaymentEventKind::ChallengeIssued { payment_request } if payment_request.quote.is_none()
+    ));
+}
@@ -0,0 +1,373 @@
+use async_trait::async_trait;
+use ethers::signers::LocalWallet;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Bytes, H160, H256, Transaction, TransactionReceipt, U256};
+use ethers::utils::{keccak256, rlp};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use x402_sdk::audit::{AuditAction, AuditLog, InMemoryAuditLog};
+use x402_sdk::relay::{
+    Eip3009Relayer, FeePolicy, HttpRelayer, RelayError, Relayer, TransactionSigner,
+    TransactionStatus, TransferAuthorization,
+};
+use x402_sdk::types::{ChainConfig, ChainType};
+
+const RELAY_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const OTHER_KEY: &str = "0x8da4ef21b864d2cc526dbdb2a120bd2874c36c9d0a1fb7f8c63d7f7a8b41de8f";
+const TOKEN: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+const GAS_PRICE: u64 = 30_000_000_000;
+
+fn authorization() -> TransferAuthorization {
+    TransferAuthorization {
+        token: TOKEN.to_string(),
+        from: "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5".to_string(),
+        to: "0x8ba1f109551bD432803012645Ac136ddd64DBA72".to_string(),
+        value: "5000000".to_string(),
+        valid_after: 0,
+        valid_before: 1_700_003_600,
+        nonce: format!("0x{}", "11".repeat(32)),
+        signature: format!("0x{}", "22".repeat(65)),
+    }
+}
+
+fn chain() -> ChainConfig {
+    ChainConfig::from_chain_type(ChainType::ethereum())
+}
+
+/// Node state behind the fake JSON-RPC endpoint.
+#[derive(Default)]
+struct Node {
+    pending_count: u64,
+    sent: Vec<Transaction>,
+    /// receipt status of mined transactions
+    mined: HashMap<H256, u64>,
+}
+
+impl Node {
+    fn answer(&mut self, method: &str, params: &Value) -> Value {
+        match method {
+            "eth_getTransactionCount" => json!(U256::from(self.pending_count)),
+            "eth_gasPrice" => json!(U256::from(GAS_PRICE)),
+            "eth_estimateGas" => json!(U256::from(60_000)),
+            "eth_sendRawTransaction" => {
+                let raw: Bytes = serde_json::from_value(params[0].clone()).unwrap();
+                let mut transaction = rlp::decode::<Transaction>(&raw).unwrap();
+                transaction.from = transaction.recover_from().unwrap();
+                let hash = H256::from(keccak256(&raw));
+                self.sent.push(transaction);
+                json!(hash)
+            }
+            "eth_getTransactionByHash" => {
+                let hash: H256 = serde_json::from_value(params[0].clone()).unwrap();
+                json!(
+                    self.sent
+                        .iter()
+                        .find(|transaction| transaction.hash == hash)
+                )
+            }
+            "eth_getTransactionReceipt" => {
+                let hash: H256 = serde_json::from_value(params[0].clone()).unwrap();
+                json!(self.mined.get(&hash).map(|status| TransactionReceipt {
+                    transaction_hash: hash,
+                    status: Some((*status).into()),
+                    ..TransactionReceipt::default()
+                }))
+            }
+            other => panic!("unexpected call: {}", other),
+        }
+    }
+}
+
+/// answers one request per connection with `answer(body)` as JSON
+async fn serve<F>(answer: F) -> String
+where
+    F: Fn(Value) -> (u16, Value) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let answer = Arc::new(answer);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let answer = answer.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                // the body is the JSON after the head, complete once it parses
+                let body = loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((_, body)) = text.split_once("\r\n\r\n")
+                        && let Ok(body) = serde_json::from_str::<Value>(body)
+                    {
+                        break body;
+                    }
+                };
+                let (status, body) = answer(body);
+                let body = serde_json::to_vec(&body).unwrap();
+                let head = format!(
+                    "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(&body).await.unwrap();
+            });
+        }
+    });
+    url
+}
+
+async fn node(node: Arc<Mutex<Node>>) -> String {
+    serve(move |request| {
+        let result = node
+            .lock()
+            .unwrap()
+            .answer(request["method"].as_str().unwrap(), &request["params"]);
+        (
+            200,
+            json!({"jsonrpc": "2.0", "id": request["id"], "result": result}),
+        )
+    })
+    .await
+}
+
+async fn relayer(node_state: &Arc<Mutex<Node>>) -> Eip3009Relayer {
+    let url = node(node_state.clone()).await;
+    Eip3009Relayer::new(RELAY_KEY)
+        .unwrap()
+        .with_rpc(ChainType::ethereum(), &url)
+}
+
+#[test]
+fn authorizations_encode_transfer_with_authorization() {
+    let calldata = authorization().calldata().unwrap();
+    assert_eq!(&calldata[..4], &[0xe3, 0xee, 0x16, 0x0e]);
+    assert_eq!(calldata.len(), 4 + 9 * 32);
+    // v is the last byte of the signature
+    assert_eq!(calldata[4 + 6 * 32 + 31], 0x22);
+
+    let mut short_nonce = authorization();
+    short_nonce.nonce = "0x11".to_string();
+    let mut bad_value = authorization();
+    bad_value.value = "5 USDC".to_string();
+    let mut bad_signature = authorization();
+    bad_signature.signature = format!("0x{}", "22".repeat(64));
+    let mut bad_address = authorization();
+    bad_address.to = "merchant".to_string();
+    for invalid in [short_nonce, bad_value, bad_signature, bad_address] {
+        assert!(matches!(
+            invalid.calldata(),
+            Err(RelayError::InvalidAuthorization(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn submissions_take_consecutive_nonces_from_the_relay_wallet() {
+    let state = Arc::new(Mutex::new(Node {
+        pending_count: 12,
+        ..Node::default()
+    }));
+    let relayer = relayer(&state).await;
+
+    let first = relayer.submit(&chain(), &authorization()).await.unwrap();
+    let second = relayer.submit(&chain(), &authorization()).await.unwrap();
+    assert_ne!(first, second);
+
+    let node = state.lock().unwrap();
+    let sent: Vec<_> = node.sent.iter().map(|tx| tx.nonce.as_u64()).collect();
+    assert_eq!(sent, [12, 13]);
+    let transaction = &node.sent[0];
+    assert_eq!(format!("{:?}", transaction.hash), first);
+    assert_eq!(format!("{:?}", transaction.from), relayer.address());
+    assert_eq!(transaction.to, Some(TOKEN.parse::<H160>().unwrap()));
+    assert_eq!(
+        transaction.input.to_vec(),
+        authorization().calldata().unwrap()
+    );
+    assert_eq!(transaction.gas_price, Some(GAS_PRICE.into()));
+}
+
+#[tokio::test]
+async fn replacements_reuse_the_nonce_with_bumped_fees() {
+    let state = Arc::new(Mutex::new(Node::default()));
+    let relayer = relayer(&state).await;
+
+    let first = relayer.submit(&chain(), &authorization()).await.unwrap();
+    let replacement = relayer
+        .replace(&chain(), &authorization(), &first, 20)
+        .await
+        .unwrap();
+    assert_ne!(first, replacement);
+
+    let node = state.lock().unwrap();
+    assert_eq!(node.sent[1].nonce, node.sent[0].nonce);
+    assert_eq!(node.sent[1].gas_price, Some((GAS_PRICE * 120 / 100).into()));
+}
+
+#[tokio::test]
+async fn the_fee_cap_bounds_the_gas_price() {
+    let state = Arc::new(Mutex::new(Node::default()));
+    let relayer = relayer(&state).await.with_fee_policy(
+        ChainType::ethereum(),
+        FeePolicy::legacy().with_max_fee(20_000_000_000),
+    );
+
+    let first = relayer.submit(&chain(), &authorization()).await.unwrap();
+    relayer
+        .replace(&chain(), &authorization(), &first, 20)
+        .await
+        .unwrap();
+    let node = state.lock().unwrap();
+    for transaction in &node.sent {
+        assert_eq!(transaction.gas_price, Some(20_000_000_000u64.into()));
+    }
+}
+
+#[tokio::test]
+async fn unpriced_gas_costs_are_refused_before_signing() {
+    let state = Arc::new(Mutex::new(Node::default()));
+    let audit_log = Arc::new(InMemoryAuditLog::new());
+    let relayer = relayer(&state)
+        .await
+        .with_fee_policy(
+            ChainType::ethereum(),
+            FeePolicy::legacy().with_max_cost_bps(500),
+        )
+        .with_audit_log(audit_log.clone());
+
+    assert!(matches!(
+        relayer.submit(&chain(), &authorization()).await,
+        Err(RelayError::FeeTooHigh(_))
+    ));
+    assert!(state.lock().unwrap().sent.is_empty());
+    let entries = audit_log.entries_for(&authorization().nonce).await.unwrap();
+    assert!(entries.is_empty());
+}
+
+#[tokio::test]
+async fn receipts_report_the_transaction_status() {
+    let state = Arc::new(Mutex::new(Node::default()));
+    let relayer = relayer(&state).await;
+    let hash = relayer.submit(&chain(), &authorization()).await.unwrap();
+    assert_eq!(
+        relayer.transaction_status(&chain(), &hash).await.unwrap(),
+        TransactionStatus::Pending
+    );
+
+    state.lock().unwrap().mined.insert(hash.parse().unwrap(), 1);
+    assert_eq!(
+        relayer.transaction_status(&chain(), &hash).await.unwrap(),
+        TransactionStatus::Confirmed
+    );
+    let reverted = H256::repeat_byte(0xee);
+    state.lock().unwrap().mined.insert(reverted, 0);
+    assert_eq!(
+        relayer
+            .transaction_status(&chain(), &format!("{:?}", reverted))
+            .await
+            .unwrap(),
+        TransactionStatus::Reverted
+    );
+    assert!(relayer.transaction_status(&chain(), "0xabc").await.is_err());
+}
+
+#[tokio::test]
+async fn chains_without_an_rpc_are_not_relayed() {
+    let relayer = Eip3009Relayer::new(RELAY_KEY).unwrap();
+    assert!(matches!(
+        relayer.submit(&chain(), &authorization()).await,
+        Err(RelayError::ChainNotSupported(_))
+    ));
+    assert!(matches!(
+        Eip3009Relayer::new("0xnot-a-key"),
+        Err(RelayError::InvalidAuthorization(_))
+    ));
+}
+
+/// Signer claiming the relay address but signing with another key.
+struct ImpostorSigner {
+    relay: LocalWallet,
+    other: LocalWallet,
+}
+
+#[async_trait]
+impl TransactionSigner for ImpostorSigner {
+    fn address(&self) -> H160 {
+        TransactionSigner::address(&self.relay)
+    }
+
+    async fn sign_transaction(&self, transaction: &TypedTransaction) -> Result<Bytes, RelayError> {
+        TransactionSigner::sign_transaction(&self.other, transaction).await
+    }
+}
+
+#[tokio::test]
+async fn signatures_of_another_address_are_rejected_and_audited() {
+    let state = Arc::new(Mutex::new(Node::default()));
+    let url = node(state.clone()).await;
+    let audit_log = Arc::new(InMemoryAuditLog::new());
+    let signer = ImpostorSigner {
+        relay: RELAY_KEY.trim_start_matches("0x").parse().unwrap(),
+        other: OTHER_KEY.trim_start_matches("0x").parse().unwrap(),
+    };
+    let relayer = Eip3009Relayer::from_signer(Arc::new(signer))
+        .with_rpc(ChainType::ethereum(), &url)
+        .with_audit_log(audit_log.clone());
+
+    assert!(matches!(
+        relayer.submit(&chain(), &authorization()).await,
+        Err(RelayError::Rejected(_))
+    ));
+    assert!(state.lock().unwrap().sent.is_empty());
+    let entries = audit_log.entries_for(&authorization().nonce).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert!(matches!(
+        &entries[0].action,
+        AuditAction::TransactionSigningFailed { reason } if reason.contains("another address")
+    ));
+    assert_eq!(entries[0].operator, relayer.address());
+}
+
+#[tokio::test]
+async fn a_relay_service_answers_with_the_transaction_hash() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let url = serve(move |request| {
+        recorded.lock().unwrap().push(request);
+        (200, json!({"transaction_hash": "0xrelayed"}))
+    })
+    .await;
+
+    let relayer = HttpRelayer::new(&url);
+    assert_eq!(
+        relayer.submit(&chain(), &authorization()).await.unwrap(),
+        "0xrelayed"
+    );
+    // the default status of a relay service is mined on answer
+    assert_eq!(
+        relayer
+            .transaction_status(&chain(), "0xrelayed")
+            .await
+            .unwrap(),
+        TransactionStatus::Confirmed
+    );
+    let request = requests.lock().unwrap().remove(0);
+    assert_eq!(request["chain_id"], chain().chain_id);
+    assert_eq!(
+        serde_json::from_value::<TransferAuthorization>(request["authorization"].clone()).unwrap(),
+        authorization()
+    );
+
+    let url = serve(|_| (503, json!({"error": "busy"}))).await;
+    assert!(matches!(
+        HttpRelayer::new(&url)
+            .submit(&chain(), &authorization())
+            .await,
+        Err(RelayError::Rejected(_))
+    ));
+}
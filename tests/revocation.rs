@@ -0,0 +1,150 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::crypto::Ed25519Suite;
+use x402_sdk::keys::KeyRing;
+use x402_sdk::receipt::{Receipt, ReceiptError};
+use x402_sdk::resource::Resource;
+use x402_sdk::revocation::{
+    InMemoryRevocationStore, RevocationEntry, RevocationReason, RevocationScope, RevocationStore,
+};
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const OTHER_PAYER: &str = "0x8ba1f109551bD432803012645Ac136ddd64DBA72";
+const NOW: u64 = 1_700_000_000;
+
+fn engine(clock: &MockClock) -> (X402, MockVerifier) {
+    let key_ring = KeyRing::new(0);
+    key_ring.add_key("2026-01", Ed25519Suite::new([1; 32]), 0, None);
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().with_receipt_ttl(3600).build());
+    let engine = engine
+        .with_clock(Arc::new(clock.clone()))
+        .with_key_ring(Arc::new(key_ring));
+    (engine, verifier)
+}
+
+async fn paid_receipt(engine: &X402, verifier: &MockVerifier, payer: &str, path: &str) -> Receipt {
+    let result = engine
+        .handle_access_request(payer, path, None, Some("1000"))
+        .await
+        .unwrap();
+    let nonce = result.x402_response.unwrap().payment_required.nonce;
+    verifier.set_paid_amount(Some(1000));
+    let receipt = engine
+        .handle_access_request(payer, path, Some(&nonce), None)
+        .await
+        .unwrap()
+        .receipt
+        .unwrap();
+    verifier.set_paid_amount(None);
+    receipt
+}
+
+fn revoked(result: Result<(), EngineError>) -> Option<RevocationReason> {
+    match result {
+        Err(EngineError::ReceiptError(ReceiptError::Revoked(reason))) => Some(reason),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn a_revoked_receipt_is_rejected() {
+    let clock = MockClock::new(NOW);
+    let (engine, verifier) = engine(&clock);
+    let receipt = paid_receipt(&engine, &verifier, PAYER, "/premium").await;
+    let other = paid_receipt(&engine, &verifier, PAYER, "/premium").await;
+
+    engine
+        .revoke_receipt(&receipt.receipt_id, RevocationReason::Fraudulent)
+        .await
+        .unwrap();
+    assert_eq!(
+        revoked(engine.validate_receipt(&receipt).await),
+        Some(RevocationReason::Fraudulent)
+    );
+    engine.validate_receipt(&other).await.unwrap();
+}
+
+#[tokio::test]
+async fn revoking_a_payment_rejects_its_receipts() {
+    let clock = MockClock::new(NOW);
+    let (engine, verifier) = engine(&clock);
+    let receipt = paid_receipt(&engine, &verifier, PAYER, "/premium").await;
+
+    engine
+        .revoke_payment(&receipt.nonce, RevocationReason::Reorged)
+        .await
+        .unwrap();
+    assert_eq!(
+        revoked(engine.validate_receipt(&receipt).await),
+        Some(RevocationReason::Reorged)
+    );
+}
+
+#[tokio::test]
+async fn bulk_revocations_cover_earlier_receipts_only() {
+    let clock = MockClock::new(NOW);
+    let (engine, verifier) = engine(&clock);
+    let before = paid_receipt(&engine, &verifier, PAYER, "/premium").await;
+    let other_payer = paid_receipt(&engine, &verifier, OTHER_PAYER, "/premium").await;
+
+    let sessions = engine
+        .revoke_entitlements(
+            "ops",
+            RevocationScope::Payer(PAYER.to_string()),
+            "chargeback",
+        )
+        .await
+        .unwrap();
+    assert_eq!(sessions, 1);
+    assert!(revoked(engine.validate_receipt(&before).await).is_some());
+    engine.validate_receipt(&other_payer).await.unwrap();
+
+    // access paid for after the revocation holds
+    clock.advance(1);
+    let after = paid_receipt(&engine, &verifier, PAYER, "/premium").await;
+    engine.validate_receipt(&after).await.unwrap();
+}
+
+#[tokio::test]
+async fn resource_revocations_cover_the_paths_below() {
+    let clock = MockClock::new(NOW);
+    let (engine, verifier) = engine(&clock);
+    let article = paid_receipt(&engine, &verifier, PAYER, "/premium/article").await;
+    let sibling = paid_receipt(&engine, &verifier, PAYER, "/premium-archive").await;
+
+    engine
+        .revoke_entitlements(
+            "ops",
+            RevocationScope::Resource("/premium".to_string()),
+            "takedown",
+        )
+        .await
+        .unwrap();
+    assert!(revoked(engine.validate_receipt(&article).await).is_some());
+    engine.validate_receipt(&sibling).await.unwrap();
+
+    let scope = RevocationScope::Resource("/premium".to_string());
+    assert!(scope.covers(OTHER_PAYER, &Resource::new("GET", "/premium/a/b").unwrap()));
+    assert!(!scope.covers(
+        OTHER_PAYER,
+        &Resource::new("GET", "/premium-archive").unwrap()
+    ));
+}
+
+#[tokio::test]
+async fn revocation_entries_expire_with_the_receipts() {
+    let store = InMemoryRevocationStore::new();
+    let entry = RevocationEntry {
+        reason: RevocationReason::Refunded,
+        revoked_at: NOW,
+        expires_at: NOW + 3600,
+    };
+    store.revoke("receipt-1", entry).await.unwrap();
+
+    assert!(store.get("receipt-1", NOW + 3599).await.unwrap().is_some());
+    assert!(store.get("receipt-1", NOW + 3600).await.unwrap().is_none());
+    assert_eq!(store.purge_expired(NOW + 3600).await.unwrap(), 1);
+    assert_eq!(store.purge_expired(NOW + 3600).await.unwrap(), 0);
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "cli")]
+
+use x402_sdk::scaffold::{ScaffoldError, Stack, render, scaffold};
+
+#[test]
+fn every_stack_renders_a_complete_project() {
+    for stack in [Stack::Axum, Stack::Actix, Stack::Worker] {
+        let files = render(stack, "paid-api").unwrap();
+        for path in ["Cargo.toml", "src/lib.rs", "src/main.rs", "tests/flow.rs"] {
+            assert!(
+                files.iter().any(|file| file.path == path),
+                "{} has no {}",
+                stack.name(),
+                path
+            );
+        }
+        for file in &files {
+            assert!(!file.contents.contains("{{"), "{} not rendered", file.path);
+        }
+        let manifest = &files
+            .iter()
+            .find(|f| f.path == "Cargo.toml")
+            .unwrap()
+            .contents;
+        assert!(manifest.contains("name = \"paid-api\""));
+        assert!(manifest.contains(env!("CARGO_PKG_VERSION")));
+        let main = &files
+            .iter()
+            .find(|f| f.path == "src/main.rs")
+            .unwrap()
+            .contents;
+        assert!(main.contains("paid_api::engine"));
+    }
+}
+
+#[test]
+fn stacks_are_parsed_by_name() {
+    assert_eq!("axum".parse::<Stack>().unwrap(), Stack::Axum);
+    assert_eq!("actix-web".parse::<Stack>().unwrap(), Stack::Actix);
+    assert!(matches!(
+        "rocket".parse::<Stack>(),
+        Err(ScaffoldError::UnknownStack(_))
+    ));
+    assert!(matches!(
+        render(Stack::Axum, "../escape"),
+        Err(ScaffoldError::InvalidName(_))
+    ));
+}
+
+#[test]
+fn projects_are_written_into_empty_directories_only() {
+    let dir = std::env::temp_dir().join(format!("x402-scaffold-{}", uuid::Uuid::new_v4()));
+    let written = scaffold(Stack::Worker, "authz-worker", &dir).unwrap();
+    assert!(written.iter().all(|path| path.exists()));
+    assert!(dir.join("tests/flow.rs").exists());
+
+    assert!(matches!(
+        scaffold(Stack::Worker, "authz-worker", &dir),
+        Err(ScaffoldError::AlreadyExists(_))
+    ));
+    std::fs::remove_dir_all(dir).unwrap();
+}
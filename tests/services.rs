@@ -0,0 +1,116 @@
+use std::sync::Arc;
+use x402_sdk::config::{ConfigBuilder, ConfigManager};
+use x402_sdk::context::RequestContext;
+use x402_sdk::crypto::HmacSha256Suite;
+use x402_sdk::keys::KeyRing;
+use x402_sdk::resource::Resource;
+use x402_sdk::revocation::{InMemoryRevocationStore, RevocationReason};
+use x402_sdk::services::entitlement::Entitlements;
+use x402_sdk::services::verification::Verifications;
+use x402_sdk::services::{ChallengeService, EntitlementService, VerificationService};
+use x402_sdk::testing::{MockVerifier, mock_engine};
+use x402_sdk::types::ChainType;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+async fn challenge(challenges: &dyn ChallengeService, resource: &Resource) -> String {
+    let result = challenges
+        .issue_challenge(
+            PAYER,
+            resource,
+            Some("1000"),
+            None,
+            &RequestContext::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 402);
+    result.x402_response.unwrap().payment_required.nonce
+}
+
+#[tokio::test]
+async fn services_split_the_payment_flow() {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = Arc::new(engine);
+    let challenges: Arc<dyn ChallengeService> = engine.clone();
+    let verification: Arc<dyn VerificationService> = engine.clone();
+    let entitlements: Arc<dyn EntitlementService> = engine;
+
+    let resource = Resource::new("GET", "/premium").unwrap();
+    let nonce = challenge(challenges.as_ref(), &resource).await;
+
+    verifier.set_paid_amount(Some(1000));
+    let paid = verification
+        .verify_payment(PAYER, &nonce, &resource)
+        .await
+        .unwrap();
+    assert!(paid.is_paid);
+
+    entitlements
+        .revoke_payment(&nonce, RevocationReason::Refunded)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_worker_verifies_the_sessions_an_edge_issued() {
+    // the edge never sees the payment, only the worker's verifier does
+    let (edge, _edge_verifier) = mock_engine(ConfigBuilder::new().build());
+    let verifier = MockVerifier::new();
+    let mut worker = Verifications::new(
+        Arc::new(ConfigManager::from_config(ConfigBuilder::new().build())),
+        edge.sessions().clone(),
+    );
+    worker
+        .verifier_registry_mut()
+        .register_verifier(ChainType::ethereum(), Box::new(verifier.clone()));
+
+    let resource = Resource::new("GET", "/premium").unwrap();
+    let nonce = challenge(edge.challenges(), &resource).await;
+    verifier.set_paid_amount(Some(1000));
+    let worker: Arc<dyn VerificationService> = Arc::new(worker);
+    let paid = worker
+        .verify_payment(PAYER, &nonce, &resource)
+        .await
+        .unwrap();
+    assert!(paid.is_paid);
+    assert!(edge.export_sessions()[0].verified);
+    assert_eq!(verifier.verified_requests()[0].nonce, nonce);
+}
+
+#[tokio::test]
+async fn entitlements_checked_elsewhere_see_the_revocations() {
+    let key_ring = KeyRing::new(0);
+    key_ring.add_key("hmac", HmacSha256Suite::new("hmac", b"secret"), 0, None);
+    let key_ring = Arc::new(key_ring);
+    let revocations = Arc::new(InMemoryRevocationStore::new());
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine
+        .with_key_ring(key_ring.clone())
+        .with_revocation_store(revocations.clone());
+    let checker = Entitlements::new(
+        Arc::new(ConfigManager::from_config(ConfigBuilder::new().build())),
+        Default::default(),
+    )
+    .with_key_ring(key_ring)
+    .with_revocation_store(revocations);
+
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap();
+    let nonce = result.x402_response.unwrap().payment_required.nonce;
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    let receipt = result.receipt.unwrap();
+    assert!(checker.validate_receipt(&receipt).await.is_ok());
+
+    engine
+        .revoke_payment(&nonce, RevocationReason::Refunded)
+        .await
+        .unwrap();
+    assert!(checker.validate_receipt(&receipt).await.is_err());
+}
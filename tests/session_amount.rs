@@ -0,0 +1,98 @@
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::testing::{MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn engine() -> (X402, MockVerifier) {
+    mock_engine(ConfigBuilder::new().build())
+}
+
+async fn issue(engine: &X402, custom_amount: Option<&str>) -> (String, String) {
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, custom_amount)
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 402);
+    let request = result.x402_response.unwrap().payment_required;
+    (request.nonce, request.amount)
+}
+
+#[tokio::test]
+async fn verification_uses_amount_quoted_at_issuance() {
+    let (engine, verifier) = engine();
+    let (nonce, amount) = issue(&engine, Some("5000")).await;
+    assert_eq!(amount, "5000");
+
+    verifier.set_paid_amount(Some(5000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), Some("1"))
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+    assert_eq!(verifier.verified_requests()[0].amount, "5000");
+}
+
+#[tokio::test]
+async fn lower_custom_amount_on_follow_up_does_not_unlock_underpayment() {
+    let (engine, verifier) = engine();
+    let (nonce, _) = issue(&engine, Some("5000")).await;
+
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), Some("1000"))
+        .await
+        .unwrap();
+    assert!(!result.should_serve_content);
+    assert_eq!(verifier.verified_requests()[0].amount, "5000");
+}
+
+#[tokio::test]
+async fn retry_challenge_keeps_session_amount() {
+    let (engine, _verifier) = engine();
+    let (nonce, _) = issue(&engine, Some("5000")).await;
+
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), Some("1"))
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 402);
+    let retry = result.x402_response.unwrap().payment_required;
+    assert_eq!(retry.amount, "5000");
+}
+
+#[tokio::test]
+async fn retry_without_custom_amount_keeps_custom_session_amount() {
+    let (engine, verifier) = engine();
+    let (nonce, _) = issue(&engine, Some("5000")).await;
+
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    let retry = result.x402_response.unwrap().payment_required;
+    assert_eq!(retry.amount, "5000");
+
+    verifier.set_paid_amount(Some(5000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&retry.nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+    assert!(
+        verifier
+            .verified_requests()
+            .iter()
+            .all(|request| request.amount == "5000")
+    );
+}
+
+#[tokio::test]
+async fn unknown_nonce_falls_back_to_request_amount() {
+    let (engine, _verifier) = engine();
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some("unknown"), Some("42"))
+        .await
+        .unwrap();
+    assert_eq!(result.x402_response.unwrap().payment_required.amount, "42");
+}
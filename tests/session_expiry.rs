@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::{EngineError, X402};
+use x402_sdk::resource::Resource;
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn engine(clock: Arc<MockClock>) -> (X402, MockVerifier) {
+    let config = ConfigBuilder::new()
+        .with_expiration_time(600)
+        .with_expiry_grace(30)
+        .build();
+    let (engine, verifier) = mock_engine(config);
+    verifier.set_paid_amount(Some(5000));
+    (engine.with_clock(clock), verifier)
+}
+
+async fn issue(engine: &X402) -> (String, Option<u64>) {
+    let request = engine
+        .handle_access_request(PAYER, "/premium", None, Some("5000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required;
+    (request.nonce, request.expires_at)
+}
+
+#[tokio::test]
+async fn payments_landing_within_the_grace_period_verify() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, _verifier) = engine(clock.clone());
+    let (nonce, expires_at) = issue(&engine).await;
+    assert_eq!(expires_at, Some(1_600));
+
+    clock.set(1_630);
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+}
+
+#[tokio::test]
+async fn expired_challenges_are_refused() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, verifier) = engine(clock.clone());
+    let (nonce, _) = issue(&engine).await;
+
+    clock.set(1_631);
+    let err = engine
+        .verify_payment(PAYER, &nonce, &Resource::new("GET", "/premium").unwrap())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        EngineError::SessionExpired { expired_at: 1_600 }
+    ));
+    assert!(verifier.verified_requests().is_empty());
+}
+
+#[tokio::test]
+async fn expired_challenges_are_reissued_at_the_current_price() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, _verifier) = engine(clock.clone());
+    let (nonce, _) = issue(&engine).await;
+
+    clock.set(2_000);
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&nonce), Some("7000"))
+        .await
+        .unwrap();
+    assert!(!result.should_serve_content);
+    assert_eq!(result.http_status, 402);
+    let reissued = result.x402_response.unwrap().payment_required;
+    assert_ne!(reissued.nonce, nonce);
+    assert_eq!(reissued.amount, "7000");
+    assert_eq!(reissued.expires_at, Some(2_600));
+}
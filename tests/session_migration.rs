@@ -0,0 +1,76 @@
+use x402_sdk::core::{SessionImportReport, X402};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+async fn issue_challenge(engine: &X402, path: &str) -> String {
+    engine
+        .handle_access_request(PAYER, path, None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce
+}
+
+#[tokio::test]
+async fn sessions_round_trip_through_json_lines() {
+    let source = X402::from_default_config().unwrap();
+    let first = issue_challenge(&source, "/premium").await;
+    let second = issue_challenge(&source, "/reports").await;
+
+    let mut exported = Vec::new();
+    assert_eq!(source.export_sessions_to(&mut exported).unwrap(), 2);
+    assert_eq!(exported.iter().filter(|byte| **byte == b'\n').count(), 2);
+
+    let target = X402::from_default_config().unwrap();
+    let report = target.import_sessions_from(exported.as_slice()).unwrap();
+    assert_eq!(
+        report,
+        SessionImportReport {
+            imported: 2,
+            skipped: 0
+        }
+    );
+    let mut nonces: Vec<String> = target
+        .export_sessions()
+        .into_iter()
+        .map(|session| session.payment_request.nonce)
+        .collect();
+    nonces.sort();
+    let mut expected = vec![first.clone(), second];
+    expected.sort();
+    assert_eq!(nonces, expected);
+    assert!(target.session_attempts(&first).is_some());
+}
+
+#[tokio::test]
+async fn migration_keeps_sessions_already_in_the_target() {
+    let source = X402::from_default_config().unwrap();
+    issue_challenge(&source, "/premium").await;
+    let target = X402::from_default_config().unwrap().with_session_shards(2);
+
+    assert_eq!(source.migrate_sessions_to(&target).imported, 1);
+    assert_eq!(
+        source.migrate_sessions_to(&target),
+        SessionImportReport {
+            imported: 0,
+            skipped: 1
+        }
+    );
+    assert_eq!(target.export_sessions().len(), 1);
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    let engine = X402::from_default_config().unwrap();
+    assert!(
+        engine
+            .import_sessions_from("not json\n".as_bytes())
+            .is_err()
+    );
+    assert_eq!(
+        engine.import_sessions_from("\n".as_bytes()).unwrap(),
+        SessionImportReport::default()
+    );
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+use x402_sdk::types::PaymentRequest;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const OTHER_PAYER: &str = "0x8ba1f109551bD432803012645Ac136ddd64DBA72";
+
+fn engine(clock: Arc<MockClock>) -> (X402, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().with_expiration_time(600).build());
+    (engine.with_clock(clock), verifier)
+}
+
+async fn request(
+    engine: &X402,
+    payer: &str,
+    path: &str,
+    nonce: Option<&str>,
+    custom_amount: Option<&str>,
+) -> PaymentRequest {
+    let result = engine
+        .handle_access_request(payer, path, nonce, custom_amount)
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 402);
+    result.x402_response.unwrap().payment_required
+}
+
+#[tokio::test]
+async fn retries_of_unpaid_sessions_get_the_same_challenge() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, _verifier) = engine(clock.clone());
+    let issued = request(&engine, PAYER, "/premium", None, Some("5000")).await;
+
+    clock.set(1_300);
+    let retry = request(
+        &engine,
+        PAYER,
+        "/premium",
+        Some(&issued.nonce),
+        Some("7000"),
+    )
+    .await;
+    assert_eq!(retry.nonce, issued.nonce);
+    assert_eq!(retry.amount, "5000");
+    assert_eq!(retry.expires_at, Some(1_600));
+    assert_eq!(engine.export_sessions().len(), 1);
+}
+
+#[tokio::test]
+async fn payments_made_during_retries_unlock_the_session() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, verifier) = engine(clock);
+    let issued = request(&engine, PAYER, "/premium", None, Some("5000")).await;
+    let retry = request(&engine, PAYER, "/premium", Some(&issued.nonce), None).await;
+
+    verifier.set_paid_amount(Some(5000));
+    let result = engine
+        .handle_access_request(PAYER, "/premium", Some(&issued.nonce), None)
+        .await
+        .unwrap();
+    assert_eq!(retry.nonce, issued.nonce);
+    assert!(result.should_serve_content);
+}
+
+#[tokio::test]
+async fn sessions_of_other_payers_or_resources_are_not_returned() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, _verifier) = engine(clock);
+    let issued = request(&engine, PAYER, "/premium", None, Some("5000")).await;
+
+    let other_payer = request(
+        &engine,
+        OTHER_PAYER,
+        "/premium",
+        Some(&issued.nonce),
+        Some("5000"),
+    )
+    .await;
+    assert_ne!(other_payer.nonce, issued.nonce);
+    let other_resource = request(&engine, PAYER, "/other", Some(&issued.nonce), Some("5000")).await;
+    assert_ne!(other_resource.nonce, issued.nonce);
+}
@@ -0,0 +1,258 @@
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use x402_sdk::config::{ConfigBuilder, CurrencyConfig, CurrencyType};
+use x402_sdk::core::X402;
+use x402_sdk::relay::{RelayError, Relayer, TransactionStatus, TransferAuthorization};
+use x402_sdk::store::{
+    FileSettlementStore, InMemorySettlementStore, SettlementStatus, SettlementStore,
+};
+use x402_sdk::testing::{MockClock, mock_engine};
+use x402_sdk::types::ChainConfig;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const TOKEN: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+const NOW: u64 = 1_700_000_000;
+
+/// Relayer answering submissions in the scripted order, transactions stay
+/// in the given status.
+#[derive(Clone, Default)]
+struct ScriptedRelayer {
+    submissions: Arc<Mutex<VecDeque<Result<String, String>>>>,
+    status: Arc<Mutex<Option<TransactionStatus>>>,
+    replaced: Arc<Mutex<Vec<String>>>,
+}
+
+impl ScriptedRelayer {
+    fn answer(&self, submission: Result<&str, &str>) {
+        self.submissions
+            .lock()
+            .unwrap()
+            .push_back(submission.map(str::to_string).map_err(str::to_string));
+    }
+
+    fn set_status(&self, status: TransactionStatus) {
+        *self.status.lock().unwrap() = Some(status);
+    }
+
+    fn next(&self) -> Result<String, RelayError> {
+        self.submissions
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Err("nothing scripted".to_string()))
+            .map_err(RelayError::NetworkError)
+    }
+}
+
+#[async_trait]
+impl Relayer for ScriptedRelayer {
+    async fn submit(
+        &self,
+        _chain: &ChainConfig,
+        _authorization: &TransferAuthorization,
+    ) -> Result<String, RelayError> {
+        self.next()
+    }
+
+    async fn replace(
+        &self,
+        _chain: &ChainConfig,
+        _authorization: &TransferAuthorization,
+        previous: &str,
+        _gas_bump_percent: u32,
+    ) -> Result<String, RelayError> {
+        self.replaced.lock().unwrap().push(previous.to_string());
+        self.next()
+    }
+
+    async fn transaction_status(
+        &self,
+        _chain: &ChainConfig,
+        _transaction_hash: &str,
+    ) -> Result<TransactionStatus, RelayError> {
+        Ok(self
+            .status
+            .lock()
+            .unwrap()
+            .unwrap_or(TransactionStatus::Pending))
+    }
+}
+
+fn engine(relayer: &ScriptedRelayer, store: Arc<dyn SettlementStore>, clock: &MockClock) -> X402 {
+    let mut config = ConfigBuilder::new()
+        .with_settlement_retries(3, 10, 25)
+        .build();
+    config.service.default_currency = CurrencyConfig {
+        currency_type: CurrencyType::Erc20,
+        address: Some(TOKEN.to_string()),
+        decimals: 6,
+    };
+    let (engine, _verifier) = mock_engine(config);
+    engine
+        .with_clock(Arc::new(clock.clone()))
+        .with_relayer(Arc::new(relayer.clone()))
+        .with_settlement_store(store)
+}
+
+/// challenge the payer and sign an authorization paying it in full
+async fn authorization(engine: &X402) -> (String, TransferAuthorization) {
+    let result = engine
+        .handle_access_request(PAYER, "/premium", None, Some("5"))
+        .await
+        .unwrap();
+    let request = result.x402_response.unwrap().payment_required;
+    let authorization = TransferAuthorization {
+        token: TOKEN.to_string(),
+        from: PAYER.to_string(),
+        to: request.recipient.clone(),
+        value: "5000000".to_string(),
+        valid_after: 0,
+        valid_before: NOW + 3600,
+        nonce: format!("0x{}", "11".repeat(32)),
+        signature: format!("0x{}", "22".repeat(65)),
+    };
+    (request.nonce, authorization)
+}
+
+fn temp_path() -> PathBuf {
+    std::env::temp_dir().join(format!("x402-settlements-{}.json", uuid::Uuid::new_v4()))
+}
+
+#[tokio::test]
+async fn a_session_is_queued_once() {
+    let clock = MockClock::new(NOW);
+    let relayer = ScriptedRelayer::default();
+    let engine = engine(&relayer, Arc::new(InMemorySettlementStore::new()), &clock);
+    let (nonce, authorization) = authorization(&engine).await;
+
+    let job = engine
+        .queue_settlement(&nonce, &authorization)
+        .await
+        .unwrap();
+    assert_eq!(job.status, SettlementStatus::Pending);
+    assert_eq!(job.next_attempt_at, NOW);
+
+    let mut other = authorization.clone();
+    other.nonce = format!("0x{}", "33".repeat(32));
+    let again = engine.queue_settlement(&nonce, &other).await.unwrap();
+    assert_eq!(again.authorization, authorization);
+}
+
+#[tokio::test]
+async fn authorizations_not_paying_the_session_are_refused() {
+    let clock = MockClock::new(NOW);
+    let relayer = ScriptedRelayer::default();
+    let engine = engine(&relayer, Arc::new(InMemorySettlementStore::new()), &clock);
+    let (nonce, authorization) = authorization(&engine).await;
+
+    let mut short = authorization.clone();
+    short.value = "4999999".to_string();
+    assert!(engine.queue_settlement(&nonce, &short).await.is_err());
+    let mut expired = authorization;
+    expired.valid_before = NOW;
+    assert!(engine.queue_settlement(&nonce, &expired).await.is_err());
+    assert!(engine.process_settlements().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn failed_submissions_back_off_until_the_last_attempt() {
+    let clock = MockClock::new(NOW);
+    let relayer = ScriptedRelayer::default();
+    let engine = engine(&relayer, Arc::new(InMemorySettlementStore::new()), &clock);
+    let (nonce, authorization) = authorization(&engine).await;
+    engine
+        .queue_settlement(&nonce, &authorization)
+        .await
+        .unwrap();
+
+    relayer.answer(Err("connection reset"));
+    let job = engine.process_settlements().await.unwrap().remove(0);
+    assert_eq!(job.status, SettlementStatus::Pending);
+    assert_eq!(job.attempts, 1);
+    assert_eq!(job.next_attempt_at, NOW + 10);
+    assert!(job.last_error.unwrap().contains("connection reset"));
+
+    // not due before the backoff elapsed
+    clock.advance(9);
+    assert!(engine.process_settlements().await.unwrap().is_empty());
+
+    clock.advance(1);
+    relayer.answer(Err("connection reset"));
+    let job = engine.process_settlements().await.unwrap().remove(0);
+    assert_eq!(job.attempts, 2);
+    // doubled after every further failure
+    assert_eq!(job.next_attempt_at, NOW + 10 + 20);
+
+    clock.advance(20);
+    relayer.answer(Err("connection reset"));
+    let job = engine.process_settlements().await.unwrap().remove(0);
+    assert_eq!(job.status, SettlementStatus::Failed);
+    assert_eq!(job.attempts, 3);
+
+    clock.advance(3600);
+    assert!(engine.process_settlements().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn stuck_transactions_are_replaced_then_confirmed() {
+    let clock = MockClock::new(NOW);
+    let relayer = ScriptedRelayer::default();
+    let engine = engine(&relayer, Arc::new(InMemorySettlementStore::new()), &clock);
+    let (nonce, authorization) = authorization(&engine).await;
+    engine
+        .queue_settlement(&nonce, &authorization)
+        .await
+        .unwrap();
+
+    relayer.answer(Ok("0xfirst"));
+    let job = engine.process_settlements().await.unwrap().remove(0);
+    assert_eq!(job.status, SettlementStatus::Submitted);
+    assert_eq!(job.transaction_hashes, ["0xfirst"]);
+
+    // unmined past the replacement delay, 120 seconds by default
+    clock.advance(120);
+    relayer.answer(Ok("0xsecond"));
+    let job = engine.process_settlements().await.unwrap().remove(0);
+    assert_eq!(job.transaction_hashes, ["0xfirst", "0xsecond"]);
+    assert_eq!(*relayer.replaced.lock().unwrap(), ["0xfirst"]);
+
+    clock.advance(120);
+    relayer.set_status(TransactionStatus::Confirmed);
+    let job = engine.process_settlements().await.unwrap().remove(0);
+    assert_eq!(job.status, SettlementStatus::Confirmed);
+    assert_eq!(job.last_error, None);
+}
+
+#[tokio::test]
+async fn queued_settlements_survive_a_restart() {
+    let path = temp_path();
+    let clock = MockClock::new(NOW);
+    let relayer = ScriptedRelayer::default();
+    {
+        let store = Arc::new(FileSettlementStore::open(&path).unwrap());
+        let engine = engine(&relayer, store, &clock);
+        let (nonce, authorization) = authorization(&engine).await;
+        engine
+            .queue_settlement(&nonce, &authorization)
+            .await
+            .unwrap();
+        relayer.answer(Err("connection reset"));
+        engine.process_settlements().await.unwrap();
+    }
+
+    // a new process picks the job up where the last one left it
+    clock.advance(10);
+    let store = Arc::new(FileSettlementStore::open(&path).unwrap());
+    let engine = engine(&relayer, store, &clock);
+    relayer.answer(Ok("0xafter-restart"));
+    let job = engine.process_settlements().await.unwrap().remove(0);
+    assert_eq!(job.status, SettlementStatus::Submitted);
+    assert_eq!(job.attempts, 2);
+
+    let reopened = FileSettlementStore::open(&path).unwrap();
+    let stored = reopened.job(&job.key).await.unwrap().unwrap();
+    assert_eq!(stored.transaction_hashes, ["0xafter-restart"]);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,53 @@
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::signing::{ChallengeSigner, SignatureError, verify_challenge_signature};
+use x402_sdk::testing::mock_engine;
+use x402_sdk::types::X402ProtocolResponse;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const SIGNER_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const OTHER_KEY: &str = "0x646f1ce2fdad0e6deeeb5c7e8e5543bdde65e86029e2fd9fc169899c440a7913";
+
+async fn challenge(engine: &X402) -> X402ProtocolResponse {
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("1000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+}
+
+#[tokio::test]
+async fn challenges_verify_against_the_server_address() {
+    let signer = ChallengeSigner::from_private_key(SIGNER_KEY).unwrap();
+    let address = signer.address();
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine.with_challenge_signer(signer);
+
+    let response = challenge(&engine).await;
+    verify_challenge_signature(&response, &address).unwrap();
+
+    let other = ChallengeSigner::from_private_key(OTHER_KEY).unwrap();
+    assert!(matches!(
+        verify_challenge_signature(&response, &other.address()),
+        Err(SignatureError::SignerMismatch { .. })
+    ));
+    // a client must not accept a challenge altered in transit
+    let mut tampered = response.clone();
+    tampered.payment_required.recipient = other.address();
+    assert!(verify_challenge_signature(&tampered, &address).is_err());
+}
+
+#[tokio::test]
+async fn unsigned_challenges_are_refused() {
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    let response = challenge(&engine).await;
+    assert!(response.signature.is_none());
+    let address = ChallengeSigner::from_private_key(SIGNER_KEY)
+        .unwrap()
+        .address();
+    assert!(matches!(
+        verify_challenge_signature(&response, &address),
+        Err(SignatureError::MissingSignature)
+    ));
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+use x402_sdk::config::{ConfigBuilder, ConfigManager};
+use x402_sdk::core::X402;
+use x402_sdk::simulation::{SimulationRule, simulated_transaction_hash};
+use x402_sdk::testing::MockClock;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+async fn engine(rule: SimulationRule, clock: Arc<MockClock>) -> X402 {
+    let config = ConfigBuilder::new().with_simulation(rule).build();
+    let mut engine = X402::new(ConfigManager::from_config(config))
+        .unwrap()
+        .with_clock(clock);
+    let report = engine.register_all_configured().await;
+    assert!(report.failed.is_empty());
+    assert!(!report.registered.is_empty());
+    engine
+}
+
+async fn issue(engine: &X402) -> String {
+    engine
+        .handle_access_request(PAYER, "/premium", None, Some("5000"))
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required
+        .nonce
+}
+
+async fn served(engine: &X402, nonce: &str) -> bool {
+    engine
+        .handle_access_request(PAYER, "/premium", Some(nonce), None)
+        .await
+        .unwrap()
+        .should_serve_content
+}
+
+#[tokio::test]
+async fn simulated_payments_complete_the_flow() {
+    let engine = engine(SimulationRule::AlwaysPay, Arc::new(MockClock::new(1_000))).await;
+    let nonce = issue(&engine).await;
+    assert!(served(&engine, &nonce).await);
+}
+
+#[tokio::test]
+async fn payments_land_after_the_configured_delay() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let engine = engine(SimulationRule::PayAfter { secs: 30 }, clock.clone()).await;
+    let nonce = issue(&engine).await;
+    assert!(!served(&engine, &nonce).await);
+
+    clock.set(1_029);
+    assert!(!served(&engine, &nonce).await);
+    clock.set(1_030);
+    assert!(served(&engine, &nonce).await);
+}
+
+#[tokio::test]
+async fn never_pay_keeps_asking_for_payment() {
+    let engine = engine(SimulationRule::NeverPay, Arc::new(MockClock::new(1_000))).await;
+    let nonce = issue(&engine).await;
+    assert!(!served(&engine, &nonce).await);
+}
+
+#[tokio::test]
+async fn a_share_of_sessions_is_paid_consistently() {
+    let engine = engine(
+        SimulationRule::PayPercent { percent: 50 },
+        Arc::new(MockClock::new(1_000)),
+    )
+    .await;
+    let mut paid = 0;
+    for _ in 0..40 {
+        let nonce = issue(&engine).await;
+        let outcome = served(&engine, &nonce).await;
+        assert_eq!(served(&engine, &nonce).await, outcome);
+        paid += usize::from(outcome);
+    }
+    assert!(paid > 0 && paid < 40);
+}
+
+#[test]
+fn simulated_transactions_are_stable_per_session() {
+    let hash = simulated_transaction_hash("nonce");
+    assert_eq!(hash, simulated_transaction_hash("nonce"));
+    assert_eq!(hash.len(), 66);
+    assert_ne!(hash, simulated_transaction_hash("other"));
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "sled")]
+
+use std::sync::Arc;
+use x402_sdk::encryption::{EnvelopeCipher, LocalKey};
+use x402_sdk::revocation::{RevocationEntry, RevocationReason, RevocationStore};
+use x402_sdk::store::{NonceStore, PaymentRecord, PaymentStore, SledStore, UsageStore};
+use x402_sdk::types::Finality;
+
+fn store() -> SledStore {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    SledStore::from_db(db, None).unwrap()
+}
+
+fn record(recorded_at: u64) -> PaymentRecord {
+    PaymentRecord {
+        recorded_at,
+        nonce: format!("nonce-{}", recorded_at),
+        payer: "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5".to_string(),
+        chain: "Ethereum".to_string(),
+        chain_id: "1".to_string(),
+        token: "ETH".to_string(),
+        gross: "1000".to_string(),
+        fee: "0".to_string(),
+        transaction_hash: None,
+        resource: "GET /premium".to_string(),
+        finality: Finality::Provisional,
+        tax: None,
+        invoice: None,
+    }
+}
+
+#[tokio::test]
+async fn payments_come_back_in_time_order() {
+    let store = store();
+    for recorded_at in [300, 100, 200] {
+        store.record_payment(record(recorded_at)).await.unwrap();
+    }
+    let payments = store.payments_between(100, 300).await.unwrap();
+    let times: Vec<u64> = payments.iter().map(|record| record.recorded_at).collect();
+    assert_eq!(times, vec![100, 200]);
+
+    store
+        .set_finality("nonce-200", Finality::Finalized)
+        .await
+        .unwrap();
+    let provisional = store.provisional_payments().await.unwrap();
+    assert_eq!(provisional.len(), 2);
+    assert_eq!(
+        store.payment("nonce-200").await.unwrap().unwrap().finality,
+        Finality::Finalized
+    );
+}
+
+#[tokio::test]
+async fn counters_and_nonces_persist() {
+    let store = store();
+    assert_eq!(store.increment("GET /premium").await.unwrap(), 1);
+    assert_eq!(store.increment("GET /premium").await.unwrap(), 2);
+    assert_eq!(UsageStore::get(&store, "GET /premium").await.unwrap(), 2);
+
+    assert_eq!(store.next_nonce("1:0xrelay").await.unwrap(), None);
+    store.save_next_nonce("1:0xrelay", 7).await.unwrap();
+    assert_eq!(store.next_nonce("1:0xrelay").await.unwrap(), Some(7));
+}
+
+#[tokio::test]
+async fn revocations_expire() {
+    let store = store();
+    let entry = RevocationEntry {
+        reason: RevocationReason::Refunded,
+        revoked_at: 100,
+        expires_at: 200,
+    };
+    store.revoke("nonce-1", entry).await.unwrap();
+    assert!(
+        RevocationStore::get(&store, "nonce-1", 150)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        RevocationStore::get(&store, "nonce-1", 200)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(store.purge_expired(200).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn encrypted_records_need_the_key() {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let cipher = |key| EnvelopeCipher::new(Arc::new(LocalKey::new("test", [key; 32])));
+    let store = SledStore::from_db(db.clone(), Some(cipher(1))).unwrap();
+    store.record_payment(record(100)).await.unwrap();
+    assert_eq!(
+        store.payment("nonce-100").await.unwrap().unwrap().payer,
+        record(100).payer
+    );
+
+    let raw = db.open_tree("payments").unwrap().iter().values().next();
+    let raw = raw.unwrap().unwrap();
+    assert!(!String::from_utf8_lossy(&raw).contains("0x742e4d6c"));
+    let wrong_key = SledStore::from_db(db, Some(cipher(2))).unwrap();
+    assert!(wrong_key.payment("nonce-100").await.is_err());
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "sqlite")]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use x402_sdk::audit::{AuditAction, AuditEntry, AuditLog};
+use x402_sdk::revocation::{RevocationEntry, RevocationReason, RevocationStore};
+use x402_sdk::store::{
+    PaymentRecord, PaymentStore, SQLITE_SCHEMA_VERSION, SqliteStore, UsageStore,
+};
+use x402_sdk::types::Finality;
+
+fn temp_path() -> PathBuf {
+    std::env::temp_dir().join(format!("x402-{}.db", uuid::Uuid::new_v4()))
+}
+
+fn record(recorded_at: u64) -> PaymentRecord {
+    PaymentRecord {
+        recorded_at,
+        nonce: format!("nonce-{}", recorded_at),
+        payer: "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5".to_string(),
+        chain: "Ethereum".to_string(),
+        chain_id: "1".to_string(),
+        token: "ETH".to_string(),
+        gross: "1000".to_string(),
+        fee: "0".to_string(),
+        transaction_hash: None,
+        resource: "GET /premium".to_string(),
+        finality: Finality::Provisional,
+        tax: None,
+        invoice: None,
+    }
+}
+
+#[tokio::test]
+async fn migrations_run_once_and_data_survives_reopening() {
+    let path = temp_path();
+    let store = SqliteStore::open(&path).unwrap();
+    assert_eq!(store.schema_version().await.unwrap(), SQLITE_SCHEMA_VERSION);
+    store.record_payment(record(100)).await.unwrap();
+    drop(store);
+
+    let store = SqliteStore::open(&path).unwrap();
+    assert_eq!(store.schema_version().await.unwrap(), SQLITE_SCHEMA_VERSION);
+    assert!(store.payment("nonce-100").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn payments_are_ordered_and_finality_is_tracked() {
+    let store = SqliteStore::open(temp_path()).unwrap();
+    for recorded_at in [300, 100, 200] {
+        store.record_payment(record(recorded_at)).await.unwrap();
+    }
+    let times: Vec<u64> = store
+        .payments_between(0, 300)
+        .await
+        .unwrap()
+        .iter()
+        .map(|record| record.recorded_at)
+        .collect();
+    assert_eq!(times, vec![100, 200]);
+
+    store
+        .set_finality("nonce-100", Finality::Finalized)
+        .await
+        .unwrap();
+    let provisional = store.provisional_payments().await.unwrap();
+    assert_eq!(provisional.len(), 2);
+    assert!(provisional.iter().all(|record| record.nonce != "nonce-100"));
+}
+
+#[tokio::test]
+async fn revocations_and_audit_entries_persist() {
+    let store = SqliteStore::open(temp_path()).unwrap();
+    let entry = RevocationEntry {
+        reason: RevocationReason::Fraudulent,
+        revoked_at: 100,
+        expires_at: 200,
+    };
+    store.revoke("nonce-1", entry).await.unwrap();
+    assert!(
+        RevocationStore::get(&store, "nonce-1", 199)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert_eq!(store.purge_expired(200).await.unwrap(), 1);
+
+    for (timestamp, action) in [
+        (1, AuditAction::DisputeOpened),
+        (2, AuditAction::DisputeResolved),
+    ] {
+        store
+            .append(AuditEntry {
+                timestamp,
+                operator: "support@example.org".to_string(),
+                action,
+                target: "nonce-1".to_string(),
+                note: None,
+            })
+            .await
+            .unwrap();
+    }
+    let entries = store.entries_for("nonce-1").await.unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].action, AuditAction::DisputeOpened);
+}
+
+/// two connections on one file, as two processes would open it, wait for
+/// each other's write locks instead of failing
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_writers_do_not_lose_updates() {
+    let path = temp_path();
+    let stores = [
+        Arc::new(SqliteStore::open(&path).unwrap()),
+        Arc::new(SqliteStore::open(&path).unwrap()),
+    ];
+    let mut tasks = Vec::new();
+    for task in 0..8 {
+        let store = stores[task % 2].clone();
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..25 {
+                store.increment("GET /premium").await.unwrap();
+            }
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+    assert_eq!(
+        UsageStore::get(stores[0].as_ref(), "GET /premium")
+            .await
+            .unwrap(),
+        200
+    );
+}
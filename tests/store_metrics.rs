@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use x402_sdk::core::X402;
+use x402_sdk::store::{
+    InMemoryPaymentStore, InstrumentedStore, LATENCY_BUCKETS_MS, PaymentStore, StoreError,
+    StoreErrorClass, StoreMetrics, UsageStore,
+};
+
+/// usage store whose backend is down
+struct DownStore;
+
+#[async_trait]
+impl UsageStore for DownStore {
+    async fn increment(&self, _key: &str) -> Result<u64, StoreError> {
+        Err(StoreError::Backend("connection reset".to_string()))
+    }
+
+    async fn get(&self, _key: &str) -> Result<u64, StoreError> {
+        Err(StoreError::Encryption(
+            "decryption failed, wrong key?".to_string(),
+        ))
+    }
+}
+
+#[tokio::test]
+async fn calls_are_counted_per_operation() {
+    let metrics = Arc::new(StoreMetrics::new());
+    let store = InstrumentedStore::new(
+        "payments",
+        Arc::new(InMemoryPaymentStore::new()),
+        metrics.clone(),
+    );
+    assert!(
+        store
+            .payments_between(0, u64::MAX)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(store.provisional_payments().await.unwrap().is_empty());
+    assert!(store.provisional_payments().await.unwrap().is_empty());
+
+    let stats = metrics
+        .operation("payments", "provisional_payments")
+        .unwrap();
+    assert_eq!(stats.calls, 2);
+    assert_eq!(stats.error_count(), 0);
+    assert_eq!(stats.latency_buckets.len(), LATENCY_BUCKETS_MS.len() + 1);
+    assert_eq!(stats.latency_buckets.iter().sum::<u64>(), 2);
+    assert!(stats.mean_latency().is_some());
+    assert_eq!(metrics.snapshot().len(), 2);
+}
+
+#[tokio::test]
+async fn errors_are_classified() {
+    let metrics = Arc::new(StoreMetrics::new());
+    let store = InstrumentedStore::new("usage", Arc::new(DownStore), metrics.clone());
+    assert!(store.increment("0xabc").await.is_err());
+    assert!(store.increment("0xabc").await.is_err());
+    assert!(store.get("0xabc").await.is_err());
+
+    let increments = metrics.operation("usage", "increment").unwrap();
+    assert_eq!(increments.errors[&StoreErrorClass::Backend], 2);
+    let gets = metrics.operation("usage", "get").unwrap();
+    assert_eq!(gets.errors[&StoreErrorClass::Encryption], 1);
+}
+
+#[tokio::test]
+async fn slow_calls_are_flagged() {
+    let metrics = Arc::new(StoreMetrics::new());
+    let store = InstrumentedStore::new(
+        "payments",
+        Arc::new(InMemoryPaymentStore::new()),
+        metrics.clone(),
+    )
+    .with_slow_threshold(Duration::ZERO);
+    store.provisional_payments().await.unwrap();
+    assert_eq!(
+        metrics
+            .operation("payments", "provisional_payments")
+            .unwrap()
+            .slow_calls,
+        1
+    );
+}
+
+#[tokio::test]
+async fn engine_stores_can_be_instrumented() {
+    let metrics = Arc::new(StoreMetrics::new());
+    let payments: Arc<dyn PaymentStore> = Arc::new(InMemoryPaymentStore::new());
+    let engine = X402::from_default_config()
+        .unwrap()
+        .with_payment_store(Arc::new(InstrumentedStore::new(
+            "payments",
+            payments,
+            metrics.clone(),
+        )));
+    engine
+        .handle_access_request(
+            "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5",
+            "/premium",
+            None,
+            Some("1000"),
+        )
+        .await
+        .unwrap();
+    assert!(
+        metrics
+            .snapshot()
+            .iter()
+            .all(|stats| stats.store == "payments")
+    );
+}
@@ -0,0 +1,48 @@
+use x402_sdk::context::RequestContext;
+use x402_sdk::events::{PaymentEvent, PaymentEventKind};
+use x402_sdk::resource::Resource;
+use x402_sdk::store::OutboxEntry;
+use x402_sdk::streaming::{EVENT_SCHEMA, EventEnvelope};
+
+fn entry(kind: PaymentEventKind) -> OutboxEntry {
+    OutboxEntry {
+        id: "3b7d0c1e".to_string(),
+        event: PaymentEvent {
+            timestamp: 1_700_000_000,
+            user_address: "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5".to_string(),
+            resource: Resource::new("GET", "/premium").unwrap(),
+            context: RequestContext::default(),
+            kind,
+        },
+        attempts: 0,
+        next_attempt_at: 1_700_000_000,
+        last_error: None,
+        failed: false,
+        created_at: 1_700_000_000,
+    }
+}
+
+#[test]
+fn envelope_carries_schema_id_and_event_type() {
+    let envelope = EventEnvelope::new(&entry(PaymentEventKind::ContentServed {
+        nonce: "abc".to_string(),
+    }));
+    assert_eq!(envelope.schema, EVENT_SCHEMA);
+    assert_eq!(envelope.id, "3b7d0c1e");
+    assert_eq!(envelope.event_type, "content_served");
+
+    let json: serde_json::Value = serde_json::from_slice(&envelope.to_json()).unwrap();
+    assert_eq!(json["schema"], "x402.payment_event.v1");
+    assert_eq!(json["event_type"], "content_served");
+    assert_eq!(json["event"]["kind"]["ContentServed"]["nonce"], "abc");
+}
+
+#[test]
+fn event_types_are_snake_case() {
+    assert_eq!(PaymentEventKind::AccessGranted.name(), "access_granted");
+    let envelope = EventEnvelope::new(&entry(PaymentEventKind::SessionLockedOut {
+        nonce: "abc".to_string(),
+        locked_until: 1,
+    }));
+    assert_eq!(envelope.event_type, "session_locked_out");
+}
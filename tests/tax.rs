@@ -0,0 +1,152 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::context::RequestContext;
+use x402_sdk::pricing::PricingError;
+use x402_sdk::resource::Resource;
+use x402_sdk::tax::{
+    TaxCalculator, TaxJurisdiction, TaxLine, TaxRateTable, TaxTreatment, tax_line,
+};
+use x402_sdk::testing::mock_engine;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn rates() -> TaxRateTable {
+    TaxRateTable::new()
+        .with_rate("DE", "VAT", 1_900)
+        .with_rate("us-ca", "sales tax", 725)
+}
+
+fn from(jurisdiction: &str) -> RequestContext {
+    RequestContext::new("GET").with_extension(TaxJurisdiction(jurisdiction.to_string()))
+}
+
+#[tokio::test]
+async fn rates_are_looked_up_by_jurisdiction() {
+    let resource = Resource::new("GET", "/premium").unwrap();
+    let line = rates()
+        .calculate("10000", &resource, &from("US-CA"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(line.name, "sales tax");
+    assert_eq!(line.rate_bps, 725);
+    assert_eq!(line.amount, "725");
+    // jurisdictions match whatever their case
+    assert_eq!(rates().tax_for("de", 10_000).unwrap().amount, "1900");
+
+    // elsewhere, or without a jurisdiction, nothing is taxed
+    assert_eq!(
+        rates()
+            .calculate("10000", &resource, &from("FR"))
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        rates()
+            .calculate("10000", &resource, &RequestContext::new("GET"))
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(matches!(
+        rates().calculate("ten", &resource, &from("DE")).await,
+        Err(PricingError::InvalidAmount(_))
+    ));
+}
+
+#[test]
+fn tax_amounts_round_half_up() {
+    let exclusive = |rate_bps, amount| {
+        TaxLine::compute("DE", "VAT", rate_bps, TaxTreatment::Exclusive, amount).amount
+    };
+    assert_eq!(exclusive(725, 6), "0");
+    assert_eq!(exclusive(725, 10), "1");
+    assert_eq!(exclusive(2_500, 2), "1");
+    assert_eq!(exclusive(2_500, 1), "0");
+    // no overflow on the largest amounts
+    assert_eq!(exclusive(10_000, u128::MAX), u128::MAX.to_string());
+
+    // inclusive tax is what the gross amount holds beyond the net
+    let inclusive = |rate_bps, amount| {
+        TaxLine::compute("DE", "VAT", rate_bps, TaxTreatment::Inclusive, amount).amount
+    };
+    assert_eq!(inclusive(1_900, 11_900), "1900");
+    assert_eq!(inclusive(1_900, 100), "16");
+    assert_eq!(inclusive(1_900, 1), "0");
+}
+
+#[tokio::test]
+async fn exclusive_tax_is_added_to_the_challenge() {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = engine.with_tax_calculator(Arc::new(rates()));
+
+    let result = engine
+        .handle_access_request_with_context(PAYER, "/premium", None, Some("1000"), &from("de"))
+        .await
+        .unwrap();
+    let request = result.x402_response.unwrap().payment_required;
+    assert_eq!(request.amount, "1190");
+    assert_eq!(
+        tax_line(&request),
+        Some(TaxLine {
+            jurisdiction: "de".to_string(),
+            name: "VAT".to_string(),
+            rate_bps: 1_900,
+            amount: "190".to_string(),
+            treatment: TaxTreatment::Exclusive,
+        })
+    );
+
+    // the net amount underpays
+    verifier.set_paid_amount(Some(1000));
+    let result = engine
+        .handle_access_request_with_context(
+            PAYER,
+            "/premium",
+            Some(&request.nonce),
+            None,
+            &from("de"),
+        )
+        .await
+        .unwrap();
+    assert!(!result.should_serve_content);
+    verifier.set_paid_amount(Some(1190));
+    let result = engine
+        .handle_access_request_with_context(
+            PAYER,
+            "/premium",
+            Some(&request.nonce),
+            None,
+            &from("de"),
+        )
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+}
+
+#[tokio::test]
+async fn inclusive_tax_is_only_annotated() {
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine =
+        engine.with_tax_calculator(Arc::new(rates().with_treatment(TaxTreatment::Inclusive)));
+
+    let result = engine
+        .handle_access_request_with_context(PAYER, "/premium", None, Some("1000"), &from("DE"))
+        .await
+        .unwrap();
+    let request = result.x402_response.unwrap().payment_required;
+    assert_eq!(request.amount, "1000");
+    let line = tax_line(&request).unwrap();
+    assert_eq!(line.amount, "160");
+    assert_eq!(line.treatment, TaxTreatment::Inclusive);
+
+    // untaxed jurisdictions get a challenge without a tax line
+    let result = engine
+        .handle_access_request_with_context(PAYER, "/other", None, Some("1000"), &from("FR"))
+        .await
+        .unwrap();
+    let request = result.x402_response.unwrap().payment_required;
+    assert_eq!(request.amount, "1000");
+    assert_eq!(tax_line(&request), None);
+}
@@ -0,0 +1,40 @@
+use x402_sdk::context::RequestContext;
+use x402_sdk::telemetry::{
+    TRACEPARENT_HEADER, amount_bucket, current_trace_parent, in_request_trace, nonce_hash,
+    set_trace_parent,
+};
+
+const TRACE_PARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[test]
+fn nonce_hash_is_short_and_stable() {
+    let hash = nonce_hash("b8c1f0e2-6f7a-4d1e-9a55-3c2d7e0f9a11");
+    assert_eq!(hash.len(), 16);
+    assert_eq!(hash, nonce_hash("b8c1f0e2-6f7a-4d1e-9a55-3c2d7e0f9a11"));
+    assert_ne!(hash, nonce_hash("another nonce"));
+}
+
+#[test]
+fn amounts_are_bucketed_by_order_of_magnitude() {
+    assert_eq!(amount_bucket("0"), "0");
+    assert_eq!(amount_bucket("7"), "1e0");
+    assert_eq!(amount_bucket("1500000"), "1e6");
+    assert_eq!(amount_bucket("999999"), "1e5");
+    assert_eq!(amount_bucket("1.5"), "invalid");
+}
+
+#[tokio::test]
+async fn trace_context_is_scoped_to_the_request() {
+    assert_eq!(current_trace_parent(), None);
+    let context = RequestContext::new("GET").with_header("TraceParent", TRACE_PARENT);
+    let seen = in_request_trace(&context, async {
+        let incoming = current_trace_parent();
+        set_trace_parent(Some("00-child".to_string()));
+        (incoming, current_trace_parent())
+    })
+    .await;
+    assert_eq!(seen.0.as_deref(), Some(TRACE_PARENT));
+    assert_eq!(seen.1.as_deref(), Some("00-child"));
+    assert_eq!(current_trace_parent(), None);
+    assert_eq!(TRACEPARENT_HEADER, "traceparent");
+}
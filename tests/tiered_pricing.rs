@@ -0,0 +1,104 @@
+use std::sync::Arc;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::pricing::TieredPricing;
+use x402_sdk::store::InMemoryUsageStore;
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+use x402_sdk::usage::UsageTracker;
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const OTHER_PAYER: &str = "0x8ba1f109551bD432803012645Ac136ddd64DBA72";
+const NOW: u64 = 1_700_000_000;
+const PERIOD: u64 = 86_400;
+
+fn engine(clock: &MockClock) -> (X402, MockVerifier, Arc<UsageTracker>) {
+    let usage = Arc::new(
+        UsageTracker::new(Arc::new(InMemoryUsageStore::new()), PERIOD)
+            .with_clock(Arc::new(clock.clone())),
+    );
+    let pricing = TieredPricing::new(usage.clone())
+        .with_tier(2, "1000")
+        .with_tier(4, "700")
+        .with_final_tier("500")
+        .for_pattern("/api/**");
+    let config = ConfigBuilder::new().with_payment_amount("9000").build();
+    let (engine, verifier) = mock_engine(config);
+    let engine = engine
+        .with_clock(Arc::new(clock.clone()))
+        .with_usage_tracker(usage.clone())
+        .with_pricing_provider(Arc::new(pricing));
+    verifier.set_paid_amount(Some(1_000_000));
+    (engine, verifier, usage)
+}
+
+/// amount challenged for the next request, after which it is paid
+async fn paid_request(engine: &X402, payer: &str, path: &str) -> String {
+    let request = engine
+        .handle_access_request(payer, path, None, None)
+        .await
+        .unwrap()
+        .x402_response
+        .unwrap()
+        .payment_required;
+    let result = engine
+        .handle_access_request(payer, path, Some(&request.nonce), None)
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+    request.amount
+}
+
+#[tokio::test]
+async fn the_price_drops_with_the_volume_of_the_period() {
+    let clock = MockClock::new(NOW - NOW % PERIOD);
+    let (engine, _verifier, usage) = engine(&clock);
+
+    let mut amounts = Vec::new();
+    for _ in 0..6 {
+        amounts.push(paid_request(&engine, PAYER, "/api/search").await);
+    }
+    assert_eq!(amounts, ["1000", "1000", "700", "700", "500", "500"]);
+    assert_eq!(usage.usage(PAYER).await.unwrap(), 6);
+}
+
+#[tokio::test]
+async fn volume_is_counted_per_payer_and_period() {
+    let clock = MockClock::new(NOW - NOW % PERIOD);
+    let (engine, _verifier, usage) = engine(&clock);
+    for _ in 0..3 {
+        paid_request(&engine, PAYER, "/api/search").await;
+    }
+
+    assert_eq!(
+        paid_request(&engine, OTHER_PAYER, "/api/search").await,
+        "1000"
+    );
+    // the address is matched case-insensitively
+    assert_eq!(
+        paid_request(&engine, &PAYER.to_lowercase(), "/api/search").await,
+        "700"
+    );
+
+    clock.advance(PERIOD);
+    assert_eq!(usage.usage(PAYER).await.unwrap(), 0);
+    assert_eq!(paid_request(&engine, PAYER, "/api/search").await, "1000");
+}
+
+#[tokio::test]
+async fn resources_outside_the_pattern_keep_their_price() {
+    let clock = MockClock::new(NOW);
+    let (engine, _verifier, _usage) = engine(&clock);
+    assert_eq!(paid_request(&engine, PAYER, "/reports/q3").await, "9000");
+}
+
+#[test]
+fn tiers_include_their_upper_bound() {
+    let usage = Arc::new(UsageTracker::new(Arc::new(InMemoryUsageStore::new()), 0));
+    let pricing = TieredPricing::new(usage.clone()).with_tier(100, "1000");
+    assert_eq!(pricing.tier_for(100).unwrap().amount, "1000");
+    // no open-ended tier, nothing quoted past the last band
+    assert!(pricing.tier_for(101).is_none());
+
+    let pricing = pricing.with_final_tier("500");
+    assert_eq!(pricing.tier_for(101).unwrap().amount, "500");
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "native")]
+
+use solana_network_sdk::trade::{InstructionInfo, TokenBalance, TransactionInfo, UiTokenAmount};
+use std::collections::HashMap;
+use x402_sdk::types::AmountTolerance;
+use x402_sdk::verifier::minimum_amount;
+use x402_sdk::verifier::solana::{
+    SolanaVerifier, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, TokenTransfer,
+};
+
+const PAYER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const RECIPIENT: &str = "HN7cABqLq46Es1jh92dQQisAq662SmxELLLsHHe4YWrH";
+const MINT: &str = "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo";
+
+fn balance(owner: &str, amount: u128) -> TokenBalance {
+    TokenBalance {
+        account_index: 0,
+        mint: MINT.to_string(),
+        owner: owner.to_string(),
+        ui_token_amount: UiTokenAmount {
+            ui_amount: None,
+            decimals: 6,
+            amount: amount.to_string(),
+            ui_amount_string: None,
+        },
+    }
+}
+
+/// successful transfer of `sent` units by the payer, `received` of which
+/// reach the recipient, through `program_id`
+fn transfer(program_id: &str, sent: u128, received: u128) -> TransactionInfo {
+    TransactionInfo {
+        status: "success".to_string(),
+        pre_token_balances: vec![balance(PAYER, 5_000_000), balance(RECIPIENT, 0)],
+        post_token_balances: vec![
+            balance(PAYER, 5_000_000 - sent),
+            balance(RECIPIENT, received),
+        ],
+        instructions: vec![InstructionInfo {
+            program_id: program_id.to_string(),
+            accounts: vec![PAYER.to_string(), RECIPIENT.to_string()],
+            data: String::new(),
+            stack_height: None,
+            program: "spl-token".to_string(),
+        }],
+        ..TransactionInfo::default()
+    }
+}
+
+#[test]
+fn transfer_fees_are_not_credited() {
+    // 1 token with a 1% transfer fee withheld
+    let transaction = transfer(TOKEN_2022_PROGRAM_ID, 1_000_000, 990_000);
+    let transfer = SolanaVerifier::token_transfer(&transaction, PAYER, RECIPIENT, MINT).unwrap();
+    assert_eq!(
+        transfer,
+        TokenTransfer {
+            program_id: TOKEN_2022_PROGRAM_ID.to_string(),
+            sent: 1_000_000,
+            received: 990_000,
+            decimals: 6,
+        }
+    );
+    assert_eq!(transfer.fee(), 10_000);
+
+    // the payer sent the price but the recipient was credited less
+    let required = SolanaVerifier::parse_token_amount("1", 6).unwrap();
+    assert!(transfer.sent >= required);
+    assert!(transfer.received < required);
+
+    // unless the shortfall is within the dust tolerance of the mint
+    let tolerances = HashMap::from([(MINT.to_string(), AmountTolerance::BasisPoints(100))]);
+    assert!(transfer.received >= minimum_amount(&tolerances, MINT, required));
+}
+
+#[test]
+fn classic_transfers_credit_what_was_sent() {
+    let transaction = transfer(TOKEN_PROGRAM_ID, 1_000_000, 1_000_000);
+    let transfer = SolanaVerifier::token_transfer(&transaction, PAYER, RECIPIENT, MINT).unwrap();
+    assert_eq!(transfer.program_id, TOKEN_PROGRAM_ID);
+    assert_eq!(transfer.fee(), 0);
+    assert_eq!(
+        transfer.received,
+        SolanaVerifier::parse_token_amount("1", 6).unwrap()
+    );
+}
+
+#[test]
+fn transfers_crediting_nothing_are_ignored() {
+    let transaction = transfer(TOKEN_2022_PROGRAM_ID, 1_000_000, 0);
+    assert!(SolanaVerifier::token_transfer(&transaction, PAYER, RECIPIENT, MINT).is_none());
+    // another mint than the requested one
+    let transaction = transfer(TOKEN_2022_PROGRAM_ID, 1_000_000, 990_000);
+    assert!(
+        SolanaVerifier::token_transfer(
+            &transaction,
+            PAYER,
+            RECIPIENT,
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+        )
+        .is_none()
+    );
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "tower")]
+
+use http::{Request, Response, StatusCode};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use tower::{Service, ServiceBuilder, ServiceExt};
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::core::X402;
+use x402_sdk::crypto::Ed25519Suite;
+use x402_sdk::keys::{JWKS_PATH, JwkSet, KeyRing};
+use x402_sdk::services::{AccessRequest, JwksLayer};
+use x402_sdk::testing::{MockVerifier, mock_engine};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+
+fn engine() -> (Arc<X402>, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    (Arc::new(engine), verifier)
+}
+
+#[tokio::test]
+async fn the_engine_serves_access_requests() {
+    let (engine, verifier) = engine();
+    let result = engine
+        .clone()
+        .oneshot(AccessRequest::new(PAYER, "/premium").with_amount("5000"))
+        .await
+        .unwrap();
+    assert_eq!(result.http_status, 402);
+    let nonce = result.x402_response.unwrap().payment_required.nonce;
+
+    verifier.set_paid_amount(Some(5000));
+    let mut service = engine;
+    let result = service
+        .ready()
+        .await
+        .unwrap()
+        .call(AccessRequest::new(PAYER, "/premium").with_nonce(&nonce))
+        .await
+        .unwrap();
+    assert!(result.should_serve_content);
+}
+
+#[tokio::test]
+async fn the_engine_composes_with_tower_layers() {
+    let (engine, _verifier) = engine();
+    let service = ServiceBuilder::new()
+        .timeout(Duration::from_secs(5))
+        .concurrency_limit(4)
+        .service(engine);
+    let results = futures::future::join_all((0..8).map(|_| {
+        service
+            .clone()
+            .oneshot(AccessRequest::new(PAYER, "/premium").with_amount("5000"))
+    }))
+    .await;
+    for result in results {
+        assert_eq!(result.unwrap().http_status, 402);
+    }
+}
+
+#[tokio::test]
+async fn the_jwks_layer_publishes_the_signing_keys() {
+    let key_ring = KeyRing::new(0);
+    key_ring.add_key("2026-01", Ed25519Suite::new([7; 32]), 0, None);
+    let (engine, _verifier) = mock_engine(ConfigBuilder::new().build());
+    let engine = Arc::new(engine.with_key_ring(Arc::new(key_ring)));
+    let app = ServiceBuilder::new()
+        .layer(JwksLayer::new(engine))
+        .service_fn(|_: Request<()>| async {
+            Ok::<_, Infallible>(Response::new(b"upstream".to_vec()))
+        });
+
+    let response = app
+        .clone()
+        .oneshot(Request::get(JWKS_PATH).body(()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let jwks: JwkSet = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(jwks.keys[0].kid, "2026-01");
+
+    let response = app
+        .oneshot(Request::get("/premium").body(()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.body(), b"upstream");
+}
+
+#[tokio::test]
+async fn the_jwks_layer_has_nothing_to_publish_without_keys() {
+    let (engine, _verifier) = engine();
+    let response = ServiceBuilder::new()
+        .layer(JwksLayer::new(engine))
+        .service_fn(|_: Request<()>| async { Ok::<_, Infallible>(Response::new(Vec::<u8>::new())) })
+        .oneshot(Request::get(JWKS_PATH).body(()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,61 @@
+use x402_sdk::types::{ChainConfig, ChainType, PaymentVerification, TransactionLog};
+
+fn log(hash: u8, block_number: u64, log_index: u64) -> TransactionLog {
+    TransactionLog {
+        transaction_hash: format!("0x{:064x}", hash),
+        from: "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5".to_string(),
+        to: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+        value: "1000".to_string(),
+        block_number,
+        log_index,
+        data: None,
+        explorer_url: None,
+    }
+}
+
+fn verification(payment: u8, transaction_logs: Vec<TransactionLog>) -> PaymentVerification {
+    PaymentVerification {
+        is_paid: true,
+        paid_amount: "1000".to_string(),
+        transaction_hash: Some(format!("0x{:064x}", payment)),
+        verified_at: 0,
+        chain: ChainConfig::new(ChainType::ethereum(), None),
+        transaction_logs,
+        explorer_url: None,
+        conversion: None,
+        paid_currency: None,
+        failure_reason: None,
+        proof: None,
+    }
+}
+
+fn positions(verification: &PaymentVerification) -> Vec<(u64, u64)> {
+    verification
+        .transaction_logs
+        .iter()
+        .map(|log| (log.block_number, log.log_index))
+        .collect()
+}
+
+#[test]
+fn overlapping_scans_are_deduplicated_and_ordered() {
+    let verification = verification(
+        1,
+        vec![
+            log(3, 9, 0),
+            log(1, 5, 2),
+            log(2, 5, 1),
+            log(1, 5, 2),
+            log(3, 9, 0),
+        ],
+    )
+    .with_bounded_logs(0);
+    assert_eq!(positions(&verification), vec![(5, 1), (5, 2), (9, 0)]);
+}
+
+#[test]
+fn capped_logs_keep_the_payment_and_the_newest() {
+    let logs = (1..=6).map(|n| log(n, u64::from(n), 0)).collect();
+    let verification = verification(1, logs).with_bounded_logs(3);
+    assert_eq!(positions(&verification), vec![(1, 0), (5, 0), (6, 0)]);
+}
@@ -0,0 +1,163 @@
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::H256;
+use ethers::utils::{hex, keccak256};
+use x402_sdk::address::to_hex;
+use x402_sdk::relay::TransferAuthorization;
+use x402_sdk::typed_data::{Eip712Domain, Permit, TypedDataError, TypedMessage};
+
+/// `TRANSFER_WITH_AUTHORIZATION_TYPEHASH` of EIP-3009
+const TRANSFER_TYPEHASH: &str =
+    "0x7c7c6cdb67a18743f49ec6fa9b35f50d52ed05cbed4cc592e13b44501c1a2267";
+/// `PERMIT_TYPEHASH` of EIP-2612
+const PERMIT_TYPEHASH: &str = "0x6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9";
+const DOMAIN_TYPEHASH: &str = "0x8b73c3c69bb8fe3d512ecc4cf759cc79239f7b179b0ffacaa9a75d522b39400f";
+
+fn usdc() -> Eip712Domain {
+    Eip712Domain::new(
+        "USD Coin",
+        "2",
+        1,
+        "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+    )
+}
+
+fn authorization(from: &str) -> TransferAuthorization {
+    TransferAuthorization {
+        token: usdc().verifying_contract,
+        from: from.to_string(),
+        to: "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(),
+        value: "1000000".to_string(),
+        valid_after: 0,
+        valid_before: 1_700_000_600,
+        nonce: to_hex([0x42; 32]),
+        signature: String::new(),
+    }
+}
+
+/// the signer of the EIP-712 specification example
+fn cow() -> LocalWallet {
+    LocalWallet::from_bytes(&keccak256("cow")).unwrap()
+}
+
+#[test]
+fn type_hashes_match_the_token_standards() {
+    assert_eq!(
+        to_hex(TransferAuthorization::type_hash()),
+        TRANSFER_TYPEHASH
+    );
+    assert_eq!(to_hex(Permit::type_hash()), PERMIT_TYPEHASH);
+    assert_eq!(to_hex(Eip712Domain::type_hash()), DOMAIN_TYPEHASH);
+}
+
+#[test]
+fn domain_separators_match_known_contracts() {
+    // domain of the EIP-712 specification example
+    let mail = Eip712Domain::new(
+        "Ether Mail",
+        "1",
+        1,
+        "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+    );
+    assert_eq!(
+        to_hex(mail.separator().unwrap()),
+        "0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+    // `DOMAIN_SEPARATOR()` of USDC on Ethereum mainnet
+    assert_eq!(
+        to_hex(usdc().separator().unwrap()),
+        "0x06c37168a7db5138defc7866392bb87a741f9b3d104deb5094588ce041cae335"
+    );
+}
+
+#[test]
+fn wallet_signatures_recover_the_payer() {
+    let wallet = cow();
+    assert_eq!(
+        to_hex(wallet.address()),
+        "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826"
+    );
+    let domain = usdc();
+    let mut authorization = authorization(&to_hex(wallet.address()));
+    let hash = domain.signing_hash(&authorization).unwrap();
+    let signature = wallet.sign_hash(H256::from(hash)).unwrap();
+    authorization.signature = format!("0x{}", signature);
+    assert_eq!(
+        domain
+            .recover_signer(&authorization, &authorization.signature)
+            .unwrap(),
+        to_hex(wallet.address())
+    );
+
+    // some hardware wallets report `v` as 0 or 1
+    let mut bytes = signature.to_vec();
+    bytes[64] -= 27;
+    assert_eq!(
+        domain
+            .recover_signer(&authorization, &hex::encode(bytes))
+            .unwrap(),
+        to_hex(wallet.address())
+    );
+
+    // any changed member yields another signer
+    authorization.value = "1000001".to_string();
+    assert_ne!(
+        domain
+            .recover_signer(&authorization, &authorization.signature)
+            .unwrap(),
+        to_hex(wallet.address())
+    );
+}
+
+#[test]
+fn permits_sign_and_recover() {
+    let wallet = cow();
+    let permit = Permit {
+        owner: to_hex(wallet.address()),
+        spender: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+        value: "1000000".to_string(),
+        nonce: 0,
+        deadline: 1_700_000_600,
+    };
+    let domain = usdc();
+    let signature = wallet
+        .sign_hash(H256::from(domain.signing_hash(&permit).unwrap()))
+        .unwrap();
+    assert_eq!(
+        domain
+            .recover_signer(&permit, &signature.to_string())
+            .unwrap(),
+        permit.owner
+    );
+}
+
+#[test]
+fn typed_data_lists_the_domain_and_primary_type() {
+    let domain = usdc();
+    let typed_data =
+        domain.typed_data(&authorization("0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5"));
+    assert_eq!(typed_data["primaryType"], "TransferWithAuthorization");
+    assert_eq!(typed_data["domain"]["chainId"], 1);
+    assert_eq!(
+        typed_data["domain"]["verifyingContract"],
+        domain.verifying_contract
+    );
+    assert_eq!(
+        typed_data["types"]["TransferWithAuthorization"][5]["type"],
+        "bytes32"
+    );
+    assert_eq!(typed_data["message"]["validBefore"], "1700000600");
+}
+
+#[test]
+fn malformed_members_are_rejected() {
+    let mut authorization = authorization("0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5");
+    authorization.nonce = "0x42".to_string();
+    assert_eq!(
+        usdc().signing_hash(&authorization),
+        Err(TypedDataError::InvalidField("nonce".to_string()))
+    );
+    assert!(matches!(
+        usdc().recover_signer(&authorization, "0x1234"),
+        Err(TypedDataError::InvalidSignature(_))
+    ));
+}
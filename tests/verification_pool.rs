@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use std::time::Duration;
+use x402_sdk::config::VerificationPoolConfig;
+use x402_sdk::testing::MockVerifier;
+use x402_sdk::types::{
+    ChainConfig, ChainType, Currency, PaymentMetadata, PaymentRequest, PaymentVerification,
+};
+use x402_sdk::verifier::pool::PooledVerifier;
+use x402_sdk::verifier::{PaymentVerifier, VerificationError};
+
+const PAYER: &str = "0x742e4d6c9ff68c6e355b069e2775d3dd6876b4a5";
+
+/// Verifier holding its pool slot for a while, like a slow RPC.
+struct SlowVerifier {
+    inner: MockVerifier,
+    delay: Duration,
+}
+
+#[async_trait]
+impl PaymentVerifier for SlowVerifier {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        tokio::time::sleep(self.delay).await;
+        self.inner
+            .verify_payment(payment_request, payer_address)
+            .await
+    }
+
+    fn supports_chain(&self, _chain_type: &ChainType) -> bool {
+        true
+    }
+}
+
+fn request() -> PaymentRequest {
+    PaymentRequest {
+        amount: "1000".to_string(),
+        currency: Currency::Native,
+        recipient: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+        chain: ChainConfig::new(ChainType::ethereum(), None),
+        description: None,
+        expires_at: None,
+        nonce: "nonce".to_string(),
+        beneficiary: None,
+        reference: None,
+        metadata: PaymentMetadata::new(),
+        quote: None,
+    }
+}
+
+fn pool(max_queued: usize, queue_timeout_ms: u64) -> PooledVerifier {
+    let inner = MockVerifier::new();
+    inner.set_paid_amount(Some(1000));
+    PooledVerifier::new(
+        Box::new(SlowVerifier {
+            inner,
+            delay: Duration::from_millis(50),
+        }),
+        VerificationPoolConfig {
+            max_concurrent: 1,
+            max_queued,
+            queue_timeout_ms,
+            retry_after_secs: 3,
+        },
+    )
+}
+
+#[tokio::test]
+async fn full_queue_turns_verifications_away() {
+    let pool = pool(0, 1000);
+    let request = request();
+    let (running, rejected) = tokio::join!(pool.verify_payment(&request, PAYER), async {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        pool.verify_payment(&request, PAYER).await
+    });
+    assert!(running.unwrap().is_paid);
+    assert!(matches!(
+        rejected,
+        Err(VerificationError::Saturated { retry_after: 3 })
+    ));
+    assert_eq!(pool.status().rejected, 1);
+    assert_eq!(pool.status().queued, 0);
+}
+
+#[tokio::test]
+async fn queued_verification_runs_once_a_slot_frees_up() {
+    let pool = pool(1, 1000);
+    let request = request();
+    let (first, second) = tokio::join!(
+        pool.verify_payment(&request, PAYER),
+        pool.verify_payment(&request, PAYER)
+    );
+    assert!(first.unwrap().is_paid);
+    assert!(second.unwrap().is_paid);
+    assert_eq!(pool.status().rejected, 0);
+    assert_eq!(pool.status().running, 0);
+}
+
+#[tokio::test]
+async fn queued_verification_gives_up_after_the_timeout() {
+    let pool = pool(1, 10);
+    let request = request();
+    let (running, timed_out) = tokio::join!(
+        pool.verify_payment(&request, PAYER),
+        pool.verify_payment(&request, PAYER)
+    );
+    assert!(running.unwrap().is_paid);
+    assert!(matches!(
+        timed_out,
+        Err(VerificationError::Saturated { .. })
+    ));
+    assert_eq!(pool.status().queued, 0);
+}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use x402_sdk::testing::MockVerifier;
+use x402_sdk::types::{ChainType, EvmChain, PaymentRequest, PaymentVerification, SolanaChain};
+use x402_sdk::verifier::{PaymentVerifier, VerificationError, VerifierRegistry};
+
+/// verifier serving every EVM chain
+struct EvmOnly(MockVerifier);
+
+#[async_trait]
+impl PaymentVerifier for EvmOnly {
+    async fn verify_payment(
+        &self,
+        payment_request: &PaymentRequest,
+        payer_address: &str,
+    ) -> Result<PaymentVerification, VerificationError> {
+        self.0.verify_payment(payment_request, payer_address).await
+    }
+
+    fn supports_chain(&self, chain_type: &ChainType) -> bool {
+        matches!(chain_type, ChainType::Evm(_))
+    }
+}
+
+#[test]
+fn chains_resolve_to_a_verifier_supporting_them() {
+    let mut registry = VerifierRegistry::new();
+    registry.register_verifier(
+        ChainType::Evm(EvmChain::Ethereum),
+        Box::new(EvmOnly(MockVerifier::new())),
+    );
+
+    assert!(registry.has_verifier(&ChainType::Evm(EvmChain::Base)));
+    assert!(registry.has_verifier(&ChainType::Evm(EvmChain::Custom("31337".to_string()))));
+    assert!(!registry.has_verifier(&ChainType::Solana(SolanaChain::Mainnet)));
+    // only the registered key is listed
+    assert_eq!(
+        registry.supported_chains(),
+        vec![ChainType::Evm(EvmChain::Ethereum)]
+    );
+}
+
+/// address of the verifier a chain resolves to
+fn resolved(registry: &VerifierRegistry, chain: EvmChain) -> Option<*const ()> {
+    registry
+        .get_verifier(&ChainType::Evm(chain))
+        .map(|verifier| verifier as *const dyn PaymentVerifier as *const ())
+}
+
+#[test]
+fn exact_registrations_win_over_capability() {
+    let mut registry = VerifierRegistry::new();
+    registry.register_verifier(
+        ChainType::Evm(EvmChain::Ethereum),
+        Box::new(EvmOnly(MockVerifier::new())),
+    );
+    registry.register_verifier(
+        ChainType::Evm(EvmChain::Polygon),
+        Box::new(EvmOnly(MockVerifier::new())),
+    );
+    let ethereum = resolved(&registry, EvmChain::Ethereum);
+    // the first registered verifier serves the other chains
+    assert_eq!(resolved(&registry, EvmChain::Base), ethereum);
+    assert_ne!(resolved(&registry, EvmChain::Polygon), ethereum);
+
+    registry.remove_verifier(&ChainType::Evm(EvmChain::Ethereum));
+    assert_eq!(
+        resolved(&registry, EvmChain::Base),
+        resolved(&registry, EvmChain::Polygon)
+    );
+    registry.remove_verifier(&ChainType::Evm(EvmChain::Polygon));
+    assert_eq!(resolved(&registry, EvmChain::Ethereum), None);
+    assert!(registry.supported_chains().is_empty());
+}
@@ -0,0 +1,128 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use x402_sdk::config::ConfigBuilder;
+use x402_sdk::context::RequestContext;
+use x402_sdk::core::X402;
+use x402_sdk::testing::{MockClock, MockVerifier, mock_engine};
+use x402_sdk::websocket::{
+    PAYMENT_REQUIRED_CLOSE_CODE, SocketEntitlement, TerminateReason, Upgrade, WebSocketPaywall,
+};
+
+const PAYER: &str = "0x742E4D6c9Ff68c6E355B069E2775D3Dd6876b4a5";
+const SOCKET: &str = "/ws/prices";
+
+fn engine(clock: Arc<MockClock>) -> (Arc<X402>, MockVerifier) {
+    let (engine, verifier) = mock_engine(ConfigBuilder::new().build());
+    (Arc::new(engine.with_clock(clock)), verifier)
+}
+
+/// pays the challenge of the upgrade request and upgrades again
+async fn paid_upgrade(paywall: &WebSocketPaywall, verifier: &MockVerifier) -> SocketEntitlement {
+    let context = RequestContext::new("GET");
+    let Upgrade::Refuse(result) = paywall
+        .authorize_upgrade(PAYER, SOCKET, None, None, &context)
+        .await
+        .unwrap()
+    else {
+        panic!("unpaid upgrade accepted");
+    };
+    assert_eq!(result.http_status, 402);
+    let nonce = result.x402_response.unwrap().payment_required.nonce;
+
+    verifier.set_paid_amount(Some(5000));
+    match paywall
+        .authorize_upgrade(PAYER, SOCKET, Some(&nonce), None, &context)
+        .await
+        .unwrap()
+    {
+        Upgrade::Accept(entitlement) => entitlement,
+        Upgrade::Refuse(result) => panic!("paid upgrade refused: {}", result.http_status),
+    }
+}
+
+#[tokio::test]
+async fn the_message_quota_terminates_the_socket() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, verifier) = engine(clock);
+    let terminated = Arc::new(Mutex::new(Vec::new()));
+    let hook_terminated = terminated.clone();
+    let paywall = WebSocketPaywall::new(engine)
+        .with_price("5000")
+        .with_max_messages(2)
+        .with_terminate_hook(move |entitlement, reason| {
+            assert_eq!(entitlement.payer(), PAYER);
+            hook_terminated.lock().unwrap().push(reason);
+        });
+    let entitlement = paid_upgrade(&paywall, &verifier).await;
+
+    assert!(entitlement.record_message().is_ok());
+    assert_eq!(entitlement.remaining_messages(), Some(1));
+    assert!(terminated.lock().unwrap().is_empty());
+    assert!(entitlement.record_message().is_ok());
+    assert_eq!(
+        entitlement.record_message(),
+        Err(TerminateReason::MessagesExhausted)
+    );
+    assert_eq!(
+        *terminated.lock().unwrap(),
+        vec![TerminateReason::MessagesExhausted]
+    );
+    assert_eq!(
+        entitlement.exhausted().await,
+        TerminateReason::MessagesExhausted
+    );
+}
+
+#[tokio::test]
+async fn the_duration_terminates_the_socket() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, verifier) = engine(clock.clone());
+    let paywall = WebSocketPaywall::new(engine)
+        .with_price("5000")
+        .with_max_duration(Duration::from_secs(60));
+    let entitlement = paid_upgrade(&paywall, &verifier).await;
+    assert_eq!(entitlement.expires_at(), Some(1_060));
+    assert!(entitlement.record_message().is_ok());
+
+    clock.set(1_060);
+    let reason = entitlement.check().unwrap_err();
+    assert_eq!(reason, TerminateReason::DurationElapsed);
+    assert_eq!(reason.close_code(), PAYMENT_REQUIRED_CLOSE_CODE);
+    assert!(entitlement.record_message().is_err());
+}
+
+#[tokio::test]
+async fn waiters_wake_up_when_the_quota_runs_out() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, verifier) = engine(clock);
+    let paywall = WebSocketPaywall::new(engine)
+        .with_price("5000")
+        .with_max_messages(1);
+    let entitlement = Arc::new(paid_upgrade(&paywall, &verifier).await);
+
+    let waiter = tokio::spawn({
+        let entitlement = entitlement.clone();
+        async move { entitlement.exhausted().await }
+    });
+    tokio::task::yield_now().await;
+    assert!(entitlement.record_message().is_ok());
+    let reason = tokio::time::timeout(Duration::from_secs(5), waiter)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(reason, TerminateReason::MessagesExhausted);
+}
+
+#[tokio::test]
+async fn unlimited_sockets_are_never_terminated() {
+    let clock = Arc::new(MockClock::new(1_000));
+    let (engine, verifier) = engine(clock.clone());
+    let paywall = WebSocketPaywall::new(engine).with_price("5000");
+    let entitlement = paid_upgrade(&paywall, &verifier).await;
+    clock.advance(86_400);
+    for _ in 0..100 {
+        assert!(entitlement.record_message().is_ok());
+    }
+    assert_eq!(entitlement.remaining_messages(), None);
+    assert!(entitlement.check().is_ok());
+}
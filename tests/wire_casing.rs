@@ -0,0 +1,104 @@
+use serde_json::json;
+use x402_sdk::headers::{decode_payment_required, encode_payment_required_in};
+use x402_sdk::signing::{ChallengeSigner, verify_challenge_signature};
+use x402_sdk::types::{
+    ChainConfig, ChainType, PaymentMetadata, PaymentRequest, X402ProtocolResponse,
+};
+use x402_sdk::wire::FieldCasing;
+
+const SIGNING_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+fn request() -> PaymentRequest {
+    PaymentRequest {
+        amount: "1000".to_string(),
+        currency: x402_sdk::types::Currency::Native,
+        recipient: "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".to_string(),
+        chain: ChainConfig::new(ChainType::ethereum(), None),
+        description: None,
+        expires_at: Some(1_700_000_600),
+        nonce: "nonce".to_string(),
+        beneficiary: None,
+        reference: None,
+        metadata: PaymentMetadata::new()
+            .with_merchant_name("Example")
+            .with_extension("plan", json!({ "expires_at": 1 })),
+        quote: None,
+    }
+}
+
+#[test]
+fn camel_case_challenges_from_other_sdks_are_read() {
+    let response: X402ProtocolResponse = serde_json::from_value(json!({
+        "status": 402,
+        "paymentRequired": {
+            "maxAmountRequired": "1000",
+            "currency": "Native",
+            "payTo": "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "chain": { "chainType": { "Evm": "Ethereum" }, "chainId": "1", "rpcUrl": null },
+            "description": null,
+            "expiresAt": 1_700_000_600,
+            "nonce": "nonce",
+            "metadata": { "merchantName": "Example" },
+        },
+        "verificationUrl": "https://api.example.com/verify/nonce",
+    }))
+    .unwrap();
+    let request = response.payment_required;
+    assert_eq!(request.amount, "1000");
+    assert_eq!(
+        request.recipient,
+        "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+    );
+    assert_eq!(request.chain.chain_id, "1");
+    assert_eq!(request.expires_at, Some(1_700_000_600));
+    assert_eq!(request.metadata.merchant_name.as_deref(), Some("Example"));
+    assert!(response.verification_url.is_some());
+}
+
+#[test]
+fn requests_are_serialized_in_snake_case() {
+    let value = serde_json::to_value(request()).unwrap();
+    assert_eq!(value["amount"], "1000");
+    assert_eq!(value["chain"]["chain_id"], "1");
+    assert_eq!(value["metadata"]["merchant_name"], "Example");
+}
+
+#[test]
+fn camel_case_is_written_on_request_and_reads_back() {
+    let value = FieldCasing::Camel.to_value(&request()).unwrap();
+    assert_eq!(value["maxAmountRequired"], "1000");
+    assert_eq!(value["payTo"], "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+    assert_eq!(value["expiresAt"], 1_700_000_600);
+    assert_eq!(value["chain"]["chainId"], "1");
+    assert_eq!(value["metadata"]["merchantName"], "Example");
+    // fields of the service are left as they are
+    assert_eq!(value["metadata"]["extensions"]["plan"]["expires_at"], 1);
+    assert!(value.get("amount").is_none());
+
+    let request: PaymentRequest = serde_json::from_value(value).unwrap();
+    assert_eq!(request.amount, "1000");
+    assert_eq!(request.expires_at, Some(1_700_000_600));
+    assert_eq!(
+        FieldCasing::Snake.to_value(&request).unwrap(),
+        serde_json::to_value(&request).unwrap()
+    );
+}
+
+#[test]
+fn signatures_hold_in_either_casing() {
+    let signer = ChallengeSigner::from_private_key(SIGNING_KEY).unwrap();
+    let mut response = X402ProtocolResponse {
+        status: 402,
+        payment_required: request(),
+        verification_url: None,
+        signature: None,
+        message: None,
+        requote: None,
+    };
+    signer.sign_response(&mut response).unwrap();
+    for casing in [FieldCasing::Snake, FieldCasing::Camel] {
+        let header = encode_payment_required_in(&response, casing).unwrap();
+        let received = decode_payment_required(&header).unwrap();
+        verify_challenge_signature(&received, &signer.address()).unwrap();
+    }
+}
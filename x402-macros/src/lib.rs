@@ -0,0 +1,148 @@
+/// Attribute macros of x402-sdk.
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{FnArg, ItemFn, LitStr, parse_macro_input};
+
+/// chains known to `x402_sdk::paid::chain_by_name`
+const CHAINS: &[&str] = &[
+    "ethereum",
+    "polygon",
+    "bsc",
+    "arbitrum",
+    "optimism",
+    "avalanche",
+    "base",
+    "solana",
+];
+
+#[derive(Default)]
+struct PaidArgs {
+    amount: Option<LitStr>,
+    chain: Option<LitStr>,
+    route: Option<LitStr>,
+    description: Option<LitStr>,
+}
+
+/// `"<amount> <symbol>"` with a decimal amount
+fn valid_price(price: &str) -> bool {
+    let Some((value, symbol)) = price.trim().split_once(' ') else {
+        return false;
+    };
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    !whole.is_empty()
+        && whole.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit())
+        && !symbol.trim().is_empty()
+        && symbol.trim().chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn option(value: Option<LitStr>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote!(::core::option::Option::Some(#value)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+/// Marks a handler as paid, e.g.
+/// `#[paid(amount = "0.10 USDC", chain = "base", route = "GET /reports/**")]`.
+///
+/// The handler is kept as it is, next to it a module of the same name
+/// holds its price as the `PAID` constant, an `x402_sdk::paid::PaidHandler`.
+/// Registering it with `RulePricing::with_paid_handler` and handing the
+/// pricing to the engine behind the paywall middleware charges the route:
+///
+/// ```rust,ignore
+/// use std::sync::Arc;
+/// use x402_sdk::paid;
+/// use x402_sdk::pricing::RulePricing;
+///
+/// #[paid(amount = "0.01 ETH", chain = "base", route = "GET /reports/**")]
+/// async fn report() -> &'static str {
+///     "paid report"
+/// }
+///
+/// let pricing = RulePricing::new().with_paid_handler(&report::PAID)?;
+/// let engine = engine.with_pricing_provider(Arc::new(pricing));
+/// ```
+///
+/// Arguments:
+/// - `amount`: `"<amount> <symbol>"`, the native symbol of the chain, a
+///   stablecoin or `USD`
+/// - `chain`: one of `ethereum`, `polygon`, `bsc`, `arbitrum`, `optimism`,
+///   `avalanche`, `base` or `solana`, only prices in `USD` may omit it and
+///   are charged on the engine default chain
+/// - `route`: `"[METHOD ]/path"` pattern of the handler, or given when the
+///   handler is registered with `RulePricing::with_paid_route`
+/// - `description`: shown to payers in the challenge
+#[proc_macro_attribute]
+pub fn paid(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = PaidArgs::default();
+    let parser = syn::meta::parser(|meta| {
+        let slot = if meta.path.is_ident("amount") {
+            &mut args.amount
+        } else if meta.path.is_ident("chain") {
+            &mut args.chain
+        } else if meta.path.is_ident("route") {
+            &mut args.route
+        } else if meta.path.is_ident("description") {
+            &mut args.description
+        } else {
+            return Err(meta.error("expected `amount`, `chain`, `route` or `description`"));
+        };
+        *slot = Some(meta.value()?.parse()?);
+        Ok(())
+    });
+    parse_macro_input!(attr with parser);
+    let handler = parse_macro_input!(item as ItemFn);
+
+    let Some(amount) = args.amount else {
+        return syn::Error::new(Span::call_site(), "missing `amount`, e.g. \"0.10 USDC\"")
+            .to_compile_error()
+            .into();
+    };
+    if !valid_price(&amount.value()) {
+        return syn::Error::new(
+            amount.span(),
+            "expected \"<amount> <symbol>\", e.g. \"0.10 USDC\"",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if let Some(chain) = &args.chain
+        && !CHAINS.contains(&chain.value().to_ascii_lowercase().as_str())
+    {
+        let message = format!("unknown chain, expected one of {}", CHAINS.join(", "));
+        return syn::Error::new(chain.span(), message)
+            .to_compile_error()
+            .into();
+    }
+    if let Some(receiver @ FnArg::Receiver(_)) = handler.sig.inputs.first() {
+        return syn::Error::new_spanned(receiver, "#[paid] handlers have to be free functions")
+            .to_compile_error()
+            .into();
+    }
+
+    let vis = &handler.vis;
+    let ident = &handler.sig.ident;
+    let name = LitStr::new(&ident.to_string(), ident.span());
+    let chain = option(args.chain);
+    let route = option(args.route);
+    let description = option(args.description);
+    let doc = format!("Price of the paid handler `{}`.", ident);
+    quote! {
+        #handler
+
+        #[doc = #doc]
+        #vis mod #ident {
+            pub const PAID: ::x402_sdk::paid::PaidHandler = ::x402_sdk::paid::PaidHandler {
+                name: #name,
+                price: #amount,
+                chain: #chain,
+                route: #route,
+                description: #description,
+            };
+        }
+    }
+    .into()
+}